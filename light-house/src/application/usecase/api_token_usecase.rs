@@ -0,0 +1,169 @@
+use std::{str::FromStr, sync::Arc};
+
+use chrono::{Duration, SubsecRound, Utc};
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::api_token_usecase::ApiTokenUsecase, domain::{dto::{api_token_dto::{ApiTokenIdentityDto, CreateApiTokenDto, ReqCreateApiTokenDto, ResApiTokenDto, ResCreatedApiTokenDto, ResListApiTokenDto}, dto_enum::api_token_scope::ApiTokenScopeVariant}, entities::api_token, req_repository::{api_token_repository::{ApiTokenRepositoryBase, ApiTokenRepositoryUtility}, user_repository::UserRepositoryBase}}, implentation::secret_token::{generate_secret_token, hash_secret_token}, soc::soc_usecase::UsecaseError};
+
+
+
+
+// every generated token starts with this, so it is easy to spot in a leaked config
+const TOKEN_PREFIX: &str = "lhb_";
+// characters of the token kept in plain text to tell tokens apart
+const TOKEN_PREFIX_LENGTH: usize = 12;
+const INVALID_TOKEN: &str = "Invalid MCP token";
+
+
+fn uuid_string(id: &[u8]) -> Result<String, UsecaseError> {
+    Uuid::from_slice(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+fn uuid_of(id: &[u8]) -> Result<Uuid, UsecaseError> {
+    Uuid::from_slice(id).map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+fn scopes_of(token: &api_token::Model) -> Result<Vec<ApiTokenScopeVariant>, UsecaseError> {
+    let scopes: Vec<String> = serde_json::from_value(token.scopes.clone())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid scopes: {}", err)))?;
+    scopes
+        .iter()
+        .map(|scope| ApiTokenScopeVariant::from_str(scope).map_err(UsecaseError::Unexpected))
+        .collect()
+}
+
+fn to_api_token_entry(token: api_token::Model) -> Result<ResApiTokenDto, UsecaseError> {
+    Ok(ResApiTokenDto {
+        id: uuid_string(&token.id)?,
+        scopes: scopes_of(&token)?,
+        name: token.name,
+        token_prefix: token.token_prefix,
+        expires_at: token.expires_at.map(|expires_at| expires_at.to_rfc3339()),
+        last_used_at: token.last_used_at.map(|last_used_at| last_used_at.to_rfc3339()),
+        last_used_ip: token.last_used_ip,
+        revoked_at: token.revoked_at.map(|revoked_at| revoked_at.to_rfc3339()),
+        created_at: token.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+        updated_at: token.updated_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    })
+}
+
+
+
+
+pub struct ApiTokenUseCase<R, M>
+where
+    R: ApiTokenRepositoryBase + ApiTokenRepositoryUtility + Send + Sync,
+    M: UserRepositoryBase + Send + Sync,
+{
+    api_token_repo: Arc<R>,
+    mcp_repo: Arc<M>,
+}
+
+impl<R, M> ApiTokenUseCase<R, M>
+where
+    R: ApiTokenRepositoryBase + ApiTokenRepositoryUtility + Send + Sync,
+    M: UserRepositoryBase + Send + Sync,
+{
+    pub fn new(api_token_repo: Arc<R>, mcp_repo: Arc<M>) -> Self {
+        Self { api_token_repo, mcp_repo }
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<R, M> ApiTokenUsecase for ApiTokenUseCase<R, M>
+where
+    R: ApiTokenRepositoryBase + ApiTokenRepositoryUtility + Send + Sync,
+    M: UserRepositoryBase + Send + Sync,
+{
+    async fn create_api_token(&self, user_id: Uuid, token_dto: ReqCreateApiTokenDto) -> Result<ResCreatedApiTokenDto, UsecaseError>
+    {
+        // Step 1: Keep each scope once, in the order given
+        let mut scopes: Vec<ApiTokenScopeVariant> = Vec::new();
+        for scope in token_dto.scopes {
+            if !scopes.contains(&scope) {
+                scopes.push(scope);
+            }
+        }
+        if scopes.is_empty() {
+            return Err(UsecaseError::InvalidData("At least one scope is required".to_string()));
+        }
+
+        // Step 2: Generate the token, only its hash is stored
+        let token = generate_secret_token(TOKEN_PREFIX);
+        let expires_at = token_dto
+            .expires_in_days
+            .map(|days| Utc::now().trunc_subsecs(0) + Duration::days(days));
+        let create_dto = CreateApiTokenDto {
+            name: token_dto.name.trim().to_string(),
+            token_hash: hash_secret_token(&token),
+            token_prefix: token.chars().take(TOKEN_PREFIX_LENGTH).collect(),
+            scopes,
+            expires_at,
+        };
+
+        // Step 3: Store and return the token once
+        let created = self.api_token_repo.create(user_id, create_dto).await?;
+        Ok(ResCreatedApiTokenDto {
+            token,
+            api_token: to_api_token_entry(created)?,
+        })
+    }
+
+    async fn get_all_api_token(&self, user_id: Uuid) -> Result<ResListApiTokenDto, UsecaseError>
+    {
+        let tokens = self.api_token_repo.find_all_by_user_id(user_id).await?;
+        let data = tokens
+            .into_iter()
+            .map(to_api_token_entry)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ResListApiTokenDto {
+            length: data.len() as i32,
+            data,
+        })
+    }
+
+    async fn revoke_api_token(&self, user_id: Uuid, token_id: Uuid) -> Result<ResApiTokenDto, UsecaseError>
+    {
+        let revoked = self.api_token_repo.revoke(user_id, token_id, Utc::now().trunc_subsecs(0)).await?;
+        to_api_token_entry(revoked)
+    }
+
+    async fn authenticate_api_token(&self, token: &str, ip: Option<String>) -> Result<ApiTokenIdentityDto, UsecaseError>
+    {
+        // Step 1: Find the token by its hash, the message is the same whatever is wrong with it,
+        // the old per-user tokens were moved into api tokens and are found the same way
+        let now = Utc::now();
+        let api_token = match self.api_token_repo.find_by_token_hash(&hash_secret_token(token)).await? {
+            Some(api_token) => api_token,
+            None => return Err(UsecaseError::ResourceNotFound(INVALID_TOKEN.to_string())),
+        };
+        let is_revoked = api_token.revoked_at.is_some();
+        let is_expired = api_token.expires_at.is_some_and(|expires_at| expires_at <= now);
+        if is_revoked || is_expired {
+            return Err(UsecaseError::ResourceNotFound(INVALID_TOKEN.to_string()));
+        }
+
+        // Step 2: Tokens of a disabled account stop working with it
+        let user_id = uuid_of(&api_token.user_id)?;
+        match self.mcp_repo.find_by_id(user_id).await? {
+            Some(user) if user.disabled_at.is_none() => {}
+            _ => return Err(UsecaseError::ResourceNotFound(INVALID_TOKEN.to_string())),
+        }
+
+        // Step 3: Record the use, a failure here must not lock the client out
+        let token_id = uuid_of(&api_token.id)?;
+        if let Err(err) = self.api_token_repo.touch(token_id, now.trunc_subsecs(0), ip).await {
+            log::warn!("Failed to record the use of API token {}: {}", token_id, err);
+        }
+
+        Ok(ApiTokenIdentityDto {
+            user_id,
+            token_id,
+            scopes: scopes_of(&api_token)?,
+        })
+    }
+}
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use uuid::Uuid;
 
-use crate::{application::usecase_req_impl::asset_usecase::AssetUsecase, domain::{dto::asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto, ResEntryAssetDto, ResListAssetDto}, req_repository::asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}}, soc::soc_usecase::UsecaseError};
+use crate::{application::usecase_req_impl::{asset_usecase::AssetUsecase, attachment_usecase::AttachmentCleanupUsecase}, domain::{dto::{dto_enum::attachment_owner::AttachmentOwnerVariant, asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto, ResEntryAssetDto, ResListAssetDto}}, req_repository::asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}}, soc::soc_usecase::UsecaseError};
 
 
 
@@ -13,15 +13,16 @@ pub struct AssetUseCase<T>
 where 
     T: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
 {
-    asset_repository: Arc<T>
+    asset_repository: Arc<T>,
+    attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>,
 }
 
 impl<T> AssetUseCase<T>
 where 
     T: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
 {
-    pub fn new(asset_repository: Arc<T>) -> Self {
-        Self { asset_repository }
+    pub fn new(asset_repository: Arc<T>, attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>) -> Self {
+        Self { asset_repository, attachment_cleanup }
     }
 }
 
@@ -196,7 +197,13 @@ where
         // Step 2: Delete the asset if it exists
         if asset_exists {
             match self.asset_repository.delete(user_id, asset_id).await {
-                Ok(_) => Ok(()), // Successfully deleted
+                Ok(_) => {
+                    // the attachments go along, the cleanup worker picks up anything left behind
+                    if let Err(err) = self.attachment_cleanup.remove_owner_attachments(user_id, AttachmentOwnerVariant::Asset, asset_id).await {
+                        log::warn!("Attachments of asset {} not removed: {}", asset_id, err);
+                    }
+                    Ok(())
+                }
                 Err(err) => Err(UsecaseError::from(err)), // Handle repository errors
             }
         } else {
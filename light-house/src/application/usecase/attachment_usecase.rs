@@ -0,0 +1,227 @@
+use std::sync::Arc;
+
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::attachment_usecase::{AttachmentCleanupUsecase, AttachmentUsecase}, configuration::attachment_config::AttachmentConfig, domain::{dto::{attachment_dto::{AttachmentContentDto, AttachmentUploadDto, CreateAttachmentDto, ResAttachmentUsageDto, ResEntryAttachmentDto, ResListAttachmentDto}, dto_enum::attachment_owner::AttachmentOwnerVariant}, entities::attachment, req_repository::{attachment_repository::{AttachmentRepositoryBase, AttachmentRepositoryUtility}, attachment_storage_repository::AttachmentStorage}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+// orphans removed per cleanup run, the rest waits for the next tick
+const ORPHAN_BATCH_SIZE: u64 = 200;
+
+
+fn to_attachment_entry(attachment: attachment::Model) -> Result<ResEntryAttachmentDto, UsecaseError> {
+    let owner_type = attachment.owner_type
+        .parse::<AttachmentOwnerVariant>()
+        .map_err(UsecaseError::Unexpected)?;
+
+    Ok(ResEntryAttachmentDto {
+        id: Uuid::from_slice(&attachment.id)
+            .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))?
+            .to_string(),
+        owner_type,
+        owner_id: Uuid::from_slice(&attachment.owner_id)
+            .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))?
+            .to_string(),
+        file_name: attachment.file_name,
+        content_type: attachment.content_type,
+        size_bytes: attachment.size_bytes,
+        sha256: attachment.sha256,
+        created_at: attachment.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    })
+}
+
+
+
+pub struct AttachmentUseCase<T>
+where
+    T: AttachmentRepositoryBase + AttachmentRepositoryUtility + Send + Sync,
+{
+    attachment_repo: Arc<T>,
+    storage: Arc<dyn AttachmentStorage>,
+    config: AttachmentConfig,
+}
+
+impl<T> AttachmentUseCase<T>
+where
+    T: AttachmentRepositoryBase + AttachmentRepositoryUtility + Send + Sync,
+{
+    pub fn new(attachment_repo: Arc<T>, storage: Arc<dyn AttachmentStorage>, config: AttachmentConfig) -> Self {
+        Self { attachment_repo, storage, config }
+    }
+
+    async fn find_owned(&self, user_id: Uuid, attachment_id: Uuid) -> Result<attachment::Model, UsecaseError> {
+        self.attachment_repo
+            .find_by_user_id_and_attachment_id(user_id, attachment_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(format!("Attachment with ID {} not found", attachment_id)))
+    }
+
+    fn quota_exceeded(&self, used_bytes: i64) -> UsecaseError {
+        UsecaseError::Conflict(format!(
+            "Attachment storage quota of {} bytes exceeded, {} bytes in use",
+            self.config.quota_bytes,
+            used_bytes
+        ))
+    }
+
+    // the row goes first so a storage failure leaves an unreferenced file rather than a broken attachment
+    async fn remove(&self, attachment: attachment::Model) -> Result<(), UsecaseError> {
+        let attachment_id = Uuid::from_slice(&attachment.id)
+            .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))?;
+        self.attachment_repo.delete(attachment_id).await?;
+
+        if let Err(err) = self.storage.delete(attachment.storage_key.clone()).await {
+            log::error!("Attachment {} deleted but its content '{}' was not: {}", attachment_id, attachment.storage_key, err);
+        }
+        Ok(())
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<T> AttachmentUsecase for AttachmentUseCase<T>
+where
+    T: AttachmentRepositoryBase + AttachmentRepositoryUtility + Send + Sync,
+{
+    async fn upload_attachment(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid, upload_dto: AttachmentUploadDto) -> Result<ResEntryAttachmentDto, UsecaseError> {
+        let size_bytes = upload_dto.content.len() as u64;
+        if size_bytes == 0 {
+            return Err(UsecaseError::InvalidData("The uploaded file is empty".to_string()));
+        }
+        if size_bytes > self.config.max_file_bytes {
+            return Err(UsecaseError::InvalidData(format!(
+                "The uploaded file is larger than {} bytes",
+                self.config.max_file_bytes
+            )));
+        }
+
+        if !self.attachment_repo.owner_exists(user_id, owner_type, owner_id).await? {
+            return Err(UsecaseError::ResourceNotFound(format!(
+                "{} with ID {} not found",
+                owner_type.as_str(),
+                owner_id
+            )));
+        }
+
+        // refused early here without storing anything, create checks again under a lock
+        let (_, used_bytes) = self.attachment_repo.usage_by_user(user_id).await?;
+        if used_bytes.max(0) as u64 + size_bytes > self.config.quota_bytes {
+            return Err(self.quota_exceeded(used_bytes));
+        }
+
+        // content first, then the row pointing at it
+        let attachment_id = Uuid::new_v4();
+        let storage_key = format!("{}/{}", user_id, attachment_id);
+        let sha256 = hex::encode(Sha256::digest(&upload_dto.content));
+        self.storage
+            .put(storage_key.clone(), upload_dto.content, upload_dto.content_type.clone())
+            .await?;
+
+        let create_dto = CreateAttachmentDto {
+            id: attachment_id,
+            owner_type,
+            owner_id,
+            file_name: upload_dto.file_name,
+            content_type: upload_dto.content_type,
+            size_bytes: size_bytes as i64,
+            sha256,
+            storage_key: storage_key.clone(),
+        };
+        let rejected = match self.attachment_repo.create(user_id, create_dto, self.config.quota_bytes as i64).await {
+            Ok(Some(attachment)) => return to_attachment_entry(attachment),
+            // another upload took the space in the meantime
+            Ok(None) => match self.attachment_repo.usage_by_user(user_id).await {
+                Ok((_, used_bytes)) => self.quota_exceeded(used_bytes),
+                Err(err) => err.into(),
+            },
+            Err(err) => err.into(),
+        };
+        if let Err(storage_err) = self.storage.delete(storage_key.clone()).await {
+            log::error!("Failed to remove the content '{}' of a rejected attachment: {}", storage_key, storage_err);
+        }
+        Err(rejected)
+    }
+
+    async fn get_all_attachment_by_owner(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid) -> Result<ResListAttachmentDto, UsecaseError> {
+        let attachments = self.attachment_repo.find_all_by_owner(user_id, owner_type, owner_id).await?;
+        let data = attachments
+            .into_iter()
+            .map(to_attachment_entry)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ResListAttachmentDto {
+            length: data.len() as i32,
+            data,
+        })
+    }
+
+    async fn get_attachment(&self, user_id: Uuid, attachment_id: Uuid) -> Result<Option<ResEntryAttachmentDto>, UsecaseError> {
+        match self.attachment_repo.find_by_user_id_and_attachment_id(user_id, attachment_id).await? {
+            Some(attachment) => Ok(Some(to_attachment_entry(attachment)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn download_attachment(&self, user_id: Uuid, attachment_id: Uuid) -> Result<AttachmentContentDto, UsecaseError> {
+        let attachment = self.find_owned(user_id, attachment_id).await?;
+        let content = self.storage.get(attachment.storage_key).await?;
+
+        Ok(AttachmentContentDto {
+            file_name: attachment.file_name,
+            content_type: attachment.content_type,
+            content,
+        })
+    }
+
+    async fn delete_attachment(&self, user_id: Uuid, attachment_id: Uuid) -> Result<(), UsecaseError> {
+        let attachment = self.find_owned(user_id, attachment_id).await?;
+        self.remove(attachment).await
+    }
+
+    async fn get_usage(&self, user_id: Uuid) -> Result<ResAttachmentUsageDto, UsecaseError> {
+        let (count, used_bytes) = self.attachment_repo.usage_by_user(user_id).await?;
+
+        Ok(ResAttachmentUsageDto {
+            count,
+            used_bytes,
+            quota_bytes: self.config.quota_bytes as i64,
+            max_file_bytes: self.config.max_file_bytes as i64,
+        })
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<T> AttachmentCleanupUsecase for AttachmentUseCase<T>
+where
+    T: AttachmentRepositoryBase + AttachmentRepositoryUtility + Send + Sync,
+{
+    async fn purge_orphaned_attachments(&self) -> Result<usize, UsecaseError> {
+        let orphans = self.attachment_repo.find_orphans(ORPHAN_BATCH_SIZE).await?;
+        let mut removed = 0;
+        for orphan in orphans {
+            match self.remove(orphan).await {
+                Ok(()) => removed += 1,
+                Err(UsecaseError::ResourceNotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(removed)
+    }
+
+    async fn remove_owner_attachments(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid) -> Result<usize, UsecaseError> {
+        let attachments = self.attachment_repo.find_all_by_owner(user_id, owner_type, owner_id).await?;
+        let mut removed = 0;
+        for attachment in attachments {
+            match self.remove(attachment).await {
+                Ok(()) => removed += 1,
+                Err(UsecaseError::ResourceNotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(removed)
+    }
+}
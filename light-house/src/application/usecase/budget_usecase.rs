@@ -0,0 +1,422 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::budget_usecase::BudgetUsecase, domain::{dto::{budget_dto::{CreateBudgetDto, ResBudgetAlertDto, ResBudgetStatusDto, ResEntryBudgetDto, ResListBudgetAlertDto, ResListBudgetDto, ResListBudgetStatusDto, UpdateBudgetDto}, dto_enum::{budget_period_type::BudgetPeriodVariant, report_period::ReportPeriodVariant}}, entities::{budget, budget_period}, req_repository::{budget_repository::{BudgetRepositoryBase, BudgetRepositoryUtility}, expense_type_repository::{ExpenseTypeRepositoryBase, ExpenseTypeRepositoryUtility}}}, implentation::decimal_utoipa::DecimalWrapper, soc::soc_usecase::UsecaseError};
+
+
+
+
+const MAX_BUDGET_PERIODS: usize = 1200;
+
+// period start and end (last second, inclusive)
+type PeriodBounds = (DateTime<Utc>, DateTime<Utc>);
+
+
+// a stored period with what was paid in it, spent stays zero when the caller did not need it
+struct BudgetPeriodState {
+    period: budget_period::Model,
+    spent: Decimal,
+}
+
+impl BudgetPeriodState {
+    fn budgeted(&self) -> Decimal {
+        self.period.amount + self.period.rollover_amount
+    }
+}
+
+
+fn uuid_string(id: &[u8]) -> Result<String, UsecaseError> {
+    Uuid::from_slice(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+fn period_type_of(budget: &budget::Model) -> Result<BudgetPeriodVariant, UsecaseError> {
+    BudgetPeriodVariant::from_str(&budget.period_type).map_err(UsecaseError::Unexpected)
+}
+
+// every period from start_date up to the one holding `until`
+fn expected_periods(budget: &budget::Model, until: DateTime<Utc>) -> Result<Vec<PeriodBounds>, UsecaseError> {
+    match period_type_of(budget)? {
+        BudgetPeriodVariant::Custom => {
+            let end_date = budget
+                .end_date
+                .ok_or_else(|| UsecaseError::Unexpected("Custom budget without end_date".to_string()))?;
+            Ok(vec![(budget.start_date, end_date)])
+        }
+        BudgetPeriodVariant::Monthly => {
+            let mut periods = Vec::new();
+            let mut start = budget.start_date;
+            while start <= until && budget.end_date.is_none_or(|end_date| start <= end_date) {
+                if periods.len() >= MAX_BUDGET_PERIODS {
+                    return Err(UsecaseError::InvalidData(format!(
+                        "The budget would need more than {} periods",
+                        MAX_BUDGET_PERIODS
+                    )));
+                }
+                let next_start = ReportPeriodVariant::Month.next_start(ReportPeriodVariant::Month.start_of(start));
+                let mut end = next_start - Duration::seconds(1);
+                if let Some(end_date) = budget.end_date {
+                    end = end.min(end_date);
+                }
+                periods.push((start, end));
+                start = next_start;
+            }
+            Ok(periods)
+        }
+    }
+}
+
+fn to_budget_entry(budget: budget::Model, expense_type_name: String) -> Result<ResEntryBudgetDto, UsecaseError> {
+    Ok(ResEntryBudgetDto {
+        id: uuid_string(&budget.id)?,
+        name: budget.name.clone(),
+        expense_type_id: uuid_string(&budget.expense_type_id)?,
+        expense_type_name,
+        amount: DecimalWrapper(budget.amount),
+        period_type: period_type_of(&budget)?,
+        start_date: budget.start_date.to_rfc3339(),
+        end_date: budget.end_date.map(|end_date| end_date.to_rfc3339()),
+        rollover: budget.rollover,
+        created_at: budget.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+        updated_at: budget.updated_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    })
+}
+
+
+
+pub struct BudgetUseCase<B, E>
+where
+    B: BudgetRepositoryBase + BudgetRepositoryUtility + Send + Sync,
+    E: ExpenseTypeRepositoryBase + ExpenseTypeRepositoryUtility + Send + Sync,
+{
+    budget_repo: Arc<B>,
+    expense_type_repo: Arc<E>,
+}
+
+impl<B, E> BudgetUseCase<B, E>
+where
+    B: BudgetRepositoryBase + BudgetRepositoryUtility + Send + Sync,
+    E: ExpenseTypeRepositoryBase + ExpenseTypeRepositoryUtility + Send + Sync,
+{
+    pub fn new(budget_repo: Arc<B>, expense_type_repo: Arc<E>) -> Self {
+        Self {
+            budget_repo,
+            expense_type_repo,
+        }
+    }
+
+    async fn expense_type_names(&self, user_id: Uuid) -> Result<HashMap<Vec<u8>, String>, UsecaseError> {
+        let expense_types = self.expense_type_repo.find_all_by_user_id(user_id).await?;
+        Ok(expense_types.into_iter().map(|expense_type| (expense_type.id, expense_type.name)).collect())
+    }
+
+    async fn expense_type_name(&self, user_id: Uuid, expense_type_id: &[u8]) -> Result<String, UsecaseError> {
+        let expense_type_id = Uuid::from_slice(expense_type_id).map_err(|err| UsecaseError::Unexpected(err.to_string()))?;
+        Ok(self
+            .expense_type_repo
+            .find_by_user_id_and_expense_type_id(expense_type_id, user_id)
+            .await?
+            .map(|expense_type| expense_type.name)
+            .unwrap_or_default())
+    }
+
+    // Creates the missing periods up to `until`, fixes their end when the budget's end_date moved
+    // and recomputes the rollover chain, spent is loaded for periods ending at or after `spent_from`
+    async fn sync_periods(
+        &self,
+        user_id: Uuid,
+        budget: &budget::Model,
+        until: DateTime<Utc>,
+        spent_from: DateTime<Utc>,
+    ) -> Result<Vec<BudgetPeriodState>, UsecaseError> {
+        let budget_id = Uuid::from_slice(&budget.id).map_err(|err| UsecaseError::Unexpected(err.to_string()))?;
+        let expense_type_id = Uuid::from_slice(&budget.expense_type_id).map_err(|err| UsecaseError::Unexpected(err.to_string()))?;
+
+        // Step 1: Drop periods a shortened end_date no longer covers
+        if let Some(end_date) = budget.end_date {
+            self.budget_repo.delete_periods_starting_after(budget_id, end_date).await?;
+        }
+
+        // Step 2: Match the stored periods with the expected ones, creating what is missing
+        let mut stored: HashMap<DateTime<Utc>, budget_period::Model> = self
+            .budget_repo
+            .find_periods(budget_id)
+            .await?
+            .into_iter()
+            .map(|period| (period.period_start, period))
+            .collect();
+
+        let mut states: Vec<BudgetPeriodState> = Vec::new();
+        for (start, end) in expected_periods(budget, until)? {
+            let mut period = match stored.remove(&start) {
+                Some(period) => period,
+                None => self.budget_repo.create_period(budget_id, start, end, budget.amount).await?,
+            };
+
+            // Step 3: Carry what was left of the previous period when rollover is on
+            let rollover_amount = match states.last() {
+                Some(previous) if budget.rollover => previous.budgeted() - previous.spent,
+                _ => Decimal::ZERO,
+            };
+            if period.period_end != end || period.rollover_amount != rollover_amount {
+                let period_id = Uuid::from_slice(&period.id).map_err(|err| UsecaseError::Unexpected(err.to_string()))?;
+                period = self.budget_repo.update_period(period_id, end, rollover_amount).await?;
+            }
+
+            let spent = if budget.rollover || end >= spent_from {
+                self.budget_repo.sum_spent(user_id, expense_type_id, start, end).await?
+            } else {
+                Decimal::ZERO
+            };
+
+            states.push(BudgetPeriodState { period, spent });
+        }
+
+        Ok(states)
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<B, E> BudgetUsecase for BudgetUseCase<B, E>
+where
+    B: BudgetRepositoryBase + BudgetRepositoryUtility + Send + Sync,
+    E: ExpenseTypeRepositoryBase + ExpenseTypeRepositoryUtility + Send + Sync,
+{
+    async fn create_budget(
+        &self,
+        user_id: Uuid,
+        budget_dto: CreateBudgetDto
+    )
+        -> Result<ResEntryBudgetDto, UsecaseError>
+    {
+        // Step 1: The expense type must belong to the user
+        let expense_type = match self
+            .expense_type_repo
+            .find_by_user_id_and_expense_type_id(budget_dto.expense_type_id, user_id)
+            .await?
+        {
+            Some(expense_type) => expense_type,
+            None => return Err(UsecaseError::ResourceNotFound("Expense type not found".to_string())),
+        };
+
+        // Step 2: Save the budget and open its first period
+        let budget = self.budget_repo.create(user_id, budget_dto).await?;
+        self.sync_periods(user_id, &budget, budget.start_date, budget.start_date).await?;
+
+        to_budget_entry(budget, expense_type.name)
+    }
+
+    async fn get_budget(
+        &self,
+        user_id: Uuid,
+        budget_id: Uuid
+    )
+        -> Result<Option<ResEntryBudgetDto>, UsecaseError>
+    {
+        match self.budget_repo.find_by_id(user_id, budget_id).await? {
+            Some(budget) => {
+                let expense_type_name = self.expense_type_name(user_id, &budget.expense_type_id).await?;
+                Ok(Some(to_budget_entry(budget, expense_type_name)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn update_budget(
+        &self,
+        user_id: Uuid,
+        budget_id: Uuid,
+        budget_dto: UpdateBudgetDto
+    )
+        -> Result<ResEntryBudgetDto, UsecaseError>
+    {
+        // Step 1: Check the changes against the stored budget
+        let existing_budget = match self.budget_repo.find_by_id(user_id, budget_id).await? {
+            Some(budget) => budget,
+            None => return Err(UsecaseError::ResourceNotFound("Budget not found".to_string())),
+        };
+        if let Some(end_date) = budget_dto.end_date
+            && end_date < existing_budget.start_date
+        {
+            return Err(UsecaseError::InvalidData("end_date must not be before start_date".to_string()));
+        }
+        if budget_dto.rollover == Some(true) && period_type_of(&existing_budget)? == BudgetPeriodVariant::Custom {
+            return Err(UsecaseError::InvalidData("Rollover is only available for monthly budgets".to_string()));
+        }
+
+        // Step 2: Save, then line the periods up with the new end_date and rollover setting
+        let budget = self.budget_repo.update(user_id, budget_id, budget_dto).await?;
+        self.sync_periods(user_id, &budget, Utc::now(), Utc::now()).await?;
+
+        let expense_type_name = self.expense_type_name(user_id, &budget.expense_type_id).await?;
+        to_budget_entry(budget, expense_type_name)
+    }
+
+    async fn delete_budget(
+        &self,
+        user_id: Uuid,
+        budget_id: Uuid
+    )
+        -> Result<(), UsecaseError>
+    {
+        self.budget_repo.delete(user_id, budget_id).await?;
+        Ok(())
+    }
+
+    async fn get_all_budget(
+        &self,
+        user_id: Uuid
+    )
+        -> Result<ResListBudgetDto, UsecaseError>
+    {
+        let expense_type_names = self.expense_type_names(user_id).await?;
+        let budgets = self.budget_repo.find_all_by_user_id(user_id).await?;
+
+        let mut data = Vec::with_capacity(budgets.len());
+        for budget in budgets {
+            let expense_type_name = expense_type_names.get(&budget.expense_type_id).cloned().unwrap_or_default();
+            data.push(to_budget_entry(budget, expense_type_name)?);
+        }
+
+        Ok(ResListBudgetDto { length: data.len() as i32, data })
+    }
+
+    async fn get_budget_status(
+        &self,
+        user_id: Uuid,
+        date: DateTime<Utc>
+    )
+        -> Result<ResListBudgetStatusDto, UsecaseError>
+    {
+        let expense_type_names = self.expense_type_names(user_id).await?;
+        let budgets = self.budget_repo.find_all_by_user_id(user_id).await?;
+
+        let mut data = Vec::new();
+        for budget in budgets {
+            // Step 1: Skip budgets that do not run on the date
+            if date < budget.start_date || budget.end_date.is_some_and(|end_date| date > end_date) {
+                continue;
+            }
+
+            // Step 2: Bring the periods up to date and pick the one holding the date
+            let states = self.sync_periods(user_id, &budget, date, date).await?;
+            let state = match states
+                .into_iter()
+                .rev()
+                .find(|state| state.period.period_start <= date && date <= state.period.period_end)
+            {
+                Some(state) => state,
+                None => continue,
+            };
+
+            let budgeted = state.budgeted();
+            let used_percent = if budgeted > Decimal::ZERO {
+                Some(DecimalWrapper((state.spent * Decimal::ONE_HUNDRED / budgeted).round_dp(2)))
+            } else {
+                None
+            };
+
+            data.push(ResBudgetStatusDto {
+                budget_id: uuid_string(&budget.id)?,
+                budget_name: budget.name,
+                expense_type_id: uuid_string(&budget.expense_type_id)?,
+                expense_type_name: expense_type_names.get(&budget.expense_type_id).cloned().unwrap_or_default(),
+                period_id: uuid_string(&state.period.id)?,
+                period_start: state.period.period_start.to_rfc3339(),
+                period_end: state.period.period_end.to_rfc3339(),
+                amount: DecimalWrapper(state.period.amount),
+                rollover_amount: DecimalWrapper(state.period.rollover_amount),
+                budgeted: DecimalWrapper(budgeted),
+                spent: DecimalWrapper(state.spent),
+                remaining: DecimalWrapper(budgeted - state.spent),
+                used_percent,
+                is_over_budget: state.spent > budgeted,
+            });
+        }
+
+        Ok(ResListBudgetStatusDto {
+            date: date.to_rfc3339(),
+            over_budget_count: data.iter().filter(|status| status.is_over_budget).count() as i32,
+            length: data.len() as i32,
+            data,
+        })
+    }
+
+    async fn get_budget_alerts(
+        &self,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>
+    )
+        -> Result<ResListBudgetAlertDto, UsecaseError>
+    {
+        if from > to {
+            return Err(UsecaseError::InvalidData("from must not be after to".to_string()));
+        }
+
+        let expense_type_names = self.expense_type_names(user_id).await?;
+        let budgets = self.budget_repo.find_all_by_user_id(user_id).await?;
+
+        let mut data = Vec::new();
+        for budget in budgets {
+            if budget.start_date > to || budget.end_date.is_some_and(|end_date| end_date < from) {
+                continue;
+            }
+            let expense_type_id = Uuid::from_slice(&budget.expense_type_id).map_err(|err| UsecaseError::Unexpected(err.to_string()))?;
+
+            let states = self.sync_periods(user_id, &budget, to, from).await?;
+            for state in states {
+                // Step 1: Only overspent periods touching the range can hold an alert
+                let budgeted = state.budgeted();
+                if state.period.period_end < from || state.period.period_start > to || state.spent <= budgeted {
+                    continue;
+                }
+
+                // Step 2: Walk the payments of the period to find the one that crossed the budget
+                let payments = self
+                    .budget_repo
+                    .find_spending(user_id, expense_type_id, state.period.period_start, state.period.period_end)
+                    .await?;
+                let mut running_total = Decimal::ZERO;
+                let crossing = payments.into_iter().find(|payment| {
+                    running_total += payment.amount;
+                    running_total > budgeted
+                });
+
+                if let Some(payment) = crossing
+                    && payment.occurred_at >= from
+                    && payment.occurred_at <= to
+                {
+                    data.push(ResBudgetAlertDto {
+                        budget_id: uuid_string(&budget.id)?,
+                        budget_name: budget.name.clone(),
+                        expense_type_name: expense_type_names.get(&budget.expense_type_id).cloned().unwrap_or_default(),
+                        period_start: state.period.period_start.to_rfc3339(),
+                        period_end: state.period.period_end.to_rfc3339(),
+                        budgeted: DecimalWrapper(budgeted),
+                        spent: DecimalWrapper(state.spent),
+                        overspent: DecimalWrapper(state.spent - budgeted),
+                        transaction_id: uuid_string(&payment.id)?,
+                        transaction_amount: DecimalWrapper(payment.amount),
+                        transaction_occurred_at: payment.occurred_at.to_rfc3339(),
+                    });
+                }
+            }
+        }
+
+        // newest first, like a feed
+        data.sort_by(|a, b| b.transaction_occurred_at.cmp(&a.transaction_occurred_at));
+
+        Ok(ResListBudgetAlertDto {
+            from: from.to_rfc3339(),
+            to: to.to_rfc3339(),
+            length: data.len() as i32,
+            data,
+        })
+    }
+}
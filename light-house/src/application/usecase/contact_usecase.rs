@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use uuid::Uuid;
 
-use crate::{application::usecase_req_impl::contact_usecase::ContactUsecase, domain::{dto::contact_dto::{ReqCreateContactDto, ReqUpdateContactDto, ResEntryContactDto, ResListContactDto}, req_repository::contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}}, soc::soc_usecase::UsecaseError};
+use crate::{application::usecase_req_impl::{attachment_usecase::AttachmentCleanupUsecase, contact_usecase::ContactUsecase}, domain::{dto::{contact_dto::{ReqCreateContactDto, ReqUpdateContactDto, ResEntryContactDto, ResListContactDto}, dto_enum::attachment_owner::AttachmentOwnerVariant}, req_repository::contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}}, soc::soc_usecase::UsecaseError};
 
 
 
@@ -13,15 +13,16 @@ pub struct ContactUseCase<T>
 where
     T: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync
 {
-    contact_repository: Arc<T>
+    contact_repository: Arc<T>,
+    attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>,
 }
 
 impl<T> ContactUseCase<T>
 where
     T: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync
 {
-    pub fn new(contact_repository: Arc<T>) -> Self {
-        Self { contact_repository }
+    pub fn new(contact_repository: Arc<T>, attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>) -> Self {
+        Self { contact_repository, attachment_cleanup }
     }
 
 
@@ -245,7 +246,13 @@ where
         // Step 2: Delete the contact if it exists
         if contact_exists {
             match self.contact_repository.delete(user_id, contact_id).await {
-                Ok(_) => Ok(()),
+                Ok(_) => {
+                    // the attachments go along, the cleanup worker picks up anything left behind
+                    if let Err(err) = self.attachment_cleanup.remove_owner_attachments(user_id, AttachmentOwnerVariant::Contact, contact_id).await {
+                        log::warn!("Attachments of contact {} not removed: {}", contact_id, err);
+                    }
+                    Ok(())
+                }
                 Err(err) => Err(UsecaseError::from(err)),
             }
         } else {
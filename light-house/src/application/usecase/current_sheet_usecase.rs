@@ -1,14 +1,42 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Datelike, Duration, Months, NaiveTime, Utc};
+use rust_decimal::Decimal;
 use uuid::Uuid;
-use rust_decimal::prelude::ToPrimitive;
-use crate::{application::usecase_req_impl::current_sheet_usecase::CurrentSheetUsecase, domain::{dto::current_sheet_dto::{ResCurrentSheetDto, ResListCurrentSheetDto}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, balance_repository::{BalanceRepositoryBase, BalanceRepositoryUtill}}}, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
+use crate::implentation::decimal_utoipa::DecimalWrapper;
+use crate::{application::usecase_req_impl::current_sheet_usecase::CurrentSheetUsecase, domain::{dto::{current_sheet_dto::{ReqRebuildCurrentSheetDto, ResBalanceAsOfDto, ResBalanceHistoryDto, ResBalancePointDto, ResCurrentSheetDto, ResCurrentSheetDiscrepancyDto, ResListCurrentSheetDto, ResRebuildCurrentSheetDto}, dto_enum::balance_interval::BalanceIntervalVariant}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, balance_repository::{BalanceRepositoryBase, BalanceRepositoryUtill}}}, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
 
 
 
 
 
 
+// upper bound for one history request, a bit over ten years of daily points
+const MAX_HISTORY_POINTS: usize = 4000;
+
+// first instant of the day, ISO week (monday) or month that contains `at`
+fn period_start(at: DateTime<Utc>, interval: BalanceIntervalVariant) -> DateTime<Utc> {
+    let date = at.date_naive();
+    let date = match interval {
+        BalanceIntervalVariant::Day => date,
+        BalanceIntervalVariant::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+        BalanceIntervalVariant::Month => date.with_day(1).unwrap_or(date),
+    };
+    date.and_time(NaiveTime::MIN).and_utc()
+}
+
+fn next_period_start(start: DateTime<Utc>, interval: BalanceIntervalVariant) -> DateTime<Utc> {
+    match interval {
+        BalanceIntervalVariant::Day => start + Duration::days(1),
+        BalanceIntervalVariant::Week => start + Duration::days(7),
+        BalanceIntervalVariant::Month => start
+            .checked_add_months(Months::new(1))
+            .unwrap_or(start + Duration::days(31)),
+    }
+}
+
+
+
 pub struct CurrentUseCase<T, A>
 where 
     T: BalanceRepositoryBase + BalanceRepositoryUtill + Send + Sync,
@@ -70,16 +98,14 @@ where
 
     // Step 3: Map the result to ResCurrentSheetDto
     let res_current_sheet = ResCurrentSheetDto {
-        id: String::from_utf8(current_sheet.id).map_err(|e| {
+        id: Uuid::from_slice(&current_sheet.id).map_err(|e| {
             UsecaseError::from(RepositoryError::InvalidInput(e.to_string()))
-        })?,
+        })?.to_string(),
         asset_name,
-        balance: current_sheet.balance.to_f64().ok_or_else(|| {
-            UsecaseError::from(RepositoryError::InvalidInput("Failed to convert Decimal to f64".to_string()))
-        })?,
+        balance: DecimalWrapper(current_sheet.balance),
         last_transaction_id: current_sheet
             .last_transaction_id
-            .map(|id| String::from_utf8(id).unwrap_or_else(|_| "Invalid ID".to_string())),
+            .map(|id| Uuid::from_slice(&id).map_or_else(|_| "Invalid ID".to_string(), |id| id.to_string())),
         updated_at: current_sheet
             .updated_at
             .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
@@ -122,14 +148,10 @@ where
                 Err(_) => return Err(UsecaseError::from(RepositoryError::InvalidInput("Invalid ID".to_string()))),
             },
             asset_name,
-            balance: sheet.balance.to_f64().ok_or_else(|| {
-                UsecaseError::from(RepositoryError::InvalidInput(
-                    "Failed to convert Decimal to f64".to_string(),
-                ))
-            })?,
+            balance: DecimalWrapper(sheet.balance),
             last_transaction_id: sheet
                 .last_transaction_id
-                .map(|id| String::from_utf8(id).unwrap_or_else(|_| "Invalid ID".to_string())),
+                .map(|id| Uuid::from_slice(&id).map_or_else(|_| "Invalid ID".to_string(), |id| id.to_string())),
             updated_at: sheet
                 .updated_at
                 .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
@@ -203,14 +225,10 @@ where
                     Err(_) => return Err(UsecaseError::from(RepositoryError::InvalidInput("Invalid ID".to_string()))),
                 },
                 asset_name,
-                balance: sheet.balance.to_f64().ok_or_else(|| {
-                    UsecaseError::from(RepositoryError::InvalidInput(
-                        "Failed to convert Decimal to f64".to_string(),
-                    ))
-                })?,
+                balance: DecimalWrapper(sheet.balance),
                 last_transaction_id: sheet
                     .last_transaction_id
-                    .map(|id| String::from_utf8(id).unwrap_or_else(|_| "Invalid ID".to_string())),
+                    .map(|id| Uuid::from_slice(&id).map_or_else(|_| "Invalid ID".to_string(), |id| id.to_string())),
                 updated_at: sheet
                     .updated_at
                     .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
@@ -228,4 +246,165 @@ where
         // Step 5: Return the response object
         Ok(res_list)
     }
+
+
+    async fn rebuild_current_sheets(&self, user_id: Uuid, dto: ReqRebuildCurrentSheetDto) -> Result<ResRebuildCurrentSheetDto, UsecaseError> {
+        // Step 1: Parse the optional asset filter, anything but an explicit false is a dry run
+        let asset_id = match dto.asset_id {
+            Some(asset_id) => Some(Uuid::parse_str(&asset_id).map_err(|_| {
+                UsecaseError::InvalidData("Invalid asset_id".to_string())
+            })?),
+            None => None,
+        };
+        let dry_run = dto.dry_run.unwrap_or(true);
+
+        if let Some(asset_id) = asset_id
+            && self.asset_repo.find_by_id(user_id, asset_id).await?.is_none()
+        {
+            return Err(UsecaseError::ResourceNotFound(format!("Asset {} not found", asset_id)));
+        }
+
+        // Step 2: Replay the transaction history against the stored sheets
+        let reconciled = self
+            .balance_repo
+            .rebuild_current_sheets(user_id, asset_id, dry_run)
+            .await?;
+        let checked = reconciled.len() as i32;
+
+        // Step 3: Report every sheet that does not match its history
+        let mut discrepancies = Vec::new();
+        for sheet in reconciled.into_iter().filter(|sheet| !sheet.is_consistent()) {
+            let sheet_asset_id = Uuid::from_slice(&sheet.asset_id).map_err(|e| {
+                UsecaseError::from(RepositoryError::InvalidInput(e.to_string()))
+            })?;
+            let asset_name = match self.asset_repo.find_by_id(user_id, sheet_asset_id).await? {
+                Some(asset) => asset.name,
+                None => String::from("Unknown"),
+            };
+
+            discrepancies.push(ResCurrentSheetDiscrepancyDto {
+                current_sheet_id: Uuid::from_slice(&sheet.current_sheet_id).map_err(|e| {
+                    UsecaseError::from(RepositoryError::InvalidInput(e.to_string()))
+                })?.to_string(),
+                asset_id: sheet_asset_id.to_string(),
+                asset_name,
+                stored_balance: DecimalWrapper(sheet.stored_balance),
+                computed_balance: DecimalWrapper(sheet.computed_balance),
+                difference: DecimalWrapper(sheet.computed_balance - sheet.stored_balance),
+                stored_last_transaction_id: sheet
+                    .stored_last_transaction_id
+                    .map(|id| Uuid::from_slice(&id).map_or_else(|_| "Invalid ID".to_string(), |id| id.to_string())),
+                computed_last_transaction_id: sheet
+                    .computed_last_transaction_id
+                    .map(|id| Uuid::from_slice(&id).map_or_else(|_| "Invalid ID".to_string(), |id| id.to_string())),
+            });
+        }
+
+        // Step 4: Return the report
+        let discrepancy_count = discrepancies.len() as i32;
+        Ok(ResRebuildCurrentSheetDto {
+            dry_run,
+            checked,
+            discrepancy_count,
+            fixed: if dry_run { 0 } else { discrepancy_count },
+            discrepancies,
+        })
+    }
+
+
+    async fn get_balance_as_of(&self, user_id: Uuid, asset_id: Uuid, as_of: DateTime<Utc>) -> Result<Option<ResBalanceAsOfDto>, UsecaseError> {
+        // Step 1: Make sure the asset belongs to the user
+        let asset = match self.asset_repo.find_by_id(user_id, asset_id).await? {
+            Some(asset) => asset,
+            None => return Ok(None),
+        };
+
+        // Step 2: Replay the history up to the requested instant
+        let balance = self
+            .balance_repo
+            .get_balance_as_of(user_id, asset_id, as_of)
+            .await?;
+
+        // Step 3: Return the response object
+        Ok(Some(ResBalanceAsOfDto {
+            asset_id: asset_id.to_string(),
+            asset_name: asset.name,
+            as_of: as_of.to_rfc3339(),
+            balance: DecimalWrapper(balance),
+        }))
+    }
+
+
+
+    async fn get_balance_history(
+        &self,
+        user_id: Uuid,
+        asset_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        interval: BalanceIntervalVariant,
+    ) -> Result<Option<ResBalanceHistoryDto>, UsecaseError> {
+        if from > to {
+            return Err(UsecaseError::InvalidData("from must not be after to".to_string()));
+        }
+
+        // Step 1: Make sure the asset belongs to the user
+        let asset = match self.asset_repo.find_by_id(user_id, asset_id).await? {
+            Some(asset) => asset,
+            None => return Ok(None),
+        };
+
+        // Step 2: Balance right before the range and every movement inside it
+        let opening_balance = self
+            .balance_repo
+            .get_balance_as_of(user_id, asset_id, from - Duration::microseconds(1))
+            .await?;
+        let movements = self
+            .balance_repo
+            .get_balance_movements(user_id, asset_id, from, to)
+            .await?;
+
+        // Step 3: Walk the periods, each point holds the closing balance of its period
+        let mut movements = movements.into_iter().peekable();
+        let mut balance = opening_balance;
+        let mut data = Vec::new();
+        let mut start = period_start(from, interval);
+        while start <= to {
+            if data.len() >= MAX_HISTORY_POINTS {
+                return Err(UsecaseError::InvalidData(format!(
+                    "The range holds more than {} {} periods, use a larger interval",
+                    MAX_HISTORY_POINTS,
+                    interval.as_str()
+                )));
+            }
+
+            let next_start = next_period_start(start, interval);
+            let mut net_change = Decimal::ZERO;
+            while let Some(movement) = movements.next_if(|movement| movement.occurred_at < next_start) {
+                net_change += movement.amount;
+            }
+            balance += net_change;
+
+            data.push(ResBalancePointDto {
+                period_start: start.max(from).to_rfc3339(),
+                period_end: (next_start - Duration::microseconds(1)).min(to).to_rfc3339(),
+                net_change: DecimalWrapper(net_change),
+                balance: DecimalWrapper(balance),
+            });
+            start = next_start;
+        }
+
+        // Step 4: Return the response object
+        Ok(Some(ResBalanceHistoryDto {
+            asset_id: asset_id.to_string(),
+            asset_name: asset.name,
+            interval,
+            from: from.to_rfc3339(),
+            to: to.to_rfc3339(),
+            opening_balance: DecimalWrapper(opening_balance),
+            closing_balance: DecimalWrapper(balance),
+            length: data.len() as i32,
+            data,
+        }))
+    }
 }
\ No newline at end of file
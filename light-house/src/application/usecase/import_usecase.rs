@@ -0,0 +1,633 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::{import_usecase::{ImportProfileUsecase, ImportUsecase}, transaction_usecase::{RecordIncomeUsecase, RecordPaymentUsecase}}, domain::{dto::{recurring_transaction_dto::RECURRING_REFERENCE_PREFIX, dto_enum::{date_order::DateOrderVariant, import_record_status::ImportRecordStatusVariant, transaction_type::TransactionTypeVariant}, import_dto::{ImportCandidateDto, ParsedStatementDto, ReqCommitImportDto, ReqCreateImportProfileDto, ReqImportRecordDto, ReqImportStatementDto, ReqUpdateImportProfileDto, ResEntryImportProfileDto, ResImportCommitDto, ResImportCommitResultDto, ResImportLineErrorDto, ResImportPreviewDto, ResImportRecordDto, ResListImportProfileDto, StatementLineDto}, transaction_dto::{ReqCreateIncomeDto, ReqCreatePaymentDto}}, entities::import_profile, req_repository::import_repository::{ImportProfileRepositoryBase, ImportRepositoryUtility}}, implentation::{csv_statement::{parse_csv_statement, CsvStatementFormat}, ofx_statement::parse_ofx_statement, qif_statement::parse_qif_statement, date_time_utill::{parse_occurred_at, parse_timezone}, decimal_utoipa::DecimalWrapper}, soc::soc_usecase::UsecaseError};
+
+
+
+
+fn uuid_string(id: &[u8]) -> Result<String, UsecaseError> {
+    Uuid::from_slice(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, UsecaseError> {
+    Uuid::parse_str(value.trim())
+        .map_err(|_| UsecaseError::InvalidData(format!("The {} is not a valid UUID", field)))
+}
+
+fn timezone_of(timezone: Option<&str>) -> Result<Tz, UsecaseError> {
+    match timezone {
+        Some(timezone) => parse_timezone(timezone).map_err(UsecaseError::InvalidData),
+        None => Ok(Tz::UTC),
+    }
+}
+
+// an empty optional id is the same as none
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// the message shown for a record that could not be posted
+fn failure_message(err: UsecaseError) -> String {
+    match err {
+        UsecaseError::ValidationFailed(details) => details
+            .into_iter()
+            .map(|detail| detail.message)
+            .collect::<Vec<_>>()
+            .join(", "),
+        UsecaseError::ResourceNotFound(msg)
+        | UsecaseError::PermissionDenied(msg)
+        | UsecaseError::Conflict(msg)
+        | UsecaseError::InvalidData(msg) => msg,
+        err => err.to_string(),
+    }
+}
+
+// a statement needs either a signed amount column or at least one of debit and credit
+fn check_amount_columns(amount_column: Option<&str>, debit_column: Option<&str>, credit_column: Option<&str>) -> Result<(), UsecaseError> {
+    let is_set = |column: Option<&str>| column.is_some_and(|column| !column.trim().is_empty());
+    if !is_set(amount_column) && !is_set(debit_column) && !is_set(credit_column) {
+        return Err(UsecaseError::InvalidData(
+            "Either the amount_column or a debit_column or credit_column is required".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn format_of(profile: &import_profile::Model) -> Result<CsvStatementFormat, UsecaseError> {
+    let encoding = encoding_rs::Encoding::for_label(profile.encoding.as_bytes())
+        .ok_or_else(|| UsecaseError::Unexpected(format!("Unknown encoding '{}'", profile.encoding)))?;
+
+    Ok(CsvStatementFormat {
+        delimiter: profile.delimiter.bytes().next().unwrap_or(b','),
+        has_header: profile.has_header,
+        skip_rows: profile.skip_rows.max(0) as usize,
+        date_column: profile.date_column.clone(),
+        date_format: profile.date_format.clone(),
+        amount_column: profile.amount_column.clone(),
+        debit_column: profile.debit_column.clone(),
+        credit_column: profile.credit_column.clone(),
+        description_column: profile.description_column.clone(),
+        decimal_separator: profile.decimal_separator.chars().next().unwrap_or('.'),
+        encoding,
+    })
+}
+
+fn to_import_profile_entry(profile: import_profile::Model) -> Result<ResEntryImportProfileDto, UsecaseError> {
+    Ok(ResEntryImportProfileDto {
+        id: uuid_string(&profile.id)?,
+        name: profile.name,
+        delimiter: profile.delimiter,
+        has_header: profile.has_header,
+        skip_rows: profile.skip_rows,
+        date_column: profile.date_column,
+        date_format: profile.date_format,
+        amount_column: profile.amount_column,
+        debit_column: profile.debit_column,
+        credit_column: profile.credit_column,
+        description_column: profile.description_column,
+        decimal_separator: profile.decimal_separator,
+        encoding: profile.encoding,
+        created_at: profile.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+        updated_at: profile.updated_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    })
+}
+
+
+// an OFX or QIF file may hold several accounts, only one is imported onto the asset
+fn select_account(statements: Vec<ParsedStatementDto>, account_id: Option<&str>) -> Result<ParsedStatementDto, UsecaseError> {
+    let account_id = account_id.map(str::trim).filter(|account_id| !account_id.is_empty());
+    let accounts = || {
+        statements
+            .iter()
+            .map(|statement| statement.account_id.clone().unwrap_or_else(|| "(unnamed)".to_string()))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match account_id {
+        Some(account_id) => {
+            let index = statements
+                .iter()
+                .position(|statement| statement.account_id.as_deref() == Some(account_id))
+                .ok_or_else(|| UsecaseError::InvalidData(format!("Account '{}' is not in the file, it holds: {}", account_id, accounts())))?;
+            Ok(statements.into_iter().nth(index).unwrap_or_default())
+        }
+        None if statements.len() > 1 => Err(UsecaseError::InvalidData(format!(
+            "The file holds several accounts ({}), choose one with account_id",
+            accounts()
+        ))),
+        None => statements
+            .into_iter()
+            .next()
+            .ok_or_else(|| UsecaseError::InvalidData("The file holds no statement".to_string())),
+    }
+}
+
+
+// Transactions already on the asset. A record carrying the bank's id matches the transaction
+// stored with that id first, otherwise the type, amount and local date must agree, and a record
+// with an id is then only matched with a transaction entered without one. Each transaction
+// matches at most one record, so a statement with two identical coffees against one stored
+// coffee only flags the first as a duplicate
+struct DuplicateIndex {
+    timezone: Tz,
+    candidates: Vec<ImportCandidateDto>,
+    taken: Vec<bool>,
+    by_reference: HashMap<String, usize>,
+    by_key: HashMap<(TransactionTypeVariant, Decimal, NaiveDate), Vec<usize>>,
+}
+
+impl DuplicateIndex {
+    fn new(timezone: Tz, mut candidates: Vec<ImportCandidateDto>) -> Self {
+        // a posted recurring occurrence carries the scheduler's reference, not the bank's,
+        // so a statement line with a FITID still matches it by key
+        for candidate in candidates.iter_mut() {
+            if candidate.external_reference.as_deref().is_some_and(|reference| reference.starts_with(RECURRING_REFERENCE_PREFIX)) {
+                candidate.external_reference = None;
+            }
+        }
+        let mut by_reference = HashMap::new();
+        let mut by_key: HashMap<_, Vec<usize>> = HashMap::new();
+        for (index, candidate) in candidates.iter().enumerate() {
+            if let Some(reference) = &candidate.external_reference {
+                by_reference.entry(reference.clone()).or_insert(index);
+            }
+            let local_date = candidate.occurred_at.with_timezone(&timezone).date_naive();
+            by_key
+                .entry((candidate.transaction_type, candidate.amount.normalize(), local_date))
+                .or_default()
+                .push(index);
+        }
+        Self {
+            timezone,
+            taken: vec![false; candidates.len()],
+            candidates,
+            by_reference,
+            by_key,
+        }
+    }
+
+    fn take(&mut self, transaction_type: TransactionTypeVariant, amount: Decimal, occurred_at: DateTime<Utc>, external_reference: Option<&str>) -> Option<Uuid> {
+        let external_reference = external_reference.filter(|reference| !reference.trim().is_empty());
+        let by_reference = external_reference
+            .and_then(|reference| self.by_reference.get(reference).copied())
+            .filter(|&index| !self.taken[index]);
+
+        let index = match by_reference {
+            Some(index) => index,
+            None => {
+                let local_date = occurred_at.with_timezone(&self.timezone).date_naive();
+                self.by_key
+                    .get(&(transaction_type, amount.normalize(), local_date))?
+                    .iter()
+                    .copied()
+                    .find(|&index| {
+                        !self.taken[index]
+                            && (external_reference.is_none() || self.candidates[index].external_reference.is_none())
+                    })?
+            }
+        };
+        self.taken[index] = true;
+        Some(self.candidates[index].id)
+    }
+}
+
+
+
+pub struct ImportUseCase<R, P, I>
+where
+    R: ImportProfileRepositoryBase + ImportRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+{
+    import_repo: Arc<R>,
+    payment_usecase: Arc<P>,
+    income_usecase: Arc<I>,
+}
+
+impl<R, P, I> ImportUseCase<R, P, I>
+where
+    R: ImportProfileRepositoryBase + ImportRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+{
+    pub fn new(import_repo: Arc<R>, payment_usecase: Arc<P>, income_usecase: Arc<I>) -> Self {
+        Self {
+            import_repo,
+            payment_usecase,
+            income_usecase,
+        }
+    }
+
+    async fn find_owned_profile(&self, user_id: Uuid, profile_id: Uuid) -> Result<import_profile::Model, UsecaseError> {
+        self.import_repo
+            .find_profile_by_user_id_and_profile_id(user_id, profile_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(format!("Import profile with ID {} not found", profile_id)))
+    }
+
+    async fn check_asset(&self, user_id: Uuid, asset_id: &str) -> Result<Uuid, UsecaseError> {
+        let asset_id = parse_uuid(asset_id, "asset_id")?;
+        if !self.import_repo.is_asset_owned(user_id, asset_id).await? {
+            return Err(UsecaseError::ResourceNotFound(format!("Asset with ID {} not found", asset_id)));
+        }
+        Ok(asset_id)
+    }
+
+    // the stored incomes and payments of the asset over the whole days spanned by `dates`,
+    // and those carrying one of the bank's references whatever their date
+    async fn duplicate_index<'a>(&self, user_id: Uuid, asset_id: Uuid, timezone: Tz, dates: &[DateTime<Utc>], external_references: impl Iterator<Item = Option<&'a str>>)
+        -> Result<DuplicateIndex, UsecaseError>
+    {
+        let (Some(first), Some(last)) = (dates.iter().min(), dates.iter().max()) else {
+            return Ok(DuplicateIndex::new(timezone, Vec::new()));
+        };
+        let day_start = |date: NaiveDate| {
+            timezone
+                .from_local_datetime(&date.and_time(NaiveTime::MIN))
+                .earliest()
+                .map(|start| start.with_timezone(&Utc))
+                .unwrap_or_else(|| date.and_time(NaiveTime::MIN).and_utc())
+        };
+        let from = day_start(first.with_timezone(&timezone).date_naive());
+        let to = day_start(last.with_timezone(&timezone).date_naive() + chrono::Duration::days(1)) - chrono::Duration::microseconds(1);
+
+        let mut references: Vec<String> = Vec::new();
+        for reference in external_references.flatten().map(str::trim).filter(|reference| !reference.is_empty()) {
+            if !references.iter().any(|known| known == reference) {
+                references.push(reference.to_string());
+            }
+        }
+
+        let candidates = self.import_repo.find_import_candidates(user_id, asset_id, from, to, &references).await?;
+        Ok(DuplicateIndex::new(timezone, candidates))
+    }
+
+    fn to_record(line: &StatementLineDto, statement_dto: &ReqImportStatementDto) -> ResImportRecordDto {
+        let transaction_type = if line.amount.is_sign_negative() {
+            TransactionTypeVariant::Payment
+        } else {
+            TransactionTypeVariant::Income
+        };
+        ResImportRecordDto {
+            line: line.line,
+            transaction_type,
+            amount: DecimalWrapper(line.amount.abs()),
+            occurred_at: line.occurred_on.format("%Y-%m-%d").to_string(),
+            note: line.description.clone(),
+            contact_id: non_empty(statement_dto.contact_id.clone()),
+            expense_id: match transaction_type {
+                TransactionTypeVariant::Payment => non_empty(statement_dto.expense_id.clone()),
+                _ => None,
+            },
+            external_reference: line.external_reference.clone(),
+            duplicate_of: None,
+        }
+    }
+
+    // the records of one parsed statement, flagged against what the asset already holds
+    async fn preview_statement(&self, user_id: Uuid, asset_id: Uuid, timezone: Tz, statement_dto: ReqImportStatementDto, parsed: ParsedStatementDto)
+        -> Result<ResImportPreviewDto, UsecaseError>
+    {
+        let mut errors: Vec<ResImportLineErrorDto> = parsed.errors;
+
+        // Step 1: propose an income or a payment per line
+        let mut records = Vec::with_capacity(parsed.lines.len());
+        let mut dates = Vec::with_capacity(parsed.lines.len());
+        for line in &parsed.lines {
+            let occurred_on = line.occurred_on.format("%Y-%m-%d").to_string();
+            match parse_occurred_at(&occurred_on, statement_dto.timezone.as_deref()) {
+                Ok(occurred_at) => {
+                    records.push(Self::to_record(line, &statement_dto));
+                    dates.push(occurred_at);
+                }
+                Err(message) => errors.push(ResImportLineErrorDto { line: line.line, message }),
+            }
+        }
+
+        // Step 2: flag the records already on the asset
+        let references = records.iter().map(|record| record.external_reference.as_deref());
+        let mut duplicates = self.duplicate_index(user_id, asset_id, timezone, &dates, references).await?;
+        for (record, occurred_at) in records.iter_mut().zip(dates) {
+            record.duplicate_of = duplicates
+                .take(record.transaction_type, record.amount.0, occurred_at, record.external_reference.as_deref())
+                .map(|id| id.to_string());
+        }
+        errors.sort_by_key(|error| error.line);
+
+        let count = |transaction_type: TransactionTypeVariant| {
+            records.iter().filter(|record| record.transaction_type == transaction_type).count() as i32
+        };
+        Ok(ResImportPreviewDto {
+            asset_id: asset_id.to_string(),
+            account_id: parsed.account_id,
+            timezone: statement_dto.timezone,
+            income_count: count(TransactionTypeVariant::Income),
+            payment_count: count(TransactionTypeVariant::Payment),
+            duplicate_count: records.iter().filter(|record| record.duplicate_of.is_some()).count() as i32,
+            records,
+            errors,
+        })
+    }
+
+    async fn post_record(&self, user_id: Uuid, asset_id: Uuid, timezone: Option<String>, transaction_type_ids: &HashMap<TransactionTypeVariant, Uuid>, record: ReqImportRecordDto)
+        -> Result<String, UsecaseError>
+    {
+        let transaction_type_id = transaction_type_ids
+            .get(&record.transaction_type)
+            .ok_or_else(|| UsecaseError::InvalidData("Only income and payment records can be imported".to_string()))?
+            .to_string();
+
+        match record.transaction_type {
+            TransactionTypeVariant::Payment => {
+                let expense_id = non_empty(record.expense_id)
+                    .ok_or_else(|| UsecaseError::InvalidData("The expense_id is required for a payment".to_string()))?;
+                let payment_dto = ReqCreatePaymentDto {
+                    transaction_type_id,
+                    amount: record.amount,
+                    expense_id,
+                    asset_id: asset_id.to_string(),
+                    contact_id: record.contact_id,
+                    note: record.note,
+                    occurred_at: record.occurred_at,
+                    timezone,
+                    tag_ids: record.tag_ids,
+                    external_reference: non_empty(record.external_reference),
+                    splits: None,
+                };
+                Ok(self.payment_usecase.create_payment(user_id, payment_dto).await?.id)
+            }
+            _ => {
+                let income_dto = ReqCreateIncomeDto {
+                    transaction_type_id,
+                    amount: record.amount,
+                    asset_id: asset_id.to_string(),
+                    contact_id: record.contact_id,
+                    note: record.note,
+                    occurred_at: record.occurred_at,
+                    timezone,
+                    tag_ids: record.tag_ids,
+                    external_reference: non_empty(record.external_reference),
+                };
+                Ok(self.income_usecase.create_income(user_id, income_dto).await?.id)
+            }
+        }
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<R, P, I> ImportProfileUsecase for ImportUseCase<R, P, I>
+where
+    R: ImportProfileRepositoryBase + ImportRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+{
+    async fn create_import_profile(&self, user_id: Uuid, profile_dto: ReqCreateImportProfileDto) -> Result<ResEntryImportProfileDto, UsecaseError> {
+        check_amount_columns(
+            profile_dto.amount_column.as_deref(),
+            profile_dto.debit_column.as_deref(),
+            profile_dto.credit_column.as_deref(),
+        )?;
+
+        let created = self.import_repo.create(user_id, profile_dto).await?;
+        to_import_profile_entry(created)
+    }
+
+    async fn get_import_profile(&self, user_id: Uuid, profile_id: Uuid) -> Result<Option<ResEntryImportProfileDto>, UsecaseError> {
+        match self.import_repo.find_profile_by_user_id_and_profile_id(user_id, profile_id).await? {
+            Some(profile) => Ok(Some(to_import_profile_entry(profile)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn update_import_profile(&self, user_id: Uuid, profile_id: Uuid, profile_dto: ReqUpdateImportProfileDto) -> Result<ResEntryImportProfileDto, UsecaseError> {
+        // the profile as it would be once updated must still have an amount source
+        let existing = self.find_owned_profile(user_id, profile_id).await?;
+        check_amount_columns(
+            profile_dto.amount_column.as_deref().or(existing.amount_column.as_deref()),
+            profile_dto.debit_column.as_deref().or(existing.debit_column.as_deref()),
+            profile_dto.credit_column.as_deref().or(existing.credit_column.as_deref()),
+        )?;
+
+        let updated = self.import_repo.update(user_id, profile_id, profile_dto).await?;
+        to_import_profile_entry(updated)
+    }
+
+    async fn delete_import_profile(&self, user_id: Uuid, profile_id: Uuid) -> Result<(), UsecaseError> {
+        self.import_repo.delete(user_id, profile_id).await?;
+        Ok(())
+    }
+
+    async fn get_all_import_profile(&self, user_id: Uuid) -> Result<ResListImportProfileDto, UsecaseError> {
+        let profiles = self.import_repo.find_all_profiles_by_user_id(user_id).await?;
+        let data = profiles
+            .into_iter()
+            .map(to_import_profile_entry)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ResListImportProfileDto {
+            length: data.len() as i32,
+            data,
+        })
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<R, P, I> ImportUsecase for ImportUseCase<R, P, I>
+where
+    R: ImportProfileRepositoryBase + ImportRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+{
+    async fn preview_csv(&self, user_id: Uuid, profile_id: Uuid, statement_dto: ReqImportStatementDto, content: Vec<u8>) -> Result<ResImportPreviewDto, UsecaseError> {
+        if content.is_empty() {
+            return Err(UsecaseError::InvalidData("The uploaded file is empty".to_string()));
+        }
+        let profile = self.find_owned_profile(user_id, profile_id).await?;
+        let asset_id = self.check_asset(user_id, &statement_dto.asset_id).await?;
+        let timezone = timezone_of(statement_dto.timezone.as_deref())?;
+
+        let parsed = parse_csv_statement(&content, &format_of(&profile)?).map_err(UsecaseError::InvalidData)?;
+        self.preview_statement(user_id, asset_id, timezone, statement_dto, parsed).await
+    }
+
+    async fn preview_ofx(&self, user_id: Uuid, statement_dto: ReqImportStatementDto, content: Vec<u8>) -> Result<ResImportPreviewDto, UsecaseError> {
+        if content.is_empty() {
+            return Err(UsecaseError::InvalidData("The uploaded file is empty".to_string()));
+        }
+        let asset_id = self.check_asset(user_id, &statement_dto.asset_id).await?;
+        let timezone = timezone_of(statement_dto.timezone.as_deref())?;
+
+        let statements = parse_ofx_statement(&content).map_err(UsecaseError::InvalidData)?;
+        let parsed = select_account(statements, statement_dto.account_id.as_deref())?;
+        self.preview_statement(user_id, asset_id, timezone, statement_dto, parsed).await
+    }
+
+    async fn preview_qif(&self, user_id: Uuid, statement_dto: ReqImportStatementDto, date_order: DateOrderVariant, content: Vec<u8>) -> Result<ResImportPreviewDto, UsecaseError> {
+        if content.is_empty() {
+            return Err(UsecaseError::InvalidData("The uploaded file is empty".to_string()));
+        }
+        let asset_id = self.check_asset(user_id, &statement_dto.asset_id).await?;
+        let timezone = timezone_of(statement_dto.timezone.as_deref())?;
+
+        let statements = parse_qif_statement(&content, date_order).map_err(UsecaseError::InvalidData)?;
+        let parsed = select_account(statements, statement_dto.account_id.as_deref())?;
+        self.preview_statement(user_id, asset_id, timezone, statement_dto, parsed).await
+    }
+
+    async fn commit_import(&self, user_id: Uuid, commit_dto: ReqCommitImportDto) -> Result<ResImportCommitDto, UsecaseError> {
+        let asset_id = self.check_asset(user_id, &commit_dto.asset_id).await?;
+        let timezone = timezone_of(commit_dto.timezone.as_deref())?;
+        let skip_duplicates = commit_dto.skip_duplicates.unwrap_or(true);
+
+        let mut transaction_type_ids = HashMap::new();
+        for transaction_type in [TransactionTypeVariant::Income, TransactionTypeVariant::Payment] {
+            let transaction_type_id = self.import_repo.find_transaction_type_id(transaction_type).await?;
+            transaction_type_ids.insert(transaction_type, transaction_type_id);
+        }
+
+        // Step 1: the dates were validated with the request, read them once for the duplicate check
+        let dates = commit_dto.records
+            .iter()
+            .map(|record| parse_occurred_at(&record.occurred_at, commit_dto.timezone.as_deref()).map_err(UsecaseError::InvalidData))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut duplicates = if skip_duplicates {
+            let references = commit_dto.records.iter().map(|record| record.external_reference.as_deref());
+            Some(self.duplicate_index(user_id, asset_id, timezone, &dates, references).await?)
+        } else {
+            None
+        };
+
+        // Step 2: post each record on its own
+        let mut results = Vec::with_capacity(commit_dto.records.len());
+        for (index, (record, occurred_at)) in commit_dto.records.into_iter().zip(dates).enumerate() {
+            let line = record.line;
+            let duplicate_of = duplicates
+                .as_mut()
+                .and_then(|duplicates| duplicates.take(record.transaction_type, record.amount.0, occurred_at, record.external_reference.as_deref()));
+
+            let result = match duplicate_of {
+                Some(duplicate_of) => ResImportCommitResultDto {
+                    index: index as i32,
+                    line,
+                    status: ImportRecordStatusVariant::Duplicate,
+                    transaction_id: Some(duplicate_of.to_string()),
+                    message: None,
+                },
+                None => match self.post_record(user_id, asset_id, commit_dto.timezone.clone(), &transaction_type_ids, record).await {
+                    Ok(transaction_id) => ResImportCommitResultDto {
+                        index: index as i32,
+                        line,
+                        status: ImportRecordStatusVariant::Created,
+                        transaction_id: Some(transaction_id),
+                        message: None,
+                    },
+                    Err(err) => ResImportCommitResultDto {
+                        index: index as i32,
+                        line,
+                        status: ImportRecordStatusVariant::Failed,
+                        transaction_id: None,
+                        message: Some(failure_message(err)),
+                    },
+                },
+            };
+            results.push(result);
+        }
+
+        let count = |status: ImportRecordStatusVariant| {
+            results.iter().filter(|result| result.status == status).count() as i32
+        };
+        Ok(ResImportCommitDto {
+            created_count: count(ImportRecordStatusVariant::Created),
+            duplicate_count: count(ImportRecordStatusVariant::Duplicate),
+            failed_count: count(ImportRecordStatusVariant::Failed),
+            results,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::dto::recurring_transaction_dto::occurrence_reference;
+
+    use super::*;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    fn candidate(amount: &str, occurred_at: &str, external_reference: Option<&str>) -> ImportCandidateDto {
+        ImportCandidateDto {
+            id: Uuid::now_v7(),
+            transaction_type: TransactionTypeVariant::Payment,
+            amount: amount.parse().unwrap(),
+            occurred_at: utc(occurred_at),
+            external_reference: external_reference.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn two_identical_lines_against_one_stored_keep_one() {
+        let stored = candidate("4.50", "2025-06-01T08:00:00Z", None);
+        let stored_id = stored.id;
+        let mut index = DuplicateIndex::new(Tz::UTC, vec![stored]);
+        let coffee = "4.5".parse().unwrap();
+
+        assert_eq!(index.take(TransactionTypeVariant::Payment, coffee, utc("2025-06-01T00:00:00Z"), None), Some(stored_id));
+        assert_eq!(index.take(TransactionTypeVariant::Payment, coffee, utc("2025-06-01T00:00:00Z"), None), None);
+    }
+
+    #[test]
+    fn key_needs_the_same_type_and_local_day() {
+        let stored = candidate("4.50", "2025-06-01T20:00:00Z", None);
+        let stored_id = stored.id;
+        let mut index = DuplicateIndex::new(Tz::Asia__Bangkok, vec![stored]);
+        let coffee = "4.50".parse().unwrap();
+
+        // 20:00 UTC on the 1st is already the 2nd in Bangkok
+        assert_eq!(index.take(TransactionTypeVariant::Payment, coffee, utc("2025-06-01T00:00:00Z"), None), None);
+        assert_eq!(index.take(TransactionTypeVariant::Income, coffee, utc("2025-06-01T18:00:00Z"), None), None);
+        assert_eq!(index.take(TransactionTypeVariant::Payment, coffee, utc("2025-06-01T18:00:00Z"), None), Some(stored_id));
+    }
+
+    #[test]
+    fn reference_matches_before_the_key() {
+        let by_key = candidate("4.50", "2025-06-01T08:00:00Z", None);
+        let by_reference = candidate("4.50", "2025-05-30T08:00:00Z", Some("FIT-1"));
+        let (by_key_id, by_reference_id) = (by_key.id, by_reference.id);
+        let mut index = DuplicateIndex::new(Tz::UTC, vec![by_key, by_reference]);
+        let coffee = "4.50".parse().unwrap();
+
+        assert_eq!(index.take(TransactionTypeVariant::Payment, coffee, utc("2025-06-01T00:00:00Z"), Some("FIT-1")), Some(by_reference_id));
+        assert_eq!(index.take(TransactionTypeVariant::Payment, coffee, utc("2025-06-01T00:00:00Z"), Some("FIT-2")), Some(by_key_id));
+    }
+
+    #[test]
+    fn different_references_on_the_same_key_are_not_duplicates() {
+        let stored = candidate("4.50", "2025-06-01T08:00:00Z", Some("FIT-1"));
+        let mut index = DuplicateIndex::new(Tz::UTC, vec![stored]);
+
+        assert_eq!(index.take(TransactionTypeVariant::Payment, "4.50".parse().unwrap(), utc("2025-06-01T00:00:00Z"), Some("FIT-2")), None);
+    }
+
+    #[test]
+    fn posted_recurring_occurrence_matches_a_line_with_a_bank_reference() {
+        let stored = candidate("4.50", "2025-06-01T08:00:00Z", Some(&occurrence_reference(Uuid::now_v7())));
+        let stored_id = stored.id;
+        let mut index = DuplicateIndex::new(Tz::UTC, vec![stored]);
+
+        assert_eq!(index.take(TransactionTypeVariant::Payment, "4.50".parse().unwrap(), utc("2025-06-01T00:00:00Z"), Some("FIT-1")), Some(stored_id));
+    }
+}
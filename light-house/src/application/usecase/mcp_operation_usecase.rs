@@ -0,0 +1,512 @@
+use std::{str::FromStr, sync::Arc};
+
+use chrono::{Duration, SubsecRound, Utc};
+use rust_decimal::Decimal;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{application::usecase_req_impl::{contact_usecase::ContactUsecase, expense_usecase::ExpenseUsecase, mcp_operation_usecase::McpOperationUsecase, transaction_usecase::{RecordIncomeUsecase, RecordPaymentUsecase, TransferUsecase}}, domain::{dto::{contact_dto::{ReqCreateContactDto, ReqUpdateContactDto}, dto_enum::{mcp_operation::{McpOperationStatusVariant, McpOperationVariant}, transaction_type::TransactionTypeVariant}, expense_dto::{ReqCreateExpenseDto, ReqUpdateExpenseDto}, mcp_operation_dto::{CreateMcpOperationDto, McpTransactionSnapshotDto, ReqMcpProposeOperationDto, ResListMcpPendingOperationDto, ResMcpBalanceImpactDto, ResMcpPendingOperationDto}, transaction_dto::{ReqCreateIncomeDto, ReqCreatePaymentDto, ReqCreateTransferDto, ReqUpdateIncomeDto, ReqUpdatePaymentDto, ReqUpdateTransferDto}}, entities::mcp_pending_operation, req_repository::mcp_operation_repository::{McpOperationRepositoryBase, McpOperationRepositoryUtility}}, implentation::decimal_utoipa::DecimalWrapper, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
+
+
+
+
+// how long a proposal waits for the user before it expires
+const PENDING_OPERATION_TTL_HOURS: i64 = 24;
+
+
+fn uuid_string(id: &[u8]) -> Result<String, UsecaseError> {
+    Uuid::from_slice(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+fn uuid_of(id: &[u8]) -> Result<Uuid, UsecaseError> {
+    Uuid::from_slice(id).map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, UsecaseError> {
+    Uuid::parse_str(value).map_err(|_| UsecaseError::InvalidData(format!("Invalid {}", field)))
+}
+
+fn operation_of(operation: &mcp_pending_operation::Model) -> Result<McpOperationVariant, UsecaseError> {
+    McpOperationVariant::from_str(&operation.operation).map_err(UsecaseError::Unexpected)
+}
+
+fn status_of(operation: &mcp_pending_operation::Model) -> Result<McpOperationStatusVariant, UsecaseError> {
+    McpOperationStatusVariant::from_str(&operation.status).map_err(UsecaseError::Unexpected)
+}
+
+fn from_payload<T: DeserializeOwned>(payload: Option<Value>) -> Result<T, UsecaseError> {
+    serde_json::from_value(payload.unwrap_or_else(|| Value::Object(Map::new())))
+        .map_err(|err| UsecaseError::InvalidData(format!("Invalid payload: {}", err)))
+}
+
+fn to_payload<T: Serialize>(dto: &T) -> Result<Option<Value>, UsecaseError> {
+    serde_json::to_value(dto)
+        .map(Some)
+        .map_err(|err| UsecaseError::Unexpected(err.to_string()))
+}
+
+fn validate_dto<T: Validate>(dto: &T) -> Result<(), UsecaseError> {
+    dto.validate().map_err(|errors| UsecaseError::InvalidData(format!("Validation errors: {}", errors)))
+}
+
+// how a transaction moves the balances, reversed for the old side of an update or a delete
+fn movements_of(transaction_type: TransactionTypeVariant, amount: Decimal, asset_id: Uuid, destination_asset_id: Option<Uuid>) -> Vec<(Uuid, Decimal)> {
+    match transaction_type {
+        TransactionTypeVariant::Payment => vec![(asset_id, -amount)],
+        TransactionTypeVariant::Income => vec![(asset_id, amount)],
+        TransactionTypeVariant::Transfer => {
+            let mut movements = vec![(asset_id, -amount)];
+            movements.extend(destination_asset_id.map(|destination_asset_id| (destination_asset_id, amount)));
+            movements
+        }
+    }
+}
+
+fn reversed_movements_of(snapshot: &McpTransactionSnapshotDto) -> Vec<(Uuid, Decimal)> {
+    movements_of(snapshot.transaction_type, snapshot.amount, snapshot.asset_id, snapshot.destination_asset_id)
+        .into_iter()
+        .map(|(asset_id, change)| (asset_id, -change))
+        .collect()
+}
+
+fn to_operation_entry(operation: mcp_pending_operation::Model) -> Result<ResMcpPendingOperationDto, UsecaseError> {
+    let balance_impact: Vec<ResMcpBalanceImpactDto> = serde_json::from_value(operation.balance_impact.clone())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid balance impact: {}", err)))?;
+
+    Ok(ResMcpPendingOperationDto {
+        id: uuid_string(&operation.id)?,
+        operation: operation_of(&operation)?,
+        target_id: operation.target_id.as_deref().map(uuid_string).transpose()?,
+        payload: operation.payload.clone(),
+        balance_impact,
+        status: status_of(&operation)?,
+        result: operation.result.clone(),
+        message: operation.message.clone(),
+        expires_at: operation.expires_at.to_rfc3339(),
+        decided_at: operation.decided_at.map(|decided_at| decided_at.to_rfc3339()),
+        created_at: operation.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+        updated_at: operation.updated_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    })
+}
+
+
+
+pub struct McpOperationUseCase<R, P, I, T, C, E>
+where
+    R: McpOperationRepositoryBase + McpOperationRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+    T: TransferUsecase + Send + Sync,
+    C: ContactUsecase + Send + Sync,
+    E: ExpenseUsecase + Send + Sync,
+{
+    operation_repo: Arc<R>,
+    payment_usecase: Arc<P>,
+    income_usecase: Arc<I>,
+    transfer_usecase: Arc<T>,
+    contact_usecase: Arc<C>,
+    expense_usecase: Arc<E>,
+}
+
+impl<R, P, I, T, C, E> McpOperationUseCase<R, P, I, T, C, E>
+where
+    R: McpOperationRepositoryBase + McpOperationRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+    T: TransferUsecase + Send + Sync,
+    C: ContactUsecase + Send + Sync,
+    E: ExpenseUsecase + Send + Sync,
+{
+    pub fn new(operation_repo: Arc<R>, payment_usecase: Arc<P>, income_usecase: Arc<I>, transfer_usecase: Arc<T>, contact_usecase: Arc<C>, expense_usecase: Arc<E>) -> Self {
+        Self {
+            operation_repo,
+            payment_usecase,
+            income_usecase,
+            transfer_usecase,
+            contact_usecase,
+            expense_usecase,
+        }
+    }
+
+    // the assistant only knows the kind of transaction and usually means "now",
+    // so both are filled in before the payload is read as a create request
+    async fn fill_create_defaults(&self, payload: Option<Value>, transaction_type: TransactionTypeVariant) -> Result<Option<Value>, UsecaseError> {
+        let mut fields = match payload {
+            Some(Value::Object(fields)) => fields,
+            None => Map::new(),
+            Some(_) => return Err(UsecaseError::InvalidData("The payload must be a JSON object".to_string())),
+        };
+        if !fields.contains_key("transaction_type_id") {
+            let transaction_type_id = self.operation_repo.find_transaction_type_id(transaction_type).await?;
+            fields.insert("transaction_type_id".to_string(), Value::String(transaction_type_id.to_string()));
+        }
+        if !fields.contains_key("occurred_at") && !fields.contains_key("created_at") {
+            fields.insert("occurred_at".to_string(), Value::String(Utc::now().trunc_subsecs(0).to_rfc3339()));
+        }
+        Ok(Some(Value::Object(fields)))
+    }
+
+    // Reads the payload as the request the operation will run with and works out how it moves the balances
+    async fn prepare_payload(
+        &self,
+        operation: McpOperationVariant,
+        payload: Option<Value>,
+        snapshot: Option<&McpTransactionSnapshotDto>,
+    )
+        -> Result<(Option<Value>, Vec<(Uuid, Decimal)>), UsecaseError>
+    {
+        let snapshot_of = || snapshot.ok_or_else(|| UsecaseError::Unexpected("Missing target transaction".to_string()));
+
+        match operation {
+            McpOperationVariant::CreatePayment => {
+                let payment_dto: ReqCreatePaymentDto = from_payload(self.fill_create_defaults(payload, TransactionTypeVariant::Payment).await?)?;
+                validate_dto(&payment_dto)?;
+                let movements = movements_of(TransactionTypeVariant::Payment, payment_dto.amount.0, parse_uuid(&payment_dto.asset_id, "asset_id")?, None);
+                Ok((to_payload(&payment_dto)?, movements))
+            }
+            McpOperationVariant::CreateIncome => {
+                let income_dto: ReqCreateIncomeDto = from_payload(self.fill_create_defaults(payload, TransactionTypeVariant::Income).await?)?;
+                validate_dto(&income_dto)?;
+                let movements = movements_of(TransactionTypeVariant::Income, income_dto.amount.0, parse_uuid(&income_dto.asset_id, "asset_id")?, None);
+                Ok((to_payload(&income_dto)?, movements))
+            }
+            McpOperationVariant::CreateTransfer => {
+                let transfer_dto: ReqCreateTransferDto = from_payload(self.fill_create_defaults(payload, TransactionTypeVariant::Transfer).await?)?;
+                validate_dto(&transfer_dto)?;
+                let movements = movements_of(
+                    TransactionTypeVariant::Transfer,
+                    transfer_dto.amount.0,
+                    parse_uuid(&transfer_dto.asset_id, "asset_id")?,
+                    Some(parse_uuid(&transfer_dto.destination_asset_id, "destination_asset_id")?),
+                );
+                Ok((to_payload(&transfer_dto)?, movements))
+            }
+            McpOperationVariant::UpdatePayment => {
+                let payment_dto: ReqUpdatePaymentDto = from_payload(payload)?;
+                validate_dto(&payment_dto)?;
+                let snapshot = snapshot_of()?;
+                let mut movements = reversed_movements_of(snapshot);
+                movements.extend(movements_of(
+                    TransactionTypeVariant::Payment,
+                    payment_dto.amount.map(|amount| amount.0).unwrap_or(snapshot.amount),
+                    payment_dto.asset_id.as_deref().map(|id| parse_uuid(id, "asset_id")).transpose()?.unwrap_or(snapshot.asset_id),
+                    None,
+                ));
+                Ok((to_payload(&payment_dto)?, movements))
+            }
+            McpOperationVariant::UpdateIncome => {
+                let income_dto: ReqUpdateIncomeDto = from_payload(payload)?;
+                validate_dto(&income_dto)?;
+                let snapshot = snapshot_of()?;
+                let mut movements = reversed_movements_of(snapshot);
+                movements.extend(movements_of(
+                    TransactionTypeVariant::Income,
+                    income_dto.amount.map(|amount| amount.0).unwrap_or(snapshot.amount),
+                    income_dto.asset_id.as_deref().map(|id| parse_uuid(id, "asset_id")).transpose()?.unwrap_or(snapshot.asset_id),
+                    None,
+                ));
+                Ok((to_payload(&income_dto)?, movements))
+            }
+            McpOperationVariant::UpdateTransfer => {
+                let transfer_dto: ReqUpdateTransferDto = from_payload(payload)?;
+                validate_dto(&transfer_dto)?;
+                let snapshot = snapshot_of()?;
+                let destination_asset_id = match transfer_dto.destination_asset_id.as_deref() {
+                    Some(id) => Some(parse_uuid(id, "destination_asset_id")?),
+                    None => snapshot.destination_asset_id,
+                };
+                let mut movements = reversed_movements_of(snapshot);
+                movements.extend(movements_of(
+                    TransactionTypeVariant::Transfer,
+                    transfer_dto.amount.map(|amount| amount.0).unwrap_or(snapshot.amount),
+                    transfer_dto.asset_id.as_deref().map(|id| parse_uuid(id, "asset_id")).transpose()?.unwrap_or(snapshot.asset_id),
+                    destination_asset_id,
+                ));
+                Ok((to_payload(&transfer_dto)?, movements))
+            }
+            McpOperationVariant::DeletePayment | McpOperationVariant::DeleteIncome | McpOperationVariant::DeleteTransfer => {
+                Ok((None, reversed_movements_of(snapshot_of()?)))
+            }
+            McpOperationVariant::CreateContact => {
+                let contact_dto: ReqCreateContactDto = from_payload(payload)?;
+                validate_dto(&contact_dto)?;
+                Ok((to_payload(&contact_dto)?, Vec::new()))
+            }
+            McpOperationVariant::UpdateContact => {
+                let contact_dto: ReqUpdateContactDto = from_payload(payload)?;
+                Ok((to_payload(&contact_dto)?, Vec::new()))
+            }
+            McpOperationVariant::CreateExpense => {
+                let expense_dto: ReqCreateExpenseDto = from_payload(payload)?;
+                validate_dto(&expense_dto)?;
+                Ok((to_payload(&expense_dto)?, Vec::new()))
+            }
+            McpOperationVariant::UpdateExpense => {
+                let expense_dto: ReqUpdateExpenseDto = from_payload(payload)?;
+                validate_dto(&expense_dto)?;
+                Ok((to_payload(&expense_dto)?, Vec::new()))
+            }
+            McpOperationVariant::DeleteContact | McpOperationVariant::DeleteExpense => Ok((None, Vec::new())),
+        }
+    }
+
+    // Sums the movements per asset and puts them next to the current balances
+    async fn balance_impact(&self, user_id: Uuid, movements: Vec<(Uuid, Decimal)>) -> Result<Vec<ResMcpBalanceImpactDto>, UsecaseError> {
+        // Step 1: One change per asset, in the order the assets first appear
+        let mut changes: Vec<(Uuid, Decimal)> = Vec::new();
+        for (asset_id, change) in movements {
+            match changes.iter_mut().find(|(existing_id, _)| *existing_id == asset_id) {
+                Some((_, total)) => *total += change,
+                None => changes.push((asset_id, change)),
+            }
+        }
+        // an update that keeps the asset and amount moves nothing
+        changes.retain(|(_, change)| !change.is_zero());
+
+        // Step 2: The assets must be the user's, their balances are shown as they are now
+        let balances = self
+            .operation_repo
+            .find_asset_balances(user_id, changes.iter().map(|(asset_id, _)| *asset_id).collect())
+            .await?;
+
+        Ok(balances
+            .into_iter()
+            .zip(changes)
+            .map(|(balance, (_, change))| ResMcpBalanceImpactDto {
+                asset_id: balance.asset_id.to_string(),
+                asset_name: balance.asset_name,
+                current_balance: DecimalWrapper(balance.balance),
+                change: DecimalWrapper(change),
+                balance_after: DecimalWrapper(balance.balance + change),
+            })
+            .collect())
+    }
+
+    // Runs the confirmed operation, the result is the created or updated record
+    async fn execute(&self, user_id: Uuid, operation: McpOperationVariant, target_id: Option<Uuid>, payload: Option<Value>) -> Result<Option<Value>, UsecaseError> {
+        let target = || target_id.ok_or_else(|| UsecaseError::Unexpected(format!("{} has no target", operation.as_str())));
+
+        match operation {
+            McpOperationVariant::CreatePayment => to_payload(&self.payment_usecase.create_payment(user_id, from_payload(payload)?).await?),
+            McpOperationVariant::UpdatePayment => to_payload(&self.payment_usecase.update_payment(user_id, target()?, from_payload(payload)?).await?),
+            McpOperationVariant::DeletePayment => self.payment_usecase.delete_payment(user_id, target()?).await.map(|_| None),
+            McpOperationVariant::CreateIncome => to_payload(&self.income_usecase.create_income(user_id, from_payload(payload)?).await?),
+            McpOperationVariant::UpdateIncome => to_payload(&self.income_usecase.update_income(user_id, target()?, from_payload(payload)?).await?),
+            McpOperationVariant::DeleteIncome => self.income_usecase.delete_income(user_id, target()?).await.map(|_| None),
+            McpOperationVariant::CreateTransfer => to_payload(&self.transfer_usecase.create_transfer(user_id, from_payload(payload)?).await?),
+            McpOperationVariant::UpdateTransfer => to_payload(&self.transfer_usecase.update_transfer(user_id, target()?, from_payload(payload)?).await?),
+            McpOperationVariant::DeleteTransfer => self.transfer_usecase.delete_transfer(user_id, target()?).await.map(|_| None),
+            McpOperationVariant::CreateContact => to_payload(&self.contact_usecase.create_contact(user_id, from_payload(payload)?).await?),
+            McpOperationVariant::UpdateContact => to_payload(&self.contact_usecase.update_contact(user_id, target()?, from_payload(payload)?).await?),
+            McpOperationVariant::DeleteContact => self.contact_usecase.delete_contact(user_id, target()?).await.map(|_| None),
+            McpOperationVariant::CreateExpense => to_payload(&self.expense_usecase.create_expense(user_id, from_payload(payload)?).await?),
+            McpOperationVariant::UpdateExpense => to_payload(&self.expense_usecase.update_expense(user_id, target()?, from_payload(payload)?).await?),
+            McpOperationVariant::DeleteExpense => self.expense_usecase.delete_expense(user_id, target()?).await.map(|_| None),
+        }
+    }
+
+    // Expires what is overdue and returns the operation if it is still pending
+    async fn find_pending(&self, user_id: Uuid, operation_id: Uuid) -> Result<mcp_pending_operation::Model, UsecaseError> {
+        self.operation_repo.expire_pending(user_id, Utc::now()).await?;
+        let pending = self
+            .operation_repo
+            .find_by_id(user_id, operation_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(format!("Operation {} not found", operation_id)))?;
+
+        let status = status_of(&pending)?;
+        if status != McpOperationStatusVariant::Pending {
+            return Err(UsecaseError::Conflict(format!("The operation is already {}", status.as_str())));
+        }
+        Ok(pending)
+    }
+
+    async fn refreshed_entry(&self, user_id: Uuid, operation_id: Uuid) -> Result<ResMcpPendingOperationDto, UsecaseError> {
+        let operation = self
+            .operation_repo
+            .find_by_id(user_id, operation_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(format!("Operation {} not found", operation_id)))?;
+        to_operation_entry(operation)
+    }
+}
+
+
+
+#[async_trait::async_trait]
+impl<R, P, I, T, C, E> McpOperationUsecase for McpOperationUseCase<R, P, I, T, C, E>
+where
+    R: McpOperationRepositoryBase + McpOperationRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+    T: TransferUsecase + Send + Sync,
+    C: ContactUsecase + Send + Sync,
+    E: ExpenseUsecase + Send + Sync,
+{
+    async fn propose_operation(
+        &self,
+        user_id: Uuid,
+        operation_dto: ReqMcpProposeOperationDto
+    )
+        -> Result<ResMcpPendingOperationDto, UsecaseError>
+    {
+        let operation = operation_dto.operation;
+
+        // Step 1: Updates and deletes name the record they change, creates must not
+        let target_id = match (operation.requires_target(), operation_dto.target_id) {
+            (true, Some(target_id)) => Some(parse_uuid(&target_id, "target_id")?),
+            (true, None) => return Err(UsecaseError::InvalidData(format!("{} needs a target_id", operation.as_str()))),
+            (false, Some(_)) => return Err(UsecaseError::InvalidData(format!("{} takes no target_id", operation.as_str()))),
+            (false, None) => None,
+        };
+
+        // Step 2: The target as it is now, confirming checks it has not changed since
+        let target_updated_at = match target_id {
+            Some(target_id) => self.operation_repo.find_target_updated_at(user_id, operation, target_id).await?,
+            None => None,
+        };
+        let snapshot = match (operation.transaction_type(), target_id) {
+            (Some(transaction_type), Some(target_id)) => {
+                let snapshot = self
+                    .operation_repo
+                    .find_transaction_snapshot(user_id, target_id)
+                    .await?
+                    .filter(|snapshot| snapshot.transaction_type == transaction_type)
+                    .ok_or_else(|| UsecaseError::ResourceNotFound(format!("{} {} not found", transaction_type.as_str(), target_id)))?;
+                Some(snapshot)
+            }
+            _ => None,
+        };
+
+        // Step 3: Read the payload as the request it will run with and work out the balance impact
+        let (payload, movements) = self.prepare_payload(operation, operation_dto.payload, snapshot.as_ref()).await?;
+        let balance_impact = self.balance_impact(user_id, movements).await?;
+        let balance_impact = serde_json::to_value(&balance_impact).map_err(|err| UsecaseError::Unexpected(err.to_string()))?;
+
+        // Step 4: Keep it pending until the user decides
+        let created = self
+            .operation_repo
+            .create(user_id, CreateMcpOperationDto {
+                operation,
+                target_id,
+                target_updated_at,
+                payload,
+                balance_impact,
+                expires_at: Utc::now().trunc_subsecs(0) + Duration::hours(PENDING_OPERATION_TTL_HOURS),
+            })
+            .await?;
+        to_operation_entry(created)
+    }
+
+    async fn get_operation(
+        &self,
+        user_id: Uuid,
+        operation_id: Uuid
+    )
+        -> Result<Option<ResMcpPendingOperationDto>, UsecaseError>
+    {
+        self.operation_repo.expire_pending(user_id, Utc::now()).await?;
+        match self.operation_repo.find_by_id(user_id, operation_id).await? {
+            Some(operation) => Ok(Some(to_operation_entry(operation)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_all_operation(
+        &self,
+        user_id: Uuid,
+        status: Option<McpOperationStatusVariant>
+    )
+        -> Result<ResListMcpPendingOperationDto, UsecaseError>
+    {
+        self.operation_repo.expire_pending(user_id, Utc::now()).await?;
+        let operations = self.operation_repo.find_all_by_user_id(user_id, status).await?;
+        let data = operations
+            .into_iter()
+            .map(to_operation_entry)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ResListMcpPendingOperationDto {
+            length: data.len() as i32,
+            data,
+        })
+    }
+
+    async fn confirm_operation(
+        &self,
+        user_id: Uuid,
+        operation_id: Uuid
+    )
+        -> Result<ResMcpPendingOperationDto, UsecaseError>
+    {
+        // Step 1: Only a pending operation that has not expired can be confirmed
+        let pending = self.find_pending(user_id, operation_id).await?;
+        let operation = operation_of(&pending)?;
+        let target_id = pending.target_id.as_deref().map(uuid_of).transpose()?;
+
+        // Step 2: The balance impact the user saw was worked out from the target as it was then
+        if let Some(target_id) = target_id {
+            let changed = match self.operation_repo.find_target_updated_at(user_id, operation, target_id).await {
+                Ok(updated_at) => updated_at != pending.target_updated_at,
+                Err(RepositoryError::NotFound(_)) => true,
+                Err(err) => return Err(err.into()),
+            };
+            if changed {
+                let message = "The target changed after the operation was proposed".to_string();
+                self.operation_repo
+                    .update_status(user_id, operation_id, McpOperationStatusVariant::Pending, McpOperationStatusVariant::Failed, None, Some(message.clone()))
+                    .await?;
+                return Err(UsecaseError::Conflict(message));
+            }
+        }
+
+        // Step 3: Claim it, a second confirmation finds it no longer pending
+        let claimed = self
+            .operation_repo
+            .update_status(user_id, operation_id, McpOperationStatusVariant::Pending, McpOperationStatusVariant::Confirmed, None, None)
+            .await?;
+        if !claimed {
+            return Err(UsecaseError::Conflict("The operation was decided by another request".to_string()));
+        }
+
+        // Step 4: Run it and keep the outcome
+        match self.execute(user_id, operation, target_id, pending.payload).await {
+            Ok(result) => {
+                self.operation_repo
+                    .update_status(user_id, operation_id, McpOperationStatusVariant::Confirmed, McpOperationStatusVariant::Confirmed, result, None)
+                    .await?;
+            }
+            Err(err) => {
+                self.operation_repo
+                    .update_status(user_id, operation_id, McpOperationStatusVariant::Confirmed, McpOperationStatusVariant::Failed, None, Some(err.to_string()))
+                    .await?;
+                return Err(err);
+            }
+        }
+
+        self.refreshed_entry(user_id, operation_id).await
+    }
+
+    async fn reject_operation(
+        &self,
+        user_id: Uuid,
+        operation_id: Uuid
+    )
+        -> Result<ResMcpPendingOperationDto, UsecaseError>
+    {
+        self.find_pending(user_id, operation_id).await?;
+        let rejected = self
+            .operation_repo
+            .update_status(user_id, operation_id, McpOperationStatusVariant::Pending, McpOperationStatusVariant::Rejected, None, None)
+            .await?;
+        if !rejected {
+            return Err(UsecaseError::Conflict("The operation was decided by another request".to_string()));
+        }
+
+        self.refreshed_entry(user_id, operation_id).await
+    }
+}
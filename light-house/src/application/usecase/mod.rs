@@ -8,3 +8,14 @@ pub mod expense_usecase;
 pub mod transaction;
 pub mod current_sheet_usecase;
 pub mod wrapper;
+
+pub mod report_usecase;
+pub mod budget_usecase;
+pub mod recurring_transaction_usecase;
+
+pub mod mcp_operation_usecase;
+pub mod api_token_usecase;
+pub mod user_data_usecase;
+pub mod tag_usecase;
+pub mod attachment_usecase;
+pub mod import_usecase;
\ No newline at end of file
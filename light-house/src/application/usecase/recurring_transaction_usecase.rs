@@ -0,0 +1,614 @@
+use std::{collections::HashSet, str::FromStr, sync::Arc};
+
+use chrono::{DateTime, Duration, NaiveDate, SubsecRound, Utc};
+use chrono_tz::Tz;
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::{recurring_transaction_usecase::{RecurringTransactionRunnerUsecase, RecurringTransactionUsecase}, transaction_usecase::{RecordIncomeUsecase, RecordPaymentUsecase, TransferUsecase}}, domain::{dto::{dto_enum::{recurring_schedule_type::RecurringScheduleVariant, recurring_status::{RecurringOccurrenceStatusVariant, RecurringStatusVariant}, transaction_type::TransactionTypeVariant}, recurring_transaction_dto::{occurrence_reference, CreateRecurringTransactionDto, ReqUpdateRecurringTransactionDto, ResEntryRecurringTransactionDto, ResListRecurringOccurrenceDto, ResListRecurringTransactionDto, ResRecurringOccurrenceDto, ResRecurringPreviewDto, ResRecurringPreviewEntryDto}, transaction_dto::{ReqCreateIncomeDto, ReqCreatePaymentDto, ReqCreateTransferDto}}, entities::{recurring_occurrence, recurring_transaction}, req_repository::recurring_transaction_repository::{RecurringTransactionRepositoryBase, RecurringTransactionRepositoryUtility}}, implentation::{date_time_utill::{parse_occurred_at, parse_timezone}, decimal_utoipa::DecimalWrapper, recurrence_rule::RecurrenceRule}, soc::soc_usecase::UsecaseError};
+
+
+
+
+// schedules and occurrences handled per worker tick, the rest waits for the next tick
+const MAX_DUE_SCHEDULES: u64 = 100;
+const MAX_CATCH_UP_OCCURRENCES: usize = 100;
+// how far ahead a skip by date looks for the occurrence
+const MAX_SKIP_LOOKAHEAD: usize = 400;
+// a pending claim older than this was left behind by a run that stopped before recording its result
+const STALE_CLAIM_MINUTES: i64 = 10;
+
+
+fn uuid_string(id: &[u8]) -> Result<String, UsecaseError> {
+    Uuid::from_slice(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+fn uuid_of(id: &[u8]) -> Result<Uuid, UsecaseError> {
+    Uuid::from_slice(id).map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+fn rule_of(recurring: &recurring_transaction::Model) -> Result<RecurrenceRule, UsecaseError> {
+    RecurrenceRule::from_str(&recurring.rrule).map_err(UsecaseError::Unexpected)
+}
+
+fn timezone_of(recurring: &recurring_transaction::Model) -> Result<Tz, UsecaseError> {
+    parse_timezone(&recurring.timezone).map_err(UsecaseError::Unexpected)
+}
+
+fn status_of(recurring: &recurring_transaction::Model) -> Result<RecurringStatusVariant, UsecaseError> {
+    RecurringStatusVariant::from_str(&recurring.status).map_err(UsecaseError::Unexpected)
+}
+
+// the template keeps a destination only for transfers and an expense only for payments
+fn transaction_type_of(recurring: &recurring_transaction::Model) -> TransactionTypeVariant {
+    if recurring.destination_asset_id.is_some() {
+        TransactionTypeVariant::Transfer
+    } else if recurring.expense_id.is_some() {
+        TransactionTypeVariant::Payment
+    } else {
+        TransactionTypeVariant::Income
+    }
+}
+
+// occurrences of the schedule at or after `from`, within end_at
+fn occurrences_from(
+    recurring: &recurring_transaction::Model,
+    from: DateTime<Utc>,
+    until: Option<DateTime<Utc>>,
+    limit: usize,
+) -> Result<Vec<DateTime<Utc>>, UsecaseError> {
+    let until = match (recurring.end_at, until) {
+        (Some(end_at), Some(until)) => Some(end_at.min(until)),
+        (end_at, until) => end_at.or(until),
+    };
+    Ok(rule_of(recurring)?.occurrences(recurring.start_at, timezone_of(recurring)?, Some(from), until, limit))
+}
+
+fn next_occurrence(recurring: &recurring_transaction::Model, from: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, UsecaseError> {
+    Ok(occurrences_from(recurring, from, None, 1)?.first().copied())
+}
+
+fn to_recurring_entry(recurring: recurring_transaction::Model) -> Result<ResEntryRecurringTransactionDto, UsecaseError> {
+    Ok(ResEntryRecurringTransactionDto {
+        id: uuid_string(&recurring.id)?,
+        name: recurring.name.clone(),
+        transaction_type: transaction_type_of(&recurring),
+        amount: DecimalWrapper(recurring.amount),
+        asset_id: uuid_string(&recurring.asset_id)?,
+        destination_asset_id: recurring.destination_asset_id.as_deref().map(uuid_string).transpose()?,
+        expense_id: recurring.expense_id.as_deref().map(uuid_string).transpose()?,
+        contact_id: uuid_string(&recurring.contact_id)?,
+        note: recurring.note.clone(),
+        schedule_type: RecurringScheduleVariant::from_str(&recurring.schedule_type).map_err(UsecaseError::Unexpected)?,
+        rrule: recurring.rrule.clone(),
+        timezone: recurring.timezone.clone(),
+        start_at: recurring.start_at.to_rfc3339(),
+        end_at: recurring.end_at.map(|end_at| end_at.to_rfc3339()),
+        status: status_of(&recurring)?,
+        next_run_at: recurring.next_run_at.map(|next_run_at| next_run_at.to_rfc3339()),
+        last_run_at: recurring.last_run_at.map(|last_run_at| last_run_at.to_rfc3339()),
+        created_at: recurring.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+        updated_at: recurring.updated_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    })
+}
+
+fn to_occurrence_entry(occurrence: recurring_occurrence::Model) -> Result<ResRecurringOccurrenceDto, UsecaseError> {
+    Ok(ResRecurringOccurrenceDto {
+        scheduled_for: occurrence.scheduled_for.to_rfc3339(),
+        status: RecurringOccurrenceStatusVariant::from_str(&occurrence.status).map_err(UsecaseError::Unexpected)?,
+        transaction_id: occurrence.transaction_id.as_deref().map(uuid_string).transpose()?,
+        message: occurrence.message,
+    })
+}
+
+
+
+pub struct RecurringTransactionUseCase<R, P, I, T>
+where
+    R: RecurringTransactionRepositoryBase + RecurringTransactionRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+    T: TransferUsecase + Send + Sync,
+{
+    recurring_repo: Arc<R>,
+    payment_usecase: Arc<P>,
+    income_usecase: Arc<I>,
+    transfer_usecase: Arc<T>,
+}
+
+impl<R, P, I, T> RecurringTransactionUseCase<R, P, I, T>
+where
+    R: RecurringTransactionRepositoryBase + RecurringTransactionRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+    T: TransferUsecase + Send + Sync,
+{
+    pub fn new(recurring_repo: Arc<R>, payment_usecase: Arc<P>, income_usecase: Arc<I>, transfer_usecase: Arc<T>) -> Self {
+        Self {
+            recurring_repo,
+            payment_usecase,
+            income_usecase,
+            transfer_usecase,
+        }
+    }
+
+    async fn find_owned(&self, user_id: Uuid, recurring_transaction_id: Uuid) -> Result<recurring_transaction::Model, UsecaseError> {
+        match self.recurring_repo.find_by_id(user_id, recurring_transaction_id).await? {
+            Some(recurring) => Ok(recurring),
+            None => Err(UsecaseError::ResourceNotFound("Recurring transaction not found".to_string())),
+        }
+    }
+
+    // Posts the template through the matching use case with occurred_at set to the occurrence,
+    // the external_reference ties the transaction to the occurrence so it can only go in once
+    async fn post_occurrence(&self, recurring: &recurring_transaction::Model, scheduled_for: DateTime<Utc>, external_reference: String) -> Result<Uuid, UsecaseError> {
+        let user_id = uuid_of(&recurring.user_id)?;
+        let transaction_type_id = uuid_string(&recurring.transaction_type_id)?;
+        let asset_id = uuid_string(&recurring.asset_id)?;
+        let contact_id = uuid_string(&recurring.contact_id)?;
+
+        let transaction_id = match transaction_type_of(recurring) {
+            TransactionTypeVariant::Payment => {
+                let payment_dto = ReqCreatePaymentDto {
+                    transaction_type_id,
+                    amount: DecimalWrapper(recurring.amount),
+                    expense_id: recurring.expense_id.as_deref().map(uuid_string).transpose()?.unwrap_or_default(),
+                    asset_id,
+                    contact_id,
+                    note: recurring.note.clone(),
+                    occurred_at: scheduled_for.to_rfc3339(),
+                    timezone: None,
+                    tag_ids: None,
+                    external_reference: Some(external_reference),
+                    splits: None,
+                };
+                self.payment_usecase.create_payment(user_id, payment_dto).await?.id
+            }
+            TransactionTypeVariant::Income => {
+                let income_dto = ReqCreateIncomeDto {
+                    transaction_type_id,
+                    amount: DecimalWrapper(recurring.amount),
+                    asset_id,
+                    contact_id,
+                    note: recurring.note.clone(),
+                    occurred_at: scheduled_for.to_rfc3339(),
+                    timezone: None,
+                    tag_ids: None,
+                    external_reference: Some(external_reference),
+                };
+                self.income_usecase.create_income(user_id, income_dto).await?.id
+            }
+            TransactionTypeVariant::Transfer => {
+                let transfer_dto = ReqCreateTransferDto {
+                    transaction_type_id,
+                    amount: DecimalWrapper(recurring.amount),
+                    asset_id,
+                    destination_asset_id: recurring.destination_asset_id.as_deref().map(uuid_string).transpose()?.unwrap_or_default(),
+                    contact_id,
+                    note: recurring.note.clone(),
+                    occurred_at: scheduled_for.to_rfc3339(),
+                    timezone: None,
+                    tag_ids: None,
+                    external_reference: Some(external_reference),
+                };
+                self.transfer_usecase.create_transfer(user_id, transfer_dto).await?.id
+            }
+        };
+
+        Uuid::parse_str(&transaction_id).map_err(|err| UsecaseError::Unexpected(err.to_string()))
+    }
+
+    // Claims the occurrence, posts it and records the outcome, returns whether a transaction was posted
+    async fn handle_occurrence(&self, recurring: &recurring_transaction::Model, scheduled_for: DateTime<Utc>, now: DateTime<Utc>) -> Result<bool, UsecaseError> {
+        let recurring_transaction_id = uuid_of(&recurring.id)?;
+
+        // Step 1: The unique claim row keeps the occurrence from being posted twice
+        let occurrence = match self
+            .recurring_repo
+            .claim_occurrence(recurring_transaction_id, scheduled_for, RecurringOccurrenceStatusVariant::Pending)
+            .await?
+        {
+            Some(occurrence) => occurrence,
+            None => {
+                // already posted, skipped or failed, unless a previous run stopped halfway
+                let existing = self.recurring_repo.find_occurrence(recurring_transaction_id, scheduled_for).await?;
+                match existing {
+                    Some(occurrence)
+                        if occurrence.status == RecurringOccurrenceStatusVariant::Pending.as_str()
+                            && occurrence.created_at.is_some_and(|created_at| created_at < now - Duration::minutes(STALE_CLAIM_MINUTES)) =>
+                    {
+                        // Step 2: Take the claim over in one update, a run racing for it gets false and leaves it
+                        let stale_before = now - Duration::minutes(STALE_CLAIM_MINUTES);
+                        if !self.recurring_repo.take_over_occurrence(uuid_of(&occurrence.id)?, stale_before, now).await? {
+                            return Ok(false);
+                        }
+                        occurrence
+                    }
+                    _ => return Ok(false),
+                }
+            }
+        };
+
+        // Step 3: Post and record the result, a failed occurrence is not retried
+        let occurrence_id = uuid_of(&occurrence.id)?;
+        let external_reference = occurrence_reference(occurrence_id);
+        let result = match self.post_occurrence(recurring, scheduled_for, external_reference.clone()).await {
+            // the reference is taken: the run that left the claim behind posted it before it stopped
+            Err(UsecaseError::Conflict(message)) => match self.recurring_repo.find_posted_transaction(recurring.clone(), &external_reference).await? {
+                Some(posted) => {
+                    self.recurring_repo
+                        .finish_occurrence(occurrence_id, RecurringOccurrenceStatusVariant::Posted, Some(uuid_of(&posted.id)?), None)
+                        .await?;
+                    return Ok(false);
+                }
+                None => Err(UsecaseError::Conflict(message)),
+            },
+            result => result,
+        };
+        match result {
+            Ok(transaction_id) => {
+                self.recurring_repo
+                    .finish_occurrence(occurrence_id, RecurringOccurrenceStatusVariant::Posted, Some(transaction_id), None)
+                    .await?;
+                Ok(true)
+            }
+            Err(err) => {
+                log::error!("Recurring transaction {} failed for {}: {}", recurring_transaction_id, scheduled_for, err);
+                self.recurring_repo
+                    .finish_occurrence(occurrence_id, RecurringOccurrenceStatusVariant::Failed, None, Some(err.to_string()))
+                    .await?;
+                Ok(false)
+            }
+        }
+    }
+
+    async fn run_schedule(&self, recurring: &recurring_transaction::Model, now: DateTime<Utc>) -> Result<usize, UsecaseError> {
+        let recurring_transaction_id = uuid_of(&recurring.id)?;
+        let from = match recurring.next_run_at {
+            Some(next_run_at) => next_run_at,
+            None => return Ok(0),
+        };
+
+        // Step 1: Every occurrence from next_run_at up to now, a server that was down catches up here
+        let mut posted = 0;
+        let mut last_run_at = None;
+        for scheduled_for in occurrences_from(recurring, from, Some(now), MAX_CATCH_UP_OCCURRENCES)? {
+            if self.handle_occurrence(recurring, scheduled_for, now).await? {
+                posted += 1;
+            }
+            last_run_at = Some(scheduled_for);
+        }
+
+        // Step 2: Move the schedule on, keeping a pause made while the run was going
+        let next_run_at = next_occurrence(recurring, last_run_at.unwrap_or(from) + Duration::seconds(1))?;
+        let user_id = uuid_of(&recurring.user_id)?;
+        let current_status = match self.recurring_repo.find_by_id(user_id, recurring_transaction_id).await? {
+            Some(current) => status_of(&current)?,
+            None => return Ok(posted),
+        };
+        let status = match (current_status, next_run_at) {
+            (RecurringStatusVariant::Paused, _) => RecurringStatusVariant::Paused,
+            (_, Some(_)) => RecurringStatusVariant::Active,
+            (_, None) => RecurringStatusVariant::Finished,
+        };
+        self.recurring_repo
+            .update_schedule_state(recurring_transaction_id, status, next_run_at, last_run_at)
+            .await?;
+
+        Ok(posted)
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<R, P, I, T> RecurringTransactionUsecase for RecurringTransactionUseCase<R, P, I, T>
+where
+    R: RecurringTransactionRepositoryBase + RecurringTransactionRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+    T: TransferUsecase + Send + Sync,
+{
+    async fn create_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        recurring_dto: CreateRecurringTransactionDto
+    )
+        -> Result<ResEntryRecurringTransactionDto, UsecaseError>
+    {
+        // the first occurrence is at or after start_at, one in the past is posted on the next tick
+        let timezone = parse_timezone(&recurring_dto.timezone).map_err(UsecaseError::InvalidData)?;
+        let next_run_at = recurring_dto
+            .rule
+            .occurrences(recurring_dto.start_at, timezone, None, recurring_dto.end_at, 1)
+            .first()
+            .copied();
+        if next_run_at.is_none() {
+            return Err(UsecaseError::InvalidData("The schedule has no occurrence".to_string()));
+        }
+
+        let recurring = self.recurring_repo.create(user_id, recurring_dto, next_run_at).await?;
+        to_recurring_entry(recurring)
+    }
+
+    async fn get_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        recurring_transaction_id: Uuid
+    )
+        -> Result<Option<ResEntryRecurringTransactionDto>, UsecaseError>
+    {
+        match self.recurring_repo.find_by_id(user_id, recurring_transaction_id).await? {
+            Some(recurring) => Ok(Some(to_recurring_entry(recurring)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn update_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        recurring_transaction_id: Uuid,
+        recurring_dto: ReqUpdateRecurringTransactionDto
+    )
+        -> Result<ResEntryRecurringTransactionDto, UsecaseError>
+    {
+        // Step 1: Parse the changes in the schedule's timezone
+        let existing = self.find_owned(user_id, recurring_transaction_id).await?;
+        let update_dto = recurring_dto.into_update(&existing.timezone).map_err(UsecaseError::InvalidData)?;
+        if let Some(end_at) = update_dto.end_at
+            && end_at < existing.start_at
+        {
+            return Err(UsecaseError::InvalidData("end_at must not be before start_at".to_string()));
+        }
+        let end_at_changed = update_dto.end_at.is_some();
+
+        // Step 2: Save, a new end_at can finish the schedule or give it more occurrences
+        let recurring = self.recurring_repo.update(user_id, recurring_transaction_id, update_dto).await?;
+        if !end_at_changed {
+            return to_recurring_entry(recurring);
+        }
+
+        let from = match (recurring.next_run_at, recurring.last_run_at) {
+            (Some(next_run_at), _) => next_run_at,
+            (None, Some(last_run_at)) => last_run_at + Duration::seconds(1),
+            (None, None) => recurring.start_at,
+        };
+        let next_run_at = next_occurrence(&recurring, from)?;
+        let status = match (status_of(&recurring)?, next_run_at) {
+            (RecurringStatusVariant::Paused, _) => RecurringStatusVariant::Paused,
+            (_, Some(_)) => RecurringStatusVariant::Active,
+            (_, None) => RecurringStatusVariant::Finished,
+        };
+        let recurring = self
+            .recurring_repo
+            .update_schedule_state(recurring_transaction_id, status, next_run_at, None)
+            .await?;
+
+        to_recurring_entry(recurring)
+    }
+
+    async fn delete_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        recurring_transaction_id: Uuid
+    )
+        -> Result<(), UsecaseError>
+    {
+        self.recurring_repo.delete(user_id, recurring_transaction_id).await?;
+        Ok(())
+    }
+
+    async fn get_all_recurring_transaction(
+        &self,
+        user_id: Uuid
+    )
+        -> Result<ResListRecurringTransactionDto, UsecaseError>
+    {
+        let recurring_list = self.recurring_repo.find_all_by_user_id(user_id).await?;
+
+        let mut data = Vec::with_capacity(recurring_list.len());
+        for recurring in recurring_list {
+            data.push(to_recurring_entry(recurring)?);
+        }
+
+        Ok(ResListRecurringTransactionDto { length: data.len() as i32, data })
+    }
+
+    async fn pause_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        recurring_transaction_id: Uuid
+    )
+        -> Result<ResEntryRecurringTransactionDto, UsecaseError>
+    {
+        let recurring = self.find_owned(user_id, recurring_transaction_id).await?;
+        match status_of(&recurring)? {
+            RecurringStatusVariant::Paused => to_recurring_entry(recurring),
+            RecurringStatusVariant::Finished => Err(UsecaseError::InvalidData("The recurring transaction has finished".to_string())),
+            RecurringStatusVariant::Active => {
+                let recurring = self
+                    .recurring_repo
+                    .update_schedule_state(recurring_transaction_id, RecurringStatusVariant::Paused, recurring.next_run_at, None)
+                    .await?;
+                to_recurring_entry(recurring)
+            }
+        }
+    }
+
+    async fn resume_recurring_transaction(
+        &self,
+        user_id: Uuid,
+        recurring_transaction_id: Uuid
+    )
+        -> Result<ResEntryRecurringTransactionDto, UsecaseError>
+    {
+        let recurring = self.find_owned(user_id, recurring_transaction_id).await?;
+        match status_of(&recurring)? {
+            RecurringStatusVariant::Active => to_recurring_entry(recurring),
+            RecurringStatusVariant::Finished => Err(UsecaseError::InvalidData("The recurring transaction has finished".to_string())),
+            RecurringStatusVariant::Paused => {
+                let next_run_at = next_occurrence(&recurring, Utc::now().trunc_subsecs(0))?;
+                let status = if next_run_at.is_some() { RecurringStatusVariant::Active } else { RecurringStatusVariant::Finished };
+                let recurring = self
+                    .recurring_repo
+                    .update_schedule_state(recurring_transaction_id, status, next_run_at, None)
+                    .await?;
+                to_recurring_entry(recurring)
+            }
+        }
+    }
+
+    async fn skip_occurrence(
+        &self,
+        user_id: Uuid,
+        recurring_transaction_id: Uuid,
+        occurrence: Option<String>
+    )
+        -> Result<ResRecurringOccurrenceDto, UsecaseError>
+    {
+        let recurring = self.find_owned(user_id, recurring_transaction_id).await?;
+        let timezone = timezone_of(&recurring)?;
+
+        // Step 1: Only occurrences that are still to come can be skipped
+        let earliest = match (status_of(&recurring)?, recurring.next_run_at) {
+            (RecurringStatusVariant::Finished, _) | (_, None) => {
+                return Err(UsecaseError::InvalidData("The recurring transaction has finished".to_string()));
+            }
+            (RecurringStatusVariant::Paused, Some(next_run_at)) => next_run_at.max(Utc::now().trunc_subsecs(0)),
+            (RecurringStatusVariant::Active, Some(next_run_at)) => next_run_at,
+        };
+
+        // Step 2: Find the occurrence, by local date or by its exact time
+        let scheduled_for = match occurrence.as_deref() {
+            None => next_occurrence(&recurring, earliest)?,
+            Some(value) => {
+                let upcoming = occurrences_from(&recurring, earliest, None, MAX_SKIP_LOOKAHEAD)?;
+                match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                    Ok(date) => upcoming.into_iter().find(|at| at.with_timezone(&timezone).date_naive() == date),
+                    Err(_) => {
+                        let at = parse_occurred_at(value, Some(&recurring.timezone)).map_err(UsecaseError::InvalidData)?;
+                        upcoming.into_iter().find(|upcoming_at| *upcoming_at == at)
+                    }
+                }
+            }
+        };
+        let scheduled_for = match scheduled_for {
+            Some(scheduled_for) => scheduled_for,
+            None => return Err(UsecaseError::InvalidData("No upcoming occurrence matches".to_string())),
+        };
+
+        // Step 3: A skipped row takes the occurrence's slot, the worker passes over it
+        let skipped = match self
+            .recurring_repo
+            .claim_occurrence(recurring_transaction_id, scheduled_for, RecurringOccurrenceStatusVariant::Skipped)
+            .await?
+        {
+            Some(skipped) => skipped,
+            None => return Err(UsecaseError::Conflict("The occurrence was already handled".to_string())),
+        };
+
+        if recurring.next_run_at == Some(scheduled_for) {
+            let next_run_at = next_occurrence(&recurring, scheduled_for + Duration::seconds(1))?;
+            let status = match (status_of(&recurring)?, next_run_at) {
+                (RecurringStatusVariant::Paused, _) => RecurringStatusVariant::Paused,
+                (_, Some(_)) => RecurringStatusVariant::Active,
+                (_, None) => RecurringStatusVariant::Finished,
+            };
+            self.recurring_repo
+                .update_schedule_state(recurring_transaction_id, status, next_run_at, None)
+                .await?;
+        }
+
+        to_occurrence_entry(skipped)
+    }
+
+    async fn preview_occurrences(
+        &self,
+        user_id: Uuid,
+        recurring_transaction_id: Uuid,
+        count: usize
+    )
+        -> Result<ResRecurringPreviewDto, UsecaseError>
+    {
+        let recurring = self.find_owned(user_id, recurring_transaction_id).await?;
+        let timezone = timezone_of(&recurring)?;
+        let status = status_of(&recurring)?;
+
+        // a paused schedule resumes from now, so that is where its preview starts
+        let from = match (status, recurring.next_run_at) {
+            (RecurringStatusVariant::Finished, _) | (_, None) => None,
+            (RecurringStatusVariant::Paused, Some(next_run_at)) => Some(next_run_at.max(Utc::now().trunc_subsecs(0))),
+            (RecurringStatusVariant::Active, Some(next_run_at)) => Some(next_run_at),
+        };
+
+        let mut data = Vec::new();
+        if let Some(from) = from {
+            let skipped: HashSet<DateTime<Utc>> = self
+                .recurring_repo
+                .find_occurrences(recurring_transaction_id, Some(from))
+                .await?
+                .into_iter()
+                .filter(|occurrence| occurrence.status == RecurringOccurrenceStatusVariant::Skipped.as_str())
+                .map(|occurrence| occurrence.scheduled_for)
+                .collect();
+
+            for scheduled_for in occurrences_from(&recurring, from, None, count)? {
+                data.push(ResRecurringPreviewEntryDto {
+                    scheduled_for: scheduled_for.to_rfc3339(),
+                    local_date: scheduled_for.with_timezone(&timezone).format("%Y-%m-%d").to_string(),
+                    skipped: skipped.contains(&scheduled_for),
+                });
+            }
+        }
+
+        Ok(ResRecurringPreviewDto {
+            recurring_transaction_id: recurring_transaction_id.to_string(),
+            status,
+            length: data.len() as i32,
+            data,
+        })
+    }
+
+    async fn get_occurrences(
+        &self,
+        user_id: Uuid,
+        recurring_transaction_id: Uuid
+    )
+        -> Result<ResListRecurringOccurrenceDto, UsecaseError>
+    {
+        self.find_owned(user_id, recurring_transaction_id).await?;
+        let occurrences = self.recurring_repo.find_occurrences(recurring_transaction_id, None).await?;
+
+        let mut data = Vec::with_capacity(occurrences.len());
+        for occurrence in occurrences {
+            data.push(to_occurrence_entry(occurrence)?);
+        }
+
+        Ok(ResListRecurringOccurrenceDto { length: data.len() as i32, data })
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<R, P, I, T> RecurringTransactionRunnerUsecase for RecurringTransactionUseCase<R, P, I, T>
+where
+    R: RecurringTransactionRepositoryBase + RecurringTransactionRepositoryUtility + Send + Sync,
+    P: RecordPaymentUsecase + Send + Sync,
+    I: RecordIncomeUsecase + Send + Sync,
+    T: TransferUsecase + Send + Sync,
+{
+    async fn run_due_occurrences(&self, now: DateTime<Utc>) -> Result<usize, UsecaseError> {
+        let due = self.recurring_repo.find_due(now, MAX_DUE_SCHEDULES).await?;
+
+        // one broken schedule must not hold back the others
+        let mut posted = 0;
+        for recurring in due {
+            match self.run_schedule(&recurring, now).await {
+                Ok(count) => posted += count,
+                Err(err) => log::error!("Failed to run recurring transaction {}: {}", recurring.name, err),
+            }
+        }
+
+        Ok(posted)
+    }
+}
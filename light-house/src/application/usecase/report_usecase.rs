@@ -0,0 +1,283 @@
+use std::{collections::{BTreeMap, HashMap}, sync::Arc};
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::report_usecase::ReportUsecase, domain::{dto::{dto_enum::{expense_report_group::ExpenseReportGroupVariant, report_period::ReportPeriodVariant}, report_dto::{ReportGroupTotalDto, ResExpenseReportDto, ResExpenseReportGroupDto, ResReportBreakdownDto, ResReportPeriodDto, ResReportSummaryDto}}, req_repository::report_repository::ReportRepositoryUtility}, implentation::decimal_utoipa::DecimalWrapper, soc::soc_usecase::UsecaseError};
+
+
+
+
+const MAX_REPORT_PERIODS: usize = 1000;
+
+
+#[derive(Default)]
+struct PeriodTotals {
+    income: Decimal,
+    payment: Decimal,
+    transfer: Decimal,
+    income_count: i64,
+    payment_count: i64,
+    transfer_count: i64,
+}
+
+
+// sums rows sharing the same group, biggest total first
+fn to_breakdown<'a>(rows: impl Iterator<Item = &'a ReportGroupTotalDto>, fallback_name: &str) -> Result<Vec<ResReportBreakdownDto>, UsecaseError> {
+    let mut groups: HashMap<Option<Vec<u8>>, (String, Decimal, i64)> = HashMap::new();
+    for row in rows {
+        let entry = groups
+            .entry(row.group_id.clone())
+            .or_insert_with(|| (row.group_name.clone().unwrap_or_else(|| fallback_name.to_string()), Decimal::ZERO, 0));
+        entry.1 += row.total;
+        entry.2 += row.count;
+    }
+
+    let mut breakdown = groups
+        .into_iter()
+        .map(|(group_id, (name, total, count))| {
+            let id = group_id.as_deref().map(uuid_string).transpose()?;
+            Ok(ResReportBreakdownDto { id, name, total: DecimalWrapper(total), count })
+        })
+        .collect::<Result<Vec<_>, UsecaseError>>()?;
+    breakdown.sort_by(|a, b| b.total.0.cmp(&a.total.0).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(breakdown)
+}
+
+// percent of `part` in `whole`, None when whole is zero
+fn percent_of(part: Decimal, whole: Decimal) -> Option<Decimal> {
+    if whole.is_zero() {
+        return None;
+    }
+    Some((part * Decimal::ONE_HUNDRED / whole).round_dp(2))
+}
+
+fn uuid_string(id: &[u8]) -> Result<String, UsecaseError> {
+    Uuid::from_slice(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+
+
+pub struct ReportUseCase<R>
+where
+    R: ReportRepositoryUtility + Send + Sync,
+{
+    report_repo: Arc<R>,
+}
+
+impl<R> ReportUseCase<R>
+where
+    R: ReportRepositoryUtility + Send + Sync,
+{
+    pub fn new(report_repo: Arc<R>) -> Self {
+        Self { report_repo }
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<R> ReportUsecase for ReportUseCase<R>
+where
+    R: ReportRepositoryUtility + Send + Sync,
+{
+    async fn get_summary(
+        &self,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        period: ReportPeriodVariant,
+    ) -> Result<ResReportSummaryDto, UsecaseError> {
+        if from > to {
+            return Err(UsecaseError::InvalidData("from must not be after to".to_string()));
+        }
+
+        // Step 1: List the periods first so an oversized range fails before hitting the database
+        let mut period_starts = Vec::new();
+        let mut start = period.start_of(from);
+        while start <= to {
+            if period_starts.len() >= MAX_REPORT_PERIODS {
+                return Err(UsecaseError::InvalidData(format!(
+                    "The range holds more than {} {} periods, use a larger period",
+                    MAX_REPORT_PERIODS,
+                    period.as_str()
+                )));
+            }
+            period_starts.push(start);
+            start = period.next_start(start);
+        }
+
+        // Step 2: Grouped totals from the database
+        let type_totals = self.report_repo.sum_by_period_and_type(user_id, from, to, period).await?;
+        let payment_rows = self.report_repo.sum_payments_by_expense_type(user_id, from, to, period).await?;
+        let income_rows = self.report_repo.sum_incomes_by_contact(user_id, from, to, period).await?;
+
+        let mut totals_by_period: BTreeMap<NaiveDate, PeriodTotals> = BTreeMap::new();
+        for row in type_totals {
+            let totals = totals_by_period.entry(row.period_start).or_default();
+            match row.transaction_type_name.as_str() {
+                "income" => {
+                    totals.income += row.total;
+                    totals.income_count += row.count;
+                }
+                "payment" => {
+                    totals.payment += row.total;
+                    totals.payment_count += row.count;
+                }
+                "transfer" => {
+                    totals.transfer += row.total;
+                    totals.transfer_count += row.count;
+                }
+                other => log::error!("Skipping total {} with unknown transaction type {}", row.total, other),
+            }
+        }
+
+        // Step 3: One entry per period, empty periods included so charts have no gaps
+        let mut summary = PeriodTotals::default();
+        let mut data = Vec::with_capacity(period_starts.len());
+        for start in period_starts {
+            let next_start = period.next_start(start);
+            let key = start.date_naive();
+            let totals = totals_by_period.remove(&key).unwrap_or_default();
+
+            summary.income += totals.income;
+            summary.payment += totals.payment;
+            summary.transfer += totals.transfer;
+
+            data.push(ResReportPeriodDto {
+                period_start: start.max(from).to_rfc3339(),
+                period_end: (next_start - Duration::microseconds(1)).min(to).to_rfc3339(),
+                total_income: DecimalWrapper(totals.income),
+                total_payment: DecimalWrapper(totals.payment),
+                net_cash_flow: DecimalWrapper(totals.income - totals.payment),
+                transfer_volume: DecimalWrapper(totals.transfer),
+                income_count: totals.income_count,
+                payment_count: totals.payment_count,
+                transfer_count: totals.transfer_count,
+                payment_by_expense_type: to_breakdown(payment_rows.iter().filter(|row| row.period_start == key), "Uncategorized")?,
+                income_by_contact: to_breakdown(income_rows.iter().filter(|row| row.period_start == key), "No contact")?,
+            });
+        }
+
+        // Step 4: Return the response object
+        Ok(ResReportSummaryDto {
+            period,
+            from: from.to_rfc3339(),
+            to: to.to_rfc3339(),
+            total_income: DecimalWrapper(summary.income),
+            total_payment: DecimalWrapper(summary.payment),
+            net_cash_flow: DecimalWrapper(summary.income - summary.payment),
+            transfer_volume: DecimalWrapper(summary.transfer),
+            payment_by_expense_type: to_breakdown(payment_rows.iter(), "Uncategorized")?,
+            income_by_contact: to_breakdown(income_rows.iter(), "No contact")?,
+            length: data.len() as i32,
+            data,
+        })
+    }
+
+    async fn get_expense_report(
+        &self,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        group_by: ExpenseReportGroupVariant,
+        tag_id: Option<Uuid>,
+    ) -> Result<ResExpenseReportDto, UsecaseError> {
+        if from > to {
+            return Err(UsecaseError::InvalidData("from must not be after to".to_string()));
+        }
+
+        // Step 1: The previous range has the same length and ends right before `from`
+        let previous_to = from - Duration::microseconds(1);
+        let previous_from = previous_to - (to - from);
+
+        // Step 2: Grouped totals of both ranges from the database
+        let current_rows = self.report_repo.sum_payments_by_group(user_id, from, to, group_by, tag_id).await?;
+        let previous_rows = self.report_repo.sum_payments_by_group(user_id, previous_from, previous_to, group_by, tag_id).await?;
+
+        let fallback_name = match group_by {
+            ExpenseReportGroupVariant::ExpenseType | ExpenseReportGroupVariant::Expense => "Uncategorized",
+            ExpenseReportGroupVariant::Contact => "No contact",
+            ExpenseReportGroupVariant::Asset => "Unknown asset",
+            ExpenseReportGroupVariant::Tag => "Untagged",
+        };
+
+        // Step 3: Merge both ranges per group, a group paid only in the previous range shows with a zero total
+        let mut groups: HashMap<Option<Vec<u8>>, (String, Decimal, i64, Decimal)> = HashMap::new();
+        for row in current_rows {
+            let entry = groups
+                .entry(row.group_id)
+                .or_insert_with(|| (row.group_name.unwrap_or_else(|| fallback_name.to_string()), Decimal::ZERO, 0, Decimal::ZERO));
+            entry.1 += row.total;
+            entry.2 += row.count;
+        }
+        for row in previous_rows {
+            let entry = groups
+                .entry(row.group_id)
+                .or_insert_with(|| (row.group_name.unwrap_or_else(|| fallback_name.to_string()), Decimal::ZERO, 0, Decimal::ZERO));
+            entry.3 += row.total;
+        }
+
+        // a payment with several tags or split lines sits in several groups, so the totals come from a grouping without overlap
+        let overlapping = matches!(
+            group_by,
+            ExpenseReportGroupVariant::Tag | ExpenseReportGroupVariant::ExpenseType | ExpenseReportGroupVariant::Expense
+        );
+        let (total, count, previous_total) = if overlapping {
+            let current_totals = self.report_repo.sum_payments_by_group(user_id, from, to, ExpenseReportGroupVariant::Asset, tag_id).await?;
+            let previous_totals = self.report_repo.sum_payments_by_group(user_id, previous_from, previous_to, ExpenseReportGroupVariant::Asset, tag_id).await?;
+            (
+                current_totals.iter().map(|row| row.total).sum(),
+                current_totals.iter().map(|row| row.count).sum(),
+                previous_totals.iter().map(|row| row.total).sum(),
+            )
+        } else {
+            (
+                groups.values().map(|group| group.1).sum::<Decimal>(),
+                groups.values().map(|group| group.2).sum::<i64>(),
+                groups.values().map(|group| group.3).sum::<Decimal>(),
+            )
+        };
+
+        let mut data = groups
+            .into_iter()
+            .map(|(group_id, (name, group_total, group_count, group_previous_total))| {
+                Ok(ResExpenseReportGroupDto {
+                    id: group_id.as_deref().map(uuid_string).transpose()?,
+                    name,
+                    total: DecimalWrapper(group_total),
+                    count: group_count,
+                    share_percent: DecimalWrapper(percent_of(group_total, total).unwrap_or(Decimal::ZERO)),
+                    previous_total: DecimalWrapper(group_previous_total),
+                    change_amount: DecimalWrapper(group_total - group_previous_total),
+                    change_percent: percent_of(group_total - group_previous_total, group_previous_total).map(DecimalWrapper),
+                })
+            })
+            .collect::<Result<Vec<_>, UsecaseError>>()?;
+        data.sort_by(|a, b| {
+            b.total.0
+                .cmp(&a.total.0)
+                .then_with(|| b.previous_total.0.cmp(&a.previous_total.0))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        // Step 4: Return the response object
+        Ok(ResExpenseReportDto {
+            group_by,
+            from: from.to_rfc3339(),
+            to: to.to_rfc3339(),
+            previous_from: previous_from.to_rfc3339(),
+            previous_to: previous_to.to_rfc3339(),
+            total: DecimalWrapper(total),
+            count,
+            previous_total: DecimalWrapper(previous_total),
+            change_amount: DecimalWrapper(total - previous_total),
+            change_percent: percent_of(total - previous_total, previous_total).map(DecimalWrapper),
+            length: data.len() as i32,
+            data,
+        })
+    }
+}
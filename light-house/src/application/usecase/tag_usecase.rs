@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::tag_usecase::TagUsecase, domain::{dto::tag_dto::{ReqCreateTagDto, ReqUpdateTagDto, ResEntryTagDto, ResListTagDto}, entities::tag, req_repository::tag_repository::{TagRepositoryBase, TagRepositoryUtility}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+fn to_tag_entry(tag: tag::Model, transaction_count: i64) -> Result<ResEntryTagDto, UsecaseError> {
+    Ok(ResEntryTagDto {
+        id: Uuid::from_slice(&tag.id)
+            .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))?
+            .to_string(),
+        name: tag.name,
+        color: tag.color,
+        transaction_count,
+        created_at: tag.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+        updated_at: tag.updated_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    })
+}
+
+
+
+pub struct TagUseCase<T>
+where
+    T: TagRepositoryBase + TagRepositoryUtility + Send + Sync,
+{
+    tag_repo: Arc<T>,
+}
+
+impl<T> TagUseCase<T>
+where
+    T: TagRepositoryBase + TagRepositoryUtility + Send + Sync,
+{
+    pub fn new(tag_repo: Arc<T>) -> Self {
+        Self { tag_repo }
+    }
+
+    async fn transaction_count(&self, user_id: Uuid, tag_id: &[u8]) -> Result<i64, UsecaseError> {
+        let counts = self.tag_repo.count_transactions_by_tag(user_id).await?;
+        Ok(counts.get(tag_id).copied().unwrap_or(0))
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<T> TagUsecase for TagUseCase<T>
+where
+    T: TagRepositoryBase + TagRepositoryUtility + Send + Sync,
+{
+    async fn create_tag(&self, user_id: Uuid, tag_dto: ReqCreateTagDto) -> Result<ResEntryTagDto, UsecaseError> {
+        let tag = self.tag_repo.create(user_id, tag_dto).await?;
+        to_tag_entry(tag, 0)
+    }
+
+    async fn get_tag(&self, user_id: Uuid, tag_id: Uuid) -> Result<Option<ResEntryTagDto>, UsecaseError> {
+        match self.tag_repo.find_by_user_id_and_tag_id(tag_id, user_id).await? {
+            Some(tag) => {
+                let transaction_count = self.transaction_count(user_id, &tag.id).await?;
+                Ok(Some(to_tag_entry(tag, transaction_count)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn update_tag(&self, user_id: Uuid, tag_id: Uuid, tag_dto: ReqUpdateTagDto) -> Result<ResEntryTagDto, UsecaseError> {
+        let tag = self.tag_repo.update(tag_dto, user_id, tag_id).await?;
+        let transaction_count = self.transaction_count(user_id, &tag.id).await?;
+        to_tag_entry(tag, transaction_count)
+    }
+
+    async fn delete_tag(&self, user_id: Uuid, tag_id: Uuid) -> Result<(), UsecaseError> {
+        self.tag_repo.delete(user_id, tag_id).await?;
+        Ok(())
+    }
+
+    async fn get_all_tag(&self, user_id: Uuid) -> Result<ResListTagDto, UsecaseError> {
+        let tags = self.tag_repo.find_all_by_user_id(user_id).await?;
+        let counts = self.tag_repo.count_transactions_by_tag(user_id).await?;
+
+        let data = tags
+            .into_iter()
+            .map(|tag| {
+                let transaction_count = counts.get(&tag.id).copied().unwrap_or(0);
+                to_tag_entry(tag, transaction_count)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ResListTagDto {
+            length: data.len() as i32,
+            data,
+        })
+    }
+}
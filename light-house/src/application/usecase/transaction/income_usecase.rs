@@ -2,7 +2,12 @@ use std::sync::Arc;
 
 use uuid::Uuid;
 
-use crate::{application::usecase_req_impl::transaction_usecase::RecordIncomeUsecase, domain::{dto::transaction_dto::{ReqCreateIncomeDto, ReqUpdateIncomeDto, ResEntryIncomeDto, ResListIncomeDto}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, transaction_repository::{RecordIncomeRepositoryUtility, TransactionTypeRepositoryUtility}}}, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
+use crate::application::usecase_req_impl::attachment_usecase::AttachmentCleanupUsecase;
+use crate::domain::dto::dto_enum::attachment_owner::AttachmentOwnerVariant;
+use crate::implentation::decimal_utoipa::DecimalWrapper;
+use crate::domain::dto::pagination_dto::ResPageMetaDto;
+
+use crate::{application::usecase_req_impl::transaction_usecase::RecordIncomeUsecase, domain::{dto::{tag_dto::ResTransactionTagDto, transaction_dto::{ReqCreateIncomeDto, ReqUpdateIncomeDto, ResEntryIncomeDto, ResListIncomeDto}}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, tag_repository::TransactionTagRepositoryUtility, transaction_repository::{RecordIncomeRepositoryUtility, TransactionTypeRepositoryUtility}}}, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
 
 
 
@@ -11,7 +16,7 @@ use crate::{application::usecase_req_impl::transaction_usecase::RecordIncomeUsec
 
 pub struct IncomeUseCase<T, A, C, TT>
 where
-    T: RecordIncomeRepositoryUtility + Send + Sync,
+    T: RecordIncomeRepositoryUtility + TransactionTagRepositoryUtility + Send + Sync,
     A: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
     C: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync,
     TT: TransactionTypeRepositoryUtility + Send + Sync,
@@ -20,12 +25,13 @@ where
     asset_repo: Arc<A>,
     contact_repo: Arc<C>,
     transaction_type_repo: Arc<TT>,
+    attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>,
 }
 
 
 impl<T, A, C, TT> IncomeUseCase<T, A, C, TT>
 where
-    T: RecordIncomeRepositoryUtility + Send + Sync,
+    T: RecordIncomeRepositoryUtility + TransactionTagRepositoryUtility + Send + Sync,
     A: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
     C: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync,
     TT: TransactionTypeRepositoryUtility + Send + Sync,
@@ -35,21 +41,30 @@ where
         asset_repo: Arc<A>,
         contact_repo: Arc<C>,
         transaction_type_repo: Arc<TT>,
+        attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>,
     ) -> Self {
         Self {
             income_repo,
             asset_repo,
             contact_repo,
             transaction_type_repo,
+            attachment_cleanup,
         }
     }
+
+    // tags of one transaction as shown in its response
+    async fn transaction_tags(&self, user_id: Uuid, transaction_id: &[u8]) -> Result<Vec<ResTransactionTagDto>, UsecaseError> {
+        let mut tags = self.income_repo.find_tags_by_transaction_ids(user_id, vec![transaction_id.to_vec()]).await?;
+        ResTransactionTagDto::from_tags(&tags.remove(transaction_id).unwrap_or_default())
+            .map_err(UsecaseError::Unexpected)
+    }
 }
 
 
 #[async_trait::async_trait]
 impl<T, A, C, TT> RecordIncomeUsecase for IncomeUseCase<T, A, C, TT>
 where
-    T: RecordIncomeRepositoryUtility + Send + Sync,
+    T: RecordIncomeRepositoryUtility + TransactionTagRepositoryUtility + Send + Sync,
     A: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
     C: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync,
     TT: TransactionTypeRepositoryUtility + Send + Sync,
@@ -150,6 +165,7 @@ where
         log::debug!("Contact name: {}", contact_name);
 
         // Step 5: Map the result to ResEntryIncomeDto
+        let tags = self.transaction_tags(user_id, &income_created.id).await?;
         let res_entry = ResEntryIncomeDto {
             id: match Uuid::from_slice(&income_created.id) {
                 Ok(uuid) => uuid.to_string(),
@@ -159,16 +175,18 @@ where
                 }
             },
             transaction_type_name,
-            amount: income_created.amount,
+            amount: DecimalWrapper(income_created.amount),
             asset_name,
             contact_name,
             note: income_created.note,
+            occurred_at: income_created.occurred_at.to_rfc3339(),
             created_at: income_created
                 .created_at
                 .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
             updated_at: income_created
                 .updated_at
                 .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+            tags,
         };
         log::info!("Amount in IncomeUseCase: {}", res_entry.amount);
         log::debug!("Response DTO: {:?}", res_entry);
@@ -237,6 +255,7 @@ where
                     Err(err) => return Err(UsecaseError::from(err)),
                 };
 
+                let tags = self.transaction_tags(user_id, &income.id).await?;
                 Some(ResEntryIncomeDto {
                     id: match Uuid::from_slice(&income.id) {
                         Ok(uuid) => uuid.to_string(),
@@ -246,16 +265,18 @@ where
                         }
                     },
                     transaction_type_name,
-                    amount: income.amount,
+                    amount: DecimalWrapper(income.amount),
                     asset_name,
                     contact_name,
                     note: income.note,
+                    occurred_at: income.occurred_at.to_rfc3339(),
                     created_at: income
                         .created_at
                         .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
                     updated_at: income
                         .updated_at
                         .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+                    tags,
                 })
             }
             Ok(None) => return Ok(None),
@@ -336,6 +357,7 @@ let contact_name = match self
 };
 
     // Step 5: Map the result to ResEntryIncomeDto
+    let tags = self.transaction_tags(user_id, &updated_income.id).await?;
     let res_entry = ResEntryIncomeDto {
         id: match Uuid::from_slice(&updated_income.id) {
             Ok(uuid) => uuid.to_string(),
@@ -345,16 +367,18 @@ let contact_name = match self
             }
         },
         transaction_type_name,
-        amount: updated_income.amount,
+        amount: DecimalWrapper(updated_income.amount),
         asset_name,
         contact_name,
         note: updated_income.note,
+        occurred_at: updated_income.occurred_at.to_rfc3339(),
         created_at: updated_income
             .created_at
             .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
         updated_at: updated_income
             .updated_at
             .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+        tags,
     };
 
     // Step 6: Return the response object
@@ -379,7 +403,13 @@ let contact_name = match self
     // Step 2: Delete the income record if it exists
     if income_exists {
         match self.income_repo.delete_income_record(user_id, transaction_id).await {
-            Ok(_) => Ok(()), // Successfully deleted
+            Ok(_) => {
+                // the attachments go along, the cleanup worker picks up anything left behind
+                if let Err(err) = self.attachment_cleanup.remove_owner_attachments(user_id, AttachmentOwnerVariant::Transaction, transaction_id).await {
+                    log::warn!("Attachments of income {} not removed: {}", transaction_id, err);
+                }
+                Ok(())
+            }
             Err(err) => Err(UsecaseError::from(err)), // Handle repository errors
         }
     } else {
@@ -399,6 +429,11 @@ let contact_name = match self
             Err(err) => return Err(UsecaseError::from(err)),
         };
 
+        let tags_by_transaction = self
+            .income_repo
+            .find_tags_by_transaction_ids(user_id, incomes.iter().map(|income| income.id.clone()).collect())
+            .await?;
+
         let mut data = Vec::new();
         for income in incomes {
             let transaction_type_name = match self
@@ -465,23 +500,26 @@ let contact_name = match self
                 }
             },
             transaction_type_name,
-            amount: income.amount,
+            amount: DecimalWrapper(income.amount),
             asset_name,
             contact_name,
             note: income.note,
+            occurred_at: income.occurred_at.to_rfc3339(),
             created_at: income
                 .created_at
                 .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
             updated_at: income
                 .updated_at
                 .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+            tags: ResTransactionTagDto::from_tags(tags_by_transaction.get(&income.id).map(Vec::as_slice).unwrap_or_default())
+                .map_err(UsecaseError::Unexpected)?,
         };
 
         data.push(res_entry);
     }
 
     let res_list = ResListIncomeDto {
-        length: data.len() as i32,
+        meta: ResPageMetaDto::single_page(data.len() as u64),
         data,
     };
 
@@ -1,4 +1,5 @@
 pub mod transfer_usecase;
 pub mod income_usecase;
 pub mod payment_usecase;
-pub mod transaction_type_usecase;
\ No newline at end of file
+pub mod transaction_type_usecase;
+pub mod transaction_usecase;
\ No newline at end of file
@@ -1,8 +1,14 @@
 use std::sync::Arc;
 
+use rust_decimal::Decimal;
 use uuid::Uuid;
 
-use crate::{application::usecase_req_impl::transaction_usecase::RecordPaymentUsecase, domain::{dto::transaction_dto::{ReqCreatePaymentDto, ReqUpdatePaymentDto, ResEntryPaymentDto, ResListPaymentDto}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, expense_repository::{ExpenseRepositoryBase, ExpenseRepositoryUtill}, transaction_repository::{RecordPaymentRepositoryUtility, TransactionTypeRepositoryUtility}}}, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
+use crate::application::usecase_req_impl::attachment_usecase::AttachmentCleanupUsecase;
+use crate::domain::dto::dto_enum::attachment_owner::AttachmentOwnerVariant;
+use crate::implentation::decimal_utoipa::DecimalWrapper;
+use crate::domain::dto::pagination_dto::ResPageMetaDto;
+
+use crate::{application::usecase_req_impl::transaction_usecase::RecordPaymentUsecase, domain::{dto::{tag_dto::ResTransactionTagDto, transaction_dto::{ReqCreatePaymentDto, ReqPaymentSplitDto, ReqUpdatePaymentDto, ResEntryPaymentDto, ResListPaymentDto, ResPaymentSplitDto}}, entities::payment_split, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, expense_repository::{ExpenseRepositoryBase, ExpenseRepositoryUtill}, payment_split_repository::PaymentSplitRepositoryUtility, tag_repository::TransactionTagRepositoryUtility, transaction_repository::{RecordPaymentRepositoryUtility, TransactionTypeRepositoryUtility}}}, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
 
 
 
@@ -10,7 +16,7 @@ use crate::{application::usecase_req_impl::transaction_usecase::RecordPaymentUse
 
 pub struct PaymentUseCase<T, A, C, TT, E>
 where
-    T: RecordPaymentRepositoryUtility + Send + Sync,
+    T: RecordPaymentRepositoryUtility + TransactionTagRepositoryUtility + PaymentSplitRepositoryUtility + Send + Sync,
     A: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
     C: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync,
     TT: TransactionTypeRepositoryUtility + Send + Sync,
@@ -21,11 +27,12 @@ where
     contact_repo: Arc<C>,
     transaction_type_repo: Arc<TT>,
     expense_repo: Arc<E>,
+    attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>,
 }
 
 impl<T, A, C, TT, E> PaymentUseCase<T, A, C, TT, E>
 where
-    T: RecordPaymentRepositoryUtility + Send + Sync,
+    T: RecordPaymentRepositoryUtility + TransactionTagRepositoryUtility + PaymentSplitRepositoryUtility + Send + Sync,
     A: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
     C: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync,
     TT: TransactionTypeRepositoryUtility + Send + Sync,
@@ -37,30 +44,105 @@ where
         contact_repo: Arc<C>,
         transaction_type_repo: Arc<TT>,
         expense_repo: Arc<E>,
+        attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>,
     ) -> Self {
         Self {
             payment_repo,
             asset_repo,
             contact_repo,
             transaction_type_repo,
-            expense_repo
+            expense_repo,
+            attachment_cleanup,
         }
     }
+
+    // tags of one transaction as shown in its response
+    async fn transaction_tags(&self, user_id: Uuid, transaction_id: &[u8]) -> Result<Vec<ResTransactionTagDto>, UsecaseError> {
+        let mut tags = self.payment_repo.find_tags_by_transaction_ids(user_id, vec![transaction_id.to_vec()]).await?;
+        ResTransactionTagDto::from_tags(&tags.remove(transaction_id).unwrap_or_default())
+            .map_err(UsecaseError::Unexpected)
+    }
+
+    // split lines of one transaction as shown in its response
+    async fn payment_splits(&self, user_id: Uuid, transaction_id: &[u8]) -> Result<Vec<ResPaymentSplitDto>, UsecaseError> {
+        let mut splits = self.payment_repo.find_splits_by_transaction_ids(vec![transaction_id.to_vec()]).await?;
+        self.to_split_entries(user_id, &splits.remove(transaction_id).unwrap_or_default()).await
+    }
+
+    async fn to_split_entries(&self, user_id: Uuid, splits: &[payment_split::Model]) -> Result<Vec<ResPaymentSplitDto>, UsecaseError> {
+        let mut entries = Vec::with_capacity(splits.len());
+        for split in splits {
+            let expense_id = Uuid::from_slice(&split.expense_id).map_err(|err| UsecaseError::Unexpected(err.to_string()))?;
+            let expense_name = match self.expense_repo.find_by_user_id_and_expense_id(user_id, expense_id).await? {
+                Some(expense) => expense.description,
+                None => String::from("Unknown"),
+            };
+            entries.push(ResPaymentSplitDto {
+                id: Uuid::from_slice(&split.id).map_err(|err| UsecaseError::Unexpected(err.to_string()))?.to_string(),
+                expense_id: expense_id.to_string(),
+                expense_name,
+                amount: DecimalWrapper(split.amount),
+                note: split.note.clone(),
+            });
+        }
+        Ok(entries)
+    }
+
+    // a split payment needs two lines or more, each on an expense of the user, adding up to the payment amount
+    async fn check_splits(&self, user_id: Uuid, amount: Decimal, splits: &[ReqPaymentSplitDto]) -> Result<(), UsecaseError> {
+        if splits.len() < 2 {
+            return Err(UsecaseError::InvalidData(
+                "A split payment needs at least two lines, use expense_id for a single expense".to_string(),
+            ));
+        }
+        let mut total = Decimal::ZERO;
+        for split in splits {
+            if split.amount.0 <= Decimal::ZERO {
+                return Err(UsecaseError::InvalidData("Every split amount must be greater than zero".to_string()));
+            }
+            let expense_id = Uuid::parse_str(split.expense_id.trim())
+                .map_err(|_| UsecaseError::InvalidData(format!("Invalid split expense id '{}'", split.expense_id)))?;
+            if self.expense_repo.find_by_user_id_and_expense_id(user_id, expense_id).await?.is_none() {
+                return Err(UsecaseError::ResourceNotFound(format!("Expense {} not found", expense_id)));
+            }
+            total += split.amount.0;
+        }
+        if total != amount {
+            return Err(UsecaseError::InvalidData(format!(
+                "The splits add up to {} but the payment amount is {}",
+                total, amount
+            )));
+        }
+        Ok(())
+    }
 }
 
 
 #[async_trait::async_trait]
 impl<T, A, C, TT, E> RecordPaymentUsecase for PaymentUseCase<T, A, C, TT, E>
 where
-    T: RecordPaymentRepositoryUtility + Send + Sync,
+    T: RecordPaymentRepositoryUtility + TransactionTagRepositoryUtility + PaymentSplitRepositoryUtility + Send + Sync,
     A: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
     C: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync,
     TT: TransactionTypeRepositoryUtility + Send + Sync,
     E: ExpenseRepositoryBase + ExpenseRepositoryUtill + Send + Sync,
 {
 
-    async fn create_payment(&self, user_id: Uuid, payment_dto: ReqCreatePaymentDto) -> Result<ResEntryPaymentDto, UsecaseError>
+    async fn create_payment(&self, user_id: Uuid, mut payment_dto: ReqCreatePaymentDto) -> Result<ResEntryPaymentDto, UsecaseError>
     {   
+        // Step 0: A split payment is stored under the expense of its first line
+        match payment_dto.splits.as_deref() {
+            Some(splits) if !splits.is_empty() => {
+                self.check_splits(user_id, payment_dto.amount.0, splits).await?;
+                payment_dto.expense_id = splits[0].expense_id.trim().to_string();
+            }
+            _ => {
+                if payment_dto.expense_id.trim().is_empty() {
+                    return Err(UsecaseError::InvalidData("The expense_id must not be empty unless splits are given".to_string()));
+                }
+            }
+        }
+
         // Step 1: Create the payment record in the database
     let payment_created = match self.payment_repo.create_payment_record(user_id, payment_dto).await {
         Ok(payment) => payment,
@@ -144,24 +226,29 @@ where
         Err(err) => return Err(UsecaseError::from(err)),
     };
     // Step 5: Map the result to ResEntryPaymentDto
+    let tags = self.transaction_tags(user_id, &payment_created.id).await?;
+    let splits = self.payment_splits(user_id, &payment_created.id).await?;
     let res_entry = ResEntryPaymentDto {
         id: match Uuid::from_slice(&payment_created.id) {
             Ok(id) => id.to_string(),
             Err(_) => "Unknown".to_string(),
         },
         transaction_type_name,
-        amount: payment_created.amount,
+        amount: DecimalWrapper(payment_created.amount),
         expense_name,
         contact_name,
         asset_name,
         note: payment_created.note,
         // Add other fields as needed
+        occurred_at: payment_created.occurred_at.to_rfc3339(),
         created_at: payment_created
             .created_at
             .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
         updated_at: payment_created
             .updated_at
             .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+        tags,
+        splits,
     };
 
     // Step 6: Return the response object
@@ -253,23 +340,28 @@ where
             };
 
             // Step 5: Map the result to ResEntryPaymentDto
+            let tags = self.transaction_tags(user_id, &payment.id).await?;
+            let splits = self.payment_splits(user_id, &payment.id).await?;
             Some(ResEntryPaymentDto {
                 id: match Uuid::from_slice(&payment.id) {
                     Ok(id) => id.to_string(),
                     Err(_) => "Unknown".to_string(),
                 },
                 transaction_type_name,
-                amount: payment.amount,
+                amount: DecimalWrapper(payment.amount),
                 expense_name,
                 contact_name,
                 asset_name,
                 note: payment.note,
+                occurred_at: payment.occurred_at.to_rfc3339(),
                 created_at: payment
                     .created_at
                     .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
                 updated_at: payment
                     .updated_at
                     .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+                tags,
+                splits,
             })
         }
         Ok(None) => return Ok(None), // Payment record not found
@@ -280,8 +372,41 @@ where
     Ok(payment)
     }
 
-    async fn update_payment(&self, user_id: Uuid,  transaction_id: Uuid, payment_dto: ReqUpdatePaymentDto) -> Result<ResEntryPaymentDto, UsecaseError>
+    async fn update_payment(&self, user_id: Uuid,  transaction_id: Uuid, mut payment_dto: ReqUpdatePaymentDto) -> Result<ResEntryPaymentDto, UsecaseError>
     {
+        // Step 0: Keep the split lines in line with the amount, an empty list removes them
+        if let Some(current) = self.payment_repo.get_payment_record_by_id(user_id, transaction_id).await? {
+            match payment_dto.splits.as_deref() {
+                Some(splits) if !splits.is_empty() => {
+                    let amount = payment_dto.amount.map_or(current.amount, |amount| amount.0);
+                    self.check_splits(user_id, amount, splits).await?;
+                    payment_dto.expense_id = Some(splits[0].expense_id.trim().to_string());
+                }
+                Some(_) => {}
+                None => {
+                    let is_split = !self
+                        .payment_repo
+                        .find_splits_by_transaction_ids(vec![current.id.clone()])
+                        .await?
+                        .is_empty();
+                    let current_expense_id = current
+                        .expense_id
+                        .as_deref()
+                        .and_then(|id| Uuid::from_slice(id).ok());
+                    let amount_changed = payment_dto.amount.is_some_and(|amount| amount.0 != current.amount);
+                    let expense_changed = payment_dto
+                        .expense_id
+                        .as_deref()
+                        .is_some_and(|id| Uuid::parse_str(id.trim()).ok() != current_expense_id);
+                    if is_split && (amount_changed || expense_changed) {
+                        return Err(UsecaseError::InvalidData(
+                            "Send the splits again to change the amount or the expense of a split payment".to_string(),
+                        ));
+                    }
+                }
+            }
+        }
+
         // Step 1: Call the repository to update the payment record
     let updated_payment = match self
     .payment_repo
@@ -372,23 +497,28 @@ where
     };
 
     // Step 5: Map the result to ResEntryPaymentDto
+    let tags = self.transaction_tags(user_id, &updated_payment.id).await?;
+    let splits = self.payment_splits(user_id, &updated_payment.id).await?;
     let res_entry = ResEntryPaymentDto {
         id: match Uuid::from_slice(&updated_payment.id) {
             Ok(id) => id.to_string(),
             Err(_) => "Unknown".to_string(),
         },
         transaction_type_name,
-        amount: updated_payment.amount,
+        amount: DecimalWrapper(updated_payment.amount),
         expense_name,
         contact_name,
         asset_name,
         note: updated_payment.note,
+        occurred_at: updated_payment.occurred_at.to_rfc3339(),
         created_at: updated_payment
             .created_at
             .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
         updated_at: updated_payment
             .updated_at
             .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+        tags,
+        splits,
     };
 
     // Step 6: Return the response object
@@ -413,7 +543,13 @@ where
     // Step 2: Delete the payment record if it exists
     if payment_exists {
         match self.payment_repo.delete_payment_record(user_id, transaction_id).await {
-            Ok(_) => Ok(()), // Successfully deleted
+            Ok(_) => {
+                // the attachments go along, the cleanup worker picks up anything left behind
+                if let Err(err) = self.attachment_cleanup.remove_owner_attachments(user_id, AttachmentOwnerVariant::Transaction, transaction_id).await {
+                    log::warn!("Attachments of payment {} not removed: {}", transaction_id, err);
+                }
+                Ok(())
+            }
             Err(err) => Err(UsecaseError::from(err)), // Handle repository errors
         }
     } else {
@@ -437,6 +573,15 @@ where
         };
 
         // Step 2: Map the payment records to ResEntryPaymentDto
+        let tags_by_transaction = self
+            .payment_repo
+            .find_tags_by_transaction_ids(user_id, payments.iter().map(|payment| payment.id.clone()).collect())
+            .await?;
+        let mut splits_by_transaction = self
+            .payment_repo
+            .find_splits_by_transaction_ids(payments.iter().map(|payment| payment.id.clone()).collect())
+            .await?;
+
         let mut data = Vec::new();
         for payment in payments {
             log::debug!("Processing payment record: {:?}", payment);
@@ -515,6 +660,10 @@ where
                 }
             };
 
+            let splits = self
+                .to_split_entries(user_id, &splits_by_transaction.remove(&payment.id).unwrap_or_default())
+                .await?;
+
             // Map the payment record to ResEntryPaymentDto
             let res_entry = ResEntryPaymentDto {
                 id: match Uuid::from_slice(&payment.id) {
@@ -522,17 +671,21 @@ where
                     Err(_) => "Unknown".to_string(),
                 },
                 transaction_type_name,
-                amount: payment.amount,
+                amount: DecimalWrapper(payment.amount),
                 expense_name,
                 contact_name,
                 asset_name,
                 note: payment.note,
+                occurred_at: payment.occurred_at.to_rfc3339(),
                 created_at: payment
                     .created_at
                     .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
                 updated_at: payment
                     .updated_at
                     .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+                tags: ResTransactionTagDto::from_tags(tags_by_transaction.get(&payment.id).map(Vec::as_slice).unwrap_or_default())
+                    .map_err(UsecaseError::Unexpected)?,
+                splits,
             };
 
             data.push(res_entry);
@@ -540,7 +693,7 @@ where
 
         // Step 3: Create the response object
         let res_list = ResListPaymentDto {
-            length: data.len() as i32,
+            meta: ResPageMetaDto::single_page(data.len() as u64),
             data,
         };
 
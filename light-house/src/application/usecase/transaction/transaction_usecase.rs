@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::transaction_usecase::TransactionSearchUsecase, domain::{dto::{pagination_dto::ResPageMetaDto, tag_dto::ResTransactionTagDto, transaction_dto::{ResEntryTransactionDto, ResListTransactionDto, TransactionCursorDto, TransactionSearchDto}}, req_repository::transaction_repository::TransactionSearchRepositoryUtility}, implentation::decimal_utoipa::DecimalWrapper, soc::soc_usecase::UsecaseError};
+
+
+
+
+
+pub struct TransactionUseCase<T>
+where
+    T: TransactionSearchRepositoryUtility + Send + Sync,
+{
+    transaction_repo: Arc<T>,
+}
+
+impl<T> TransactionUseCase<T>
+where
+    T: TransactionSearchRepositoryUtility + Send + Sync,
+{
+    pub fn new(transaction_repo: Arc<T>) -> Self {
+        Self { transaction_repo }
+    }
+}
+
+
+fn uuid_string(id: &[u8]) -> Result<String, UsecaseError> {
+    Uuid::from_slice(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+
+#[async_trait::async_trait]
+impl<T> TransactionSearchUsecase for TransactionUseCase<T>
+where
+    T: TransactionSearchRepositoryUtility + Send + Sync,
+{
+    async fn search_transactions(
+        &self,
+        user_id: Uuid,
+        search: TransactionSearchDto
+    )
+        -> Result<ResListTransactionDto, UsecaseError>
+    {
+        let sort_by = search.sort_by;
+        let use_cursor = search.cursor.is_some();
+        let page = search.page;
+        let per_page = search.per_page;
+
+        // Step 1: fetch the requested page
+        let result = self.transaction_repo.search_transactions(user_id, search).await?;
+
+        // Step 2: resolve display names for everything the page points to
+        let names = self.transaction_repo.find_reference_names(user_id, result.records.clone()).await?;
+
+        // Step 3: cursor of the last record, only when another page follows
+        let next_cursor = match result.records.last() {
+            Some(last) if result.has_more => Some(
+                TransactionCursorDto::from_record(last, sort_by)
+                    .map_err(UsecaseError::Unexpected)?
+                    .encode()
+            ),
+            _ => None,
+        };
+
+        let mut data = Vec::with_capacity(result.records.len());
+        for record in result.records {
+            let name_of = |map: &std::collections::HashMap<Vec<u8>, String>, id: &Vec<u8>| map.get(id).cloned().unwrap_or_default();
+            data.push(ResEntryTransactionDto {
+                id: uuid_string(&record.id)?,
+                transaction_type_name: name_of(&names.transaction_types, &record.transaction_type_id),
+                amount: DecimalWrapper(record.amount),
+                asset_id: uuid_string(&record.asset_id)?,
+                asset_name: name_of(&names.assets, &record.asset_id),
+                destination_asset_id: record.destination_asset_id.as_deref().map(uuid_string).transpose()?,
+                destination_asset_name: record.destination_asset_id.as_ref().map(|id| name_of(&names.assets, id)),
+                contact_id: record.contact_id.as_deref().map(uuid_string).transpose()?,
+                contact_name: record.contact_id.as_ref().map(|id| name_of(&names.contacts, id)),
+                expense_id: record.expense_id.as_deref().map(uuid_string).transpose()?,
+                expense_name: record.expense_id.as_ref().map(|id| name_of(&names.expenses, id)),
+                note: record.note,
+                occurred_at: record.occurred_at.to_rfc3339(),
+                external_reference: record.external_reference,
+                created_at: record.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+                updated_at: record.updated_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+                tags: ResTransactionTagDto::from_tags(names.tags.get(&record.id).map(Vec::as_slice).unwrap_or_default())
+                    .map_err(UsecaseError::Unexpected)?,
+            });
+        }
+
+        let meta = ResPageMetaDto::new(
+            result.total,
+            if use_cursor { None } else { Some(page) },
+            per_page,
+            result.has_more,
+            next_cursor,
+        );
+
+        Ok(ResListTransactionDto { meta, data })
+    }
+}
@@ -2,7 +2,12 @@ use std::sync::Arc;
 
 use uuid::Uuid;
 
-use crate::{application::usecase_req_impl::transaction_usecase::TransferUsecase, domain::{dto::transaction_dto::{ReqCreateTransferDto, ReqUpdateTransferDto, ResEntryTransferDto, ResListTransferDto}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, transaction_repository::{TransactionTypeRepositoryUtility, TransferRepositoryUtility}}}, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
+use crate::application::usecase_req_impl::attachment_usecase::AttachmentCleanupUsecase;
+use crate::domain::dto::dto_enum::attachment_owner::AttachmentOwnerVariant;
+use crate::implentation::decimal_utoipa::DecimalWrapper;
+use crate::domain::dto::pagination_dto::ResPageMetaDto;
+
+use crate::{application::usecase_req_impl::transaction_usecase::TransferUsecase, domain::{dto::{tag_dto::ResTransactionTagDto, transaction_dto::{ReqCreateTransferDto, ReqUpdateTransferDto, ResEntryTransferDto, ResListTransferDto}}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, tag_repository::TransactionTagRepositoryUtility, transaction_repository::{TransactionTypeRepositoryUtility, TransferRepositoryUtility}}}, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
 
 
 
@@ -10,7 +15,7 @@ use crate::{application::usecase_req_impl::transaction_usecase::TransferUsecase,
 
 pub struct TransferUseCase<T, A, C, TT>
 where
-    T: TransferRepositoryUtility + Send + Sync,
+    T: TransferRepositoryUtility + TransactionTagRepositoryUtility + Send + Sync,
     A: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
     C: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync,
     TT: TransactionTypeRepositoryUtility + Send + Sync,
@@ -19,6 +24,7 @@ where
     asset_repo: Arc<A>,
     contact_repo: Arc<C>,
     transaction_type_repo: Arc<TT>,
+    attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>,
 }
 
 
@@ -26,7 +32,7 @@ where
 
 impl<T, A, C, TT> TransferUseCase<T, A, C, TT>
 where
-    T: TransferRepositoryUtility + Send + Sync,
+    T: TransferRepositoryUtility + TransactionTagRepositoryUtility + Send + Sync,
     A: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
     C: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync,
     TT: TransactionTypeRepositoryUtility + Send + Sync,
@@ -36,14 +42,23 @@ where
         asset_repo: Arc<A>,
         contact_repo: Arc<C>,
         transaction_type_repo: Arc<TT>,
+        attachment_cleanup: Arc<dyn AttachmentCleanupUsecase>,
     ) -> Self {
         Self {
             transfer_repo,
             asset_repo,
             contact_repo,
             transaction_type_repo,
+            attachment_cleanup,
         }
     }
+
+    // tags of one transaction as shown in its response
+    async fn transaction_tags(&self, user_id: Uuid, transaction_id: &[u8]) -> Result<Vec<ResTransactionTagDto>, UsecaseError> {
+        let mut tags = self.transfer_repo.find_tags_by_transaction_ids(user_id, vec![transaction_id.to_vec()]).await?;
+        ResTransactionTagDto::from_tags(&tags.remove(transaction_id).unwrap_or_default())
+            .map_err(UsecaseError::Unexpected)
+    }
 }
 
 
@@ -51,7 +66,7 @@ where
 #[async_trait::async_trait]
 impl<T, A, C, TT> TransferUsecase for TransferUseCase<T, A, C, TT>
 where
-    T: TransferRepositoryUtility + Send + Sync,
+    T: TransferRepositoryUtility + TransactionTagRepositoryUtility + Send + Sync,
     A: AssetRepositoryBase + AssetRepositoryUtility + Send + Sync,
     C: ContactRepositoryBase + ContactRepositoryUtility + Send + Sync,
     TT: TransactionTypeRepositoryUtility + Send + Sync,
@@ -129,19 +144,22 @@ where
     };
 
     // Step 6: Map the result to ResEntryTransferDto
+    let tags = self.transaction_tags(user_id, &transfer_created.id).await?;
     let res_entry = ResEntryTransferDto {
         id: match Uuid::from_slice(&transfer_created.id) {
             Ok(id) => id.to_string(),
             Err(e) => return Err(UsecaseError::from(RepositoryError::InvalidInput(e.to_string()))),
         },
         transaction_type_name,
-        amount: transfer_created.amount,
+        amount: DecimalWrapper(transfer_created.amount),
         asset_name,
         destination_asset_name,
         contact_name,
         note: transfer_created.note,
+        occurred_at: transfer_created.occurred_at.to_rfc3339(),
         created_at: transfer_created.created_at.map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
         updated_at: transfer_created.updated_at.map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+        tags,
     };
 
     // Step 7: Return the response object
@@ -238,23 +256,26 @@ where
             };
 
             // Step 6: Map the result to ResEntryTransferDto
+            let tags = self.transaction_tags(user_id, &transfer.id).await?;
             Some(ResEntryTransferDto {
                 id: match Uuid::from_slice(&transfer.id) {
                     Ok(id) => id.to_string(),
                     Err(e) => return Err(UsecaseError::from(RepositoryError::InvalidInput(e.to_string()))),
                 },
                 transaction_type_name,
-                amount: transfer.amount,
+                amount: DecimalWrapper(transfer.amount),
                 asset_name,
                 destination_asset_name,
                 contact_name,
                 note: transfer.note,
+                occurred_at: transfer.occurred_at.to_rfc3339(),
                 created_at: transfer
                     .created_at
                     .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
                 updated_at: transfer
                     .updated_at
                     .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+                tags,
             })
         }
         Ok(None) => return Ok(None), // Transfer not found
@@ -362,23 +383,26 @@ where
     };
 
     // Step 6: Map the result to ResEntryTransferDto
+    let tags = self.transaction_tags(user_id, &updated_transfer.id).await?;
     let res_entry = ResEntryTransferDto {
         id: match Uuid::from_slice(&updated_transfer.id) {
             Ok(id) => id.to_string(),
             Err(e) => return Err(UsecaseError::from(RepositoryError::InvalidInput(e.to_string()))),
         },
         transaction_type_name,
-        amount: updated_transfer.amount,
+        amount: DecimalWrapper(updated_transfer.amount),
         asset_name,
         destination_asset_name,
         contact_name,
         note: updated_transfer.note,
+        occurred_at: updated_transfer.occurred_at.to_rfc3339(),
         created_at: updated_transfer
             .created_at
             .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
         updated_at: updated_transfer
             .updated_at
             .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+        tags,
     };
 
         // Step 7: Return the response object
@@ -402,7 +426,13 @@ where
     // Step 2: Delete the transfer if it exists
     if transfer_exists {
         match self.transfer_repo.delete_transfer(user_id, transaction_id).await {
-            Ok(_) => Ok(()), // Successfully deleted
+            Ok(_) => {
+                // the attachments go along, the cleanup worker picks up anything left behind
+                if let Err(err) = self.attachment_cleanup.remove_owner_attachments(user_id, AttachmentOwnerVariant::Transaction, transaction_id).await {
+                    log::warn!("Attachments of transfer {} not removed: {}", transaction_id, err);
+                }
+                Ok(())
+            }
             Err(err) => Err(UsecaseError::from(err)), // Handle repository errors
         }
     } else {
@@ -422,6 +452,11 @@ where
     };
 
     // Step 2: Map the transfers to ResEntryTransferDto
+    let tags_by_transaction = self
+        .transfer_repo
+        .find_tags_by_transaction_ids(user_id, transfers.iter().map(|transfer| transfer.id.clone()).collect())
+        .await?;
+
     let mut data = Vec::new();
     for transfer in transfers {
         // Fetch the transaction type name using the transaction_type_id
@@ -515,17 +550,20 @@ where
                 Err(e) => return Err(UsecaseError::from(RepositoryError::InvalidInput(e.to_string()))),
             },
             transaction_type_name,
-            amount: transfer.amount,
+            amount: DecimalWrapper(transfer.amount),
             asset_name,
             destination_asset_name,
             contact_name,
             note: transfer.note,
+            occurred_at: transfer.occurred_at.to_rfc3339(),
             created_at: transfer
                 .created_at
                 .map_or_else(|| "Unknown".to_string(), |dt| dt.to_string()),
             updated_at: transfer
                 .updated_at
                 .map_or_else(|| "Unknown".to_string(), |dt| dt.to_rfc3339()),
+            tags: ResTransactionTagDto::from_tags(tags_by_transaction.get(&transfer.id).map(Vec::as_slice).unwrap_or_default())
+                .map_err(UsecaseError::Unexpected)?,
         };
 
         data.push(res_entry);
@@ -533,7 +571,7 @@ where
 
         // Step 3: Create the response object
         let res_list = ResListTransferDto {
-            length: data.len() as i64,
+            meta: ResPageMetaDto::single_page(data.len() as u64),
             data,
         };
 
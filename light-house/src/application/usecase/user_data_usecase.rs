@@ -0,0 +1,374 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{application::{usecase::user_usecase::account_throttle_key, usecase_req_impl::user_data_usecase::UserDataUsecase}, domain::{dto::user_data_dto::{ReqDeleteAccountDto, ResUserExportDto, UserDataDto}, req_repository::{attachment_storage_repository::AttachmentStorage, auth_repository::AuthRepository, sign_in_limiter_repository::SignInLimiterRepository, user_data_repository::UserDataRepository}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+// bumped when a field of the export changes meaning
+const EXPORT_FORMAT_VERSION: u32 = 1;
+
+fn uuid_string(id: &[u8]) -> Result<String, UsecaseError> {
+    Uuid::from_slice(id)
+        .map(|uuid| uuid.to_string())
+        .map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+fn optional_uuid_string(id: &Option<Vec<u8>>) -> Result<Option<String>, UsecaseError> {
+    id.as_deref().map(uuid_string).transpose()
+}
+
+fn rfc3339(at: Option<DateTime<Utc>>) -> Option<String> {
+    at.map(|at| at.to_rfc3339())
+}
+
+// every table of the export as json, ids as uuid strings and times in rfc3339
+fn to_export(data: UserDataDto, exported_at: DateTime<Utc>) -> Result<ResUserExportDto, UsecaseError> {
+    let user = &data.user;
+    let profile = json!({
+        "id": uuid_string(&user.id)?,
+        "username": user.username,
+        "email": user.email,
+        "first_name": user.first_name,
+        "last_name": user.last_name,
+        "gender": data.gender.as_ref().map(|gender| gender.name.clone()),
+        "user_role": data.user_role.as_ref().map(|role| role.name.clone()),
+        "email_verified_at": rfc3339(user.email_verified_at),
+        "disabled_at": rfc3339(user.disabled_at),
+        "created_at": rfc3339(user.created_at),
+        "updated_at": rfc3339(user.updated_at),
+    });
+
+    let transaction_types = data.transaction_types.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let asset_types = data.asset_types.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let assets = data.assets.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "asset_type_id": uuid_string(&row.asset_type_id)?,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let contact_types = data.contact_types.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let contacts = data.contacts.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "business_name": row.business_name,
+        "phone": row.phone,
+        "description": row.description,
+        "contact_type_id": uuid_string(&row.contact_type_id)?,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let expense_types = data.expense_types.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let expenses = data.expenses.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "description": row.description,
+        "expense_type_id": uuid_string(&row.expense_type_id)?,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let transactions = data.transactions.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "transaction_type_id": uuid_string(&row.transaction_type_id)?,
+        "amount": row.amount,
+        "asset_id": uuid_string(&row.asset_id)?,
+        "destination_asset_id": optional_uuid_string(&row.destination_asset_id)?,
+        "expense_id": optional_uuid_string(&row.expense_id)?,
+        "contact_id": optional_uuid_string(&row.contact_id)?,
+        "note": row.note,
+        "occurred_at": row.occurred_at.to_rfc3339(),
+        "external_reference": row.external_reference,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let tags = data.tags.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "color": row.color,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let transaction_tags = data.transaction_tags.iter().map(|row| Ok(json!({
+        "transaction_id": uuid_string(&row.transaction_id)?,
+        "tag_id": uuid_string(&row.tag_id)?,
+        "created_at": rfc3339(row.created_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let payment_splits = data.payment_splits.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "transaction_id": uuid_string(&row.transaction_id)?,
+        "expense_id": uuid_string(&row.expense_id)?,
+        "amount": row.amount,
+        "note": row.note,
+        "position": row.position,
+        "created_at": rfc3339(row.created_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let current_sheets = data.current_sheets.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "asset_id": uuid_string(&row.asset_id)?,
+        "balance": row.balance,
+        "last_transaction_id": optional_uuid_string(&row.last_transaction_id)?,
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let journal_entries = data.journal_entries.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "transaction_id": optional_uuid_string(&row.transaction_id)?,
+        "memo": row.memo,
+        "created_at": rfc3339(row.created_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let journal_lines = data.journal_lines.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "journal_entry_id": uuid_string(&row.journal_entry_id)?,
+        "account_type": row.account_type,
+        "account_id": uuid_string(&row.account_id)?,
+        "contact_id": optional_uuid_string(&row.contact_id)?,
+        "debit": row.debit,
+        "credit": row.credit,
+        "created_at": rfc3339(row.created_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let budgets = data.budgets.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "expense_type_id": uuid_string(&row.expense_type_id)?,
+        "amount": row.amount,
+        "period_type": row.period_type,
+        "start_date": row.start_date.to_rfc3339(),
+        "end_date": rfc3339(row.end_date),
+        "rollover": row.rollover,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let budget_periods = data.budget_periods.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "budget_id": uuid_string(&row.budget_id)?,
+        "period_start": row.period_start.to_rfc3339(),
+        "period_end": row.period_end.to_rfc3339(),
+        "amount": row.amount,
+        "rollover_amount": row.rollover_amount,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let recurring_transactions = data.recurring_transactions.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "transaction_type_id": uuid_string(&row.transaction_type_id)?,
+        "amount": row.amount,
+        "asset_id": uuid_string(&row.asset_id)?,
+        "destination_asset_id": optional_uuid_string(&row.destination_asset_id)?,
+        "expense_id": optional_uuid_string(&row.expense_id)?,
+        "contact_id": uuid_string(&row.contact_id)?,
+        "note": row.note,
+        "schedule_type": row.schedule_type,
+        "rrule": row.rrule,
+        "timezone": row.timezone,
+        "start_at": row.start_at.to_rfc3339(),
+        "end_at": rfc3339(row.end_at),
+        "status": row.status,
+        "next_run_at": rfc3339(row.next_run_at),
+        "last_run_at": rfc3339(row.last_run_at),
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let recurring_occurrences = data.recurring_occurrences.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "recurring_transaction_id": uuid_string(&row.recurring_transaction_id)?,
+        "scheduled_for": row.scheduled_for.to_rfc3339(),
+        "status": row.status,
+        "transaction_id": optional_uuid_string(&row.transaction_id)?,
+        "message": row.message,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let mcp_pending_operations = data.mcp_pending_operations.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "operation": row.operation,
+        "target_id": optional_uuid_string(&row.target_id)?,
+        "payload": row.payload,
+        "balance_impact": row.balance_impact,
+        "status": row.status,
+        "result": row.result,
+        "message": row.message,
+        "expires_at": row.expires_at.to_rfc3339(),
+        "decided_at": rfc3339(row.decided_at),
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let api_tokens = data.api_tokens.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "token_prefix": row.token_prefix,
+        "scopes": row.scopes,
+        "expires_at": rfc3339(row.expires_at),
+        "last_used_at": rfc3339(row.last_used_at),
+        "last_used_ip": row.last_used_ip,
+        "revoked_at": rfc3339(row.revoked_at),
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let sessions = data.sessions.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "user_agent": row.user_agent,
+        "ip_address": row.ip_address,
+        "expires_at": row.expires_at.to_rfc3339(),
+        "last_used_at": rfc3339(row.last_used_at),
+        "revoked_at": rfc3339(row.revoked_at),
+        "created_at": rfc3339(row.created_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let sign_in_attempts = data.sign_in_attempts.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "email": row.email,
+        "ip_address": row.ip_address,
+        "user_agent": row.user_agent,
+        "succeeded": row.succeeded,
+        "failure_reason": row.failure_reason,
+        "created_at": rfc3339(row.created_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let attachments = data.attachments.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "owner_type": row.owner_type,
+        "owner_id": uuid_string(&row.owner_id)?,
+        "file_name": row.file_name,
+        "content_type": row.content_type,
+        "size_bytes": row.size_bytes,
+        "sha256": row.sha256,
+        "created_at": rfc3339(row.created_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let import_profiles = data.import_profiles.iter().map(|row| Ok(json!({
+        "id": uuid_string(&row.id)?,
+        "name": row.name,
+        "delimiter": row.delimiter,
+        "has_header": row.has_header,
+        "skip_rows": row.skip_rows,
+        "date_column": row.date_column,
+        "date_format": row.date_format,
+        "amount_column": row.amount_column,
+        "debit_column": row.debit_column,
+        "credit_column": row.credit_column,
+        "description_column": row.description_column,
+        "decimal_separator": row.decimal_separator,
+        "encoding": row.encoding,
+        "created_at": rfc3339(row.created_at),
+        "updated_at": rfc3339(row.updated_at),
+    }))).collect::<Result<Vec<Value>, UsecaseError>>()?;
+    let two_factor = data.two_factor.as_ref().map(|row| json!({
+        "enabled": row.confirmed_at.is_some(),
+        "confirmed_at": rfc3339(row.confirmed_at),
+        "recovery_codes_left": data.recovery_codes_left,
+        "created_at": rfc3339(row.created_at),
+    }));
+
+    Ok(ResUserExportDto {
+        format_version: EXPORT_FORMAT_VERSION,
+        exported_at: exported_at.to_rfc3339(),
+        profile,
+        transaction_types,
+        asset_types,
+        assets,
+        contact_types,
+        contacts,
+        expense_types,
+        expenses,
+        transactions,
+        tags,
+        transaction_tags,
+        payment_splits,
+        current_sheets,
+        journal_entries,
+        journal_lines,
+        budgets,
+        budget_periods,
+        recurring_transactions,
+        recurring_occurrences,
+        mcp_pending_operations,
+        api_tokens,
+        sessions,
+        sign_in_attempts,
+        attachments,
+        import_profiles,
+        two_factor,
+    })
+}
+
+
+
+
+pub struct UserDataUseCase<D, A>
+where
+    D: UserDataRepository + Send + Sync,
+    A: AuthRepository + Send + Sync,
+{
+    user_data_repo: Arc<D>,
+    auth_repo: Arc<A>,
+    // the account counter of a deleted email is dropped with it
+    sign_in_limiter: Arc<dyn SignInLimiterRepository>,
+    // so is the content of every attachment
+    attachment_storage: Arc<dyn AttachmentStorage>,
+}
+
+impl<D, A> UserDataUseCase<D, A>
+where
+    D: UserDataRepository + Send + Sync,
+    A: AuthRepository + Send + Sync,
+{
+    pub fn new(user_data_repo: Arc<D>, auth_repo: Arc<A>, sign_in_limiter: Arc<dyn SignInLimiterRepository>, attachment_storage: Arc<dyn AttachmentStorage>) -> Self {
+        Self { user_data_repo, auth_repo, sign_in_limiter, attachment_storage }
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<D, A> UserDataUsecase for UserDataUseCase<D, A>
+where
+    D: UserDataRepository + Send + Sync,
+    A: AuthRepository + Send + Sync,
+{
+    async fn export_user_data(&self, user_id: Uuid) -> Result<ResUserExportDto, UsecaseError>
+    {
+        let data = self.user_data_repo.export_user_data(user_id).await?;
+        to_export(data, Utc::now().trunc_subsecs(0))
+    }
+
+    async fn delete_account(&self, user_id: Uuid, delete_dto: ReqDeleteAccountDto) -> Result<(), UsecaseError>
+    {
+        // Step 1: Only the owner of the password may do this, there is no undo
+        if !self.auth_repo.verify_password(user_id, &delete_dto.password).await? {
+            return Err(UsecaseError::PermissionDenied("Wrong password".to_string()));
+        }
+
+        // Step 2: Remove every row of the user in one transaction
+        let (deleted_user, storage_keys) = self.user_data_repo.delete_user_data(user_id).await?;
+        log::info!("Account {} deleted with all its data", user_id);
+
+        // Step 3: Nothing is left to protect, a failure here only logs
+        if let Err(err) = self.sign_in_limiter.clear_throttle(&account_throttle_key(&deleted_user.email)).await {
+            log::warn!("Failed to clear the sign in counter of a deleted account: {}", err);
+        }
+        for storage_key in storage_keys {
+            if let Err(err) = self.attachment_storage.delete(storage_key.clone()).await {
+                log::warn!("Failed to delete the attachment content '{}' of a deleted account: {}", storage_key, err);
+            }
+        }
+        Ok(())
+    }
+}
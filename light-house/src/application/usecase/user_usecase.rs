@@ -1,13 +1,68 @@
 use std::sync::Arc;
 
+use chrono::{DateTime, Duration, SubsecRound, Utc};
 use uuid::Uuid;
 
-use crate::{application::usecase_req_impl::user_usecase::UserUsecase, domain::{dto::auth_dto::{ReqSignInDto, ReqSignUpDto, ReqUpdateUserDto, ResMcpDto, ResMeDto, ResSignInDto}, req_repository::{auth_repository::AuthRepository, gender_repository::GenderRepository, user_repository::{McpRepositoryBase, UserRepositoryBase, UserRepositoryUtility}, user_role_repository::RoleManagementRepository}}, soc::soc_usecase::UsecaseError};
+use crate::{application::usecase_req_impl::{admin_usecase::AdminUsecase, user_usecase::{UserAccountUsecase, UserSessionUsecase, UserTwoFactorUsecase, UserUsecase}}, configuration::{mail_config::MailConfig, session_config::SessionConfig, sign_in_limit_config::SignInLimitConfig, two_factor_config::TwoFactorConfig}, domain::{dto::{account_dto::{CreateActionTokenDto, MailMessageDto, ReqForgotPasswordDto, ReqResetPasswordDto, ReqVerifyEmailDto, ResAccountMessageDto}, admin_dto::{AdminUserSearchDto, ReqAssignRoleDto, ResAdminUserDto, ResListAdminUserDto, ResListRoleDto, ResRoleDto, ResSystemStatsDto, ADMIN_ROLE, DEFAULT_ROLE}, auth_dto::{ReqSignInDto, ReqSignUpDto, ReqUpdateUserDto, ResMeDto, ResSignInDto, ResSignInStepDto}, pagination_dto::ResPageMetaDto, session_dto::{CreateSessionDto, ReqRefreshTokenDto, ResListSessionDto, ResRevokeSessionDto, ResSessionDto, RotateSessionDto, SessionClientDto}, sign_in_attempt_dto::{CreateSignInAttemptDto, SignInThrottleDto}, two_factor_dto::{ReqTwoFactorCodeDto, ReqTwoFactorPasswordDto, ReqTwoFactorSignInDto, ResTwoFactorChallengeDto, ResTwoFactorEnrolmentDto, ResTwoFactorRecoveryCodesDto, ResTwoFactorStatusDto}, dto_enum::{action_token_purpose::ActionTokenPurposeVariant, sign_in_failure::SignInFailureVariant}}, entities::{user, user_action_token, user_role, user_session}, req_repository::{action_token_repository::ActionTokenRepository, auth_repository::AuthRepository, gender_repository::GenderRepository, mailer_repository::Mailer, session_repository::{SessionRepositoryBase, SessionRepositoryUtility}, sign_in_attempt_repository::SignInAttemptRepository, sign_in_limiter_repository::SignInLimiterRepository, two_factor_repository::TwoFactorRepository, user_repository::{UserRepositoryBase, UserRepositoryUtility}, user_role_repository::RoleManagementRepository}}, implentation::{secret_token::{generate_secret_token, hash_secret_token}, totp::{generate_recovery_code, generate_totp_secret, matching_totp_step, normalize_recovery_code, totp_uri}}, soc::{soc_repository::RepositoryError, soc_usecase::UsecaseError}};
 
 
 
 
 
+// every generated refresh token starts with this
+const REFRESH_TOKEN_PREFIX: &str = "lhr_";
+const INVALID_REFRESH_TOKEN: &str = "Invalid refresh token";
+// whether the email is unknown or the password wrong, the client is told the same
+const INVALID_CREDENTIALS: &str = "Invalid email or password";
+// tokens mailed to verify an email or reset a password
+const VERIFY_EMAIL_TOKEN_PREFIX: &str = "lhv_";
+const RESET_PASSWORD_TOKEN_PREFIX: &str = "lhp_";
+const INVALID_ACTION_TOKEN: &str = "Invalid or expired token";
+const FORGOT_PASSWORD_ANSWER: &str = "If the email belongs to an account, a link to reset the password has been sent to it";
+// handed out after the password when two factor is on, traded for a session together with a code
+const TWO_FACTOR_CHALLENGE_PREFIX: &str = "lhc_";
+const INVALID_TWO_FACTOR_CODE: &str = "Invalid two factor code";
+const TWO_FACTOR_ALREADY_ENABLED: &str = "Two factor authentication is already enabled";
+const TWO_FACTOR_NOT_ENABLED: &str = "Two factor authentication is not enabled";
+
+fn uuid_of(id: &[u8]) -> Result<Uuid, UsecaseError> {
+    Uuid::from_slice(id).map_err(|err| UsecaseError::Unexpected(format!("Invalid UUID: {}", err)))
+}
+
+// the sign in limiter counter of an account
+pub(crate) fn account_throttle_key(email: &str) -> String {
+    format!("account:{}", email.trim().to_lowercase())
+}
+
+fn to_session_entry(session: user_session::Model, current_session_id: Uuid) -> Result<ResSessionDto, UsecaseError> {
+    let session_id = uuid_of(&session.id)?;
+    Ok(ResSessionDto {
+        id: session_id.to_string(),
+        user_agent: session.user_agent,
+        ip_address: session.ip_address,
+        is_current: session_id == current_session_id,
+        last_used_at: session.last_used_at.map(|last_used_at| last_used_at.to_rfc3339()),
+        expires_at: session.expires_at.to_rfc3339(),
+        created_at: session.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    })
+}
+
+fn to_admin_user_entry(user: user::Model, role: Option<user_role::Model>) -> Result<ResAdminUserDto, UsecaseError> {
+    Ok(ResAdminUserDto {
+        id: uuid_of(&user.id)?.to_string(),
+        username: user.username,
+        email: user.email,
+        first_name: user.first_name,
+        last_name: user.last_name,
+        user_role: role.map(|role| role.name).unwrap_or_else(|| DEFAULT_ROLE.to_string()),
+        is_disabled: user.disabled_at.is_some(),
+        disabled_at: user.disabled_at.map(|disabled_at| disabled_at.to_rfc3339()),
+        created_at: user.created_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+        updated_at: user.updated_at.map(|dt| dt.to_rfc3339()).unwrap_or_default(),
+    })
+}
+
+
 pub struct UserUseCase<T>
 where
     T: UserRepositoryBase
@@ -15,10 +70,23 @@ where
         + GenderRepository
         + RoleManagementRepository
         + AuthRepository
+        + SessionRepositoryBase
+        + SessionRepositoryUtility
+        + SignInAttemptRepository
+        + ActionTokenRepository
+        + TwoFactorRepository
         + Send
         + Sync,
 {
     user_repository: Arc<T>,
+    // picked at startup, see SignInLimitConfig::backend
+    sign_in_limiter: Arc<dyn SignInLimiterRepository>,
+    // picked at startup, see MailConfig::backend
+    mailer: Arc<dyn Mailer>,
+    session_config: SessionConfig,
+    sign_in_limit_config: SignInLimitConfig,
+    mail_config: MailConfig,
+    two_factor_config: TwoFactorConfig,
 }
 
 impl<T> UserUseCase<T>
@@ -28,18 +96,262 @@ where
         + GenderRepository
         + RoleManagementRepository
         + AuthRepository
+        + SessionRepositoryBase
+        + SessionRepositoryUtility
+        + SignInAttemptRepository
+        + ActionTokenRepository
+        + TwoFactorRepository
         + Send
         + Sync,
 {
-    pub fn new(user_repository: Arc<T>) -> Self {
-        Self { user_repository }
+    pub fn new(user_repository: Arc<T>, sign_in_limiter: Arc<dyn SignInLimiterRepository>, mailer: Arc<dyn Mailer>) -> Self {
+        Self {
+            user_repository,
+            sign_in_limiter,
+            mailer,
+            session_config: SessionConfig::default(),
+            sign_in_limit_config: SignInLimitConfig::default(),
+            mail_config: MailConfig::default(),
+            two_factor_config: TwoFactorConfig::default(),
+        }
+    }
+
+    fn refresh_expires_at(&self, now: DateTime<Utc>) -> DateTime<Utc> {
+        now + Duration::days(self.session_config.refresh_token_ttl_days)
+    }
+
+    // the counters a sign in from this client is held to, the account one always comes first
+    fn sign_in_throttle_keys(&self, email: &str, client: &SessionClientDto) -> Vec<(String, u32, u32)> {
+        let config = &self.sign_in_limit_config;
+        let mut throttle_keys = vec![(account_throttle_key(email), config.account_free_attempts, config.account_lockout_threshold)];
+        if let Some(ip_address) = &client.ip_address {
+            throttle_keys.push((format!("ip:{}", ip_address), config.ip_free_attempts, config.ip_lockout_threshold));
+        }
+        throttle_keys
+    }
+
+    // the latest time any of the counters blocks sign in until, None when it is allowed now
+    async fn sign_in_blocked_until(&self, keys: &[(String, u32, u32)], now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, UsecaseError> {
+        let mut blocked_until: Option<DateTime<Utc>> = None;
+        for (key, _, _) in keys {
+            if let Some(until) = self.sign_in_limiter.get_throttle(key).await?.and_then(|throttle| throttle.blocked_until)
+                && until > now
+            {
+                blocked_until = blocked_until.max(Some(until));
+            }
+        }
+        Ok(blocked_until)
+    }
+
+    // counts a failure, past the free attempts the wait doubles with every failure up to the lockout
+    async fn register_sign_in_failure(&self, key: &str, free_attempts: u32, lockout_threshold: u32, now: DateTime<Utc>) -> Result<(), UsecaseError> {
+        let config = &self.sign_in_limit_config;
+        let window_start = now - Duration::minutes(config.failure_window_minutes);
+        let previous = self.sign_in_limiter
+            .get_throttle(key)
+            .await?
+            .filter(|throttle| throttle.last_failure_at >= window_start);
+
+        // a counter starting over is a good moment to drop the ones nobody touched for a while
+        if previous.is_none() {
+            let stale_before = now - Duration::minutes(config.failure_window_minutes.max(config.lockout_minutes));
+            if let Err(err) = self.sign_in_limiter.purge_throttle_before(stale_before).await {
+                log::warn!("Failed to purge sign in counters: {}", err);
+            }
+        }
+
+        let failure_count = previous.map_or(0, |throttle| throttle.failure_count).saturating_add(1);
+        let blocked_until = if failure_count >= lockout_threshold {
+            Some(now + Duration::minutes(config.lockout_minutes))
+        } else if failure_count > free_attempts {
+            let doublings = (failure_count - free_attempts - 1).min(30);
+            let wait_secs = config.backoff_base_secs.saturating_mul(1_i64 << doublings).min(config.backoff_max_secs);
+            Some(now + Duration::seconds(wait_secs))
+        } else {
+            None
+        };
+
+        self.sign_in_limiter
+            .save_throttle(key, SignInThrottleDto { failure_count, last_failure_at: now, blocked_until })
+            .await?;
+        Ok(())
+    }
+
+    // the audit trail must never be the reason a sign in fails
+    async fn record_sign_in_attempt(&self, dto: CreateSignInAttemptDto) {
+        if let Err(err) = self.user_repository.record_sign_in_attempt(dto).await {
+            log::warn!("Failed to record sign in attempt: {}", err);
+        }
+    }
+
+    // stores a new token of the purpose and returns it, the unused ones mailed before stop working
+    async fn issue_action_token(&self, user_id: Uuid, email: &str, purpose: ActionTokenPurposeVariant, now: DateTime<Utc>) -> Result<String, UsecaseError> {
+        let (prefix, expires_at) = match purpose {
+            ActionTokenPurposeVariant::VerifyEmail => (VERIFY_EMAIL_TOKEN_PREFIX, now + Duration::hours(self.mail_config.verify_email_ttl_hours)),
+            ActionTokenPurposeVariant::ResetPassword => (RESET_PASSWORD_TOKEN_PREFIX, now + Duration::minutes(self.mail_config.reset_password_ttl_minutes)),
+            ActionTokenPurposeVariant::TwoFactorChallenge => (TWO_FACTOR_CHALLENGE_PREFIX, now + Duration::minutes(self.two_factor_config.challenge_ttl_minutes)),
+        };
+        self.user_repository.invalidate_action_tokens(user_id, purpose, now).await?;
+
+        let token = generate_secret_token(prefix);
+        let token_dto = CreateActionTokenDto {
+            purpose,
+            token_hash: hash_secret_token(&token),
+            email: email.to_string(),
+            expires_at,
+        };
+        self.user_repository.create_action_token(user_id, token_dto).await?;
+        Ok(token)
+    }
+
+    // seconds left before another mail of the purpose may go to the user, None when it may go now
+    async fn action_mail_cooldown(&self, user_id: Uuid, purpose: ActionTokenPurposeVariant, now: DateTime<Utc>) -> Result<Option<i64>, UsecaseError> {
+        let latest = self.user_repository.find_latest_action_token(user_id, purpose).await?;
+        let remaining = latest
+            .and_then(|token| token.created_at)
+            .map(|created_at| (created_at + Duration::seconds(self.mail_config.resend_cooldown_secs) - now).num_seconds())
+            .filter(|remaining| *remaining > 0);
+        Ok(remaining)
+    }
+
+    // the token that was mailed for the purpose, as long as it is unused and not expired
+    async fn find_live_action_token(&self, token: &str, purpose: ActionTokenPurposeVariant, now: DateTime<Utc>) -> Result<user_action_token::Model, UsecaseError> {
+        self.user_repository
+            .find_action_token_by_hash(&hash_secret_token(token.trim()))
+            .await?
+            .filter(|action_token| action_token.purpose == purpose.as_str() && action_token.used_at.is_none() && action_token.expires_at > now)
+            .ok_or_else(|| UsecaseError::InvalidData(INVALID_ACTION_TOKEN.to_string()))
+    }
+
+    async fn send_verification_mail(&self, user: &user::Model, now: DateTime<Utc>) -> Result<(), UsecaseError> {
+        let token = self.issue_action_token(uuid_of(&user.id)?, &user.email, ActionTokenPurposeVariant::VerifyEmail, now).await?;
+        let message = MailMessageDto {
+            to: user.email.clone(),
+            subject: "Verify your email address".to_string(),
+            body: format!(
+                "Hello {},\n\nOpen the link below to confirm this address for your Light House account, it is valid for {} hours:\n\n{}/verify-email?token={}\n\nIf you did not ask for this, ignore this mail.",
+                user.first_name,
+                self.mail_config.verify_email_ttl_hours,
+                self.mail_config.app_base_url,
+                token,
+            ),
+        };
+        self.mailer
+            .send(message)
+            .await
+            .map_err(|err| UsecaseError::ExternalServiceError(err.to_string()))
+    }
+
+    // the user as an admin sees them, after a change made through the role repository
+    async fn admin_user_entry(&self, user_id: Uuid) -> Result<ResAdminUserDto, UsecaseError> {
+        let user = self.user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(format!("User with ID '{}' not found", user_id)))?;
+        let role = self.user_repository.get_role_by_id(uuid_of(&user.user_role_id)?).await?;
+        to_admin_user_entry(user, role)
+    }
+
+    // a new session for a completed sign in, only the hash of its refresh token is stored
+    async fn start_session(&self, user: &user::Model, client: SessionClientDto, now: DateTime<Utc>) -> Result<ResSignInDto, UsecaseError> {
+        let refresh_token = generate_secret_token(REFRESH_TOKEN_PREFIX);
+        let session_dto = CreateSessionDto {
+            refresh_token_hash: hash_secret_token(&refresh_token),
+            user_agent: client.user_agent,
+            ip_address: client.ip_address,
+            expires_at: self.refresh_expires_at(now),
+        };
+        let session = self.user_repository.create_session(uuid_of(&user.id)?, session_dto).await?;
+        self.issue_tokens(user, &session, refresh_token, now).await
+    }
+
+    // fresh recovery codes replace the previous ones, the plain codes are only returned here
+    async fn new_recovery_codes(&self, user_id: Uuid) -> Result<ResTwoFactorRecoveryCodesDto, UsecaseError> {
+        let recovery_codes: Vec<String> = (0..self.two_factor_config.recovery_code_count)
+            .map(|_| generate_recovery_code())
+            .collect();
+        let code_hashes = recovery_codes
+            .iter()
+            .map(|code| hash_secret_token(&normalize_recovery_code(code)))
+            .collect();
+        self.user_repository.replace_recovery_codes(user_id, code_hashes).await?;
+        Ok(ResTwoFactorRecoveryCodesDto { recovery_codes })
+    }
+
+    // a password asked again while signed in, held to the same counters as signing in,
+    // false when it is wrong and the failure has been counted
+    async fn check_password_again(&self, user: &user::Model, password: &str, client: &SessionClientDto, now: DateTime<Utc>) -> Result<bool, UsecaseError> {
+        let user_id = uuid_of(&user.id)?;
+        let mut attempt = CreateSignInAttemptDto {
+            email: user.email.clone(),
+            user_id: Some(user_id),
+            ip_address: client.ip_address.clone(),
+            user_agent: client.user_agent.clone(),
+            failure: None,
+        };
+
+        let throttle_keys = self.sign_in_throttle_keys(&user.email, client);
+        if let Some(blocked_until) = self.sign_in_blocked_until(&throttle_keys, now).await? {
+            attempt.failure = Some(SignInFailureVariant::Throttled);
+            self.record_sign_in_attempt(attempt).await;
+            let wait_secs = (blocked_until - now).num_seconds().max(1);
+            return Err(UsecaseError::TooManyRequests(format!("Too many failed attempts, try again in {} seconds", wait_secs)));
+        }
+
+        if !self.user_repository.verify_password(user_id, password).await? {
+            for (key, free_attempts, lockout_threshold) in &throttle_keys {
+                self.register_sign_in_failure(key, *free_attempts, *lockout_threshold, now).await?;
+            }
+            attempt.failure = Some(SignInFailureVariant::WrongPassword);
+            self.record_sign_in_attempt(attempt).await;
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    // the password asked again before two factor is changed, a wrong one is refused
+    async fn confirm_password(&self, user_id: Uuid, password: &str, client: &SessionClientDto) -> Result<(), UsecaseError> {
+        let user = self.user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(format!("User with ID '{}' not found", user_id)))?;
+        if !self.check_password_again(&user, password, client, Utc::now().trunc_subsecs(0)).await? {
+            return Err(UsecaseError::PermissionDenied("Wrong password".to_string()));
+        }
+        Ok(())
+    }
+
+    // the response of a sign in or a refresh, the refresh token is only known here
+    async fn issue_tokens(&self, user: &user::Model, session: &user_session::Model, refresh_token: String, now: DateTime<Utc>) -> Result<ResSignInDto, UsecaseError> {
+        let user_id = uuid_of(&user.id)?;
+        let session_id = uuid_of(&session.id)?;
+        // the role is read again on every refresh, so a role change reaches the token within its lifetime
+        let role = self.user_repository
+            .get_role_by_id(uuid_of(&user.user_role_id)?)
+            .await?
+            .map(|role| role.name)
+            .unwrap_or_else(|| DEFAULT_ROLE.to_string());
+        let expires_at = (now + Duration::minutes(self.session_config.access_token_ttl_minutes)).min(session.expires_at);
+        let token = self.user_repository
+            .generate_access_token(user_id, session_id, &role, expires_at)
+            .await
+            .map_err(|err| UsecaseError::Unexpected(err.to_string()))?;
+
+        Ok(ResSignInDto {
+            token,
+            expires_at: expires_at.to_rfc3339(),
+            refresh_token,
+            refresh_expires_at: session.expires_at.to_rfc3339(),
+            session_id: session_id.to_string(),
+        })
     }
 }
 
+
 #[async_trait::async_trait]
 impl<T> UserUsecase for UserUseCase<T>
 where 
-    T: UserRepositoryBase + UserRepositoryUtility + GenderRepository + RoleManagementRepository + AuthRepository + Send + Sync,
+    T: UserRepositoryBase + UserRepositoryUtility + GenderRepository + RoleManagementRepository + AuthRepository + SessionRepositoryBase + SessionRepositoryUtility + SignInAttemptRepository + ActionTokenRepository + TwoFactorRepository + Send + Sync,
 {
     async fn register_user(&self, user_dto: ReqSignUpDto) -> Result<ResMeDto, UsecaseError>
     {
@@ -52,7 +364,12 @@ where
             Err(err) => return Err(UsecaseError::from(err)),
         };
 
-        // Step 2: Fetch the gender name using the gender_id from the created user
+        // Step 2: Mail the verification link, the account works without it so a failure only logs
+        if let Err(err) = self.send_verification_mail(&created_user, Utc::now().trunc_subsecs(0)).await {
+            log::warn!("Failed to send the verification mail to {}: {}", created_user.email, err);
+        }
+
+        // Step 3: Fetch the gender name using the gender_id from the created user
         let gender_id = match Uuid::from_slice(&created_user.gender_id) {
             Ok(id) => id,
             Err(err) => return Err(UsecaseError::Unexpected(err.to_string())),
@@ -63,7 +380,7 @@ where
             Err(err) => return Err(UsecaseError::from(err)),
         };
 
-        // Step 3: Fetch the role name using the role_id from the created user
+        // Step 4: Fetch the role name using the role_id from the created user
         let role_id = match Uuid::from_slice(&created_user.user_role_id) {
             Ok(id) => id,
             Err(err) => return Err(UsecaseError::Unexpected(err.to_string())),
@@ -74,7 +391,7 @@ where
             Err(err) => return Err(UsecaseError::from(err)),
         };
 
-        // Step 4: Populate the response object
+        // Step 5: Populate the response object
         res_me.id = match Uuid::from_slice(&created_user.id) {
             Ok(id) => id.to_string(),
             Err(err) => return Err(UsecaseError::Unexpected(err.to_string())),
@@ -85,19 +402,82 @@ where
         res_me.last_name = created_user.last_name;
         res_me.gender = gender.map(|g| g.name).unwrap_or_default();
         res_me.user_role = role.map(|r| r.name).unwrap_or_default();
+        res_me.email_verified = created_user.email_verified_at.is_some();
 
-        // Step 5: Return the response object
+        // Step 6: Return the response object
         Ok(res_me)
 
     }
 
-    async fn login(&self, user_dto: ReqSignInDto) -> Result<ResSignInDto, UsecaseError>
+    async fn login(&self, user_dto: ReqSignInDto, client: SessionClientDto) -> Result<ResSignInStepDto, UsecaseError>
     {
-        let result = self.user_repository.sign_in(user_dto).await;
-        match result {
-            Ok(res) => Ok(res),
-            Err(err) => Err(UsecaseError::Unexpected(err.to_string())),
+        let now = Utc::now().trunc_subsecs(0);
+        let email = user_dto.email.trim().to_lowercase();
+        let mut attempt = CreateSignInAttemptDto {
+            email: email.clone(),
+            user_id: None,
+            ip_address: client.ip_address.clone(),
+            user_agent: client.user_agent.clone(),
+            failure: None,
+        };
+
+        // Step 1: Refuse without checking the password while the account or the ip is throttled
+        let throttle_keys = self.sign_in_throttle_keys(&email, &client);
+        if let Some(blocked_until) = self.sign_in_blocked_until(&throttle_keys, now).await? {
+            attempt.failure = Some(SignInFailureVariant::Throttled);
+            self.record_sign_in_attempt(attempt).await;
+            let wait_secs = (blocked_until - now).num_seconds().max(1);
+            return Err(UsecaseError::TooManyRequests(format!("Too many sign in attempts, try again in {} seconds", wait_secs)));
+        }
+
+        // Step 2: Check the email and password, both mistakes look the same to the client
+        attempt.user_id = match self.user_repository.find_by_email(&email).await? {
+            Some(account) => Some(uuid_of(&account.id)?),
+            None => None,
+        };
+        let sign_in_dto = ReqSignInDto { email, password: user_dto.password };
+        let user = match self.user_repository.sign_in(sign_in_dto).await {
+            Ok(user) => user,
+            Err(RepositoryError::NotFound(_)) | Err(RepositoryError::InvalidInput(_)) => {
+                for (key, free_attempts, lockout_threshold) in &throttle_keys {
+                    self.register_sign_in_failure(key, *free_attempts, *lockout_threshold, now).await?;
+                }
+                attempt.failure = Some(match attempt.user_id {
+                    Some(_) => SignInFailureVariant::WrongPassword,
+                    None => SignInFailureVariant::UnknownEmail,
+                });
+                self.record_sign_in_attempt(attempt).await;
+                return Err(UsecaseError::ResourceNotFound(INVALID_CREDENTIALS.to_string()));
+            }
+            Err(err) => return Err(UsecaseError::from(err)),
+        };
+        if user.disabled_at.is_some() {
+            attempt.failure = Some(SignInFailureVariant::AccountDisabled);
+            self.record_sign_in_attempt(attempt).await;
+            return Err(UsecaseError::PermissionDenied("Account is disabled".to_string()));
         }
+
+        // Step 3: With two factor on the password alone only earns a challenge,
+        // the counters keep running until the code is right as well
+        let user_id = uuid_of(&user.id)?;
+        let two_factor = self.user_repository.find_two_factor_by_user_id(user_id).await?;
+        if two_factor.is_some_and(|two_factor| two_factor.confirmed_at.is_some()) {
+            let challenge_token = self.issue_action_token(user_id, &user.email, ActionTokenPurposeVariant::TwoFactorChallenge, now).await?;
+            let expires_at = now + Duration::minutes(self.two_factor_config.challenge_ttl_minutes);
+            return Ok(ResSignInStepDto::TwoFactorRequired(ResTwoFactorChallengeDto {
+                two_factor_required: true,
+                challenge_token,
+                expires_at: expires_at.to_rfc3339(),
+            }));
+        }
+
+        // Step 4: The account counter starts over, the ip one keeps counting other accounts
+        self.sign_in_limiter.clear_throttle(&throttle_keys[0].0).await?;
+        self.record_sign_in_attempt(attempt).await;
+
+        // Step 5: Start a session and return the token pair
+        let signed_in = self.start_session(&user, client, now).await?;
+        Ok(ResSignInStepDto::SignedIn(signed_in))
     }
 
     async fn me(&self, user_id: Uuid) -> Result<ResMeDto, UsecaseError>
@@ -142,6 +522,7 @@ where
                     return Err(UsecaseError::Unexpected("Invalid UUID format".to_string()));
                 }
             },
+            email_verified: user.email_verified_at.is_some(),
             username: user.username,
             email: user.email,
             first_name: user.first_name,
@@ -154,15 +535,45 @@ where
         Ok(res_me)
     }
 
-    async fn update_user(&self, user_id: Uuid, user_dto: ReqUpdateUserDto) -> Result<ResMeDto, UsecaseError>
+    async fn update_user(&self, user_id: Uuid, session_id: Uuid, user_dto: ReqUpdateUserDto, client: SessionClientDto) -> Result<ResMeDto, UsecaseError>
     {
-        // Step 1: Update the user in the database
+        let now = Utc::now().trunc_subsecs(0);
+
+        // Step 1: A new password or email has to be confirmed with the current password
+        let current_user = match self.user_repository.find_by_id(user_id).await? {
+            Some(user) => user,
+            None => return Err(UsecaseError::ResourceNotFound(format!("User with ID '{}' not found", user_id))),
+        };
+        let changes_password = user_dto.password.as_deref().is_some_and(|password| !password.is_empty());
+        let changes_email = user_dto.email.as_deref().is_some_and(|email| !email.is_empty() && email != current_user.email);
+        if changes_password || changes_email {
+            let current_password = match user_dto.current_password.as_deref().filter(|password| !password.is_empty()) {
+                Some(password) => password,
+                None => return Err(UsecaseError::InvalidData("current_password is required to change the password or the email".to_string())),
+            };
+            if !self.check_password_again(&current_user, current_password, &client, now).await? {
+                return Err(UsecaseError::PermissionDenied("Current password is incorrect".to_string()));
+            }
+        }
+
+        // Step 2: Update the user in the database, a new email is no longer verified
         let updated_user = match self.user_repository.update(user_dto, user_id).await {
             Ok(user) => user,
             Err(err) => return Err(UsecaseError::from(err)),
         };
+        if changes_email
+            && let Err(err) = self.send_verification_mail(&updated_user, now).await
+        {
+            log::warn!("Failed to send the verification mail to {}: {}", updated_user.email, err);
+        }
+
+        // a new password signs out every other session along with its refresh token
+        if changes_password {
+            let revoked = self.user_repository.revoke_all_session_by_user_id(user_id, Some(session_id), now).await?;
+            log::info!("Password of user {} changed, {} other session(s) revoked", user_id, revoked);
+        }
 
-        // Step 2: Fetch the gender name using the gender_id from the updated user
+        // Step 3: Fetch the gender name using the gender_id from the updated user
         let gender_id = match Uuid::from_slice(&updated_user.gender_id) {
             Ok(id) => id,
             Err(err) => return Err(UsecaseError::Unexpected(err.to_string())),
@@ -180,7 +591,7 @@ where
             },
         };
 
-        // Step 3: Fetch the role name using the user_role_id from the updated user
+        // Step 4: Fetch the role name using the user_role_id from the updated user
         let role_id = match Uuid::from_slice(&updated_user.user_role_id) {
             Ok(id) => id,
             Err(err) => return Err(UsecaseError::Unexpected(err.to_string())),
@@ -198,7 +609,7 @@ where
             },
         };
 
-        // Step 4: Populate the response object
+        // Step 5: Populate the response object
         let res_me = ResMeDto {
             id: match Uuid::from_slice(&updated_user.id) {
                 Ok(uuid) => uuid.to_string(),
@@ -207,6 +618,7 @@ where
                     return Err(UsecaseError::Unexpected(err.to_string()));
                 }
             },
+            email_verified: updated_user.email_verified_at.is_some(),
             username: updated_user.username,
             email: updated_user.email,
             first_name: updated_user.first_name,
@@ -215,26 +627,497 @@ where
             user_role,
         };
 
-        // Step 5: Return the response object
+        // Step 6: Return the response object
         Ok(res_me)
     }
 
-    async fn get_mcp_token(&self, user_id: Uuid) -> Result<ResMcpDto, UsecaseError>
+    
+
+}//
+
+
+#[async_trait::async_trait]
+impl<T> UserSessionUsecase for UserUseCase<T>
+where 
+    T: UserRepositoryBase + UserRepositoryUtility + GenderRepository + RoleManagementRepository + AuthRepository + SessionRepositoryBase + SessionRepositoryUtility + SignInAttemptRepository + ActionTokenRepository + TwoFactorRepository + Send + Sync,
+{
+    async fn refresh_token(&self, refresh_dto: ReqRefreshTokenDto, client: SessionClientDto) -> Result<ResSignInDto, UsecaseError>
     {
-        // Step 1: Fetch the user by ID
-        let user = match self.user_repository.find_by_id(user_id).await {
-            Ok(Some(user)) => user,
-            Ok(None) => return Err(UsecaseError::ResourceNotFound(format!("User with ID '{}' not found", user_id))),
-            Err(err) => return Err(UsecaseError::from(err)),
+        let now = Utc::now().trunc_subsecs(0);
+        let token_hash = hash_secret_token(&refresh_dto.refresh_token);
+
+        // Step 1: A token that was already rotated away means it leaked, end the session it belonged to
+        let session = match self.user_repository.find_by_refresh_token_hash(&token_hash).await? {
+            Some(session) => session,
+            None => {
+                if let Some(reused) = self.user_repository.find_by_previous_token_hash(&token_hash).await? {
+                    let user_id = uuid_of(&reused.user_id)?;
+                    let session_id = uuid_of(&reused.id)?;
+                    if self.user_repository.revoke_session(user_id, session_id, now).await? {
+                        log::warn!("Refresh token reused, session {} revoked", session_id);
+                    }
+                }
+                return Err(UsecaseError::ResourceNotFound(INVALID_REFRESH_TOKEN.to_string()));
+            }
         };
-        let result = ResMcpDto {
-            mcp_token: user.mcp_token,
+        if session.revoked_at.is_some() || session.expires_at <= now {
+            return Err(UsecaseError::ResourceNotFound(INVALID_REFRESH_TOKEN.to_string()));
+        }
+        let user = match self.user_repository.find_by_id(uuid_of(&session.user_id)?).await? {
+            Some(user) if user.disabled_at.is_none() => user,
+            _ => return Err(UsecaseError::ResourceNotFound(INVALID_REFRESH_TOKEN.to_string())),
+        };
+
+        // Step 2: Rotate, a concurrent refresh with the same token loses here
+        let refresh_token = generate_secret_token(REFRESH_TOKEN_PREFIX);
+        let session_id = uuid_of(&session.id)?;
+        let rotate_dto = RotateSessionDto {
+            from_token_hash: token_hash,
+            to_token_hash: hash_secret_token(&refresh_token),
+            expires_at: self.refresh_expires_at(now),
+            user_agent: client.user_agent,
+            ip_address: client.ip_address,
         };
-        // Step 2: Return the MCP token
-        Ok(result)
+        if !self.user_repository.rotate(session_id, rotate_dto, now).await? {
+            return Err(UsecaseError::ResourceNotFound(INVALID_REFRESH_TOKEN.to_string()));
+        }
 
+        // Step 3: Return the new pair with the session as stored now
+        let session = self.user_repository
+            .find_session_by_id(uuid_of(&session.user_id)?, session_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(INVALID_REFRESH_TOKEN.to_string()))?;
+        self.issue_tokens(&user, &session, refresh_token, now).await
     }
 
-    
+    async fn sign_out(&self, user_id: Uuid, session_id: Uuid) -> Result<(), UsecaseError>
+    {
+        self.user_repository.revoke_session(user_id, session_id, Utc::now().trunc_subsecs(0)).await?;
+        Ok(())
+    }
+
+    async fn get_all_session(&self, user_id: Uuid, current_session_id: Uuid) -> Result<ResListSessionDto, UsecaseError>
+    {
+        let sessions = self.user_repository.find_all_active_session_by_user_id(user_id, Utc::now()).await?;
+        let data = sessions
+            .into_iter()
+            .map(|session| to_session_entry(session, current_session_id))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ResListSessionDto {
+            length: data.len() as i32,
+            data,
+        })
+    }
+
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<(), UsecaseError>
+    {
+        if self.user_repository.revoke_session(user_id, session_id, Utc::now().trunc_subsecs(0)).await? {
+            Ok(())
+        } else {
+            Err(UsecaseError::ResourceNotFound("Session not found".to_string()))
+        }
+    }
+
+    async fn revoke_other_session(&self, user_id: Uuid, current_session_id: Uuid) -> Result<ResRevokeSessionDto, UsecaseError>
+    {
+        let revoked = self.user_repository
+            .revoke_all_session_by_user_id(user_id, Some(current_session_id), Utc::now().trunc_subsecs(0))
+            .await?;
+        Ok(ResRevokeSessionDto { revoked })
+    }
+
+    async fn is_session_active(&self, user_id: Uuid, session_id: Uuid) -> Result<bool, UsecaseError>
+    {
+        let session = self.user_repository.find_session_by_id(user_id, session_id).await?;
+        Ok(session.is_some_and(|session| session.revoked_at.is_none() && session.expires_at > Utc::now()))
+    }
+}
+
 
-}//
\ No newline at end of file
+#[async_trait::async_trait]
+impl<T> UserAccountUsecase for UserUseCase<T>
+where 
+    T: UserRepositoryBase + UserRepositoryUtility + GenderRepository + RoleManagementRepository + AuthRepository + SessionRepositoryBase + SessionRepositoryUtility + SignInAttemptRepository + ActionTokenRepository + TwoFactorRepository + Send + Sync,
+{
+    async fn send_email_verification(&self, user_id: Uuid) -> Result<ResAccountMessageDto, UsecaseError>
+    {
+        let now = Utc::now().trunc_subsecs(0);
+
+        // Step 1: Nothing to send for a verified address
+        let user = self.user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(format!("User with ID '{}' not found", user_id)))?;
+        if user.email_verified_at.is_some() {
+            return Err(UsecaseError::InvalidData("Email is already verified".to_string()));
+        }
+
+        // Step 2: One mail per cooldown, then a new link replaces the previous one
+        if let Some(wait_secs) = self.action_mail_cooldown(user_id, ActionTokenPurposeVariant::VerifyEmail, now).await? {
+            return Err(UsecaseError::TooManyRequests(format!("A verification mail was sent recently, try again in {} seconds", wait_secs)));
+        }
+        self.send_verification_mail(&user, now).await?;
+
+        Ok(ResAccountMessageDto {
+            message: format!("A verification link has been sent to {}", user.email),
+        })
+    }
+
+    async fn verify_email(&self, verify_dto: ReqVerifyEmailDto) -> Result<ResAccountMessageDto, UsecaseError>
+    {
+        let now = Utc::now().trunc_subsecs(0);
+
+        // Step 1: Use the token up, a second use of the same token fails here
+        let token = self.find_live_action_token(&verify_dto.token, ActionTokenPurposeVariant::VerifyEmail, now).await?;
+        if !self.user_repository.consume_action_token(uuid_of(&token.id)?, now).await? {
+            return Err(UsecaseError::InvalidData(INVALID_ACTION_TOKEN.to_string()));
+        }
+
+        // Step 2: Only the address the token was mailed to is verified
+        if !self.user_repository.mark_email_verified(uuid_of(&token.user_id)?, &token.email, now).await? {
+            return Err(UsecaseError::InvalidData("The email of the account changed since this link was sent".to_string()));
+        }
+
+        Ok(ResAccountMessageDto {
+            message: "Email verified".to_string(),
+        })
+    }
+
+    async fn forgot_password(&self, forgot_dto: ReqForgotPasswordDto) -> Result<ResAccountMessageDto, UsecaseError>
+    {
+        let now = Utc::now().trunc_subsecs(0);
+        let answer = ResAccountMessageDto {
+            message: FORGOT_PASSWORD_ANSWER.to_string(),
+        };
+
+        // Step 1: An unknown or disabled account gets the same answer
+        let user = match self.user_repository.find_by_email(forgot_dto.email.trim()).await? {
+            Some(user) if user.disabled_at.is_none() => user,
+            _ => return Ok(answer),
+        };
+        let user_id = uuid_of(&user.id)?;
+        if self.action_mail_cooldown(user_id, ActionTokenPurposeVariant::ResetPassword, now).await?.is_some() {
+            return Ok(answer);
+        }
+
+        // Step 2: Mail the link in the background, how long sending takes must not tell the account exists
+        let token = self.issue_action_token(user_id, &user.email, ActionTokenPurposeVariant::ResetPassword, now).await?;
+        let message = MailMessageDto {
+            to: user.email.clone(),
+            subject: "Reset your password".to_string(),
+            body: format!(
+                "Hello {},\n\nOpen the link below to choose a new password for your Light House account, it is valid for {} minutes and works once:\n\n{}/reset-password?token={}\n\nIf you did not ask for this, ignore this mail, your password stays as it is.",
+                user.first_name,
+                self.mail_config.reset_password_ttl_minutes,
+                self.mail_config.app_base_url,
+                token,
+            ),
+        };
+        let mailer = Arc::clone(&self.mailer);
+        tokio::spawn(async move {
+            if let Err(err) = mailer.send(message).await {
+                log::warn!("Failed to send the password reset mail: {}", err);
+            }
+        });
+
+        Ok(answer)
+    }
+
+    async fn reset_password(&self, reset_dto: ReqResetPasswordDto) -> Result<ResAccountMessageDto, UsecaseError>
+    {
+        let now = Utc::now().trunc_subsecs(0);
+
+        // Step 1: Use the token up, a second use of the same token fails here
+        let token = self.find_live_action_token(&reset_dto.token, ActionTokenPurposeVariant::ResetPassword, now).await?;
+        if !self.user_repository.consume_action_token(uuid_of(&token.id)?, now).await? {
+            return Err(UsecaseError::InvalidData(INVALID_ACTION_TOKEN.to_string()));
+        }
+        let user_id = uuid_of(&token.user_id)?;
+
+        // Step 2: Set the password, any other reset link still out there stops working
+        self.user_repository.set_password(user_id, &reset_dto.new_password).await?;
+        self.user_repository.invalidate_action_tokens(user_id, ActionTokenPurposeVariant::ResetPassword, now).await?;
+
+        // Step 3: Whoever knew the old password is signed out, the account may sign in again at once
+        let revoked = self.user_repository.revoke_all_session_by_user_id(user_id, None, now).await?;
+        self.sign_in_limiter.clear_throttle(&account_throttle_key(&token.email)).await?;
+        log::info!("Password of user {} reset, {} session(s) revoked", user_id, revoked);
+
+        // Step 4: Following the link proved the address, unless it changed since
+        self.user_repository.mark_email_verified(user_id, &token.email, now).await?;
+
+        Ok(ResAccountMessageDto {
+            message: "Password has been reset, sign in with the new password".to_string(),
+        })
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<T> UserTwoFactorUsecase for UserUseCase<T>
+where 
+    T: UserRepositoryBase + UserRepositoryUtility + GenderRepository + RoleManagementRepository + AuthRepository + SessionRepositoryBase + SessionRepositoryUtility + SignInAttemptRepository + ActionTokenRepository + TwoFactorRepository + Send + Sync,
+{
+    async fn get_two_factor_status(&self, user_id: Uuid) -> Result<ResTwoFactorStatusDto, UsecaseError>
+    {
+        let two_factor = self.user_repository.find_two_factor_by_user_id(user_id).await?;
+        let confirmed_at = two_factor.as_ref().and_then(|two_factor| two_factor.confirmed_at);
+        let recovery_codes_left = match confirmed_at {
+            Some(_) => self.user_repository.count_unused_recovery_codes(user_id).await?,
+            None => 0,
+        };
+
+        Ok(ResTwoFactorStatusDto {
+            enabled: confirmed_at.is_some(),
+            pending_enrolment: two_factor.is_some() && confirmed_at.is_none(),
+            confirmed_at: confirmed_at.map(|confirmed_at| confirmed_at.to_rfc3339()),
+            recovery_codes_left,
+        })
+    }
+
+    async fn begin_two_factor_enrolment(&self, user_id: Uuid) -> Result<ResTwoFactorEnrolmentDto, UsecaseError>
+    {
+        let now = Utc::now().trunc_subsecs(0);
+
+        // Step 1: A second enrolment would lock the user out of the first authenticator
+        let user = self.user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(format!("User with ID '{}' not found", user_id)))?;
+        let two_factor = self.user_repository.find_two_factor_by_user_id(user_id).await?;
+        if two_factor.is_some_and(|two_factor| two_factor.confirmed_at.is_some()) {
+            return Err(UsecaseError::InvalidData(TWO_FACTOR_ALREADY_ENABLED.to_string()));
+        }
+
+        // Step 2: Store a new secret, it only counts once a code confirms it
+        let secret = generate_totp_secret();
+        self.user_repository.save_pending_two_factor(user_id, &secret, now).await?;
+
+        // Step 3: Return it in both forms authenticator apps take
+        let otpauth_uri = totp_uri(&secret, &self.two_factor_config.issuer, &user.email)
+            .ok_or_else(|| UsecaseError::Unexpected("Failed to build the otpauth uri".to_string()))?;
+        Ok(ResTwoFactorEnrolmentDto { secret, otpauth_uri })
+    }
+
+    async fn confirm_two_factor_enrolment(&self, user_id: Uuid, code_dto: ReqTwoFactorCodeDto) -> Result<ResTwoFactorRecoveryCodesDto, UsecaseError>
+    {
+        let now = Utc::now().trunc_subsecs(0);
+
+        // Step 1: There has to be a pending enrolment
+        let two_factor = match self.user_repository.find_two_factor_by_user_id(user_id).await? {
+            Some(two_factor) if two_factor.confirmed_at.is_some() => return Err(UsecaseError::InvalidData(TWO_FACTOR_ALREADY_ENABLED.to_string())),
+            Some(two_factor) => two_factor,
+            None => return Err(UsecaseError::InvalidData("Start the enrolment before confirming it".to_string())),
+        };
+
+        // Step 2: A right code proves the authenticator app holds the secret
+        let step = matching_totp_step(&two_factor.secret, code_dto.code.trim(), now.timestamp() as u64)
+            .ok_or_else(|| UsecaseError::InvalidData(INVALID_TWO_FACTOR_CODE.to_string()))?;
+        if !self.user_repository.confirm_two_factor(user_id, step as i64, now).await? {
+            return Err(UsecaseError::InvalidData(TWO_FACTOR_ALREADY_ENABLED.to_string()));
+        }
+
+        // Step 3: Hand out the recovery codes, this is the only time they are shown
+        self.new_recovery_codes(user_id).await
+    }
+
+    async fn regenerate_recovery_codes(&self, user_id: Uuid, password_dto: ReqTwoFactorPasswordDto, client: SessionClientDto) -> Result<ResTwoFactorRecoveryCodesDto, UsecaseError>
+    {
+        self.confirm_password(user_id, &password_dto.password, &client).await?;
+        let two_factor = self.user_repository.find_two_factor_by_user_id(user_id).await?;
+        if two_factor.is_none_or(|two_factor| two_factor.confirmed_at.is_none()) {
+            return Err(UsecaseError::InvalidData(TWO_FACTOR_NOT_ENABLED.to_string()));
+        }
+        self.new_recovery_codes(user_id).await
+    }
+
+    async fn disable_two_factor(&self, user_id: Uuid, password_dto: ReqTwoFactorPasswordDto, client: SessionClientDto) -> Result<ResTwoFactorStatusDto, UsecaseError>
+    {
+        self.confirm_password(user_id, &password_dto.password, &client).await?;
+        if self.user_repository.find_two_factor_by_user_id(user_id).await?.is_none() {
+            return Err(UsecaseError::InvalidData(TWO_FACTOR_NOT_ENABLED.to_string()));
+        }
+        self.user_repository.delete_two_factor(user_id).await?;
+        log::info!("Two factor authentication of user {} turned off", user_id);
+        self.get_two_factor_status(user_id).await
+    }
+
+    async fn verify_two_factor_sign_in(&self, sign_in_dto: ReqTwoFactorSignInDto, client: SessionClientDto) -> Result<ResSignInDto, UsecaseError>
+    {
+        let now = Utc::now().trunc_subsecs(0);
+
+        // Step 1: The challenge stands for the password that was already checked
+        let challenge = self
+            .find_live_action_token(&sign_in_dto.challenge_token, ActionTokenPurposeVariant::TwoFactorChallenge, now)
+            .await
+            .map_err(|_| UsecaseError::ResourceNotFound(INVALID_ACTION_TOKEN.to_string()))?;
+        let user_id = uuid_of(&challenge.user_id)?;
+        let mut attempt = CreateSignInAttemptDto {
+            email: challenge.email.clone(),
+            user_id: Some(user_id),
+            ip_address: client.ip_address.clone(),
+            user_agent: client.user_agent.clone(),
+            failure: None,
+        };
+
+        // Step 2: Guessing codes is held to the same counters as guessing passwords
+        let throttle_keys = self.sign_in_throttle_keys(&challenge.email, &client);
+        if let Some(blocked_until) = self.sign_in_blocked_until(&throttle_keys, now).await? {
+            attempt.failure = Some(SignInFailureVariant::Throttled);
+            self.record_sign_in_attempt(attempt).await;
+            let wait_secs = (blocked_until - now).num_seconds().max(1);
+            return Err(UsecaseError::TooManyRequests(format!("Too many sign in attempts, try again in {} seconds", wait_secs)));
+        }
+
+        // Step 3: The account may have changed since the password was checked
+        let user = self.user_repository
+            .find_by_id(user_id)
+            .await?
+            .ok_or_else(|| UsecaseError::ResourceNotFound(INVALID_ACTION_TOKEN.to_string()))?;
+        if user.disabled_at.is_some() {
+            attempt.failure = Some(SignInFailureVariant::AccountDisabled);
+            self.record_sign_in_attempt(attempt).await;
+            return Err(UsecaseError::PermissionDenied("Account is disabled".to_string()));
+        }
+        let two_factor = self.user_repository
+            .find_two_factor_by_user_id(user_id)
+            .await?
+            .filter(|two_factor| two_factor.confirmed_at.is_some())
+            .ok_or_else(|| UsecaseError::ResourceNotFound(INVALID_ACTION_TOKEN.to_string()))?;
+
+        // Step 4: Six digits are an authenticator code, anything else a recovery code, each works once
+        let code = sign_in_dto.code.trim();
+        let accepted = if code.len() == 6 && code.chars().all(|c| c.is_ascii_digit()) {
+            match matching_totp_step(&two_factor.secret, code, now.timestamp() as u64) {
+                Some(step) => self.user_repository.use_two_factor_step(user_id, step as i64, now).await?,
+                None => false,
+            }
+        } else {
+            self.user_repository
+                .use_recovery_code(user_id, &hash_secret_token(&normalize_recovery_code(code)), now)
+                .await?
+        };
+        if !accepted {
+            for (key, free_attempts, lockout_threshold) in &throttle_keys {
+                self.register_sign_in_failure(key, *free_attempts, *lockout_threshold, now).await?;
+            }
+            attempt.failure = Some(SignInFailureVariant::WrongTwoFactorCode);
+            self.record_sign_in_attempt(attempt).await;
+            return Err(UsecaseError::ResourceNotFound(INVALID_TWO_FACTOR_CODE.to_string()));
+        }
+
+        // Step 5: Use the challenge up, the account counter starts over
+        if !self.user_repository.consume_action_token(uuid_of(&challenge.id)?, now).await? {
+            return Err(UsecaseError::ResourceNotFound(INVALID_ACTION_TOKEN.to_string()));
+        }
+        self.sign_in_limiter.clear_throttle(&throttle_keys[0].0).await?;
+        self.record_sign_in_attempt(attempt).await;
+
+        // Step 6: Start a session and return the token pair
+        self.start_session(&user, client, now).await
+    }
+}
+
+
+#[async_trait::async_trait]
+impl<T> AdminUsecase for UserUseCase<T>
+where 
+    T: UserRepositoryBase + UserRepositoryUtility + GenderRepository + RoleManagementRepository + AuthRepository + SessionRepositoryBase + SessionRepositoryUtility + SignInAttemptRepository + ActionTokenRepository + TwoFactorRepository + Send + Sync,
+{
+    async fn is_admin(&self, user_id: Uuid) -> Result<bool, UsecaseError>
+    {
+        Ok(self.user_repository.has_role(user_id, ADMIN_ROLE).await?)
+    }
+
+    async fn get_all_user(&self, search: AdminUserSearchDto) -> Result<ResListAdminUserDto, UsecaseError>
+    {
+        let page = search.page;
+        let per_page = search.per_page;
+        let (users, total) = self.user_repository.find_all_user_with_role(search).await?;
+        let data = users
+            .into_iter()
+            .map(|(user, role)| to_admin_user_entry(user, role))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(ResListAdminUserDto {
+            meta: ResPageMetaDto::new(total, Some(page), per_page, page * per_page < total, None),
+            data,
+        })
+    }
+
+    async fn get_all_role(&self) -> Result<ResListRoleDto, UsecaseError>
+    {
+        let roles = self.user_repository.get_all_role().await?;
+        let data = roles
+            .into_iter()
+            .map(|role| Ok(ResRoleDto {
+                id: uuid_of(&role.id)?.to_string(),
+                name: role.name,
+            }))
+            .collect::<Result<Vec<_>, UsecaseError>>()?;
+
+        Ok(ResListRoleDto {
+            length: data.len() as i32,
+            data,
+        })
+    }
+
+    async fn assign_role(&self, admin_id: Uuid, target_user_id: Uuid, role_dto: ReqAssignRoleDto) -> Result<ResAdminUserDto, UsecaseError>
+    {
+        // Step 1: An admin can not demote themselves, there could be no admin left
+        let role = role_dto.role.trim().to_lowercase();
+        if admin_id == target_user_id && role != ADMIN_ROLE {
+            return Err(UsecaseError::InvalidData("You can not change your own role".to_string()));
+        }
+
+        // Step 2: The repository checks the admin role and that the role exists
+        self.user_repository.assign_role(admin_id, target_user_id, &role).await?;
+        self.admin_user_entry(target_user_id).await
+    }
+
+    async fn revoke_role(&self, admin_id: Uuid, target_user_id: Uuid, role: String) -> Result<ResAdminUserDto, UsecaseError>
+    {
+        // Step 1: Revoking falls back to the default role, which can not be revoked itself
+        let role = role.trim().to_lowercase();
+        if role == DEFAULT_ROLE {
+            return Err(UsecaseError::InvalidData(format!("The '{}' role can not be revoked", DEFAULT_ROLE)));
+        }
+        if admin_id == target_user_id {
+            return Err(UsecaseError::InvalidData("You can not change your own role".to_string()));
+        }
+
+        // Step 2: Back to the default role
+        self.user_repository.revoke_role(admin_id, target_user_id, &role).await?;
+        self.admin_user_entry(target_user_id).await
+    }
+
+    async fn disable_user(&self, admin_id: Uuid, target_user_id: Uuid) -> Result<ResAdminUserDto, UsecaseError>
+    {
+        // Step 1: Locking yourself out is never intended
+        if admin_id == target_user_id {
+            return Err(UsecaseError::InvalidData("You can not disable your own account".to_string()));
+        }
+
+        // Step 2: Disable the account, then end the sessions it still has
+        let now = Utc::now().trunc_subsecs(0);
+        let user = self.user_repository.set_user_disabled(admin_id, target_user_id, Some(now)).await?;
+        let revoked = self.user_repository.revoke_all_session_by_user_id(target_user_id, None, now).await?;
+        log::info!("User {} disabled by {}, {} session(s) revoked", target_user_id, admin_id, revoked);
+
+        let role = self.user_repository.get_role_by_id(uuid_of(&user.user_role_id)?).await?;
+        to_admin_user_entry(user, role)
+    }
+
+    async fn enable_user(&self, admin_id: Uuid, target_user_id: Uuid) -> Result<ResAdminUserDto, UsecaseError>
+    {
+        let user = self.user_repository.set_user_disabled(admin_id, target_user_id, None).await?;
+        let role = self.user_repository.get_role_by_id(uuid_of(&user.user_role_id)?).await?;
+        to_admin_user_entry(user, role)
+    }
+
+    async fn get_system_stats(&self) -> Result<ResSystemStatsDto, UsecaseError>
+    {
+        Ok(self.user_repository.get_system_stats(Utc::now()).await?)
+    }
+}
\ No newline at end of file
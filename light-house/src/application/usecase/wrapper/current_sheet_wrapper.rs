@@ -1,8 +1,11 @@
 use std::{ops::Deref, sync::Arc};
 
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::DatabaseTransaction;
 use uuid::Uuid;
 
-use crate::{domain::{dto::asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto}, entities::{asset, asset_type, current_sheet}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, balance_repository::{BalanceRepositoryBase, BalanceRepositoryUtill}}}, infrastructure::database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, balance_repo::BalanceRepositoryImpl}, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::{asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto}, current_sheet_dto::{BalanceMovementDto, CurrentSheetReconcileDto}}, entities::{asset, asset_type, current_sheet}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, balance_repository::{BalanceRepositoryBase, BalanceRepositoryUtill}}}, infrastructure::database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, balance_repo::BalanceRepositoryImpl}, soc::soc_repository::RepositoryError};
 
 
 
@@ -45,17 +48,21 @@ impl BalanceRepositoryBase for CurrentSheetRepositoryComposite {
     {
         self.balance_repository.get_all_current_sheets_by_user(user_id).await
     }
-    async fn create_current_sheet(&self, user_id: Uuid, asset_id: Uuid, initial_balance: f64) -> Result<current_sheet::Model, RepositoryError>
+    async fn lock_current_sheet_by_asset_id(&self, txn: &DatabaseTransaction, user_id: Uuid, asset_id: Uuid) -> Result<Option<current_sheet::Model>, RepositoryError>
     {
-        self.balance_repository.create_current_sheet(user_id, asset_id, initial_balance).await
+        self.balance_repository.lock_current_sheet_by_asset_id(txn, user_id, asset_id).await
     }
-    async fn update_current_sheet(&self, user_id: Uuid, asset_id: Uuid, balance: Option<f64>) -> Result<current_sheet::Model, RepositoryError>
+    async fn create_current_sheet(&self, txn: &DatabaseTransaction, user_id: Uuid, asset_id: Uuid, initial_balance: Decimal) -> Result<current_sheet::Model, RepositoryError>
     {
-        self.balance_repository.update_current_sheet(user_id, asset_id, balance).await
+        self.balance_repository.create_current_sheet(txn, user_id, asset_id, initial_balance).await
     }
-    async fn delete_current_sheet_by_asset_id(&self, user_id: Uuid, asset_id: Uuid) -> Result<(), RepositoryError>
+    async fn update_current_sheet(&self, txn: &DatabaseTransaction, user_id: Uuid, asset_id: Uuid, balance: Option<Decimal>) -> Result<current_sheet::Model, RepositoryError>
     {
-        self.balance_repository.delete_current_sheet_by_asset_id(user_id, asset_id).await
+        self.balance_repository.update_current_sheet(txn, user_id, asset_id, balance).await
+    }
+    async fn delete_current_sheet_by_asset_id(&self, txn: &DatabaseTransaction, user_id: Uuid, asset_id: Uuid) -> Result<(), RepositoryError>
+    {
+        self.balance_repository.delete_current_sheet_by_asset_id(txn, user_id, asset_id).await
     }
     async fn get_current_sheet_by_id(&self, user_id: Uuid, current_sheet_id: Uuid) -> Result<Option<current_sheet::Model>, RepositoryError>
     {
@@ -73,6 +80,18 @@ impl BalanceRepositoryUtill for CurrentSheetRepositoryComposite{
     {
         self.balance_repository.get_all_current_sheets_by_asset_id(user_id, asset_id).await
     }
+    async fn rebuild_current_sheets(&self, user_id: Uuid, asset_id: Option<Uuid>, dry_run: bool) -> Result<Vec<CurrentSheetReconcileDto>, RepositoryError>
+    {
+        self.balance_repository.rebuild_current_sheets(user_id, asset_id, dry_run).await
+    }
+    async fn get_balance_as_of(&self, user_id: Uuid, asset_id: Uuid, as_of: DateTime<Utc>) -> Result<Decimal, RepositoryError>
+    {
+        self.balance_repository.get_balance_as_of(user_id, asset_id, as_of).await
+    }
+    async fn get_balance_movements(&self, user_id: Uuid, asset_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<BalanceMovementDto>, RepositoryError>
+    {
+        self.balance_repository.get_balance_movements(user_id, asset_id, from, to).await
+    }
 }
 
 
@@ -1,8 +1,9 @@
-use std::{ops::Deref, sync::Arc};
+use std::{collections::HashMap, ops::Deref, sync::Arc};
 
+use sea_orm::DatabaseTransaction;
 use uuid::Uuid;
 
-use crate::{domain::{dto::{asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto}, contact_dto::{ReqCreateContactDto, ReqUpdateContactDto}, transaction_dto::{ReqCreateIncomeDto, ReqUpdateIncomeDto}}, entities::{asset, asset_type, contact, contact_type, transaction}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, transaction_repository::RecordIncomeRepositoryUtility}}, infrastructure::database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, transaction::income_repo::IncomeRepositoryImpl}, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::{asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto}, contact_dto::{ReqCreateContactDto, ReqUpdateContactDto}, transaction_dto::{ReqCreateIncomeDto, ReqUpdateIncomeDto}}, entities::{asset, asset_type, contact, contact_type, tag, transaction}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, tag_repository::TransactionTagRepositoryUtility, transaction_repository::RecordIncomeRepositoryUtility}}, infrastructure::database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, tag_repo::TagRepositoryImpl, transaction::income_repo::IncomeRepositoryImpl}, soc::soc_repository::RepositoryError};
 
 
 
@@ -13,7 +14,7 @@ pub struct IncomeRepositoryComposite {
     pub income_repository: Arc<IncomeRepositoryImpl>,
     pub asset_repository: Arc<AssetRepositoryImpl>,
     pub contact_repository: Arc<ContactRepositoryImpl>,
-    
+    pub tag_repository: Arc<TagRepositoryImpl>,
 }
 
 
@@ -28,6 +29,9 @@ impl IncomeRepositoryComposite{
     pub fn contact_repository(&self) -> &Arc<ContactRepositoryImpl>{
         &self.contact_repository
     }
+    pub fn tag_repository(&self) -> &Arc<TagRepositoryImpl>{
+        &self.tag_repository
+    }
 
 }
 
@@ -150,5 +154,18 @@ impl ContactRepositoryUtility for IncomeRepositoryComposite{
     }
 }
 
-
-
+#[async_trait::async_trait]
+impl TransactionTagRepositoryUtility for IncomeRepositoryComposite {
+    async fn set_transaction_tags(&self, txn: &DatabaseTransaction, user_id: Uuid, transaction_id: Uuid, tag_ids: Vec<String>) -> Result<(), RepositoryError>
+    {
+        self.tag_repository.set_transaction_tags(txn, user_id, transaction_id, tag_ids).await
+    }
+    async fn delete_transaction_tags(&self, txn: &DatabaseTransaction, transaction_id: Uuid) -> Result<(), RepositoryError>
+    {
+        self.tag_repository.delete_transaction_tags(txn, transaction_id).await
+    }
+    async fn find_tags_by_transaction_ids(&self, user_id: Uuid, transaction_ids: Vec<Vec<u8>>) -> Result<HashMap<Vec<u8>, Vec<tag::Model>>, RepositoryError>
+    {
+        self.tag_repository.find_tags_by_transaction_ids(user_id, transaction_ids).await
+    }
+}
\ No newline at end of file
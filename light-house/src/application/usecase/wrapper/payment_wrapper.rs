@@ -1,8 +1,9 @@
-use std::{ops::Deref, sync::Arc};
+use std::{collections::HashMap, ops::Deref, sync::Arc};
 
+use sea_orm::DatabaseTransaction;
 use uuid::Uuid;
 
-use crate::{domain::{dto::{asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto}, contact_dto::{ReqCreateContactDto, ReqUpdateContactDto}, transaction_dto::{ReqCreatePaymentDto, ReqUpdatePaymentDto}}, entities::{asset, asset_type, contact, contact_type, transaction}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, transaction_repository::RecordPaymentRepositoryUtility}}, infrastructure::database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, transaction::payment_repo::PaymentRepositoryImpl}, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::{asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto}, contact_dto::{ReqCreateContactDto, ReqUpdateContactDto}, transaction_dto::{ReqCreatePaymentDto, ReqPaymentSplitDto, ReqUpdatePaymentDto}}, entities::{asset, asset_type, contact, contact_type, payment_split, tag, transaction}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, payment_split_repository::PaymentSplitRepositoryUtility, tag_repository::TransactionTagRepositoryUtility, transaction_repository::RecordPaymentRepositoryUtility}}, infrastructure::database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, payment_split_repo::PaymentSplitRepositoryImpl, tag_repo::TagRepositoryImpl, transaction::payment_repo::PaymentRepositoryImpl}, soc::soc_repository::RepositoryError};
 
 
 
@@ -11,6 +12,8 @@ pub struct PaymentRepositoryComposite {
     pub payment_repository: Arc<PaymentRepositoryImpl>,
     pub asset_repository: Arc<AssetRepositoryImpl>,
     pub contact_repository: Arc<ContactRepositoryImpl>,
+    pub tag_repository: Arc<TagRepositoryImpl>,
+    pub payment_split_repository: Arc<PaymentSplitRepositoryImpl>,
 }
 impl PaymentRepositoryComposite {
     pub fn payment_repository(&self) -> &Arc<PaymentRepositoryImpl> {
@@ -24,6 +27,14 @@ impl PaymentRepositoryComposite {
     pub fn contact_repository(&self) -> &Arc<ContactRepositoryImpl> {
         &self.contact_repository
     }
+
+    pub fn tag_repository(&self) -> &Arc<TagRepositoryImpl> {
+        &self.tag_repository
+    }
+
+    pub fn payment_split_repository(&self) -> &Arc<PaymentSplitRepositoryImpl> {
+        &self.payment_split_repository
+    }
 }
 impl Deref for PaymentRepositoryComposite {
     type Target = PaymentRepositoryImpl;
@@ -143,4 +154,40 @@ impl ContactRepositoryUtility for PaymentRepositoryComposite{
     {
         self.contact_repository.find_contact_type_by_id(user_id, contact_type_id).await
     }
+}
+
+#[async_trait::async_trait]
+impl TransactionTagRepositoryUtility for PaymentRepositoryComposite {
+    async fn set_transaction_tags(&self, txn: &DatabaseTransaction, user_id: Uuid, transaction_id: Uuid, tag_ids: Vec<String>) -> Result<(), RepositoryError>
+    {
+        self.tag_repository.set_transaction_tags(txn, user_id, transaction_id, tag_ids).await
+    }
+    async fn delete_transaction_tags(&self, txn: &DatabaseTransaction, transaction_id: Uuid) -> Result<(), RepositoryError>
+    {
+        self.tag_repository.delete_transaction_tags(txn, transaction_id).await
+    }
+    async fn find_tags_by_transaction_ids(&self, user_id: Uuid, transaction_ids: Vec<Vec<u8>>) -> Result<HashMap<Vec<u8>, Vec<tag::Model>>, RepositoryError>
+    {
+        self.tag_repository.find_tags_by_transaction_ids(user_id, transaction_ids).await
+    }
+}
+
+#[async_trait::async_trait]
+impl PaymentSplitRepositoryUtility for PaymentRepositoryComposite {
+    async fn set_payment_splits(&self, txn: &DatabaseTransaction, transaction_id: Uuid, splits: Vec<ReqPaymentSplitDto>) -> Result<Vec<payment_split::Model>, RepositoryError>
+    {
+        self.payment_split_repository.set_payment_splits(txn, transaction_id, splits).await
+    }
+    async fn find_payment_splits(&self, txn: &DatabaseTransaction, transaction_id: Uuid) -> Result<Vec<payment_split::Model>, RepositoryError>
+    {
+        self.payment_split_repository.find_payment_splits(txn, transaction_id).await
+    }
+    async fn delete_payment_splits(&self, txn: &DatabaseTransaction, transaction_id: Uuid) -> Result<(), RepositoryError>
+    {
+        self.payment_split_repository.delete_payment_splits(txn, transaction_id).await
+    }
+    async fn find_splits_by_transaction_ids(&self, transaction_ids: Vec<Vec<u8>>) -> Result<HashMap<Vec<u8>, Vec<payment_split::Model>>, RepositoryError>
+    {
+        self.payment_split_repository.find_splits_by_transaction_ids(transaction_ids).await
+    }
 }
\ No newline at end of file
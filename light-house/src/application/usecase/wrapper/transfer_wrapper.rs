@@ -1,8 +1,9 @@
-use std::{ops::Deref, sync::Arc};
+use std::{collections::HashMap, ops::Deref, sync::Arc};
 
+use sea_orm::DatabaseTransaction;
 use uuid::Uuid;
 
-use crate::{domain::{dto::{assest_type_dto::{ReqCreateAssetTypeDto, ReqUpdateAssestTypeDto}, asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto}, contact_dto::{ReqCreateContactDto, ReqUpdateContactDto}, expense_dto::{ReqCreateExpenseDto, ReqUpdateExpenseDto}, expense_type_dto::{ReqCreateExpenseTypeDto, ReqUpdateExpenseTypeDto}, transaction_dto::{ReqCreateTransferDto, ReqUpdateTransferDto}}, entities::{asset, asset_type, contact, contact_type, expense, expense_type, transaction}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, asset_type_repository::{AssetTypeRepositoryBase, AssetTypeRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, expense_repository::{ExpenseRepositoryBase, ExpenseRepositoryUtill}, expense_type_repository::{ExpenseTypeRepositoryBase, ExpenseTypeRepositoryUtility}, transaction_repository::TransferRepositoryUtility}}, infrastructure::database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, asset_type_repo::AssetTypeRepositoryImpl, contact_repo::ContactRepositoryImpl, expense_repo::ExpenseRepositoryImpl, expense_type_repos::ExpenseTypeRepositoryImpl, transaction::transfer_repo::TransferRepositoryImpl}, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::{assest_type_dto::{ReqCreateAssetTypeDto, ReqUpdateAssestTypeDto}, asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto}, contact_dto::{ReqCreateContactDto, ReqUpdateContactDto}, expense_dto::{ReqCreateExpenseDto, ReqUpdateExpenseDto}, expense_type_dto::{ReqCreateExpenseTypeDto, ReqUpdateExpenseTypeDto}, transaction_dto::{ReqCreateTransferDto, ReqUpdateTransferDto}}, entities::{asset, asset_type, contact, contact_type, expense, expense_type, tag, transaction}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, asset_type_repository::{AssetTypeRepositoryBase, AssetTypeRepositoryUtility}, contact_repository::{ContactRepositoryBase, ContactRepositoryUtility}, expense_repository::{ExpenseRepositoryBase, ExpenseRepositoryUtill}, expense_type_repository::{ExpenseTypeRepositoryBase, ExpenseTypeRepositoryUtility}, tag_repository::TransactionTagRepositoryUtility, transaction_repository::TransferRepositoryUtility}}, infrastructure::database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, asset_type_repo::AssetTypeRepositoryImpl, contact_repo::ContactRepositoryImpl, expense_repo::ExpenseRepositoryImpl, expense_type_repos::ExpenseTypeRepositoryImpl, tag_repo::TagRepositoryImpl, transaction::transfer_repo::TransferRepositoryImpl}, soc::soc_repository::RepositoryError};
 
 
 
@@ -16,6 +17,7 @@ pub struct TransferRepositoryComposite{
     pub expense_repository: Arc<ExpenseRepositoryImpl>,
     pub expense_type_repository: Arc<ExpenseTypeRepositoryImpl>,
     pub contact_repository: Arc<ContactRepositoryImpl>,
+    pub tag_repository: Arc<TagRepositoryImpl>,
 }
 
 
@@ -43,6 +45,10 @@ impl TransferRepositoryComposite{
     pub fn contact_repository(&self) -> &ContactRepositoryImpl {
         &self.contact_repository
     }
+
+    pub fn tag_repository(&self) -> &TagRepositoryImpl {
+        &self.tag_repository
+    }
 }
 
 impl Deref for TransferRepositoryComposite {
@@ -287,4 +293,20 @@ impl ContactRepositoryUtility for TransferRepositoryComposite {
     {
         self.contact_repository.find_contact_type_by_id(user_id, contact_type_id).await
     }
+}
+
+#[async_trait::async_trait]
+impl TransactionTagRepositoryUtility for TransferRepositoryComposite {
+    async fn set_transaction_tags(&self, txn: &DatabaseTransaction, user_id: Uuid, transaction_id: Uuid, tag_ids: Vec<String>) -> Result<(), RepositoryError>
+    {
+        self.tag_repository.set_transaction_tags(txn, user_id, transaction_id, tag_ids).await
+    }
+    async fn delete_transaction_tags(&self, txn: &DatabaseTransaction, transaction_id: Uuid) -> Result<(), RepositoryError>
+    {
+        self.tag_repository.delete_transaction_tags(txn, transaction_id).await
+    }
+    async fn find_tags_by_transaction_ids(&self, user_id: Uuid, transaction_ids: Vec<Vec<u8>>) -> Result<HashMap<Vec<u8>, Vec<tag::Model>>, RepositoryError>
+    {
+        self.tag_repository.find_tags_by_transaction_ids(user_id, transaction_ids).await
+    }
 }
\ No newline at end of file
@@ -1,9 +1,10 @@
 use std::{ops::Deref, sync::Arc};
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::{domain::{dto::auth_dto::{ReqSignInDto, ReqSignUpDto, ReqUpdateUserDto, ResSignInDto}, entities::{gender, user, user_role}, req_repository::{auth_repository::AuthRepository, gender_repository::GenderRepository, user_repository::{McpRepositoryBase, UserRepositoryBase, UserRepositoryUtility}, user_role_repository::RoleManagementRepository}}, infrastructure::database::mysql::impl_repository::{
-    auth_repo::AuthRepositoryImpl, gender_repo::GenderRepositoryImpl,
-    role_repo::RoleManagementRepositoryImpl, user_repo::UserRepositoryImpl,
+use crate::{domain::{dto::{account_dto::CreateActionTokenDto, admin_dto::{AdminUserSearchDto, ResSystemStatsDto}, auth_dto::{ReqSignInDto, ReqSignUpDto, ReqUpdateUserDto}, session_dto::{CreateSessionDto, RotateSessionDto}, sign_in_attempt_dto::CreateSignInAttemptDto, dto_enum::action_token_purpose::ActionTokenPurposeVariant}, entities::{gender, user, user_action_token, user_role, user_session, user_two_factor}, req_repository::{action_token_repository::ActionTokenRepository, auth_repository::AuthRepository, gender_repository::GenderRepository, session_repository::{SessionRepositoryBase, SessionRepositoryUtility}, sign_in_attempt_repository::SignInAttemptRepository, two_factor_repository::TwoFactorRepository, user_repository::{UserRepositoryBase, UserRepositoryUtility}, user_role_repository::RoleManagementRepository}}, infrastructure::database::mysql::impl_repository::{
+    action_token_repo::ActionTokenRepositoryImpl, auth_repo::AuthRepositoryImpl, gender_repo::GenderRepositoryImpl,
+    role_repo::RoleManagementRepositoryImpl, session_repo::SessionRepositoryImpl, sign_in_attempt_repo::SignInAttemptRepositoryImpl, two_factor_repo::TwoFactorRepositoryImpl, user_repo::UserRepositoryImpl,
 }, soc::soc_repository::RepositoryError};
 
 pub struct UserRepositoryComposite {
@@ -11,6 +12,10 @@ pub struct UserRepositoryComposite {
     pub auth_repository: Arc<AuthRepositoryImpl>,
     pub role_repository: Arc<RoleManagementRepositoryImpl>,
     pub gender_repository: Arc<GenderRepositoryImpl>,
+    pub session_repository: Arc<SessionRepositoryImpl>,
+    pub sign_in_attempt_repository: Arc<SignInAttemptRepositoryImpl>,
+    pub action_token_repository: Arc<ActionTokenRepositoryImpl>,
+    pub two_factor_repository: Arc<TwoFactorRepositoryImpl>,
     
 }
 
@@ -30,6 +35,22 @@ impl UserRepositoryComposite {
     pub fn gender_repository(&self) -> &GenderRepositoryImpl {
         &self.gender_repository
     }
+
+    pub fn session_repository(&self) -> &SessionRepositoryImpl {
+        &self.session_repository
+    }
+
+    pub fn sign_in_attempt_repository(&self) -> &SignInAttemptRepositoryImpl {
+        &self.sign_in_attempt_repository
+    }
+
+    pub fn action_token_repository(&self) -> &ActionTokenRepositoryImpl {
+        &self.action_token_repository
+    }
+
+    pub fn two_factor_repository(&self) -> &TwoFactorRepositoryImpl {
+        &self.two_factor_repository
+    }
 }
 
 impl Deref for UserRepositoryComposite {
@@ -66,9 +87,18 @@ impl UserRepositoryBase for UserRepositoryComposite {
 
 #[async_trait::async_trait]
 impl AuthRepository for UserRepositoryComposite {
-    async fn sign_in(&self, sign_in_dto: ReqSignInDto) -> Result<ResSignInDto, RepositoryError>{
+    async fn sign_in(&self, sign_in_dto: ReqSignInDto) -> Result<user::Model, RepositoryError>{
         self.auth_repository.sign_in(sign_in_dto).await
     }
+    async fn generate_access_token(&self, user_id: Uuid, session_id: Uuid, role: &str, expires_at: DateTime<Utc>) -> Result<String, RepositoryError>{
+        self.auth_repository.generate_access_token(user_id, session_id, role, expires_at).await
+    }
+    async fn verify_password(&self, user_id: Uuid, password: &str) -> Result<bool, RepositoryError>{
+        self.auth_repository.verify_password(user_id, password).await
+    }
+    async fn set_password(&self, user_id: Uuid, password: &str) -> Result<(), RepositoryError>{
+        self.auth_repository.set_password(user_id, password).await
+    }
     
 }
 
@@ -91,6 +121,22 @@ impl RoleManagementRepository for UserRepositoryComposite {
     {
         self.role_repository.get_role_by_id(role_id).await
     }
+    async fn get_all_role(&self) -> Result<Vec<user_role::Model>, RepositoryError>
+    {
+        self.role_repository.get_all_role().await
+    }
+    async fn find_all_user_with_role(&self, search: AdminUserSearchDto) -> Result<(Vec<(user::Model, Option<user_role::Model>)>, u64), RepositoryError>
+    {
+        self.role_repository.find_all_user_with_role(search).await
+    }
+    async fn set_user_disabled(&self, admin_id: Uuid, target_user_id: Uuid, disabled_at: Option<DateTime<Utc>>) -> Result<user::Model, RepositoryError>
+    {
+        self.role_repository.set_user_disabled(admin_id, target_user_id, disabled_at).await
+    }
+    async fn get_system_stats(&self, now: DateTime<Utc>) -> Result<ResSystemStatsDto, RepositoryError>
+    {
+        self.role_repository.get_system_stats(now).await
+    }
 
 }
 
@@ -119,4 +165,121 @@ impl UserRepositoryUtility for UserRepositoryComposite{
         self.user_repository.find_by_email(email).await
     }
 
+}
+
+
+#[async_trait::async_trait]
+impl SessionRepositoryBase for UserRepositoryComposite {
+    async fn create_session(&self, user_id: Uuid, dto: CreateSessionDto) -> Result<user_session::Model, RepositoryError>
+    {
+        self.session_repository.create_session(user_id, dto).await
+    }
+    async fn find_session_by_id(&self, user_id: Uuid, session_id: Uuid) -> Result<Option<user_session::Model>, RepositoryError>
+    {
+        self.session_repository.find_session_by_id(user_id, session_id).await
+    }
+    async fn find_all_active_session_by_user_id(&self, user_id: Uuid, now: DateTime<Utc>) -> Result<Vec<user_session::Model>, RepositoryError>
+    {
+        self.session_repository.find_all_active_session_by_user_id(user_id, now).await
+    }
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid, now: DateTime<Utc>) -> Result<bool, RepositoryError>
+    {
+        self.session_repository.revoke_session(user_id, session_id, now).await
+    }
+    async fn revoke_all_session_by_user_id(&self, user_id: Uuid, keep: Option<Uuid>, now: DateTime<Utc>) -> Result<u64, RepositoryError>
+    {
+        self.session_repository.revoke_all_session_by_user_id(user_id, keep, now).await
+    }
+}
+
+
+#[async_trait::async_trait]
+impl SessionRepositoryUtility for UserRepositoryComposite {
+    async fn find_by_refresh_token_hash(&self, token_hash: &str) -> Result<Option<user_session::Model>, RepositoryError>
+    {
+        self.session_repository.find_by_refresh_token_hash(token_hash).await
+    }
+    async fn find_by_previous_token_hash(&self, token_hash: &str) -> Result<Option<user_session::Model>, RepositoryError>
+    {
+        self.session_repository.find_by_previous_token_hash(token_hash).await
+    }
+    async fn rotate(&self, session_id: Uuid, dto: RotateSessionDto, now: DateTime<Utc>) -> Result<bool, RepositoryError>
+    {
+        self.session_repository.rotate(session_id, dto, now).await
+    }
+}
+
+
+#[async_trait::async_trait]
+impl SignInAttemptRepository for UserRepositoryComposite {
+    async fn record_sign_in_attempt(&self, dto: CreateSignInAttemptDto) -> Result<(), RepositoryError>
+    {
+        self.sign_in_attempt_repository.record_sign_in_attempt(dto).await
+    }
+}
+
+
+#[async_trait::async_trait]
+impl ActionTokenRepository for UserRepositoryComposite {
+    async fn create_action_token(&self, user_id: Uuid, dto: CreateActionTokenDto) -> Result<user_action_token::Model, RepositoryError>
+    {
+        self.action_token_repository.create_action_token(user_id, dto).await
+    }
+    async fn find_action_token_by_hash(&self, token_hash: &str) -> Result<Option<user_action_token::Model>, RepositoryError>
+    {
+        self.action_token_repository.find_action_token_by_hash(token_hash).await
+    }
+    async fn find_latest_action_token(&self, user_id: Uuid, purpose: ActionTokenPurposeVariant) -> Result<Option<user_action_token::Model>, RepositoryError>
+    {
+        self.action_token_repository.find_latest_action_token(user_id, purpose).await
+    }
+    async fn consume_action_token(&self, token_id: Uuid, now: DateTime<Utc>) -> Result<bool, RepositoryError>
+    {
+        self.action_token_repository.consume_action_token(token_id, now).await
+    }
+    async fn invalidate_action_tokens(&self, user_id: Uuid, purpose: ActionTokenPurposeVariant, now: DateTime<Utc>) -> Result<u64, RepositoryError>
+    {
+        self.action_token_repository.invalidate_action_tokens(user_id, purpose, now).await
+    }
+    async fn mark_email_verified(&self, user_id: Uuid, email: &str, now: DateTime<Utc>) -> Result<bool, RepositoryError>
+    {
+        self.action_token_repository.mark_email_verified(user_id, email, now).await
+    }
+}
+
+
+#[async_trait::async_trait]
+impl TwoFactorRepository for UserRepositoryComposite {
+    async fn find_two_factor_by_user_id(&self, user_id: Uuid) -> Result<Option<user_two_factor::Model>, RepositoryError>
+    {
+        self.two_factor_repository.find_two_factor_by_user_id(user_id).await
+    }
+    async fn save_pending_two_factor(&self, user_id: Uuid, secret: &str, now: DateTime<Utc>) -> Result<user_two_factor::Model, RepositoryError>
+    {
+        self.two_factor_repository.save_pending_two_factor(user_id, secret, now).await
+    }
+    async fn confirm_two_factor(&self, user_id: Uuid, step: i64, now: DateTime<Utc>) -> Result<bool, RepositoryError>
+    {
+        self.two_factor_repository.confirm_two_factor(user_id, step, now).await
+    }
+    async fn use_two_factor_step(&self, user_id: Uuid, step: i64, now: DateTime<Utc>) -> Result<bool, RepositoryError>
+    {
+        self.two_factor_repository.use_two_factor_step(user_id, step, now).await
+    }
+    async fn replace_recovery_codes(&self, user_id: Uuid, code_hashes: Vec<String>) -> Result<(), RepositoryError>
+    {
+        self.two_factor_repository.replace_recovery_codes(user_id, code_hashes).await
+    }
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str, now: DateTime<Utc>) -> Result<bool, RepositoryError>
+    {
+        self.two_factor_repository.use_recovery_code(user_id, code_hash, now).await
+    }
+    async fn count_unused_recovery_codes(&self, user_id: Uuid) -> Result<u64, RepositoryError>
+    {
+        self.two_factor_repository.count_unused_recovery_codes(user_id).await
+    }
+    async fn delete_two_factor(&self, user_id: Uuid) -> Result<(), RepositoryError>
+    {
+        self.two_factor_repository.delete_two_factor(user_id).await
+    }
 }
\ No newline at end of file
@@ -0,0 +1,19 @@
+use uuid::Uuid;
+
+use crate::{domain::dto::admin_dto::{AdminUserSearchDto, ReqAssignRoleDto, ResAdminUserDto, ResListAdminUserDto, ResListRoleDto, ResSystemStatsDto}, soc::soc_usecase::UsecaseError};
+
+
+
+#[async_trait::async_trait]
+pub trait AdminUsecase {
+    // read from the database on every call, the role inside a token may be minutes old
+    async fn is_admin(&self, user_id: Uuid) -> Result<bool, UsecaseError>;
+    async fn get_all_user(&self, search: AdminUserSearchDto) -> Result<ResListAdminUserDto, UsecaseError>;
+    async fn get_all_role(&self) -> Result<ResListRoleDto, UsecaseError>;
+    async fn assign_role(&self, admin_id: Uuid, target_user_id: Uuid, role_dto: ReqAssignRoleDto) -> Result<ResAdminUserDto, UsecaseError>;
+    async fn revoke_role(&self, admin_id: Uuid, target_user_id: Uuid, role: String) -> Result<ResAdminUserDto, UsecaseError>;
+    // also ends every session of the user
+    async fn disable_user(&self, admin_id: Uuid, target_user_id: Uuid) -> Result<ResAdminUserDto, UsecaseError>;
+    async fn enable_user(&self, admin_id: Uuid, target_user_id: Uuid) -> Result<ResAdminUserDto, UsecaseError>;
+    async fn get_system_stats(&self) -> Result<ResSystemStatsDto, UsecaseError>;
+}
\ No newline at end of file
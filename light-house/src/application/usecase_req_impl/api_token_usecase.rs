@@ -0,0 +1,16 @@
+use uuid::Uuid;
+
+use crate::{domain::dto::api_token_dto::{ApiTokenIdentityDto, ReqCreateApiTokenDto, ResApiTokenDto, ResCreatedApiTokenDto, ResListApiTokenDto}, soc::soc_usecase::UsecaseError};
+
+
+
+
+#[async_trait::async_trait]
+pub trait ApiTokenUsecase {
+    // the plain token is only part of this response
+    async fn create_api_token(&self, user_id: Uuid, token_dto: ReqCreateApiTokenDto) -> Result<ResCreatedApiTokenDto, UsecaseError>;
+    async fn get_all_api_token(&self, user_id: Uuid) -> Result<ResListApiTokenDto, UsecaseError>;
+    async fn revoke_api_token(&self, user_id: Uuid, token_id: Uuid) -> Result<ResApiTokenDto, UsecaseError>;
+    // ResourceNotFound for unknown, expired and revoked tokens alike
+    async fn authenticate_api_token(&self, token: &str, ip: Option<String>) -> Result<ApiTokenIdentityDto, UsecaseError>;
+}
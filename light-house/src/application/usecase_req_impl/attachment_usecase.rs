@@ -0,0 +1,27 @@
+use uuid::Uuid;
+
+use crate::{domain::dto::{attachment_dto::{AttachmentContentDto, AttachmentUploadDto, ResAttachmentUsageDto, ResEntryAttachmentDto, ResListAttachmentDto}, dto_enum::attachment_owner::AttachmentOwnerVariant}, soc::soc_usecase::UsecaseError};
+
+
+
+
+#[async_trait::async_trait]
+pub trait AttachmentUsecase {
+    // the owner must be a transaction, contact or asset of the user, the file counts against the quota
+    async fn upload_attachment(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid, upload_dto: AttachmentUploadDto) -> Result<ResEntryAttachmentDto, UsecaseError>;
+    async fn get_all_attachment_by_owner(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid) -> Result<ResListAttachmentDto, UsecaseError>;
+    async fn get_attachment(&self, user_id: Uuid, attachment_id: Uuid) -> Result<Option<ResEntryAttachmentDto>, UsecaseError>;
+    async fn download_attachment(&self, user_id: Uuid, attachment_id: Uuid) -> Result<AttachmentContentDto, UsecaseError>;
+    async fn delete_attachment(&self, user_id: Uuid, attachment_id: Uuid) -> Result<(), UsecaseError>;
+    async fn get_usage(&self, user_id: Uuid) -> Result<ResAttachmentUsageDto, UsecaseError>;
+}
+
+
+// used by the background worker and the delete paths of the owners, not exposed over HTTP
+#[async_trait::async_trait]
+pub trait AttachmentCleanupUsecase: Send + Sync {
+    // removes attachments whose transaction, contact or asset is gone, returns how many were removed
+    async fn purge_orphaned_attachments(&self) -> Result<usize, UsecaseError>;
+    // removes the attachments of a transaction, contact or asset being deleted, returns how many were removed
+    async fn remove_owner_attachments(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid) -> Result<usize, UsecaseError>;
+}
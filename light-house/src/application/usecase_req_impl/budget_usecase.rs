@@ -0,0 +1,19 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::dto::budget_dto::{CreateBudgetDto, ResEntryBudgetDto, ResListBudgetAlertDto, ResListBudgetDto, ResListBudgetStatusDto, UpdateBudgetDto}, soc::soc_usecase::UsecaseError};
+
+
+
+
+#[async_trait::async_trait]
+pub trait BudgetUsecase {
+    async fn create_budget(&self, user_id: Uuid, budget_dto: CreateBudgetDto) -> Result<ResEntryBudgetDto, UsecaseError>;
+    async fn get_budget(&self, user_id: Uuid, budget_id: Uuid) -> Result<Option<ResEntryBudgetDto>, UsecaseError>;
+    async fn update_budget(&self, user_id: Uuid, budget_id: Uuid, budget_dto: UpdateBudgetDto) -> Result<ResEntryBudgetDto, UsecaseError>;
+    async fn delete_budget(&self, user_id: Uuid, budget_id: Uuid) -> Result<(), UsecaseError>;
+    async fn get_all_budget(&self, user_id: Uuid) -> Result<ResListBudgetDto, UsecaseError>;
+    // spending of the period holding `date` for every budget, budgets not running on that date are left out
+    async fn get_budget_status(&self, user_id: Uuid, date: DateTime<Utc>) -> Result<ResListBudgetStatusDto, UsecaseError>;
+    async fn get_budget_alerts(&self, user_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<ResListBudgetAlertDto, UsecaseError>;
+}
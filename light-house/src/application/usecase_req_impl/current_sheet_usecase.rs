@@ -1,6 +1,7 @@
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::{domain::dto::current_sheet_dto::{ResCurrentSheetDto, ResListCurrentSheetDto}, soc::soc_usecase::UsecaseError};
+use crate::{domain::dto::{current_sheet_dto::{ReqRebuildCurrentSheetDto, ResBalanceAsOfDto, ResBalanceHistoryDto, ResCurrentSheetDto, ResListCurrentSheetDto, ResRebuildCurrentSheetDto}, dto_enum::balance_interval::BalanceIntervalVariant}, soc::soc_usecase::UsecaseError};
 
 
 
@@ -10,4 +11,7 @@ pub trait CurrentSheetUsecase {
     async fn get_current_sheet_by_id(&self, user_id: Uuid, curret_sheet_id: Uuid) -> Result<Option<ResCurrentSheetDto>, UsecaseError>;
     async fn get_all_current_sheets_by_user(&self, user_id: Uuid) -> Result<ResListCurrentSheetDto, UsecaseError>;
     async fn get_all_current_sheets_by_asset_id(&self, user_id: Uuid, asset_id: Uuid) -> Result<ResListCurrentSheetDto, UsecaseError>;
+    async fn rebuild_current_sheets(&self, user_id: Uuid, dto: ReqRebuildCurrentSheetDto) -> Result<ResRebuildCurrentSheetDto, UsecaseError>;
+    async fn get_balance_as_of(&self, user_id: Uuid, asset_id: Uuid, as_of: DateTime<Utc>) -> Result<Option<ResBalanceAsOfDto>, UsecaseError>;
+    async fn get_balance_history(&self, user_id: Uuid, asset_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, interval: BalanceIntervalVariant) -> Result<Option<ResBalanceHistoryDto>, UsecaseError>;
 }
\ No newline at end of file
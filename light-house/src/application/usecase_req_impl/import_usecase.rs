@@ -0,0 +1,28 @@
+use uuid::Uuid;
+
+use crate::{domain::dto::{dto_enum::date_order::DateOrderVariant, import_dto::{ReqCommitImportDto, ReqCreateImportProfileDto, ReqImportStatementDto, ReqUpdateImportProfileDto, ResEntryImportProfileDto, ResImportCommitDto, ResImportPreviewDto, ResListImportProfileDto}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+#[async_trait::async_trait]
+pub trait ImportProfileUsecase {
+    async fn create_import_profile(&self, user_id: Uuid, profile_dto: ReqCreateImportProfileDto) -> Result<ResEntryImportProfileDto, UsecaseError>;
+    async fn get_import_profile(&self, user_id: Uuid, profile_id: Uuid) -> Result<Option<ResEntryImportProfileDto>, UsecaseError>;
+    async fn update_import_profile(&self, user_id: Uuid, profile_id: Uuid, profile_dto: ReqUpdateImportProfileDto) -> Result<ResEntryImportProfileDto, UsecaseError>;
+    async fn delete_import_profile(&self, user_id: Uuid, profile_id: Uuid) -> Result<(), UsecaseError>;
+    async fn get_all_import_profile(&self, user_id: Uuid) -> Result<ResListImportProfileDto, UsecaseError>;
+}
+
+
+#[async_trait::async_trait]
+pub trait ImportUsecase {
+    // reads the statement with the profile, nothing is written
+    async fn preview_csv(&self, user_id: Uuid, profile_id: Uuid, statement_dto: ReqImportStatementDto, content: Vec<u8>) -> Result<ResImportPreviewDto, UsecaseError>;
+    // same preview for an OFX 1.x / 2.x file, `account_id` picks the account when it holds several
+    async fn preview_ofx(&self, user_id: Uuid, statement_dto: ReqImportStatementDto, content: Vec<u8>) -> Result<ResImportPreviewDto, UsecaseError>;
+    // same preview for a QIF file, whose dates carry no order of their own
+    async fn preview_qif(&self, user_id: Uuid, statement_dto: ReqImportStatementDto, date_order: DateOrderVariant, content: Vec<u8>) -> Result<ResImportPreviewDto, UsecaseError>;
+    // posts each record through the income or payment use case, a failed record does not stop the others
+    async fn commit_import(&self, user_id: Uuid, commit_dto: ReqCommitImportDto) -> Result<ResImportCommitDto, UsecaseError>;
+}
@@ -0,0 +1,17 @@
+use uuid::Uuid;
+
+use crate::{domain::dto::{dto_enum::mcp_operation::McpOperationStatusVariant, mcp_operation_dto::{ReqMcpProposeOperationDto, ResListMcpPendingOperationDto, ResMcpPendingOperationDto}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+#[async_trait::async_trait]
+pub trait McpOperationUsecase {
+    // stores the write with its balance impact, nothing changes until the user confirms it
+    async fn propose_operation(&self, user_id: Uuid, operation_dto: ReqMcpProposeOperationDto) -> Result<ResMcpPendingOperationDto, UsecaseError>;
+    async fn get_operation(&self, user_id: Uuid, operation_id: Uuid) -> Result<Option<ResMcpPendingOperationDto>, UsecaseError>;
+    async fn get_all_operation(&self, user_id: Uuid, status: Option<McpOperationStatusVariant>) -> Result<ResListMcpPendingOperationDto, UsecaseError>;
+    // runs the operation through the same use cases as the routes
+    async fn confirm_operation(&self, user_id: Uuid, operation_id: Uuid) -> Result<ResMcpPendingOperationDto, UsecaseError>;
+    async fn reject_operation(&self, user_id: Uuid, operation_id: Uuid) -> Result<ResMcpPendingOperationDto, UsecaseError>;
+}
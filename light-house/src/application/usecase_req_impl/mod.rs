@@ -8,4 +8,13 @@ pub mod contact_usecase;
 pub mod transaction_usecase;
 pub mod current_sheet_usecase;
 pub mod transaction_type_usecase;
-pub mod mcp_usecase;
\ No newline at end of file
+pub mod report_usecase;
+pub mod budget_usecase;
+pub mod recurring_transaction_usecase;
+pub mod mcp_operation_usecase;
+pub mod api_token_usecase;
+pub mod admin_usecase;
+pub mod user_data_usecase;
+pub mod tag_usecase;
+pub mod attachment_usecase;
+pub mod import_usecase;
\ No newline at end of file
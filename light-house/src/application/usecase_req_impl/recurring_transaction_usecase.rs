@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::dto::recurring_transaction_dto::{CreateRecurringTransactionDto, ReqUpdateRecurringTransactionDto, ResEntryRecurringTransactionDto, ResListRecurringOccurrenceDto, ResListRecurringTransactionDto, ResRecurringOccurrenceDto, ResRecurringPreviewDto}, soc::soc_usecase::UsecaseError};
+
+
+
+
+#[async_trait::async_trait]
+pub trait RecurringTransactionUsecase {
+    async fn create_recurring_transaction(&self, user_id: Uuid, recurring_dto: CreateRecurringTransactionDto) -> Result<ResEntryRecurringTransactionDto, UsecaseError>;
+    async fn get_recurring_transaction(&self, user_id: Uuid, recurring_transaction_id: Uuid) -> Result<Option<ResEntryRecurringTransactionDto>, UsecaseError>;
+    // end_at is read in the schedule's own timezone, so the request DTO is parsed here
+    async fn update_recurring_transaction(&self, user_id: Uuid, recurring_transaction_id: Uuid, recurring_dto: ReqUpdateRecurringTransactionDto) -> Result<ResEntryRecurringTransactionDto, UsecaseError>;
+    async fn delete_recurring_transaction(&self, user_id: Uuid, recurring_transaction_id: Uuid) -> Result<(), UsecaseError>;
+    async fn get_all_recurring_transaction(&self, user_id: Uuid) -> Result<ResListRecurringTransactionDto, UsecaseError>;
+    async fn pause_recurring_transaction(&self, user_id: Uuid, recurring_transaction_id: Uuid) -> Result<ResEntryRecurringTransactionDto, UsecaseError>;
+    // occurrences missed while paused are not posted, the schedule picks up at the next one
+    async fn resume_recurring_transaction(&self, user_id: Uuid, recurring_transaction_id: Uuid) -> Result<ResEntryRecurringTransactionDto, UsecaseError>;
+    async fn skip_occurrence(&self, user_id: Uuid, recurring_transaction_id: Uuid, occurrence: Option<String>) -> Result<ResRecurringOccurrenceDto, UsecaseError>;
+    async fn preview_occurrences(&self, user_id: Uuid, recurring_transaction_id: Uuid, count: usize) -> Result<ResRecurringPreviewDto, UsecaseError>;
+    async fn get_occurrences(&self, user_id: Uuid, recurring_transaction_id: Uuid) -> Result<ResListRecurringOccurrenceDto, UsecaseError>;
+}
+
+
+// used by the background worker, not exposed over HTTP
+#[async_trait::async_trait]
+pub trait RecurringTransactionRunnerUsecase {
+    // posts every occurrence due at `now`, returns how many transactions were posted
+    async fn run_due_occurrences(&self, now: DateTime<Utc>) -> Result<usize, UsecaseError>;
+}
@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::dto::{dto_enum::{expense_report_group::ExpenseReportGroupVariant, report_period::ReportPeriodVariant}, report_dto::{ResExpenseReportDto, ResReportSummaryDto}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+#[async_trait::async_trait]
+pub trait ReportUsecase {
+    async fn get_summary(&self, user_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, period: ReportPeriodVariant) -> Result<ResReportSummaryDto, UsecaseError>;
+    async fn get_expense_report(&self, user_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, group_by: ExpenseReportGroupVariant, tag_id: Option<Uuid>) -> Result<ResExpenseReportDto, UsecaseError>;
+}
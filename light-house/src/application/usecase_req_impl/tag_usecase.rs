@@ -0,0 +1,16 @@
+use uuid::Uuid;
+
+use crate::{domain::dto::tag_dto::{ReqCreateTagDto, ReqUpdateTagDto, ResEntryTagDto, ResListTagDto}, soc::soc_usecase::UsecaseError};
+
+
+
+
+#[async_trait::async_trait]
+pub trait TagUsecase {
+    async fn create_tag(&self, user_id: Uuid, tag_dto: ReqCreateTagDto) -> Result<ResEntryTagDto, UsecaseError>;
+    async fn get_tag(&self, user_id: Uuid, tag_id: Uuid) -> Result<Option<ResEntryTagDto>, UsecaseError>;
+    async fn update_tag(&self, user_id: Uuid, tag_id: Uuid, tag_dto: ReqUpdateTagDto) -> Result<ResEntryTagDto, UsecaseError>;
+    // the tag is taken off every transaction carrying it, the transactions stay
+    async fn delete_tag(&self, user_id: Uuid, tag_id: Uuid) -> Result<(), UsecaseError>;
+    async fn get_all_tag(&self, user_id: Uuid) -> Result<ResListTagDto, UsecaseError>;
+}
@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-use crate::{domain::dto::transaction_dto::{ReqCreateIncomeDto, ReqCreatePaymentDto, ReqCreateTransferDto, ReqUpdateIncomeDto, ReqUpdatePaymentDto, ReqUpdateTransferDto, ResEntryIncomeDto, ResEntryPaymentDto, ResEntryTransferDto, ResListIncomeDto, ResListPaymentDto, ResListTransferDto}, soc::soc_usecase::UsecaseError};
+use crate::{domain::dto::transaction_dto::{ReqCreateIncomeDto, ReqCreatePaymentDto, ReqCreateTransferDto, ReqUpdateIncomeDto, ReqUpdatePaymentDto, ReqUpdateTransferDto, ResEntryIncomeDto, ResEntryPaymentDto, ResEntryTransferDto, ResListIncomeDto, ResListPaymentDto, ResListTransferDto, ResListTransactionDto, TransactionSearchDto}, soc::soc_usecase::UsecaseError};
 
 
 
@@ -32,4 +32,10 @@ pub trait RecordPaymentUsecase{
     async fn update_payment(&self, user_id: Uuid,  transaction_id: Uuid, payment_dto: ReqUpdatePaymentDto) -> Result<ResEntryPaymentDto, UsecaseError>;
     async fn delete_payment(&self, user_id: Uuid , transaction_id: Uuid) -> Result<(), UsecaseError>;
     async fn get_all_payment(&self, user_id: Uuid) -> Result<ResListPaymentDto, UsecaseError>;
-}
\ No newline at end of file
+}
+
+
+#[async_trait::async_trait]
+pub trait TransactionSearchUsecase {
+    async fn search_transactions(&self, user_id: Uuid, search: TransactionSearchDto) -> Result<ResListTransactionDto, UsecaseError>;
+}
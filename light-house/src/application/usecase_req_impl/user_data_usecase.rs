@@ -0,0 +1,14 @@
+use uuid::Uuid;
+
+use crate::{domain::dto::user_data_dto::{ReqDeleteAccountDto, ResUserExportDto}, soc::soc_usecase::UsecaseError};
+
+
+
+
+#[async_trait::async_trait]
+pub trait UserDataUsecase {
+    // everything stored about the user, secrets left out
+    async fn export_user_data(&self, user_id: Uuid) -> Result<ResUserExportDto, UsecaseError>;
+    // removes the account and all its data for good, the password is checked first
+    async fn delete_account(&self, user_id: Uuid, delete_dto: ReqDeleteAccountDto) -> Result<(), UsecaseError>;
+}
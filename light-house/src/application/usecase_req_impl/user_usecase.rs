@@ -1,15 +1,59 @@
 use uuid::Uuid;
 
-use crate::{domain::dto::auth_dto::{ReqSignInDto, ReqSignUpDto, ReqUpdateUserDto, ResMcpDto, ResMeDto, ResSignInDto}, soc::soc_usecase::UsecaseError};
+use crate::{domain::dto::{account_dto::{ReqForgotPasswordDto, ReqResetPasswordDto, ReqVerifyEmailDto, ResAccountMessageDto}, auth_dto::{ReqSignInDto, ReqSignUpDto, ReqUpdateUserDto, ResMeDto, ResSignInDto, ResSignInStepDto}, session_dto::{ReqRefreshTokenDto, ResListSessionDto, ResRevokeSessionDto, SessionClientDto}, two_factor_dto::{ReqTwoFactorCodeDto, ReqTwoFactorPasswordDto, ReqTwoFactorSignInDto, ResTwoFactorEnrolmentDto, ResTwoFactorRecoveryCodesDto, ResTwoFactorStatusDto}}, soc::soc_usecase::UsecaseError};
 
 
 
 #[async_trait::async_trait]
 pub trait UserUsecase {
     async fn register_user(&self, user_dto: ReqSignUpDto) -> Result<ResMeDto, UsecaseError>;
-    async fn login(&self, user_dto: ReqSignInDto) -> Result<ResSignInDto, UsecaseError>;
+    // starts a session, the response carries its access and refresh token.
+    // with two factor on it carries a challenge for verify_two_factor_sign_in instead
+    async fn login(&self, user_dto: ReqSignInDto, client: SessionClientDto) -> Result<ResSignInStepDto, UsecaseError>;
     async fn me(&self, user_id: Uuid) -> Result<ResMeDto, UsecaseError>;
-    async fn update_user(&self, user_id: Uuid, user_dto: ReqUpdateUserDto) -> Result<ResMeDto, UsecaseError>;
-    async fn get_mcp_token(&self, user_id: Uuid) -> Result<ResMcpDto, UsecaseError>;
+    // a new password or email needs the current password, a new email has to be verified again,
+    // a new password revokes every session but the current one
+    async fn update_user(&self, user_id: Uuid, session_id: Uuid, user_dto: ReqUpdateUserDto, client: SessionClientDto) -> Result<ResMeDto, UsecaseError>;
     
+}
+
+
+#[async_trait::async_trait]
+pub trait UserSessionUsecase {
+    // swaps the refresh token for a new pair, a refresh token used twice revokes its session
+    async fn refresh_token(&self, refresh_dto: ReqRefreshTokenDto, client: SessionClientDto) -> Result<ResSignInDto, UsecaseError>;
+    async fn sign_out(&self, user_id: Uuid, session_id: Uuid) -> Result<(), UsecaseError>;
+    async fn get_all_session(&self, user_id: Uuid, current_session_id: Uuid) -> Result<ResListSessionDto, UsecaseError>;
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid) -> Result<(), UsecaseError>;
+    // signs out everywhere except the current session
+    async fn revoke_other_session(&self, user_id: Uuid, current_session_id: Uuid) -> Result<ResRevokeSessionDto, UsecaseError>;
+    // checked by the request guard on every call
+    async fn is_session_active(&self, user_id: Uuid, session_id: Uuid) -> Result<bool, UsecaseError>;
+}
+
+
+#[async_trait::async_trait]
+pub trait UserAccountUsecase {
+    // mails a new verification link, the previous one stops working
+    async fn send_email_verification(&self, user_id: Uuid) -> Result<ResAccountMessageDto, UsecaseError>;
+    async fn verify_email(&self, verify_dto: ReqVerifyEmailDto) -> Result<ResAccountMessageDto, UsecaseError>;
+    // answers the same whether or not the email belongs to an account
+    async fn forgot_password(&self, forgot_dto: ReqForgotPasswordDto) -> Result<ResAccountMessageDto, UsecaseError>;
+    // sets the new password and signs the user out everywhere
+    async fn reset_password(&self, reset_dto: ReqResetPasswordDto) -> Result<ResAccountMessageDto, UsecaseError>;
+}
+
+
+#[async_trait::async_trait]
+pub trait UserTwoFactorUsecase {
+    async fn get_two_factor_status(&self, user_id: Uuid) -> Result<ResTwoFactorStatusDto, UsecaseError>;
+    // a new secret, two factor is only on once a code from it is confirmed
+    async fn begin_two_factor_enrolment(&self, user_id: Uuid) -> Result<ResTwoFactorEnrolmentDto, UsecaseError>;
+    // turns two factor on and returns the recovery codes, shown only this once
+    async fn confirm_two_factor_enrolment(&self, user_id: Uuid, code_dto: ReqTwoFactorCodeDto) -> Result<ResTwoFactorRecoveryCodesDto, UsecaseError>;
+    // both ask for the password again, held to the sign in attempt limits
+    async fn regenerate_recovery_codes(&self, user_id: Uuid, password_dto: ReqTwoFactorPasswordDto, client: SessionClientDto) -> Result<ResTwoFactorRecoveryCodesDto, UsecaseError>;
+    async fn disable_two_factor(&self, user_id: Uuid, password_dto: ReqTwoFactorPasswordDto, client: SessionClientDto) -> Result<ResTwoFactorStatusDto, UsecaseError>;
+    // second step of a sign in, trades the challenge and a code for a session
+    async fn verify_two_factor_sign_in(&self, sign_in_dto: ReqTwoFactorSignInDto, client: SessionClientDto) -> Result<ResSignInDto, UsecaseError>;
 }
\ No newline at end of file
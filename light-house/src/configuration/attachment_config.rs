@@ -0,0 +1,68 @@
+use crate::configuration::sign_in_limit_config::env_or;
+
+
+
+
+// where attachment content is stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachmentStorageBackend {
+    // one file per attachment under ATTACHMENT_LOCAL_DIR
+    Local,
+    // an S3 compatible bucket, see the ATTACHMENT_S3_* variables, needs the `s3` cargo feature
+    S3,
+}
+
+
+// sizes are given in megabytes and kept in bytes,
+// S3 credentials come from the usual AWS_ACCESS_KEY_ID / AWS_SECRET_ACCESS_KEY variables
+#[derive(Debug, Clone)]
+pub struct AttachmentConfig {
+    pub backend: AttachmentStorageBackend,
+    pub local_dir: String,
+    pub max_file_bytes: u64,
+    // total size of every attachment of one user
+    pub quota_bytes: u64,
+    pub s3_bucket: String,
+    // set for MinIO and other S3 compatible services, the bucket is then addressed by path
+    pub s3_endpoint: Option<String>,
+    pub s3_region: String,
+    pub s3_prefix: String,
+    pub cleanup_enabled: bool,
+    pub cleanup_interval_secs: u64,
+}
+
+impl Default for AttachmentConfig {
+    fn default() -> Self {
+        let backend = match std::env::var("ATTACHMENT_STORAGE").map(|value| value.to_lowercase()).as_deref() {
+            Ok("s3") => AttachmentStorageBackend::S3,
+            _ => AttachmentStorageBackend::Local,
+        };
+        let local_dir = std::env::var("ATTACHMENT_LOCAL_DIR").unwrap_or_else(|_| "./attachments".to_string());
+        let max_file_bytes = env_or("ATTACHMENT_MAX_FILE_MB", 10_u64).max(1) * 1024 * 1024;
+        let quota_bytes = (env_or("ATTACHMENT_QUOTA_MB", 200_u64) * 1024 * 1024).max(max_file_bytes);
+        let s3_bucket = std::env::var("ATTACHMENT_S3_BUCKET").unwrap_or_else(|_| "light-house-attachments".to_string());
+        let s3_endpoint = std::env::var("ATTACHMENT_S3_ENDPOINT").ok().filter(|value| !value.is_empty());
+        let s3_region = std::env::var("ATTACHMENT_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+        let s3_prefix = std::env::var("ATTACHMENT_S3_PREFIX")
+            .unwrap_or_default()
+            .trim_matches('/')
+            .to_string();
+        let cleanup_enabled = std::env::var("ATTACHMENT_CLEANUP_ENABLED")
+            .map(|value| value != "false" && value != "0")
+            .unwrap_or(true);
+        let cleanup_interval_secs = env_or("ATTACHMENT_CLEANUP_INTERVAL_SECS", 3600_u64).max(60);
+
+        Self {
+            backend,
+            local_dir,
+            max_file_bytes,
+            quota_bytes,
+            s3_bucket,
+            s3_endpoint,
+            s3_region,
+            s3_prefix,
+            cleanup_enabled,
+            cleanup_interval_secs,
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use crate::configuration::sign_in_limit_config::env_or;
+
+
+
+
+// where outgoing mail goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MailerBackend {
+    // an smtp server, see the SMTP_* variables
+    Smtp,
+    // one file per mail in MAIL_OUTBOX_DIR and a log line, for local development and tests
+    File,
+}
+
+
+// how the smtp connection is secured
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmtpSecurity {
+    // tls from the first byte, usually port 465
+    Tls,
+    // plain connection upgraded with STARTTLS, usually port 587
+    StartTls,
+    // no encryption, only for a relay on the same host or network
+    None,
+}
+
+
+// Links in the mails point at app_base_url, the client is expected to read the token from the
+// query string and post it back to the api
+#[derive(Debug, Clone)]
+pub struct MailConfig {
+    pub backend: MailerBackend,
+    pub from: String,
+    pub outbox_dir: String,
+    pub smtp_host: String,
+    pub smtp_port: Option<u16>,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_security: SmtpSecurity,
+    pub app_base_url: String,
+    pub verify_email_ttl_hours: i64,
+    pub reset_password_ttl_minutes: i64,
+    // a new mail of the same kind is not sent to the same account more often than this
+    pub resend_cooldown_secs: i64,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        let backend = match std::env::var("MAILER").map(|value| value.to_lowercase()).as_deref() {
+            Ok("smtp") => MailerBackend::Smtp,
+            _ => MailerBackend::File,
+        };
+        let smtp_security = match std::env::var("SMTP_SECURITY").map(|value| value.to_lowercase()).as_deref() {
+            Ok("tls") => SmtpSecurity::Tls,
+            Ok("none") => SmtpSecurity::None,
+            _ => SmtpSecurity::StartTls,
+        };
+        let from = std::env::var("MAIL_FROM").unwrap_or_else(|_| "Light House <no-reply@localhost>".to_string());
+        let outbox_dir = std::env::var("MAIL_OUTBOX_DIR").unwrap_or_else(|_| "./mail_outbox".to_string());
+        let smtp_host = std::env::var("SMTP_HOST").unwrap_or_else(|_| "localhost".to_string());
+        let smtp_port = std::env::var("SMTP_PORT").ok().and_then(|value| value.parse::<u16>().ok());
+        let smtp_username = std::env::var("SMTP_USERNAME").ok().filter(|value| !value.is_empty());
+        let smtp_password = std::env::var("SMTP_PASSWORD").ok().filter(|value| !value.is_empty());
+        let app_base_url = std::env::var("APP_BASE_URL")
+            .unwrap_or_else(|_| "http://localhost:8000".to_string())
+            .trim_end_matches('/')
+            .to_string();
+        let verify_email_ttl_hours = env_or("VERIFY_EMAIL_TTL_HOURS", 48_i64).max(1);
+        let reset_password_ttl_minutes = env_or("RESET_PASSWORD_TTL_MINUTES", 60_i64).max(1);
+        let resend_cooldown_secs = env_or("MAIL_RESEND_COOLDOWN_SECS", 60_i64).max(0);
+
+        Self {
+            backend,
+            from,
+            outbox_dir,
+            smtp_host,
+            smtp_port,
+            smtp_username,
+            smtp_password,
+            smtp_security,
+            app_base_url,
+            verify_email_ttl_hours,
+            reset_password_ttl_minutes,
+            resend_cooldown_secs,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::net::SocketAddr;
+
+pub struct McpConfig {
+    pub sse_enabled: bool,
+    pub sse_bind: SocketAddr,
+    // token of the user served over stdio
+    pub stdio_token: Option<String>,
+}
+
+impl Default for McpConfig {
+    fn default() -> Self {
+        let sse_enabled = std::env::var("MCP_SSE_ENABLED")
+            .map(|value| value != "false" && value != "0")
+            .unwrap_or(true);
+        let sse_bind = std::env::var("MCP_SSE_BIND")
+            .unwrap_or_else(|_| "127.0.0.1:8001".to_string())
+            .parse::<SocketAddr>()
+            .unwrap_or_else(|_| SocketAddr::from(([127, 0, 0, 1], 8001)));
+        let stdio_token = std::env::var("MCP_TOKEN").ok().filter(|token| !token.trim().is_empty());
+
+        Self {
+            sse_enabled,
+            sse_bind,
+            stdio_token,
+        }
+    }
+}
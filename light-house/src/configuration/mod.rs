@@ -1,4 +1,11 @@
 pub mod jwt_config;
 pub mod mysql_config;
 pub mod api_doc_config;
-pub mod api_security_addon;
\ No newline at end of file
+pub mod api_security_addon;
+pub mod recurring_config;
+pub mod mcp_config;
+pub mod session_config;
+pub mod sign_in_limit_config;
+pub mod mail_config;
+pub mod two_factor_config;
+pub mod attachment_config;
\ No newline at end of file
@@ -0,0 +1,22 @@
+pub struct RecurringWorkerConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,
+}
+
+impl Default for RecurringWorkerConfig {
+    fn default() -> Self {
+        let enabled = std::env::var("RECURRING_WORKER_ENABLED")
+            .map(|value| value != "false" && value != "0")
+            .unwrap_or(true);
+        let interval_secs = std::env::var("RECURRING_WORKER_INTERVAL_SECS")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse::<u64>()
+            .unwrap_or(60)
+            .max(1);
+
+        Self {
+            enabled,
+            interval_secs,
+        }
+    }
+}
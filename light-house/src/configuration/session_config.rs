@@ -0,0 +1,24 @@
+pub struct SessionConfig {
+    pub access_token_ttl_minutes: i64,
+    pub refresh_token_ttl_days: i64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        let access_token_ttl_minutes = std::env::var("ACCESS_TOKEN_TTL_MINUTES")
+            .unwrap_or_else(|_| "15".to_string())
+            .parse::<i64>()
+            .unwrap_or(15)
+            .max(1);
+        let refresh_token_ttl_days = std::env::var("REFRESH_TOKEN_TTL_DAYS")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<i64>()
+            .unwrap_or(30)
+            .max(1);
+
+        Self {
+            access_token_ttl_minutes,
+            refresh_token_ttl_days,
+        }
+    }
+}
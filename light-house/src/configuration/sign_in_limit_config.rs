@@ -0,0 +1,61 @@
+// where failed sign in attempts are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignInLimiterBackend {
+    // this process only, fine for a single instance
+    Memory,
+    // shared by every instance through the sign_in_throttle table
+    Database,
+}
+
+
+// Failures are counted per account (email) and per ip. After the free attempts each failure
+// doubles the wait before the next try, reaching the lockout threshold blocks for lockout_minutes.
+// A counter starts over once its last failure is older than failure_window_minutes
+#[derive(Debug, Clone)]
+pub struct SignInLimitConfig {
+    pub backend: SignInLimiterBackend,
+    pub account_free_attempts: u32,
+    pub account_lockout_threshold: u32,
+    pub ip_free_attempts: u32,
+    pub ip_lockout_threshold: u32,
+    pub backoff_base_secs: i64,
+    pub backoff_max_secs: i64,
+    pub lockout_minutes: i64,
+    pub failure_window_minutes: i64,
+}
+
+pub(crate) fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse::<T>().ok())
+        .unwrap_or(default)
+}
+
+impl Default for SignInLimitConfig {
+    fn default() -> Self {
+        let backend = match std::env::var("SIGN_IN_LIMITER").map(|value| value.to_lowercase()).as_deref() {
+            Ok("database") | Ok("db") => SignInLimiterBackend::Database,
+            _ => SignInLimiterBackend::Memory,
+        };
+        let account_free_attempts = env_or("SIGN_IN_ACCOUNT_FREE_ATTEMPTS", 3_u32);
+        let account_lockout_threshold = env_or("SIGN_IN_ACCOUNT_LOCKOUT_THRESHOLD", 10_u32).max(account_free_attempts + 1);
+        let ip_free_attempts = env_or("SIGN_IN_IP_FREE_ATTEMPTS", 10_u32);
+        let ip_lockout_threshold = env_or("SIGN_IN_IP_LOCKOUT_THRESHOLD", 50_u32).max(ip_free_attempts + 1);
+        let backoff_base_secs = env_or("SIGN_IN_BACKOFF_BASE_SECS", 1_i64).max(1);
+        let backoff_max_secs = env_or("SIGN_IN_BACKOFF_MAX_SECS", 300_i64).max(backoff_base_secs);
+        let lockout_minutes = env_or("SIGN_IN_LOCKOUT_MINUTES", 15_i64).max(1);
+        let failure_window_minutes = env_or("SIGN_IN_FAILURE_WINDOW_MINUTES", 60_i64).max(1);
+
+        Self {
+            backend,
+            account_free_attempts,
+            account_lockout_threshold,
+            ip_free_attempts,
+            ip_lockout_threshold,
+            backoff_base_secs,
+            backoff_max_secs,
+            lockout_minutes,
+            failure_window_minutes,
+        }
+    }
+}
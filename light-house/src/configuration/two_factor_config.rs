@@ -0,0 +1,22 @@
+use crate::configuration::sign_in_limit_config::env_or;
+
+
+
+
+// issuer is the label authenticator apps show next to the code
+#[derive(Debug, Clone)]
+pub struct TwoFactorConfig {
+    pub issuer: String,
+    pub challenge_ttl_minutes: i64,
+    pub recovery_code_count: usize,
+}
+
+impl Default for TwoFactorConfig {
+    fn default() -> Self {
+        Self {
+            issuer: std::env::var("TWO_FACTOR_ISSUER").unwrap_or_else(|_| "Light House".to_string()),
+            challenge_ttl_minutes: env_or("TWO_FACTOR_CHALLENGE_TTL_MINUTES", 5_i64).max(1),
+            recovery_code_count: env_or("TWO_FACTOR_RECOVERY_CODES", 10_usize).clamp(1, 20),
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::dto::dto_enum::action_token_purpose::ActionTokenPurposeVariant;
+
+
+
+
+// a single use token about to be mailed, only its hash is stored
+pub struct CreateActionTokenDto {
+    pub purpose: ActionTokenPurposeVariant,
+    pub token_hash: String,
+    pub email: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+
+// plain text mail handed to a Mailer
+#[derive(Debug, Clone)]
+pub struct MailMessageDto {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqVerifyEmailDto {
+    #[validate(length(min = 1, message = "The token must not be empty"))]
+    pub token: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqForgotPasswordDto {
+    #[validate(email(message = "Invalid email"))]
+    pub email: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqResetPasswordDto {
+    #[validate(length(min = 1, message = "The token must not be empty"))]
+    pub token: String,
+
+    #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
+    pub new_password: String,
+}
+
+
+// the same answer whether or not anything was sent, so it tells nothing about the account
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResAccountMessageDto {
+    pub message: String,
+}
@@ -0,0 +1,140 @@
+use rocket::FromForm;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
+
+use crate::domain::dto::pagination_dto::ResPageMetaDto;
+
+
+
+
+// role every account gets at sign up, and falls back to when a role is revoked
+pub const DEFAULT_ROLE: &str = "user";
+pub const ADMIN_ROLE: &str = "admin";
+
+
+// >>>>>>>> User listing <<<<<<<<
+#[derive(Debug, Deserialize, FromForm, IntoParams)]
+#[serde(crate = "rocket::serde")]
+#[into_params(parameter_in = Query)]
+pub struct ReqAdminUserQueryDto {
+    /// case insensitive text inside the username or the email
+    pub search: Option<String>,
+    /// only users holding this role
+    pub role: Option<String>,
+    /// true for disabled accounts only, false for active accounts only
+    pub disabled: Option<bool>,
+    /// 1 based page number
+    pub page: Option<String>,
+    /// defaults to 20, at most 100
+    pub per_page: Option<String>,
+}
+
+impl ReqAdminUserQueryDto {
+    pub const DEFAULT_PER_PAGE: u64 = 20;
+    pub const MAX_PER_PAGE: u64 = 100;
+
+    // the error message is returned to the client as is
+    pub fn into_search(self) -> Result<AdminUserSearchDto, String> {
+        let page = match self.page {
+            Some(page) => page.parse::<u64>().map_err(|_| "Invalid page".to_string())?,
+            None => 1,
+        };
+        if page == 0 {
+            return Err("page starts at 1".to_string());
+        }
+        let per_page = match self.per_page {
+            Some(per_page) => per_page.parse::<u64>().map_err(|_| "Invalid per_page".to_string())?,
+            None => Self::DEFAULT_PER_PAGE,
+        };
+        if per_page == 0 || per_page > Self::MAX_PER_PAGE {
+            return Err(format!("per_page must be between 1 and {}", Self::MAX_PER_PAGE));
+        }
+
+        Ok(AdminUserSearchDto {
+            search: self.search.map(|search| search.trim().to_string()).filter(|search| !search.is_empty()),
+            role: self.role.map(|role| role.trim().to_lowercase()).filter(|role| !role.is_empty()),
+            disabled: self.disabled,
+            page,
+            per_page,
+        })
+    }
+}
+
+
+// parsed form of ReqAdminUserQueryDto handed to the repository
+#[derive(Debug, Clone)]
+pub struct AdminUserSearchDto {
+    pub search: Option<String>,
+    pub role: Option<String>,
+    pub disabled: Option<bool>,
+    pub page: u64,
+    pub per_page: u64,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResAdminUserDto {
+    pub id: String,
+    pub username: String,
+    pub email: String,
+    pub first_name: String,
+    pub last_name: String,
+    pub user_role: String,
+    pub is_disabled: bool,
+    pub disabled_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListAdminUserDto {
+    pub meta: ResPageMetaDto,
+    pub data: Vec<ResAdminUserDto>,
+}
+
+
+// >>>>>>>> Roles <<<<<<<<
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqAssignRoleDto {
+    #[validate(length(min = 1, max = 50, message = "Role must be between 1 and 50 characters"))]
+    pub role: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResRoleDto {
+    pub id: String,
+    pub name: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListRoleDto {
+    pub length: i32,
+    pub data: Vec<ResRoleDto>,
+}
+
+
+// >>>>>>>> System stats <<<<<<<<
+#[derive(Debug, Serialize, Deserialize, ToSchema, Default)]
+#[serde(crate = "rocket::serde")]
+pub struct ResSystemStatsDto {
+    pub user_count: u64,
+    pub disabled_user_count: u64,
+    pub admin_count: u64,
+    // users with a live session, signed in on at least one device
+    pub signed_in_user_count: u64,
+    pub active_session_count: u64,
+    pub active_api_token_count: u64,
+    pub asset_count: u64,
+    pub contact_count: u64,
+    pub transaction_count: u64,
+    pub pending_mcp_operation_count: u64,
+}
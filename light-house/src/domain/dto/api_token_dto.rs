@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::domain::dto::dto_enum::api_token_scope::ApiTokenScopeVariant;
+
+
+
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqCreateApiTokenDto {
+    #[validate(length(min = 1, max = 100, message = "The name must be between 1 and 100 characters"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "At least one scope is required"))]
+    pub scopes: Vec<ApiTokenScopeVariant>,
+    // the token never expires when omitted
+    #[validate(range(min = 1, max = 3650, message = "The expires_in_days must be between 1 and 3650"))]
+    pub expires_in_days: Option<i64>,
+}
+
+
+pub struct CreateApiTokenDto {
+    pub name: String,
+    pub token_hash: String,
+    pub token_prefix: String,
+    pub scopes: Vec<ApiTokenScopeVariant>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+
+// who an MCP request acts for and what it may do
+#[derive(Debug, Clone)]
+pub struct ApiTokenIdentityDto {
+    pub user_id: Uuid,
+    pub token_id: Uuid,
+    pub scopes: Vec<ApiTokenScopeVariant>,
+}
+
+impl ApiTokenIdentityDto {
+    pub fn allows(&self, scope: ApiTokenScopeVariant) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResApiTokenDto {
+    pub id: String,
+    pub name: String,
+    pub token_prefix: String,
+    pub scopes: Vec<ApiTokenScopeVariant>,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+    pub last_used_ip: Option<String>,
+    pub revoked_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResCreatedApiTokenDto {
+    // only returned here, store it now since just its hash is kept
+    pub token: String,
+    pub api_token: ResApiTokenDto,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListApiTokenDto {
+    pub length: i32,
+    pub data: Vec<ResApiTokenDto>,
+}
@@ -0,0 +1,80 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::domain::dto::dto_enum::attachment_owner::AttachmentOwnerVariant;
+
+
+
+
+
+// multipart/form-data body of an upload, only used for the api docs
+#[derive(Debug, ToSchema)]
+pub struct ReqUploadAttachmentDto {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+}
+
+
+// a received file before it is stored
+#[derive(Debug, Clone)]
+pub struct AttachmentUploadDto {
+    pub file_name: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+
+// the row written once the content is in the storage, the id is part of the storage key
+pub struct CreateAttachmentDto {
+    pub id: Uuid,
+    pub owner_type: AttachmentOwnerVariant,
+    pub owner_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+    pub storage_key: String,
+}
+
+
+// a stored file on its way to the client
+#[derive(Debug, Clone)]
+pub struct AttachmentContentDto {
+    pub file_name: String,
+    pub content_type: String,
+    pub content: Vec<u8>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResEntryAttachmentDto {
+    pub id: String,
+    pub owner_type: AttachmentOwnerVariant,
+    pub owner_id: String,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    // hex sha-256 of the content
+    pub sha256: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListAttachmentDto {
+    pub length: i32,
+    pub data: Vec<ResEntryAttachmentDto>,
+}
+
+
+// storage used by the user against the quota
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResAttachmentUsageDto {
+    pub count: i64,
+    pub used_bytes: i64,
+    pub quota_bytes: i64,
+    pub max_file_bytes: i64,
+}
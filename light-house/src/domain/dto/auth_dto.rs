@@ -1,8 +1,10 @@
-use rocket::{serde::{Deserialize, Serialize}, Responder};
+use rocket::serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::domain::dto::two_factor_dto::ResTwoFactorChallengeDto;
+
 
 
 #[derive(Deserialize, Serialize, ToSchema, Validate, Clone)]
@@ -15,15 +17,30 @@ pub struct ReqSignInDto{
     #[validate(length(min = 8, message = "Password must be at least 8 characters"))]
     pub password: String
 }
-#[derive(Serialize, Deserialize, Responder, ToSchema, Clone)]
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
 #[serde(crate = "rocket::serde")]
 pub struct ResSignInDto{
     
+    // short lived access token, sent as `Authorization: Bearer <token>`
     pub token: String,
+    pub expires_at: String,
+    // exchanged at /token/refresh for a new pair, each refresh token works once
+    pub refresh_token: String,
+    pub refresh_expires_at: String,
+    pub session_id: String,
     
 }
 
 
+// a sign in ends with a token pair, or with a challenge when the user has two factor on
+#[derive(Serialize, Deserialize, ToSchema, Clone)]
+#[serde(crate = "rocket::serde", untagged)]
+pub enum ResSignInStepDto {
+    SignedIn(ResSignInDto),
+    TwoFactorRequired(ResTwoFactorChallengeDto),
+}
+
+
 #[derive(Deserialize,Validate, ToSchema,Debug, PartialEq, Clone)]
 #[serde(crate = "rocket::serde")]
 pub struct ReqSignUpDto{
@@ -53,6 +70,8 @@ pub struct Claims {
     pub sub: Uuid,
     pub role: String,
     pub exp: u64,
+    // the session the token belongs to, signing out revokes it
+    pub sid: Uuid,
 }
 
 #[derive(Deserialize,Validate, ToSchema,Debug)]
@@ -64,6 +83,8 @@ pub struct ReqUpdateUserDto{
     pub first_name: Option<String>,
     pub last_name: Option<String>,
     pub gender: Option<String>,
+    // required when the password or the email changes
+    pub current_password: Option<String>,
 
 }
 
@@ -77,12 +98,6 @@ pub struct ResMeDto {
     pub username: String,
     pub email: String,
     pub first_name: String,
-    pub last_name: String
-}
-
-
-#[derive(Deserialize, Serialize, ToSchema)]
-#[serde(crate = "rocket::serde")]
-pub struct ResMcpDto {
-    pub mcp_token: String
+    pub last_name: String,
+    pub email_verified: bool
 }
\ No newline at end of file
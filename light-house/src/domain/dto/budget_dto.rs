@@ -0,0 +1,215 @@
+use chrono::{DateTime, SubsecRound, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{domain::dto::dto_enum::budget_period_type::BudgetPeriodVariant, implentation::{date_time_utill::{parse_occurred_at, parse_occurred_until}, decimal_utoipa::DecimalWrapper}};
+
+
+
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqCreateBudgetDto {
+    #[validate(length(min = 1, message = "The name must not be empty"))]
+    pub name: String,
+    #[validate(length(min = 1, message = "The expense_type_id must not be empty"))]
+    pub expense_type_id: String,
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: DecimalWrapper,
+    pub period_type: BudgetPeriodVariant,
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The start_date must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub start_date: String,
+    // required for custom budgets, optional last day of a monthly budget
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The end_date must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub end_date: Option<String>,
+    // IANA name such as "Asia/Bangkok", only used when the dates carry no offset
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_timezone", message = "The timezone must be an IANA name such as Asia/Bangkok"))]
+    pub timezone: Option<String>,
+    // monthly only, defaults to false
+    pub rollover: Option<bool>,
+}
+
+impl ReqCreateBudgetDto {
+    // Parses the fields and checks the rules between them, the error message is returned to the client as is
+    pub fn into_create(self) -> Result<CreateBudgetDto, String> {
+        let expense_type_id = Uuid::parse_str(&self.expense_type_id).map_err(|_| "Invalid expense_type_id".to_string())?;
+        // TIMESTAMP columns keep whole seconds, periods are matched on these exact values
+        let start_date = parse_occurred_at(&self.start_date, self.timezone.as_deref())?.trunc_subsecs(0);
+        let end_date = self
+            .end_date
+            .as_deref()
+            .map(|end_date| parse_occurred_until(end_date, self.timezone.as_deref()).map(|end_date| end_date.trunc_subsecs(0)))
+            .transpose()?;
+        let rollover = self.rollover.unwrap_or(false);
+
+        if let Some(end_date) = end_date
+            && end_date < start_date
+        {
+            return Err("end_date must not be before start_date".to_string());
+        }
+        if self.period_type == BudgetPeriodVariant::Custom {
+            if end_date.is_none() {
+                return Err("A custom budget needs an end_date".to_string());
+            }
+            if rollover {
+                return Err("Rollover is only available for monthly budgets".to_string());
+            }
+        }
+
+        Ok(CreateBudgetDto {
+            name: self.name,
+            expense_type_id,
+            amount: self.amount.0,
+            period_type: self.period_type,
+            start_date,
+            end_date,
+            rollover,
+        })
+    }
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqUpdateBudgetDto {
+    #[validate(length(min = 1, message = "The name must not be empty"))]
+    pub name: Option<String>,
+    // applies to the current and upcoming periods, past periods keep their amount
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: Option<DecimalWrapper>,
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The end_date must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub end_date: Option<String>,
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_timezone", message = "The timezone must be an IANA name such as Asia/Bangkok"))]
+    pub timezone: Option<String>,
+    pub rollover: Option<bool>,
+}
+
+impl ReqUpdateBudgetDto {
+    pub fn into_update(self) -> Result<UpdateBudgetDto, String> {
+        let end_date = self
+            .end_date
+            .as_deref()
+            .map(|end_date| parse_occurred_until(end_date, self.timezone.as_deref()).map(|end_date| end_date.trunc_subsecs(0)))
+            .transpose()?;
+
+        Ok(UpdateBudgetDto {
+            name: self.name,
+            amount: self.amount.map(|amount| amount.0),
+            end_date,
+            rollover: self.rollover,
+        })
+    }
+}
+
+
+// parsed and cross checked form of ReqCreateBudgetDto handed to the repository
+#[derive(Debug, Clone)]
+pub struct CreateBudgetDto {
+    pub name: String,
+    pub expense_type_id: Uuid,
+    pub amount: Decimal,
+    pub period_type: BudgetPeriodVariant,
+    pub start_date: DateTime<Utc>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub rollover: bool,
+}
+
+
+// parsed form of ReqUpdateBudgetDto handed to the repository
+#[derive(Debug, Clone)]
+pub struct UpdateBudgetDto {
+    pub name: Option<String>,
+    pub amount: Option<Decimal>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub rollover: Option<bool>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResEntryBudgetDto {
+    pub id: String,
+    pub name: String,
+    pub expense_type_id: String,
+    pub expense_type_name: String,
+    pub amount: DecimalWrapper,
+    pub period_type: BudgetPeriodVariant,
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub rollover: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListBudgetDto {
+    pub length: i32,
+    pub data: Vec<ResEntryBudgetDto>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResBudgetStatusDto {
+    pub budget_id: String,
+    pub budget_name: String,
+    pub expense_type_id: String,
+    pub expense_type_name: String,
+    pub period_id: String,
+    pub period_start: String,
+    pub period_end: String,
+    pub amount: DecimalWrapper,
+    pub rollover_amount: DecimalWrapper,
+    // amount + rollover_amount
+    pub budgeted: DecimalWrapper,
+    pub spent: DecimalWrapper,
+    pub remaining: DecimalWrapper,
+    // None when nothing is budgeted for the period
+    pub used_percent: Option<DecimalWrapper>,
+    pub is_over_budget: bool,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListBudgetStatusDto {
+    pub date: String,
+    pub over_budget_count: i32,
+    pub length: i32,
+    pub data: Vec<ResBudgetStatusDto>,
+}
+
+
+// the payment that pushed a budget period over its budgeted amount
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResBudgetAlertDto {
+    pub budget_id: String,
+    pub budget_name: String,
+    pub expense_type_name: String,
+    pub period_start: String,
+    pub period_end: String,
+    pub budgeted: DecimalWrapper,
+    pub spent: DecimalWrapper,
+    pub overspent: DecimalWrapper,
+    pub transaction_id: String,
+    // for a split payment only the lines on the budget's expense type
+    pub transaction_amount: DecimalWrapper,
+    pub transaction_occurred_at: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListBudgetAlertDto {
+    pub from: String,
+    pub to: String,
+    pub length: i32,
+    pub data: Vec<ResBudgetAlertDto>,
+}
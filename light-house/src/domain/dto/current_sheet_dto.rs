@@ -1,5 +1,10 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::{domain::dto::dto_enum::balance_interval::BalanceIntervalVariant, implentation::decimal_utoipa::DecimalWrapper};
 
 
 
@@ -10,7 +15,7 @@ use utoipa::ToSchema;
 pub struct ResCurrentSheetDto {
     pub id: String,
     pub asset_name: String,
-    pub balance: f64,
+    pub balance: DecimalWrapper,
     pub last_transaction_id: Option<String>,
     pub updated_at: String,
 }
@@ -23,3 +28,101 @@ pub struct ResListCurrentSheetDto {
     pub data: Vec<ResCurrentSheetDto>,
 }
 
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqRebuildCurrentSheetDto {
+    // rebuild every sheet of the user when no asset is given
+    #[validate(length(min = 1, message = "The asset_id must not be empty"))]
+    pub asset_id: Option<String>,
+    // defaults to true, nothing is written unless dry_run is explicitly false
+    pub dry_run: Option<bool>,
+}
+
+
+// one stored sheet compared with the balance replayed from the transaction history
+#[derive(Debug, Clone)]
+pub struct CurrentSheetReconcileDto {
+    pub current_sheet_id: Vec<u8>,
+    pub asset_id: Vec<u8>,
+    pub stored_balance: Decimal,
+    pub computed_balance: Decimal,
+    pub stored_last_transaction_id: Option<Vec<u8>>,
+    pub computed_last_transaction_id: Option<Vec<u8>>,
+}
+
+impl CurrentSheetReconcileDto {
+    pub fn is_consistent(&self) -> bool {
+        self.stored_balance == self.computed_balance
+            && self.stored_last_transaction_id == self.computed_last_transaction_id
+    }
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResCurrentSheetDiscrepancyDto {
+    pub current_sheet_id: String,
+    pub asset_id: String,
+    pub asset_name: String,
+    pub stored_balance: DecimalWrapper,
+    pub computed_balance: DecimalWrapper,
+    pub difference: DecimalWrapper,
+    pub stored_last_transaction_id: Option<String>,
+    pub computed_last_transaction_id: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResRebuildCurrentSheetDto {
+    pub dry_run: bool,
+    pub checked: i32,
+    pub discrepancy_count: i32,
+    pub fixed: i32,
+    pub discrepancies: Vec<ResCurrentSheetDiscrepancyDto>,
+}
+
+
+// signed change of one asset caused by a single transaction, positive when money comes in
+#[derive(Debug, Clone)]
+pub struct BalanceMovementDto {
+    pub occurred_at: DateTime<Utc>,
+    pub amount: Decimal,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResBalanceAsOfDto {
+    pub asset_id: String,
+    pub asset_name: String,
+    pub as_of: String,
+    pub balance: DecimalWrapper,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResBalancePointDto {
+    pub period_start: String,
+    pub period_end: String,
+    pub net_change: DecimalWrapper,
+    pub balance: DecimalWrapper,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResBalanceHistoryDto {
+    pub asset_id: String,
+    pub asset_name: String,
+    pub interval: BalanceIntervalVariant,
+    pub from: String,
+    pub to: String,
+    pub opening_balance: DecimalWrapper,
+    pub closing_balance: DecimalWrapper,
+    pub length: i32,
+    pub data: Vec<ResBalancePointDto>,
+}
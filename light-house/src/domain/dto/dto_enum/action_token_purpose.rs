@@ -0,0 +1,40 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// what a single use token lets its holder do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ActionTokenPurposeVariant {
+    VerifyEmail,
+    ResetPassword,
+    // handed out by a sign in with the right password, traded for a session with a second factor
+    TwoFactorChallenge,
+}
+
+impl ActionTokenPurposeVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActionTokenPurposeVariant::VerifyEmail => "verify_email",
+            ActionTokenPurposeVariant::ResetPassword => "reset_password",
+            ActionTokenPurposeVariant::TwoFactorChallenge => "two_factor_challenge",
+        }
+    }
+}
+
+impl FromStr for ActionTokenPurposeVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "verify_email" => Ok(ActionTokenPurposeVariant::VerifyEmail),
+            "reset_password" => Ok(ActionTokenPurposeVariant::ResetPassword),
+            "two_factor_challenge" => Ok(ActionTokenPurposeVariant::TwoFactorChallenge),
+            _ => Err(format!("Invalid action token purpose '{}'", value)),
+        }
+    }
+}
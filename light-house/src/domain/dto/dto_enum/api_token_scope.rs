@@ -0,0 +1,56 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// what an API token may do over MCP, the web API itself always takes the JWT
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, ToSchema)]
+pub enum ApiTokenScopeVariant {
+    // balances, transactions, contacts, expenses and operation status
+    #[serde(rename = "read")]
+    Read,
+    // propose payments, incomes and transfers
+    #[serde(rename = "write:transactions")]
+    WriteTransactions,
+    #[serde(rename = "write:contacts")]
+    WriteContacts,
+    #[serde(rename = "write:expenses")]
+    WriteExpenses,
+    #[serde(rename = "reports")]
+    Reports,
+}
+
+impl ApiTokenScopeVariant {
+    pub const ALL: [ApiTokenScopeVariant; 5] = [
+        ApiTokenScopeVariant::Read,
+        ApiTokenScopeVariant::WriteTransactions,
+        ApiTokenScopeVariant::WriteContacts,
+        ApiTokenScopeVariant::WriteExpenses,
+        ApiTokenScopeVariant::Reports,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiTokenScopeVariant::Read => "read",
+            ApiTokenScopeVariant::WriteTransactions => "write:transactions",
+            ApiTokenScopeVariant::WriteContacts => "write:contacts",
+            ApiTokenScopeVariant::WriteExpenses => "write:expenses",
+            ApiTokenScopeVariant::Reports => "reports",
+        }
+    }
+}
+
+impl FromStr for ApiTokenScopeVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.to_lowercase();
+        ApiTokenScopeVariant::ALL
+            .into_iter()
+            .find(|scope| scope.as_str() == value)
+            .ok_or_else(|| format!("Invalid scope '{}'", value))
+    }
+}
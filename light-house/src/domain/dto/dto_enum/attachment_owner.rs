@@ -0,0 +1,39 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// the kind of record an attachment belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttachmentOwnerVariant {
+    Transaction,
+    Contact,
+    Asset,
+}
+
+impl AttachmentOwnerVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttachmentOwnerVariant::Transaction => "transaction",
+            AttachmentOwnerVariant::Contact => "contact",
+            AttachmentOwnerVariant::Asset => "asset",
+        }
+    }
+}
+
+impl FromStr for AttachmentOwnerVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "transaction" => Ok(AttachmentOwnerVariant::Transaction),
+            "contact" => Ok(AttachmentOwnerVariant::Contact),
+            "asset" => Ok(AttachmentOwnerVariant::Asset),
+            _ => Err(format!("Invalid owner type '{}', expected transaction, contact or asset", value)),
+        }
+    }
+}
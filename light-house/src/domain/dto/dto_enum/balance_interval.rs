@@ -0,0 +1,38 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceIntervalVariant {
+    Day,
+    Week,
+    Month,
+}
+
+impl BalanceIntervalVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BalanceIntervalVariant::Day => "day",
+            BalanceIntervalVariant::Week => "week",
+            BalanceIntervalVariant::Month => "month",
+        }
+    }
+}
+
+impl FromStr for BalanceIntervalVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "day" => Ok(BalanceIntervalVariant::Day),
+            "week" => Ok(BalanceIntervalVariant::Week),
+            "month" => Ok(BalanceIntervalVariant::Month),
+            _ => Err(format!("Invalid interval '{}', expected day, week or month", value)),
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// monthly budgets repeat every calendar month (UTC) from start_date,
+// a custom budget is a single period from start_date to end_date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriodVariant {
+    Monthly,
+    Custom,
+}
+
+impl BudgetPeriodVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BudgetPeriodVariant::Monthly => "monthly",
+            BudgetPeriodVariant::Custom => "custom",
+        }
+    }
+}
+
+impl FromStr for BudgetPeriodVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "monthly" => Ok(BudgetPeriodVariant::Monthly),
+            "custom" => Ok(BudgetPeriodVariant::Custom),
+            _ => Err(format!("Invalid period_type '{}', expected monthly or custom", value)),
+        }
+    }
+}
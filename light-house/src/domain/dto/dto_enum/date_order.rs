@@ -0,0 +1,37 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// order of day, month and year in a date without named fields, such as 06/01/25 in a QIF file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DateOrderVariant {
+    // month/day/year, what US Quicken writes
+    Mdy,
+    Dmy,
+}
+
+impl DateOrderVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DateOrderVariant::Mdy => "mdy",
+            DateOrderVariant::Dmy => "dmy",
+        }
+    }
+}
+
+impl FromStr for DateOrderVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "mdy" => Ok(DateOrderVariant::Mdy),
+            "dmy" => Ok(DateOrderVariant::Dmy),
+            _ => Err(format!("Invalid date order '{}', expected mdy or dmy", value)),
+        }
+    }
+}
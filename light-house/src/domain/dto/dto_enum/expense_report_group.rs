@@ -0,0 +1,45 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpenseReportGroupVariant {
+    ExpenseType,
+    Expense,
+    Contact,
+    Asset,
+    // a payment with several tags counts in each of them
+    Tag,
+}
+
+impl ExpenseReportGroupVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpenseReportGroupVariant::ExpenseType => "expense_type",
+            ExpenseReportGroupVariant::Expense => "expense",
+            ExpenseReportGroupVariant::Contact => "contact",
+            ExpenseReportGroupVariant::Asset => "asset",
+            ExpenseReportGroupVariant::Tag => "tag",
+        }
+    }
+}
+
+impl FromStr for ExpenseReportGroupVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "expense_type" => Ok(ExpenseReportGroupVariant::ExpenseType),
+            "expense" => Ok(ExpenseReportGroupVariant::Expense),
+            "contact" => Ok(ExpenseReportGroupVariant::Contact),
+            "asset" => Ok(ExpenseReportGroupVariant::Asset),
+            "tag" => Ok(ExpenseReportGroupVariant::Tag),
+            _ => Err(format!("Invalid group_by '{}', expected expense_type, expense, contact, asset or tag", value)),
+        }
+    }
+}
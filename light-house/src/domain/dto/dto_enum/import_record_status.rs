@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// what the commit of an import did with one record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportRecordStatusVariant {
+    Created,
+    // matches a transaction already on the asset, left out
+    Duplicate,
+    Failed,
+}
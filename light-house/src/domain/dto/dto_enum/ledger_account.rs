@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// Account kinds a journal line can post against, stored as snake_case text in journal_line.account_type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+pub enum LedgerAccountVariant {
+    Asset,
+    Expense,
+    Income,
+    Contact,
+}
+
+impl LedgerAccountVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LedgerAccountVariant::Asset => "asset",
+            LedgerAccountVariant::Expense => "expense",
+            LedgerAccountVariant::Income => "income",
+            LedgerAccountVariant::Contact => "contact",
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use super::{api_token_scope::ApiTokenScopeVariant, transaction_type::TransactionTypeVariant};
+
+
+
+
+// writes an MCP client may propose, nothing runs until the user confirms it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum McpOperationVariant {
+    CreatePayment,
+    UpdatePayment,
+    DeletePayment,
+    CreateIncome,
+    UpdateIncome,
+    DeleteIncome,
+    CreateTransfer,
+    UpdateTransfer,
+    DeleteTransfer,
+    CreateContact,
+    UpdateContact,
+    DeleteContact,
+    CreateExpense,
+    UpdateExpense,
+    DeleteExpense,
+}
+
+impl McpOperationVariant {
+    pub const ALL: [McpOperationVariant; 15] = [
+        McpOperationVariant::CreatePayment,
+        McpOperationVariant::UpdatePayment,
+        McpOperationVariant::DeletePayment,
+        McpOperationVariant::CreateIncome,
+        McpOperationVariant::UpdateIncome,
+        McpOperationVariant::DeleteIncome,
+        McpOperationVariant::CreateTransfer,
+        McpOperationVariant::UpdateTransfer,
+        McpOperationVariant::DeleteTransfer,
+        McpOperationVariant::CreateContact,
+        McpOperationVariant::UpdateContact,
+        McpOperationVariant::DeleteContact,
+        McpOperationVariant::CreateExpense,
+        McpOperationVariant::UpdateExpense,
+        McpOperationVariant::DeleteExpense,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            McpOperationVariant::CreatePayment => "create_payment",
+            McpOperationVariant::UpdatePayment => "update_payment",
+            McpOperationVariant::DeletePayment => "delete_payment",
+            McpOperationVariant::CreateIncome => "create_income",
+            McpOperationVariant::UpdateIncome => "update_income",
+            McpOperationVariant::DeleteIncome => "delete_income",
+            McpOperationVariant::CreateTransfer => "create_transfer",
+            McpOperationVariant::UpdateTransfer => "update_transfer",
+            McpOperationVariant::DeleteTransfer => "delete_transfer",
+            McpOperationVariant::CreateContact => "create_contact",
+            McpOperationVariant::UpdateContact => "update_contact",
+            McpOperationVariant::DeleteContact => "delete_contact",
+            McpOperationVariant::CreateExpense => "create_expense",
+            McpOperationVariant::UpdateExpense => "update_expense",
+            McpOperationVariant::DeleteExpense => "delete_expense",
+        }
+    }
+
+    // updates and deletes name the record they change
+    pub fn requires_target(&self) -> bool {
+        !matches!(
+            self,
+            McpOperationVariant::CreatePayment
+                | McpOperationVariant::CreateIncome
+                | McpOperationVariant::CreateTransfer
+                | McpOperationVariant::CreateContact
+                | McpOperationVariant::CreateExpense
+        )
+    }
+
+    // the transaction kind for operations that move money, None for contacts and expenses
+    pub fn transaction_type(&self) -> Option<TransactionTypeVariant> {
+        match self {
+            McpOperationVariant::CreatePayment | McpOperationVariant::UpdatePayment | McpOperationVariant::DeletePayment => Some(TransactionTypeVariant::Payment),
+            McpOperationVariant::CreateIncome | McpOperationVariant::UpdateIncome | McpOperationVariant::DeleteIncome => Some(TransactionTypeVariant::Income),
+            McpOperationVariant::CreateTransfer | McpOperationVariant::UpdateTransfer | McpOperationVariant::DeleteTransfer => Some(TransactionTypeVariant::Transfer),
+            _ => None,
+        }
+    }
+
+    // scope the API token needs to propose the operation
+    pub fn required_scope(&self) -> ApiTokenScopeVariant {
+        match self {
+            McpOperationVariant::CreateContact | McpOperationVariant::UpdateContact | McpOperationVariant::DeleteContact => ApiTokenScopeVariant::WriteContacts,
+            McpOperationVariant::CreateExpense | McpOperationVariant::UpdateExpense | McpOperationVariant::DeleteExpense => ApiTokenScopeVariant::WriteExpenses,
+            _ => ApiTokenScopeVariant::WriteTransactions,
+        }
+    }
+}
+
+impl FromStr for McpOperationVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.to_lowercase();
+        McpOperationVariant::ALL
+            .into_iter()
+            .find(|operation| operation.as_str() == value)
+            .ok_or_else(|| format!("Invalid operation '{}'", value))
+    }
+}
+
+
+// expired is set lazily, when an operation past expires_at is read or confirmed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum McpOperationStatusVariant {
+    Pending,
+    Confirmed,
+    Rejected,
+    Expired,
+    Failed,
+}
+
+impl McpOperationStatusVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            McpOperationStatusVariant::Pending => "pending",
+            McpOperationStatusVariant::Confirmed => "confirmed",
+            McpOperationStatusVariant::Rejected => "rejected",
+            McpOperationStatusVariant::Expired => "expired",
+            McpOperationStatusVariant::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for McpOperationStatusVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "pending" => Ok(McpOperationStatusVariant::Pending),
+            "confirmed" => Ok(McpOperationStatusVariant::Confirmed),
+            "rejected" => Ok(McpOperationStatusVariant::Rejected),
+            "expired" => Ok(McpOperationStatusVariant::Expired),
+            "failed" => Ok(McpOperationStatusVariant::Failed),
+            _ => Err(format!("Invalid operation status '{}'", value)),
+        }
+    }
+}
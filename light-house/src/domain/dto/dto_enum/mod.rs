@@ -1,2 +1,17 @@
 pub mod transaction_type;
-pub mod gender_choice;
\ No newline at end of file
+pub mod gender_choice;
+pub mod ledger_account;
+pub mod balance_interval;
+pub mod transaction_sort;
+pub mod report_period;
+pub mod expense_report_group;
+pub mod budget_period_type;
+pub mod recurring_schedule_type;
+pub mod recurring_status;
+pub mod mcp_operation;
+pub mod api_token_scope;
+pub mod sign_in_failure;
+pub mod action_token_purpose;
+pub mod attachment_owner;
+pub mod import_record_status;
+pub mod date_order;
\ No newline at end of file
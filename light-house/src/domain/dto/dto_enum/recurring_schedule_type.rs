@@ -0,0 +1,48 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// every schedule is stored as an RRULE, the type only remembers how it was entered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurringScheduleVariant {
+    Daily,
+    Weekly,
+    MonthlyDay,
+    LastBusinessDay,
+    Rrule,
+}
+
+impl RecurringScheduleVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecurringScheduleVariant::Daily => "daily",
+            RecurringScheduleVariant::Weekly => "weekly",
+            RecurringScheduleVariant::MonthlyDay => "monthly_day",
+            RecurringScheduleVariant::LastBusinessDay => "last_business_day",
+            RecurringScheduleVariant::Rrule => "rrule",
+        }
+    }
+}
+
+impl FromStr for RecurringScheduleVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "daily" => Ok(RecurringScheduleVariant::Daily),
+            "weekly" => Ok(RecurringScheduleVariant::Weekly),
+            "monthly_day" => Ok(RecurringScheduleVariant::MonthlyDay),
+            "last_business_day" => Ok(RecurringScheduleVariant::LastBusinessDay),
+            "rrule" => Ok(RecurringScheduleVariant::Rrule),
+            _ => Err(format!(
+                "Invalid schedule_type '{}', expected daily, weekly, monthly_day, last_business_day or rrule",
+                value
+            )),
+        }
+    }
+}
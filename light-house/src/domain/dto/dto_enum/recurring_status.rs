@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// a finished schedule has no occurrence left (COUNT / UNTIL / end_at reached)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurringStatusVariant {
+    Active,
+    Paused,
+    Finished,
+}
+
+impl RecurringStatusVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecurringStatusVariant::Active => "active",
+            RecurringStatusVariant::Paused => "paused",
+            RecurringStatusVariant::Finished => "finished",
+        }
+    }
+}
+
+impl FromStr for RecurringStatusVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "active" => Ok(RecurringStatusVariant::Active),
+            "paused" => Ok(RecurringStatusVariant::Paused),
+            "finished" => Ok(RecurringStatusVariant::Finished),
+            _ => Err(format!("Invalid recurring status '{}'", value)),
+        }
+    }
+}
+
+
+// pending is the claim taken before posting, it turns into posted or failed once the use case returns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RecurringOccurrenceStatusVariant {
+    Pending,
+    Posted,
+    Skipped,
+    Failed,
+}
+
+impl RecurringOccurrenceStatusVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecurringOccurrenceStatusVariant::Pending => "pending",
+            RecurringOccurrenceStatusVariant::Posted => "posted",
+            RecurringOccurrenceStatusVariant::Skipped => "skipped",
+            RecurringOccurrenceStatusVariant::Failed => "failed",
+        }
+    }
+}
+
+impl FromStr for RecurringOccurrenceStatusVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "pending" => Ok(RecurringOccurrenceStatusVariant::Pending),
+            "posted" => Ok(RecurringOccurrenceStatusVariant::Posted),
+            "skipped" => Ok(RecurringOccurrenceStatusVariant::Skipped),
+            "failed" => Ok(RecurringOccurrenceStatusVariant::Failed),
+            _ => Err(format!("Invalid occurrence status '{}'", value)),
+        }
+    }
+}
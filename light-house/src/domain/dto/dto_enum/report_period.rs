@@ -0,0 +1,67 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Datelike, Duration, Months, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportPeriodVariant {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl ReportPeriodVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReportPeriodVariant::Day => "day",
+            ReportPeriodVariant::Week => "week",
+            ReportPeriodVariant::Month => "month",
+            ReportPeriodVariant::Year => "year",
+        }
+    }
+
+    // first instant of the day, ISO week (monday), month or year that contains `at`, in UTC
+    pub fn start_of(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let date = at.date_naive();
+        let date = match self {
+            ReportPeriodVariant::Day => date,
+            ReportPeriodVariant::Week => date - Duration::days(date.weekday().num_days_from_monday() as i64),
+            ReportPeriodVariant::Month => date.with_day(1).unwrap_or(date),
+            ReportPeriodVariant::Year => date.with_ordinal(1).unwrap_or(date),
+        };
+        date.and_time(NaiveTime::MIN).and_utc()
+    }
+
+    pub fn next_start(&self, start: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            ReportPeriodVariant::Day => start + Duration::days(1),
+            ReportPeriodVariant::Week => start + Duration::days(7),
+            ReportPeriodVariant::Month => start
+                .checked_add_months(Months::new(1))
+                .unwrap_or(start + Duration::days(31)),
+            ReportPeriodVariant::Year => start
+                .checked_add_months(Months::new(12))
+                .unwrap_or(start + Duration::days(366)),
+        }
+    }
+}
+
+impl FromStr for ReportPeriodVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "day" => Ok(ReportPeriodVariant::Day),
+            "week" => Ok(ReportPeriodVariant::Week),
+            "month" => Ok(ReportPeriodVariant::Month),
+            "year" => Ok(ReportPeriodVariant::Year),
+            _ => Err(format!("Invalid period '{}', expected day, week, month or year", value)),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+// why a sign in attempt was refused, stored with the attempt, never shown to the client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignInFailureVariant {
+    // unknown email or wrong password, told apart only in the audit record
+    UnknownEmail,
+    WrongPassword,
+    // refused before the password was checked
+    Throttled,
+    AccountDisabled,
+    // right password, wrong authenticator or recovery code
+    WrongTwoFactorCode,
+}
+
+impl SignInFailureVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignInFailureVariant::UnknownEmail => "unknown_email",
+            SignInFailureVariant::WrongPassword => "wrong_password",
+            SignInFailureVariant::Throttled => "throttled",
+            SignInFailureVariant::AccountDisabled => "account_disabled",
+            SignInFailureVariant::WrongTwoFactorCode => "wrong_two_factor_code",
+        }
+    }
+}
+
+impl FromStr for SignInFailureVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "unknown_email" => Ok(SignInFailureVariant::UnknownEmail),
+            "wrong_password" => Ok(SignInFailureVariant::WrongPassword),
+            "throttled" => Ok(SignInFailureVariant::Throttled),
+            "account_disabled" => Ok(SignInFailureVariant::AccountDisabled),
+            "wrong_two_factor_code" => Ok(SignInFailureVariant::WrongTwoFactorCode),
+            _ => Err(format!("Invalid sign in failure '{}'", value)),
+        }
+    }
+}
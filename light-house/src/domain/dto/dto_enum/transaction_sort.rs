@@ -0,0 +1,46 @@
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionSortVariant {
+    OccurredAt,
+    Amount,
+}
+
+impl FromStr for TransactionSortVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "occurred_at" | "date" => Ok(TransactionSortVariant::OccurredAt),
+            "amount" => Ok(TransactionSortVariant::Amount),
+            _ => Err(format!("Invalid sort_by '{}', expected occurred_at or amount", value)),
+        }
+    }
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrderVariant {
+    Asc,
+    Desc,
+}
+
+impl FromStr for SortOrderVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "asc" => Ok(SortOrderVariant::Asc),
+            "desc" => Ok(SortOrderVariant::Desc),
+            _ => Err(format!("Invalid order '{}', expected asc or desc", value)),
+        }
+    }
+}
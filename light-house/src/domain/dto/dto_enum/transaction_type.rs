@@ -1,9 +1,36 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
-#[derive(Deserialize, Serialize, ToSchema)]
+// matches the seeded transaction_type names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
 pub enum TransactionTypeVariant {
     Income,
     Payment,
     Transfer,
-}
\ No newline at end of file
+}
+
+impl TransactionTypeVariant {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionTypeVariant::Income => "income",
+            TransactionTypeVariant::Payment => "payment",
+            TransactionTypeVariant::Transfer => "transfer",
+        }
+    }
+}
+
+impl FromStr for TransactionTypeVariant {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_lowercase().as_str() {
+            "income" => Ok(TransactionTypeVariant::Income),
+            "payment" => Ok(TransactionTypeVariant::Payment),
+            "transfer" => Ok(TransactionTypeVariant::Transfer),
+            _ => Err(format!("Invalid transaction type '{}', expected income, payment or transfer", value)),
+        }
+    }
+}
@@ -0,0 +1,336 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::{domain::dto::dto_enum::{date_order::DateOrderVariant, import_record_status::ImportRecordStatusVariant, transaction_type::TransactionTypeVariant}, implentation::decimal_utoipa::DecimalWrapper};
+
+
+
+
+
+
+// one printable ascii character that is not a quote
+pub fn validate_delimiter(delimiter: &str) -> Result<(), ValidationError> {
+    let is_valid = delimiter.len() == 1
+        && delimiter.bytes().all(|byte| (byte == b'\t' || byte.is_ascii_graphic()) && byte != b'"');
+    if !is_valid {
+        return Err(ValidationError::new("invalid_delimiter"));
+    }
+    Ok(())
+}
+
+pub fn validate_decimal_separator(separator: &str) -> Result<(), ValidationError> {
+    if separator != "." && separator != "," {
+        return Err(ValidationError::new("invalid_decimal_separator"));
+    }
+    Ok(())
+}
+
+// a chrono format with at least one field and no unknown specifier
+pub fn validate_date_format(format: &str) -> Result<(), ValidationError> {
+    let mut items = chrono::format::StrftimeItems::new(format).peekable();
+    let has_field = items.peek().is_some();
+    if !has_field || items.any(|item| matches!(item, chrono::format::Item::Error)) || !format.contains('%') {
+        return Err(ValidationError::new("invalid_date_format"));
+    }
+    Ok(())
+}
+
+pub fn validate_encoding(label: &str) -> Result<(), ValidationError> {
+    if encoding_rs::Encoding::for_label(label.as_bytes()).is_none() {
+        return Err(ValidationError::new("invalid_encoding"));
+    }
+    Ok(())
+}
+
+
+// columns are header names when the file has a header line, 1-based positions otherwise
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqCreateImportProfileDto {
+    #[validate(length(min = 1, max = 50, message = "The name must be between 1 and 50 characters"))]
+    pub name: String,
+    // "," when left out
+    #[validate(custom(function = "validate_delimiter", message = "The delimiter must be one character such as , ; or a tab"))]
+    pub delimiter: Option<String>,
+    // true when left out
+    pub has_header: Option<bool>,
+    #[validate(range(min = 0, max = 100, message = "The skip_rows must be between 0 and 100"))]
+    pub skip_rows: Option<i32>,
+    #[validate(length(min = 1, max = 100, message = "The date_column must be between 1 and 100 characters"))]
+    pub date_column: String,
+    #[validate(custom(function = "validate_date_format", message = "The date_format must be a chrono format such as %d/%m/%Y"))]
+    pub date_format: String,
+    // signed amount, positive is income and negative is payment
+    #[validate(length(max = 100, message = "The amount_column must be at most 100 characters"))]
+    pub amount_column: Option<String>,
+    // money leaving the account, used when there is no amount_column
+    #[validate(length(max = 100, message = "The debit_column must be at most 100 characters"))]
+    pub debit_column: Option<String>,
+    // money entering the account, used when there is no amount_column
+    #[validate(length(max = 100, message = "The credit_column must be at most 100 characters"))]
+    pub credit_column: Option<String>,
+    #[validate(length(min = 1, max = 100, message = "The description_column must be between 1 and 100 characters"))]
+    pub description_column: String,
+    // "." when left out
+    #[validate(custom(function = "validate_decimal_separator", message = "The decimal_separator must be . or ,"))]
+    pub decimal_separator: Option<String>,
+    // "utf-8" when left out
+    #[validate(custom(function = "validate_encoding", message = "The encoding must be a label such as utf-8, windows-1252 or windows-874"))]
+    pub encoding: Option<String>,
+}
+
+
+// an empty amount_column, debit_column or credit_column removes it
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqUpdateImportProfileDto {
+    #[validate(length(min = 1, max = 50, message = "The name must be between 1 and 50 characters"))]
+    pub name: Option<String>,
+    #[validate(custom(function = "validate_delimiter", message = "The delimiter must be one character such as , ; or a tab"))]
+    pub delimiter: Option<String>,
+    pub has_header: Option<bool>,
+    #[validate(range(min = 0, max = 100, message = "The skip_rows must be between 0 and 100"))]
+    pub skip_rows: Option<i32>,
+    #[validate(length(min = 1, max = 100, message = "The date_column must be between 1 and 100 characters"))]
+    pub date_column: Option<String>,
+    #[validate(custom(function = "validate_date_format", message = "The date_format must be a chrono format such as %d/%m/%Y"))]
+    pub date_format: Option<String>,
+    #[validate(length(max = 100, message = "The amount_column must be at most 100 characters"))]
+    pub amount_column: Option<String>,
+    #[validate(length(max = 100, message = "The debit_column must be at most 100 characters"))]
+    pub debit_column: Option<String>,
+    #[validate(length(max = 100, message = "The credit_column must be at most 100 characters"))]
+    pub credit_column: Option<String>,
+    #[validate(length(min = 1, max = 100, message = "The description_column must be between 1 and 100 characters"))]
+    pub description_column: Option<String>,
+    #[validate(custom(function = "validate_decimal_separator", message = "The decimal_separator must be . or ,"))]
+    pub decimal_separator: Option<String>,
+    #[validate(custom(function = "validate_encoding", message = "The encoding must be a label such as utf-8, windows-1252 or windows-874"))]
+    pub encoding: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResEntryImportProfileDto {
+    pub id: String,
+    pub name: String,
+    pub delimiter: String,
+    pub has_header: bool,
+    pub skip_rows: i32,
+    pub date_column: String,
+    pub date_format: String,
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub description_column: String,
+    pub decimal_separator: String,
+    pub encoding: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListImportProfileDto {
+    pub length: i32,
+    pub data: Vec<ResEntryImportProfileDto>,
+}
+
+
+// multipart/form-data body of a csv import, only used for the api docs
+#[derive(Debug, ToSchema)]
+pub struct ReqImportCsvDto {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    pub profile_id: String,
+    pub asset_id: String,
+    pub contact_id: Option<String>,
+    pub expense_id: Option<String>,
+    pub timezone: Option<String>,
+}
+
+// multipart/form-data body of an OFX import, only used for the api docs
+#[derive(Debug, ToSchema)]
+pub struct ReqImportOfxDto {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    pub asset_id: String,
+    // ACCTID of the account to read, only needed when the file holds several
+    pub account_id: Option<String>,
+    pub contact_id: Option<String>,
+    pub expense_id: Option<String>,
+    pub timezone: Option<String>,
+}
+
+// multipart/form-data body of a QIF import, only used for the api docs
+#[derive(Debug, ToSchema)]
+pub struct ReqImportQifDto {
+    #[schema(value_type = String, format = Binary)]
+    pub file: Vec<u8>,
+    pub asset_id: String,
+    // name of the !Account block to read, only needed when the file holds several
+    pub account_id: Option<String>,
+    pub contact_id: Option<String>,
+    pub expense_id: Option<String>,
+    pub timezone: Option<String>,
+    // mdy when left out
+    pub date_order: Option<DateOrderVariant>,
+}
+
+
+// what every proposed record of a statement gets, the client may change each record before the commit
+#[derive(Debug, Clone, Validate)]
+pub struct ReqImportStatementDto {
+    #[validate(length(min = 1, message = "The asset_id must not be empty"))]
+    pub asset_id: String,
+    // the bank account to read from a file holding several, ignored for csv
+    pub account_id: Option<String>,
+    pub contact_id: Option<String>,
+    // only used for payments
+    pub expense_id: Option<String>,
+    // IANA name, the statement dates are read in it
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_timezone", message = "The timezone must be an IANA name such as Asia/Bangkok"))]
+    pub timezone: Option<String>,
+}
+
+
+// one line of a statement once parsed, before it becomes an income or a payment
+#[derive(Debug, Clone)]
+pub struct StatementLineDto {
+    // line number in the file, for error messages
+    pub line: i32,
+    pub occurred_on: NaiveDate,
+    // positive is money in, negative is money out
+    pub amount: Decimal,
+    pub description: String,
+    // the bank's id of the line (OFX FITID, QIF check number), None in csv
+    pub external_reference: Option<String>,
+}
+
+// the lines of one bank account in a statement file
+#[derive(Debug, Clone, Default)]
+pub struct ParsedStatementDto {
+    // ACCTID of an OFX statement, name of a QIF !Account block
+    pub account_id: Option<String>,
+    pub lines: Vec<StatementLineDto>,
+    // lines that could not be read, the rest of the file is still used
+    pub errors: Vec<ResImportLineErrorDto>,
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResImportRecordDto {
+    pub line: i32,
+    // income or payment
+    pub transaction_type: TransactionTypeVariant,
+    pub amount: DecimalWrapper,
+    // YYYY-MM-DD, read in the timezone of the import
+    pub occurred_at: String,
+    pub note: String,
+    pub contact_id: Option<String>,
+    pub expense_id: Option<String>,
+    pub external_reference: Option<String>,
+    // id of the transaction on the asset with the same external reference,
+    // or else with the same type, amount and date
+    pub duplicate_of: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResImportLineErrorDto {
+    pub line: i32,
+    pub message: String,
+}
+
+// nothing is written by a preview
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResImportPreviewDto {
+    pub asset_id: String,
+    // the bank account read from the file, None for csv
+    pub account_id: Option<String>,
+    pub timezone: Option<String>,
+    pub income_count: i32,
+    pub payment_count: i32,
+    pub duplicate_count: i32,
+    pub records: Vec<ResImportRecordDto>,
+    // lines that could not be read, they are not part of the records
+    pub errors: Vec<ResImportLineErrorDto>,
+}
+
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqImportRecordDto {
+    // line of the statement, echoed in the result
+    pub line: Option<i32>,
+    // income or payment
+    pub transaction_type: TransactionTypeVariant,
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: DecimalWrapper,
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The occurred_at must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub occurred_at: String,
+    #[validate(length(min = 1, message = "The note must not be empty"))]
+    pub note: String,
+    #[validate(length(min = 1, message = "The contact_id must not be empty"))]
+    pub contact_id: String,
+    // required for payments
+    pub expense_id: Option<String>,
+    pub tag_ids: Option<Vec<String>>,
+    // stored on the transaction so a later import of the same line is flagged
+    #[validate(length(max = 255, message = "The external_reference must be at most 255 characters"))]
+    pub external_reference: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqCommitImportDto {
+    #[validate(length(min = 1, message = "The asset_id must not be empty"))]
+    pub asset_id: String,
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_timezone", message = "The timezone must be an IANA name such as Asia/Bangkok"))]
+    pub timezone: Option<String>,
+    // true when left out, false posts records matching an existing transaction as well
+    pub skip_duplicates: Option<bool>,
+    #[validate(length(min = 1, max = 1000, message = "Between 1 and 1000 records can be committed at once"))]
+    #[validate(nested)]
+    pub records: Vec<ReqImportRecordDto>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResImportCommitResultDto {
+    // position in the committed records
+    pub index: i32,
+    pub line: Option<i32>,
+    pub status: ImportRecordStatusVariant,
+    pub transaction_id: Option<String>,
+    pub message: Option<String>,
+}
+
+// every record is posted on its own, a failed record does not undo the others
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResImportCommitDto {
+    pub created_count: i32,
+    pub duplicate_count: i32,
+    pub failed_count: i32,
+    pub results: Vec<ResImportCommitResultDto>,
+}
+
+
+// an income or payment already on the asset, what a proposed record is compared with
+#[derive(Debug, Clone)]
+pub struct ImportCandidateDto {
+    pub id: Uuid,
+    pub transaction_type: TransactionTypeVariant,
+    pub amount: Decimal,
+    pub occurred_at: DateTime<Utc>,
+    pub external_reference: Option<String>,
+}
@@ -0,0 +1,214 @@
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::{domain::{dto::dto_enum::ledger_account::LedgerAccountVariant, entities::{payment_split, transaction}}, soc::soc_repository::RepositoryError};
+
+
+
+
+// One side of a journal entry, exactly one of debit / credit is non zero
+#[derive(Debug, Clone)]
+pub struct ReqJournalLineDto {
+    pub account_type: LedgerAccountVariant,
+    pub account_id: Uuid,
+    pub contact_id: Option<Uuid>,
+    pub debit: Decimal,
+    pub credit: Decimal,
+}
+
+impl ReqJournalLineDto {
+    pub fn debit(account_type: LedgerAccountVariant, account_id: Uuid, contact_id: Option<Uuid>, amount: Decimal) -> Self {
+        Self { account_type, account_id, contact_id, debit: amount, credit: Decimal::ZERO }
+    }
+
+    pub fn credit(account_type: LedgerAccountVariant, account_id: Uuid, contact_id: Option<Uuid>, amount: Decimal) -> Self {
+        Self { account_type, account_id, contact_id, debit: Decimal::ZERO, credit: amount }
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ReqPostJournalEntryDto {
+    pub transaction_id: Option<Uuid>,
+    // journal_entry.memo is a VARCHAR(255), it names the transaction and leaves the note on the row
+    pub memo: String,
+    pub lines: Vec<ReqJournalLineDto>,
+}
+
+impl ReqPostJournalEntryDto {
+    // income: debit the receiving asset, credit the income source (the paying contact)
+    pub fn for_income(record: &transaction::Model) -> Result<Self, RepositoryError> {
+        let asset_id = uuid_from_bytes(&record.asset_id, "asset_id")?;
+        let contact_id = required_uuid(&record.contact_id, "contact_id")?;
+        let transaction_id = uuid_from_bytes(&record.id, "transaction id")?;
+        Ok(Self {
+            transaction_id: Some(transaction_id),
+            memo: format!("income {}", transaction_id),
+            lines: vec![
+                ReqJournalLineDto::debit(LedgerAccountVariant::Asset, asset_id, Some(contact_id), record.amount),
+                ReqJournalLineDto::credit(LedgerAccountVariant::Income, contact_id, Some(contact_id), record.amount),
+            ],
+        })
+    }
+
+    // payment: debit the expense category, or every split line's expense, credit the paying asset
+    pub fn for_payment(record: &transaction::Model, splits: &[payment_split::Model]) -> Result<Self, RepositoryError> {
+        let asset_id = uuid_from_bytes(&record.asset_id, "asset_id")?;
+        let contact_id = optional_uuid(&record.contact_id, "contact_id")?;
+        let transaction_id = uuid_from_bytes(&record.id, "transaction id")?;
+        let mut lines = if splits.is_empty() {
+            let expense_id = required_uuid(&record.expense_id, "expense_id")?;
+            vec![ReqJournalLineDto::debit(LedgerAccountVariant::Expense, expense_id, contact_id, record.amount)]
+        } else {
+            splits
+                .iter()
+                .map(|split| {
+                    let expense_id = uuid_from_bytes(&split.expense_id, "split expense_id")?;
+                    Ok(ReqJournalLineDto::debit(LedgerAccountVariant::Expense, expense_id, contact_id, split.amount))
+                })
+                .collect::<Result<Vec<_>, RepositoryError>>()?
+        };
+        lines.push(ReqJournalLineDto::credit(LedgerAccountVariant::Asset, asset_id, contact_id, record.amount));
+        Ok(Self {
+            transaction_id: Some(transaction_id),
+            memo: format!("payment {}", transaction_id),
+            lines,
+        })
+    }
+
+    // transfer: debit the destination asset, credit the source asset
+    pub fn for_transfer(record: &transaction::Model) -> Result<Self, RepositoryError> {
+        let source_asset_id = uuid_from_bytes(&record.asset_id, "asset_id")?;
+        let destination_asset_id = required_uuid(&record.destination_asset_id, "destination_asset_id")?;
+        let contact_id = optional_uuid(&record.contact_id, "contact_id")?;
+        let transaction_id = uuid_from_bytes(&record.id, "transaction id")?;
+        Ok(Self {
+            transaction_id: Some(transaction_id),
+            memo: format!("transfer {}", transaction_id),
+            lines: vec![
+                ReqJournalLineDto::debit(LedgerAccountVariant::Asset, destination_asset_id, contact_id, record.amount),
+                ReqJournalLineDto::credit(LedgerAccountVariant::Asset, source_asset_id, contact_id, record.amount),
+            ],
+        })
+    }
+
+    // Mirror image of this entry, used to back out a transaction before it is changed or deleted
+    pub fn reversal(&self) -> Self {
+        Self {
+            transaction_id: self.transaction_id,
+            memo: format!("reversal of {}", self.memo),
+            lines: self
+                .lines
+                .iter()
+                .map(|line| ReqJournalLineDto {
+                    account_type: line.account_type,
+                    account_id: line.account_id,
+                    contact_id: line.contact_id,
+                    debit: line.credit,
+                    credit: line.debit,
+                })
+                .collect(),
+        }
+    }
+}
+
+
+fn uuid_from_bytes(bytes: &[u8], field: &str) -> Result<Uuid, RepositoryError> {
+    Uuid::from_slice(bytes)
+        .map_err(|err| RepositoryError::InvalidInput(format!("Invalid {}: {}", field, err)))
+}
+
+fn optional_uuid(bytes: &Option<Vec<u8>>, field: &str) -> Result<Option<Uuid>, RepositoryError> {
+    match bytes {
+        Some(bytes) => Ok(Some(uuid_from_bytes(bytes, field)?)),
+        None => Ok(None),
+    }
+}
+
+fn required_uuid(bytes: &Option<Vec<u8>>, field: &str) -> Result<Uuid, RepositoryError> {
+    optional_uuid(bytes, field)?
+        .ok_or_else(|| RepositoryError::InvalidInput(format!("Missing {} for journal entry", field)))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    // journal_entry.memo is created as string(), a VARCHAR(255)
+    const MAX_MEMO_CHARS: usize = 255;
+
+    fn record_with_longest_note() -> transaction::Model {
+        transaction::Model {
+            id: Uuid::new_v4().as_bytes().to_vec(),
+            transaction_type_id: Uuid::new_v4().as_bytes().to_vec(),
+            amount: Decimal::new(1250, 2),
+            asset_id: Uuid::new_v4().as_bytes().to_vec(),
+            destination_asset_id: Some(Uuid::new_v4().as_bytes().to_vec()),
+            expense_id: Some(Uuid::new_v4().as_bytes().to_vec()),
+            contact_id: Some(Uuid::new_v4().as_bytes().to_vec()),
+            note: "n".repeat(255),
+            created_at: None,
+            updated_at: None,
+            user_id: Uuid::new_v4().as_bytes().to_vec(),
+            occurred_at: Utc::now(),
+            external_reference: None,
+        }
+    }
+
+    fn split_of(record: &transaction::Model, amount: Decimal) -> payment_split::Model {
+        payment_split::Model {
+            id: Uuid::new_v4().as_bytes().to_vec(),
+            transaction_id: record.id.clone(),
+            expense_id: Uuid::new_v4().as_bytes().to_vec(),
+            amount,
+            note: Some("s".repeat(255)),
+            position: 0,
+            created_at: None,
+        }
+    }
+
+    // create posts the entry, update posts the reversal of the old one then the new one,
+    // delete posts the reversal: every memo along the way must fit the column
+    fn assert_memos_fit(entry: &ReqPostJournalEntryDto) {
+        assert!(entry.memo.chars().count() <= MAX_MEMO_CHARS, "memo too long: {}", entry.memo);
+        let reversal = entry.reversal();
+        assert!(reversal.memo.chars().count() <= MAX_MEMO_CHARS, "reversal memo too long: {}", reversal.memo);
+    }
+
+    #[test]
+    fn income_memo_fits_with_longest_note() {
+        let record = record_with_longest_note();
+        assert_memos_fit(&ReqPostJournalEntryDto::for_income(&record).unwrap());
+    }
+
+    #[test]
+    fn payment_memo_fits_with_longest_note() {
+        let record = record_with_longest_note();
+        assert_memos_fit(&ReqPostJournalEntryDto::for_payment(&record, &[]).unwrap());
+
+        let splits = [split_of(&record, Decimal::new(1000, 2)), split_of(&record, Decimal::new(250, 2))];
+        assert_memos_fit(&ReqPostJournalEntryDto::for_payment(&record, &splits).unwrap());
+    }
+
+    #[test]
+    fn transfer_memo_fits_with_longest_note() {
+        let record = record_with_longest_note();
+        assert_memos_fit(&ReqPostJournalEntryDto::for_transfer(&record).unwrap());
+    }
+
+    #[test]
+    fn reversal_swaps_debit_and_credit() {
+        let record = record_with_longest_note();
+        let entry = ReqPostJournalEntryDto::for_income(&record).unwrap();
+        let reversal = entry.reversal();
+
+        assert_eq!(reversal.transaction_id, entry.transaction_id);
+        for (line, reversed) in entry.lines.iter().zip(&reversal.lines) {
+            assert_eq!(reversed.debit, line.credit);
+            assert_eq!(reversed.credit, line.debit);
+        }
+    }
+}
\ No newline at end of file
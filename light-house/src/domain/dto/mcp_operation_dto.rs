@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{domain::dto::dto_enum::{mcp_operation::{McpOperationStatusVariant, McpOperationVariant}, transaction_type::TransactionTypeVariant}, implentation::decimal_utoipa::DecimalWrapper};
+
+
+
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqMcpProposeOperationDto {
+    pub operation: McpOperationVariant,
+    // the transaction, contact or expense an update or delete changes
+    pub target_id: Option<String>,
+    // body of the matching create or update request, creates of income, payment and transfer
+    // may leave out transaction_type_id and occurred_at, deletes take no payload
+    #[schema(value_type = Option<Object>)]
+    pub payload: Option<Value>,
+}
+
+
+pub struct CreateMcpOperationDto {
+    pub operation: McpOperationVariant,
+    pub target_id: Option<Uuid>,
+    pub target_updated_at: Option<DateTime<Utc>>,
+    pub payload: Option<Value>,
+    pub balance_impact: Value,
+    pub expires_at: DateTime<Utc>,
+}
+
+
+// the transaction an update or delete proposal changes, as it is when proposed
+pub struct McpTransactionSnapshotDto {
+    pub transaction_type: TransactionTypeVariant,
+    pub amount: Decimal,
+    pub asset_id: Uuid,
+    pub destination_asset_id: Option<Uuid>,
+}
+
+
+pub struct McpAssetBalanceDto {
+    pub asset_id: Uuid,
+    pub asset_name: String,
+    // zero for an asset with no current sheet yet
+    pub balance: Decimal,
+}
+
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResMcpBalanceImpactDto {
+    pub asset_id: String,
+    pub asset_name: String,
+    // balance when the operation was proposed
+    pub current_balance: DecimalWrapper,
+    pub change: DecimalWrapper,
+    pub balance_after: DecimalWrapper,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResMcpPendingOperationDto {
+    pub id: String,
+    pub operation: McpOperationVariant,
+    pub target_id: Option<String>,
+    #[schema(value_type = Option<Object>)]
+    pub payload: Option<Value>,
+    // empty for contacts, expenses and changes that leave every balance as it is
+    pub balance_impact: Vec<ResMcpBalanceImpactDto>,
+    pub status: McpOperationStatusVariant,
+    // the created or updated record once confirmed
+    #[schema(value_type = Option<Object>)]
+    pub result: Option<Value>,
+    pub message: Option<String>,
+    pub expires_at: String,
+    pub decided_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListMcpPendingOperationDto {
+    pub length: i32,
+    pub data: Vec<ResMcpPendingOperationDto>,
+}
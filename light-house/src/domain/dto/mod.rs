@@ -10,3 +10,20 @@ pub mod transaction_dto;
 pub mod transaction_type_dto;
 pub mod current_sheet_dto;
 pub mod gender_dto;
+pub mod ledger_dto;
+pub mod pagination_dto;
+
+pub mod report_dto;
+pub mod budget_dto;
+pub mod recurring_transaction_dto;
+pub mod mcp_operation_dto;
+pub mod api_token_dto;
+pub mod session_dto;
+pub mod admin_dto;
+pub mod sign_in_attempt_dto;
+pub mod account_dto;
+pub mod two_factor_dto;
+pub mod user_data_dto;
+pub mod tag_dto;
+pub mod attachment_dto;
+pub mod import_dto;
\ No newline at end of file
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResPageMetaDto {
+    // number of records matching the filters, across all pages
+    pub total: u64,
+    // None when the page was requested by cursor
+    pub page: Option<u64>,
+    pub per_page: u64,
+    pub total_pages: u64,
+    pub has_more: bool,
+    // pass back as ?cursor= to continue after the last record of this page
+    pub next_cursor: Option<String>,
+}
+
+impl ResPageMetaDto {
+    // the whole result is returned at once
+    pub fn single_page(total: u64) -> Self {
+        Self {
+            total,
+            page: Some(1),
+            per_page: total,
+            total_pages: if total == 0 { 0 } else { 1 },
+            has_more: false,
+            next_cursor: None,
+        }
+    }
+
+    pub fn new(total: u64, page: Option<u64>, per_page: u64, has_more: bool, next_cursor: Option<String>) -> Self {
+        Self {
+            total,
+            page,
+            per_page,
+            total_pages: if per_page == 0 { 0 } else { total.div_ceil(per_page) },
+            has_more,
+            next_cursor,
+        }
+    }
+}
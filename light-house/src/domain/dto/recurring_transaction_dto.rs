@@ -0,0 +1,312 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{domain::dto::dto_enum::{recurring_schedule_type::RecurringScheduleVariant, recurring_status::{RecurringOccurrenceStatusVariant, RecurringStatusVariant}, transaction_type::TransactionTypeVariant}, implentation::{date_time_utill::{parse_occurred_at, parse_occurred_until, parse_timezone}, decimal_utoipa::DecimalWrapper, recurrence_rule::{parse_weekday, RecurrenceRule}}};
+
+
+
+
+// external_reference of the transaction posted for an occurrence, the unique index on
+// (user_id, asset_id, external_reference) lets an occurrence be posted once whatever happens to the run
+pub const RECURRING_REFERENCE_PREFIX: &str = "recurring:";
+
+pub fn occurrence_reference(occurrence_id: Uuid) -> String {
+    format!("{}{}", RECURRING_REFERENCE_PREFIX, occurrence_id)
+}
+
+
+fn parse_uuid(value: &str, field: &str) -> Result<Uuid, String> {
+    Uuid::parse_str(value).map_err(|_| format!("Invalid {}", field))
+}
+
+fn parse_optional_uuid(value: Option<&str>, field: &str) -> Result<Option<Uuid>, String> {
+    value.map(|value| parse_uuid(value, field)).transpose()
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqCreateRecurringTransactionDto {
+    #[validate(length(min = 1, message = "The name must not be empty"))]
+    pub name: String,
+    pub transaction_type: TransactionTypeVariant,
+    // template of the transaction posted on every occurrence
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: DecimalWrapper,
+    #[validate(length(min = 1, message = "The asset_id must not be empty"))]
+    pub asset_id: String,
+    // transfer only
+    pub destination_asset_id: Option<String>,
+    // payment only
+    pub expense_id: Option<String>,
+    #[validate(length(min = 1, message = "The contact_id must not be empty"))]
+    pub contact_id: String,
+    #[validate(length(min = 1, message = "The note must not be empty"))]
+    pub note: String,
+    pub schedule_type: RecurringScheduleVariant,
+    // repeat every n days / weeks / months, defaults to 1
+    #[validate(range(min = 1, max = 366, message = "The interval must be between 1 and 366"))]
+    pub interval: Option<u32>,
+    // weekly, such as ["MO", "TH"], defaults to the weekday of start_at
+    pub weekdays: Option<Vec<String>>,
+    // monthly_day, 1..31 or -1 for the last day of the month, months without the day are skipped
+    #[validate(range(min = -31, max = 31, message = "The day_of_month must be between -31 and 31"))]
+    pub day_of_month: Option<i32>,
+    // rrule, such as "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=1"
+    pub rrule: Option<String>,
+    // first occurrence, the local time of day is kept on every occurrence
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The start_at must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub start_at: String,
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The end_at must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub end_at: Option<String>,
+    // IANA name such as "Asia/Bangkok", the schedule runs in this timezone, defaults to UTC
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_timezone", message = "The timezone must be an IANA name such as Asia/Bangkok"))]
+    pub timezone: Option<String>,
+}
+
+impl ReqCreateRecurringTransactionDto {
+    // Parses the fields and builds the RRULE, the error message is returned to the client as is
+    pub fn into_create(self) -> Result<CreateRecurringTransactionDto, String> {
+        let timezone = self.timezone.unwrap_or_else(|| "UTC".to_string());
+        parse_timezone(&timezone)?;
+
+        // Step 1: The template fields the transaction type needs
+        let asset_id = parse_uuid(&self.asset_id, "asset_id")?;
+        let contact_id = parse_uuid(&self.contact_id, "contact_id")?;
+        let mut destination_asset_id = parse_optional_uuid(self.destination_asset_id.as_deref(), "destination_asset_id")?;
+        let mut expense_id = parse_optional_uuid(self.expense_id.as_deref(), "expense_id")?;
+        match self.transaction_type {
+            TransactionTypeVariant::Income => {
+                destination_asset_id = None;
+                expense_id = None;
+            }
+            TransactionTypeVariant::Payment => {
+                if expense_id.is_none() {
+                    return Err("A recurring payment needs an expense_id".to_string());
+                }
+                destination_asset_id = None;
+            }
+            TransactionTypeVariant::Transfer => {
+                match destination_asset_id {
+                    None => return Err("A recurring transfer needs a destination_asset_id".to_string()),
+                    Some(destination) if destination == asset_id => {
+                        return Err("destination_asset_id must differ from asset_id".to_string());
+                    }
+                    Some(_) => {}
+                }
+                expense_id = None;
+            }
+        }
+
+        // Step 2: Dates, TIMESTAMP columns keep whole seconds and occurrences are matched on them
+        let start_at = parse_occurred_at(&self.start_at, Some(&timezone))?.trunc_subsecs(0);
+        let end_at = self
+            .end_at
+            .as_deref()
+            .map(|end_at| parse_occurred_until(end_at, Some(&timezone)).map(|end_at| end_at.trunc_subsecs(0)))
+            .transpose()?;
+        if let Some(end_at) = end_at
+            && end_at < start_at
+        {
+            return Err("end_at must not be before start_at".to_string());
+        }
+
+        // Step 3: Every schedule type is stored as an RRULE
+        let interval = self.interval.unwrap_or(1);
+        let rule = match self.schedule_type {
+            RecurringScheduleVariant::Daily => RecurrenceRule::daily(interval),
+            RecurringScheduleVariant::Weekly => {
+                let weekdays = self
+                    .weekdays
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|weekday| parse_weekday(weekday))
+                    .collect::<Result<Vec<_>, _>>()?;
+                RecurrenceRule::weekly(interval, weekdays)
+            }
+            RecurringScheduleVariant::MonthlyDay => match self.day_of_month {
+                Some(0) | None => return Err("A monthly_day schedule needs a day_of_month between 1 and 31 or -1".to_string()),
+                Some(day) => RecurrenceRule::monthly_on_day(interval, day),
+            },
+            RecurringScheduleVariant::LastBusinessDay => RecurrenceRule::last_business_day(interval),
+            RecurringScheduleVariant::Rrule => match self.rrule.as_deref() {
+                Some(rrule) => RecurrenceRule::from_str(rrule)?,
+                None => return Err("An rrule schedule needs an rrule".to_string()),
+            },
+        };
+
+        Ok(CreateRecurringTransactionDto {
+            name: self.name,
+            transaction_type: self.transaction_type,
+            amount: self.amount.0,
+            asset_id,
+            destination_asset_id,
+            expense_id,
+            contact_id,
+            note: self.note,
+            schedule_type: self.schedule_type,
+            rule,
+            timezone,
+            start_at,
+            end_at,
+        })
+    }
+}
+
+
+// the schedule itself cannot be changed, create a new recurring transaction instead
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqUpdateRecurringTransactionDto {
+    #[validate(length(min = 1, message = "The name must not be empty"))]
+    pub name: Option<String>,
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: Option<DecimalWrapper>,
+    pub asset_id: Option<String>,
+    pub destination_asset_id: Option<String>,
+    pub expense_id: Option<String>,
+    pub contact_id: Option<String>,
+    #[validate(length(min = 1, message = "The note must not be empty"))]
+    pub note: Option<String>,
+    // read in the schedule's timezone
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The end_at must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub end_at: Option<String>,
+}
+
+impl ReqUpdateRecurringTransactionDto {
+    pub fn into_update(self, timezone: &str) -> Result<UpdateRecurringTransactionDto, String> {
+        Ok(UpdateRecurringTransactionDto {
+            name: self.name,
+            amount: self.amount.map(|amount| amount.0),
+            asset_id: parse_optional_uuid(self.asset_id.as_deref(), "asset_id")?,
+            destination_asset_id: parse_optional_uuid(self.destination_asset_id.as_deref(), "destination_asset_id")?,
+            expense_id: parse_optional_uuid(self.expense_id.as_deref(), "expense_id")?,
+            contact_id: parse_optional_uuid(self.contact_id.as_deref(), "contact_id")?,
+            note: self.note,
+            end_at: self
+                .end_at
+                .as_deref()
+                .map(|end_at| parse_occurred_until(end_at, Some(timezone)).map(|end_at| end_at.trunc_subsecs(0)))
+                .transpose()?,
+        })
+    }
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate, Default)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqSkipRecurringOccurrenceDto {
+    // YYYY-MM-DD (in the schedule's timezone) or the exact RFC 3339 timestamp, defaults to the next occurrence
+    pub occurrence: Option<String>,
+}
+
+
+
+// parsed form of ReqCreateRecurringTransactionDto
+#[derive(Debug, Clone)]
+pub struct CreateRecurringTransactionDto {
+    pub name: String,
+    pub transaction_type: TransactionTypeVariant,
+    pub amount: Decimal,
+    pub asset_id: Uuid,
+    pub destination_asset_id: Option<Uuid>,
+    pub expense_id: Option<Uuid>,
+    pub contact_id: Uuid,
+    pub note: String,
+    pub schedule_type: RecurringScheduleVariant,
+    pub rule: RecurrenceRule,
+    pub timezone: String,
+    pub start_at: DateTime<Utc>,
+    pub end_at: Option<DateTime<Utc>>,
+}
+
+
+#[derive(Debug, Clone, Default)]
+pub struct UpdateRecurringTransactionDto {
+    pub name: Option<String>,
+    pub amount: Option<Decimal>,
+    pub asset_id: Option<Uuid>,
+    pub destination_asset_id: Option<Uuid>,
+    pub expense_id: Option<Uuid>,
+    pub contact_id: Option<Uuid>,
+    pub note: Option<String>,
+    pub end_at: Option<DateTime<Utc>>,
+}
+
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResEntryRecurringTransactionDto {
+    pub id: String,
+    pub name: String,
+    pub transaction_type: TransactionTypeVariant,
+    pub amount: DecimalWrapper,
+    pub asset_id: String,
+    pub destination_asset_id: Option<String>,
+    pub expense_id: Option<String>,
+    pub contact_id: String,
+    pub note: String,
+    pub schedule_type: RecurringScheduleVariant,
+    pub rrule: String,
+    pub timezone: String,
+    pub start_at: String,
+    pub end_at: Option<String>,
+    pub status: RecurringStatusVariant,
+    pub next_run_at: Option<String>,
+    pub last_run_at: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListRecurringTransactionDto {
+    pub length: i32,
+    pub data: Vec<ResEntryRecurringTransactionDto>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResRecurringOccurrenceDto {
+    pub scheduled_for: String,
+    pub status: RecurringOccurrenceStatusVariant,
+    pub transaction_id: Option<String>,
+    pub message: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListRecurringOccurrenceDto {
+    pub length: i32,
+    pub data: Vec<ResRecurringOccurrenceDto>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResRecurringPreviewEntryDto {
+    pub scheduled_for: String,
+    // local date in the schedule's timezone
+    pub local_date: String,
+    pub skipped: bool,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResRecurringPreviewDto {
+    pub recurring_transaction_id: String,
+    pub status: RecurringStatusVariant,
+    pub length: i32,
+    pub data: Vec<ResRecurringPreviewEntryDto>,
+}
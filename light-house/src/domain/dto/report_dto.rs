@@ -0,0 +1,121 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{domain::dto::dto_enum::{expense_report_group::ExpenseReportGroupVariant, report_period::ReportPeriodVariant}, implentation::decimal_utoipa::DecimalWrapper};
+
+
+
+
+
+// one row of SUM/COUNT grouped by period and transaction type
+#[derive(Debug, Clone)]
+pub struct ReportPeriodTotalDto {
+    pub period_start: NaiveDate,
+    pub transaction_type_name: String,
+    pub total: Decimal,
+    pub count: i64,
+}
+
+
+// one row of SUM/COUNT grouped by period and a referenced record (expense type, contact, ...),
+// group_id is None for transactions that do not reference one
+#[derive(Debug, Clone)]
+pub struct ReportGroupTotalDto {
+    pub period_start: NaiveDate,
+    pub group_id: Option<Vec<u8>>,
+    pub group_name: Option<String>,
+    pub total: Decimal,
+    pub count: i64,
+}
+
+
+// SUM/COUNT of payments grouped by the record picked with ExpenseReportGroupVariant
+#[derive(Debug, Clone)]
+pub struct ExpenseGroupTotalDto {
+    pub group_id: Option<Vec<u8>>,
+    pub group_name: Option<String>,
+    pub total: Decimal,
+    pub count: i64,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResReportBreakdownDto {
+    pub id: Option<String>,
+    pub name: String,
+    pub total: DecimalWrapper,
+    pub count: i64,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResReportPeriodDto {
+    pub period_start: String,
+    pub period_end: String,
+    pub total_income: DecimalWrapper,
+    pub total_payment: DecimalWrapper,
+    // total_income - total_payment, transfers only move money between assets
+    pub net_cash_flow: DecimalWrapper,
+    pub transfer_volume: DecimalWrapper,
+    pub income_count: i64,
+    pub payment_count: i64,
+    pub transfer_count: i64,
+    pub payment_by_expense_type: Vec<ResReportBreakdownDto>,
+    pub income_by_contact: Vec<ResReportBreakdownDto>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResReportSummaryDto {
+    pub period: ReportPeriodVariant,
+    pub from: String,
+    pub to: String,
+    pub total_income: DecimalWrapper,
+    pub total_payment: DecimalWrapper,
+    pub net_cash_flow: DecimalWrapper,
+    pub transfer_volume: DecimalWrapper,
+    pub payment_by_expense_type: Vec<ResReportBreakdownDto>,
+    pub income_by_contact: Vec<ResReportBreakdownDto>,
+    pub length: i32,
+    pub data: Vec<ResReportPeriodDto>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResExpenseReportGroupDto {
+    pub id: Option<String>,
+    pub name: String,
+    pub total: DecimalWrapper,
+    pub count: i64,
+    // percent of the total payments of the range, 2 decimal places
+    pub share_percent: DecimalWrapper,
+    // same group over the range of equal length right before `from`
+    pub previous_total: DecimalWrapper,
+    pub change_amount: DecimalWrapper,
+    // None when nothing was paid in the previous range
+    pub change_percent: Option<DecimalWrapper>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResExpenseReportDto {
+    pub group_by: ExpenseReportGroupVariant,
+    pub from: String,
+    pub to: String,
+    pub previous_from: String,
+    pub previous_to: String,
+    pub total: DecimalWrapper,
+    pub count: i64,
+    pub previous_total: DecimalWrapper,
+    pub change_amount: DecimalWrapper,
+    pub change_percent: Option<DecimalWrapper>,
+    pub length: i32,
+    pub data: Vec<ResExpenseReportGroupDto>,
+}
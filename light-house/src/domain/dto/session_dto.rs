@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+
+
+
+
+// where a sign in or a refresh came from
+#[derive(Debug, Clone, Default)]
+pub struct SessionClientDto {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+
+pub struct CreateSessionDto {
+    pub refresh_token_hash: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+
+pub struct RotateSessionDto {
+    pub from_token_hash: String,
+    pub to_token_hash: String,
+    pub expires_at: DateTime<Utc>,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqRefreshTokenDto {
+    #[validate(length(min = 1, message = "The refresh_token must not be empty"))]
+    pub refresh_token: String,
+}
+
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResSessionDto {
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    // the session the request was made with
+    pub is_current: bool,
+    pub last_used_at: Option<String>,
+    pub expires_at: String,
+    pub created_at: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListSessionDto {
+    pub length: i32,
+    pub data: Vec<ResSessionDto>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResRevokeSessionDto {
+    // how many sessions were signed out
+    pub revoked: u64,
+}
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::dto::dto_enum::sign_in_failure::SignInFailureVariant;
+
+
+
+
+// audit record of one sign in attempt
+#[derive(Debug, Clone)]
+pub struct CreateSignInAttemptDto {
+    pub email: String,
+    pub user_id: Option<Uuid>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    // None when the attempt succeeded
+    pub failure: Option<SignInFailureVariant>,
+}
+
+
+// failed attempts counted by a sign in limiter for one account or one ip
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignInThrottleDto {
+    pub failure_count: u32,
+    pub last_failure_at: DateTime<Utc>,
+    // further attempts are refused until then, without checking the password
+    pub blocked_until: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::domain::entities::tag;
+
+
+
+
+
+// "#" followed by six hex digits, an empty string clears the color on update
+pub fn validate_tag_color(color: &str) -> Result<(), ValidationError> {
+    if color.is_empty() {
+        return Ok(());
+    }
+    let is_hex_color = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|ch| ch.is_ascii_hexdigit());
+    if !is_hex_color {
+        return Err(ValidationError::new("invalid_tag_color"));
+    }
+    Ok(())
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqCreateTagDto {
+    #[validate(length(min = 1, max = 50, message = "The name must be between 1 and 50 characters"))]
+    pub name: String,
+    #[validate(custom(function = "validate_tag_color", message = "The color must look like #1e90ff"))]
+    pub color: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqUpdateTagDto {
+    #[validate(length(min = 1, max = 50, message = "The name must be between 1 and 50 characters"))]
+    pub name: Option<String>,
+    #[validate(custom(function = "validate_tag_color", message = "The color must look like #1e90ff"))]
+    pub color: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResEntryTagDto {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    // transactions carrying the tag
+    pub transaction_count: i64,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListTagDto {
+    pub length: i32,
+    pub data: Vec<ResEntryTagDto>,
+}
+
+
+// tag as it is shown on a transaction
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResTransactionTagDto {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+}
+
+impl ResTransactionTagDto {
+    pub fn from_tags(tags: &[tag::Model]) -> Result<Vec<Self>, String> {
+        tags.iter()
+            .map(|tag| {
+                Ok(Self {
+                    id: Uuid::from_slice(&tag.id).map_err(|err| err.to_string())?.to_string(),
+                    name: tag.name.clone(),
+                    color: tag.color.clone(),
+                })
+            })
+            .collect()
+    }
+}
@@ -1,7 +1,15 @@
+use std::{collections::HashMap, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use rocket::FromForm;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 use validator::Validate;
 
+use crate::{domain::{dto::{dto_enum::transaction_sort::{SortOrderVariant, TransactionSortVariant}, pagination_dto::ResPageMetaDto, tag_dto::ResTransactionTagDto}, entities::{tag, transaction}}, implentation::{date_time_utill::{parse_to_datetime_utc_end_of_day, parse_to_datetime_utc_start_of_day}, decimal_utoipa::DecimalWrapper}};
+
 
 // >>>>>>>> Payment <<<<<<<<
 #[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
@@ -9,9 +17,10 @@ use validator::Validate;
 pub struct ReqCreatePaymentDto {
     #[validate(length(min = 1, message = "The transaction_type_id must not be empty"))]
     pub transaction_type_id: String,
-    #[validate(range(min = 0.1, message = "The amount must not be empty"))]
-    pub amount: f64,
-    #[validate(length(min = 1, message = "The expense_id must not be empty"))]
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: DecimalWrapper,
+    // may be left empty when splits are given, the first split's expense is stored instead
+    #[serde(default)]
     pub expense_id: String,
     #[validate(length(min = 1, message = "The asset_id must not be empty"))]
     pub asset_id: String,
@@ -19,21 +28,65 @@ pub struct ReqCreatePaymentDto {
     pub contact_id: String,
     #[validate(length(min = 1, message = "The note must not be empty"))]
     pub note: String,
-    #[validate(length(min = 1, message = "The created_at must not be empty"))]
-    pub created_at: String,
+    // business date of the entry, "created_at" is still accepted from older clients
+    #[serde(alias = "created_at")]
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The occurred_at must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub occurred_at: String,
+    // IANA name such as "Asia/Bangkok", only used when occurred_at carries no offset
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_timezone", message = "The timezone must be an IANA name such as Asia/Bangkok"))]
+    pub timezone: Option<String>,
+    // ids of the user's tags to attach
+    pub tag_ids: Option<Vec<String>>,
+    // the bank's id of an imported line such as an OFX FITID, spots the line when it is imported again
+    #[validate(length(max = 255, message = "The external_reference must be at most 255 characters"))]
+    pub external_reference: Option<String>,
+    // spreads the amount over several expenses, the lines must add up to the amount
+    #[validate(nested)]
+    pub splits: Option<Vec<ReqPaymentSplitDto>>,
 }
 
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+// one line of a split payment
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqPaymentSplitDto {
+    #[validate(length(min = 1, message = "The split expense_id must not be empty"))]
+    pub expense_id: String,
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The split amount must be greater than zero"))]
+    pub amount: DecimalWrapper,
+    #[validate(length(max = 255, message = "The split note must be at most 255 characters"))]
+    pub note: Option<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 #[serde(crate = "rocket::serde")]
 pub struct ReqUpdatePaymentDto {
     //pub transaction_type_id: Option<String>,
-    pub amount: Option<f64>,
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: Option<DecimalWrapper>,
     pub expense_id: Option<String>,
     pub asset_id: Option<String>,
     pub contact_id: Option<String>,
     pub note: Option<String>,
-    pub created_at: Option<String>,
+    #[serde(alias = "created_at")]
+    pub occurred_at: Option<String>,
+    pub timezone: Option<String>,
+    // replaces every tag of the transaction, an empty list removes them all
+    pub tag_ids: Option<Vec<String>>,
+    // replaces every line, an empty list turns it back into a plain payment on its expense_id
+    #[validate(nested)]
+    pub splits: Option<Vec<ReqPaymentSplitDto>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResPaymentSplitDto {
+    pub id: String,
+    pub expense_id: String,
+    pub expense_name: String,
+    pub amount: DecimalWrapper,
+    pub note: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -41,19 +94,23 @@ pub struct ReqUpdatePaymentDto {
 pub struct ResEntryPaymentDto{
     pub id: String,
     pub transaction_type_name: String,
-    pub amount: f64,
+    pub amount: DecimalWrapper,
     pub expense_name: String,
     pub contact_name: String,
     pub asset_name: String,
     pub note: String,
+    pub occurred_at: String,
     pub created_at: String,
     pub updated_at: String,
+    pub tags: Vec<ResTransactionTagDto>,
+    // the lines of a split payment, empty for a payment on a single expense
+    pub splits: Vec<ResPaymentSplitDto>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct ResListPaymentDto{
-    pub length: i32,
+    pub meta: ResPageMetaDto,
     pub data: Vec<ResEntryPaymentDto>,
 }
 
@@ -63,26 +120,42 @@ pub struct ResListPaymentDto{
 pub struct ReqCreateIncomeDto {
     #[validate(length(min = 1, message = "The transaction_type_id must not be empty"))]
     pub transaction_type_id: String,
-    pub amount: f64,
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: DecimalWrapper,
     #[validate(length(min = 1, message = "The aseet_id must not be empty"))]
     pub asset_id: String,
     #[validate(length(min = 1, message = "The contact_id must not be empty"))]
     pub contact_id: String,
     #[validate(length(min = 1, message = "The note must not be empty"))]
     pub note: String,
-    #[validate(length(min = 1, message = "The created_at must not be empty"))]
-    pub created_at: String,
+    // business date of the entry, "created_at" is still accepted from older clients
+    #[serde(alias = "created_at")]
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The occurred_at must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub occurred_at: String,
+    // IANA name such as "Asia/Bangkok", only used when occurred_at carries no offset
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_timezone", message = "The timezone must be an IANA name such as Asia/Bangkok"))]
+    pub timezone: Option<String>,
+    // ids of the user's tags to attach
+    pub tag_ids: Option<Vec<String>>,
+    // the bank's id of an imported line such as an OFX FITID, spots the line when it is imported again
+    #[validate(length(max = 255, message = "The external_reference must be at most 255 characters"))]
+    pub external_reference: Option<String>,
 }
 
 
-#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 #[serde(crate = "rocket::serde")]
 pub struct ReqUpdateIncomeDto {
-    pub amount: Option<f64>,
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: Option<DecimalWrapper>,
     pub asset_id: Option<String>,
     pub contact_id: Option<String>,
     pub note: Option<String>,
-    pub created_at: Option<String>,
+    #[serde(alias = "created_at")]
+    pub occurred_at: Option<String>,
+    pub timezone: Option<String>,
+    // replaces every tag of the transaction, an empty list removes them all
+    pub tag_ids: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -90,18 +163,20 @@ pub struct ReqUpdateIncomeDto {
 pub struct ResEntryIncomeDto{
     pub id: String,
     pub transaction_type_name: String,
-    pub amount: f64,
+    pub amount: DecimalWrapper,
     pub asset_name: String,
     pub contact_name: String,
     pub note: String,
+    pub occurred_at: String,
     pub created_at: String,
     pub updated_at: String,
+    pub tags: Vec<ResTransactionTagDto>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct ResListIncomeDto{
-    pub length: i32,
+    pub meta: ResPageMetaDto,
     pub data: Vec<ResEntryIncomeDto>,
 }
 
@@ -111,8 +186,8 @@ pub struct ResListIncomeDto{
 pub struct ReqCreateTransferDto {
     #[validate(length(min = 1, message = "The transaction_type_id must not be empty"))]
     pub transaction_type_id: String,
-    #[validate(range(min = 0.1, message = "The amount must not be empty"))]
-    pub amount: f64,
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: DecimalWrapper,
     #[validate(length(min = 1, message = "The asset_id must not be empty"))]
     pub asset_id: String,
     #[validate(length(min = 1, message = "The destination_asset_id must not be empty"))]
@@ -121,20 +196,35 @@ pub struct ReqCreateTransferDto {
     pub contact_id: String,
     #[validate(length(min = 1, message = "The note must not be empty"))]
     pub note: String,
-    #[validate(length(min = 1, message = "The created_at must not be empty"))]
-    pub created_at: String,
+    // business date of the entry, "created_at" is still accepted from older clients
+    #[serde(alias = "created_at")]
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_occurred_at", message = "The occurred_at must be an RFC 3339 timestamp or a YYYY-MM-DD date between 1970-01-01 and 2038-01-19"))]
+    pub occurred_at: String,
+    // IANA name such as "Asia/Bangkok", only used when occurred_at carries no offset
+    #[validate(custom(function = "crate::implentation::date_time_utill::validate_timezone", message = "The timezone must be an IANA name such as Asia/Bangkok"))]
+    pub timezone: Option<String>,
+    // ids of the user's tags to attach
+    pub tag_ids: Option<Vec<String>>,
+    // unique per asset, a posted recurring occurrence is found again by it
+    #[validate(length(max = 255, message = "The external_reference must be at most 255 characters"))]
+    pub external_reference: Option<String>,
 }
 
 
-#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone, Validate)]
 #[serde(crate = "rocket::serde")]
 pub struct ReqUpdateTransferDto {
-    pub amount: Option<f64>,
+    #[validate(custom(function = "crate::implentation::decimal_utoipa::validate_positive_amount", message = "The amount must be greater than zero"))]
+    pub amount: Option<DecimalWrapper>,
     pub asset_id: Option<String>,
     pub destination_asset_id: Option<String>,
     pub contact_id: Option<String>,
     pub note: Option<String>,
-    pub created_at: Option<String>,
+    #[serde(alias = "created_at")]
+    pub occurred_at: Option<String>,
+    pub timezone: Option<String>,
+    // replaces every tag of the transaction, an empty list removes them all
+    pub tag_ids: Option<Vec<String>>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -142,19 +232,298 @@ pub struct ReqUpdateTransferDto {
 pub struct ResEntryTransferDto{
     pub id: String,
     pub transaction_type_name: String,
-    pub amount: f64,
+    pub amount: DecimalWrapper,
     pub asset_name: String,
     pub destination_asset_name: String,
     pub contact_name: String,
     pub note: String,
+    pub occurred_at: String,
     pub created_at: String,
     pub updated_at: String,
+    pub tags: Vec<ResTransactionTagDto>,
 }
 
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(crate = "rocket::serde")]
 pub struct ResListTransferDto{
-    pub length: i64,
+    pub meta: ResPageMetaDto,
     pub data: Vec<ResEntryTransferDto>,
-}
\ No newline at end of file
+}
+
+// >>>>>>>> Transaction listing <<<<<<<<
+#[derive(Debug, Deserialize, FromForm, IntoParams)]
+#[serde(crate = "rocket::serde")]
+#[into_params(parameter_in = Query)]
+pub struct ReqTransactionQueryDto {
+    /// income, payment or transfer, several can be comma separated
+    #[serde(rename = "type")]
+    #[field(name = "type")]
+    pub transaction_type: Option<String>,
+    /// matches the source or the destination asset
+    pub asset_id: Option<String>,
+    pub contact_id: Option<String>,
+    pub expense_id: Option<String>,
+    pub expense_type_id: Option<String>,
+    /// transactions carrying this tag
+    pub tag_id: Option<String>,
+    pub min_amount: Option<String>,
+    pub max_amount: Option<String>,
+    /// YYYY-MM-DD or RFC 3339 timestamp, compared with occurred_at
+    pub from: Option<String>,
+    /// YYYY-MM-DD or RFC 3339 timestamp, compared with occurred_at
+    pub to: Option<String>,
+    /// case insensitive text inside the note
+    pub note: Option<String>,
+    /// occurred_at (default) or amount
+    pub sort_by: Option<String>,
+    /// asc or desc (default)
+    pub order: Option<String>,
+    /// 1 based page number up to 100000, ignored when cursor is given
+    pub page: Option<String>,
+    /// defaults to 20, at most 100
+    pub per_page: Option<String>,
+    /// next_cursor of the previous page
+    pub cursor: Option<String>,
+}
+
+impl ReqTransactionQueryDto {
+    pub const DEFAULT_PER_PAGE: u64 = 20;
+    pub const MAX_PER_PAGE: u64 = 100;
+    // keeps (page - 1) * per_page far from overflowing, deeper pages are reached with the cursor
+    pub const MAX_PAGE: u64 = 100_000;
+
+    // Parses every filter, the error message is returned to the client as is
+    pub fn into_search(self) -> Result<TransactionSearchDto, String> {
+        fn parse_uuid(field: &str, value: Option<String>) -> Result<Option<Uuid>, String> {
+            value
+                .map(|value| Uuid::parse_str(&value).map_err(|_| format!("Invalid {}", field)))
+                .transpose()
+        }
+        fn parse_amount(field: &str, value: Option<String>) -> Result<Option<Decimal>, String> {
+            value
+                .map(|value| Decimal::from_str(&value).map_err(|_| format!("Invalid {}", field)))
+                .transpose()
+        }
+
+        let transaction_types = match self.transaction_type {
+            Some(types) => types
+                .split(',')
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+                .map(|name| match name.as_str() {
+                    "income" | "payment" | "transfer" => Ok(name),
+                    _ => Err(format!("Invalid type '{}', expected income, payment or transfer", name)),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+
+        let min_amount = parse_amount("min_amount", self.min_amount)?;
+        let max_amount = parse_amount("max_amount", self.max_amount)?;
+        if let (Some(min), Some(max)) = (min_amount, max_amount)
+            && min > max
+        {
+            return Err("min_amount must not be greater than max_amount".to_string());
+        }
+
+        let from = self.from.as_deref().map(parse_to_datetime_utc_start_of_day).transpose()?;
+        let to = self.to.as_deref().map(parse_to_datetime_utc_end_of_day).transpose()?;
+        if let (Some(from), Some(to)) = (from, to)
+            && from > to
+        {
+            return Err("from must not be after to".to_string());
+        }
+
+        let sort_by = self
+            .sort_by
+            .as_deref()
+            .map(TransactionSortVariant::from_str)
+            .transpose()?
+            .unwrap_or(TransactionSortVariant::OccurredAt);
+        let order = self
+            .order
+            .as_deref()
+            .map(SortOrderVariant::from_str)
+            .transpose()?
+            .unwrap_or(SortOrderVariant::Desc);
+
+        let page = match self.page {
+            Some(page) => page.parse::<u64>().map_err(|_| "Invalid page".to_string())?,
+            None => 1,
+        };
+        if page == 0 || page > Self::MAX_PAGE {
+            return Err(format!("page must be between 1 and {}", Self::MAX_PAGE));
+        }
+        let per_page = match self.per_page {
+            Some(per_page) => per_page.parse::<u64>().map_err(|_| "Invalid per_page".to_string())?,
+            None => Self::DEFAULT_PER_PAGE,
+        };
+        if per_page == 0 || per_page > Self::MAX_PER_PAGE {
+            return Err(format!("per_page must be between 1 and {}", Self::MAX_PER_PAGE));
+        }
+
+        let cursor = self
+            .cursor
+            .as_deref()
+            .map(|cursor| TransactionCursorDto::decode(cursor, sort_by))
+            .transpose()?;
+
+        Ok(TransactionSearchDto {
+            transaction_types,
+            asset_id: parse_uuid("asset_id", self.asset_id)?,
+            contact_id: parse_uuid("contact_id", self.contact_id)?,
+            expense_id: parse_uuid("expense_id", self.expense_id)?,
+            expense_type_id: parse_uuid("expense_type_id", self.expense_type_id)?,
+            tag_id: parse_uuid("tag_id", self.tag_id)?,
+            min_amount,
+            max_amount,
+            from,
+            to,
+            note: self.note.filter(|note| !note.trim().is_empty()),
+            sort_by,
+            order,
+            page,
+            per_page,
+            cursor,
+        })
+    }
+}
+
+
+// parsed form of ReqTransactionQueryDto handed to the repository
+#[derive(Debug, Clone)]
+pub struct TransactionSearchDto {
+    pub transaction_types: Vec<String>,
+    pub asset_id: Option<Uuid>,
+    pub contact_id: Option<Uuid>,
+    pub expense_id: Option<Uuid>,
+    pub expense_type_id: Option<Uuid>,
+    pub tag_id: Option<Uuid>,
+    pub min_amount: Option<Decimal>,
+    pub max_amount: Option<Decimal>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub note: Option<String>,
+    pub sort_by: TransactionSortVariant,
+    pub order: SortOrderVariant,
+    pub page: u64,
+    pub per_page: u64,
+    pub cursor: Option<TransactionCursorDto>,
+}
+
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransactionCursorKeyDto {
+    OccurredAt(DateTime<Utc>),
+    Amount(Decimal),
+}
+
+// position after the last record of a page: its sort key plus its id as tie breaker
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransactionCursorDto {
+    pub key: TransactionCursorKeyDto,
+    pub id: Uuid,
+}
+
+impl TransactionCursorDto {
+    pub fn from_record(record: &transaction::Model, sort_by: TransactionSortVariant) -> Result<Self, String> {
+        let key = match sort_by {
+            TransactionSortVariant::OccurredAt => TransactionCursorKeyDto::OccurredAt(record.occurred_at),
+            TransactionSortVariant::Amount => TransactionCursorKeyDto::Amount(record.amount),
+        };
+        let id = Uuid::from_slice(&record.id).map_err(|err| err.to_string())?;
+        Ok(Self { key, id })
+    }
+
+    // hex of "<key kind>|<key>|<id>" so clients treat it as an opaque token
+    pub fn encode(&self) -> String {
+        let raw = match &self.key {
+            TransactionCursorKeyDto::OccurredAt(occurred_at) => format!("o|{}|{}", occurred_at.timestamp_micros(), self.id),
+            TransactionCursorKeyDto::Amount(amount) => format!("a|{}|{}", amount, self.id),
+        };
+        raw.bytes().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    pub fn decode(cursor: &str, sort_by: TransactionSortVariant) -> Result<Self, String> {
+        let invalid = || "Invalid cursor".to_string();
+        if !cursor.len().is_multiple_of(2) {
+            return Err(invalid());
+        }
+        let bytes = (0..cursor.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(cursor.get(index..index + 2).ok_or_else(invalid)?, 16).map_err(|_| invalid()))
+            .collect::<Result<Vec<u8>, String>>()?;
+        let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+
+        let mut parts = raw.splitn(3, '|');
+        let (Some(kind), Some(key), Some(id)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        let key = match (kind, sort_by) {
+            ("o", TransactionSortVariant::OccurredAt) => TransactionCursorKeyDto::OccurredAt(
+                key.parse::<i64>()
+                    .ok()
+                    .and_then(DateTime::from_timestamp_micros)
+                    .ok_or_else(invalid)?,
+            ),
+            ("a", TransactionSortVariant::Amount) => TransactionCursorKeyDto::Amount(Decimal::from_str(key).map_err(|_| invalid())?),
+            _ => return Err("The cursor belongs to a different sort_by".to_string()),
+        };
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+
+        Ok(Self { key, id })
+    }
+}
+
+
+// one page of matching rows, has_more is true when another page follows
+#[derive(Debug, Clone)]
+pub struct TransactionPageDto {
+    pub records: Vec<transaction::Model>,
+    pub total: u64,
+    pub has_more: bool,
+}
+
+
+// display names of everything the rows of a page point to, keyed by binary id
+#[derive(Debug, Clone, Default)]
+pub struct TransactionReferenceNamesDto {
+    pub transaction_types: HashMap<Vec<u8>, String>,
+    pub assets: HashMap<Vec<u8>, String>,
+    pub contacts: HashMap<Vec<u8>, String>,
+    pub expenses: HashMap<Vec<u8>, String>,
+    pub tags: HashMap<Vec<u8>, Vec<tag::Model>>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResEntryTransactionDto {
+    pub id: String,
+    pub transaction_type_name: String,
+    pub amount: DecimalWrapper,
+    pub asset_id: String,
+    pub asset_name: String,
+    pub destination_asset_id: Option<String>,
+    pub destination_asset_name: Option<String>,
+    pub contact_id: Option<String>,
+    pub contact_name: Option<String>,
+    pub expense_id: Option<String>,
+    pub expense_name: Option<String>,
+    pub note: String,
+    pub occurred_at: String,
+    // set on imported transactions, the bank's id of the line
+    pub external_reference: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub tags: Vec<ResTransactionTagDto>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResListTransactionDto {
+    pub meta: ResPageMetaDto,
+    pub data: Vec<ResEntryTransactionDto>,
+}
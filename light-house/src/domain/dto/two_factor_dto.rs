@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResTwoFactorStatusDto {
+    pub enabled: bool,
+    // a secret was generated but no code confirmed it yet
+    pub pending_enrolment: bool,
+    pub confirmed_at: Option<String>,
+    pub recovery_codes_left: u64,
+}
+
+
+// shown once, the secret is typed in or the uri scanned as a qr code by the authenticator app
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResTwoFactorEnrolmentDto {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqTwoFactorCodeDto {
+    #[validate(length(min = 6, max = 6, message = "The code must be 6 digits"))]
+    pub code: String,
+}
+
+
+// the password of the user, asked again before two factor is turned off or its codes replaced
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqTwoFactorPasswordDto {
+    #[validate(length(min = 1, message = "The password must not be empty"))]
+    pub password: String,
+}
+
+
+// shown once, each code works once in place of the authenticator app
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResTwoFactorRecoveryCodesDto {
+    pub recovery_codes: Vec<String>,
+}
+
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqTwoFactorSignInDto {
+    #[validate(length(min = 1, message = "The challenge_token must not be empty"))]
+    pub challenge_token: String,
+
+    // the 6 digits of the authenticator app or a recovery code
+    #[validate(length(min = 6, max = 32, message = "The code must be between 6 and 32 characters"))]
+    pub code: String,
+}
+
+
+// answer of a sign in with the right password when two factor is on
+#[derive(Debug, Serialize, Deserialize, ToSchema, Clone)]
+#[serde(crate = "rocket::serde")]
+pub struct ResTwoFactorChallengeDto {
+    // always true, tells this answer apart from a token pair
+    pub two_factor_required: bool,
+    // posted to /sign-in/two-factor with the code
+    pub challenge_token: String,
+    pub expires_at: String,
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::domain::entities::{api_token, asset, asset_type, attachment, budget, budget_period, contact, contact_type, current_sheet, expense, expense_type, gender, import_profile, journal_entry, journal_line, mcp_pending_operation, payment_split, recurring_occurrence, recurring_transaction, sign_in_attempt, tag, transaction, transaction_tag, transaction_type, user, user_role, user_session, user_two_factor};
+
+
+
+
+// every row a user owns, read in one go for the export
+pub struct UserDataDto {
+    pub user: user::Model,
+    pub gender: Option<gender::Model>,
+    pub user_role: Option<user_role::Model>,
+    pub transaction_types: Vec<transaction_type::Model>,
+    pub asset_types: Vec<asset_type::Model>,
+    pub assets: Vec<asset::Model>,
+    pub contact_types: Vec<contact_type::Model>,
+    pub contacts: Vec<contact::Model>,
+    pub expense_types: Vec<expense_type::Model>,
+    pub expenses: Vec<expense::Model>,
+    pub transactions: Vec<transaction::Model>,
+    pub tags: Vec<tag::Model>,
+    pub transaction_tags: Vec<transaction_tag::Model>,
+    pub payment_splits: Vec<payment_split::Model>,
+    pub current_sheets: Vec<current_sheet::Model>,
+    pub journal_entries: Vec<journal_entry::Model>,
+    pub journal_lines: Vec<journal_line::Model>,
+    pub budgets: Vec<budget::Model>,
+    pub budget_periods: Vec<budget_period::Model>,
+    pub recurring_transactions: Vec<recurring_transaction::Model>,
+    pub recurring_occurrences: Vec<recurring_occurrence::Model>,
+    pub mcp_pending_operations: Vec<mcp_pending_operation::Model>,
+    pub api_tokens: Vec<api_token::Model>,
+    pub sessions: Vec<user_session::Model>,
+    pub sign_in_attempts: Vec<sign_in_attempt::Model>,
+    pub attachments: Vec<attachment::Model>,
+    pub import_profiles: Vec<import_profile::Model>,
+    pub two_factor: Option<user_two_factor::Model>,
+    pub recovery_codes_left: u64,
+}
+
+
+// one list per table, ids as uuid strings. Password hashes, token hashes and the totp secret are left out
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(crate = "rocket::serde")]
+pub struct ResUserExportDto {
+    pub format_version: u32,
+    pub exported_at: String,
+    #[schema(value_type = Object)]
+    pub profile: Value,
+    #[schema(value_type = Vec<Object>)]
+    pub transaction_types: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub asset_types: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub assets: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub contact_types: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub contacts: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub expense_types: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub expenses: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub transactions: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub tags: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub transaction_tags: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub payment_splits: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub current_sheets: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub journal_entries: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub journal_lines: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub budgets: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub budget_periods: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub recurring_transactions: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub recurring_occurrences: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub mcp_pending_operations: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub api_tokens: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub sessions: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub sign_in_attempts: Vec<Value>,
+    // metadata only, the files are downloaded one by one from the attachment routes
+    #[schema(value_type = Vec<Object>)]
+    pub attachments: Vec<Value>,
+    #[schema(value_type = Vec<Object>)]
+    pub import_profiles: Vec<Value>,
+    #[schema(value_type = Option<Object>)]
+    pub two_factor: Option<Value>,
+}
+
+
+// the password is asked again, there is no undo
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+#[serde(crate = "rocket::serde")]
+pub struct ReqDeleteAccountDto {
+    #[validate(length(min = 1, message = "The password must not be empty"))]
+    pub password: String,
+}
@@ -9,7 +9,7 @@ pub struct Model {
     pub id: Vec<u8>,
     #[sea_orm(column_type = "Binary(16)")]
     pub asset_id: Vec<u8>,
-    #[sea_orm(column_type = "Decimal(Some((10, 0)))")]
+    #[sea_orm(column_type = "Decimal(Some((19, 4)))")]
     pub balance: Decimal,
     #[sea_orm(column_type = "Binary(16)", nullable)]
     pub last_transaction_id: Option<Vec<u8>>,
@@ -33,7 +33,7 @@ pub enum Relation {
         from = "Column::LastTransactionId",
         to = "super::transaction::Column::Id",
         on_update = "Cascade",
-        on_delete = "Cascade"
+        on_delete = "SetNull"
     )]
     Transaction,
     #[sea_orm(
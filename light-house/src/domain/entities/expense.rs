@@ -26,6 +26,8 @@ pub enum Relation {
         on_delete = "Restrict"
     )]
     ExpenseType,
+    #[sea_orm(has_many = "super::payment_split::Entity")]
+    PaymentSplit,
     #[sea_orm(has_many = "super::transaction::Entity")]
     Transaction,
     #[sea_orm(
@@ -44,6 +46,12 @@ impl Related<super::expense_type::Entity> for Entity {
     }
 }
 
+impl Related<super::payment_split::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PaymentSplit.def()
+    }
+}
+
 impl Related<super::transaction::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Transaction.def()
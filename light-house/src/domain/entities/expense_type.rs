@@ -16,6 +16,8 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::budget::Entity")]
+    Budget,
     #[sea_orm(has_many = "super::expense::Entity")]
     Expense,
     #[sea_orm(
@@ -28,6 +30,12 @@ pub enum Relation {
     User,
 }
 
+impl Related<super::budget::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Budget.def()
+    }
+}
+
 impl Related<super::expense::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Expense.def()
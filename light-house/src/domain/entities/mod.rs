@@ -2,16 +2,35 @@
 
 pub mod prelude;
 
+pub mod api_token;
 pub mod asset;
 pub mod asset_type;
+pub mod attachment;
+pub mod budget;
+pub mod budget_period;
 pub mod contact;
 pub mod contact_type;
 pub mod current_sheet;
 pub mod expense;
 pub mod expense_type;
 pub mod gender;
+pub mod import_profile;
+pub mod journal_entry;
+pub mod journal_line;
+pub mod mcp_pending_operation;
+pub mod payment_split;
+pub mod recurring_occurrence;
+pub mod recurring_transaction;
+pub mod sign_in_attempt;
+pub mod sign_in_throttle;
+pub mod tag;
 pub mod transaction;
+pub mod transaction_tag;
 pub mod transaction_type;
+pub mod two_factor_recovery_code;
 pub mod user;
+pub mod user_action_token;
 pub mod user_contact;
 pub mod user_role;
+pub mod user_session;
+pub mod user_two_factor;
@@ -1,15 +1,34 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.0
 
+pub use super::api_token::Entity as ApiToken;
 pub use super::asset::Entity as Asset;
 pub use super::asset_type::Entity as AssetType;
+pub use super::attachment::Entity as Attachment;
+pub use super::budget::Entity as Budget;
+pub use super::budget_period::Entity as BudgetPeriod;
 pub use super::contact::Entity as Contact;
 pub use super::contact_type::Entity as ContactType;
 pub use super::current_sheet::Entity as CurrentSheet;
 pub use super::expense::Entity as Expense;
 pub use super::expense_type::Entity as ExpenseType;
 pub use super::gender::Entity as Gender;
+pub use super::import_profile::Entity as ImportProfile;
+pub use super::journal_entry::Entity as JournalEntry;
+pub use super::journal_line::Entity as JournalLine;
+pub use super::mcp_pending_operation::Entity as McpPendingOperation;
+pub use super::payment_split::Entity as PaymentSplit;
+pub use super::recurring_occurrence::Entity as RecurringOccurrence;
+pub use super::recurring_transaction::Entity as RecurringTransaction;
+pub use super::sign_in_attempt::Entity as SignInAttempt;
+pub use super::sign_in_throttle::Entity as SignInThrottle;
+pub use super::tag::Entity as Tag;
 pub use super::transaction::Entity as Transaction;
+pub use super::transaction_tag::Entity as TransactionTag;
 pub use super::transaction_type::Entity as TransactionType;
+pub use super::two_factor_recovery_code::Entity as TwoFactorRecoveryCode;
 pub use super::user::Entity as User;
+pub use super::user_action_token::Entity as UserActionToken;
 pub use super::user_contact::Entity as UserContact;
 pub use super::user_role::Entity as UserRole;
+pub use super::user_session::Entity as UserSession;
+pub use super::user_two_factor::Entity as UserTwoFactor;
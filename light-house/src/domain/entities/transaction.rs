@@ -2,15 +2,15 @@
 
 use sea_orm::entity::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "transaction")]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false, column_type = "Binary(16)")]
     pub id: Vec<u8>,
     #[sea_orm(column_type = "Binary(16)")]
     pub transaction_type_id: Vec<u8>,
-    #[sea_orm(column_type = "Double")]
-    pub amount: f64,
+    #[sea_orm(column_type = "Decimal(Some((19, 4)))")]
+    pub amount: Decimal,
     #[sea_orm(column_type = "Binary(16)")]
     pub asset_id: Vec<u8>,
     #[sea_orm(column_type = "Binary(16)", nullable)]
@@ -24,6 +24,8 @@ pub struct Model {
     pub updated_at: Option<DateTimeUtc>,
     #[sea_orm(column_type = "Binary(16)")]
     pub user_id: Vec<u8>,
+    pub occurred_at: DateTimeUtc,
+    pub external_reference: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -62,6 +64,12 @@ pub enum Relation {
         on_delete = "Restrict"
     )]
     Expense,
+    #[sea_orm(has_many = "super::journal_entry::Entity")]
+    JournalEntry,
+    #[sea_orm(has_many = "super::payment_split::Entity")]
+    PaymentSplit,
+    #[sea_orm(has_many = "super::transaction_tag::Entity")]
+    TransactionTag,
     #[sea_orm(
         belongs_to = "super::transaction_type::Entity",
         from = "Column::TransactionTypeId",
@@ -98,6 +106,24 @@ impl Related<super::expense::Entity> for Entity {
     }
 }
 
+impl Related<super::journal_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::JournalEntry.def()
+    }
+}
+
+impl Related<super::payment_split::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PaymentSplit.def()
+    }
+}
+
+impl Related<super::transaction_tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TransactionTag.def()
+    }
+}
+
 impl Related<super::transaction_type::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::TransactionType.def()
@@ -110,4 +136,13 @@ impl Related<super::user::Entity> for Entity {
     }
 }
 
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::transaction_tag::Relation::Tag.def()
+    }
+    fn via() -> Option<RelationDef> {
+        Some(super::transaction_tag::Relation::Transaction.def().rev())
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -14,22 +14,28 @@ pub struct Model {
     pub email: String,
     pub first_name: String,
     pub last_name: String,
-    #[sea_orm(unique)]
-    pub mcp_token: String,
     #[sea_orm(column_type = "Binary(16)")]
     pub gender_id: Vec<u8>,
     #[sea_orm(column_type = "Binary(16)")]
     pub user_role_id: Vec<u8>,
+    pub disabled_at: Option<DateTimeUtc>,
+    pub email_verified_at: Option<DateTimeUtc>,
     pub created_at: Option<DateTimeUtc>,
     pub updated_at: Option<DateTimeUtc>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::api_token::Entity")]
+    ApiToken,
     #[sea_orm(has_many = "super::asset::Entity")]
     Asset,
+    #[sea_orm(has_many = "super::attachment::Entity")]
+    Attachment,
     #[sea_orm(has_many = "super::asset_type::Entity")]
     AssetType,
+    #[sea_orm(has_many = "super::budget::Entity")]
+    Budget,
     #[sea_orm(has_many = "super::contact_type::Entity")]
     ContactType,
     #[sea_orm(has_many = "super::current_sheet::Entity")]
@@ -46,8 +52,22 @@ pub enum Relation {
         on_delete = "Restrict"
     )]
     Gender,
+    #[sea_orm(has_many = "super::import_profile::Entity")]
+    ImportProfile,
+    #[sea_orm(has_many = "super::journal_entry::Entity")]
+    JournalEntry,
+    #[sea_orm(has_many = "super::mcp_pending_operation::Entity")]
+    McpPendingOperation,
+    #[sea_orm(has_many = "super::recurring_transaction::Entity")]
+    RecurringTransaction,
+    #[sea_orm(has_many = "super::sign_in_attempt::Entity")]
+    SignInAttempt,
+    #[sea_orm(has_many = "super::tag::Entity")]
+    Tag,
     #[sea_orm(has_many = "super::transaction::Entity")]
     Transaction,
+    #[sea_orm(has_many = "super::two_factor_recovery_code::Entity")]
+    TwoFactorRecoveryCode,
     #[sea_orm(has_many = "super::user_contact::Entity")]
     UserContact,
     #[sea_orm(
@@ -58,6 +78,18 @@ pub enum Relation {
         on_delete = "Restrict"
     )]
     UserRole,
+    #[sea_orm(has_many = "super::user_action_token::Entity")]
+    UserActionToken,
+    #[sea_orm(has_many = "super::user_session::Entity")]
+    UserSession,
+    #[sea_orm(has_one = "super::user_two_factor::Entity")]
+    UserTwoFactor,
+}
+
+impl Related<super::api_token::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApiToken.def()
+    }
 }
 
 impl Related<super::asset::Entity> for Entity {
@@ -66,12 +98,24 @@ impl Related<super::asset::Entity> for Entity {
     }
 }
 
+impl Related<super::attachment::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Attachment.def()
+    }
+}
+
 impl Related<super::asset_type::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::AssetType.def()
     }
 }
 
+impl Related<super::budget::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Budget.def()
+    }
+}
+
 impl Related<super::contact_type::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ContactType.def()
@@ -102,12 +146,54 @@ impl Related<super::gender::Entity> for Entity {
     }
 }
 
+impl Related<super::import_profile::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ImportProfile.def()
+    }
+}
+
+impl Related<super::journal_entry::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::JournalEntry.def()
+    }
+}
+
+impl Related<super::mcp_pending_operation::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::McpPendingOperation.def()
+    }
+}
+
+impl Related<super::recurring_transaction::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecurringTransaction.def()
+    }
+}
+
+impl Related<super::sign_in_attempt::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SignInAttempt.def()
+    }
+}
+
+impl Related<super::tag::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Tag.def()
+    }
+}
+
 impl Related<super::transaction::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Transaction.def()
     }
 }
 
+impl Related<super::two_factor_recovery_code::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TwoFactorRecoveryCode.def()
+    }
+}
+
 impl Related<super::user_contact::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::UserContact.def()
@@ -120,6 +206,24 @@ impl Related<super::user_role::Entity> for Entity {
     }
 }
 
+impl Related<super::user_action_token::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserActionToken.def()
+    }
+}
+
+impl Related<super::user_session::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserSession.def()
+    }
+}
+
+impl Related<super::user_two_factor::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::UserTwoFactor.def()
+    }
+}
+
 impl Related<super::contact::Entity> for Entity {
     fn to() -> RelationDef {
         super::user_contact::Relation::Contact.def()
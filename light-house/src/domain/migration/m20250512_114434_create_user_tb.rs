@@ -91,6 +91,8 @@ pub enum User {
     McpToken,
     GenderId,
     UserRoleId,
+    DisabledAt,
+    EmailVerifiedAt,
     CreatedAt,
     UpdatedAt,
 }
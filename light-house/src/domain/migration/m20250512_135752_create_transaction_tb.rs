@@ -137,4 +137,6 @@ pub enum Transaction {
     CreatedAt,
     UpdatedAt,
     UserId,
+    OccurredAt,
+    ExternalReference,
 }
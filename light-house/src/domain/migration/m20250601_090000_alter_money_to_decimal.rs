@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+use super::{m20250512_135752_create_transaction_tb::Transaction, m20250512_142622_create_current_sheet::CurrentSheet};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Money is stored as DECIMAL(19,4) so amounts and balances are exact.
+        // MODIFY COLUMN converts the existing rows in place.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .modify_column(
+                        ColumnDef::new(Transaction::Amount)
+                            .decimal_len(19, 4)
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CurrentSheet::Table)
+                    .modify_column(
+                        ColumnDef::new(CurrentSheet::Balance)
+                            .decimal_len(19, 4)
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .modify_column(
+                        ColumnDef::new(Transaction::Amount)
+                            .double()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(CurrentSheet::Table)
+                    .modify_column(
+                        ColumnDef::new(CurrentSheet::Balance)
+                            .decimal_len(10, 2)
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+}
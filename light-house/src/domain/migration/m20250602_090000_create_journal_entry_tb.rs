@@ -0,0 +1,80 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::{m20250512_114434_create_user_tb::User, m20250512_135752_create_transaction_tb::Transaction};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(JournalEntry::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JournalEntry::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(JournalEntry::TransactionId)
+                            .uuid()
+                            .null(),
+                    )
+                    .foreign_key(
+                        // keep the journal as an audit trail after the transaction row is deleted
+                        ForeignKey::create()
+                            .name("fk_journal_entry_transaction")
+                            .from(JournalEntry::Table, JournalEntry::TransactionId)
+                            .to(Transaction::Table, Transaction::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(string(JournalEntry::Memo).not_null())
+                    .col(
+                        ColumnDef::new(JournalEntry::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(JournalEntry::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_journal_entry_user")
+                            .from(JournalEntry::Table, JournalEntry::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(JournalEntry::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum JournalEntry {
+    Table,
+    Id,
+    TransactionId,
+    Memo,
+    CreatedAt,
+    UserId,
+}
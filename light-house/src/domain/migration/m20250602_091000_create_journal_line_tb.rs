@@ -0,0 +1,98 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20250602_090000_create_journal_entry_tb::JournalEntry;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(JournalLine::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(JournalLine::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(JournalLine::JournalEntryId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_journal_line_journal_entry")
+                            .from(JournalLine::Table, JournalLine::JournalEntryId)
+                            .to(JournalEntry::Table, JournalEntry::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    // asset | expense | income | contact, account_id points into the matching table
+                    .col(string_len(JournalLine::AccountType, 16).not_null())
+                    .col(
+                        ColumnDef::new(JournalLine::AccountId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(JournalLine::ContactId)
+                            .uuid()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(JournalLine::Debit)
+                            .decimal_len(19, 4)
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(JournalLine::Credit)
+                            .decimal_len(19, 4)
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(JournalLine::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_journal_line_account")
+                            .col(JournalLine::AccountType)
+                            .col(JournalLine::AccountId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(JournalLine::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum JournalLine {
+    Table,
+    Id,
+    JournalEntryId,
+    AccountType,
+    AccountId,
+    ContactId,
+    Debit,
+    Credit,
+    CreatedAt,
+}
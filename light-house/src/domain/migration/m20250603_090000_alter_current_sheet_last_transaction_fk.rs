@@ -0,0 +1,56 @@
+use sea_orm_migration::prelude::*;
+
+use super::{m20250512_135752_create_transaction_tb::Transaction, m20250512_142622_create_current_sheet::CurrentSheet};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Deleting the last transaction of an asset must clear the pointer, not delete the current sheet
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_current_sheet_last_transaction_id")
+                    .table(CurrentSheet::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_current_sheet_last_transaction_id")
+                    .from(CurrentSheet::Table, CurrentSheet::LastTransactionId)
+                    .to(Transaction::Table, Transaction::Id)
+                    .on_delete(ForeignKeyAction::SetNull)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_current_sheet_last_transaction_id")
+                    .table(CurrentSheet::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_foreign_key(
+                ForeignKey::create()
+                    .name("fk_current_sheet_last_transaction_id")
+                    .from(CurrentSheet::Table, CurrentSheet::LastTransactionId)
+                    .to(Transaction::Table, Transaction::Id)
+                    .on_delete(ForeignKeyAction::Cascade)
+                    .on_update(ForeignKeyAction::Cascade)
+                    .to_owned(),
+            )
+            .await
+    }
+}
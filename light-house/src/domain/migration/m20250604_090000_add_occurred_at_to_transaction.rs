@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20250512_135752_create_transaction_tb::Transaction;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // occurred_at is the business date chosen by the user,
+        // created_at / updated_at stay as the audit trail of the row itself
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .add_column(
+                        ColumnDef::new(Transaction::OccurredAt)
+                            .timestamp()
+                            .not_null()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // existing rows keep the date they were recorded with
+        manager
+            .exec_stmt(
+                Query::update()
+                    .table(Transaction::Table)
+                    .value(Transaction::OccurredAt, Expr::col(Transaction::CreatedAt))
+                    .and_where(Expr::col(Transaction::CreatedAt).is_not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transaction_user_occurred_at")
+                    .table(Transaction::Table)
+                    .col(Transaction::UserId)
+                    .col(Transaction::OccurredAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_transaction_user_occurred_at")
+                    .table(Transaction::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .drop_column(Transaction::OccurredAt)
+                    .to_owned(),
+            )
+            .await
+    }
+}
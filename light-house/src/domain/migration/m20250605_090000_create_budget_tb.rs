@@ -0,0 +1,121 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::{m20250512_114434_create_user_tb::User, m20250512_132030_create_expense_type_tb::ExpenseType};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Budget::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Budget::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(Budget::Name).not_null())
+                    .col(
+                        ColumnDef::new(Budget::ExpenseTypeId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_budget_expense_type")
+                            .from(Budget::Table, Budget::ExpenseTypeId)
+                            .to(ExpenseType::Table, ExpenseType::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    // amount of every new period
+                    .col(
+                        ColumnDef::new(Budget::Amount)
+                            .decimal_len(19, 4)
+                            .not_null(),
+                    )
+                    // monthly | custom
+                    .col(string_len(Budget::PeriodType, 16).not_null())
+                    .col(
+                        ColumnDef::new(Budget::StartDate)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(Budget::EndDate)
+                            .timestamp()
+                            .null(),
+                    )
+                    // carry what is left (or overspent) into the next monthly period
+                    .col(
+                        ColumnDef::new(Budget::Rollover)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(
+                        ColumnDef::new(Budget::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(Budget::UpdatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(Budget::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_budget_user")
+                            .from(Budget::Table, Budget::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_budget_user_expense_type")
+                            .col(Budget::UserId)
+                            .col(Budget::ExpenseTypeId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(Budget::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum Budget {
+    Table,
+    Id,
+    Name,
+    ExpenseTypeId,
+    Amount,
+    PeriodType,
+    StartDate,
+    EndDate,
+    Rollover,
+    CreatedAt,
+    UpdatedAt,
+    UserId,
+}
@@ -0,0 +1,102 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20250605_090000_create_budget_tb::Budget;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(BudgetPeriod::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(BudgetPeriod::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetPeriod::BudgetId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_budget_period_budget")
+                            .from(BudgetPeriod::Table, BudgetPeriod::BudgetId)
+                            .to(Budget::Table, Budget::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetPeriod::PeriodStart)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetPeriod::PeriodEnd)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetPeriod::Amount)
+                            .decimal_len(19, 4)
+                            .not_null(),
+                    )
+                    // left over (negative when overspent) of the previous period, zero without rollover
+                    .col(
+                        ColumnDef::new(BudgetPeriod::RolloverAmount)
+                            .decimal_len(19, 4)
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetPeriod::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(BudgetPeriod::UpdatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .index(
+                        Index::create()
+                            .name("uq_budget_period_budget_start")
+                            .col(BudgetPeriod::BudgetId)
+                            .col(BudgetPeriod::PeriodStart)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(BudgetPeriod::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Table,
+    Id,
+    BudgetId,
+    PeriodStart,
+    PeriodEnd,
+    Amount,
+    RolloverAmount,
+    CreatedAt,
+    UpdatedAt,
+}
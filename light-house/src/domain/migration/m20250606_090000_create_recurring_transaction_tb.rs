@@ -0,0 +1,192 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::{m20250512_114434_create_user_tb::User, m20250512_131405_create_asset_tb::Asset, m20250512_132512_create_expense_tb::Expense, m20250512_133540_create_contact_tb::Contact, m20250512_134954_create_transaction_type_tb::TransactionType};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecurringTransaction::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecurringTransaction::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string(RecurringTransaction::Name).not_null())
+                    // template of the transaction posted on every occurrence
+                    .col(
+                        ColumnDef::new(RecurringTransaction::TransactionTypeId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_transaction_type")
+                            .from(RecurringTransaction::Table, RecurringTransaction::TransactionTypeId)
+                            .to(TransactionType::Table, TransactionType::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::Amount)
+                            .decimal_len(19, 4)
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::AssetId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_asset")
+                            .from(RecurringTransaction::Table, RecurringTransaction::AssetId)
+                            .to(Asset::Table, Asset::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::DestinationAssetId)
+                            .uuid()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_destination_asset")
+                            .from(RecurringTransaction::Table, RecurringTransaction::DestinationAssetId)
+                            .to(Asset::Table, Asset::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::ExpenseId)
+                            .uuid()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_expense")
+                            .from(RecurringTransaction::Table, RecurringTransaction::ExpenseId)
+                            .to(Expense::Table, Expense::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::ContactId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_contact")
+                            .from(RecurringTransaction::Table, RecurringTransaction::ContactId)
+                            .to(Contact::Table, Contact::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(string(RecurringTransaction::Note).not_null())
+                    // daily | weekly | monthly_day | last_business_day | rrule
+                    .col(string_len(RecurringTransaction::ScheduleType, 32).not_null())
+                    // every schedule is stored as an RRULE
+                    .col(string_len(RecurringTransaction::Rrule, 255).not_null())
+                    .col(string_len(RecurringTransaction::Timezone, 64).not_null())
+                    .col(
+                        ColumnDef::new(RecurringTransaction::StartAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::EndAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    // active | paused | finished
+                    .col(string_len(RecurringTransaction::Status, 16).not_null())
+                    // next occurrence the worker has to handle, null once finished
+                    .col(
+                        ColumnDef::new(RecurringTransaction::NextRunAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::LastRunAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::UpdatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringTransaction::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_transaction_user")
+                            .from(RecurringTransaction::Table, RecurringTransaction::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_recurring_transaction_status_next_run")
+                            .col(RecurringTransaction::Status)
+                            .col(RecurringTransaction::NextRunAt),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(RecurringTransaction::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum RecurringTransaction {
+    Table,
+    Id,
+    Name,
+    TransactionTypeId,
+    Amount,
+    AssetId,
+    DestinationAssetId,
+    ExpenseId,
+    ContactId,
+    Note,
+    ScheduleType,
+    Rrule,
+    Timezone,
+    StartAt,
+    EndAt,
+    Status,
+    NextRunAt,
+    LastRunAt,
+    CreatedAt,
+    UpdatedAt,
+    UserId,
+}
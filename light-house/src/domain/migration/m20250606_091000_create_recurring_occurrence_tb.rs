@@ -0,0 +1,107 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::{m20250512_135752_create_transaction_tb::Transaction, m20250606_090000_create_recurring_transaction_tb::RecurringTransaction};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(RecurringOccurrence::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RecurringOccurrence::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringOccurrence::RecurringTransactionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_occurrence_recurring_transaction")
+                            .from(RecurringOccurrence::Table, RecurringOccurrence::RecurringTransactionId)
+                            .to(RecurringTransaction::Table, RecurringTransaction::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringOccurrence::ScheduledFor)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    // pending | posted | skipped | failed
+                    .col(string_len(RecurringOccurrence::Status, 16).not_null())
+                    // the posted transaction, kept when the transaction is deleted later on
+                    .col(
+                        ColumnDef::new(RecurringOccurrence::TransactionId)
+                            .uuid()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_recurring_occurrence_transaction")
+                            .from(RecurringOccurrence::Table, RecurringOccurrence::TransactionId)
+                            .to(Transaction::Table, Transaction::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringOccurrence::Message)
+                            .string_len(512)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringOccurrence::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(RecurringOccurrence::UpdatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    // one row per scheduled date, what keeps a restart from posting twice
+                    .index(
+                        Index::create()
+                            .name("uq_recurring_occurrence_scheduled_for")
+                            .col(RecurringOccurrence::RecurringTransactionId)
+                            .col(RecurringOccurrence::ScheduledFor)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(RecurringOccurrence::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum RecurringOccurrence {
+    Table,
+    Id,
+    RecurringTransactionId,
+    ScheduledFor,
+    Status,
+    TransactionId,
+    Message,
+    CreatedAt,
+    UpdatedAt,
+}
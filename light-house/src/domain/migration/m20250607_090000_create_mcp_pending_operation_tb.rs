@@ -0,0 +1,131 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20250512_114434_create_user_tb::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(McpPendingOperation::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(McpPendingOperation::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    // create_payment | update_payment | delete_payment | ... | delete_expense
+                    .col(string_len(McpPendingOperation::Operation, 32).not_null())
+                    // transaction, contact or expense the operation changes, none for creates
+                    .col(
+                        ColumnDef::new(McpPendingOperation::TargetId)
+                            .uuid()
+                            .null(),
+                    )
+                    // updated_at of the target when proposed, confirming fails once the target has changed
+                    .col(
+                        ColumnDef::new(McpPendingOperation::TargetUpdatedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(McpPendingOperation::Payload)
+                            .json()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(McpPendingOperation::BalanceImpact)
+                            .json()
+                            .not_null(),
+                    )
+                    // pending | confirmed | rejected | expired | failed
+                    .col(string_len(McpPendingOperation::Status, 16).not_null())
+                    .col(
+                        ColumnDef::new(McpPendingOperation::Result)
+                            .json()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(McpPendingOperation::Message)
+                            .string_len(512)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(McpPendingOperation::ExpiresAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(McpPendingOperation::DecidedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(McpPendingOperation::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(McpPendingOperation::UpdatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(McpPendingOperation::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_mcp_pending_operation_user")
+                            .from(McpPendingOperation::Table, McpPendingOperation::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_mcp_pending_operation_user_status")
+                            .col(McpPendingOperation::UserId)
+                            .col(McpPendingOperation::Status),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(McpPendingOperation::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum McpPendingOperation {
+    Table,
+    Id,
+    Operation,
+    TargetId,
+    TargetUpdatedAt,
+    Payload,
+    BalanceImpact,
+    Status,
+    Result,
+    Message,
+    ExpiresAt,
+    DecidedAt,
+    CreatedAt,
+    UpdatedAt,
+    UserId,
+}
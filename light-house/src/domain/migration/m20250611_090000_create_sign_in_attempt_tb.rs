@@ -0,0 +1,109 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20250512_114434_create_user_tb::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // audit trail, one row per sign in attempt whatever its outcome
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SignInAttempt::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SignInAttempt::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    // as typed, lower cased, the account may not exist
+                    .col(string_len(SignInAttempt::Email, 255).not_null())
+                    .col(
+                        ColumnDef::new(SignInAttempt::IpAddress)
+                            .string_len(45)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SignInAttempt::UserAgent)
+                            .string_len(255)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SignInAttempt::Succeeded)
+                            .boolean()
+                            .not_null(),
+                    )
+                    // see SignInFailureVariant
+                    .col(
+                        ColumnDef::new(SignInAttempt::FailureReason)
+                            .string_len(50)
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SignInAttempt::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    // only set when the email belongs to an account
+                    .col(
+                        ColumnDef::new(SignInAttempt::UserId)
+                            .uuid()
+                            .null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_sign_in_attempt_user")
+                            .from(SignInAttempt::Table, SignInAttempt::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_sign_in_attempt_email_created_at")
+                            .col(SignInAttempt::Email)
+                            .col(SignInAttempt::CreatedAt),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_sign_in_attempt_ip_created_at")
+                            .col(SignInAttempt::IpAddress)
+                            .col(SignInAttempt::CreatedAt),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_sign_in_attempt_user")
+                            .col(SignInAttempt::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(SignInAttempt::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum SignInAttempt {
+    Table,
+    Id,
+    Email,
+    IpAddress,
+    UserAgent,
+    Succeeded,
+    FailureReason,
+    CreatedAt,
+    UserId,
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // failed sign in counters of the database limiter, keyed by account or by ip
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(SignInThrottle::Table)
+                    .if_not_exists()
+                    .col(string_len(SignInThrottle::ThrottleKey, 300).not_null().primary_key())
+                    // consecutive failures, reset by a success or once the last failure is old enough
+                    .col(
+                        ColumnDef::new(SignInThrottle::FailureCount)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(SignInThrottle::LastFailureAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(SignInThrottle::BlockedUntil)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(SignInThrottle::UpdatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_sign_in_throttle_last_failure_at")
+                            .col(SignInThrottle::LastFailureAt),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(SignInThrottle::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum SignInThrottle {
+    Table,
+    ThrottleKey,
+    FailureCount,
+    LastFailureAt,
+    BlockedUntil,
+    UpdatedAt,
+}
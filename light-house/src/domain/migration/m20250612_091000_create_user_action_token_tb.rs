@@ -0,0 +1,90 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20250512_114434_create_user_tb::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // single use tokens mailed to the user, to verify their email or reset their password
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserActionToken::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserActionToken::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    // sha-256 of the mailed token in hex
+                    .col(string_len_uniq(UserActionToken::TokenHash, 64).not_null())
+                    // see ActionTokenPurposeVariant
+                    .col(string_len(UserActionToken::Purpose, 30).not_null())
+                    // the address the token was mailed to
+                    .col(string_len(UserActionToken::Email, 255).not_null())
+                    .col(
+                        ColumnDef::new(UserActionToken::ExpiresAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserActionToken::UsedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserActionToken::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(UserActionToken::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_action_token_user")
+                            .from(UserActionToken::Table, UserActionToken::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_user_action_token_user_purpose")
+                            .col(UserActionToken::UserId)
+                            .col(UserActionToken::Purpose),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(UserActionToken::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum UserActionToken {
+    Table,
+    Id,
+    TokenHash,
+    Purpose,
+    Email,
+    ExpiresAt,
+    UsedAt,
+    CreatedAt,
+    UserId,
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20250512_114434_create_user_tb::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // totp secret of a user, two factor sign in is on once the enrolment is confirmed
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(UserTwoFactor::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(UserTwoFactor::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    // base32, as shown to the authenticator app
+                    .col(string_len(UserTwoFactor::Secret, 64).not_null())
+                    // null while the enrolment waits for its first code
+                    .col(
+                        ColumnDef::new(UserTwoFactor::ConfirmedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    // time step of the last accepted code, a code is never accepted twice
+                    .col(
+                        ColumnDef::new(UserTwoFactor::LastUsedStep)
+                            .big_integer()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(UserTwoFactor::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(UserTwoFactor::UpdatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(UserTwoFactor::UserId)
+                            .uuid()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_user_two_factor_user")
+                            .from(UserTwoFactor::Table, UserTwoFactor::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(UserTwoFactor::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum UserTwoFactor {
+    Table,
+    Id,
+    Secret,
+    ConfirmedAt,
+    LastUsedStep,
+    CreatedAt,
+    UpdatedAt,
+    UserId,
+}
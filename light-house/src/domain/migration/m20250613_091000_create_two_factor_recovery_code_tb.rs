@@ -0,0 +1,77 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20250512_114434_create_user_tb::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // one time codes standing in for the authenticator app, shown once when they are generated
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TwoFactorRecoveryCode::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TwoFactorRecoveryCode::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    // sha-256 of the code in hex
+                    .col(string_len_uniq(TwoFactorRecoveryCode::CodeHash, 64).not_null())
+                    .col(
+                        ColumnDef::new(TwoFactorRecoveryCode::UsedAt)
+                            .timestamp()
+                            .null(),
+                    )
+                    .col(
+                        ColumnDef::new(TwoFactorRecoveryCode::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(TwoFactorRecoveryCode::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_two_factor_recovery_code_user")
+                            .from(TwoFactorRecoveryCode::Table, TwoFactorRecoveryCode::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_two_factor_recovery_code_user")
+                            .col(TwoFactorRecoveryCode::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(TwoFactorRecoveryCode::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum TwoFactorRecoveryCode {
+    Table,
+    Id,
+    CodeHash,
+    UsedAt,
+    CreatedAt,
+    UserId,
+}
@@ -0,0 +1,81 @@
+use sea_orm_migration::prelude::*;
+
+use super::{m20250512_135752_create_transaction_tb::Transaction, m20250614_090000_create_tag_tb::Tag};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(TransactionTag::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TransactionTag::TransactionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_transaction_tag_transaction")
+                            .from(TransactionTag::Table, TransactionTag::TransactionId)
+                            .to(Transaction::Table, Transaction::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionTag::TagId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_transaction_tag_tag")
+                            .from(TransactionTag::Table, TransactionTag::TagId)
+                            .to(Tag::Table, Tag::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .primary_key(
+                        Index::create()
+                            .col(TransactionTag::TransactionId)
+                            .col(TransactionTag::TagId),
+                    )
+                    .col(
+                        ColumnDef::new(TransactionTag::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    // reverse lookup for the tag filter and the grouped reports
+                    .index(
+                        Index::create()
+                            .name("idx_transaction_tag_tag")
+                            .col(TransactionTag::TagId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(TransactionTag::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum TransactionTag {
+    Table,
+    TransactionId,
+    TagId,
+    CreatedAt,
+}
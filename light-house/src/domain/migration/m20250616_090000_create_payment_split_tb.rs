@@ -0,0 +1,103 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::{m20250512_132512_create_expense_tb::Expense, m20250512_135752_create_transaction_tb::Transaction};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PaymentSplit::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PaymentSplit::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PaymentSplit::TransactionId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payment_split_transaction")
+                            .from(PaymentSplit::Table, PaymentSplit::TransactionId)
+                            .to(Transaction::Table, Transaction::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .col(
+                        ColumnDef::new(PaymentSplit::ExpenseId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_payment_split_expense")
+                            .from(PaymentSplit::Table, PaymentSplit::ExpenseId)
+                            .to(Expense::Table, Expense::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    // the lines of one payment add up to its amount
+                    .col(
+                        ColumnDef::new(PaymentSplit::Amount)
+                            .decimal_len(19, 4)
+                            .not_null(),
+                    )
+                    .col(string_len_null(PaymentSplit::Note, 255))
+                    // keeps the lines in the order they were given
+                    .col(
+                        ColumnDef::new(PaymentSplit::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PaymentSplit::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_payment_split_transaction")
+                            .col(PaymentSplit::TransactionId),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_payment_split_expense")
+                            .col(PaymentSplit::ExpenseId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(PaymentSplit::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum PaymentSplit {
+    Table,
+    Id,
+    TransactionId,
+    ExpenseId,
+    Amount,
+    Note,
+    Position,
+    CreatedAt,
+}
@@ -0,0 +1,95 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20250512_114434_create_user_tb::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // receipts and documents of a transaction, contact or asset, the file itself lives in the attachment storage
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(Attachment::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Attachment::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    // "transaction", "contact" or "asset", owner_id points into that table
+                    .col(string_len(Attachment::OwnerType, 20).not_null())
+                    .col(
+                        ColumnDef::new(Attachment::OwnerId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .col(string_len(Attachment::FileName, 255).not_null())
+                    .col(string_len(Attachment::ContentType, 127).not_null())
+                    .col(big_integer(Attachment::SizeBytes).not_null())
+                    // hex sha-256 of the content
+                    .col(string_len(Attachment::Sha256, 64).not_null())
+                    // where the content sits in the storage backend
+                    .col(string_len(Attachment::StorageKey, 255).not_null())
+                    .col(
+                        ColumnDef::new(Attachment::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(Attachment::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_attachment_user")
+                            .from(Attachment::Table, Attachment::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_attachment_owner")
+                            .col(Attachment::OwnerType)
+                            .col(Attachment::OwnerId),
+                    )
+                    .index(
+                        Index::create()
+                            .name("idx_attachment_user")
+                            .col(Attachment::UserId),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(Attachment::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum Attachment {
+    Table,
+    Id,
+    OwnerType,
+    OwnerId,
+    FileName,
+    ContentType,
+    SizeBytes,
+    Sha256,
+    StorageKey,
+    CreatedAt,
+    UserId,
+}
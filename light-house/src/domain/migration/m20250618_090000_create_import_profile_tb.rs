@@ -0,0 +1,109 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use super::m20250512_114434_create_user_tb::User;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // how the csv statement of one bank is read, columns are header names or 1-based positions
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(ImportProfile::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ImportProfile::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(string_len(ImportProfile::Name, 50).not_null())
+                    .col(string_len(ImportProfile::Delimiter, 1).not_null().default(","))
+                    .col(
+                        ColumnDef::new(ImportProfile::HasHeader)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    // lines before the header, banks like to put the account details there
+                    .col(integer(ImportProfile::SkipRows).not_null().default(0))
+                    .col(string_len(ImportProfile::DateColumn, 100).not_null())
+                    // chrono format such as %d/%m/%Y
+                    .col(string_len(ImportProfile::DateFormat, 50).not_null())
+                    // a signed amount column, or separate debit and credit columns
+                    .col(string_len_null(ImportProfile::AmountColumn, 100))
+                    .col(string_len_null(ImportProfile::DebitColumn, 100))
+                    .col(string_len_null(ImportProfile::CreditColumn, 100))
+                    .col(string_len(ImportProfile::DescriptionColumn, 100).not_null())
+                    .col(string_len(ImportProfile::DecimalSeparator, 1).not_null().default("."))
+                    // WHATWG label such as utf-8, windows-1252 or windows-874
+                    .col(string_len(ImportProfile::Encoding, 40).not_null().default("utf-8"))
+                    .col(
+                        ColumnDef::new(ImportProfile::CreatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(ImportProfile::UpdatedAt)
+                            .timestamp()
+                            .extra("DEFAULT CURRENT_TIMESTAMP".to_owned()),
+                    )
+                    .col(
+                        ColumnDef::new(ImportProfile::UserId)
+                            .uuid()
+                            .not_null(),
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_import_profile_user")
+                            .from(ImportProfile::Table, ImportProfile::UserId)
+                            .to(User::Table, User::Id)
+                            .on_delete(ForeignKeyAction::Restrict)
+                            .on_update(ForeignKeyAction::Cascade),
+                    )
+                    .index(
+                        Index::create()
+                            .name("uq_import_profile_user_name")
+                            .col(ImportProfile::UserId)
+                            .col(ImportProfile::Name)
+                            .unique(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        
+
+        manager
+            .drop_table(Table::drop().table(ImportProfile::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+#[sea_orm(rename_all = "snake_case")]
+pub enum ImportProfile {
+    Table,
+    Id,
+    Name,
+    Delimiter,
+    HasHeader,
+    SkipRows,
+    DateColumn,
+    DateFormat,
+    AmountColumn,
+    DebitColumn,
+    CreditColumn,
+    DescriptionColumn,
+    DecimalSeparator,
+    Encoding,
+    CreatedAt,
+    UpdatedAt,
+    UserId,
+}
@@ -0,0 +1,60 @@
+use sea_orm_migration::prelude::*;
+
+use super::m20250512_135752_create_transaction_tb::Transaction;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // the bank's own id of an imported line (OFX FITID, QIF check number, ...),
+        // null for everything entered by hand
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .add_column(
+                        ColumnDef::new(Transaction::ExternalReference)
+                            .string_len(255)
+                            .null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // a bank id is stored once per asset, hand entered rows stay null and never collide
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_transaction_asset_external_reference")
+                    .table(Transaction::Table)
+                    .unique()
+                    .col(Transaction::UserId)
+                    .col(Transaction::AssetId)
+                    .col(Transaction::ExternalReference)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_transaction_asset_external_reference")
+                    .table(Transaction::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Transaction::Table)
+                    .drop_column(Transaction::ExternalReference)
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+use super::{m20250512_114434_create_user_tb::User, m20250608_090000_create_api_token_tb::ApiToken};
+
+// name of the api token each legacy mcp token is moved into
+const LEGACY_TOKEN_NAME: &str = "Legacy MCP token";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // every plain text token handed out at sign up becomes a hashed api token,
+        // clients keep working and the user can now see and revoke it,
+        // a bcrypt value left by the old regenerate was never usable and is dropped
+        manager
+            .exec_stmt(
+                Query::insert()
+                    .into_table(ApiToken::Table)
+                    .columns([
+                        ApiToken::Id,
+                        ApiToken::Name,
+                        ApiToken::TokenHash,
+                        ApiToken::TokenPrefix,
+                        ApiToken::Scopes,
+                        ApiToken::UserId,
+                    ])
+                    .select_from(
+                        Query::select()
+                            .expr(Expr::cust("UUID_TO_BIN(UUID())"))
+                            .expr(Expr::val(LEGACY_TOKEN_NAME))
+                            .expr(Expr::cust_with_exprs("SHA2($1, 256)", [Expr::col(User::McpToken).into()]))
+                            .expr(Expr::cust_with_exprs("LEFT($1, 12)", [Expr::col(User::McpToken).into()]))
+                            .expr(Expr::cust("JSON_ARRAY('read', 'write:transactions', 'write:contacts', 'write:expenses', 'reports')"))
+                            .column(User::Id)
+                            .from(User::Table)
+                            .and_where(Expr::col(User::McpToken).ne(""))
+                            .and_where(Expr::col(User::McpToken).not_like("$2%"))
+                            .to_owned(),
+                    )
+                    .map_err(|err| DbErr::Migration(err.to_string()))?
+                    .to_owned(),
+            )
+            .await?;
+
+        // the plain text copy goes away with the column
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .drop_column(User::McpToken)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // only the hash was kept, the moved tokens stay api tokens
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(User::Table)
+                    .add_column(
+                        ColumnDef::new(User::McpToken)
+                            .string()
+                            .null()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+}
@@ -13,6 +13,32 @@ mod m20250512_134954_create_transaction_type_tb;
 mod m20250512_135752_create_transaction_tb;
 mod m20250512_142622_create_current_sheet;
 mod m20250512_143438_create_user_contact;
+mod m20250601_090000_alter_money_to_decimal;
+mod m20250602_090000_create_journal_entry_tb;
+mod m20250602_091000_create_journal_line_tb;
+mod m20250603_090000_alter_current_sheet_last_transaction_fk;
+mod m20250604_090000_add_occurred_at_to_transaction;
+mod m20250605_090000_create_budget_tb;
+mod m20250605_091000_create_budget_period_tb;
+mod m20250606_090000_create_recurring_transaction_tb;
+mod m20250606_091000_create_recurring_occurrence_tb;
+mod m20250607_090000_create_mcp_pending_operation_tb;
+mod m20250608_090000_create_api_token_tb;
+mod m20250609_090000_create_user_session_tb;
+mod m20250610_090000_add_disabled_at_to_user;
+mod m20250611_090000_create_sign_in_attempt_tb;
+mod m20250611_091000_create_sign_in_throttle_tb;
+mod m20250612_090000_add_email_verified_at_to_user;
+mod m20250612_091000_create_user_action_token_tb;
+mod m20250613_090000_create_user_two_factor_tb;
+mod m20250613_091000_create_two_factor_recovery_code_tb;
+mod m20250614_090000_create_tag_tb;
+mod m20250614_091000_create_transaction_tag_tb;
+mod m20250616_090000_create_payment_split_tb;
+mod m20250617_090000_create_attachment_tb;
+mod m20250618_090000_create_import_profile_tb;
+mod m20250619_090000_add_external_reference_to_transaction;
+mod m20250620_090000_move_mcp_token_to_api_token;
 
 pub struct Migrator;
 
@@ -36,6 +62,33 @@ impl MigratorTrait for Migrator {
             // 3rd teir
             Box::new(m20250512_135752_create_transaction_tb::Migration),
             Box::new(m20250512_142622_create_current_sheet::Migration),
+            // 4th teir
+            Box::new(m20250601_090000_alter_money_to_decimal::Migration),
+            Box::new(m20250602_090000_create_journal_entry_tb::Migration),
+            Box::new(m20250602_091000_create_journal_line_tb::Migration),
+            Box::new(m20250603_090000_alter_current_sheet_last_transaction_fk::Migration),
+            Box::new(m20250604_090000_add_occurred_at_to_transaction::Migration),
+            Box::new(m20250605_090000_create_budget_tb::Migration),
+            Box::new(m20250605_091000_create_budget_period_tb::Migration),
+            Box::new(m20250606_090000_create_recurring_transaction_tb::Migration),
+            Box::new(m20250606_091000_create_recurring_occurrence_tb::Migration),
+            Box::new(m20250607_090000_create_mcp_pending_operation_tb::Migration),
+            Box::new(m20250608_090000_create_api_token_tb::Migration),
+            Box::new(m20250609_090000_create_user_session_tb::Migration),
+            Box::new(m20250610_090000_add_disabled_at_to_user::Migration),
+            Box::new(m20250611_090000_create_sign_in_attempt_tb::Migration),
+            Box::new(m20250611_091000_create_sign_in_throttle_tb::Migration),
+            Box::new(m20250612_090000_add_email_verified_at_to_user::Migration),
+            Box::new(m20250612_091000_create_user_action_token_tb::Migration),
+            Box::new(m20250613_090000_create_user_two_factor_tb::Migration),
+            Box::new(m20250613_091000_create_two_factor_recovery_code_tb::Migration),
+            Box::new(m20250614_090000_create_tag_tb::Migration),
+            Box::new(m20250614_091000_create_transaction_tag_tb::Migration),
+            Box::new(m20250616_090000_create_payment_split_tb::Migration),
+            Box::new(m20250617_090000_create_attachment_tb::Migration),
+            Box::new(m20250618_090000_create_import_profile_tb::Migration),
+            Box::new(m20250619_090000_add_external_reference_to_transaction::Migration),
+            Box::new(m20250620_090000_move_mcp_token_to_api_token::Migration),
         ]
     }
 }
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::{dto::{account_dto::CreateActionTokenDto, dto_enum::action_token_purpose::ActionTokenPurposeVariant}, entities::user_action_token}, soc::soc_repository::RepositoryError};
+
+
+
+
+// single use tokens mailed for email verification and password reset
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait ActionTokenRepository: Send + Sync {
+    async fn create_action_token(&self, user_id: Uuid, dto: CreateActionTokenDto) -> Result<user_action_token::Model, RepositoryError>;
+    async fn find_action_token_by_hash(&self, token_hash: &str) -> Result<Option<user_action_token::Model>, RepositoryError>;
+    // the most recent token of the purpose, used or not
+    async fn find_latest_action_token(&self, user_id: Uuid, purpose: ActionTokenPurposeVariant) -> Result<Option<user_action_token::Model>, RepositoryError>;
+    // false when the token was already used or has expired, of two concurrent calls only one wins
+    async fn consume_action_token(&self, token_id: Uuid, now: DateTime<Utc>) -> Result<bool, RepositoryError>;
+    // marks the unused tokens of the purpose as used, returns how many
+    async fn invalidate_action_tokens(&self, user_id: Uuid, purpose: ActionTokenPurposeVariant, now: DateTime<Utc>) -> Result<u64, RepositoryError>;
+    // false when the email of the user changed since the token was mailed
+    async fn mark_email_verified(&self, user_id: Uuid, email: &str, now: DateTime<Utc>) -> Result<bool, RepositoryError>;
+}
\ No newline at end of file
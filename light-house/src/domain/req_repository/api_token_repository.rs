@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::{dto::api_token_dto::CreateApiTokenDto, entities::api_token}, soc::soc_repository::RepositoryError};
+
+
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait ApiTokenRepositoryBase {
+    async fn create(&self, user_id: Uuid, dto: CreateApiTokenDto) -> Result<api_token::Model, RepositoryError>;
+    async fn find_by_id(&self, user_id: Uuid, token_id: Uuid) -> Result<Option<api_token::Model>, RepositoryError>;
+    async fn find_all_by_user_id(&self, user_id: Uuid) -> Result<Vec<api_token::Model>, RepositoryError>;
+    // NotFound when the token does not belong to the user, revoking twice keeps the first revoked_at
+    async fn revoke(&self, user_id: Uuid, token_id: Uuid, now: DateTime<Utc>) -> Result<api_token::Model, RepositoryError>;
+}
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait ApiTokenRepositoryUtility {
+    async fn find_by_token_hash(&self, token_hash: &str) -> Result<Option<api_token::Model>, RepositoryError>;
+    // records when and from where the token was last used
+    async fn touch(&self, token_id: Uuid, now: DateTime<Utc>, ip: Option<String>) -> Result<(), RepositoryError>;
+}
@@ -0,0 +1,30 @@
+use uuid::Uuid;
+
+use crate::{domain::{dto::{attachment_dto::CreateAttachmentDto, dto_enum::attachment_owner::AttachmentOwnerVariant}, entities::attachment}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+#[async_trait::async_trait]
+pub trait AttachmentRepositoryUtility: Send + Sync {
+    async fn find_by_user_id_and_attachment_id(&self, user_id: Uuid, attachment_id: Uuid) -> Result<Option<attachment::Model>, RepositoryError>;
+    // attachments of one record, newest first
+    async fn find_all_by_owner(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid) -> Result<Vec<attachment::Model>, RepositoryError>;
+    // number of attachments of the user and their total size in bytes
+    async fn usage_by_user(&self, user_id: Uuid) -> Result<(i64, i64), RepositoryError>;
+    // whether the user owns the transaction, contact or asset
+    async fn owner_exists(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid) -> Result<bool, RepositoryError>;
+    // attachments whose transaction, contact or asset has been deleted, oldest first
+    async fn find_orphans(&self, limit: u64) -> Result<Vec<attachment::Model>, RepositoryError>;
+}
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait AttachmentRepositoryBase: Send + Sync {
+    // None when the file would take the user past quota_bytes, checked with the user row locked
+    // so parallel uploads can not both fit into the same free space
+    async fn create(&self, user_id: Uuid, dto: CreateAttachmentDto, quota_bytes: i64) -> Result<Option<attachment::Model>, RepositoryError>;
+    // removes the row only, the content is deleted from the storage by the caller
+    async fn delete(&self, attachment_id: Uuid) -> Result<(), RepositoryError>;
+}
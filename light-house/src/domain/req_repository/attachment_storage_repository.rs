@@ -0,0 +1,15 @@
+use crate::soc::soc_repository::RepositoryError;
+
+
+
+
+// where attachment content lives, on the local disk or in an S3 compatible bucket (ATTACHMENT_STORAGE),
+// keys are made by the use case and never come from the client
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait AttachmentStorage: Send + Sync {
+    async fn put(&self, key: String, content: Vec<u8>, content_type: String) -> Result<(), RepositoryError>;
+    async fn get(&self, key: String) -> Result<Vec<u8>, RepositoryError>;
+    // a missing object is not an error
+    async fn delete(&self, key: String) -> Result<(), RepositoryError>;
+}
@@ -1,4 +1,7 @@
-use crate::{domain::dto::auth_dto::{ReqSignInDto, ResSignInDto}, soc::soc_repository::RepositoryError};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::{dto::auth_dto::ReqSignInDto, entities::user}, soc::soc_repository::RepositoryError};
 
 
 
@@ -7,6 +10,13 @@ use crate::{domain::dto::auth_dto::{ReqSignInDto, ResSignInDto}, soc::soc_reposi
 #[async_trait::async_trait]
 pub trait AuthRepository: Send + Sync {
     
-    async fn sign_in(&self, sign_in_dto: ReqSignInDto) -> Result<ResSignInDto, RepositoryError>;
+    // the user the email and password belong to
+    async fn sign_in(&self, sign_in_dto: ReqSignInDto) -> Result<user::Model, RepositoryError>;
+    // HS512 access token of the session carrying the role of the user, valid until expires_at
+    async fn generate_access_token(&self, user_id: Uuid, session_id: Uuid, role: &str, expires_at: DateTime<Utc>) -> Result<String, RepositoryError>;
+    // whether the password is the current one of the user
+    async fn verify_password(&self, user_id: Uuid, password: &str) -> Result<bool, RepositoryError>;
+    // hashes and stores a new password
+    async fn set_password(&self, user_id: Uuid, password: &str) -> Result<(), RepositoryError>;
 
 }
\ No newline at end of file
@@ -1,6 +1,9 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::DatabaseTransaction;
 use uuid::Uuid;
 
-use crate::{domain::entities::current_sheet, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::current_sheet_dto::{BalanceMovementDto, CurrentSheetReconcileDto}, entities::current_sheet}, soc::soc_repository::RepositoryError};
 
 
 
@@ -11,9 +14,11 @@ pub trait BalanceRepositoryBase {
 
     async fn get_current_sheet_by_asset_id(&self, user_id: Uuid, asset_id: Uuid) -> Result<Option<current_sheet::Model>, RepositoryError>;
     async fn get_all_current_sheets_by_user(&self, user_id: Uuid) -> Result<Vec<current_sheet::Model>, RepositoryError>;
-    async fn create_current_sheet(&self, user_id: Uuid, asset_id: Uuid, initial_balance: f64) -> Result<current_sheet::Model, RepositoryError>;
-    async fn update_current_sheet(&self, user_id: Uuid, asset_id: Uuid, balance: Option<f64>) -> Result<current_sheet::Model, RepositoryError>;
-    async fn delete_current_sheet_by_asset_id(&self, user_id: Uuid, asset_id: Uuid) -> Result<(), RepositoryError>;
+    // writes run inside the caller's txn so they commit or roll back together with the rest of the change
+    async fn lock_current_sheet_by_asset_id(&self, txn: &DatabaseTransaction, user_id: Uuid, asset_id: Uuid) -> Result<Option<current_sheet::Model>, RepositoryError>;
+    async fn create_current_sheet(&self, txn: &DatabaseTransaction, user_id: Uuid, asset_id: Uuid, initial_balance: Decimal) -> Result<current_sheet::Model, RepositoryError>;
+    async fn update_current_sheet(&self, txn: &DatabaseTransaction, user_id: Uuid, asset_id: Uuid, balance: Option<Decimal>) -> Result<current_sheet::Model, RepositoryError>;
+    async fn delete_current_sheet_by_asset_id(&self, txn: &DatabaseTransaction, user_id: Uuid, asset_id: Uuid) -> Result<(), RepositoryError>;
     async fn get_current_sheet_by_id(&self, user_id: Uuid, current_sheet_id: Uuid) -> Result<Option<current_sheet::Model>, RepositoryError>;
 }
 
@@ -22,4 +27,7 @@ pub trait BalanceRepositoryBase {
 pub trait BalanceRepositoryUtill {
     async fn get_all_current_sheets_by_asset_type_id(&self, user_id: Uuid, asset_type_id: Uuid) -> Result<Vec<current_sheet::Model>, RepositoryError>;
     async fn get_all_current_sheets_by_asset_id(&self, user_id: Uuid, asset_id: Uuid) -> Result<Vec<current_sheet::Model>, RepositoryError>;
+    async fn rebuild_current_sheets(&self, user_id: Uuid, asset_id: Option<Uuid>, dry_run: bool) -> Result<Vec<CurrentSheetReconcileDto>, RepositoryError>;
+    async fn get_balance_as_of(&self, user_id: Uuid, asset_id: Uuid, as_of: DateTime<Utc>) -> Result<Decimal, RepositoryError>;
+    async fn get_balance_movements(&self, user_id: Uuid, asset_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<BalanceMovementDto>, RepositoryError>;
 }
\ No newline at end of file
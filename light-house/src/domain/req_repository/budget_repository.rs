@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use uuid::Uuid;
+
+use crate::{domain::{dto::budget_dto::{CreateBudgetDto, UpdateBudgetDto}, entities::{budget, budget_period, transaction}}, soc::soc_repository::RepositoryError};
+
+
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait BudgetRepositoryBase {
+    async fn create(&self, user_id: Uuid, dto: CreateBudgetDto) -> Result<budget::Model, RepositoryError>;
+    async fn find_by_id(&self, user_id: Uuid, budget_id: Uuid) -> Result<Option<budget::Model>, RepositoryError>;
+    async fn find_all_by_user_id(&self, user_id: Uuid) -> Result<Vec<budget::Model>, RepositoryError>;
+    // a new amount is written to the periods that have not ended yet
+    async fn update(&self, user_id: Uuid, budget_id: Uuid, dto: UpdateBudgetDto) -> Result<budget::Model, RepositoryError>;
+    async fn delete(&self, user_id: Uuid, budget_id: Uuid) -> Result<(), RepositoryError>;
+}
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait BudgetRepositoryUtility {
+    async fn find_periods(&self, budget_id: Uuid) -> Result<Vec<budget_period::Model>, RepositoryError>;
+    async fn create_period(&self, budget_id: Uuid, period_start: DateTime<Utc>, period_end: DateTime<Utc>, amount: Decimal) -> Result<budget_period::Model, RepositoryError>;
+    async fn update_period(&self, period_id: Uuid, period_end: DateTime<Utc>, rollover_amount: Decimal) -> Result<budget_period::Model, RepositoryError>;
+    async fn delete_periods_starting_after(&self, budget_id: Uuid, after: DateTime<Utc>) -> Result<(), RepositoryError>;
+    // payments whose expense belongs to the expense type, a split payment only counts its lines on that type
+    async fn sum_spent(&self, user_id: Uuid, expense_type_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Decimal, RepositoryError>;
+    // the same payments oldest first, amount holds the part spent on the expense type
+    async fn find_spending(&self, user_id: Uuid, expense_type_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<transaction::Model>, RepositoryError>;
+}
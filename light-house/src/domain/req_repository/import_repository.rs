@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::{dto::{dto_enum::transaction_type::TransactionTypeVariant, import_dto::{ImportCandidateDto, ReqCreateImportProfileDto, ReqUpdateImportProfileDto}}, entities::import_profile}, soc::soc_repository::RepositoryError};
+
+
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait ImportProfileRepositoryBase {
+    async fn create(&self, user_id: Uuid, dto: ReqCreateImportProfileDto) -> Result<import_profile::Model, RepositoryError>;
+    // an empty amount_column, debit_column or credit_column clears it
+    async fn update(&self, user_id: Uuid, profile_id: Uuid, dto: ReqUpdateImportProfileDto) -> Result<import_profile::Model, RepositoryError>;
+    async fn delete(&self, user_id: Uuid, profile_id: Uuid) -> Result<(), RepositoryError>;
+}
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait ImportRepositoryUtility {
+    async fn find_all_profiles_by_user_id(&self, user_id: Uuid) -> Result<Vec<import_profile::Model>, RepositoryError>;
+    async fn find_profile_by_user_id_and_profile_id(&self, user_id: Uuid, profile_id: Uuid) -> Result<Option<import_profile::Model>, RepositoryError>;
+    async fn is_asset_owned(&self, user_id: Uuid, asset_id: Uuid) -> Result<bool, RepositoryError>;
+    async fn find_transaction_type_id(&self, transaction_type: TransactionTypeVariant) -> Result<Uuid, RepositoryError>;
+    // incomes and payments of the asset with occurred_at in [from, to],
+    // or carrying one of the external references whatever their date
+    async fn find_import_candidates(&self, user_id: Uuid, asset_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, external_references: &[String]) -> Result<Vec<ImportCandidateDto>, RepositoryError>;
+}
@@ -0,0 +1,16 @@
+use sea_orm::DatabaseTransaction;
+use uuid::Uuid;
+
+use crate::{domain::{dto::ledger_dto::ReqPostJournalEntryDto, entities::{journal_entry, journal_line}}, soc::soc_repository::RepositoryError};
+
+
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait LedgerRepositoryBase: Send + Sync {
+    // Validates the entry is balanced, writes it and applies asset lines to current_sheet inside txn
+    async fn post_entry(&self, txn: &DatabaseTransaction, user_id: Uuid, entry: ReqPostJournalEntryDto) -> Result<journal_entry::Model, RepositoryError>;
+    async fn get_entries_by_transaction_id(&self, user_id: Uuid, transaction_id: Uuid) -> Result<Vec<journal_entry::Model>, RepositoryError>;
+    async fn get_lines_by_entry_id(&self, journal_entry_id: Uuid) -> Result<Vec<journal_line::Model>, RepositoryError>;
+}
@@ -0,0 +1,11 @@
+use crate::{domain::dto::account_dto::MailMessageDto, soc::soc_repository::RepositoryError};
+
+
+
+
+// how mail leaves the application, over smtp or into files for local development (MAILER)
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, message: MailMessageDto) -> Result<(), RepositoryError>;
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use sea_orm::prelude::Json;
+use uuid::Uuid;
+
+use crate::{domain::{dto::{dto_enum::{mcp_operation::{McpOperationStatusVariant, McpOperationVariant}, transaction_type::TransactionTypeVariant}, mcp_operation_dto::{CreateMcpOperationDto, McpAssetBalanceDto, McpTransactionSnapshotDto}}, entities::mcp_pending_operation}, soc::soc_repository::RepositoryError};
+
+
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait McpOperationRepositoryBase {
+    async fn create(&self, user_id: Uuid, dto: CreateMcpOperationDto) -> Result<mcp_pending_operation::Model, RepositoryError>;
+    async fn find_by_id(&self, user_id: Uuid, operation_id: Uuid) -> Result<Option<mcp_pending_operation::Model>, RepositoryError>;
+    async fn find_all_by_user_id(&self, user_id: Uuid, status: Option<McpOperationStatusVariant>) -> Result<Vec<mcp_pending_operation::Model>, RepositoryError>;
+    // false when the operation is no longer in `from`, so two confirmations cannot both run it
+    async fn update_status(&self, user_id: Uuid, operation_id: Uuid, from: McpOperationStatusVariant, to: McpOperationStatusVariant, result: Option<Json>, message: Option<String>) -> Result<bool, RepositoryError>;
+    // marks the user's pending operations past expires_at as expired, returns how many
+    async fn expire_pending(&self, user_id: Uuid, now: DateTime<Utc>) -> Result<u64, RepositoryError>;
+}
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait McpOperationRepositoryUtility {
+    async fn find_transaction_type_id(&self, transaction_type: TransactionTypeVariant) -> Result<Uuid, RepositoryError>;
+    async fn find_transaction_snapshot(&self, user_id: Uuid, transaction_id: Uuid) -> Result<Option<McpTransactionSnapshotDto>, RepositoryError>;
+    // updated_at of the transaction, contact or expense the operation changes, NotFound when it is gone
+    async fn find_target_updated_at(&self, user_id: Uuid, operation: McpOperationVariant, target_id: Uuid) -> Result<Option<DateTime<Utc>>, RepositoryError>;
+    // InvalidInput when an asset does not belong to the user
+    async fn find_asset_balances(&self, user_id: Uuid, asset_ids: Vec<Uuid>) -> Result<Vec<McpAssetBalanceDto>, RepositoryError>;
+}
@@ -12,3 +12,22 @@ pub mod balance_repository;
 pub mod user_role_repository;
 pub mod query_repository;
 pub mod gender_repository;
+pub mod ledger_repository;
+
+pub mod report_repository;
+pub mod budget_repository;
+pub mod recurring_transaction_repository;
+pub mod mcp_operation_repository;
+pub mod api_token_repository;
+pub mod session_repository;
+pub mod sign_in_attempt_repository;
+pub mod sign_in_limiter_repository;
+pub mod action_token_repository;
+pub mod mailer_repository;
+pub mod two_factor_repository;
+pub mod user_data_repository;
+pub mod tag_repository;
+pub mod payment_split_repository;
+pub mod attachment_storage_repository;
+pub mod attachment_repository;
+pub mod import_repository;
\ No newline at end of file
@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+
+use sea_orm::DatabaseTransaction;
+use uuid::Uuid;
+
+use crate::{domain::{dto::transaction_dto::ReqPaymentSplitDto, entities::payment_split}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+// split lines of a payment, the writes join the caller's database transaction
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait PaymentSplitRepositoryUtility: Send + Sync {
+    // replaces every split line of the payment, kept in the given order
+    async fn set_payment_splits(&self, txn: &DatabaseTransaction, transaction_id: Uuid, splits: Vec<ReqPaymentSplitDto>) -> Result<Vec<payment_split::Model>, RepositoryError>;
+    async fn find_payment_splits(&self, txn: &DatabaseTransaction, transaction_id: Uuid) -> Result<Vec<payment_split::Model>, RepositoryError>;
+    async fn delete_payment_splits(&self, txn: &DatabaseTransaction, transaction_id: Uuid) -> Result<(), RepositoryError>;
+    // split lines of every given payment keyed by binary transaction id, in line order
+    async fn find_splits_by_transaction_ids(&self, transaction_ids: Vec<Vec<u8>>) -> Result<HashMap<Vec<u8>, Vec<payment_split::Model>>, RepositoryError>;
+}
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::{dto::{dto_enum::recurring_status::{RecurringOccurrenceStatusVariant, RecurringStatusVariant}, recurring_transaction_dto::{CreateRecurringTransactionDto, UpdateRecurringTransactionDto}}, entities::{recurring_occurrence, recurring_transaction, transaction}}, soc::soc_repository::RepositoryError};
+
+
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait RecurringTransactionRepositoryBase {
+    // the asset, contact and expense of the template must belong to the user
+    async fn create(&self, user_id: Uuid, dto: CreateRecurringTransactionDto, next_run_at: Option<DateTime<Utc>>) -> Result<recurring_transaction::Model, RepositoryError>;
+    async fn find_by_id(&self, user_id: Uuid, recurring_transaction_id: Uuid) -> Result<Option<recurring_transaction::Model>, RepositoryError>;
+    async fn find_all_by_user_id(&self, user_id: Uuid) -> Result<Vec<recurring_transaction::Model>, RepositoryError>;
+    async fn update(&self, user_id: Uuid, recurring_transaction_id: Uuid, dto: UpdateRecurringTransactionDto) -> Result<recurring_transaction::Model, RepositoryError>;
+    async fn delete(&self, user_id: Uuid, recurring_transaction_id: Uuid) -> Result<(), RepositoryError>;
+}
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait RecurringTransactionRepositoryUtility {
+    // active schedules whose next_run_at is at or before `now`, every user
+    async fn find_due(&self, now: DateTime<Utc>, limit: u64) -> Result<Vec<recurring_transaction::Model>, RepositoryError>;
+    async fn update_schedule_state(&self, recurring_transaction_id: Uuid, status: RecurringStatusVariant, next_run_at: Option<DateTime<Utc>>, last_run_at: Option<DateTime<Utc>>) -> Result<recurring_transaction::Model, RepositoryError>;
+    // None when a row for the occurrence already exists, the unique index makes this the idempotency check
+    async fn claim_occurrence(&self, recurring_transaction_id: Uuid, scheduled_for: DateTime<Utc>, status: RecurringOccurrenceStatusVariant) -> Result<Option<recurring_occurrence::Model>, RepositoryError>;
+    async fn find_occurrence(&self, recurring_transaction_id: Uuid, scheduled_for: DateTime<Utc>) -> Result<Option<recurring_occurrence::Model>, RepositoryError>;
+    async fn find_occurrences(&self, recurring_transaction_id: Uuid, from: Option<DateTime<Utc>>) -> Result<Vec<recurring_occurrence::Model>, RepositoryError>;
+    async fn finish_occurrence(&self, occurrence_id: Uuid, status: RecurringOccurrenceStatusVariant, transaction_id: Option<Uuid>, message: Option<String>) -> Result<recurring_occurrence::Model, RepositoryError>;
+    // moves a pending claim created before `stale_before` to `now`, false when another run got it first
+    async fn take_over_occurrence(&self, occurrence_id: Uuid, stale_before: DateTime<Utc>, now: DateTime<Utc>) -> Result<bool, RepositoryError>;
+    // the transaction posted for the occurrence, found by its external_reference on the template's asset
+    async fn find_posted_transaction(&self, recurring: recurring_transaction::Model, external_reference: &str) -> Result<Option<transaction::Model>, RepositoryError>;
+}
@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::dto::{dto_enum::{expense_report_group::ExpenseReportGroupVariant, report_period::ReportPeriodVariant}, report_dto::{ExpenseGroupTotalDto, ReportGroupTotalDto, ReportPeriodTotalDto}}, soc::soc_repository::RepositoryError};
+
+
+
+
+// aggregation happens in the database, only grouped rows come back
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait ReportRepositoryUtility {
+    async fn sum_by_period_and_type(&self, user_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, period: ReportPeriodVariant) -> Result<Vec<ReportPeriodTotalDto>, RepositoryError>;
+    async fn sum_payments_by_expense_type(&self, user_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, period: ReportPeriodVariant) -> Result<Vec<ReportGroupTotalDto>, RepositoryError>;
+    async fn sum_incomes_by_contact(&self, user_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, period: ReportPeriodVariant) -> Result<Vec<ReportGroupTotalDto>, RepositoryError>;
+    // only payments carrying tag_id when it is given
+    async fn sum_payments_by_group(&self, user_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, group_by: ExpenseReportGroupVariant, tag_id: Option<Uuid>) -> Result<Vec<ExpenseGroupTotalDto>, RepositoryError>;
+}
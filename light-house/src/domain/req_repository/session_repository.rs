@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::{dto::session_dto::{CreateSessionDto, RotateSessionDto}, entities::user_session}, soc::soc_repository::RepositoryError};
+
+
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait SessionRepositoryBase {
+    async fn create_session(&self, user_id: Uuid, dto: CreateSessionDto) -> Result<user_session::Model, RepositoryError>;
+    async fn find_session_by_id(&self, user_id: Uuid, session_id: Uuid) -> Result<Option<user_session::Model>, RepositoryError>;
+    // sessions neither revoked nor expired at `now`, most recently used first
+    async fn find_all_active_session_by_user_id(&self, user_id: Uuid, now: DateTime<Utc>) -> Result<Vec<user_session::Model>, RepositoryError>;
+    // false when the session is not the user's or already revoked
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid, now: DateTime<Utc>) -> Result<bool, RepositoryError>;
+    // revokes every live session of the user except `keep`, returns how many
+    async fn revoke_all_session_by_user_id(&self, user_id: Uuid, keep: Option<Uuid>, now: DateTime<Utc>) -> Result<u64, RepositoryError>;
+}
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait SessionRepositoryUtility {
+    async fn find_by_refresh_token_hash(&self, token_hash: &str) -> Result<Option<user_session::Model>, RepositoryError>;
+    // the session whose previous refresh token this is, a second use of a rotated token
+    async fn find_by_previous_token_hash(&self, token_hash: &str) -> Result<Option<user_session::Model>, RepositoryError>;
+    // false when the refresh token was rotated or revoked in the meantime, so it can only be used once
+    async fn rotate(&self, session_id: Uuid, dto: RotateSessionDto, now: DateTime<Utc>) -> Result<bool, RepositoryError>;
+}
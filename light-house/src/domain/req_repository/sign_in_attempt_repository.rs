@@ -0,0 +1,10 @@
+use crate::{domain::dto::sign_in_attempt_dto::CreateSignInAttemptDto, soc::soc_repository::RepositoryError};
+
+
+
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait SignInAttemptRepository: Send + Sync {
+    async fn record_sign_in_attempt(&self, dto: CreateSignInAttemptDto) -> Result<(), RepositoryError>;
+}
\ No newline at end of file
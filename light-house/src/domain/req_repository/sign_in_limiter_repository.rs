@@ -0,0 +1,18 @@
+use chrono::{DateTime, Utc};
+
+use crate::{domain::dto::sign_in_attempt_dto::SignInThrottleDto, soc::soc_repository::RepositoryError};
+
+
+
+
+// where failed sign in attempts are counted, kept in memory or in the database (SIGN_IN_LIMITER)
+// keys look like "account:<email>" or "ip:<address>"
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait SignInLimiterRepository: Send + Sync {
+    async fn get_throttle(&self, key: &str) -> Result<Option<SignInThrottleDto>, RepositoryError>;
+    async fn save_throttle(&self, key: &str, throttle: SignInThrottleDto) -> Result<(), RepositoryError>;
+    async fn clear_throttle(&self, key: &str) -> Result<(), RepositoryError>;
+    // drop counters whose last failure is older than the given time
+    async fn purge_throttle_before(&self, before: DateTime<Utc>) -> Result<u64, RepositoryError>;
+}
\ No newline at end of file
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+
+use sea_orm::DatabaseTransaction;
+use uuid::Uuid;
+
+use crate::{domain::{dto::tag_dto::{ReqCreateTagDto, ReqUpdateTagDto}, entities::tag}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+#[async_trait::async_trait]
+pub trait TagRepositoryUtility: Send + Sync {
+    async fn find_all_by_user_id(&self, user_id: Uuid) -> Result<Vec<tag::Model>, RepositoryError>;
+    async fn find_by_user_id_and_tag_id(&self, tag_id: Uuid, user_id: Uuid) -> Result<Option<tag::Model>, RepositoryError>;
+    // number of transactions carrying each tag of the user, keyed by binary tag id
+    async fn count_transactions_by_tag(&self, user_id: Uuid) -> Result<HashMap<Vec<u8>, i64>, RepositoryError>;
+}
+
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait TagRepositoryBase: Send + Sync {
+    async fn create(&self, user_id: Uuid, dto: ReqCreateTagDto) -> Result<tag::Model, RepositoryError>;
+    async fn update(&self, dto: ReqUpdateTagDto, user_id: Uuid, tag_id: Uuid) -> Result<tag::Model, RepositoryError>;
+    // detaches the tag from every transaction before removing it
+    async fn delete(&self, user_id: Uuid, tag_id: Uuid) -> Result<(), RepositoryError>;
+}
+
+
+// links between transactions and tags, the writes join the caller's database transaction
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait TransactionTagRepositoryUtility: Send + Sync {
+    // replaces every tag of the transaction, each id must be a tag of the user
+    async fn set_transaction_tags(&self, txn: &DatabaseTransaction, user_id: Uuid, transaction_id: Uuid, tag_ids: Vec<String>) -> Result<(), RepositoryError>;
+    async fn delete_transaction_tags(&self, txn: &DatabaseTransaction, transaction_id: Uuid) -> Result<(), RepositoryError>;
+    // tags of every given transaction keyed by binary transaction id, sorted by name
+    async fn find_tags_by_transaction_ids(&self, user_id: Uuid, transaction_ids: Vec<Vec<u8>>) -> Result<HashMap<Vec<u8>, Vec<tag::Model>>, RepositoryError>;
+}
\ No newline at end of file
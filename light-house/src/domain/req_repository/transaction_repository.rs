@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-use crate::{domain::{dto::transaction_dto::{ReqCreateIncomeDto, ReqCreatePaymentDto, ReqCreateTransferDto, ReqUpdateIncomeDto, ReqUpdatePaymentDto, ReqUpdateTransferDto}, entities::{expense, transaction, transaction_type}}, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::transaction_dto::{ReqCreateIncomeDto, ReqCreatePaymentDto, ReqCreateTransferDto, ReqUpdateIncomeDto, ReqUpdatePaymentDto, ReqUpdateTransferDto, TransactionPageDto, TransactionReferenceNamesDto, TransactionSearchDto}, entities::{expense, transaction, transaction_type}}, soc::soc_repository::RepositoryError};
 
 
 
@@ -48,4 +48,14 @@ pub trait RecordPaymentRepositoryUtility {
 pub trait TransactionTypeRepositoryUtility {
     async fn get_transaction_type_by_id(&self, user_id: Uuid, transaction_type_id: Uuid) -> Result<Option<transaction_type::Model>, RepositoryError>;
     async fn get_all_transaction_types_by_user(&self) -> Result<Vec<transaction_type::Model>, RepositoryError>;
-}
\ No newline at end of file
+}
+
+
+
+// read side over every transaction type at once
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait TransactionSearchRepositoryUtility {
+    async fn search_transactions(&self, user_id: Uuid, search: TransactionSearchDto) -> Result<TransactionPageDto, RepositoryError>;
+    async fn find_reference_names(&self, user_id: Uuid, records: Vec<transaction::Model>) -> Result<TransactionReferenceNamesDto, RepositoryError>;
+}
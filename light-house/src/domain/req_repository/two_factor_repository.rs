@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{domain::entities::user_two_factor, soc::soc_repository::RepositoryError};
+
+
+
+
+// totp secret and recovery codes of a user
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait TwoFactorRepository: Send + Sync {
+    async fn find_two_factor_by_user_id(&self, user_id: Uuid) -> Result<Option<user_two_factor::Model>, RepositoryError>;
+    // starts an enrolment, a pending secret is replaced, fails when two factor is already on
+    async fn save_pending_two_factor(&self, user_id: Uuid, secret: &str, now: DateTime<Utc>) -> Result<user_two_factor::Model, RepositoryError>;
+    // false when there is no pending enrolment any more
+    async fn confirm_two_factor(&self, user_id: Uuid, step: i64, now: DateTime<Utc>) -> Result<bool, RepositoryError>;
+    // false when a code of this or a later time step was already accepted
+    async fn use_two_factor_step(&self, user_id: Uuid, step: i64, now: DateTime<Utc>) -> Result<bool, RepositoryError>;
+    // the previous codes stop working, only the hashes are stored
+    async fn replace_recovery_codes(&self, user_id: Uuid, code_hashes: Vec<String>) -> Result<(), RepositoryError>;
+    // false when the code is unknown or already used
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str, now: DateTime<Utc>) -> Result<bool, RepositoryError>;
+    async fn count_unused_recovery_codes(&self, user_id: Uuid) -> Result<u64, RepositoryError>;
+    // turns two factor off, the secret and every recovery code are removed
+    async fn delete_two_factor(&self, user_id: Uuid) -> Result<(), RepositoryError>;
+}
\ No newline at end of file
@@ -0,0 +1,16 @@
+use uuid::Uuid;
+
+use crate::{domain::{dto::user_data_dto::UserDataDto, entities::user}, soc::soc_repository::RepositoryError};
+
+
+
+
+// everything a user owns at once, for the export and the deletion of the account
+#[async_trait::async_trait]
+#[mockall::automock]
+pub trait UserDataRepository: Send + Sync {
+    async fn export_user_data(&self, user_id: Uuid) -> Result<UserDataDto, RepositoryError>;
+    // removes the user and every row that belongs to them in one transaction,
+    // returns the removed user and the storage keys of their attachments
+    async fn delete_user_data(&self, user_id: Uuid) -> Result<(user::Model, Vec<String>), RepositoryError>;
+}
\ No newline at end of file
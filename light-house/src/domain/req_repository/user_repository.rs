@@ -23,12 +23,4 @@ pub trait UserRepositoryBase: Send + Sync {
     async fn find_all(&self) -> Result<Vec<user::Model>, RepositoryError>;
     async fn update(&self, dto: ReqUpdateUserDto, user_id: Uuid) -> Result<user::Model, RepositoryError>;
     async fn delete(&self, id: Uuid) -> Result<(), RepositoryError>;
-}
-
-
-#[async_trait::async_trait]
-#[mockall::automock]
-pub trait McpRepositoryBase: Send + Sync {
-    async fn get_user_id_from_mcp_token(&self, mcp_token: &str) -> Result<user::Model, RepositoryError>;
-    async fn regenerate_mcp_token(&self, user_id: Uuid) -> Result<(), RepositoryError>;
 }
\ No newline at end of file
@@ -1,6 +1,7 @@
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
-use crate::{domain::entities::user_role, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::admin_dto::{AdminUserSearchDto, ResSystemStatsDto}, entities::{user, user_role}}, soc::soc_repository::RepositoryError};
 
 
 
@@ -11,4 +12,10 @@ pub trait RoleManagementRepository {
     async fn assign_role(&self, admin_id: Uuid, target_user_id: Uuid, role: &str) -> Result<(), RepositoryError>;
     async fn revoke_role(&self, admin_id: Uuid, target_user_id: Uuid, role: &str) -> Result<(), RepositoryError>;
     async fn get_role_by_id(&self, role_id: Uuid) -> Result<Option<user_role::Model>, RepositoryError>;
+    async fn get_all_role(&self) -> Result<Vec<user_role::Model>, RepositoryError>;
+    // one page of users with their role, and the number of users matching the filters
+    async fn find_all_user_with_role(&self, search: AdminUserSearchDto) -> Result<(Vec<(user::Model, Option<user_role::Model>)>, u64), RepositoryError>;
+    // None enables the account again
+    async fn set_user_disabled(&self, admin_id: Uuid, target_user_id: Uuid, disabled_at: Option<DateTime<Utc>>) -> Result<user::Model, RepositoryError>;
+    async fn get_system_stats(&self, now: DateTime<Utc>) -> Result<ResSystemStatsDto, RepositoryError>;
 }
\ No newline at end of file
@@ -0,0 +1,350 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use encoding_rs::Encoding;
+use rust_decimal::Decimal;
+
+use crate::domain::dto::import_dto::{ParsedStatementDto, ResImportLineErrorDto, StatementLineDto};
+
+
+
+
+// transaction.note is a VARCHAR(255)
+pub const MAX_DESCRIPTION_CHARS: usize = 255;
+
+// note of a line whose description cell is empty
+pub const DEFAULT_DESCRIPTION: &str = "Imported from bank statement";
+
+
+// How the columns of a bank's csv export are laid out, built from an import profile.
+// A column is matched by header name (case insensitive) when the file has a header line,
+// a 1-based position is accepted as well and is the only option without one
+#[derive(Debug, Clone)]
+pub struct CsvStatementFormat {
+    pub delimiter: u8,
+    pub has_header: bool,
+    pub skip_rows: usize,
+    pub date_column: String,
+    pub date_format: String,
+    pub amount_column: Option<String>,
+    pub debit_column: Option<String>,
+    pub credit_column: Option<String>,
+    pub description_column: String,
+    pub decimal_separator: char,
+    pub encoding: &'static Encoding,
+}
+
+
+// the amount source once the columns are resolved against the header
+enum AmountColumns {
+    Signed(usize),
+    DebitCredit(Option<usize>, Option<usize>),
+}
+
+
+fn resolve_column(name: &str, header: Option<&csv::StringRecord>) -> Result<usize, String> {
+    let name = name.trim();
+    if let Some(header) = header
+        && let Some(index) = header.iter().position(|cell| cell.trim().eq_ignore_ascii_case(name))
+    {
+        return Ok(index);
+    }
+    match name.parse::<usize>() {
+        Ok(position) if position >= 1 => Ok(position - 1),
+        _ if header.is_some() => Err(format!("Column '{}' is not in the header line", name)),
+        _ => Err(format!("Column '{}' must be a 1-based position, the file has no header line", name)),
+    }
+}
+
+
+// Accepts "1,234.56", "1.234,56" (with ',' as separator), "-12.00", "12.00-", "(12.00)",
+// and currency symbols or codes around the number. None for an empty cell
+pub fn parse_amount(raw: &str, decimal_separator: char) -> Result<Option<Decimal>, String> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Ok(None);
+    }
+
+    let negative = raw.starts_with('-')
+        || raw.ends_with('-')
+        || (raw.starts_with('(') && raw.ends_with(')'));
+
+    let mut number = String::with_capacity(raw.len());
+    for ch in raw.chars() {
+        if ch.is_ascii_digit() {
+            number.push(ch);
+        } else if ch == decimal_separator {
+            number.push('.');
+        }
+        // thousands separators, spaces, signs and currency marks are dropped
+    }
+    if !number.chars().any(|ch| ch.is_ascii_digit()) {
+        return Err(format!("'{}' is not an amount", raw));
+    }
+
+    let amount = number
+        .parse::<Decimal>()
+        .map_err(|_| format!("'{}' is not an amount", raw))?;
+    Ok(Some(if negative { -amount } else { amount }))
+}
+
+
+// a format with a time part such as "%d/%m/%Y %H:%M" keeps only the date
+pub fn parse_statement_date(raw: &str, date_format: &str) -> Result<NaiveDate, String> {
+    let raw = raw.trim();
+    NaiveDate::parse_from_str(raw, date_format)
+        .or_else(|_| NaiveDateTime::parse_from_str(raw, date_format).map(|date_time| date_time.date()))
+        .map_err(|_| format!("'{}' does not match the date format '{}'", raw, date_format))
+}
+
+
+pub fn clean_description(raw: &str) -> String {
+    let description = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+    if description.is_empty() {
+        return DEFAULT_DESCRIPTION.to_string();
+    }
+    description.chars().take(MAX_DESCRIPTION_CHARS).collect()
+}
+
+
+// 1-based line of the file a record starts on, counted from the byte offset
+// because the csv reader neither counts nor steps over the blank lines it skips
+fn line_at(body: &str, byte: u64, skip_rows: usize) -> i32 {
+    let bytes = body.as_bytes();
+    let start = (byte as usize).min(bytes.len());
+    let blank_lines = bytes[start..]
+        .iter()
+        .take_while(|&&byte| byte == b'\n' || byte == b'\r')
+        .filter(|&&byte| byte == b'\n')
+        .count();
+    (bytes[..start].iter().filter(|&&byte| byte == b'\n').count() + blank_lines + 1 + skip_rows) as i32
+}
+
+
+fn read_line(record: &csv::StringRecord, format: &CsvStatementFormat, date_index: usize, description_index: usize, amount_columns: &AmountColumns)
+    -> Result<(NaiveDate, Decimal, String), String>
+{
+    let cell = |index: usize| record.get(index).unwrap_or("");
+
+    let occurred_on = parse_statement_date(cell(date_index), &format.date_format)?;
+
+    let amount = match amount_columns {
+        AmountColumns::Signed(index) => parse_amount(cell(*index), format.decimal_separator)?
+            .ok_or_else(|| "The amount is empty".to_string())?,
+        AmountColumns::DebitCredit(debit_index, credit_index) => {
+            let debit = match debit_index {
+                Some(index) => parse_amount(cell(*index), format.decimal_separator)?.filter(|amount| !amount.is_zero()),
+                None => None,
+            };
+            let credit = match credit_index {
+                Some(index) => parse_amount(cell(*index), format.decimal_separator)?.filter(|amount| !amount.is_zero()),
+                None => None,
+            };
+            match (debit, credit) {
+                (Some(debit), None) => -debit.abs(),
+                (None, Some(credit)) => credit.abs(),
+                (Some(_), Some(_)) => return Err("Both the debit and the credit are filled".to_string()),
+                (None, None) => return Err("Neither the debit nor the credit is filled".to_string()),
+            }
+        }
+    };
+    if amount.is_zero() {
+        return Err("The amount is zero".to_string());
+    }
+
+    Ok((occurred_on, amount, clean_description(cell(description_index))))
+}
+
+
+// An error for the whole file (encoding, unknown column) is an Err,
+// an unreadable line only lands in `errors`
+pub fn parse_csv_statement(content: &[u8], format: &CsvStatementFormat) -> Result<ParsedStatementDto, String> {
+    // a byte order mark wins over the configured encoding
+    let (text, used_encoding, had_errors) = format.encoding.decode(content);
+    if had_errors {
+        return Err(format!("The file is not valid {}", used_encoding.name()));
+    }
+
+    // preamble lines some banks put above the table (account number, period, ...)
+    let mut body = text.as_ref();
+    for _ in 0..format.skip_rows {
+        body = match body.find('\n') {
+            Some(index) => &body[index + 1..],
+            None => "",
+        };
+    }
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(format.delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(body.as_bytes());
+    let mut records = reader.records();
+
+    let header = if format.has_header {
+        match records.next() {
+            Some(Ok(header)) => Some(header),
+            Some(Err(err)) => return Err(format!("Failed to read the header line: {}", err)),
+            None => return Err("The file has no header line".to_string()),
+        }
+    } else {
+        None
+    };
+
+    let date_index = resolve_column(&format.date_column, header.as_ref())?;
+    let description_index = resolve_column(&format.description_column, header.as_ref())?;
+    let amount_columns = match &format.amount_column {
+        Some(amount_column) => AmountColumns::Signed(resolve_column(amount_column, header.as_ref())?),
+        None => AmountColumns::DebitCredit(
+            format.debit_column.as_deref().map(|column| resolve_column(column, header.as_ref())).transpose()?,
+            format.credit_column.as_deref().map(|column| resolve_column(column, header.as_ref())).transpose()?,
+        ),
+    };
+
+    let mut parsed = ParsedStatementDto::default();
+    for record in records {
+        let record = match record {
+            Ok(record) => record,
+            Err(err) => {
+                let byte = err.position().map(|position| position.byte()).unwrap_or_default();
+                parsed.errors.push(ResImportLineErrorDto {
+                    line: line_at(body, byte, format.skip_rows),
+                    message: format!("Failed to read the line: {}", err),
+                });
+                continue;
+            }
+        };
+        // blank lines and footers such as an empty separator row
+        if record.iter().all(|cell| cell.trim().is_empty()) {
+            continue;
+        }
+
+        let line = line_at(body, record.position().map(|position| position.byte()).unwrap_or_default(), format.skip_rows);
+        match read_line(&record, format, date_index, description_index, &amount_columns) {
+            Ok((occurred_on, amount, description)) => parsed.lines.push(StatementLineDto {
+                line,
+                occurred_on,
+                amount,
+                description,
+                external_reference: None,
+            }),
+            Err(message) => parsed.errors.push(ResImportLineErrorDto { line, message }),
+        }
+    }
+
+    Ok(parsed)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn amount(raw: &str, decimal_separator: char) -> Option<Decimal> {
+        parse_amount(raw, decimal_separator).unwrap()
+    }
+
+    fn decimal(value: &str) -> Decimal {
+        value.parse().unwrap()
+    }
+
+    fn format(amount_column: Option<&str>, debit_column: Option<&str>, credit_column: Option<&str>) -> CsvStatementFormat {
+        CsvStatementFormat {
+            delimiter: b';',
+            has_header: true,
+            skip_rows: 1,
+            date_column: "Date".to_string(),
+            date_format: "%d/%m/%Y".to_string(),
+            amount_column: amount_column.map(str::to_string),
+            debit_column: debit_column.map(str::to_string),
+            credit_column: credit_column.map(str::to_string),
+            description_column: "Description".to_string(),
+            decimal_separator: ',',
+            encoding: encoding_rs::UTF_8,
+        }
+    }
+
+    #[test]
+    fn amount_follows_the_decimal_separator() {
+        assert_eq!(amount("1,234.56", '.'), Some(decimal("1234.56")));
+        assert_eq!(amount("1.234,56", ','), Some(decimal("1234.56")));
+        assert_eq!(amount("1 234,56", ','), Some(decimal("1234.56")));
+    }
+
+    #[test]
+    fn amount_sign_comes_from_minus_or_parentheses() {
+        assert_eq!(amount("-12.00", '.'), Some(decimal("-12.00")));
+        assert_eq!(amount("12.00-", '.'), Some(decimal("-12.00")));
+        assert_eq!(amount("(12.00)", '.'), Some(decimal("-12.00")));
+        assert_eq!(amount("+12.00", '.'), Some(decimal("12.00")));
+    }
+
+    #[test]
+    fn amount_drops_currency_marks() {
+        assert_eq!(amount("€ 4,50", ','), Some(decimal("4.50")));
+        assert_eq!(amount("USD 1,000.00", '.'), Some(decimal("1000.00")));
+        assert_eq!(amount("-฿120", '.'), Some(decimal("-120")));
+    }
+
+    #[test]
+    fn empty_amount_is_none_and_text_is_an_error() {
+        assert_eq!(amount("   ", '.'), None);
+        assert!(parse_amount("n/a", '.').is_err());
+        assert!(parse_amount("1.2.3", '.').is_err());
+    }
+
+    #[test]
+    fn statement_date_keeps_only_the_date_of_a_date_time_format() {
+        let expected = NaiveDate::from_ymd_opt(2025, 6, 1).unwrap();
+
+        assert_eq!(parse_statement_date(" 01/06/2025 ", "%d/%m/%Y"), Ok(expected));
+        assert_eq!(parse_statement_date("01/06/2025 14:30", "%d/%m/%Y %H:%M"), Ok(expected));
+        assert!(parse_statement_date("2025-06-01", "%d/%m/%Y").is_err());
+    }
+
+    #[test]
+    fn line_at_counts_the_blank_lines_the_reader_skips() {
+        let body = "a\nb\n\n\nc\n";
+
+        assert_eq!(line_at(body, 0, 0), 1);
+        assert_eq!(line_at(body, 2, 0), 2);
+        // the reader reports the record after a blank run at the start of the run
+        assert_eq!(line_at(body, 4, 0), 5);
+        assert_eq!(line_at(body, 6, 0), 5);
+        assert_eq!(line_at(body, 6, 3), 8);
+        assert_eq!(line_at("a\r\nb\r\n\r\nc", 6, 0), 4);
+    }
+
+    #[test]
+    fn signed_amount_column_reports_lines_of_the_file() {
+        let content = "Account 123\nDate;Amount;Description\n01/06/2025;-4,50;  Coffee   shop \n\n02/06/2025;(12,00);\n03/06/2025;0,00;Fee\n31/02/2025;1,00;Bad date\n";
+        let parsed = parse_csv_statement(content.as_bytes(), &format(Some("amount"), None, None)).unwrap();
+
+        let lines: Vec<_> = parsed.lines.iter().map(|line| (line.line, line.amount, line.description.as_str())).collect();
+        assert_eq!(lines, [
+            (3, decimal("-4.50"), "Coffee shop"),
+            (5, decimal("-12.00"), DEFAULT_DESCRIPTION),
+        ]);
+        let errors: Vec<_> = parsed.errors.iter().map(|error| error.line).collect();
+        assert_eq!(errors, [6, 7]);
+    }
+
+    #[test]
+    fn debit_and_credit_columns_give_the_sign() {
+        let content = "Statement\nDate;Debit;Credit;Description\n01/06/2025;4,50;;Coffee\n02/06/2025;;1.500,00;Salary\n03/06/2025;1,00;2,00;Both\n04/06/2025;;;Neither\n";
+        let parsed = parse_csv_statement(content.as_bytes(), &format(None, Some("Debit"), Some("3"))).unwrap();
+
+        let amounts: Vec<_> = parsed.lines.iter().map(|line| line.amount).collect();
+        assert_eq!(amounts, [decimal("-4.50"), decimal("1500.00")]);
+        let errors: Vec<_> = parsed.errors.iter().map(|error| (error.line, error.message.as_str())).collect();
+        assert_eq!(errors, [
+            (5, "Both the debit and the credit are filled"),
+            (6, "Neither the debit nor the credit is filled"),
+        ]);
+    }
+
+    #[test]
+    fn unknown_column_fails_the_whole_file() {
+        let content = "Statement\nDate;Amount;Description\n01/06/2025;-4,50;Coffee\n";
+
+        assert!(parse_csv_statement(content.as_bytes(), &format(Some("Betrag"), None, None)).is_err());
+    }
+}
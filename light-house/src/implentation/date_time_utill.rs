@@ -1,8 +1,125 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use validator::ValidationError;
+
+// occurred_at and the other business dates are MySQL TIMESTAMP columns, which only hold
+// 1970-01-01 00:00:01 UTC to 2038-01-19 03:14:07 UTC
+const TIMESTAMP_RANGE: std::ops::RangeInclusive<i64> = 1..=i32::MAX as i64;
 
 pub fn parse_to_datetime_utc(date_str: &str) -> Result<DateTime<Utc>, String> {
     match date_str.parse::<DateTime<Utc>>() {
         Ok(parsed_date) => Ok(parsed_date),
         Err(err) => Err(format!("Failed to parse date: {}", err)),
     }
-}
\ No newline at end of file
+}
+
+// Query dates accept a full RFC 3339 timestamp or a plain YYYY-MM-DD date,
+// a plain date covers the whole day in UTC
+pub fn parse_to_datetime_utc_start_of_day(date_str: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(parsed_date) = date_str.parse::<DateTime<Utc>>() {
+        return Ok(parsed_date);
+    }
+    match NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+        Ok(date) => Ok(date.and_time(NaiveTime::MIN).and_utc()),
+        Err(err) => Err(format!("Failed to parse date: {}", err)),
+    }
+}
+
+pub fn parse_to_datetime_utc_end_of_day(date_str: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(parsed_date) = date_str.parse::<DateTime<Utc>>() {
+        return Ok(parsed_date);
+    }
+    match NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+        Ok(date) => Ok(date.and_time(NaiveTime::MIN).and_utc() + Duration::days(1) - Duration::microseconds(1)),
+        Err(err) => Err(format!("Failed to parse date: {}", err)),
+    }
+}
+
+
+
+pub fn parse_timezone(timezone: &str) -> Result<Tz, String> {
+    timezone
+        .parse::<Tz>()
+        .map_err(|err| format!("Invalid timezone: {}", err))
+}
+
+// Business dates: a timestamp with an offset such as "2025-06-01T09:30:00+07:00" is taken as is,
+// a local timestamp or a plain YYYY-MM-DD date is read in `timezone` (IANA name), UTC when none is given
+pub fn parse_occurred_at(date_str: &str, timezone: Option<&str>) -> Result<DateTime<Utc>, String> {
+    if let Ok(parsed_date) = DateTime::parse_from_rfc3339(date_str) {
+        return check_timestamp_range(parsed_date.with_timezone(&Utc));
+    }
+
+    let local_date = match NaiveDateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S%.f"))
+    {
+        Ok(local_date) => local_date,
+        Err(_) => NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
+            .map(|date| date.and_time(NaiveTime::MIN))
+            .map_err(|err| format!("Failed to parse date: {}", err))?,
+    };
+
+    let occurred_at = match timezone {
+        None => local_date.and_utc(),
+        Some(timezone) => {
+            let timezone = parse_timezone(timezone)?;
+            // earliest() picks the first reading of an ambiguous DST hour, a skipped hour has none
+            timezone
+                .from_local_datetime(&local_date)
+                .earliest()
+                .map(|date| date.with_timezone(&Utc))
+                .ok_or_else(|| format!("{} does not exist in {}", date_str, timezone))?
+        }
+    };
+    check_timestamp_range(occurred_at)
+}
+
+pub fn check_timestamp_range(date: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    if TIMESTAMP_RANGE.contains(&date.timestamp()) {
+        Ok(date)
+    } else {
+        Err(format!("{} is outside the supported range of 1970-01-01 to 2038-01-19", date.to_rfc3339()))
+    }
+}
+
+// Same as parse_occurred_at, but a plain YYYY-MM-DD date means the last second of that day,
+// for inclusive "until" dates stored in a TIMESTAMP column such as the last day of a budget
+pub fn parse_occurred_until(date_str: &str, timezone: Option<&str>) -> Result<DateTime<Utc>, String> {
+    match NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+        Ok(date) => {
+            let next_day = (date + Duration::days(1)).format("%Y-%m-%d").to_string();
+            Ok(parse_occurred_at(&next_day, timezone)? - Duration::seconds(1))
+        }
+        Err(_) => parse_occurred_at(date_str, timezone),
+    }
+}
+
+
+
+// used by #[validate(custom(...))] on occurred_at / timezone fields
+pub fn validate_occurred_at(value: &str) -> Result<(), ValidationError> {
+    parse_occurred_at(value, None)
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("invalid_occurred_at"))
+}
+
+pub fn validate_timezone(value: &str) -> Result<(), ValidationError> {
+    parse_timezone(value)
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("invalid_timezone"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dates_outside_the_timestamp_range_are_rejected() {
+        assert!(parse_occurred_at("2038-01-19", None).is_ok());
+        assert!(parse_occurred_at("2040-01-01", None).is_err());
+        assert!(parse_occurred_at("2040-01-01T00:00:00Z", None).is_err());
+        assert!(parse_occurred_at("1969-12-31", Some("Asia/Bangkok")).is_err());
+        assert!(parse_occurred_until("2038-01-19", None).is_err());
+        assert!(validate_occurred_at("2040-01-01").is_err());
+    }
+}
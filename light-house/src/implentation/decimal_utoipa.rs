@@ -1,12 +1,15 @@
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
-use std::cmp::Ordering;
+use validator::ValidationError;
+use std::{cmp::Ordering, fmt};
 
 
 
+// Decimal is (de)serialized as a string such as "1250.5000" so no precision is lost,
+// a plain JSON number is also accepted on input
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Copy, ToSchema)]
-#[schema(value_type = f64, format = "decimal")]
+#[schema(value_type = String, format = "decimal", example = "1250.50")]
 pub struct DecimalWrapper(pub Decimal);
 
 impl PartialOrd for DecimalWrapper {
@@ -16,3 +19,23 @@ impl PartialOrd for DecimalWrapper {
 }
 
 
+impl fmt::Display for DecimalWrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl From<Decimal> for DecimalWrapper {
+    fn from(value: Decimal) -> Self {
+        DecimalWrapper(value)
+    }
+}
+
+
+// used by #[validate(custom(...))] on money fields
+pub fn validate_positive_amount(value: &DecimalWrapper) -> Result<(), ValidationError> {
+    if value.0 <= Decimal::ZERO {
+        return Err(ValidationError::new("amount_must_be_positive"));
+    }
+    Ok(())
+}
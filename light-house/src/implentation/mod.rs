@@ -1,2 +1,8 @@
 pub mod decimal_utoipa;
-pub mod date_time_utill;
\ No newline at end of file
+pub mod date_time_utill;
+pub mod recurrence_rule;
+pub mod secret_token;
+pub mod totp;
+pub mod csv_statement;
+pub mod ofx_statement;
+pub mod qif_statement;
\ No newline at end of file
@@ -0,0 +1,323 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{domain::dto::import_dto::{ParsedStatementDto, ResImportLineErrorDto, StatementLineDto}, implentation::csv_statement::{clean_description, parse_amount}};
+
+
+
+
+// external_reference is a VARCHAR(255)
+const MAX_REFERENCE_CHARS: usize = 255;
+
+
+enum OfxToken<'a> {
+    // tag name in upper case and the line it starts on
+    Open(String, i32),
+    Close(String),
+    Text(&'a str),
+}
+
+
+// OFX 1.x is SGML with a "KEY:VALUE" header, the charset is in CHARSET (1252, ISO-8859-1, ...).
+// OFX 2.x is XML and UTF-8 unless its declaration says otherwise
+fn decode_ofx(content: &[u8]) -> String {
+    if let Ok(text) = std::str::from_utf8(content) {
+        return text.trim_start_matches('\u{feff}').to_string();
+    }
+
+    let head = String::from_utf8_lossy(&content[..content.len().min(1024)]).to_uppercase();
+    let label = if let Some(index) = head.find("CHARSET:") {
+        let charset = head[index + 8..].lines().next().unwrap_or_default().trim().to_string();
+        if charset.chars().all(|ch| ch.is_ascii_digit()) {
+            format!("windows-{}", charset)
+        } else {
+            charset
+        }
+    } else if let Some(index) = head.find("ENCODING=\"") {
+        head[index + 10..].split('"').next().unwrap_or_default().to_string()
+    } else {
+        String::new()
+    };
+
+    let encoding = encoding_rs::Encoding::for_label(label.as_bytes()).unwrap_or(encoding_rs::WINDOWS_1252);
+    encoding.decode(content).0.into_owned()
+}
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+
+// Both versions come down to the same tokens: SGML leaves have no closing tag, so a leaf is
+// an opening tag directly followed by text, whatever comes after it
+fn tokenize(body: &str, first_line: i32) -> Vec<OfxToken<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = body;
+    let mut line = first_line;
+
+    while let Some(start) = rest.find('<') {
+        let text = &rest[..start];
+        line += text.matches('\n').count() as i32;
+        if !text.trim().is_empty() {
+            tokens.push(OfxToken::Text(text));
+        }
+        rest = &rest[start..];
+
+        // comments and processing instructions such as <?OFX ...?>
+        if rest.starts_with("<!--") {
+            let end = rest.find("-->").map(|end| end + 3).unwrap_or(rest.len());
+            line += rest[..end].matches('\n').count() as i32;
+            rest = &rest[end..];
+            continue;
+        }
+        let Some(end) = rest.find('>') else { break };
+        let tag = &rest[1..end];
+        line += tag.matches('\n').count() as i32;
+        rest = &rest[end + 1..];
+
+        if tag.starts_with('?') || tag.starts_with('!') {
+            continue;
+        }
+        let self_closing = tag.ends_with('/');
+        let name = tag
+            .trim_start_matches('/')
+            .trim_end_matches('/')
+            .split_whitespace()
+            .next()
+            .unwrap_or_default()
+            .to_uppercase();
+        if tag.starts_with('/') {
+            tokens.push(OfxToken::Close(name));
+        } else if self_closing {
+            tokens.push(OfxToken::Open(name.clone(), line));
+            tokens.push(OfxToken::Close(name));
+        } else {
+            tokens.push(OfxToken::Open(name, line));
+        }
+    }
+    if !rest.trim().is_empty() {
+        tokens.push(OfxToken::Text(rest));
+    }
+    tokens
+}
+
+
+// DTPOSTED is YYYYMMDD[HHMMSS[.XXX]][[offset:TZ]], the date the bank booked the line on is kept
+pub fn parse_ofx_date(raw: &str) -> Result<NaiveDate, String> {
+    let raw = raw.trim();
+    raw.get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .ok_or_else(|| format!("'{}' is not an OFX date", raw))
+}
+
+// TRNAMT has no thousands separator, some banks write a decimal comma
+fn parse_ofx_amount(raw: &str) -> Result<Decimal, String> {
+    let separator = if raw.contains('.') { '.' } else { ',' };
+    parse_amount(raw, separator)?.ok_or_else(|| "The amount is empty".to_string())
+}
+
+fn read_transaction(fields: &HashMap<String, String>) -> Result<(NaiveDate, Decimal, String, Option<String>), String> {
+    let posted = fields
+        .get("DTPOSTED")
+        .or_else(|| fields.get("DTUSER"))
+        .ok_or_else(|| "The transaction has no DTPOSTED".to_string())?;
+    let occurred_on = parse_ofx_date(posted)?;
+
+    let amount = parse_ofx_amount(
+        fields
+            .get("TRNAMT")
+            .ok_or_else(|| "The transaction has no TRNAMT".to_string())?,
+    )?;
+    if amount.is_zero() {
+        return Err("The amount is zero".to_string());
+    }
+
+    let description = match (fields.get("NAME"), fields.get("MEMO")) {
+        (Some(name), Some(memo)) if !memo.eq_ignore_ascii_case(name) => format!("{} - {}", name, memo),
+        (Some(name), _) => name.clone(),
+        (None, Some(memo)) => memo.clone(),
+        (None, None) => fields.get("TRNTYPE").cloned().unwrap_or_default(),
+    };
+
+    let external_reference = fields
+        .get("FITID")
+        .map(|fitid| fitid.chars().take(MAX_REFERENCE_CHARS).collect::<String>());
+
+    Ok((occurred_on, amount, clean_description(&description), external_reference))
+}
+
+
+// One statement per bank (STMTRS) or credit card (CCSTMTRS) account in the file, keyed by ACCTID.
+// An unreadable transaction only lands in `errors`
+pub fn parse_ofx_statement(content: &[u8]) -> Result<Vec<ParsedStatementDto>, String> {
+    let text = decode_ofx(content);
+    let start = text
+        .to_ascii_uppercase()
+        .find("<OFX")
+        .ok_or_else(|| "The file is not an OFX statement".to_string())?;
+    let first_line = text[..start].matches('\n').count() as i32 + 1;
+
+    let mut statements = Vec::new();
+    let mut statement: Option<ParsedStatementDto> = None;
+    let mut transaction: Option<(i32, HashMap<String, String>)> = None;
+    let mut open_tag: Option<String> = None;
+
+    for token in tokenize(&text[start..], first_line) {
+        match token {
+            OfxToken::Open(name, line) => {
+                match name.as_str() {
+                    "STMTRS" | "CCSTMTRS" => statement = Some(ParsedStatementDto::default()),
+                    "STMTTRN" => transaction = Some((line, HashMap::new())),
+                    _ => {}
+                }
+                open_tag = Some(name);
+            }
+            OfxToken::Text(value) => {
+                let Some(name) = open_tag.take() else { continue };
+                let value = decode_entities(value.trim());
+                if let Some((_, fields)) = transaction.as_mut() {
+                    fields.insert(name, value);
+                } else if name == "ACCTID"
+                    && let Some(statement) = statement.as_mut()
+                {
+                    statement.account_id = Some(value);
+                }
+            }
+            OfxToken::Close(name) => {
+                open_tag = None;
+                match name.as_str() {
+                    "STMTTRN" => {
+                        if let (Some((line, fields)), Some(statement)) = (transaction.take(), statement.as_mut()) {
+                            match read_transaction(&fields) {
+                                Ok((occurred_on, amount, description, external_reference)) => statement.lines.push(StatementLineDto {
+                                    line,
+                                    occurred_on,
+                                    amount,
+                                    description,
+                                    external_reference,
+                                }),
+                                Err(message) => statement.errors.push(ResImportLineErrorDto { line, message }),
+                            }
+                        }
+                    }
+                    "STMTRS" | "CCSTMTRS" => statements.extend(statement.take()),
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    if statements.is_empty() {
+        return Err("The file holds no bank or credit card statement".to_string());
+    }
+    Ok(statements)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SGML: &str = "OFXHEADER:100\nDATA:OFXSGML\nVERSION:102\nCHARSET:1252\n\n<OFX>\n<BANKMSGSRSV1><STMTTRNRS><STMTRS>\n<BANKACCTFROM><BANKID>001<ACCTID>12345678</BANKACCTFROM>\n<BANKTRANLIST>\n<STMTTRN>\n<TRNTYPE>DEBIT\n<DTPOSTED>20250601120000[-5:EST]\n<TRNAMT>-4.50\n<FITID>2025060101\n<NAME>COFFEE &amp; CO\n<MEMO>CARD 1234\n</STMTTRN>\n<STMTTRN>\n<TRNTYPE>CREDIT\n<DTPOSTED>20250602\n<TRNAMT>1500,00\n<FITID>2025060201\n<NAME>SALARY\n<MEMO>salary\n</STMTTRN>\n<STMTTRN>\n<TRNTYPE>DEBIT\n<TRNAMT>-1.00\n</STMTTRN>\n</BANKTRANLIST>\n</STMTRS></STMTTRNRS></BANKMSGSRSV1>\n</OFX>\n";
+
+    const XML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<?OFX OFXHEADER=\"200\" VERSION=\"220\"?>\n<OFX>\n<!-- card\nstatement -->\n<CREDITCARDMSGSRSV1><CCSTMTTRNRS><CCSTMTRS>\n<CCACCTFROM><ACCTID>4111</ACCTID></CCACCTFROM>\n<BANKTRANLIST>\n<STMTTRN>\n<TRNTYPE>DEBIT</TRNTYPE>\n<DTPOSTED>20250603</DTPOSTED>\n<TRNAMT>-12.00</TRNAMT>\n<FITID>CC-1</FITID>\n<NAME>Café</NAME>\n<MEMO/>\n</STMTTRN>\n</BANKTRANLIST>\n</CCSTMTRS></CCSTMTTRNRS></CREDITCARDMSGSRSV1>\n</OFX>\n";
+
+    fn describe(tokens: Vec<OfxToken<'_>>) -> Vec<String> {
+        tokens
+            .into_iter()
+            .map(|token| match token {
+                OfxToken::Open(name, line) => format!("<{}>@{}", name, line),
+                OfxToken::Close(name) => format!("</{}>", name),
+                OfxToken::Text(text) => text.trim().to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn tokenize_reads_sgml_leaves_without_closing_tags() {
+        let tokens = describe(tokenize("<STMTTRN>\n<trnamt>-4.50\n<NAME>A &lt;B&gt;\n</STMTTRN>", 10));
+
+        assert_eq!(tokens, ["<STMTTRN>@10", "<TRNAMT>@11", "-4.50", "<NAME>@12", "A &lt;B&gt;", "</STMTTRN>"]);
+    }
+
+    #[test]
+    fn tokenize_skips_comments_and_instructions_and_splits_self_closing_tags() {
+        let tokens = describe(tokenize("<?OFX VERSION=\"220\"?>\n<!-- a\nb -->\n<MEMO/><NAME>x</NAME>", 1));
+
+        assert_eq!(tokens, ["<MEMO>@4", "</MEMO>", "<NAME>@4", "x", "</NAME>"]);
+    }
+
+    #[test]
+    fn sgml_statement_reads_leaves_and_reports_bad_transactions() {
+        let statements = parse_ofx_statement(SGML.as_bytes()).unwrap();
+
+        assert_eq!(statements.len(), 1);
+        let statement = &statements[0];
+        assert_eq!(statement.account_id.as_deref(), Some("12345678"));
+        let lines: Vec<_> = statement
+            .lines
+            .iter()
+            .map(|line| (line.line, line.occurred_on.to_string(), line.amount.to_string(), line.description.as_str(), line.external_reference.as_deref()))
+            .collect();
+        assert_eq!(lines, [
+            (10, "2025-06-01".to_string(), "-4.50".to_string(), "COFFEE & CO - CARD 1234", Some("2025060101")),
+            (18, "2025-06-02".to_string(), "1500.00".to_string(), "SALARY", Some("2025060201")),
+        ]);
+        let errors: Vec<_> = statement.errors.iter().map(|error| (error.line, error.message.as_str())).collect();
+        assert_eq!(errors, [(26, "The transaction has no DTPOSTED")]);
+    }
+
+    #[test]
+    fn xml_statement_reads_closed_tags() {
+        let statements = parse_ofx_statement(XML.as_bytes()).unwrap();
+
+        assert_eq!(statements.len(), 1);
+        let statement = &statements[0];
+        assert_eq!(statement.account_id.as_deref(), Some("4111"));
+        assert!(statement.errors.is_empty());
+        let line = &statement.lines[0];
+        assert_eq!((line.line, line.amount.to_string(), line.description.as_str()), (9, "-12.00".to_string(), "Café"));
+        assert_eq!(line.external_reference.as_deref(), Some("CC-1"));
+    }
+
+    #[test]
+    fn file_without_a_statement_is_rejected() {
+        assert!(parse_ofx_statement(b"NAME,AMOUNT\nCoffee,-4.50\n").is_err());
+        assert!(parse_ofx_statement(b"<OFX><SIGNONMSGSRSV1></SIGNONMSGSRSV1></OFX>").is_err());
+    }
+
+    #[test]
+    fn decode_ofx_follows_the_charset_header() {
+        // 0xE9 is é in windows-1252 and in ISO-8859-1, 0xA4 is € only in ISO-8859-15
+        let windows = b"CHARSET:1252\n<OFX><NAME>Caf\xe9</OFX>";
+        let latin9 = b"CHARSET:ISO-8859-15\n<OFX><NAME>\xa4</OFX>";
+
+        assert!(decode_ofx(windows).contains("Café"));
+        assert!(decode_ofx(latin9).contains('€'));
+    }
+
+    #[test]
+    fn decode_ofx_falls_back_to_windows_1252() {
+        let xml = b"<?xml version=\"1.0\" encoding=\"windows-1252\"?><OFX><NAME>\x80 5</OFX>";
+        let unknown = b"CHARSET:NONE\n<OFX><NAME>Caf\xe9</OFX>";
+        let utf8_with_bom = "\u{feff}<OFX><NAME>Café</OFX>";
+
+        assert!(decode_ofx(xml).contains("€ 5"));
+        assert!(decode_ofx(unknown).contains("Café"));
+        assert_eq!(decode_ofx(utf8_with_bom.as_bytes()), "<OFX><NAME>Café</OFX>");
+    }
+
+    #[test]
+    fn ofx_date_keeps_the_booked_day() {
+        assert_eq!(parse_ofx_date("20250601235959.000[-5:EST]"), Ok(NaiveDate::from_ymd_opt(2025, 6, 1).unwrap()));
+        assert!(parse_ofx_date("2025-06-01").is_err());
+    }
+}
@@ -0,0 +1,281 @@
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+
+use crate::{domain::dto::{dto_enum::date_order::DateOrderVariant, import_dto::{ParsedStatementDto, ResImportLineErrorDto, StatementLineDto}}, implentation::csv_statement::{clean_description, parse_amount}};
+
+
+
+
+// external_reference is a VARCHAR(255)
+const MAX_REFERENCE_CHARS: usize = 255;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QifSection {
+    // !Type:Bank, Cash, CCard, Oth A or Oth L
+    Transactions,
+    // !Account, names the account of the sections that follow
+    Account,
+    // categories, classes, investments and memorized transactions are not imported
+    Other,
+}
+
+
+// Quicken writes 6/ 1/25 before 2000 and 6/ 1'25 after, others use 06/01/2025, 01.06.2025 or 2025-06-01
+pub fn parse_qif_date(raw: &str, date_order: DateOrderVariant) -> Result<NaiveDate, String> {
+    let invalid = || format!("'{}' is not a date", raw.trim());
+    let parts = raw
+        .split(|ch: char| !ch.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>();
+    if parts.len() != 3 {
+        return Err(invalid());
+    }
+
+    let (year, month, day) = if parts[0].len() == 4 {
+        (parts[0], parts[1], parts[2])
+    } else {
+        match date_order {
+            DateOrderVariant::Mdy => (parts[2], parts[0], parts[1]),
+            DateOrderVariant::Dmy => (parts[2], parts[1], parts[0]),
+        }
+    };
+    let mut year = year.parse::<i32>().map_err(|_| invalid())?;
+    if year < 100 {
+        year += if raw.contains('\'') || year < 50 { 2000 } else { 1900 };
+    }
+    let month = month.parse::<u32>().map_err(|_| invalid())?;
+    let day = day.parse::<u32>().map_err(|_| invalid())?;
+
+    NaiveDate::from_ymd_opt(year, month, day).ok_or_else(invalid)
+}
+
+// "-1,234.56" or "-1.234,56": the last '.' or ',' is the decimal separator when one or two digits follow it
+pub fn parse_qif_amount(raw: &str) -> Result<Decimal, String> {
+    let raw = raw.trim();
+    let separator = match raw.rfind(['.', ',']) {
+        Some(index) => {
+            let decimals = raw[index + 1..].chars().take_while(|ch| ch.is_ascii_digit()).count();
+            let separator = raw[index..].chars().next().unwrap_or('.');
+            if (1..=2).contains(&decimals) {
+                separator
+            } else if separator == '.' {
+                ','
+            } else {
+                '.'
+            }
+        }
+        None => '.',
+    };
+    parse_amount(raw, separator)?.ok_or_else(|| "The amount is empty".to_string())
+}
+
+
+fn read_record(fields: &[(char, String)], date_order: DateOrderVariant) -> Result<(NaiveDate, Decimal, String, Option<String>), String> {
+    let field = |code: char| {
+        fields
+            .iter()
+            .find(|(field_code, value)| *field_code == code && !value.trim().is_empty())
+            .map(|(_, value)| value.trim())
+    };
+
+    let occurred_on = parse_qif_date(field('D').ok_or_else(|| "The record has no date (D)".to_string())?, date_order)?;
+    let amount = parse_qif_amount(
+        field('T')
+            .or_else(|| field('U'))
+            .ok_or_else(|| "The record has no amount (T)".to_string())?,
+    )?;
+    if amount.is_zero() {
+        return Err("The amount is zero".to_string());
+    }
+
+    let description = match (field('P'), field('M')) {
+        (Some(payee), Some(memo)) if !memo.eq_ignore_ascii_case(payee) => format!("{} - {}", payee, memo),
+        (Some(payee), _) => payee.to_string(),
+        (None, Some(memo)) => memo.to_string(),
+        (None, None) => String::new(),
+    };
+
+    // N also carries codes such as ATM, DEP or Txfr, only a number identifies the line
+    let external_reference = field('N')
+        .filter(|number| number.chars().any(|ch| ch.is_ascii_digit()))
+        .map(|number| number.chars().take(MAX_REFERENCE_CHARS).collect::<String>());
+
+    Ok((occurred_on, amount, clean_description(&description), external_reference))
+}
+
+
+// One statement per !Account block (a single unnamed one when the file has none).
+// Split lines (S, E, $) are left out, the record's total T is what hits the account
+pub fn parse_qif_statement(content: &[u8], date_order: DateOrderVariant) -> Result<Vec<ParsedStatementDto>, String> {
+    let text = match std::str::from_utf8(content) {
+        Ok(text) => text.trim_start_matches('\u{feff}').to_string(),
+        Err(_) => encoding_rs::WINDOWS_1252.decode(content).0.into_owned(),
+    };
+
+    let mut statements: Vec<ParsedStatementDto> = Vec::new();
+    let mut section = QifSection::Transactions;
+    let mut account: Option<String> = None;
+    let mut record: Vec<(char, String)> = Vec::new();
+    let mut record_line = 0;
+    let mut has_header = false;
+
+    let mut finish_record = |section: QifSection, account: &mut Option<String>, record: &mut Vec<(char, String)>, record_line: i32| {
+        if record.is_empty() {
+            return;
+        }
+        match section {
+            QifSection::Account => {
+                if let Some((_, name)) = record.iter().find(|(code, _)| *code == 'N') {
+                    *account = Some(name.trim().to_string());
+                }
+            }
+            QifSection::Transactions => {
+                let index = match statements.iter().position(|statement| statement.account_id == *account) {
+                    Some(index) => index,
+                    None => {
+                        statements.push(ParsedStatementDto {
+                            account_id: account.clone(),
+                            ..Default::default()
+                        });
+                        statements.len() - 1
+                    }
+                };
+                let statement = &mut statements[index];
+                match read_record(record, date_order) {
+                    Ok((occurred_on, amount, description, external_reference)) => statement.lines.push(StatementLineDto {
+                        line: record_line,
+                        occurred_on,
+                        amount,
+                        description,
+                        external_reference,
+                    }),
+                    Err(message) => statement.errors.push(ResImportLineErrorDto { line: record_line, message }),
+                }
+            }
+            QifSection::Other => {}
+        }
+        record.clear();
+    };
+
+    for (index, raw_line) in text.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let line_number = index as i32 + 1;
+
+        if line.starts_with('!') {
+            // a record left open by a missing ^ ends at the next header
+            finish_record(section, &mut account, &mut record, record_line);
+            has_header = true;
+            let header = line.to_ascii_lowercase();
+            if header.starts_with("!account") {
+                section = QifSection::Account;
+            } else if let Some(kind) = header.strip_prefix("!type:") {
+                section = match kind.trim() {
+                    "bank" | "cash" | "ccard" | "oth a" | "oth l" => QifSection::Transactions,
+                    _ => QifSection::Other,
+                };
+            }
+            // !Option and !Clear lines keep the current section
+            continue;
+        }
+
+        if line.starts_with('^') {
+            finish_record(section, &mut account, &mut record, record_line);
+            continue;
+        }
+
+        if record.is_empty() {
+            record_line = line_number;
+        }
+        let mut chars = line.chars();
+        if let Some(code) = chars.next() {
+            record.push((code.to_ascii_uppercase(), chars.as_str().to_string()));
+        }
+    }
+    finish_record(section, &mut account, &mut record, record_line);
+
+    if !has_header && statements.iter().all(|statement| statement.lines.is_empty()) {
+        return Err("The file is not a QIF statement".to_string());
+    }
+    if statements.is_empty() {
+        return Err("The file holds no bank, cash or credit card transactions".to_string());
+    }
+    Ok(statements)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn amount(raw: &str) -> String {
+        parse_qif_amount(raw).unwrap().to_string()
+    }
+
+    #[test]
+    fn quicken_dates_carry_the_century_in_the_separator() {
+        assert_eq!(parse_qif_date("6/ 1'25", DateOrderVariant::Mdy), Ok(date(2025, 6, 1)));
+        assert_eq!(parse_qif_date("6/ 1/98", DateOrderVariant::Mdy), Ok(date(1998, 6, 1)));
+        assert_eq!(parse_qif_date("6/ 1/25", DateOrderVariant::Mdy), Ok(date(2025, 6, 1)));
+    }
+
+    #[test]
+    fn date_order_decides_day_and_month() {
+        assert_eq!(parse_qif_date("06/01/2025", DateOrderVariant::Mdy), Ok(date(2025, 6, 1)));
+        assert_eq!(parse_qif_date("06/01/2025", DateOrderVariant::Dmy), Ok(date(2025, 1, 6)));
+        assert_eq!(parse_qif_date("01.06.2025", DateOrderVariant::Dmy), Ok(date(2025, 6, 1)));
+        assert!(parse_qif_date("13/31/2025", DateOrderVariant::Dmy).is_err());
+    }
+
+    #[test]
+    fn four_digit_year_first_ignores_the_date_order() {
+        assert_eq!(parse_qif_date("2025-06-01", DateOrderVariant::Dmy), Ok(date(2025, 6, 1)));
+        assert_eq!(parse_qif_date("2025-06-01", DateOrderVariant::Mdy), Ok(date(2025, 6, 1)));
+        assert!(parse_qif_date("2025-06", DateOrderVariant::Mdy).is_err());
+    }
+
+    #[test]
+    fn amount_separator_is_the_last_one_followed_by_one_or_two_digits() {
+        assert_eq!(amount("-1,234.56"), "-1234.56");
+        assert_eq!(amount("-1.234,56"), "-1234.56");
+        assert_eq!(amount("1,234"), "1234");
+        assert_eq!(amount("1.234"), "1234");
+        assert_eq!(amount("12,5"), "12.5");
+        assert_eq!(amount("1500"), "1500");
+        assert!(parse_qif_amount("").is_err());
+    }
+
+    #[test]
+    fn statement_reads_transactions_per_account_and_skips_other_sections() {
+        let content = "!Account\nNChecking\nTBank\n^\n!Type:Bank\nD6/ 1'25\nT-4.50\nPCoffee\nN1001\n^\nD6/ 2'25\nT1,500.00\nPSalary\nMsalary\nNDEP\n^\n!Type:Cat\nNFood\n^\n!Type:Bank\nD6/31'25\nT-1.00\n^\n";
+        let statements = parse_qif_statement(content.as_bytes(), DateOrderVariant::Mdy).unwrap();
+
+        assert_eq!(statements.len(), 1);
+        let statement = &statements[0];
+        assert_eq!(statement.account_id.as_deref(), Some("Checking"));
+        let lines: Vec<_> = statement
+            .lines
+            .iter()
+            .map(|line| (line.line, line.amount.to_string(), line.description.as_str(), line.external_reference.as_deref()))
+            .collect();
+        assert_eq!(lines, [
+            (6, "-4.50".to_string(), "Coffee", Some("1001")),
+            (11, "1500.00".to_string(), "Salary", None),
+        ]);
+        let errors: Vec<_> = statement.errors.iter().map(|error| error.line).collect();
+        assert_eq!(errors, [21]);
+    }
+
+    #[test]
+    fn file_without_header_or_transactions_is_rejected() {
+        assert!(parse_qif_statement(b"Date,Amount\n2025-06-01,-4.50\n", DateOrderVariant::Mdy).is_err());
+        assert!(parse_qif_statement(b"!Type:Cat\nNFood\n^\n", DateOrderVariant::Mdy).is_err());
+    }
+}
@@ -0,0 +1,463 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Datelike, Duration, Months, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+
+
+
+
+// Upper bound of periods walked while looking for an occurrence, a rule such as
+// "every day that is the 31st and a Monday" can go a long time without matching
+const MAX_RECURRENCE_PERIODS: u32 = 100_000;
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecurrenceFrequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl RecurrenceFrequency {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecurrenceFrequency::Daily => "DAILY",
+            RecurrenceFrequency::Weekly => "WEEKLY",
+            RecurrenceFrequency::Monthly => "MONTHLY",
+        }
+    }
+}
+
+
+// The RFC 5545 RRULE subset used by recurring transactions:
+// FREQ=DAILY|WEEKLY|MONTHLY, INTERVAL, BYDAY (plain weekdays, no ordinal prefix),
+// BYMONTHDAY (1..31 or -1..-31 from the end of the month), BYSETPOS (one value, MONTHLY only),
+// COUNT and UNTIL. DTSTART is not part of the rule, it is the schedule's start_at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecurrenceRule {
+    pub frequency: RecurrenceFrequency,
+    pub interval: u32,
+    pub by_day: Vec<Weekday>,
+    pub by_month_day: Vec<i32>,
+    pub by_set_pos: Option<i32>,
+    pub count: Option<u32>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl RecurrenceRule {
+    pub fn daily(interval: u32) -> Self {
+        Self::new(RecurrenceFrequency::Daily, interval)
+    }
+
+    pub fn weekly(interval: u32, by_day: Vec<Weekday>) -> Self {
+        Self {
+            by_day,
+            ..Self::new(RecurrenceFrequency::Weekly, interval)
+        }
+    }
+
+    pub fn monthly_on_day(interval: u32, day: i32) -> Self {
+        Self {
+            by_month_day: vec![day],
+            ..Self::new(RecurrenceFrequency::Monthly, interval)
+        }
+    }
+
+    // the last Monday to Friday of the month, public holidays are not taken into account
+    pub fn last_business_day(interval: u32) -> Self {
+        Self {
+            by_day: vec![Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri],
+            by_set_pos: Some(-1),
+            ..Self::new(RecurrenceFrequency::Monthly, interval)
+        }
+    }
+
+    fn new(frequency: RecurrenceFrequency, interval: u32) -> Self {
+        Self {
+            frequency,
+            interval,
+            by_day: Vec::new(),
+            by_month_day: Vec::new(),
+            by_set_pos: None,
+            count: None,
+            until: None,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.interval == 0 {
+            return Err("INTERVAL must be at least 1".to_string());
+        }
+        if let Some(day) = self.by_month_day.iter().find(|day| **day == 0 || day.abs() > 31) {
+            return Err(format!("BYMONTHDAY {} must be between 1 and 31 or -31 and -1", day));
+        }
+        if let Some(position) = self.by_set_pos {
+            if self.frequency != RecurrenceFrequency::Monthly {
+                return Err("BYSETPOS is only supported with FREQ=MONTHLY".to_string());
+            }
+            if position == 0 || position.abs() > 31 {
+                return Err(format!("BYSETPOS {} must be between 1 and 31 or -31 and -1", position));
+            }
+        }
+        if self.count == Some(0) {
+            return Err("COUNT must be at least 1".to_string());
+        }
+        Ok(())
+    }
+
+    // Occurrences in order, starting at `start_at` read as a local time in `timezone`, so
+    // "09:00 Asia/Bangkok" stays 09:00 local on every occurrence. Only occurrences at or after
+    // `from` are returned, COUNT still counts from `start_at`. Stops after `limit` occurrences,
+    // at COUNT / UNTIL, or when `until` is passed
+    pub fn occurrences(
+        &self,
+        start_at: DateTime<Utc>,
+        timezone: Tz,
+        from: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+        limit: usize,
+    ) -> Vec<DateTime<Utc>> {
+        let local_start = start_at.with_timezone(&timezone).naive_local();
+        let start_date = local_start.date();
+        let time_of_day = local_start.time();
+        let until = match (self.until, until) {
+            (Some(rule_until), Some(until)) => Some(rule_until.min(until)),
+            (rule_until, until) => rule_until.or(until),
+        };
+
+        let mut result = Vec::new();
+        let mut emitted: u32 = 0;
+        for period in 0..MAX_RECURRENCE_PERIODS {
+            let candidates = match self.period_dates(start_date, period) {
+                Some(candidates) => candidates,
+                None => break,
+            };
+            for date in candidates {
+                if date < start_date {
+                    continue;
+                }
+                let occurrence = to_utc(date.and_time(time_of_day), timezone);
+                if until.is_some_and(|until| occurrence > until) {
+                    return result;
+                }
+                if self.count.is_some_and(|count| emitted >= count) || result.len() >= limit {
+                    return result;
+                }
+                emitted += 1;
+                if from.is_none_or(|from| occurrence >= from) {
+                    result.push(occurrence);
+                }
+            }
+        }
+        result
+    }
+
+    // The dates of the n-th period (day, week or month) counted from the start date,
+    // None once the calendar runs out
+    fn period_dates(&self, start_date: NaiveDate, period: u32) -> Option<Vec<NaiveDate>> {
+        let step = period.checked_mul(self.interval)?;
+        let mut dates = match self.frequency {
+            RecurrenceFrequency::Daily => {
+                let date = start_date.checked_add_signed(Duration::days(i64::from(step)))?;
+                vec![date]
+            }
+            RecurrenceFrequency::Weekly => {
+                let week_start = start_date
+                    .checked_sub_signed(Duration::days(i64::from(start_date.weekday().num_days_from_monday())))?
+                    .checked_add_signed(Duration::weeks(i64::from(step)))?;
+                let weekdays = if self.by_day.is_empty() { vec![start_date.weekday()] } else { self.by_day.clone() };
+                let mut dates: Vec<NaiveDate> = weekdays
+                    .iter()
+                    .filter_map(|weekday| week_start.checked_add_signed(Duration::days(i64::from(weekday.num_days_from_monday()))))
+                    .collect();
+                dates.sort();
+                dates.dedup();
+                dates
+            }
+            RecurrenceFrequency::Monthly => {
+                let month_start = start_date.with_day(1)?.checked_add_months(Months::new(step))?;
+                let days_in_month = month_start.checked_add_months(Months::new(1))?.signed_duration_since(month_start).num_days() as u32;
+                let month_days: Vec<NaiveDate> = (1..=days_in_month).filter_map(|day| month_start.with_day(day)).collect();
+                if self.by_month_day.is_empty() && self.by_day.is_empty() {
+                    // plain FREQ=MONTHLY repeats the start day, months without it are skipped
+                    month_days.into_iter().filter(|date| date.day() == start_date.day()).collect()
+                } else {
+                    month_days
+                        .into_iter()
+                        .filter(|date| self.matches_month_day(*date, days_in_month) && self.matches_weekday(*date))
+                        .collect()
+                }
+            }
+        };
+
+        if self.frequency != RecurrenceFrequency::Monthly {
+            dates.retain(|date| self.matches_month_day(*date, days_in(*date)) && self.matches_weekday(*date));
+        }
+
+        if let Some(position) = self.by_set_pos {
+            let index = if position > 0 { position as usize - 1 } else { dates.len().wrapping_sub(position.unsigned_abs() as usize) };
+            dates = dates.get(index).copied().into_iter().collect();
+        }
+
+        Some(dates)
+    }
+
+    fn matches_month_day(&self, date: NaiveDate, days_in_month: u32) -> bool {
+        self.by_month_day.is_empty()
+            || self.by_month_day.iter().any(|day| {
+                if *day > 0 {
+                    date.day() == *day as u32
+                } else {
+                    days_in_month as i32 + day + 1 == date.day() as i32
+                }
+            })
+    }
+
+    fn matches_weekday(&self, date: NaiveDate) -> bool {
+        // on a weekly rule BYDAY picks the days of the week instead of filtering them
+        self.frequency == RecurrenceFrequency::Weekly || self.by_day.is_empty() || self.by_day.contains(&date.weekday())
+    }
+}
+
+
+fn days_in(date: NaiveDate) -> u32 {
+    let month_start = date.with_day(1).unwrap_or(date);
+    month_start
+        .checked_add_months(Months::new(1))
+        .map(|next| next.signed_duration_since(month_start).num_days() as u32)
+        .unwrap_or(31)
+}
+
+// earliest() picks the first reading of an ambiguous DST hour, a skipped hour moves one hour later
+fn to_utc(local: NaiveDateTime, timezone: Tz) -> DateTime<Utc> {
+    timezone
+        .from_local_datetime(&local)
+        .earliest()
+        .or_else(|| timezone.from_local_datetime(&(local + Duration::hours(1))).earliest())
+        .map(|date| date.with_timezone(&Utc))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+fn weekday_code(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "MO",
+        Weekday::Tue => "TU",
+        Weekday::Wed => "WE",
+        Weekday::Thu => "TH",
+        Weekday::Fri => "FR",
+        Weekday::Sat => "SA",
+        Weekday::Sun => "SU",
+    }
+}
+
+pub fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    match value.trim().to_uppercase().as_str() {
+        "MO" | "MON" | "MONDAY" => Ok(Weekday::Mon),
+        "TU" | "TUE" | "TUESDAY" => Ok(Weekday::Tue),
+        "WE" | "WED" | "WEDNESDAY" => Ok(Weekday::Wed),
+        "TH" | "THU" | "THURSDAY" => Ok(Weekday::Thu),
+        "FR" | "FRI" | "FRIDAY" => Ok(Weekday::Fri),
+        "SA" | "SAT" | "SATURDAY" => Ok(Weekday::Sat),
+        "SU" | "SUN" | "SUNDAY" => Ok(Weekday::Sun),
+        _ => Err(format!("Invalid weekday '{}'", value)),
+    }
+}
+
+
+impl FromStr for RecurrenceRule {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let value = value.strip_prefix("RRULE:").unwrap_or(value);
+
+        let mut frequency = None;
+        let mut rule = Self::new(RecurrenceFrequency::Daily, 1);
+        for part in value.split(';').filter(|part| !part.trim().is_empty()) {
+            let (key, val) = part
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid RRULE part '{}'", part))?;
+            match key.trim().to_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match val.trim().to_uppercase().as_str() {
+                        "DAILY" => RecurrenceFrequency::Daily,
+                        "WEEKLY" => RecurrenceFrequency::Weekly,
+                        "MONTHLY" => RecurrenceFrequency::Monthly,
+                        other => return Err(format!("FREQ={} is not supported, expected DAILY, WEEKLY or MONTHLY", other)),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = val.trim().parse().map_err(|_| format!("Invalid INTERVAL '{}'", val))?;
+                }
+                "BYDAY" => {
+                    rule.by_day = val.split(',').map(parse_weekday).collect::<Result<_, _>>()?;
+                }
+                "BYMONTHDAY" => {
+                    rule.by_month_day = val
+                        .split(',')
+                        .map(|day| day.trim().parse().map_err(|_| format!("Invalid BYMONTHDAY '{}'", day)))
+                        .collect::<Result<_, _>>()?;
+                }
+                "BYSETPOS" => {
+                    rule.by_set_pos = Some(val.trim().parse().map_err(|_| format!("Invalid BYSETPOS '{}', only one position is supported", val))?);
+                }
+                "COUNT" => {
+                    rule.count = Some(val.trim().parse().map_err(|_| format!("Invalid COUNT '{}'", val))?);
+                }
+                "UNTIL" => {
+                    let val = val.trim();
+                    let until = NaiveDateTime::parse_from_str(val.trim_end_matches('Z'), "%Y%m%dT%H%M%S")
+                        .map(|until| until.and_utc())
+                        .or_else(|_| {
+                            NaiveDate::parse_from_str(val, "%Y%m%d")
+                                .map(|date| date.and_hms_opt(23, 59, 59).unwrap_or_default().and_utc())
+                        })
+                        .map_err(|_| format!("Invalid UNTIL '{}', expected YYYYMMDD or YYYYMMDDTHHMMSSZ", val))?;
+                    rule.until = Some(until);
+                }
+                other => return Err(format!("RRULE part {} is not supported", other)),
+            }
+        }
+
+        rule.frequency = frequency.ok_or_else(|| "RRULE needs FREQ".to_string())?;
+        if rule.count.is_some() && rule.until.is_some() {
+            return Err("RRULE cannot have both COUNT and UNTIL".to_string());
+        }
+        rule.validate()?;
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for RecurrenceRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FREQ={};INTERVAL={}", self.frequency.as_str(), self.interval)?;
+        if !self.by_day.is_empty() {
+            let days: Vec<&str> = self.by_day.iter().map(|day| weekday_code(*day)).collect();
+            write!(f, ";BYDAY={}", days.join(","))?;
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(|day| day.to_string()).collect();
+            write!(f, ";BYMONTHDAY={}", days.join(","))?;
+        }
+        if let Some(position) = self.by_set_pos {
+            write!(f, ";BYSETPOS={}", position)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, ";COUNT={}", count)?;
+        }
+        if let Some(until) = self.until {
+            write!(f, ";UNTIL={}", until.format("%Y%m%dT%H%M%SZ"))?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value).unwrap().with_timezone(&Utc)
+    }
+
+    fn dates(occurrences: &[DateTime<Utc>], timezone: Tz) -> Vec<String> {
+        occurrences
+            .iter()
+            .map(|occurrence| occurrence.with_timezone(&timezone).format("%Y-%m-%d").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn by_month_day_31_skips_short_months() {
+        let rule: RecurrenceRule = "FREQ=MONTHLY;BYMONTHDAY=31".parse().unwrap();
+        let occurrences = rule.occurrences(utc("2025-01-31T09:00:00Z"), Tz::UTC, None, None, 4);
+
+        assert_eq!(dates(&occurrences, Tz::UTC), ["2025-01-31", "2025-03-31", "2025-05-31", "2025-07-31"]);
+    }
+
+    #[test]
+    fn negative_by_month_day_is_the_last_day_of_every_month() {
+        let rule: RecurrenceRule = "FREQ=MONTHLY;BYMONTHDAY=-1".parse().unwrap();
+        let occurrences = rule.occurrences(utc("2024-01-31T09:00:00Z"), Tz::UTC, None, None, 3);
+
+        assert_eq!(dates(&occurrences, Tz::UTC), ["2024-01-31", "2024-02-29", "2024-03-31"]);
+    }
+
+    #[test]
+    fn by_set_pos_minus_one_is_the_last_business_day() {
+        let rule: RecurrenceRule = "FREQ=MONTHLY;BYDAY=MO,TU,WE,TH,FR;BYSETPOS=-1".parse().unwrap();
+        assert_eq!(rule, RecurrenceRule::last_business_day(1));
+
+        let occurrences = rule.occurrences(utc("2025-05-01T09:00:00Z"), Tz::UTC, None, None, 4);
+        // May 31 is a Saturday and August 31 a Sunday
+        assert_eq!(dates(&occurrences, Tz::UTC), ["2025-05-30", "2025-06-30", "2025-07-31", "2025-08-29"]);
+    }
+
+    #[test]
+    fn count_is_counted_from_start_not_from() {
+        let rule: RecurrenceRule = "FREQ=DAILY;COUNT=5".parse().unwrap();
+        let occurrences = rule.occurrences(
+            utc("2025-01-01T09:00:00Z"),
+            Tz::UTC,
+            Some(utc("2025-01-03T00:00:00Z")),
+            None,
+            100,
+        );
+
+        assert_eq!(dates(&occurrences, Tz::UTC), ["2025-01-03", "2025-01-04", "2025-01-05"]);
+    }
+
+    #[test]
+    fn until_is_inclusive() {
+        let rule: RecurrenceRule = "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;UNTIL=20250120".parse().unwrap();
+        let occurrences = rule.occurrences(utc("2025-01-06T09:00:00Z"), Tz::UTC, None, None, 100);
+
+        assert_eq!(dates(&occurrences, Tz::UTC), ["2025-01-06", "2025-01-09", "2025-01-20"]);
+    }
+
+    #[test]
+    fn local_time_is_kept_across_daylight_saving() {
+        let timezone: Tz = "America/New_York".parse().unwrap();
+        let rule = RecurrenceRule::daily(1);
+        // 09:00 EST on March 8, clocks go forward on March 9
+        let occurrences = rule.occurrences(utc("2025-03-08T14:00:00Z"), timezone, None, None, 3);
+
+        assert_eq!(
+            occurrences,
+            [utc("2025-03-08T14:00:00Z"), utc("2025-03-09T13:00:00Z"), utc("2025-03-10T13:00:00Z")]
+        );
+    }
+
+    #[test]
+    fn skipped_spring_forward_hour_moves_one_hour_later() {
+        let timezone: Tz = "America/New_York".parse().unwrap();
+        let rule = RecurrenceRule::daily(1);
+        // 02:30 EST on March 8, 02:30 does not exist on March 9
+        let occurrences = rule.occurrences(utc("2025-03-08T07:30:00Z"), timezone, None, None, 3);
+
+        assert_eq!(
+            occurrences,
+            [utc("2025-03-08T07:30:00Z"), utc("2025-03-09T07:30:00Z"), utc("2025-03-10T06:30:00Z")]
+        );
+        assert_eq!(occurrences[1].with_timezone(&timezone).format("%H:%M").to_string(), "03:30");
+    }
+
+    #[test]
+    fn display_round_trips_through_from_str() {
+        let rule: RecurrenceRule = "RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;COUNT=10".parse().unwrap();
+        let text = rule.to_string();
+
+        assert_eq!(text, "FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,FR;COUNT=10");
+        assert_eq!(text.parse::<RecurrenceRule>().unwrap(), rule);
+    }
+
+    #[test]
+    fn unsupported_or_conflicting_parts_are_rejected() {
+        assert!("FREQ=YEARLY".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=DAILY;COUNT=3;UNTIL=20250101".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=WEEKLY;BYSETPOS=1".parse::<RecurrenceRule>().is_err());
+        assert!("FREQ=MONTHLY;BYMONTHDAY=32".parse::<RecurrenceRule>().is_err());
+        assert!("INTERVAL=2".parse::<RecurrenceRule>().is_err());
+    }
+}
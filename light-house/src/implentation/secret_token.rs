@@ -0,0 +1,14 @@
+use sha2::{Digest, Sha256};
+
+
+
+
+// random bearer secret, 32 bytes in hex after the prefix
+pub fn generate_secret_token(prefix: &str) -> String {
+    format!("{}{}", prefix, hex::encode(rand::random::<[u8; 32]>()))
+}
+
+// secrets are looked up by this sha-256 hex digest, the secret itself is never stored
+pub fn hash_secret_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
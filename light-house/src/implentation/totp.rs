@@ -0,0 +1,56 @@
+use totp_rs::{Algorithm, Secret, TOTP};
+
+
+
+
+// authenticator apps assume these, sha1 with six digits every thirty seconds
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP_SECS: u64 = 30;
+
+// 160 bit shared secret in base32, the form authenticator apps accept when typed in by hand
+pub fn generate_totp_secret() -> String {
+    Secret::Raw(rand::random::<[u8; 20]>().to_vec()).to_encoded().to_string()
+}
+
+fn build_totp(secret: &str, issuer: &str, account: &str) -> Option<TOTP> {
+    let secret_bytes = Secret::Encoded(secret.to_string()).to_bytes().ok()?;
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        1,
+        TOTP_STEP_SECS,
+        secret_bytes,
+        Some(issuer.to_string()),
+        account.to_string(),
+    )
+    .ok()
+}
+
+// otpauth:// link for the qr code shown during enrolment
+pub fn totp_uri(secret: &str, issuer: &str, account: &str) -> Option<String> {
+    build_totp(secret, issuer, account).map(|totp| totp.get_url())
+}
+
+// the time step the code belongs to, one step of clock drift either way is accepted.
+// callers store the step so a code can not be played back inside its window
+pub fn matching_totp_step(secret: &str, code: &str, unix_time: u64) -> Option<u64> {
+    let totp = build_totp(secret, "", "")?;
+    let current_step = unix_time / TOTP_STEP_SECS;
+    [current_step.saturating_sub(1), current_step, current_step + 1]
+        .into_iter()
+        .find(|step| totp.generate(step * TOTP_STEP_SECS) == code)
+}
+
+// one time fallback code, "xxxxx-xxxxx" in lowercase hex
+pub fn generate_recovery_code() -> String {
+    let raw = hex::encode(rand::random::<[u8; 5]>());
+    format!("{}-{}", &raw[..5], &raw[5..])
+}
+
+// recovery codes are compared without the dash and case, so typing them is forgiving
+pub fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{domain::{dto::{account_dto::CreateActionTokenDto, dto_enum::action_token_purpose::ActionTokenPurposeVariant}, entities::{user, user_action_token}, req_repository::action_token_repository::ActionTokenRepository}, soc::soc_repository::RepositoryError};
+
+
+
+
+pub struct ActionTokenRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+#[async_trait::async_trait]
+impl ActionTokenRepository for ActionTokenRepositoryImpl {
+    async fn create_action_token(&self, user_id: Uuid, dto: CreateActionTokenDto)
+        -> Result<user_action_token::Model, RepositoryError>
+    {
+        let new_token = user_action_token::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            token_hash: Set(dto.token_hash),
+            purpose: Set(dto.purpose.as_str().to_string()),
+            email: Set(dto.email),
+            expires_at: Set(dto.expires_at),
+            used_at: Set(None),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let inserted_token = new_token
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(inserted_token)
+    }
+
+
+    async fn find_action_token_by_hash(&self, token_hash: &str)
+        -> Result<Option<user_action_token::Model>, RepositoryError>
+    {
+        let token = user_action_token::Entity::find()
+            .filter(user_action_token::Column::TokenHash.eq(token_hash))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(token)
+    }
+
+
+    async fn find_latest_action_token(&self, user_id: Uuid, purpose: ActionTokenPurposeVariant)
+        -> Result<Option<user_action_token::Model>, RepositoryError>
+    {
+        let token = user_action_token::Entity::find()
+            .filter(user_action_token::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(user_action_token::Column::Purpose.eq(purpose.as_str()))
+            .order_by_desc(user_action_token::Column::CreatedAt)
+            .order_by_desc(user_action_token::Column::Id)
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(token)
+    }
+
+
+    async fn consume_action_token(&self, token_id: Uuid, now: DateTime<Utc>)
+        -> Result<bool, RepositoryError>
+    {
+        // one conditional update, so a token presented twice at once is only accepted once
+        let update_result = user_action_token::Entity::update_many()
+            .col_expr(user_action_token::Column::UsedAt, Expr::value(now))
+            .filter(user_action_token::Column::Id.eq(token_id.as_bytes().to_vec()))
+            .filter(user_action_token::Column::UsedAt.is_null())
+            .filter(user_action_token::Column::ExpiresAt.gt(now))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected > 0)
+    }
+
+
+    async fn invalidate_action_tokens(&self, user_id: Uuid, purpose: ActionTokenPurposeVariant, now: DateTime<Utc>)
+        -> Result<u64, RepositoryError>
+    {
+        let update_result = user_action_token::Entity::update_many()
+            .col_expr(user_action_token::Column::UsedAt, Expr::value(now))
+            .filter(user_action_token::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(user_action_token::Column::Purpose.eq(purpose.as_str()))
+            .filter(user_action_token::Column::UsedAt.is_null())
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected)
+    }
+
+
+    async fn mark_email_verified(&self, user_id: Uuid, email: &str, now: DateTime<Utc>)
+        -> Result<bool, RepositoryError>
+    {
+        let update_result = user::Entity::update_many()
+            .col_expr(user::Column::EmailVerifiedAt, Expr::value(now))
+            .col_expr(user::Column::UpdatedAt, Expr::value(now))
+            .filter(user::Column::Id.eq(user_id.as_bytes().to_vec()))
+            .filter(user::Column::Email.eq(email))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected > 0)
+    }
+}
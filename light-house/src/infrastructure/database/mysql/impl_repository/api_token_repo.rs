@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{domain::{dto::api_token_dto::CreateApiTokenDto, entities::api_token, req_repository::api_token_repository::{ApiTokenRepositoryBase, ApiTokenRepositoryUtility}}, soc::soc_repository::RepositoryError};
+
+
+
+
+// length of the last_used_ip column
+const MAX_IP_LENGTH: usize = 45;
+
+
+pub struct ApiTokenRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+#[async_trait::async_trait]
+impl ApiTokenRepositoryBase for ApiTokenRepositoryImpl {
+    async fn create(&self, user_id: Uuid, dto: CreateApiTokenDto)
+        -> Result<api_token::Model, RepositoryError>
+    {
+        let scopes = dto.scopes.iter().map(|scope| scope.as_str()).collect::<Vec<_>>();
+        let new_token = api_token::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            name: Set(dto.name),
+            token_hash: Set(dto.token_hash),
+            token_prefix: Set(dto.token_prefix),
+            scopes: Set(serde_json::json!(scopes)),
+            expires_at: Set(dto.expires_at),
+            last_used_at: Set(None),
+            last_used_ip: Set(None),
+            revoked_at: Set(None),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let inserted_token = new_token
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(inserted_token)
+    }
+
+
+    async fn find_by_id(&self, user_id: Uuid, token_id: Uuid)
+        -> Result<Option<api_token::Model>, RepositoryError>
+    {
+        let token = api_token::Entity::find()
+            .filter(api_token::Column::Id.eq(token_id.as_bytes().to_vec()))
+            .filter(api_token::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(token)
+    }
+
+
+    async fn find_all_by_user_id(&self, user_id: Uuid)
+        -> Result<Vec<api_token::Model>, RepositoryError>
+    {
+        let tokens = api_token::Entity::find()
+            .filter(api_token::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .order_by_desc(api_token::Column::CreatedAt)
+            .order_by_desc(api_token::Column::Id)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(tokens)
+    }
+
+
+    async fn revoke(&self, user_id: Uuid, token_id: Uuid, now: DateTime<Utc>)
+        -> Result<api_token::Model, RepositoryError>
+    {
+        // Step 1: Only revoke a token that is still live
+        api_token::Entity::update_many()
+            .col_expr(api_token::Column::RevokedAt, Expr::value(now))
+            .col_expr(api_token::Column::UpdatedAt, Expr::value(now))
+            .filter(api_token::Column::Id.eq(token_id.as_bytes().to_vec()))
+            .filter(api_token::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(api_token::Column::RevokedAt.is_null())
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        // Step 2: Return the token as stored
+        self.find_by_id(user_id, token_id)
+            .await?
+            .ok_or_else(|| RepositoryError::NotFound("API token not found".to_string()))
+    }
+}
+
+
+#[async_trait::async_trait]
+impl ApiTokenRepositoryUtility for ApiTokenRepositoryImpl {
+    async fn find_by_token_hash(&self, token_hash: &str)
+        -> Result<Option<api_token::Model>, RepositoryError>
+    {
+        let token = api_token::Entity::find()
+            .filter(api_token::Column::TokenHash.eq(token_hash))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(token)
+    }
+
+
+    async fn touch(&self, token_id: Uuid, now: DateTime<Utc>, ip: Option<String>)
+        -> Result<(), RepositoryError>
+    {
+        let ip = ip.map(|ip| ip.chars().take(MAX_IP_LENGTH).collect::<String>());
+        api_token::Entity::update_many()
+            .col_expr(api_token::Column::LastUsedAt, Expr::value(now))
+            .col_expr(api_token::Column::LastUsedIp, Expr::value(ip))
+            .filter(api_token::Column::Id.eq(token_id.as_bytes().to_vec()))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
+use rust_decimal::Decimal;
 
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait};
 use uuid::Uuid;
 
 use crate::{domain::{dto::asset_dto::{ReqCreateAssetDto, ReqUpdateAssetDto}, entities::{asset, asset_type}, req_repository::{asset_repository::{AssetRepositoryBase, AssetRepositoryUtility}, balance_repository::BalanceRepositoryBase}}, soc::soc_repository::RepositoryError};
@@ -51,9 +52,14 @@ impl AssetRepositoryBase for AssetRepositoryImpl{
             ..Default::default()
         };
 
+        // The asset and its CurrentSheet are created together or not at all
+        let txn = self.db_pool.begin().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
+        })?;
+
         // Insert the asset into the database
         let inserted_asset = new_asset
-            .insert(self.db_pool.as_ref())
+            .insert(&txn)
             .await
             .map_err(|err| {
                 if let sea_orm::DbErr::Exec(exec_err) = &err {
@@ -71,13 +77,22 @@ impl AssetRepositoryBase for AssetRepositoryImpl{
             db_pool: Arc::clone(&self.db_pool),
         };
 
-        balance_repo
+        if let Err(err) = balance_repo
             .create_current_sheet(
+                &txn,
                 user_id,
                 Uuid::from_slice(&inserted_asset.id).unwrap(),
-                0.0, // Initial balance is 0
+                Decimal::ZERO, // Initial balance is 0
             )
-            .await?;
+            .await
+        {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
+
+        txn.commit().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to commit transaction: {}", err))
+        })?;
 
         // Fetch the inserted asset using the correct asset_id
         log::debug!(
@@ -210,16 +225,28 @@ impl AssetRepositoryBase for AssetRepositoryImpl{
         user_id: Uuid, 
         asset_id: Uuid
     ) -> Result<(), RepositoryError> {
+        // The asset and its CurrentSheet are deleted together or not at all
+        let txn = self.db_pool.begin().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
+        })?;
+
         // Attempt to delete the asset by ID and ensure it belongs to the user
-        let result = asset::Entity::delete_many()
+        let result = match asset::Entity::delete_many()
             .filter(asset::Column::Id.eq(asset_id.as_bytes().to_vec())) // Filter by asset ID
             .filter(asset::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
-            .exec(self.db_pool.as_ref())
+            .exec(&txn)
             .await
-            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        {
+            Ok(result) => result,
+            Err(err) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
 
         // Check if any rows were affected (i.e., if the asset was deleted)
         if result.rows_affected == 0 {
+            txn.rollback().await.ok(); // Rollback on error
             return Err(RepositoryError::NotFound(format!(
                 "Asset with ID {} not found for user {}",
                 asset_id, user_id
@@ -231,15 +258,15 @@ impl AssetRepositoryBase for AssetRepositoryImpl{
             db_pool: Arc::clone(&self.db_pool),
         };
 
-        match balance_repo.delete_current_sheet_by_asset_id(user_id, asset_id).await {
-            Ok(_) => Ok(()), // Successfully deleted
+        match balance_repo.delete_current_sheet_by_asset_id(&txn, user_id, asset_id).await {
+            Ok(_) => {} // Successfully deleted
             Err(RepositoryError::NotFound(_)) => {
                 log::warn!(
                     "No CurrentSheet record found for asset ID {} and user ID {}",
                     asset_id,
                     user_id
                 );
-                Ok(()) // Gracefully handle missing CurrentSheet
+                // Gracefully handle missing CurrentSheet
             }
             Err(err) => {
                 log::error!(
@@ -248,9 +275,16 @@ impl AssetRepositoryBase for AssetRepositoryImpl{
                     user_id,
                     err
                 );
-                Err(err) // Propagate other errors
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(err); // Propagate other errors
             }
         }
+
+        txn.commit().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to commit transaction: {}", err))
+        })?;
+
+        Ok(())
     }
 }
 
@@ -0,0 +1,215 @@
+use std::sync::Arc;
+
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, QueryTrait, Select, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{domain::{dto::{attachment_dto::CreateAttachmentDto, dto_enum::attachment_owner::AttachmentOwnerVariant}, entities::{asset, attachment, contact, transaction, user, user_contact}, req_repository::attachment_repository::{AttachmentRepositoryBase, AttachmentRepositoryUtility}}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+// number of attachments of the user and their total size,
+// SUM of a BIGINT is a DECIMAL in MySQL, cast it back
+fn usage_query(user_id: Uuid) -> Select<attachment::Entity> {
+    attachment::Entity::find()
+        .select_only()
+        .column_as(Expr::cust("COUNT(*)"), "count")
+        .column_as(Expr::cust("CAST(COALESCE(SUM(`attachment`.`size_bytes`), 0) AS SIGNED)"), "used_bytes")
+        .filter(attachment::Column::UserId.eq(user_id.as_bytes().to_vec()))
+}
+
+
+pub struct AttachmentRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>
+}
+
+impl AttachmentRepositoryImpl {
+    pub fn new(db_pool: Arc<DatabaseConnection>) -> Self {
+        Self { db_pool }
+    }
+}
+
+
+#[async_trait::async_trait]
+impl AttachmentRepositoryBase for AttachmentRepositoryImpl {
+    async fn create(&self, user_id: Uuid, dto: CreateAttachmentDto, quota_bytes: i64)
+        -> Result<Option<attachment::Model>, RepositoryError>
+    {
+        let txn = self.db_pool.begin().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
+        })?;
+
+        // uploads of one user queue up here until the one before has committed its row
+        let locked_user = user::Entity::find_by_id(user_id.as_bytes().to_vec())
+            .lock_exclusive()
+            .one(&txn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        if locked_user.is_none() {
+            txn.rollback().await.ok();
+            return Err(RepositoryError::NotFound(format!("User with ID {} not found", user_id)));
+        }
+
+        let (_, used_bytes) = usage_query(user_id)
+            .into_tuple::<(i64, i64)>()
+            .one(&txn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .unwrap_or((0, 0));
+        if used_bytes + dto.size_bytes > quota_bytes {
+            txn.rollback().await.ok();
+            return Ok(None);
+        }
+
+        let new_attachment = attachment::ActiveModel {
+            id: Set(dto.id.as_bytes().to_vec()),
+            owner_type: Set(dto.owner_type.as_str().to_string()),
+            owner_id: Set(dto.owner_id.as_bytes().to_vec()),
+            file_name: Set(dto.file_name),
+            content_type: Set(dto.content_type),
+            size_bytes: Set(dto.size_bytes),
+            sha256: Set(dto.sha256),
+            storage_key: Set(dto.storage_key),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let created = match new_attachment.insert(&txn).await {
+            Ok(created) => created,
+            Err(err) => {
+                txn.rollback().await.ok();
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
+
+        txn.commit().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to commit transaction: {}", err))
+        })?;
+        Ok(Some(created))
+    }
+
+
+    async fn delete(&self, attachment_id: Uuid)
+        -> Result<(), RepositoryError>
+    {
+        let result = attachment::Entity::delete_many()
+            .filter(attachment::Column::Id.eq(attachment_id.as_bytes().to_vec()))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound(format!(
+                "Attachment with ID {} not found",
+                attachment_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+
+#[async_trait::async_trait]
+impl AttachmentRepositoryUtility for AttachmentRepositoryImpl {
+    async fn find_by_user_id_and_attachment_id(&self, user_id: Uuid, attachment_id: Uuid)
+        -> Result<Option<attachment::Model>, RepositoryError>
+    {
+        attachment::Entity::find()
+            .filter(attachment::Column::Id.eq(attachment_id.as_bytes().to_vec()))
+            .filter(attachment::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+
+
+    async fn find_all_by_owner(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid)
+        -> Result<Vec<attachment::Model>, RepositoryError>
+    {
+        attachment::Entity::find()
+            .filter(attachment::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(attachment::Column::OwnerType.eq(owner_type.as_str()))
+            .filter(attachment::Column::OwnerId.eq(owner_id.as_bytes().to_vec()))
+            .order_by_desc(attachment::Column::CreatedAt)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+
+
+    async fn usage_by_user(&self, user_id: Uuid)
+        -> Result<(i64, i64), RepositoryError>
+    {
+        let usage = usage_query(user_id)
+            .into_tuple::<(i64, i64)>()
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(usage.unwrap_or((0, 0)))
+    }
+
+
+    async fn owner_exists(&self, user_id: Uuid, owner_type: AttachmentOwnerVariant, owner_id: Uuid)
+        -> Result<bool, RepositoryError>
+    {
+        let user_id = user_id.as_bytes().to_vec();
+        let owner_id = owner_id.as_bytes().to_vec();
+        let db = self.db_pool.as_ref();
+
+        let count = match owner_type {
+            AttachmentOwnerVariant::Transaction => transaction::Entity::find()
+                .filter(transaction::Column::Id.eq(owner_id))
+                .filter(transaction::Column::UserId.eq(user_id))
+                .count(db)
+                .await,
+            AttachmentOwnerVariant::Contact => user_contact::Entity::find()
+                .filter(user_contact::Column::ContactId.eq(owner_id))
+                .filter(user_contact::Column::UserId.eq(user_id))
+                .count(db)
+                .await,
+            AttachmentOwnerVariant::Asset => asset::Entity::find()
+                .filter(asset::Column::Id.eq(owner_id))
+                .filter(asset::Column::UserId.eq(user_id))
+                .count(db)
+                .await,
+        }
+        .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(count > 0)
+    }
+
+
+    async fn find_orphans(&self, limit: u64)
+        -> Result<Vec<attachment::Model>, RepositoryError>
+    {
+        let orphaned = |owner_type: AttachmentOwnerVariant, owner_ids: sea_orm::sea_query::SelectStatement| {
+            Condition::all()
+                .add(attachment::Column::OwnerType.eq(owner_type.as_str()))
+                .add(attachment::Column::OwnerId.not_in_subquery(owner_ids))
+        };
+
+        attachment::Entity::find()
+            .filter(
+                Condition::any()
+                    .add(orphaned(
+                        AttachmentOwnerVariant::Transaction,
+                        transaction::Entity::find().select_only().column(transaction::Column::Id).into_query(),
+                    ))
+                    .add(orphaned(
+                        AttachmentOwnerVariant::Contact,
+                        contact::Entity::find().select_only().column(contact::Column::Id).into_query(),
+                    ))
+                    .add(orphaned(
+                        AttachmentOwnerVariant::Asset,
+                        asset::Entity::find().select_only().column(asset::Column::Id).into_query(),
+                    )),
+            )
+            .order_by_asc(attachment::Column::CreatedAt)
+            .limit(limit)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+}
@@ -1,18 +1,26 @@
-use std::{sync::Arc, time::SystemTime};
+use std::sync::{Arc, LazyLock};
 
 
-use bcrypt::verify as bcrypt_verify;
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use bcrypt::{hash as bcrypt_hash, verify as bcrypt_verify, DEFAULT_COST};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
 
 use uuid::Uuid;
 
-use crate::{configuration::jwt_config::JwtSecret, domain::{dto::auth_dto::{Claims, ReqSignInDto, ResMeDto, ResSignInDto}, entities::{gender, user, user_role}, req_repository::auth_repository::AuthRepository}, soc::soc_repository::RepositoryError};
+use crate::{configuration::jwt_config::JwtSecret, domain::{dto::auth_dto::{Claims, ReqSignInDto, ResMeDto}, entities::{gender, user, user_role}, req_repository::auth_repository::AuthRepository}, soc::soc_repository::RepositoryError};
 
 
 
 
 
 
+// checked against when the email is unknown, so both failures take as long as a real check
+static UNKNOWN_USER_PASSWORD_HASH: LazyLock<String> = LazyLock::new(|| {
+    bcrypt_hash("light-house-unknown-user", DEFAULT_COST).unwrap_or_default()
+});
+
+// the client gets the same message whichever part was wrong
+const INVALID_CREDENTIALS: &str = "Invalid email or password";
 
 
 pub struct AuthRepositoryImpl {
@@ -30,43 +38,51 @@ impl AuthRepositoryImpl {
 impl AuthRepository for AuthRepositoryImpl {
     
     async fn sign_in(&self, sign_in_dto: ReqSignInDto
-    ) -> Result<ResSignInDto, RepositoryError>
+    ) -> Result<user::Model, RepositoryError>
     {
         // create connection
         let conn = Arc::clone(&self.db_pool);
-        let jwt_config = JwtSecret::default();
         // find user by email
         let user = user::Entity::find()
             .filter(user::Column::Email.eq(sign_in_dto.email))
             .one(conn.as_ref())
             .await
             .map_err(|err| RepositoryError::from(err))?;
-        // check is user exist
+        // check is user exist, an unknown email still pays for a password check
         let user = match user {
             Some(user) => user,
-            None => return Err(RepositoryError::NotFound("User not found".to_string())),
+            None => {
+                let _ = bcrypt_verify(&sign_in_dto.password, &UNKNOWN_USER_PASSWORD_HASH);
+                return Err(RepositoryError::NotFound(INVALID_CREDENTIALS.to_string()));
+            }
         };
         // check is password correct
         let is_password_correct = bcrypt_verify(&sign_in_dto.password, &user.password)
-           .map_err(|_| RepositoryError::InvalidInput("Error while verifying password".to_string()))?;
+           .map_err(|_| RepositoryError::InvalidInput(INVALID_CREDENTIALS.to_string()))?;
 
         if !is_password_correct {
-            return Err(RepositoryError::InvalidInput("Password is incorrect".to_string()));
+            return Err(RepositoryError::InvalidInput(INVALID_CREDENTIALS.to_string()));
         }
 
+        Ok(user)
+
+    }
+
+    async fn generate_access_token(&self, user_id: Uuid, session_id: Uuid, role: &str, expires_at: DateTime<Utc>
+    ) -> Result<String, RepositoryError>
+    {
+        let jwt_config = JwtSecret::default();
 
         // generate claim 
         let claim = Claims {
-            sub: Uuid::from_slice(&user.id)
-                .map_err(|_| RepositoryError::InvalidInput("Invalid UUID format".to_string()))?,
-            role: "user".to_string(),
-            exp: SystemTime::now()
-                .duration_since(SystemTime::UNIX_EPOCH)
+            sub: user_id,
+            role: role.to_string(),
+            exp: u64::try_from(expires_at.timestamp())
                 .map_err(|e| RepositoryError::InvalidInput(format!(
                     "Time error: {:?}",
                     e
-                )))?
-                .as_secs() + 4 * 60 * 60,
+                )))?,
+            sid: session_id,
         };
 
         // Generate token
@@ -78,12 +94,45 @@ impl AuthRepository for AuthRepositoryImpl {
             &claim,
             &jsonwebtoken::EncodingKey::from_secret(jwt_config.jwt_secret.as_bytes()),
         ).map_err(|_| RepositoryError::InvalidInput("Error while generating token".to_string()))?;
-        // return token
-        let res = ResSignInDto {
-            token
-        };
-        Ok(res)
 
+        Ok(token)
+    }
+
+    async fn verify_password(&self, user_id: Uuid, password: &str
+    ) -> Result<bool, RepositoryError>
+    {
+        let user = user::Entity::find()
+            .filter(user::Column::Id.eq(user_id))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!("User with ID {} not found", user_id)))?;
+
+        bcrypt_verify(password, &user.password)
+            .map_err(|_| RepositoryError::OperationFailed("Failed to verify password".to_string()))
+    }
+
+    async fn set_password(&self, user_id: Uuid, password: &str
+    ) -> Result<(), RepositoryError>
+    {
+        let user = user::Entity::find()
+            .filter(user::Column::Id.eq(user_id))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!("User with ID {} not found", user_id)))?;
+
+        let hashed_password = bcrypt_hash(password, DEFAULT_COST)
+            .map_err(|_| RepositoryError::InvalidInput("Failed to hash password".to_string()))?;
+        let mut active_model: user::ActiveModel = user.into();
+        active_model.password = Set(hashed_password);
+        active_model.updated_at = Set(Some(Utc::now()));
+        active_model
+            .update(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(())
     }
 
 }
@@ -137,6 +186,7 @@ impl AuthRepositoryImpl {
             email: user.email,
             first_name: user.first_name,
             last_name: user.last_name,
+            email_verified: user.email_verified_at.is_some(),
         };
 
         Ok(res)
@@ -1,11 +1,13 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use sea_orm::{ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter, QuerySelect};
-use sea_orm::{ActiveValue::Set, DatabaseConnection};
+use chrono::{DateTime, Utc};
+use sea_orm::{ActiveModelTrait, ColumnTrait, Condition, EntityTrait, QueryFilter, QueryOrder, QuerySelect, TransactionTrait};
+use sea_orm::{ActiveValue::Set, DatabaseConnection, DatabaseTransaction};
 use uuid::Uuid;
 use rust_decimal::Decimal;
-use rust_decimal::prelude::*;
-use crate::domain::entities::asset;
+use crate::domain::dto::current_sheet_dto::{BalanceMovementDto, CurrentSheetReconcileDto};
+use crate::domain::entities::{asset, transaction, transaction_type};
 use crate::domain::req_repository::balance_repository::BalanceRepositoryUtill;
 use crate::{domain::{entities::current_sheet, req_repository::balance_repository::BalanceRepositoryBase}, soc::soc_repository::RepositoryError};
 
@@ -15,6 +17,17 @@ pub struct BalanceRepositoryImpl {
     pub db_pool: Arc<DatabaseConnection>,
 }
 
+impl BalanceRepositoryImpl {
+    async fn find_transaction_type_names(&self) -> Result<HashMap<Vec<u8>, String>, RepositoryError> {
+        let transaction_types = transaction_type::Entity::find()
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(transaction_types.into_iter().map(|record| (record.id, record.name)).collect())
+    }
+}
+
 #[async_trait::async_trait]
 impl BalanceRepositoryBase for BalanceRepositoryImpl {
     
@@ -50,21 +63,37 @@ impl BalanceRepositoryBase for BalanceRepositoryImpl {
         Ok(current_sheets)
     }
 
+    async fn lock_current_sheet_by_asset_id(
+        &self,
+        txn: &DatabaseTransaction,
+        user_id: Uuid,
+        asset_id: Uuid,
+    ) -> Result<Option<current_sheet::Model>, RepositoryError> {
+        // SELECT ... FOR UPDATE, the row stays locked until txn commits or rolls back
+        // so concurrent postings to the same asset wait instead of overwriting each other
+        let current_sheet = current_sheet::Entity::find()
+            .filter(current_sheet::Column::AssetId.eq(asset_id.as_bytes().to_vec()))
+            .filter(current_sheet::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .lock_exclusive()
+            .one(txn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(current_sheet)
+    }
+
     async fn create_current_sheet(
         &self,
+        txn: &DatabaseTransaction,
         user_id: Uuid,
         asset_id: Uuid,
-        initial_balance: f64,
+        initial_balance: Decimal,
     ) -> Result<current_sheet::Model, RepositoryError> {
-        // Convert f64 to Decimal
-        let initial_balance_decimal = Decimal::from_f64(initial_balance)
-            .ok_or_else(|| RepositoryError::OperationFailed("Invalid balance value".to_string()))?;
-    
         // Create the ActiveModel for the current sheet
         let new_current_sheet = current_sheet::ActiveModel {
             id: Set(Uuid::new_v4().as_bytes().to_vec()),
             asset_id: Set(asset_id.as_bytes().to_vec()),
-            balance: Set(initial_balance_decimal), // Use Decimal here
+            balance: Set(initial_balance),
             user_id: Set(user_id.as_bytes().to_vec()),
             last_transaction_id: Set(None), // No transaction yet
             updated_at: Set(Some(chrono::Utc::now())),
@@ -72,7 +101,7 @@ impl BalanceRepositoryBase for BalanceRepositoryImpl {
     
         // Insert the current sheet into the database
         let inserted_current_sheet = new_current_sheet
-            .insert(self.db_pool.as_ref())
+            .insert(txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
     
@@ -81,13 +110,14 @@ impl BalanceRepositoryBase for BalanceRepositoryImpl {
 
     async fn update_current_sheet(
         &self,
+        txn: &DatabaseTransaction,
         user_id: Uuid,
         asset_id: Uuid,
-        balance: Option<f64>,
+        balance: Option<Decimal>,
     ) -> Result<current_sheet::Model, RepositoryError> {
-        // Ensure the current sheet exists and belongs to the user
+        // Ensure the current sheet exists and belongs to the user, and hold its lock until txn ends
         let current_sheet = self
-            .get_current_sheet_by_asset_id(user_id, asset_id)
+            .lock_current_sheet_by_asset_id(txn, user_id, asset_id)
             .await?
             .ok_or_else(|| {
                 RepositoryError::NotFound(format!(
@@ -101,15 +131,14 @@ impl BalanceRepositoryBase for BalanceRepositoryImpl {
     
         // Update the balance if provided
         if let Some(new_balance) = balance {
-            let new_balance_decimal = Decimal::from_f64(new_balance)
-                .ok_or_else(|| RepositoryError::OperationFailed("Invalid balance value".to_string()))?;
-            active_model.balance = Set(new_balance_decimal); // Use Decimal here
+            active_model.balance = Set(new_balance);
         }
+        active_model.updated_at = Set(Some(chrono::Utc::now()));
 
     
         // Save the updated current sheet to the database
         let updated_current_sheet = active_model
-            .update(self.db_pool.as_ref())
+            .update(txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
     
@@ -118,16 +147,14 @@ impl BalanceRepositoryBase for BalanceRepositoryImpl {
 
     async fn delete_current_sheet_by_asset_id(
         &self,
+        txn: &DatabaseTransaction,
         user_id: Uuid,
         asset_id: Uuid,
     ) -> Result<(), RepositoryError> {
         // Ensure the current sheet exists and belongs to the user
-        let current_sheet_exists = current_sheet::Entity::find()
-            .filter(current_sheet::Column::AssetId.eq(asset_id.as_bytes().to_vec()))
-            .filter(current_sheet::Column::UserId.eq(user_id.as_bytes().to_vec()))
-            .one(self.db_pool.as_ref())
-            .await
-            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        let current_sheet_exists = self
+            .lock_current_sheet_by_asset_id(txn, user_id, asset_id)
+            .await?;
 
         if current_sheet_exists.is_none() {
             return Err(RepositoryError::NotFound(format!(
@@ -139,7 +166,8 @@ impl BalanceRepositoryBase for BalanceRepositoryImpl {
         // Delete the current sheet
         current_sheet::Entity::delete_many()
             .filter(current_sheet::Column::AssetId.eq(asset_id.as_bytes().to_vec()))
-            .exec(self.db_pool.as_ref())
+            .filter(current_sheet::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .exec(txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
 
@@ -220,4 +248,227 @@ impl BalanceRepositoryUtill for BalanceRepositoryImpl {
 
         Ok(current_sheets)
     }
+    async fn rebuild_current_sheets(
+        &self,
+        user_id: Uuid,
+        asset_id: Option<Uuid>,
+        dry_run: bool,
+    ) -> Result<Vec<CurrentSheetReconcileDto>, RepositoryError>
+    {
+        // Everything is read and written inside one transaction so the replay sees a stable history
+        let txn = self.db_pool.begin().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        // Step 1: Map transaction type ids to their names
+        let transaction_types: HashMap<Vec<u8>, String> = match transaction_type::Entity::find().all(&txn).await {
+            Ok(types) => types.into_iter().map(|record| (record.id, record.name)).collect(),
+            Err(err) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
+
+        // Step 2: Load the stored sheets to check
+        let mut sheet_query = current_sheet::Entity::find()
+            .filter(current_sheet::Column::UserId.eq(user_id.as_bytes().to_vec()));
+        if let Some(asset_id) = asset_id {
+            sheet_query = sheet_query.filter(current_sheet::Column::AssetId.eq(asset_id.as_bytes().to_vec()));
+        }
+        if !dry_run {
+            // keep postings out until the fixed sheets are committed
+            sheet_query = sheet_query.lock_exclusive();
+        }
+        let current_sheets = match sheet_query.all(&txn).await {
+            Ok(sheets) => sheets,
+            Err(err) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
+
+        // Step 3: Replay the whole history of the user, oldest first, sheets start from zero
+        let transactions = match transaction::Entity::find()
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .order_by_asc(transaction::Column::OccurredAt)
+            .order_by_asc(transaction::Column::Id)
+            .all(&txn)
+            .await
+        {
+            Ok(records) => records,
+            Err(err) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
+
+        let mut balances: HashMap<Vec<u8>, Decimal> = HashMap::new();
+        let mut last_transactions: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
+        for record in transactions {
+            let type_name = transaction_types
+                .get(&record.transaction_type_id)
+                .map(|name| name.as_str())
+                .unwrap_or_default();
+            match type_name {
+                "income" => {
+                    *balances.entry(record.asset_id.clone()).or_insert(Decimal::ZERO) += record.amount;
+                }
+                "payment" => {
+                    *balances.entry(record.asset_id.clone()).or_insert(Decimal::ZERO) -= record.amount;
+                }
+                "transfer" => {
+                    *balances.entry(record.asset_id.clone()).or_insert(Decimal::ZERO) -= record.amount;
+                    if let Some(destination_asset_id) = &record.destination_asset_id {
+                        *balances.entry(destination_asset_id.clone()).or_insert(Decimal::ZERO) += record.amount;
+                    }
+                }
+                _ => {
+                    log::error!("Skipping transaction {:?} with unknown transaction type", Uuid::from_slice(&record.id));
+                    continue;
+                }
+            }
+
+            last_transactions.insert(record.asset_id.clone(), record.id.clone());
+            if let Some(destination_asset_id) = record.destination_asset_id {
+                last_transactions.insert(destination_asset_id, record.id);
+            }
+        }
+
+        // Step 4: Compare every stored sheet with the replayed values, fix it unless this is a dry run
+        let mut results = Vec::new();
+        for sheet in current_sheets {
+            let reconcile = CurrentSheetReconcileDto {
+                current_sheet_id: sheet.id.clone(),
+                asset_id: sheet.asset_id.clone(),
+                stored_balance: sheet.balance,
+                computed_balance: balances.get(&sheet.asset_id).copied().unwrap_or(Decimal::ZERO),
+                stored_last_transaction_id: sheet.last_transaction_id.clone(),
+                computed_last_transaction_id: last_transactions.get(&sheet.asset_id).cloned(),
+            };
+
+            if !dry_run && !reconcile.is_consistent() {
+                log::info!(
+                    "Fixing current sheet {:?}: balance {} -> {}",
+                    Uuid::from_slice(&sheet.id),
+                    reconcile.stored_balance,
+                    reconcile.computed_balance
+                );
+                let mut active_sheet: current_sheet::ActiveModel = sheet.into();
+                active_sheet.balance = Set(reconcile.computed_balance);
+                active_sheet.last_transaction_id = Set(reconcile.computed_last_transaction_id.clone());
+                active_sheet.updated_at = Set(Some(chrono::Utc::now()));
+                if let Err(err) = active_sheet.update(&txn).await {
+                    txn.rollback().await.ok(); // Rollback on error
+                    return Err(RepositoryError::DatabaseError(err.to_string()));
+                }
+            }
+
+            results.push(reconcile);
+        }
+
+        // Step 5: Only a real rebuild keeps its writes
+        if dry_run {
+            txn.rollback().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        } else {
+            txn.commit().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        }
+
+        Ok(results)
+    }
+    async fn get_balance_as_of(
+        &self,
+        user_id: Uuid,
+        asset_id: Uuid,
+        as_of: DateTime<Utc>,
+    ) -> Result<Decimal, RepositoryError>
+    {
+        let transaction_types = self.find_transaction_type_names().await?;
+
+        // Step 1: Sum everything leaving or landing on the asset as source, per transaction type
+        let totals_by_type = transaction::Entity::find()
+            .select_only()
+            .column(transaction::Column::TransactionTypeId)
+            .column_as(transaction::Column::Amount.sum(), "total")
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(transaction::Column::AssetId.eq(asset_id.as_bytes().to_vec()))
+            .filter(transaction::Column::OccurredAt.lte(as_of))
+            .group_by(transaction::Column::TransactionTypeId)
+            .into_tuple::<(Vec<u8>, Option<Decimal>)>()
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut balance = Decimal::ZERO;
+        for (transaction_type_id, total) in totals_by_type {
+            let total = total.unwrap_or(Decimal::ZERO);
+            match transaction_types.get(&transaction_type_id).map(|name| name.as_str()) {
+                Some("income") => balance += total,
+                Some("payment") | Some("transfer") => balance -= total,
+                _ => log::error!("Skipping total {} with unknown transaction type", total),
+            }
+        }
+
+        // Step 2: Add transfers that landed on the asset
+        let transferred_in = transaction::Entity::find()
+            .select_only()
+            .column_as(transaction::Column::Amount.sum(), "total")
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(transaction::Column::DestinationAssetId.eq(asset_id.as_bytes().to_vec()))
+            .filter(transaction::Column::OccurredAt.lte(as_of))
+            .into_tuple::<Option<Decimal>>()
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .flatten()
+            .unwrap_or(Decimal::ZERO);
+
+        Ok(balance + transferred_in)
+    }
+
+    async fn get_balance_movements(
+        &self,
+        user_id: Uuid,
+        asset_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<Vec<BalanceMovementDto>, RepositoryError>
+    {
+        let transaction_types = self.find_transaction_type_names().await?;
+        let asset_id_binary = asset_id.as_bytes().to_vec();
+
+        let transactions = transaction::Entity::find()
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(
+                Condition::any()
+                    .add(transaction::Column::AssetId.eq(asset_id_binary.clone()))
+                    .add(transaction::Column::DestinationAssetId.eq(asset_id_binary.clone())),
+            )
+            .filter(transaction::Column::OccurredAt.between(from, to))
+            .order_by_asc(transaction::Column::OccurredAt)
+            .order_by_asc(transaction::Column::Id)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut movements = Vec::new();
+        for record in transactions {
+            // a transfer from the asset to itself moves out and back in, so both sides are applied
+            let mut amount = Decimal::ZERO;
+            if record.asset_id == asset_id_binary {
+                match transaction_types.get(&record.transaction_type_id).map(|name| name.as_str()) {
+                    Some("income") => amount += record.amount,
+                    Some("payment") | Some("transfer") => amount -= record.amount,
+                    _ => {
+                        log::error!("Skipping transaction {:?} with unknown transaction type", Uuid::from_slice(&record.id));
+                        continue;
+                    }
+                }
+            }
+            if record.destination_asset_id.as_ref() == Some(&asset_id_binary) {
+                amount += record.amount;
+            }
+
+            movements.push(BalanceMovementDto { occurred_at: record.occurred_at, amount });
+        }
+
+        Ok(movements)
+    }
 }
\ No newline at end of file
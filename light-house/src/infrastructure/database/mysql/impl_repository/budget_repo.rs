@@ -0,0 +1,318 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{domain::{dto::budget_dto::{CreateBudgetDto, UpdateBudgetDto}, entities::{budget, budget_period, expense, transaction, transaction_type}, req_repository::budget_repository::{BudgetRepositoryBase, BudgetRepositoryUtility}}, infrastructure::database::mysql::impl_repository::payment_split_repo::{join_split_expenses, split_amount_sum}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+pub struct BudgetRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+#[async_trait::async_trait]
+impl BudgetRepositoryBase for BudgetRepositoryImpl {
+    async fn create(&self, user_id: Uuid, dto: CreateBudgetDto)
+        -> Result<budget::Model, RepositoryError>
+    {
+        let new_budget = budget::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            name: Set(dto.name),
+            expense_type_id: Set(dto.expense_type_id.as_bytes().to_vec()),
+            amount: Set(dto.amount),
+            period_type: Set(dto.period_type.as_str().to_string()),
+            start_date: Set(dto.start_date),
+            end_date: Set(dto.end_date),
+            rollover: Set(dto.rollover),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let inserted_budget = new_budget
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(inserted_budget)
+    }
+
+
+    async fn find_by_id(&self, user_id: Uuid, budget_id: Uuid)
+        -> Result<Option<budget::Model>, RepositoryError>
+    {
+        let budget = budget::Entity::find()
+            .filter(budget::Column::Id.eq(budget_id.as_bytes().to_vec()))
+            .filter(budget::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(budget)
+    }
+
+
+    async fn find_all_by_user_id(&self, user_id: Uuid)
+        -> Result<Vec<budget::Model>, RepositoryError>
+    {
+        let budgets = budget::Entity::find()
+            .filter(budget::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .order_by_asc(budget::Column::Name)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(budgets)
+    }
+
+
+    async fn update(&self, user_id: Uuid, budget_id: Uuid, dto: UpdateBudgetDto)
+        -> Result<budget::Model, RepositoryError>
+    {
+        let txn = self.db_pool.begin().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        // Step 1: Ensure the budget belongs to the user
+        let existing_budget = match budget::Entity::find()
+            .filter(budget::Column::Id.eq(budget_id.as_bytes().to_vec()))
+            .filter(budget::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .lock_exclusive()
+            .one(&txn)
+            .await
+        {
+            Ok(Some(budget)) => budget,
+            Ok(None) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::NotFound(format!(
+                    "Budget with ID {} not found for user {}",
+                    budget_id, user_id
+                )));
+            }
+            Err(err) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
+
+        // Step 2: Update the provided fields
+        let mut active_model: budget::ActiveModel = existing_budget.into();
+        if let Some(name) = dto.name {
+            active_model.name = Set(name);
+        }
+        if let Some(amount) = dto.amount {
+            active_model.amount = Set(amount);
+        }
+        if let Some(end_date) = dto.end_date {
+            active_model.end_date = Set(Some(end_date));
+        }
+        if let Some(rollover) = dto.rollover {
+            active_model.rollover = Set(rollover);
+        }
+        active_model.updated_at = Set(Some(Utc::now()));
+
+        let updated_budget = match active_model.update(&txn).await {
+            Ok(budget) => budget,
+            Err(err) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
+
+        // Step 3: A new amount applies to the periods that have not ended, past periods keep theirs
+        if let Some(amount) = dto.amount
+            && let Err(err) = budget_period::Entity::update_many()
+                .col_expr(budget_period::Column::Amount, Expr::value(amount))
+                .col_expr(budget_period::Column::UpdatedAt, Expr::value(Utc::now()))
+                .filter(budget_period::Column::BudgetId.eq(budget_id.as_bytes().to_vec()))
+                .filter(budget_period::Column::PeriodEnd.gte(Utc::now()))
+                .exec(&txn)
+                .await
+        {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(RepositoryError::DatabaseError(err.to_string()));
+        }
+
+        txn.commit().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(updated_budget)
+    }
+
+
+    async fn delete(&self, user_id: Uuid, budget_id: Uuid)
+        -> Result<(), RepositoryError>
+    {
+        // periods go with the budget through the cascading foreign key
+        let result = budget::Entity::delete_many()
+            .filter(budget::Column::Id.eq(budget_id.as_bytes().to_vec()))
+            .filter(budget::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound(format!(
+                "Budget with ID {} not found for user {}",
+                budget_id, user_id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+
+
+
+#[async_trait::async_trait]
+impl BudgetRepositoryUtility for BudgetRepositoryImpl {
+    async fn find_periods(&self, budget_id: Uuid)
+        -> Result<Vec<budget_period::Model>, RepositoryError>
+    {
+        let periods = budget_period::Entity::find()
+            .filter(budget_period::Column::BudgetId.eq(budget_id.as_bytes().to_vec()))
+            .order_by_asc(budget_period::Column::PeriodStart)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(periods)
+    }
+
+
+    async fn create_period(&self, budget_id: Uuid, period_start: DateTime<Utc>, period_end: DateTime<Utc>, amount: Decimal)
+        -> Result<budget_period::Model, RepositoryError>
+    {
+        let new_period = budget_period::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            budget_id: Set(budget_id.as_bytes().to_vec()),
+            period_start: Set(period_start),
+            period_end: Set(period_end),
+            amount: Set(amount),
+            rollover_amount: Set(Decimal::ZERO),
+            ..Default::default()
+        };
+
+        let inserted_period = new_period
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(|err| {
+                if let sea_orm::DbErr::Exec(exec_err) = &err
+                    && exec_err.to_string().contains("Duplicate")
+                {
+                    return RepositoryError::UniqueConstraintViolation(
+                        "The budget already has a period with the same start".to_string(),
+                    );
+                }
+                RepositoryError::DatabaseError(err.to_string())
+            })?;
+
+        Ok(inserted_period)
+    }
+
+
+    async fn update_period(&self, period_id: Uuid, period_end: DateTime<Utc>, rollover_amount: Decimal)
+        -> Result<budget_period::Model, RepositoryError>
+    {
+        let existing_period = budget_period::Entity::find_by_id(period_id.as_bytes().to_vec())
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Budget period with ID {} not found", period_id)))?;
+
+        let mut active_model: budget_period::ActiveModel = existing_period.into();
+        active_model.period_end = Set(period_end);
+        active_model.rollover_amount = Set(rollover_amount);
+        active_model.updated_at = Set(Some(Utc::now()));
+
+        let updated_period = active_model
+            .update(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(updated_period)
+    }
+
+
+    async fn delete_periods_starting_after(&self, budget_id: Uuid, after: DateTime<Utc>)
+        -> Result<(), RepositoryError>
+    {
+        budget_period::Entity::delete_many()
+            .filter(budget_period::Column::BudgetId.eq(budget_id.as_bytes().to_vec()))
+            .filter(budget_period::Column::PeriodStart.gt(after))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(())
+    }
+
+
+    async fn sum_spent(&self, user_id: Uuid, expense_type_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>)
+        -> Result<Decimal, RepositoryError>
+    {
+        let spent = join_split_expenses(transaction::Entity::find().select_only())
+            .column_as(split_amount_sum(), "total")
+            .join(JoinType::InnerJoin, transaction::Relation::TransactionType.def())
+            .filter(transaction_type::Column::Name.eq("payment"))
+            .filter(expense::Column::ExpenseTypeId.eq(expense_type_id.as_bytes().to_vec()))
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(transaction::Column::OccurredAt.between(from, to))
+            .into_tuple::<Option<Decimal>>()
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .flatten()
+            .unwrap_or(Decimal::ZERO);
+
+        Ok(spent)
+    }
+
+
+    async fn find_spending(&self, user_id: Uuid, expense_type_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>)
+        -> Result<Vec<transaction::Model>, RepositoryError>
+    {
+        // Step 1: part of every payment spent on the expense type
+        let portions: HashMap<Vec<u8>, Decimal> = join_split_expenses(transaction::Entity::find().select_only())
+            .column(transaction::Column::Id)
+            .column_as(split_amount_sum(), "total")
+            .join(JoinType::InnerJoin, transaction::Relation::TransactionType.def())
+            .filter(transaction_type::Column::Name.eq("payment"))
+            .filter(expense::Column::ExpenseTypeId.eq(expense_type_id.as_bytes().to_vec()))
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(transaction::Column::OccurredAt.between(from, to))
+            .group_by(transaction::Column::Id)
+            .into_tuple::<(Vec<u8>, Option<Decimal>)>()
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .into_iter()
+            .map(|(transaction_id, total)| (transaction_id, total.unwrap_or(Decimal::ZERO)))
+            .collect();
+        if portions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Step 2: the payments themselves, carrying that part as their amount
+        let payments = transaction::Entity::find()
+            .filter(transaction::Column::Id.is_in(portions.keys().cloned()))
+            .order_by_asc(transaction::Column::OccurredAt)
+            .order_by_asc(transaction::Column::Id)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .into_iter()
+            .map(|mut payment| {
+                if let Some(portion) = portions.get(&payment.id) {
+                    payment.amount = *portion;
+                }
+                payment
+            })
+            .collect();
+
+        Ok(payments)
+    }
+}
@@ -1,9 +1,9 @@
 use std::sync::Arc;
 
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use sea_orm::{sea_query::Query, ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
 use uuid::Uuid;
 
-use crate::{domain::{dto::expense_dto::{ReqCreateExpenseDto, ReqUpdateExpenseDto}, entities::{expense, expense_type, transaction}, req_repository::expense_repository::{ExpenseRepositoryBase, ExpenseRepositoryUtill}}, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::expense_dto::{ReqCreateExpenseDto, ReqUpdateExpenseDto}, entities::{expense, expense_type, payment_split, transaction}, req_repository::expense_repository::{ExpenseRepositoryBase, ExpenseRepositoryUtill}}, soc::soc_repository::RepositoryError};
 
 
 
@@ -253,9 +253,19 @@ impl ExpenseRepositoryUtill for ExpenseRepositoryImpl {
     async fn is_in_use_in_transaction(&self, user_id: Uuid, expense_id: Uuid) 
     -> Result<bool, RepositoryError>
     {
-        // Query the database to check if the expense is in use in the transaction table
+        // Query the database to check if the expense is in use in the transaction table or on a split line
         let is_in_use = transaction::Entity::find()
-            .filter(transaction::Column::ExpenseId.eq(expense_id.as_bytes().to_vec())) // Filter by expense ID
+            .filter(
+                Condition::any()
+                    .add(transaction::Column::ExpenseId.eq(expense_id.as_bytes().to_vec())) // Filter by expense ID
+                    .add(transaction::Column::Id.in_subquery(
+                        Query::select()
+                            .column(payment_split::Column::TransactionId)
+                            .from(payment_split::Entity)
+                            .and_where(payment_split::Column::ExpenseId.eq(expense_id.as_bytes().to_vec()))
+                            .to_owned()
+                    ))
+            )
             .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
             .count(self.db_pool.as_ref()) // Count matching records
             .await
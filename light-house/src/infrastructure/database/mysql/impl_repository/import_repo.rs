@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, SubsecRound, Utc};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{domain::{dto::{dto_enum::transaction_type::TransactionTypeVariant, import_dto::{ImportCandidateDto, ReqCreateImportProfileDto, ReqUpdateImportProfileDto}}, entities::{asset, import_profile, transaction, transaction_type}, req_repository::import_repository::{ImportProfileRepositoryBase, ImportRepositoryUtility}}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+pub struct ImportRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>
+}
+
+impl ImportRepositoryImpl {
+    pub fn new(db_pool: Arc<DatabaseConnection>) -> Self {
+        Self { db_pool }
+    }
+}
+
+
+fn import_profile_write_error(err: sea_orm::DbErr) -> RepositoryError {
+    if let sea_orm::DbErr::Exec(exec_err) = &err
+        && exec_err.to_string().contains("Duplicate")
+    {
+        return RepositoryError::UniqueConstraintViolation(
+            "Import profile with the same name already exists".to_string(),
+        );
+    }
+    RepositoryError::DatabaseError(err.to_string())
+}
+
+// an empty column name means the column is not used
+fn optional_column(column: Option<String>) -> Option<String> {
+    column
+        .map(|column| column.trim().to_string())
+        .filter(|column| !column.is_empty())
+}
+
+
+#[async_trait::async_trait]
+impl ImportProfileRepositoryBase for ImportRepositoryImpl {
+    async fn create(&self, user_id: Uuid, dto: ReqCreateImportProfileDto)
+        -> Result<import_profile::Model, RepositoryError>
+    {
+        let new_profile = import_profile::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            name: Set(dto.name.trim().to_string()),
+            delimiter: Set(dto.delimiter.unwrap_or_else(|| ",".to_string())),
+            has_header: Set(dto.has_header.unwrap_or(true)),
+            skip_rows: Set(dto.skip_rows.unwrap_or(0)),
+            date_column: Set(dto.date_column.trim().to_string()),
+            date_format: Set(dto.date_format),
+            amount_column: Set(optional_column(dto.amount_column)),
+            debit_column: Set(optional_column(dto.debit_column)),
+            credit_column: Set(optional_column(dto.credit_column)),
+            description_column: Set(dto.description_column.trim().to_string()),
+            decimal_separator: Set(dto.decimal_separator.unwrap_or_else(|| ".".to_string())),
+            encoding: Set(dto.encoding.unwrap_or_else(|| "utf-8".to_string())),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        new_profile
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(import_profile_write_error)
+    }
+
+
+    async fn update(&self, user_id: Uuid, profile_id: Uuid, dto: ReqUpdateImportProfileDto)
+        -> Result<import_profile::Model, RepositoryError>
+    {
+        // Step 1: the profile must belong to the user
+        let existing_profile = match self.find_profile_by_user_id_and_profile_id(user_id, profile_id).await? {
+            Some(profile) => profile,
+            None => {
+                return Err(RepositoryError::NotFound(format!(
+                    "Import profile with ID {} not found",
+                    profile_id
+                )));
+            }
+        };
+
+        // Step 2: apply the provided fields
+        let mut active_model: import_profile::ActiveModel = existing_profile.into();
+        if let Some(name) = dto.name {
+            active_model.name = Set(name.trim().to_string());
+        }
+        if let Some(delimiter) = dto.delimiter {
+            active_model.delimiter = Set(delimiter);
+        }
+        if let Some(has_header) = dto.has_header {
+            active_model.has_header = Set(has_header);
+        }
+        if let Some(skip_rows) = dto.skip_rows {
+            active_model.skip_rows = Set(skip_rows);
+        }
+        if let Some(date_column) = dto.date_column {
+            active_model.date_column = Set(date_column.trim().to_string());
+        }
+        if let Some(date_format) = dto.date_format {
+            active_model.date_format = Set(date_format);
+        }
+        if dto.amount_column.is_some() {
+            active_model.amount_column = Set(optional_column(dto.amount_column));
+        }
+        if dto.debit_column.is_some() {
+            active_model.debit_column = Set(optional_column(dto.debit_column));
+        }
+        if dto.credit_column.is_some() {
+            active_model.credit_column = Set(optional_column(dto.credit_column));
+        }
+        if let Some(description_column) = dto.description_column {
+            active_model.description_column = Set(description_column.trim().to_string());
+        }
+        if let Some(decimal_separator) = dto.decimal_separator {
+            active_model.decimal_separator = Set(decimal_separator);
+        }
+        if let Some(encoding) = dto.encoding {
+            active_model.encoding = Set(encoding);
+        }
+        active_model.updated_at = Set(Some(Utc::now().trunc_subsecs(0)));
+
+        active_model
+            .update(self.db_pool.as_ref())
+            .await
+            .map_err(import_profile_write_error)
+    }
+
+
+    async fn delete(&self, user_id: Uuid, profile_id: Uuid)
+        -> Result<(), RepositoryError>
+    {
+        let result = import_profile::Entity::delete_many()
+            .filter(import_profile::Column::Id.eq(profile_id.as_bytes().to_vec()))
+            .filter(import_profile::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound(format!(
+                "Import profile with ID {} not found",
+                profile_id
+            )));
+        }
+        Ok(())
+    }
+}
+
+
+#[async_trait::async_trait]
+impl ImportRepositoryUtility for ImportRepositoryImpl {
+    async fn find_all_profiles_by_user_id(&self, user_id: Uuid)
+        -> Result<Vec<import_profile::Model>, RepositoryError>
+    {
+        import_profile::Entity::find()
+            .filter(import_profile::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .order_by_asc(import_profile::Column::Name)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+
+
+    async fn find_profile_by_user_id_and_profile_id(&self, user_id: Uuid, profile_id: Uuid)
+        -> Result<Option<import_profile::Model>, RepositoryError>
+    {
+        import_profile::Entity::find()
+            .filter(import_profile::Column::Id.eq(profile_id.as_bytes().to_vec()))
+            .filter(import_profile::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+
+
+    async fn is_asset_owned(&self, user_id: Uuid, asset_id: Uuid)
+        -> Result<bool, RepositoryError>
+    {
+        let count = asset::Entity::find()
+            .filter(asset::Column::Id.eq(asset_id.as_bytes().to_vec()))
+            .filter(asset::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .count(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(count > 0)
+    }
+
+
+    async fn find_transaction_type_id(&self, transaction_type: TransactionTypeVariant)
+        -> Result<Uuid, RepositoryError>
+    {
+        let found = transaction_type::Entity::find()
+            .filter(transaction_type::Column::Name.eq(transaction_type.as_str()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Transaction type {} not found", transaction_type.as_str())))?;
+
+        Uuid::from_slice(&found.id).map_err(|err| RepositoryError::OperationFailed(format!("Invalid UUID: {}", err)))
+    }
+
+
+    async fn find_import_candidates(&self, user_id: Uuid, asset_id: Uuid, from: DateTime<Utc>, to: DateTime<Utc>, external_references: &[String])
+        -> Result<Vec<ImportCandidateDto>, RepositoryError>
+    {
+        // a line the bank booked again on another day is still found by its reference
+        let mut in_range = Condition::any().add(transaction::Column::OccurredAt.between(from, to));
+        if !external_references.is_empty() {
+            in_range = in_range.add(transaction::Column::ExternalReference.is_in(external_references.iter().cloned()));
+        }
+        let found = transaction::Entity::find()
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(transaction::Column::AssetId.eq(asset_id.as_bytes().to_vec()))
+            .filter(in_range)
+            .find_also_related(transaction_type::Entity)
+            .order_by_asc(transaction::Column::OccurredAt)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        let mut candidates = Vec::with_capacity(found.len());
+        for (found_transaction, found_type) in found {
+            // transfers out of the asset are not something a statement line is turned into
+            let transaction_type = match found_type.and_then(|found_type| found_type.name.parse::<TransactionTypeVariant>().ok()) {
+                Some(transaction_type @ (TransactionTypeVariant::Income | TransactionTypeVariant::Payment)) => transaction_type,
+                _ => continue,
+            };
+            candidates.push(ImportCandidateDto {
+                id: Uuid::from_slice(&found_transaction.id)
+                    .map_err(|err| RepositoryError::OperationFailed(format!("Invalid UUID: {}", err)))?,
+                transaction_type,
+                amount: found_transaction.amount,
+                occurred_at: found_transaction.occurred_at,
+                external_reference: found_transaction.external_reference,
+            });
+        }
+        Ok(candidates)
+    }
+}
@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use rust_decimal::Decimal;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use super::balance_repo::BalanceRepositoryImpl;
+use crate::{domain::{dto::{dto_enum::ledger_account::LedgerAccountVariant, ledger_dto::ReqPostJournalEntryDto}, entities::{current_sheet, journal_entry, journal_line, transaction}, req_repository::{balance_repository::BalanceRepositoryBase, ledger_repository::LedgerRepositoryBase}}, soc::soc_repository::RepositoryError};
+
+
+
+
+pub struct LedgerRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>
+}
+
+impl LedgerRepositoryImpl {
+    pub fn new(db_pool: Arc<DatabaseConnection>) -> Self {
+        Self { db_pool }
+    }
+
+    // An entry must have at least two lines, every line is one sided and non negative,
+    // and total debit must equal total credit
+    pub fn validate_entry(entry: &ReqPostJournalEntryDto) -> Result<(), RepositoryError> {
+        if entry.lines.len() < 2 {
+            return Err(RepositoryError::InvalidInput(
+                "A journal entry needs at least two lines".to_string(),
+            ));
+        }
+
+        let mut total_debit = Decimal::ZERO;
+        let mut total_credit = Decimal::ZERO;
+        for line in &entry.lines {
+            if line.debit < Decimal::ZERO || line.credit < Decimal::ZERO {
+                return Err(RepositoryError::InvalidInput(
+                    "Journal line amounts must not be negative".to_string(),
+                ));
+            }
+            if (line.debit == Decimal::ZERO) == (line.credit == Decimal::ZERO) {
+                return Err(RepositoryError::InvalidInput(format!(
+                    "Journal line for {} {} must have either a debit or a credit",
+                    line.account_type.as_str(),
+                    line.account_id
+                )));
+            }
+            total_debit += line.debit;
+            total_credit += line.credit;
+        }
+
+        if total_debit != total_credit {
+            return Err(RepositoryError::InvalidInput(format!(
+                "Unbalanced journal entry: debit {} does not equal credit {}",
+                total_debit, total_credit
+            )));
+        }
+
+        Ok(())
+    }
+
+    // Newest transaction that still moves the asset, either as source or as destination
+    pub async fn find_last_transaction_id<C: ConnectionTrait>(
+        conn: &C,
+        user_id: Uuid,
+        asset_id: Uuid,
+    ) -> Result<Option<Vec<u8>>, RepositoryError> {
+        let last_transaction = transaction::Entity::find()
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(
+                Condition::any()
+                    .add(transaction::Column::AssetId.eq(asset_id.as_bytes().to_vec()))
+                    .add(transaction::Column::DestinationAssetId.eq(asset_id.as_bytes().to_vec())),
+            )
+            .order_by_desc(transaction::Column::OccurredAt)
+            .order_by_desc(transaction::Column::Id)
+            .one(conn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(last_transaction.map(|record| record.id))
+    }
+}
+
+
+#[async_trait::async_trait]
+impl LedgerRepositoryBase for LedgerRepositoryImpl {
+
+    async fn post_entry(
+        &self,
+        txn: &DatabaseTransaction,
+        user_id: Uuid,
+        entry: ReqPostJournalEntryDto,
+    ) -> Result<journal_entry::Model, RepositoryError> {
+        // Step 1: Refuse anything that does not balance
+        Self::validate_entry(&entry)?;
+        log::debug!("Posting journal entry '{}' with {} lines", entry.memo, entry.lines.len());
+
+        // Step 2: Lock every touched current_sheet row up front, always in asset id order,
+        // so two postings over the same pair of assets cannot deadlock each other
+        let balance_repo = BalanceRepositoryImpl {
+            db_pool: Arc::clone(&self.db_pool),
+        };
+        let mut asset_ids: Vec<Uuid> = entry
+            .lines
+            .iter()
+            .filter(|line| line.account_type == LedgerAccountVariant::Asset)
+            .map(|line| line.account_id)
+            .collect();
+        asset_ids.sort();
+        asset_ids.dedup();
+        for asset_id in &asset_ids {
+            balance_repo.lock_current_sheet_by_asset_id(txn, user_id, *asset_id).await?;
+        }
+
+        // Step 3: Write the entry header
+        let journal_entry_id = Uuid::new_v4();
+        let inserted_entry = journal_entry::ActiveModel {
+            id: Set(journal_entry_id.as_bytes().to_vec()),
+            transaction_id: Set(entry.transaction_id.map(|id| id.as_bytes().to_vec())),
+            memo: Set(entry.memo),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        }
+        .insert(txn)
+        .await
+        .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        // Step 4: Write the lines and move asset balances with them
+        for line in entry.lines {
+            journal_line::ActiveModel {
+                id: Set(Uuid::new_v4().as_bytes().to_vec()),
+                journal_entry_id: Set(journal_entry_id.as_bytes().to_vec()),
+                account_type: Set(line.account_type.as_str().to_string()),
+                account_id: Set(line.account_id.as_bytes().to_vec()),
+                contact_id: Set(line.contact_id.map(|id| id.as_bytes().to_vec())),
+                debit: Set(line.debit),
+                credit: Set(line.credit),
+                ..Default::default()
+            }
+            .insert(txn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+            if line.account_type != LedgerAccountVariant::Asset {
+                continue;
+            }
+
+            // assets are debit normal: a debit raises the balance, a credit lowers it
+            let sheet = balance_repo
+                .lock_current_sheet_by_asset_id(txn, user_id, line.account_id)
+                .await?
+                .ok_or_else(|| {
+                    RepositoryError::NotFound(format!(
+                        "Current sheet for asset {} not found",
+                        line.account_id
+                    ))
+                })?;
+
+            let new_balance = sheet.balance + line.debit - line.credit;
+            log::debug!("Asset {} balance {} -> {}", line.account_id, sheet.balance, new_balance);
+
+            let last_transaction_id = Self::find_last_transaction_id(txn, user_id, line.account_id).await?;
+
+            let mut active_sheet: current_sheet::ActiveModel = sheet.into();
+            active_sheet.balance = Set(new_balance);
+            active_sheet.last_transaction_id = Set(last_transaction_id);
+            active_sheet.updated_at = Set(Some(chrono::Utc::now()));
+            active_sheet
+                .update(txn)
+                .await
+                .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        }
+
+        Ok(inserted_entry)
+    }
+
+
+    async fn get_entries_by_transaction_id(
+        &self,
+        user_id: Uuid,
+        transaction_id: Uuid,
+    ) -> Result<Vec<journal_entry::Model>, RepositoryError> {
+        let entries = journal_entry::Entity::find()
+            .filter(journal_entry::Column::TransactionId.eq(transaction_id.as_bytes().to_vec()))
+            .filter(journal_entry::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .order_by_asc(journal_entry::Column::CreatedAt)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(entries)
+    }
+
+
+    async fn get_lines_by_entry_id(
+        &self,
+        journal_entry_id: Uuid,
+    ) -> Result<Vec<journal_line::Model>, RepositoryError> {
+        let lines = journal_line::Entity::find()
+            .filter(journal_line::Column::JournalEntryId.eq(journal_entry_id.as_bytes().to_vec()))
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(lines)
+    }
+}
@@ -0,0 +1,287 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use sea_orm::{prelude::Json, sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{domain::{dto::{dto_enum::{mcp_operation::{McpOperationStatusVariant, McpOperationVariant}, transaction_type::TransactionTypeVariant}, mcp_operation_dto::{CreateMcpOperationDto, McpAssetBalanceDto, McpTransactionSnapshotDto}}, entities::{asset, contact, current_sheet, expense, mcp_pending_operation, transaction, transaction_type, user_contact}, req_repository::mcp_operation_repository::{McpOperationRepositoryBase, McpOperationRepositoryUtility}}, soc::soc_repository::RepositoryError};
+
+
+
+
+// length of the message column
+const MAX_MESSAGE_LENGTH: usize = 512;
+
+
+pub struct McpOperationRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+#[async_trait::async_trait]
+impl McpOperationRepositoryBase for McpOperationRepositoryImpl {
+    async fn create(&self, user_id: Uuid, dto: CreateMcpOperationDto)
+        -> Result<mcp_pending_operation::Model, RepositoryError>
+    {
+        let new_operation = mcp_pending_operation::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            operation: Set(dto.operation.as_str().to_string()),
+            target_id: Set(dto.target_id.map(|id| id.as_bytes().to_vec())),
+            target_updated_at: Set(dto.target_updated_at),
+            payload: Set(dto.payload),
+            balance_impact: Set(dto.balance_impact),
+            status: Set(McpOperationStatusVariant::Pending.as_str().to_string()),
+            result: Set(None),
+            message: Set(None),
+            expires_at: Set(dto.expires_at),
+            decided_at: Set(None),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let inserted_operation = new_operation
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(inserted_operation)
+    }
+
+
+    async fn find_by_id(&self, user_id: Uuid, operation_id: Uuid)
+        -> Result<Option<mcp_pending_operation::Model>, RepositoryError>
+    {
+        let operation = mcp_pending_operation::Entity::find()
+            .filter(mcp_pending_operation::Column::Id.eq(operation_id.as_bytes().to_vec()))
+            .filter(mcp_pending_operation::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(operation)
+    }
+
+
+    async fn find_all_by_user_id(&self, user_id: Uuid, status: Option<McpOperationStatusVariant>)
+        -> Result<Vec<mcp_pending_operation::Model>, RepositoryError>
+    {
+        let mut query = mcp_pending_operation::Entity::find()
+            .filter(mcp_pending_operation::Column::UserId.eq(user_id.as_bytes().to_vec()));
+        if let Some(status) = status {
+            query = query.filter(mcp_pending_operation::Column::Status.eq(status.as_str()));
+        }
+
+        let operations = query
+            .order_by_desc(mcp_pending_operation::Column::CreatedAt)
+            .order_by_desc(mcp_pending_operation::Column::Id)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(operations)
+    }
+
+
+    async fn update_status(
+        &self,
+        user_id: Uuid,
+        operation_id: Uuid,
+        from: McpOperationStatusVariant,
+        to: McpOperationStatusVariant,
+        result: Option<Json>,
+        message: Option<String>,
+    )
+        -> Result<bool, RepositoryError>
+    {
+        let now = Utc::now();
+
+        // Step 1: The status check is part of the update, so only one caller moves the operation on
+        let mut update = mcp_pending_operation::Entity::update_many()
+            .col_expr(mcp_pending_operation::Column::Status, Expr::value(to.as_str()))
+            .col_expr(mcp_pending_operation::Column::UpdatedAt, Expr::value(now))
+            .filter(mcp_pending_operation::Column::Id.eq(operation_id.as_bytes().to_vec()))
+            .filter(mcp_pending_operation::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(mcp_pending_operation::Column::Status.eq(from.as_str()));
+
+        // Step 2: The decision time is when the operation leaves pending
+        if from == McpOperationStatusVariant::Pending {
+            update = update.col_expr(mcp_pending_operation::Column::DecidedAt, Expr::value(now));
+        }
+        if let Some(result) = result {
+            update = update.col_expr(mcp_pending_operation::Column::Result, Expr::value(result));
+        }
+        if let Some(message) = message {
+            let message: String = message.chars().take(MAX_MESSAGE_LENGTH).collect();
+            update = update.col_expr(mcp_pending_operation::Column::Message, Expr::value(message));
+        }
+
+        let update_result = update
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected > 0)
+    }
+
+
+    async fn expire_pending(&self, user_id: Uuid, now: DateTime<Utc>)
+        -> Result<u64, RepositoryError>
+    {
+        let update_result = mcp_pending_operation::Entity::update_many()
+            .col_expr(mcp_pending_operation::Column::Status, Expr::value(McpOperationStatusVariant::Expired.as_str()))
+            .col_expr(mcp_pending_operation::Column::DecidedAt, Expr::value(now))
+            .col_expr(mcp_pending_operation::Column::UpdatedAt, Expr::value(now))
+            .filter(mcp_pending_operation::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(mcp_pending_operation::Column::Status.eq(McpOperationStatusVariant::Pending.as_str()))
+            .filter(mcp_pending_operation::Column::ExpiresAt.lte(now))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected)
+    }
+}
+
+
+#[async_trait::async_trait]
+impl McpOperationRepositoryUtility for McpOperationRepositoryImpl {
+    async fn find_transaction_type_id(&self, transaction_type: TransactionTypeVariant)
+        -> Result<Uuid, RepositoryError>
+    {
+        let found = transaction_type::Entity::find()
+            .filter(transaction_type::Column::Name.eq(transaction_type.as_str()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Transaction type {} not found", transaction_type.as_str())))?;
+
+        Uuid::from_slice(&found.id).map_err(|err| RepositoryError::OperationFailed(format!("Invalid UUID: {}", err)))
+    }
+
+
+    async fn find_transaction_snapshot(&self, user_id: Uuid, transaction_id: Uuid)
+        -> Result<Option<McpTransactionSnapshotDto>, RepositoryError>
+    {
+        // Step 1: The transaction must belong to the user
+        let found = transaction::Entity::find()
+            .filter(transaction::Column::Id.eq(transaction_id.as_bytes().to_vec()))
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .find_also_related(transaction_type::Entity)
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        let (found, found_type) = match found {
+            Some((found, Some(found_type))) => (found, found_type),
+            Some((_, None)) => return Err(RepositoryError::OperationFailed(format!("Transaction {} has no transaction type", transaction_id))),
+            None => return Ok(None),
+        };
+
+        // Step 2: Keep what the balance impact is computed from
+        let uuid_of = |id: &[u8]| Uuid::from_slice(id).map_err(|err| RepositoryError::OperationFailed(format!("Invalid UUID: {}", err)));
+        Ok(Some(McpTransactionSnapshotDto {
+            transaction_type: TransactionTypeVariant::from_str(&found_type.name).map_err(RepositoryError::OperationFailed)?,
+            amount: found.amount,
+            asset_id: uuid_of(&found.asset_id)?,
+            destination_asset_id: found.destination_asset_id.as_deref().map(uuid_of).transpose()?,
+        }))
+    }
+
+
+    async fn find_target_updated_at(&self, user_id: Uuid, operation: McpOperationVariant, target_id: Uuid)
+        -> Result<Option<DateTime<Utc>>, RepositoryError>
+    {
+        let user_bytes = user_id.as_bytes().to_vec();
+        let target_bytes = target_id.as_bytes().to_vec();
+
+        if operation.transaction_type().is_some() {
+            let found = transaction::Entity::find()
+                .filter(transaction::Column::Id.eq(target_bytes))
+                .filter(transaction::Column::UserId.eq(user_bytes))
+                .one(self.db_pool.as_ref())
+                .await
+                .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+                .ok_or_else(|| RepositoryError::NotFound(format!("Transaction {} not found", target_id)))?;
+            return Ok(found.updated_at);
+        }
+
+        match operation {
+            McpOperationVariant::UpdateContact | McpOperationVariant::DeleteContact => {
+                // contacts are shared through user_contact, the link is what makes it the user's
+                let linked = user_contact::Entity::find()
+                    .filter(user_contact::Column::UserId.eq(user_bytes))
+                    .filter(user_contact::Column::ContactId.eq(target_bytes.clone()))
+                    .one(self.db_pool.as_ref())
+                    .await
+                    .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+                if linked.is_none() {
+                    return Err(RepositoryError::NotFound(format!("Contact {} not found", target_id)));
+                }
+                let found = contact::Entity::find_by_id(target_bytes)
+                    .one(self.db_pool.as_ref())
+                    .await
+                    .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+                    .ok_or_else(|| RepositoryError::NotFound(format!("Contact {} not found", target_id)))?;
+                Ok(found.updated_at)
+            }
+            McpOperationVariant::UpdateExpense | McpOperationVariant::DeleteExpense => {
+                let found = expense::Entity::find()
+                    .filter(expense::Column::Id.eq(target_bytes))
+                    .filter(expense::Column::UserId.eq(user_bytes))
+                    .one(self.db_pool.as_ref())
+                    .await
+                    .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+                    .ok_or_else(|| RepositoryError::NotFound(format!("Expense {} not found", target_id)))?;
+                Ok(found.updated_at)
+            }
+            operation => Err(RepositoryError::InvalidInput(format!("{} has no target", operation.as_str()))),
+        }
+    }
+
+
+    async fn find_asset_balances(&self, user_id: Uuid, asset_ids: Vec<Uuid>)
+        -> Result<Vec<McpAssetBalanceDto>, RepositoryError>
+    {
+        if asset_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let asset_bytes: Vec<Vec<u8>> = asset_ids.iter().map(|id| id.as_bytes().to_vec()).collect();
+
+        // Step 1: Every asset must belong to the user
+        let assets: HashMap<Vec<u8>, asset::Model> = asset::Entity::find()
+            .filter(asset::Column::Id.is_in(asset_bytes.clone()))
+            .filter(asset::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .into_iter()
+            .map(|found| (found.id.clone(), found))
+            .collect();
+
+        // Step 2: Current balances, an asset without a sheet has not moved yet
+        let balances: HashMap<Vec<u8>, rust_decimal::Decimal> = current_sheet::Entity::find()
+            .filter(current_sheet::Column::AssetId.is_in(asset_bytes))
+            .filter(current_sheet::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .into_iter()
+            .map(|sheet| (sheet.asset_id, sheet.balance))
+            .collect();
+
+        // Step 3: Keep the order the assets were asked for
+        asset_ids
+            .into_iter()
+            .map(|asset_id| {
+                let key = asset_id.as_bytes().to_vec();
+                let found = assets
+                    .get(&key)
+                    .ok_or_else(|| RepositoryError::InvalidInput(format!("Asset {} not found", asset_id)))?;
+                Ok(McpAssetBalanceDto {
+                    asset_id,
+                    asset_name: found.name.clone(),
+                    balance: balances.get(&key).copied().unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+}
@@ -10,4 +10,20 @@ pub mod transaction;
 pub mod balance_repo;
 pub mod role_repo;
 pub mod gender_repo;
-pub mod transaction_type_repo;
\ No newline at end of file
+pub mod transaction_type_repo;
+pub mod ledger_repo;
+pub mod report_repo;
+pub mod budget_repo;
+pub mod recurring_transaction_repo;
+pub mod mcp_operation_repo;
+pub mod api_token_repo;
+pub mod session_repo;
+pub mod sign_in_attempt_repo;
+pub mod sign_in_limiter_repo;
+pub mod action_token_repo;
+pub mod two_factor_repo;
+pub mod user_data_repo;
+pub mod tag_repo;
+pub mod payment_split_repo;
+pub mod attachment_repo;
+pub mod import_repo;
\ No newline at end of file
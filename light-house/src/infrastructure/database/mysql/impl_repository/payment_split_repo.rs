@@ -0,0 +1,158 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::{SubsecRound, Utc};
+use sea_orm::{sea_query::{Expr, SimpleExpr}, ActiveValue::Set, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect, QueryTrait, RelationTrait, Select};
+use uuid::Uuid;
+
+use crate::{domain::{dto::transaction_dto::ReqPaymentSplitDto, entities::{expense, payment_split, transaction}, req_repository::payment_split_repository::PaymentSplitRepositoryUtility}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+// a split payment carries at least two and at most this many lines
+pub const MAX_SPLITS_PER_PAYMENT: usize = 50;
+
+
+pub struct PaymentSplitRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>
+}
+
+impl PaymentSplitRepositoryImpl {
+    pub fn new(db_pool: Arc<DatabaseConnection>) -> Self {
+        Self { db_pool }
+    }
+}
+
+
+// joins the split lines and the expense of every line onto a transaction query,
+// a payment without splits keeps one row on its own expense
+pub fn join_split_expenses(query: Select<transaction::Entity>) -> Select<transaction::Entity> {
+    let mut query = query.join(JoinType::LeftJoin, transaction::Relation::PaymentSplit.def());
+    QueryTrait::query(&mut query).join(
+        JoinType::LeftJoin,
+        expense::Entity,
+        Expr::cust("`expense`.`id` = COALESCE(`payment_split`.`expense_id`, `transaction`.`expense_id`)"),
+    );
+    query
+}
+
+// SUM over rows joined by join_split_expenses, the line amount for a split payment
+pub fn split_amount_sum() -> SimpleExpr {
+    Expr::cust("SUM(COALESCE(`payment_split`.`amount`, `transaction`.`amount`))")
+}
+
+
+#[async_trait::async_trait]
+impl PaymentSplitRepositoryUtility for PaymentSplitRepositoryImpl {
+    async fn set_payment_splits(
+        &self,
+        txn: &DatabaseTransaction,
+        transaction_id: Uuid,
+        splits: Vec<ReqPaymentSplitDto>
+    )
+        -> Result<Vec<payment_split::Model>, RepositoryError>
+    {
+        // Step 1: replace the current lines
+        self.delete_payment_splits(txn, transaction_id).await?;
+        if splits.is_empty() {
+            return Ok(Vec::new());
+        }
+        if splits.len() > MAX_SPLITS_PER_PAYMENT {
+            return Err(RepositoryError::InvalidInput(format!(
+                "A payment carries at most {} splits",
+                MAX_SPLITS_PER_PAYMENT
+            )));
+        }
+
+        // Step 2: build the lines in the given order
+        let created_at = Utc::now().trunc_subsecs(0);
+        let mut models = Vec::with_capacity(splits.len());
+        for (position, split) in splits.into_iter().enumerate() {
+            let expense_id = Uuid::parse_str(split.expense_id.trim())
+                .map_err(|_| RepositoryError::InvalidInput(format!("Invalid split expense id '{}'", split.expense_id)))?;
+            models.push(payment_split::Model {
+                id: Uuid::new_v4().as_bytes().to_vec(),
+                transaction_id: transaction_id.as_bytes().to_vec(),
+                expense_id: expense_id.as_bytes().to_vec(),
+                amount: split.amount.0,
+                note: split.note.filter(|note| !note.trim().is_empty()),
+                position: position as i32,
+                created_at: Some(created_at),
+            });
+        }
+
+        // Step 3: insert them
+        let lines = models.iter().map(|model| payment_split::ActiveModel {
+            id: Set(model.id.clone()),
+            transaction_id: Set(model.transaction_id.clone()),
+            expense_id: Set(model.expense_id.clone()),
+            amount: Set(model.amount),
+            note: Set(model.note.clone()),
+            position: Set(model.position),
+            created_at: Set(model.created_at),
+        });
+        payment_split::Entity::insert_many(lines)
+            .exec(txn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(models)
+    }
+
+    async fn find_payment_splits(
+        &self,
+        txn: &DatabaseTransaction,
+        transaction_id: Uuid
+    )
+        -> Result<Vec<payment_split::Model>, RepositoryError>
+    {
+        payment_split::Entity::find()
+            .filter(payment_split::Column::TransactionId.eq(transaction_id.as_bytes().to_vec()))
+            .order_by_asc(payment_split::Column::Position)
+            .all(txn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+
+    async fn delete_payment_splits(
+        &self,
+        txn: &DatabaseTransaction,
+        transaction_id: Uuid
+    )
+        -> Result<(), RepositoryError>
+    {
+        payment_split::Entity::delete_many()
+            .filter(payment_split::Column::TransactionId.eq(transaction_id.as_bytes().to_vec()))
+            .exec(txn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_splits_by_transaction_ids(
+        &self,
+        transaction_ids: Vec<Vec<u8>>
+    )
+        -> Result<HashMap<Vec<u8>, Vec<payment_split::Model>>, RepositoryError>
+    {
+        let mut splits_by_transaction: HashMap<Vec<u8>, Vec<payment_split::Model>> = HashMap::new();
+        if transaction_ids.is_empty() {
+            return Ok(splits_by_transaction);
+        }
+
+        let rows = payment_split::Entity::find()
+            .filter(payment_split::Column::TransactionId.is_in(transaction_ids))
+            .order_by_asc(payment_split::Column::Position)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        for split in rows {
+            splits_by_transaction.entry(split.transaction_id.clone()).or_default().push(split);
+        }
+
+        Ok(splits_by_transaction)
+    }
+}
@@ -0,0 +1,418 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, SqlErr, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{domain::{dto::{dto_enum::recurring_status::{RecurringOccurrenceStatusVariant, RecurringStatusVariant}, recurring_transaction_dto::{CreateRecurringTransactionDto, UpdateRecurringTransactionDto}}, entities::{asset, expense, recurring_occurrence, recurring_transaction, transaction, transaction_type, user_contact}, req_repository::recurring_transaction_repository::{RecurringTransactionRepositoryBase, RecurringTransactionRepositoryUtility}}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+pub struct RecurringTransactionRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+// Checks that the template's references belong to the user, the foreign keys only check they exist
+async fn ensure_template_owner<C: ConnectionTrait>(
+    db: &C,
+    user_id: Uuid,
+    asset_ids: &[Uuid],
+    contact_id: Option<Uuid>,
+    expense_id: Option<Uuid>,
+) -> Result<(), RepositoryError> {
+    for asset_id in asset_ids {
+        let found = asset::Entity::find()
+            .filter(asset::Column::Id.eq(asset_id.as_bytes().to_vec()))
+            .filter(asset::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(db)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        if found.is_none() {
+            return Err(RepositoryError::InvalidInput(format!("Asset {} not found", asset_id)));
+        }
+    }
+
+    if let Some(contact_id) = contact_id {
+        let found = user_contact::Entity::find()
+            .filter(user_contact::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(user_contact::Column::ContactId.eq(contact_id.as_bytes().to_vec()))
+            .one(db)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        if found.is_none() {
+            return Err(RepositoryError::InvalidInput(format!("Contact {} not found", contact_id)));
+        }
+    }
+
+    if let Some(expense_id) = expense_id {
+        let found = expense::Entity::find()
+            .filter(expense::Column::Id.eq(expense_id.as_bytes().to_vec()))
+            .filter(expense::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(db)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        if found.is_none() {
+            return Err(RepositoryError::InvalidInput(format!("Expense {} not found", expense_id)));
+        }
+    }
+
+    Ok(())
+}
+
+
+#[async_trait::async_trait]
+impl RecurringTransactionRepositoryBase for RecurringTransactionRepositoryImpl {
+    async fn create(&self, user_id: Uuid, dto: CreateRecurringTransactionDto, next_run_at: Option<DateTime<Utc>>)
+        -> Result<recurring_transaction::Model, RepositoryError>
+    {
+        // Step 1: Resolve the transaction type by name
+        let transaction_type = transaction_type::Entity::find()
+            .filter(transaction_type::Column::Name.eq(dto.transaction_type.as_str()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Transaction type {} not found", dto.transaction_type.as_str())))?;
+
+        // Step 2: The template must only point at the user's own records
+        let mut asset_ids = vec![dto.asset_id];
+        asset_ids.extend(dto.destination_asset_id);
+        ensure_template_owner(self.db_pool.as_ref(), user_id, &asset_ids, Some(dto.contact_id), dto.expense_id).await?;
+
+        // Step 3: Save the template and its schedule
+        let status = if next_run_at.is_some() { RecurringStatusVariant::Active } else { RecurringStatusVariant::Finished };
+        let new_recurring = recurring_transaction::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            name: Set(dto.name),
+            transaction_type_id: Set(transaction_type.id),
+            amount: Set(dto.amount),
+            asset_id: Set(dto.asset_id.as_bytes().to_vec()),
+            destination_asset_id: Set(dto.destination_asset_id.map(|id| id.as_bytes().to_vec())),
+            expense_id: Set(dto.expense_id.map(|id| id.as_bytes().to_vec())),
+            contact_id: Set(dto.contact_id.as_bytes().to_vec()),
+            note: Set(dto.note),
+            schedule_type: Set(dto.schedule_type.as_str().to_string()),
+            rrule: Set(dto.rule.to_string()),
+            timezone: Set(dto.timezone),
+            start_at: Set(dto.start_at),
+            end_at: Set(dto.end_at),
+            status: Set(status.as_str().to_string()),
+            next_run_at: Set(next_run_at),
+            last_run_at: Set(None),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let inserted_recurring = new_recurring
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(inserted_recurring)
+    }
+
+
+    async fn find_by_id(&self, user_id: Uuid, recurring_transaction_id: Uuid)
+        -> Result<Option<recurring_transaction::Model>, RepositoryError>
+    {
+        let recurring = recurring_transaction::Entity::find()
+            .filter(recurring_transaction::Column::Id.eq(recurring_transaction_id.as_bytes().to_vec()))
+            .filter(recurring_transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(recurring)
+    }
+
+
+    async fn find_all_by_user_id(&self, user_id: Uuid)
+        -> Result<Vec<recurring_transaction::Model>, RepositoryError>
+    {
+        let recurring_list = recurring_transaction::Entity::find()
+            .filter(recurring_transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .order_by_asc(recurring_transaction::Column::Name)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(recurring_list)
+    }
+
+
+    async fn update(&self, user_id: Uuid, recurring_transaction_id: Uuid, dto: UpdateRecurringTransactionDto)
+        -> Result<recurring_transaction::Model, RepositoryError>
+    {
+        let txn = self.db_pool.begin().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        // Step 1: Ensure the recurring transaction belongs to the user
+        let existing_recurring = match recurring_transaction::Entity::find()
+            .filter(recurring_transaction::Column::Id.eq(recurring_transaction_id.as_bytes().to_vec()))
+            .filter(recurring_transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .lock_exclusive()
+            .one(&txn)
+            .await
+        {
+            Ok(Some(recurring)) => recurring,
+            Ok(None) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::NotFound(format!(
+                    "Recurring transaction with ID {} not found for user {}",
+                    recurring_transaction_id, user_id
+                )));
+            }
+            Err(err) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
+
+        // Step 2: New references must belong to the user as well
+        let mut asset_ids: Vec<Uuid> = dto.asset_id.into_iter().collect();
+        if existing_recurring.destination_asset_id.is_some() {
+            asset_ids.extend(dto.destination_asset_id);
+        }
+        let expense_id = if existing_recurring.expense_id.is_some() { dto.expense_id } else { None };
+        if let Err(err) = ensure_template_owner(&txn, user_id, &asset_ids, dto.contact_id, expense_id).await {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
+
+        let asset_id = dto.asset_id.map(|id| id.as_bytes().to_vec()).unwrap_or(existing_recurring.asset_id.clone());
+        let destination_asset_id = match dto.destination_asset_id {
+            Some(id) if existing_recurring.destination_asset_id.is_some() => Some(id.as_bytes().to_vec()),
+            _ => existing_recurring.destination_asset_id.clone(),
+        };
+        if destination_asset_id.as_ref() == Some(&asset_id) {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(RepositoryError::InvalidInput("destination_asset_id must differ from asset_id".to_string()));
+        }
+
+        // Step 3: Update the provided fields, a destination or expense is only kept on the type that uses it
+        let mut active_model: recurring_transaction::ActiveModel = existing_recurring.into();
+        if let Some(name) = dto.name {
+            active_model.name = Set(name);
+        }
+        if let Some(amount) = dto.amount {
+            active_model.amount = Set(amount);
+        }
+        active_model.asset_id = Set(asset_id);
+        active_model.destination_asset_id = Set(destination_asset_id);
+        if let Some(expense_id) = expense_id {
+            active_model.expense_id = Set(Some(expense_id.as_bytes().to_vec()));
+        }
+        if let Some(contact_id) = dto.contact_id {
+            active_model.contact_id = Set(contact_id.as_bytes().to_vec());
+        }
+        if let Some(note) = dto.note {
+            active_model.note = Set(note);
+        }
+        if let Some(end_at) = dto.end_at {
+            active_model.end_at = Set(Some(end_at));
+        }
+        active_model.updated_at = Set(Some(Utc::now()));
+
+        let updated_recurring = match active_model.update(&txn).await {
+            Ok(recurring) => recurring,
+            Err(err) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
+
+        txn.commit().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(updated_recurring)
+    }
+
+
+    async fn delete(&self, user_id: Uuid, recurring_transaction_id: Uuid)
+        -> Result<(), RepositoryError>
+    {
+        // occurrences go with it through the cascading foreign key, posted transactions stay
+        let result = recurring_transaction::Entity::delete_many()
+            .filter(recurring_transaction::Column::Id.eq(recurring_transaction_id.as_bytes().to_vec()))
+            .filter(recurring_transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        if result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound(format!(
+                "Recurring transaction with ID {} not found for user {}",
+                recurring_transaction_id, user_id
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+
+
+
+#[async_trait::async_trait]
+impl RecurringTransactionRepositoryUtility for RecurringTransactionRepositoryImpl {
+    async fn find_due(&self, now: DateTime<Utc>, limit: u64)
+        -> Result<Vec<recurring_transaction::Model>, RepositoryError>
+    {
+        let due = recurring_transaction::Entity::find()
+            .filter(recurring_transaction::Column::Status.eq(RecurringStatusVariant::Active.as_str()))
+            .filter(recurring_transaction::Column::NextRunAt.lte(now))
+            .order_by_asc(recurring_transaction::Column::NextRunAt)
+            .limit(limit)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(due)
+    }
+
+
+    async fn update_schedule_state(
+        &self,
+        recurring_transaction_id: Uuid,
+        status: RecurringStatusVariant,
+        next_run_at: Option<DateTime<Utc>>,
+        last_run_at: Option<DateTime<Utc>>,
+    )
+        -> Result<recurring_transaction::Model, RepositoryError>
+    {
+        let existing_recurring = recurring_transaction::Entity::find_by_id(recurring_transaction_id.as_bytes().to_vec())
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Recurring transaction with ID {} not found", recurring_transaction_id)))?;
+
+        let mut active_model: recurring_transaction::ActiveModel = existing_recurring.into();
+        active_model.status = Set(status.as_str().to_string());
+        active_model.next_run_at = Set(next_run_at);
+        if last_run_at.is_some() {
+            active_model.last_run_at = Set(last_run_at);
+        }
+        active_model.updated_at = Set(Some(Utc::now()));
+
+        let updated_recurring = active_model
+            .update(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(updated_recurring)
+    }
+
+
+    async fn claim_occurrence(&self, recurring_transaction_id: Uuid, scheduled_for: DateTime<Utc>, status: RecurringOccurrenceStatusVariant)
+        -> Result<Option<recurring_occurrence::Model>, RepositoryError>
+    {
+        let new_occurrence = recurring_occurrence::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            recurring_transaction_id: Set(recurring_transaction_id.as_bytes().to_vec()),
+            scheduled_for: Set(scheduled_for),
+            status: Set(status.as_str().to_string()),
+            transaction_id: Set(None),
+            message: Set(None),
+            ..Default::default()
+        };
+
+        match new_occurrence.insert(self.db_pool.as_ref()).await {
+            Ok(occurrence) => Ok(Some(occurrence)),
+            Err(err) => match err.sql_err() {
+                Some(SqlErr::UniqueConstraintViolation(_)) => Ok(None),
+                _ => Err(RepositoryError::DatabaseError(err.to_string())),
+            },
+        }
+    }
+
+
+    async fn find_occurrence(&self, recurring_transaction_id: Uuid, scheduled_for: DateTime<Utc>)
+        -> Result<Option<recurring_occurrence::Model>, RepositoryError>
+    {
+        let occurrence = recurring_occurrence::Entity::find()
+            .filter(recurring_occurrence::Column::RecurringTransactionId.eq(recurring_transaction_id.as_bytes().to_vec()))
+            .filter(recurring_occurrence::Column::ScheduledFor.eq(scheduled_for))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(occurrence)
+    }
+
+
+    async fn find_occurrences(&self, recurring_transaction_id: Uuid, from: Option<DateTime<Utc>>)
+        -> Result<Vec<recurring_occurrence::Model>, RepositoryError>
+    {
+        let mut query = recurring_occurrence::Entity::find()
+            .filter(recurring_occurrence::Column::RecurringTransactionId.eq(recurring_transaction_id.as_bytes().to_vec()));
+        if let Some(from) = from {
+            query = query.filter(recurring_occurrence::Column::ScheduledFor.gte(from));
+        }
+
+        let occurrences = query
+            .order_by_desc(recurring_occurrence::Column::ScheduledFor)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(occurrences)
+    }
+
+
+    async fn finish_occurrence(&self, occurrence_id: Uuid, status: RecurringOccurrenceStatusVariant, transaction_id: Option<Uuid>, message: Option<String>)
+        -> Result<recurring_occurrence::Model, RepositoryError>
+    {
+        let existing_occurrence = recurring_occurrence::Entity::find_by_id(occurrence_id.as_bytes().to_vec())
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!("Occurrence with ID {} not found", occurrence_id)))?;
+
+        let mut active_model: recurring_occurrence::ActiveModel = existing_occurrence.into();
+        active_model.status = Set(status.as_str().to_string());
+        active_model.transaction_id = Set(transaction_id.map(|id| id.as_bytes().to_vec()));
+        // the column holds 512 characters
+        active_model.message = Set(message.map(|message| message.chars().take(512).collect()));
+        active_model.updated_at = Set(Some(Utc::now()));
+
+        let updated_occurrence = active_model
+            .update(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(updated_occurrence)
+    }
+
+
+    async fn take_over_occurrence(&self, occurrence_id: Uuid, stale_before: DateTime<Utc>, now: DateTime<Utc>)
+        -> Result<bool, RepositoryError>
+    {
+        // one statement, so of two runs finding the same stale claim only one moves it
+        let update_result = recurring_occurrence::Entity::update_many()
+            .col_expr(recurring_occurrence::Column::CreatedAt, Expr::value(now))
+            .col_expr(recurring_occurrence::Column::UpdatedAt, Expr::value(now))
+            .filter(recurring_occurrence::Column::Id.eq(occurrence_id.as_bytes().to_vec()))
+            .filter(recurring_occurrence::Column::Status.eq(RecurringOccurrenceStatusVariant::Pending.as_str()))
+            .filter(recurring_occurrence::Column::CreatedAt.lt(stale_before))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected == 1)
+    }
+
+
+    async fn find_posted_transaction(&self, recurring: recurring_transaction::Model, external_reference: &str)
+        -> Result<Option<transaction::Model>, RepositoryError>
+    {
+        let found = transaction::Entity::find()
+            .filter(transaction::Column::UserId.eq(recurring.user_id))
+            .filter(transaction::Column::AssetId.eq(recurring.asset_id))
+            .filter(transaction::Column::ExternalReference.eq(external_reference))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(found)
+    }
+}
@@ -0,0 +1,245 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use sea_orm::{sea_query::{Alias, Expr, Query, SimpleExpr}, ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait};
+use uuid::Uuid;
+
+use crate::{domain::{dto::{dto_enum::{expense_report_group::ExpenseReportGroupVariant, report_period::ReportPeriodVariant}, report_dto::{ExpenseGroupTotalDto, ReportGroupTotalDto, ReportPeriodTotalDto}}, entities::{asset, contact, expense, expense_type, tag, transaction, transaction_tag, transaction_type}, req_repository::report_repository::ReportRepositoryUtility}, infrastructure::database::mysql::impl_repository::payment_split_repo::{join_split_expenses, split_amount_sum}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+pub struct ReportRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+// YYYY-MM-DD of the first day of the period holding occurred_at, same boundaries as ReportPeriodVariant::start_of
+fn period_bucket(period: ReportPeriodVariant) -> SimpleExpr {
+    let sql = match period {
+        ReportPeriodVariant::Day => "DATE_FORMAT(`transaction`.`occurred_at`, '%Y-%m-%d')",
+        ReportPeriodVariant::Week => "DATE_FORMAT(DATE_SUB(`transaction`.`occurred_at`, INTERVAL WEEKDAY(`transaction`.`occurred_at`) DAY), '%Y-%m-%d')",
+        ReportPeriodVariant::Month => "DATE_FORMAT(`transaction`.`occurred_at`, '%Y-%m-01')",
+        ReportPeriodVariant::Year => "DATE_FORMAT(`transaction`.`occurred_at`, '%Y-01-01')",
+    };
+    Expr::cust(sql)
+}
+
+// a transaction joined to several split lines is still counted once per group
+fn distinct_count() -> SimpleExpr {
+    Expr::cust("COUNT(DISTINCT `transaction`.`id`)")
+}
+
+fn parse_bucket(bucket: &str) -> Result<NaiveDate, RepositoryError> {
+    NaiveDate::parse_from_str(bucket, "%Y-%m-%d")
+        .map_err(|err| RepositoryError::DatabaseError(format!("Invalid period bucket '{}': {}", bucket, err)))
+}
+
+
+impl ReportRepositoryImpl {
+    // SUM/COUNT of one transaction type grouped by period and the id/name picked by the caller's joins,
+    // `total` sums the amount column matching those joins
+    #[allow(clippy::too_many_arguments)]
+    async fn sum_grouped(
+        &self,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        period: ReportPeriodVariant,
+        transaction_type_name: &str,
+        query: sea_orm::Select<transaction::Entity>,
+        total: SimpleExpr,
+    ) -> Result<Vec<ReportGroupTotalDto>, RepositoryError>
+    {
+        let rows = query
+            .column_as(period_bucket(period), "bucket")
+            .column_as(total, "total")
+            .column_as(distinct_count(), "count")
+            .join(JoinType::InnerJoin, transaction::Relation::TransactionType.def())
+            .filter(transaction_type::Column::Name.eq(transaction_type_name))
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(transaction::Column::OccurredAt.between(from, to))
+            .group_by(Expr::col(Alias::new("bucket")))
+            .group_by(Expr::col(Alias::new("group_id")))
+            .group_by(Expr::col(Alias::new("group_name")))
+            .into_tuple::<(Option<Vec<u8>>, Option<String>, String, Option<Decimal>, i64)>()
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        rows.into_iter()
+            .map(|(group_id, group_name, bucket, total, count)| {
+                Ok(ReportGroupTotalDto {
+                    period_start: parse_bucket(&bucket)?,
+                    group_id,
+                    group_name,
+                    total: total.unwrap_or(Decimal::ZERO),
+                    count,
+                })
+            })
+            .collect()
+    }
+}
+
+
+#[async_trait::async_trait]
+impl ReportRepositoryUtility for ReportRepositoryImpl {
+    async fn sum_by_period_and_type(
+        &self,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        period: ReportPeriodVariant,
+    ) -> Result<Vec<ReportPeriodTotalDto>, RepositoryError>
+    {
+        let rows = transaction::Entity::find()
+            .select_only()
+            .column_as(period_bucket(period), "bucket")
+            .column(transaction_type::Column::Name)
+            .column_as(transaction::Column::Amount.sum(), "total")
+            .column_as(transaction::Column::Id.count(), "count")
+            .join(JoinType::InnerJoin, transaction::Relation::TransactionType.def())
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(transaction::Column::OccurredAt.between(from, to))
+            .group_by(Expr::col(Alias::new("bucket")))
+            .group_by(transaction_type::Column::Name)
+            .into_tuple::<(String, String, Option<Decimal>, i64)>()
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        rows.into_iter()
+            .map(|(bucket, transaction_type_name, total, count)| {
+                Ok(ReportPeriodTotalDto {
+                    period_start: parse_bucket(&bucket)?,
+                    transaction_type_name,
+                    total: total.unwrap_or(Decimal::ZERO),
+                    count,
+                })
+            })
+            .collect()
+    }
+
+    async fn sum_payments_by_expense_type(
+        &self,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        period: ReportPeriodVariant,
+    ) -> Result<Vec<ReportGroupTotalDto>, RepositoryError>
+    {
+        // a split payment spreads over the expense types of its lines
+        let query = join_split_expenses(transaction::Entity::find().select_only())
+            .column_as(expense_type::Column::Id, "group_id")
+            .column_as(expense_type::Column::Name, "group_name")
+            .join(JoinType::LeftJoin, expense::Relation::ExpenseType.def());
+
+        self.sum_grouped(user_id, from, to, period, "payment", query, split_amount_sum()).await
+    }
+
+    async fn sum_incomes_by_contact(
+        &self,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        period: ReportPeriodVariant,
+    ) -> Result<Vec<ReportGroupTotalDto>, RepositoryError>
+    {
+        let query = transaction::Entity::find()
+            .select_only()
+            .column_as(contact::Column::Id, "group_id")
+            .column_as(contact::Column::Name, "group_name")
+            .join(JoinType::LeftJoin, transaction::Relation::Contact.def());
+
+        self.sum_grouped(user_id, from, to, period, "income", query, transaction::Column::Amount.sum()).await
+    }
+
+    async fn sum_payments_by_group(
+        &self,
+        user_id: Uuid,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        group_by: ExpenseReportGroupVariant,
+        tag_id: Option<Uuid>,
+    ) -> Result<Vec<ExpenseGroupTotalDto>, RepositoryError>
+    {
+        // Step 1: pick the id and name of the grouping record, a split payment spreads over the expenses of its lines
+        let query = transaction::Entity::find().select_only();
+        let (query, total) = match group_by {
+            ExpenseReportGroupVariant::ExpenseType => (
+                join_split_expenses(query)
+                    .column_as(expense_type::Column::Id, "group_id")
+                    .column_as(expense_type::Column::Name, "group_name")
+                    .join(JoinType::LeftJoin, expense::Relation::ExpenseType.def()),
+                split_amount_sum(),
+            ),
+            ExpenseReportGroupVariant::Expense => (
+                join_split_expenses(query)
+                    .column_as(expense::Column::Id, "group_id")
+                    .column_as(expense::Column::Description, "group_name"),
+                split_amount_sum(),
+            ),
+            ExpenseReportGroupVariant::Contact => (
+                query
+                    .column_as(contact::Column::Id, "group_id")
+                    .column_as(contact::Column::Name, "group_name")
+                    .join(JoinType::LeftJoin, transaction::Relation::Contact.def()),
+                transaction::Column::Amount.sum(),
+            ),
+            ExpenseReportGroupVariant::Asset => (
+                query
+                    .column_as(asset::Column::Id, "group_id")
+                    .column_as(asset::Column::Name, "group_name")
+                    .join(JoinType::LeftJoin, transaction::Relation::Asset2.def()),
+                transaction::Column::Amount.sum(),
+            ),
+            ExpenseReportGroupVariant::Tag => (
+                query
+                    .column_as(tag::Column::Id, "group_id")
+                    .column_as(tag::Column::Name, "group_name")
+                    .join(JoinType::LeftJoin, transaction::Relation::TransactionTag.def())
+                    .join(JoinType::LeftJoin, transaction_tag::Relation::Tag.def()),
+                transaction::Column::Amount.sum(),
+            ),
+        };
+        let query = match tag_id {
+            Some(tag_id) => query.filter(
+                transaction::Column::Id.in_subquery(
+                    Query::select()
+                        .column(transaction_tag::Column::TransactionId)
+                        .from(transaction_tag::Entity)
+                        .and_where(transaction_tag::Column::TagId.eq(tag_id))
+                        .to_owned()
+                )
+            ),
+            None => query,
+        };
+
+        // Step 2: sum the payments of the range per group
+        let rows = query
+            .column_as(total, "total")
+            .column_as(distinct_count(), "count")
+            .join(JoinType::InnerJoin, transaction::Relation::TransactionType.def())
+            .filter(transaction_type::Column::Name.eq("payment"))
+            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(transaction::Column::OccurredAt.between(from, to))
+            .group_by(Expr::col(Alias::new("group_id")))
+            .group_by(Expr::col(Alias::new("group_name")))
+            .into_tuple::<(Option<Vec<u8>>, Option<String>, Option<Decimal>, i64)>()
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(group_id, group_name, total, count)| ExpenseGroupTotalDto {
+                group_id,
+                group_name,
+                total: total.unwrap_or(Decimal::ZERO),
+                count,
+            })
+            .collect())
+    }
+}
@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
-use sea_orm::{ ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect, RelationTrait};
+use chrono::{DateTime, Utc};
+use sea_orm::{ ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait};
 use uuid::Uuid;
-use crate::{domain::{entities::{user, user_role}, req_repository::user_role_repository::RoleManagementRepository}, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::{admin_dto::{AdminUserSearchDto, ResSystemStatsDto, ADMIN_ROLE}, dto_enum::mcp_operation::McpOperationStatusVariant}, entities::{api_token, asset, contact, mcp_pending_operation, transaction, user, user_role, user_session}, req_repository::user_role_repository::RoleManagementRepository}, soc::soc_repository::RepositoryError};
 
 
 
@@ -166,4 +167,174 @@ impl RoleManagementRepository for RoleManagementRepositoryImpl {
 
       Ok(role)
     }
+    async fn get_all_role(&self) -> Result<Vec<user_role::Model>, RepositoryError>
+    {
+        user_role::Entity::find()
+            .order_by_asc(user_role::Column::Name)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+
+
+    async fn find_all_user_with_role(
+        &self,
+        search: AdminUserSearchDto
+    )
+        -> Result<(Vec<(user::Model, Option<user_role::Model>)>, u64), RepositoryError>
+    {
+        // Step 1: Apply the filters
+        let mut query = user::Entity::find().find_also_related(user_role::Entity);
+        if let Some(text) = &search.search {
+            query = query.filter(
+                Condition::any()
+                    .add(user::Column::Username.contains(text))
+                    .add(user::Column::Email.contains(text))
+            );
+        }
+        if let Some(role) = &search.role {
+            query = query.filter(user_role::Column::Name.eq(role.as_str()));
+        }
+        match search.disabled {
+            Some(true) => query = query.filter(user::Column::DisabledAt.is_not_null()),
+            Some(false) => query = query.filter(user::Column::DisabledAt.is_null()),
+            None => {}
+        }
+
+        // Step 2: Count every matching user, regardless of the page
+        let total = query
+            .clone()
+            .count(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        // Step 3: Fetch the page, oldest account first
+        let users = query
+            .order_by_asc(user::Column::CreatedAt)
+            .order_by_asc(user::Column::Id)
+            .offset((search.page - 1) * search.per_page)
+            .limit(search.per_page)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok((users, total))
+    }
+
+
+    async fn set_user_disabled(
+        &self,
+        admin_id: Uuid,
+        target_user_id: Uuid,
+        disabled_at: Option<DateTime<Utc>>
+    )
+        -> Result<user::Model, RepositoryError>
+    {
+        // Step 1: Ensure the admin has the "admin" role
+        let is_admin = self.has_role(admin_id, ADMIN_ROLE).await?;
+        if !is_admin {
+            return Err(RepositoryError::PermissionDenied(
+                "Only admins can disable accounts".to_string(),
+            ));
+        }
+
+        // Step 2: Set or clear disabled_at of the target user
+        let update_result = user::Entity::update_many()
+            .col_expr(user::Column::DisabledAt, sea_orm::sea_query::Expr::value(disabled_at))
+            .col_expr(user::Column::UpdatedAt, sea_orm::sea_query::Expr::value(Utc::now()))
+            .filter(user::Column::Id.eq(target_user_id.as_bytes().to_vec()))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        if update_result.rows_affected == 0 {
+            return Err(RepositoryError::NotFound(format!(
+                "User with ID '{}' not found",
+                target_user_id
+            )));
+        }
+
+        // Step 3: Return the user as stored now
+        user::Entity::find_by_id(target_user_id.as_bytes().to_vec())
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!(
+                "User with ID '{}' not found",
+                target_user_id
+            )))
+    }
+
+
+    async fn get_system_stats(&self, now: DateTime<Utc>) -> Result<ResSystemStatsDto, RepositoryError>
+    {
+        let db = self.db_pool.as_ref();
+        let to_error = |err: sea_orm::DbErr| RepositoryError::DatabaseError(err.to_string());
+
+        // Step 1: Accounts
+        let user_count = user::Entity::find().count(db).await.map_err(to_error)?;
+        let disabled_user_count = user::Entity::find()
+            .filter(user::Column::DisabledAt.is_not_null())
+            .count(db)
+            .await
+            .map_err(to_error)?;
+        let admin_count = user::Entity::find()
+            .inner_join(user_role::Entity)
+            .filter(user_role::Column::Name.eq(ADMIN_ROLE))
+            .count(db)
+            .await
+            .map_err(to_error)?;
+
+        // Step 2: Sessions and API tokens that still work
+        let live_session = Condition::all()
+            .add(user_session::Column::RevokedAt.is_null())
+            .add(user_session::Column::ExpiresAt.gt(now));
+        let active_session_count = user_session::Entity::find()
+            .filter(live_session.clone())
+            .count(db)
+            .await
+            .map_err(to_error)?;
+        let signed_in_user_count = user_session::Entity::find()
+            .select_only()
+            .column(user_session::Column::UserId)
+            .distinct()
+            .filter(live_session)
+            .count(db)
+            .await
+            .map_err(to_error)?;
+        let active_api_token_count = api_token::Entity::find()
+            .filter(api_token::Column::RevokedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(api_token::Column::ExpiresAt.is_null())
+                    .add(api_token::Column::ExpiresAt.gt(now))
+            )
+            .count(db)
+            .await
+            .map_err(to_error)?;
+
+        // Step 3: Records across every user
+        let asset_count = asset::Entity::find().count(db).await.map_err(to_error)?;
+        let contact_count = contact::Entity::find().count(db).await.map_err(to_error)?;
+        let transaction_count = transaction::Entity::find().count(db).await.map_err(to_error)?;
+        let pending_mcp_operation_count = mcp_pending_operation::Entity::find()
+            .filter(mcp_pending_operation::Column::Status.eq(McpOperationStatusVariant::Pending.as_str()))
+            .filter(mcp_pending_operation::Column::ExpiresAt.gt(now))
+            .count(db)
+            .await
+            .map_err(to_error)?;
+
+        Ok(ResSystemStatsDto {
+            user_count,
+            disabled_user_count,
+            admin_count,
+            signed_in_user_count,
+            active_session_count,
+            active_api_token_count,
+            asset_count,
+            contact_count,
+            transaction_count,
+            pending_mcp_operation_count,
+        })
+    }
 }
\ No newline at end of file
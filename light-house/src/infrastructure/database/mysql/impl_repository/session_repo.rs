@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use crate::{domain::{dto::session_dto::{CreateSessionDto, RotateSessionDto}, entities::user_session, req_repository::session_repository::{SessionRepositoryBase, SessionRepositoryUtility}}, soc::soc_repository::RepositoryError};
+
+
+
+
+// lengths of the user_agent and ip_address columns, the sign in audit uses the same
+pub(crate) const MAX_USER_AGENT_LENGTH: usize = 255;
+pub(crate) const MAX_IP_LENGTH: usize = 45;
+
+
+pub(crate) fn truncated(value: Option<String>, max_length: usize) -> Option<String> {
+    value.map(|value| value.chars().take(max_length).collect())
+}
+
+
+pub struct SessionRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+#[async_trait::async_trait]
+impl SessionRepositoryBase for SessionRepositoryImpl {
+    async fn create_session(&self, user_id: Uuid, dto: CreateSessionDto)
+        -> Result<user_session::Model, RepositoryError>
+    {
+        let new_session = user_session::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            refresh_token_hash: Set(dto.refresh_token_hash),
+            previous_token_hash: Set(None),
+            user_agent: Set(truncated(dto.user_agent, MAX_USER_AGENT_LENGTH)),
+            ip_address: Set(truncated(dto.ip_address, MAX_IP_LENGTH)),
+            expires_at: Set(dto.expires_at),
+            last_used_at: Set(None),
+            revoked_at: Set(None),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        let inserted_session = new_session
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(inserted_session)
+    }
+
+
+    async fn find_session_by_id(&self, user_id: Uuid, session_id: Uuid)
+        -> Result<Option<user_session::Model>, RepositoryError>
+    {
+        let session = user_session::Entity::find()
+            .filter(user_session::Column::Id.eq(session_id.as_bytes().to_vec()))
+            .filter(user_session::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(session)
+    }
+
+
+    async fn find_all_active_session_by_user_id(&self, user_id: Uuid, now: DateTime<Utc>)
+        -> Result<Vec<user_session::Model>, RepositoryError>
+    {
+        let sessions = user_session::Entity::find()
+            .filter(user_session::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(user_session::Column::RevokedAt.is_null())
+            .filter(user_session::Column::ExpiresAt.gt(now))
+            .order_by_desc(user_session::Column::UpdatedAt)
+            .order_by_desc(user_session::Column::Id)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(sessions)
+    }
+
+
+    async fn revoke_session(&self, user_id: Uuid, session_id: Uuid, now: DateTime<Utc>)
+        -> Result<bool, RepositoryError>
+    {
+        let update_result = user_session::Entity::update_many()
+            .col_expr(user_session::Column::RevokedAt, Expr::value(now))
+            .col_expr(user_session::Column::UpdatedAt, Expr::value(now))
+            .filter(user_session::Column::Id.eq(session_id.as_bytes().to_vec()))
+            .filter(user_session::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(user_session::Column::RevokedAt.is_null())
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected > 0)
+    }
+
+
+    async fn revoke_all_session_by_user_id(&self, user_id: Uuid, keep: Option<Uuid>, now: DateTime<Utc>)
+        -> Result<u64, RepositoryError>
+    {
+        let mut update = user_session::Entity::update_many()
+            .col_expr(user_session::Column::RevokedAt, Expr::value(now))
+            .col_expr(user_session::Column::UpdatedAt, Expr::value(now))
+            .filter(user_session::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(user_session::Column::RevokedAt.is_null())
+            .filter(user_session::Column::ExpiresAt.gt(now));
+        if let Some(keep) = keep {
+            update = update.filter(user_session::Column::Id.ne(keep.as_bytes().to_vec()));
+        }
+
+        let update_result = update
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected)
+    }
+}
+
+
+#[async_trait::async_trait]
+impl SessionRepositoryUtility for SessionRepositoryImpl {
+    async fn find_by_refresh_token_hash(&self, token_hash: &str)
+        -> Result<Option<user_session::Model>, RepositoryError>
+    {
+        let session = user_session::Entity::find()
+            .filter(user_session::Column::RefreshTokenHash.eq(token_hash))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(session)
+    }
+
+
+    async fn find_by_previous_token_hash(&self, token_hash: &str)
+        -> Result<Option<user_session::Model>, RepositoryError>
+    {
+        let session = user_session::Entity::find()
+            .filter(user_session::Column::PreviousTokenHash.eq(token_hash))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(session)
+    }
+
+
+    async fn rotate(&self, session_id: Uuid, dto: RotateSessionDto, now: DateTime<Utc>)
+        -> Result<bool, RepositoryError>
+    {
+        // Step 1: The current hash is part of the filter, so of two refreshes with one token only the first wins
+        let mut update = user_session::Entity::update_many()
+            .col_expr(user_session::Column::RefreshTokenHash, Expr::value(dto.to_token_hash))
+            .col_expr(user_session::Column::PreviousTokenHash, Expr::value(dto.from_token_hash.clone()))
+            .col_expr(user_session::Column::ExpiresAt, Expr::value(dto.expires_at))
+            .col_expr(user_session::Column::LastUsedAt, Expr::value(now))
+            .col_expr(user_session::Column::UpdatedAt, Expr::value(now))
+            .filter(user_session::Column::Id.eq(session_id.as_bytes().to_vec()))
+            .filter(user_session::Column::RefreshTokenHash.eq(dto.from_token_hash))
+            .filter(user_session::Column::RevokedAt.is_null());
+
+        // Step 2: Keep the last known client when the refresh does not tell
+        if let Some(user_agent) = truncated(dto.user_agent, MAX_USER_AGENT_LENGTH) {
+            update = update.col_expr(user_session::Column::UserAgent, Expr::value(user_agent));
+        }
+        if let Some(ip_address) = truncated(dto.ip_address, MAX_IP_LENGTH) {
+            update = update.col_expr(user_session::Column::IpAddress, Expr::value(ip_address));
+        }
+
+        let update_result = update
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected > 0)
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::Arc;
+
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, DatabaseConnection};
+use uuid::Uuid;
+
+use crate::{domain::{dto::sign_in_attempt_dto::CreateSignInAttemptDto, entities::sign_in_attempt, req_repository::sign_in_attempt_repository::SignInAttemptRepository}, infrastructure::database::mysql::impl_repository::session_repo::{truncated, MAX_IP_LENGTH, MAX_USER_AGENT_LENGTH}, soc::soc_repository::RepositoryError};
+
+
+
+
+// length of the email column
+const MAX_EMAIL_LENGTH: usize = 255;
+
+
+pub struct SignInAttemptRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+#[async_trait::async_trait]
+impl SignInAttemptRepository for SignInAttemptRepositoryImpl {
+    async fn record_sign_in_attempt(&self, dto: CreateSignInAttemptDto)
+        -> Result<(), RepositoryError>
+    {
+        let new_attempt = sign_in_attempt::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            email: Set(dto.email.chars().take(MAX_EMAIL_LENGTH).collect()),
+            ip_address: Set(truncated(dto.ip_address, MAX_IP_LENGTH)),
+            user_agent: Set(truncated(dto.user_agent, MAX_USER_AGENT_LENGTH)),
+            succeeded: Set(dto.failure.is_none()),
+            failure_reason: Set(dto.failure.map(|failure| failure.as_str().to_string())),
+            user_id: Set(dto.user_id.map(|user_id| user_id.as_bytes().to_vec())),
+            ..Default::default()
+        };
+
+        new_attempt
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(())
+    }
+}
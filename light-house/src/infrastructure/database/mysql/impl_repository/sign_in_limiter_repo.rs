@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::OnConflict, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+
+use crate::{domain::{dto::sign_in_attempt_dto::SignInThrottleDto, entities::sign_in_throttle, req_repository::sign_in_limiter_repository::SignInLimiterRepository}, soc::soc_repository::RepositoryError};
+
+
+
+
+// counters shared by every instance of the server, SIGN_IN_LIMITER=database
+pub struct SignInLimiterRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+#[async_trait::async_trait]
+impl SignInLimiterRepository for SignInLimiterRepositoryImpl {
+    async fn get_throttle(&self, key: &str)
+        -> Result<Option<SignInThrottleDto>, RepositoryError>
+    {
+        let throttle = sign_in_throttle::Entity::find_by_id(key.to_string())
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(throttle.map(|throttle| SignInThrottleDto {
+            failure_count: u32::try_from(throttle.failure_count).unwrap_or_default(),
+            last_failure_at: throttle.last_failure_at,
+            blocked_until: throttle.blocked_until,
+        }))
+    }
+
+
+    async fn save_throttle(&self, key: &str, throttle: SignInThrottleDto)
+        -> Result<(), RepositoryError>
+    {
+        let model = sign_in_throttle::ActiveModel {
+            throttle_key: Set(key.to_string()),
+            failure_count: Set(i32::try_from(throttle.failure_count).unwrap_or(i32::MAX)),
+            last_failure_at: Set(throttle.last_failure_at),
+            blocked_until: Set(throttle.blocked_until),
+            updated_at: Set(Some(Utc::now())),
+        };
+
+        // one row per key, a later failure overwrites it
+        sign_in_throttle::Entity::insert(model)
+            .on_conflict(
+                OnConflict::column(sign_in_throttle::Column::ThrottleKey)
+                    .update_columns([
+                        sign_in_throttle::Column::FailureCount,
+                        sign_in_throttle::Column::LastFailureAt,
+                        sign_in_throttle::Column::BlockedUntil,
+                        sign_in_throttle::Column::UpdatedAt,
+                    ])
+                    .to_owned(),
+            )
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(())
+    }
+
+
+    async fn clear_throttle(&self, key: &str)
+        -> Result<(), RepositoryError>
+    {
+        sign_in_throttle::Entity::delete_by_id(key.to_string())
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(())
+    }
+
+
+    async fn purge_throttle_before(&self, before: DateTime<Utc>)
+        -> Result<u64, RepositoryError>
+    {
+        let result = sign_in_throttle::Entity::delete_many()
+            .filter(sign_in_throttle::Column::LastFailureAt.lt(before))
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(result.rows_affected)
+    }
+}
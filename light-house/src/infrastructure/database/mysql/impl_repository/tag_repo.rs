@@ -0,0 +1,287 @@
+use std::{collections::{HashMap, HashSet}, sync::Arc};
+
+use chrono::{SubsecRound, Utc};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, DatabaseTransaction, EntityTrait, JoinType, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, RelationTrait, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{domain::{dto::tag_dto::{ReqCreateTagDto, ReqUpdateTagDto}, entities::{tag, transaction_tag}, req_repository::tag_repository::{TagRepositoryBase, TagRepositoryUtility, TransactionTagRepositoryUtility}}, soc::soc_repository::RepositoryError};
+
+
+
+
+
+// a transaction carries at most this many tags
+pub const MAX_TAGS_PER_TRANSACTION: usize = 20;
+
+
+pub struct TagRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>
+}
+
+impl TagRepositoryImpl {
+    pub fn new(db_pool: Arc<DatabaseConnection>) -> Self {
+        Self { db_pool }
+    }
+}
+
+
+fn tag_write_error(err: sea_orm::DbErr) -> RepositoryError {
+    if let sea_orm::DbErr::Exec(exec_err) = &err
+        && exec_err.to_string().contains("Duplicate")
+    {
+        return RepositoryError::UniqueConstraintViolation(
+            "Tag with the same name already exists".to_string(),
+        );
+    }
+    RepositoryError::DatabaseError(err.to_string())
+}
+
+
+#[async_trait::async_trait]
+impl TagRepositoryBase for TagRepositoryImpl {
+    async fn create(&self, user_id: Uuid, dto: ReqCreateTagDto) 
+        -> Result<tag::Model, RepositoryError>
+    {
+        let new_tag = tag::ActiveModel {
+            id: Set(Uuid::new_v4().as_bytes().to_vec()),
+            name: Set(dto.name.trim().to_string()),
+            color: Set(dto.color.filter(|color| !color.is_empty())),
+            user_id: Set(user_id.as_bytes().to_vec()),
+            ..Default::default()
+        };
+
+        new_tag
+            .insert(self.db_pool.as_ref())
+            .await
+            .map_err(tag_write_error)
+    }
+
+
+    async fn update(&self, dto: ReqUpdateTagDto, user_id: Uuid, tag_id: Uuid) 
+        -> Result<tag::Model, RepositoryError>
+    {
+        // Step 1: the tag must belong to the user
+        let existing_tag = match self.find_by_user_id_and_tag_id(tag_id, user_id).await? {
+            Some(tag) => tag,
+            None => {
+                return Err(RepositoryError::NotFound(format!(
+                    "Tag with ID {} not found for user {}",
+                    tag_id, user_id
+                )));
+            }
+        };
+
+        // Step 2: apply the provided fields, an empty color clears it
+        let mut active_model: tag::ActiveModel = existing_tag.into();
+        if let Some(name) = dto.name {
+            active_model.name = Set(name.trim().to_string());
+        }
+        if let Some(color) = dto.color {
+            active_model.color = Set(Some(color).filter(|color| !color.is_empty()));
+        }
+        active_model.updated_at = Set(Some(Utc::now().trunc_subsecs(0)));
+
+        active_model
+            .update(self.db_pool.as_ref())
+            .await
+            .map_err(tag_write_error)
+    }
+
+
+    async fn delete(&self, user_id: Uuid, tag_id: Uuid) 
+        -> Result<(), RepositoryError>
+    {
+        if self.find_by_user_id_and_tag_id(tag_id, user_id).await?.is_none() {
+            return Err(RepositoryError::NotFound(format!(
+                "Tag with ID {} not found for user {}",
+                tag_id, user_id
+            )));
+        }
+
+        let txn = self.db_pool.begin().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
+        })?;
+
+        // Step 1: detach the tag from every transaction, the transactions stay as they are
+        if let Err(err) = transaction_tag::Entity::delete_many()
+            .filter(transaction_tag::Column::TagId.eq(tag_id.as_bytes().to_vec()))
+            .exec(&txn)
+            .await
+        {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(RepositoryError::DatabaseError(err.to_string()));
+        }
+
+        // Step 2: remove the tag itself
+        if let Err(err) = tag::Entity::delete_many()
+            .filter(tag::Column::Id.eq(tag_id.as_bytes().to_vec()))
+            .filter(tag::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .exec(&txn)
+            .await
+        {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(RepositoryError::DatabaseError(err.to_string()));
+        }
+
+        txn.commit().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to commit transaction: {}", err))
+        })?;
+
+        Ok(())
+    }
+}
+
+
+
+
+#[async_trait::async_trait]
+impl TagRepositoryUtility for TagRepositoryImpl {
+    async fn find_all_by_user_id(&self, user_id: Uuid) 
+        -> Result<Vec<tag::Model>, RepositoryError>
+    {
+        tag::Entity::find()
+            .filter(tag::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .order_by_asc(tag::Column::Name)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+
+    async fn find_by_user_id_and_tag_id(&self, tag_id: Uuid, user_id: Uuid) 
+        -> Result<Option<tag::Model>, RepositoryError>
+    {
+        tag::Entity::find()
+            .filter(tag::Column::Id.eq(tag_id.as_bytes().to_vec()))
+            .filter(tag::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+
+    async fn count_transactions_by_tag(&self, user_id: Uuid) 
+        -> Result<HashMap<Vec<u8>, i64>, RepositoryError>
+    {
+        let rows = transaction_tag::Entity::find()
+            .select_only()
+            .column(transaction_tag::Column::TagId)
+            .column_as(transaction_tag::Column::TransactionId.count(), "count")
+            .join(JoinType::InnerJoin, transaction_tag::Relation::Tag.def())
+            .filter(tag::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .group_by(transaction_tag::Column::TagId)
+            .into_tuple::<(Vec<u8>, i64)>()
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(rows.into_iter().collect())
+    }
+}
+
+
+
+
+#[async_trait::async_trait]
+impl TransactionTagRepositoryUtility for TagRepositoryImpl {
+    async fn set_transaction_tags(
+        &self,
+        txn: &DatabaseTransaction,
+        user_id: Uuid,
+        transaction_id: Uuid,
+        tag_ids: Vec<String>
+    )
+        -> Result<(), RepositoryError>
+    {
+        // Step 1: parse the ids, the same tag given twice is only linked once
+        let mut seen = HashSet::new();
+        let mut tag_ids_binary = Vec::new();
+        for tag_id in tag_ids {
+            let tag_id = Uuid::parse_str(tag_id.trim())
+                .map_err(|_| RepositoryError::InvalidInput(format!("Invalid tag id '{}'", tag_id)))?;
+            if seen.insert(tag_id) {
+                tag_ids_binary.push(tag_id.as_bytes().to_vec());
+            }
+        }
+        if tag_ids_binary.len() > MAX_TAGS_PER_TRANSACTION {
+            return Err(RepositoryError::InvalidInput(format!(
+                "A transaction carries at most {} tags",
+                MAX_TAGS_PER_TRANSACTION
+            )));
+        }
+
+        // Step 2: every tag must belong to the user
+        if !tag_ids_binary.is_empty() {
+            let owned = tag::Entity::find()
+                .filter(tag::Column::UserId.eq(user_id.as_bytes().to_vec()))
+                .filter(tag::Column::Id.is_in(tag_ids_binary.clone()))
+                .count(txn)
+                .await
+                .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+            if owned as usize != tag_ids_binary.len() {
+                return Err(RepositoryError::InvalidInput("Unknown tag id".to_string()));
+            }
+        }
+
+        // Step 3: replace the current links
+        self.delete_transaction_tags(txn, transaction_id).await?;
+        if tag_ids_binary.is_empty() {
+            return Ok(());
+        }
+        let links = tag_ids_binary.into_iter().map(|tag_id| transaction_tag::ActiveModel {
+            transaction_id: Set(transaction_id.as_bytes().to_vec()),
+            tag_id: Set(tag_id),
+            ..Default::default()
+        });
+        transaction_tag::Entity::insert_many(links)
+            .exec(txn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn delete_transaction_tags(
+        &self,
+        txn: &DatabaseTransaction,
+        transaction_id: Uuid
+    )
+        -> Result<(), RepositoryError>
+    {
+        transaction_tag::Entity::delete_many()
+            .filter(transaction_tag::Column::TransactionId.eq(transaction_id.as_bytes().to_vec()))
+            .exec(txn)
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn find_tags_by_transaction_ids(
+        &self,
+        user_id: Uuid,
+        transaction_ids: Vec<Vec<u8>>
+    )
+        -> Result<HashMap<Vec<u8>, Vec<tag::Model>>, RepositoryError>
+    {
+        let mut tags_by_transaction: HashMap<Vec<u8>, Vec<tag::Model>> = HashMap::new();
+        if transaction_ids.is_empty() {
+            return Ok(tags_by_transaction);
+        }
+
+        let rows = transaction_tag::Entity::find()
+            .filter(transaction_tag::Column::TransactionId.is_in(transaction_ids))
+            .find_also_related(tag::Entity)
+            .filter(tag::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .order_by_asc(tag::Column::Name)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        for (link, tag) in rows {
+            if let Some(tag) = tag {
+                tags_by_transaction.entry(link.transaction_id).or_default().push(tag);
+            }
+        }
+
+        Ok(tags_by_transaction)
+    }
+}
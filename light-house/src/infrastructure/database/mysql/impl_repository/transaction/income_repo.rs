@@ -1,15 +1,14 @@
 use std::sync::Arc;
 
 use sea_orm::TransactionTrait;
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, SqlErr};
 use uuid::Uuid;
-use rust_decimal::Decimal;
-use rust_decimal::prelude::*;
+use crate::domain::dto::dto_enum::transaction_type::TransactionTypeVariant;
 use crate::domain::entities::{asset, contact, transaction_type};
-use crate::implentation::date_time_utill::parse_to_datetime_utc;
+use crate::implentation::date_time_utill::parse_occurred_at;
 use crate::{
-    domain::{dto::transaction_dto::{ReqCreateIncomeDto, ReqUpdateIncomeDto}, entities::transaction, req_repository::{balance_repository::BalanceRepositoryBase, transaction_repository::RecordIncomeRepositoryUtility}},
-    infrastructure::database::mysql::impl_repository::balance_repo::BalanceRepositoryImpl,
+    domain::{dto::{ledger_dto::ReqPostJournalEntryDto, transaction_dto::{ReqCreateIncomeDto, ReqUpdateIncomeDto}}, entities::transaction, req_repository::{ledger_repository::LedgerRepositoryBase, tag_repository::TransactionTagRepositoryUtility, transaction_repository::RecordIncomeRepositoryUtility}},
+    infrastructure::database::mysql::impl_repository::{ledger_repo::LedgerRepositoryImpl, tag_repo::TagRepositoryImpl, transaction::transaction_repo::find_owned_of_type},
     soc::soc_repository::RepositoryError
 };
 
@@ -39,6 +38,11 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
     )
          -> Result<transaction::Model, RepositoryError>
     {
+        // 1: start transaction, the fk lookups below read through it too
+        let txn = self.db_pool.begin().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
+        })?;
+
         // validation fk 
         // Check if the transaction type ID is valid
         let transaction_type_id_binary = match Uuid::parse_str(&income_record_dto.transaction_type_id) {
@@ -50,7 +54,7 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
         };
         
         let is_transaction_type_valid = transaction_type::Entity::find_by_id(transaction_type_id_binary.clone())
-            .one(self.db_pool.as_ref())
+            .one(&txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
         if is_transaction_type_valid.is_none() {
@@ -68,7 +72,7 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
         };
         
         let is_asset_valid = asset::Entity::find_by_id(asset_id_binary.clone())
-            .one(self.db_pool.as_ref())
+            .one(&txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
         if is_asset_valid.is_none() {
@@ -84,7 +88,7 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
             }
         };
         let is_contact_valid = contact::Entity::find_by_id(contact_id_binary.clone())
-            .one(self.db_pool.as_ref())
+            .one(&txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
         if is_contact_valid.is_none() && is_contact_valid.is_none() {
@@ -93,34 +97,29 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
         }
   
         // handle date conversion
-        let created_date_utc = match parse_to_datetime_utc(&income_record_dto.created_at) {
+        let occurred_at_utc = match parse_occurred_at(&income_record_dto.occurred_at, income_record_dto.timezone.as_deref()) {
             Ok(date) => date,
             Err(err) => {
-                log::error!("Failed to parse created_at date: {}", err);
-                return Err(RepositoryError::InvalidInput("Invalid created_at date".to_string()));
+                log::error!("Failed to parse occurred_at date: {}", err);
+                return Err(RepositoryError::InvalidInput(format!("Invalid occurred_at date: {}", err)));
             }
         };
 
-        // 1: start transaction
-        let txn = self.db_pool.begin().await.map_err(|err| {
-            RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
-        })?;
-
-        // 2 initial balance repo to
-        let balance_repo = BalanceRepositoryImpl {
-            db_pool: Arc::clone(&self.db_pool),
-        };
+        // 2 initial ledger repo, it owns every balance change
+        let ledger_repo = LedgerRepositoryImpl::new(Arc::clone(&self.db_pool));
         log::info!("Creating income record for user: {}", user_id);
         // Create the ActiveModel for the income record
+        let transaction_id = Uuid::new_v4();
         let new_income_record = transaction::ActiveModel {
-            id: Set(Uuid::new_v4().as_bytes().to_vec()), // Generate a new UUID for the transaction
+            id: Set(transaction_id.as_bytes().to_vec()), // Generate a new UUID for the transaction
             transaction_type_id: Set(transaction_type_id_binary),
-            amount: Set(income_record_dto.amount),
+            amount: Set(income_record_dto.amount.0),
             asset_id: Set(asset_id_binary),
             contact_id: Set(Some(contact_id_binary)),
             note: Set(income_record_dto.note),
             user_id: Set(user_id.as_bytes().to_vec()),
-            created_at: Set(Some(created_date_utc)),
+            occurred_at: Set(occurred_at_utc),
+            external_reference: Set(income_record_dto.external_reference.filter(|reference| !reference.trim().is_empty())),
             ..Default::default()
         };
 
@@ -129,70 +128,37 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
             Ok(record) => record,
             Err(err) => {
                 txn.rollback().await.ok(); // Rollback on error
+                // the only unique key of a transaction is its external reference on the asset
+                if let Some(SqlErr::UniqueConstraintViolation(_)) = err.sql_err() {
+                    return Err(RepositoryError::UniqueConstraintViolation(
+                        "A transaction with this external_reference already exists on the asset".to_string(),
+                    ));
+                }
                 return Err(RepositoryError::DatabaseError(err.to_string()));
             }
         };
 
-        // Update the balance in the CurrentSheet table
-        let asset_id_uuid = match Uuid::from_slice(&inserted_income_record.asset_id) {
-            Ok(uuid) => uuid,
-            Err(e) => {
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(format!(
-                    "Invalid asset UUID: {}",
-                    e
-                )));
-            }
-        };
-
-        let current_sheet = match balance_repo
-            .get_current_sheet_by_asset_id(user_id, asset_id_uuid)
-            .await
-            {
-            Ok(Some(sheet)) => sheet,
-            Ok(None) => {
-                log::error!("Current sheet not found for asset ID: {}", asset_id_uuid);
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::NotFound(format!(
-                    "Current sheet not found for asset {}",
-                    asset_id_uuid
-                )));
-            }
+        // Post the journal entry: debit the asset, credit the income source
+        let journal_entry = match ReqPostJournalEntryDto::for_income(&inserted_income_record) {
+            Ok(entry) => entry,
             Err(err) => {
-                log::error!("Error fetching current sheet: {}", err);
                 txn.rollback().await.ok(); // Rollback on error
                 return Err(err);
             }
         };
+        if let Err(err) = ledger_repo.post_entry(&txn, user_id, journal_entry).await {
+            log::error!("Failed to post income journal entry: {}", err);
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
 
-        let the_new_balance = match Decimal::from_f64(inserted_income_record.amount) {
-            Some(amount) => {
-                log::info!("Amount from dto to add: {}", amount);
-                log::info!("Amount from current table : {} + value from database {}", current_sheet.balance, amount);
-                amount + current_sheet.balance
-            },
-            None => {
-                log::error!("Failed to convert amount to Decimal: {}", inserted_income_record.amount);
+        // Attach the tags
+        if let Some(tag_ids) = income_record_dto.tag_ids {
+            let tag_repo = TagRepositoryImpl::new(Arc::clone(&self.db_pool));
+            if let Err(err) = tag_repo.set_transaction_tags(&txn, user_id, transaction_id, tag_ids).await {
                 txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(
-                    "Failed to convert amount to Decimal".to_string(),
-                ));
+                return Err(err);
             }
-        };
-
-       
-
-        // Round the new balance to 2 decimal places
-        let rounded_balance = the_new_balance.round_dp(2);
-        log::info!("Rounded balance: {}", rounded_balance);
-
-        if let Err(err) = balance_repo
-            .update_current_sheet(user_id, asset_id_uuid, Some(rounded_balance.to_f64().unwrap()))
-            .await
-        {
-            log::error!("Failed to update current sheet balance: {}", err);
-            txn.rollback().await.ok(); // Rollback on error
-            return Err(err);
         }
 
         txn.commit().await.map_err(|err| {
@@ -215,13 +181,11 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
             RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
         })?;
     
-        let balance_repo = BalanceRepositoryImpl {
-            db_pool: Arc::clone(&self.db_pool),
-        };
+        let ledger_repo = LedgerRepositoryImpl::new(Arc::clone(&self.db_pool));
     
-        // Fetch the original transaction
-        let original_transaction = match transaction::Entity::find_by_id(transaction_id.as_bytes().to_vec())
-            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
+        // Fetch and lock the original transaction, a concurrent update or delete waits for this one
+        let original_transaction = match find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Income)
+            .lock_exclusive()
             .one(&txn)
             .await
         {
@@ -239,15 +203,12 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
             }
         };
     
-        let old_amount = original_transaction.amount;
-        let old_asset_id_uuid = match Uuid::from_slice(&original_transaction.asset_id) {
-            Ok(uuid) => uuid,
-            Err(e) => {
+        // Journal entry of the transaction as it is now, reversed once the update is saved
+        let old_journal_entry = match ReqPostJournalEntryDto::for_income(&original_transaction) {
+            Ok(entry) => entry,
+            Err(err) => {
                 txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(format!(
-                    "Invalid old asset UUID: {}",
-                    e
-                )));
+                return Err(err);
             }
         };
     
@@ -268,7 +229,7 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
     
         // Validate the new asset ID
         let is_asset_valid = asset::Entity::find_by_id(new_asset_id_binary.clone())
-            .one(self.db_pool.as_ref())
+            .one(&txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
         if is_asset_valid.is_none() {
@@ -281,7 +242,7 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
         // Update the transaction
         let mut active_model: transaction::ActiveModel = original_transaction.into();
         if let Some(amount) = income_record_dto.amount {
-            active_model.amount = Set(amount);
+            active_model.amount = Set(amount.0);
         }
         if let Some(_asset_id) = income_record_dto.asset_id {
             active_model.asset_id = Set(new_asset_id_binary.clone());
@@ -300,7 +261,7 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
     
             // Validate the contact ID
             let is_contact_valid = contact::Entity::find_by_id(contact_id_binary.clone())
-                .one(self.db_pool.as_ref())
+                .one(&txn)
                 .await
                 .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
             if is_contact_valid.is_none() {
@@ -313,13 +274,13 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
         if let Some(note) = income_record_dto.note {
             active_model.note = Set(note);
         }
-        if let Some(created_at) = income_record_dto.created_at {
-            match parse_to_datetime_utc(&created_at) {
-                Ok(date) => active_model.created_at = Set(Some(date)),
+        if let Some(occurred_at) = income_record_dto.occurred_at {
+            match parse_occurred_at(&occurred_at, income_record_dto.timezone.as_deref()) {
+                Ok(date) => active_model.occurred_at = Set(date),
                 Err(err) => {
                     txn.rollback().await.ok(); // Rollback on error
                     return Err(RepositoryError::InvalidInput(format!(
-                        "Invalid created_at date: {}",
+                        "Invalid occurred_at date: {}",
                         err
                     )));
                 }
@@ -333,133 +294,26 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
             }
         };
     
-        // Update balances
-        let new_amount = updated_transaction.amount;
-        let new_asset_id_uuid = match Uuid::from_slice(&updated_transaction.asset_id) {
-            Ok(uuid) => uuid,
-            Err(e) => {
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(format!(
-                    "Invalid new asset UUID: {}",
-                    e
-                )));
-            }
-        };
-    
-        log::debug!("Old amount: {}", old_amount);
-        log::debug!("New amount: {}", new_amount);
-        log::debug!("Old asset ID: {}", old_asset_id_uuid);
-        log::debug!("New asset ID: {}", new_asset_id_uuid);
-    
-        if old_asset_id_uuid == new_asset_id_uuid {
-            // Asset ID hasn't changed, calculate net change
-            let balance_change = new_amount - old_amount;
-            log::debug!("Balance change: {}", balance_change);
-    
-            let current_sheet = match balance_repo
-                .get_current_sheet_by_asset_id(user_id, new_asset_id_uuid)
-                .await
-            {
-                Ok(Some(sheet)) => sheet,
-                Ok(None) => {
-                    log::error!("Current sheet not found for asset ID: {}", new_asset_id_uuid);
-                    txn.rollback().await.ok(); // Rollback on error
-                    return Err(RepositoryError::NotFound(format!(
-                        "Current sheet for asset {} not found",
-                        new_asset_id_uuid
-                    )));
-                }
-                Err(err) => {
-                    log::error!("Error fetching current sheet: {}", err);
-                    txn.rollback().await.ok(); // Rollback on error
-                    return Err(err);
-                }
-            };
-    
-            let final_balance = match Decimal::from_f64(balance_change) {
-                Some(change) => current_sheet.balance + change,
-                None => {
-                    log::error!("Failed to convert balance change to Decimal");
-                    txn.rollback().await.ok(); // Rollback on error
-                    return Err(RepositoryError::OperationFailed(
-                        "Failed to convert balance change to Decimal".to_string(),
-                    ));
-                }
-            };
-            log::debug!("Final balance: {}", final_balance);
-    
-            if let Err(err) = balance_repo
-                .update_current_sheet(user_id, new_asset_id_uuid, Some(final_balance.to_f64().unwrap()))
-                .await
-            {
-                log::error!("Failed to update current sheet balance: {}", err);
+        // Back out the old posting, then post the updated one
+        let new_journal_entry = match ReqPostJournalEntryDto::for_income(&updated_transaction) {
+            Ok(entry) => entry,
+            Err(err) => {
                 txn.rollback().await.ok(); // Rollback on error
                 return Err(err);
             }
-        } else {
-            // Handle case where asset ID has changed
-            let old_current_sheet = match balance_repo
-                .get_current_sheet_by_asset_id(user_id, old_asset_id_uuid)
-                .await
-            {
-                Ok(Some(sheet)) => sheet,
-                Ok(None) => {
-                    log::error!("Current sheet not found for old asset ID: {}", old_asset_id_uuid);
-                    txn.rollback().await.ok(); // Rollback on error
-                    return Err(RepositoryError::NotFound(format!(
-                        "Current sheet for old asset {} not found",
-                        old_asset_id_uuid
-                    )));
-                }
-                Err(err) => {
-                    log::error!("Error fetching current sheet for old asset: {}", err);
-                    txn.rollback().await.ok(); // Rollback on error
-                    return Err(err);
-                }
-            };
-    
-            let new_current_sheet = match balance_repo
-                .get_current_sheet_by_asset_id(user_id, new_asset_id_uuid)
-                .await
-            {
-                Ok(Some(sheet)) => sheet,
-                Ok(None) => {
-                    log::error!("Current sheet not found for new asset ID: {}", new_asset_id_uuid);
-                    txn.rollback().await.ok(); // Rollback on error
-                    return Err(RepositoryError::NotFound(format!(
-                        "Current sheet for new asset {} not found",
-                        new_asset_id_uuid
-                    )));
-                }
-                Err(err) => {
-                    log::error!("Error fetching current sheet for new asset: {}", err);
-                    txn.rollback().await.ok(); // Rollback on error
-                    return Err(err);
-                }
-            };
-    
-            // Adjust balances for both old and new assets
-            let old_balance = old_current_sheet.balance - Decimal::from_f64(old_amount).ok_or_else(|| {
-                RepositoryError::OperationFailed("Failed to convert old_amount to Decimal".to_string())
-            })?;
-            let new_balance = new_current_sheet.balance + Decimal::from_f64(new_amount).ok_or_else(|| {
-                RepositoryError::OperationFailed("Failed to convert new_amount to Decimal".to_string())
-            })?;
-    
-            if let Err(err) = balance_repo
-                .update_current_sheet(user_id, old_asset_id_uuid, Some(old_balance.to_f64().unwrap()))
-                .await
-            {
-                log::error!("Failed to update old current sheet balance: {}", err);
+        };
+        for journal_entry in [old_journal_entry.reversal(), new_journal_entry] {
+            if let Err(err) = ledger_repo.post_entry(&txn, user_id, journal_entry).await {
+                log::error!("Failed to post income journal entry: {}", err);
                 txn.rollback().await.ok(); // Rollback on error
                 return Err(err);
             }
-    
-            if let Err(err) = balance_repo
-                .update_current_sheet(user_id, new_asset_id_uuid, Some(new_balance.to_f64().unwrap()))
-                .await
-            {
-                log::error!("Failed to update new current sheet balance: {}", err);
+        }
+
+        // Replace the tags when they are given
+        if let Some(tag_ids) = income_record_dto.tag_ids {
+            let tag_repo = TagRepositoryImpl::new(Arc::clone(&self.db_pool));
+            if let Err(err) = tag_repo.set_transaction_tags(&txn, user_id, transaction_id, tag_ids).await {
                 txn.rollback().await.ok(); // Rollback on error
                 return Err(err);
             }
@@ -489,13 +343,11 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
             RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
         })?;
 
-        let balance_repo = BalanceRepositoryImpl {
-            db_pool: Arc::clone(&self.db_pool),
-        };
+        let ledger_repo = LedgerRepositoryImpl::new(Arc::clone(&self.db_pool));
 
-        // Fetch the transaction to be deleted
-        let transaction_to_delete = match transaction::Entity::find_by_id(transaction_id.as_bytes().to_vec())
-            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
+        // Fetch and lock the transaction to be deleted, so a concurrent update cannot reverse it as well
+        let transaction_to_delete = match find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Income)
+            .lock_exclusive()
             .one(&txn)
             .await
         {
@@ -513,17 +365,12 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
             }
         };
 
-        let amount_to_subtract = transaction_to_delete.amount;
-        let asset_id_uuid = match Uuid::from_slice(&transaction_to_delete.asset_id) {
-            Ok(uuid) => uuid,
-            Err(e) => {
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(format!(
-                    "Invalid asset UUID: {}",
-                    e
-                )));
-            }
-        };
+        // Detach the tags, the links would block the delete
+        let tag_repo = TagRepositoryImpl::new(Arc::clone(&self.db_pool));
+        if let Err(err) = tag_repo.delete_transaction_tags(&txn, transaction_id).await {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
 
         // Delete the income record
         let delete_result = match transaction::Entity::delete_by_id(transaction_id.as_bytes().to_vec())
@@ -545,39 +392,17 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
             )));
         }
 
-        // Update the balance in the CurrentSheet table
-        let current_sheet = match balance_repo
-            .get_current_sheet_by_asset_id(user_id, asset_id_uuid)
-            .await
-        {
-            Ok(Some(sheet)) => sheet,
-            Ok(None) => {
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::NotFound(format!(
-                    "Current sheet not found for asset {}",
-                    asset_id_uuid
-                )));
-            }
+        // Reverse the posting, the row is gone so the entry only keeps the id in its memo
+        let mut journal_entry = match ReqPostJournalEntryDto::for_income(&transaction_to_delete) {
+            Ok(entry) => entry.reversal(),
             Err(err) => {
                 txn.rollback().await.ok(); // Rollback on error
                 return Err(err);
             }
         };
-
-        let new_balance = match Decimal::from_f64(amount_to_subtract) {
-            Some(amount) => current_sheet.balance - amount,
-            None => {
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(
-                    "Failed to convert amount_to_subtract to Decimal".to_string(),
-                ));
-            }
-        };
-
-        if let Err(err) = balance_repo
-            .update_current_sheet(user_id, asset_id_uuid, Some(new_balance.to_f64().unwrap()))
-            .await
-        {
+        journal_entry.transaction_id = None;
+        if let Err(err) = ledger_repo.post_entry(&txn, user_id, journal_entry).await {
+            log::error!("Failed to post income reversal entry: {}", err);
             txn.rollback().await.ok(); // Rollback on error
             return Err(err);
         }
@@ -599,9 +424,7 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
          -> Result<Option<transaction::Model>, RepositoryError>
     {
         // Query the database to find the income record by ID and ensure it belongs to the user
-        let income_record = transaction::Entity::find()
-            .filter(transaction::Column::Id.eq(transaction_id.as_bytes().to_vec()))
-            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
+        let income_record = find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Income)
             .one(self.db_pool.as_ref())
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
@@ -642,6 +465,8 @@ impl RecordIncomeRepositoryUtility for IncomeRepositoryImpl {
         let income_records = transaction::Entity::find()
             .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Filter by user ID
             .filter(transaction::Column::TransactionTypeId.eq(income_uuid.as_bytes().to_vec())) // Filter by transaction type
+            .order_by_desc(transaction::Column::OccurredAt) // Newest business date first
+            .order_by_desc(transaction::Column::Id)
             .all(self.db_pool.as_ref())
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
@@ -1,11 +1,10 @@
 use std::sync::Arc;
 #[allow(unused_imports)]
 use rust_decimal_macros::*;
-use rust_decimal::prelude::*;
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, TransactionTrait};
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, SqlErr, TransactionTrait};
 use uuid::Uuid;
 
-use crate::{domain::{dto::transaction_dto::{ReqCreatePaymentDto, ReqUpdatePaymentDto}, entities::{asset, contact, expense, transaction, transaction_type}, req_repository::{balance_repository::BalanceRepositoryBase, transaction_repository::RecordPaymentRepositoryUtility}}, infrastructure::database::mysql::impl_repository::balance_repo::BalanceRepositoryImpl, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::{dto_enum::transaction_type::TransactionTypeVariant, ledger_dto::ReqPostJournalEntryDto, transaction_dto::{ReqCreatePaymentDto, ReqUpdatePaymentDto}}, entities::{asset, contact, expense, transaction, transaction_type}, req_repository::{ledger_repository::LedgerRepositoryBase, payment_split_repository::PaymentSplitRepositoryUtility, tag_repository::TransactionTagRepositoryUtility, transaction_repository::RecordPaymentRepositoryUtility}}, implentation::date_time_utill::parse_occurred_at, infrastructure::database::mysql::impl_repository::{ledger_repo::LedgerRepositoryImpl, payment_split_repo::PaymentSplitRepositoryImpl, tag_repo::TagRepositoryImpl, transaction::transaction_repo::find_owned_of_type}, soc::soc_repository::RepositoryError};
 
 
 
@@ -53,7 +52,7 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         };
         
         let is_transaction_type_valid = transaction_type::Entity::find_by_id(transaction_type_id_binary.clone())
-            .one(self.db_pool.as_ref())
+            .one(&txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
         if is_transaction_type_valid.is_none() {
@@ -76,7 +75,7 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         };
 
         let is_expense_valid = expense::Entity::find_by_id(expense_id_binary.clone())
-            .one(self.db_pool.as_ref())
+            .one(&txn)
             .await
             .map_err(|err| {
                 log::error!("Error querying expense_id: {}", err);
@@ -102,7 +101,7 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         };
 
         let is_asset_valid = asset::Entity::find_by_id(asset_id_binary.clone())
-            .one(self.db_pool.as_ref())
+            .one(&txn)
             .await
             .map_err(|err| {
                 log::error!("Error querying asset_id: {}", err);
@@ -128,7 +127,7 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         };
 
         let is_contact_valid = contact::Entity::find_by_id(contact_id_binary.clone())
-            .one(self.db_pool.as_ref())
+            .one(&txn)
             .await
             .map_err(|err| {
                 log::error!("Error querying contact_id: {}", err);
@@ -139,17 +138,30 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
             return Err(RepositoryError::InvalidInput("Invalid contact id".to_string()));
         }
 
+        // Parse the business date
+        let occurred_at_utc = match parse_occurred_at(&payment_record_dto.occurred_at, payment_record_dto.timezone.as_deref()) {
+            Ok(date) => date,
+            Err(err) => {
+                log::error!("Failed to parse occurred_at date: {}", err);
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::InvalidInput(format!("Invalid occurred_at date: {}", err)));
+            }
+        };
+
         // Create the ActiveModel for the payment record
         log::debug!("Creating ActiveModel for payment record...");
+        let transaction_id = Uuid::new_v4();
         let new_payment_record = transaction::ActiveModel {
-            id: Set(Uuid::new_v4().as_bytes().to_vec()), // Generate a new UUID for the transaction
+            id: Set(transaction_id.as_bytes().to_vec()), // Generate a new UUID for the transaction
             transaction_type_id: Set(transaction_type_id_binary),
-            amount: Set(payment_record_dto.amount),
+            amount: Set(payment_record_dto.amount.0),
             expense_id: Set(Some(expense_id_binary)),
             asset_id: Set(asset_id_binary),
             contact_id: Set(Some(contact_id_binary)),
             note: Set(payment_record_dto.note),
             user_id: Set(user_id.as_bytes().to_vec()),
+            occurred_at: Set(occurred_at_utc),
+            external_reference: Set(payment_record_dto.external_reference.filter(|reference| !reference.trim().is_empty())),
             ..Default::default()
         };
 
@@ -160,6 +172,12 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
             Err(err) => {
                 log::error!("Failed to insert payment record: {}", err);
                 txn.rollback().await.ok(); // Rollback on error
+                // the only unique key of a transaction is its external reference on the asset
+                if let Some(SqlErr::UniqueConstraintViolation(_)) = err.sql_err() {
+                    return Err(RepositoryError::UniqueConstraintViolation(
+                        "A transaction with this external_reference already exists on the asset".to_string(),
+                    ));
+                }
                 if let sea_orm::DbErr::Exec(exec_err) = &err {
                     if exec_err.to_string().contains("FOREIGN KEY") {
                         return Err(RepositoryError::OperationFailed(
@@ -171,62 +189,49 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
             }
         };
 
-        // Update the balance in the CurrentSheet table
-        log::debug!("Updating balance in the CurrentSheet table...");
-        let asset_id_uuid = match Uuid::from_slice(&inserted_payment_record.asset_id) {
-            Ok(uuid) => uuid,
-            Err(e) => {
-                log::error!("Invalid asset UUID: {}", e);
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(format!(
-                    "Invalid asset UUID: {}",
-                    e
-                )));
+        // Store the split lines
+        let payment_split_repo = PaymentSplitRepositoryImpl::new(Arc::clone(&self.db_pool));
+        let splits = match payment_record_dto.splits {
+            Some(splits) => {
+                log::debug!("Storing {} split lines of the payment record...", splits.len());
+                match payment_split_repo.set_payment_splits(&txn, transaction_id, splits).await {
+                    Ok(splits) => splits,
+                    Err(err) => {
+                        log::error!("Failed to store payment splits: {}", err);
+                        txn.rollback().await.ok(); // Rollback on error
+                        return Err(err);
+                    }
+                }
             }
+            None => Vec::new(),
         };
-        let balance_repo = BalanceRepositoryImpl {
-            db_pool: Arc::clone(&self.db_pool),
-        };
-        let current_sheet = match balance_repo
-            .get_current_sheet_by_asset_id(user_id, asset_id_uuid)
-            .await
-        {
-            Ok(Some(sheet)) => sheet,
-            Ok(None) => {
-                log::error!("Current sheet not found for asset ID: {}", asset_id_uuid);
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::NotFound(format!(
-                    "Current sheet not found for asset {}",
-                    asset_id_uuid
-                )));
-            }
+
+        // Post the journal entry: debit the expense of every line, credit the asset
+        log::debug!("Posting payment journal entry...");
+        let ledger_repo = LedgerRepositoryImpl::new(Arc::clone(&self.db_pool));
+        let journal_entry = match ReqPostJournalEntryDto::for_payment(&inserted_payment_record, &splits) {
+            Ok(entry) => entry,
             Err(err) => {
-                log::error!("Error fetching current sheet: {}", err);
+                log::error!("Failed to build payment journal entry: {}", err);
                 txn.rollback().await.ok(); // Rollback on error
                 return Err(err);
             }
         };
+        if let Err(err) = ledger_repo.post_entry(&txn, user_id, journal_entry).await {
+            log::error!("Failed to post payment journal entry: {}", err);
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
 
-        let new_balance = match Decimal::from_f64(inserted_payment_record.amount) {
-            Some(amount) => current_sheet.balance - amount,
-            None => {
-                log::error!("Failed to convert amount to Decimal: {}", inserted_payment_record.amount);
+        // Attach the tags
+        if let Some(tag_ids) = payment_record_dto.tag_ids {
+            log::debug!("Attaching {} tags to the payment record...", tag_ids.len());
+            let tag_repo = TagRepositoryImpl::new(Arc::clone(&self.db_pool));
+            if let Err(err) = tag_repo.set_transaction_tags(&txn, user_id, transaction_id, tag_ids).await {
+                log::error!("Failed to attach tags to payment record: {}", err);
                 txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(
-                    "Failed to convert amount to Decimal".to_string(),
-                ));
+                return Err(err);
             }
-        };
-
-        log::debug!("New balance calculated: {}", new_balance);
-
-        if let Err(err) = balance_repo
-            .update_current_sheet(user_id, asset_id_uuid, Some(new_balance.to_f64().unwrap()))
-            .await
-        {
-            log::error!("Failed to update balance in CurrentSheet table: {}", err);
-            txn.rollback().await.ok(); // Rollback on error
-            return Err(err);
         }
 
         // Commit the transaction
@@ -256,14 +261,13 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
     })?;
 
-    let balance_repo = BalanceRepositoryImpl {
-        db_pool: Arc::clone(&self.db_pool),
-    };
+    let ledger_repo = LedgerRepositoryImpl::new(Arc::clone(&self.db_pool));
 
-    // Fetch the original transaction to get old_amount and old_asset_id
+    // Fetch and lock the original transaction so its journal entry can be reversed. A concurrent
+    // update or delete waits here and then reads the row as this one leaves it, or finds it gone
     log::debug!("Fetching original transaction for transaction_id: {}", transaction_id);
-    let original_transaction = match transaction::Entity::find_by_id(transaction_id.as_bytes().to_vec())
-        .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
+    let original_transaction = match find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Payment)
+        .lock_exclusive()
         .one(&txn)
         .await
     {
@@ -283,21 +287,26 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         }
     };
 
-    let old_amount = original_transaction.amount;
-    log::debug!("Old amount: {}", old_amount);
+    // Split lines of the transaction as it is now
+    let payment_split_repo = PaymentSplitRepositoryImpl::new(Arc::clone(&self.db_pool));
+    let original_splits = match payment_split_repo.find_payment_splits(&txn, transaction_id).await {
+        Ok(splits) => splits,
+        Err(err) => {
+            log::error!("Error fetching original payment splits: {}", err);
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
+    };
 
-    let old_asset_id_uuid = match Uuid::from_slice(&original_transaction.asset_id) {
-        Ok(uuid) => uuid,
-        Err(e) => {
-            log::error!("Invalid old asset UUID: {}", e);
+    // Journal entry of the transaction as it is now, reversed once the update is saved
+    let old_journal_entry = match ReqPostJournalEntryDto::for_payment(&original_transaction, &original_splits) {
+        Ok(entry) => entry,
+        Err(err) => {
+            log::error!("Failed to build journal entry for original transaction: {}", err);
             txn.rollback().await.ok(); // Rollback on error
-            return Err(RepositoryError::OperationFailed(format!(
-                "Invalid old asset UUID: {}",
-                e
-            )));
+            return Err(err);
         }
     };
-    log::debug!("Old asset ID: {}", old_asset_id_uuid);
 
     // Convert the existing transaction into an ActiveModel for updating
     log::debug!("Converting original transaction into ActiveModel...");
@@ -306,7 +315,7 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
     // Update fields if they are provided in the DTO
     if let Some(amount) = payment_record_dto.amount {
         log::debug!("Updating amount to: {}", amount);
-        active_model.amount = Set(amount);
+        active_model.amount = Set(amount.0);
     }
     if let Some(expense_id) = payment_record_dto.expense_id {
         log::debug!("Updating expense_id to: {}", expense_id);
@@ -349,6 +358,20 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         log::debug!("Updating note to: {}", note);
         active_model.note = Set(note);
     }
+    if let Some(occurred_at) = payment_record_dto.occurred_at {
+        log::debug!("Updating occurred_at to: {}", occurred_at);
+        match parse_occurred_at(&occurred_at, payment_record_dto.timezone.as_deref()) {
+            Ok(date) => active_model.occurred_at = Set(date),
+            Err(err) => {
+                log::error!("Invalid occurred_at: {}", err);
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::InvalidInput(format!(
+                    "Invalid occurred_at date: {}",
+                    err
+                )));
+            }
+        }
+    }
     if let Some(asset_id) = payment_record_dto.asset_id {
         log::debug!("Updating asset_id to: {}", asset_id);
         let asset_id_binary = match Uuid::parse_str(&asset_id) {
@@ -380,157 +403,50 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
     };
     log::debug!("Updated transaction saved successfully.");
 
-    // Update balances
-    let new_amount = updated_transaction.amount;
-    log::debug!("New amount: {}", new_amount);
-
-    let new_asset_id_uuid = match Uuid::from_slice(&updated_transaction.asset_id) {
-        Ok(uuid) => uuid,
-        Err(e) => {
-            log::error!("Invalid new asset UUID: {}", e);
-            txn.rollback().await.ok(); // Rollback on error
-            return Err(RepositoryError::OperationFailed(format!(
-                "Invalid new asset UUID: {}",
-                e
-            )));
+    // Replace the split lines when they are given, an empty list turns it back into a single expense payment
+    let splits = match payment_record_dto.splits {
+        Some(splits) => {
+            log::debug!("Replacing split lines with {} lines...", splits.len());
+            match payment_split_repo.set_payment_splits(&txn, transaction_id, splits).await {
+                Ok(splits) => splits,
+                Err(err) => {
+                    log::error!("Failed to replace payment splits: {}", err);
+                    txn.rollback().await.ok(); // Rollback on error
+                    return Err(err);
+                }
+            }
         }
+        None => original_splits,
     };
-    log::debug!("New asset ID: {}", new_asset_id_uuid);
-
-    if old_asset_id_uuid == new_asset_id_uuid {
-        log::debug!("Asset ID has not changed. Calculating balance change...");
-        let balance_change = old_amount - new_amount;
-        log::debug!("Balance change: {}", balance_change);
-
-        let current_sheet = match balance_repo
-            .get_current_sheet_by_asset_id(user_id, new_asset_id_uuid)
-            .await
-        {
-            Ok(Some(sheet)) => sheet,
-            Ok(None) => {
-                log::error!("Current sheet not found for asset ID: {}", new_asset_id_uuid);
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::NotFound(format!(
-                    "Current sheet for asset {} not found",
-                    new_asset_id_uuid
-                )));
-            }
-            Err(err) => {
-                log::error!("Error fetching current sheet: {}", err);
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(err);
-            }
-        };
-
-        let final_balance = match Decimal::from_f64(balance_change) {
-            Some(change) => current_sheet.balance + change,
-            None => {
-                log::error!("Failed to convert balance change to Decimal.");
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(
-                    "Failed to convert balance change to Decimal".to_string(),
-                ));
-            }
-        };
-        log::debug!("Final balance: {}", final_balance);
 
-        if let Err(err) = balance_repo
-            .update_current_sheet(user_id, new_asset_id_uuid, Some(final_balance.to_f64().unwrap()))
-            .await
-        {
-            log::error!("Failed to update current sheet balance: {}", err);
+    // Back out the old posting, then post the updated one
+    log::debug!("Reposting journal entry for updated transaction...");
+    let new_journal_entry = match ReqPostJournalEntryDto::for_payment(&updated_transaction, &splits) {
+        Ok(entry) => entry,
+        Err(err) => {
+            log::error!("Failed to build journal entry for updated transaction: {}", err);
             txn.rollback().await.ok(); // Rollback on error
             return Err(err);
         }
-        log::debug!("Balance updated successfully for unchanged asset ID.");
-    } else {
-        log::debug!("Asset ID has changed. Updating balances for old and new assets...");
-
-        // Revert old amount from old asset's balance
-        let old_asset_sheet = match balance_repo
-            .get_current_sheet_by_asset_id(user_id, old_asset_id_uuid)
-            .await
-        {
-            Ok(Some(sheet)) => sheet,
-            Ok(None) => {
-                log::error!("Current sheet not found for old asset ID: {}", old_asset_id_uuid);
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::NotFound(format!(
-                    "Current sheet for old asset {} not found",
-                    old_asset_id_uuid
-                )));
-            }
-            Err(err) => {
-                log::error!("Error fetching current sheet for old asset: {}", err);
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(err);
-            }
-        };
-
-        let balance_after_reverting_old = match Decimal::from_f64(old_amount) {
-            Some(amount) => old_asset_sheet.balance + amount,
-            None => {
-                log::error!("Failed to convert old_amount to Decimal.");
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(
-                    "Failed to convert old_amount to Decimal".to_string(),
-                ));
-            }
-        };
-        log::debug!("Balance after reverting old amount: {}", balance_after_reverting_old);
-
-        if let Err(err) = balance_repo
-            .update_current_sheet(user_id, old_asset_id_uuid, Some(balance_after_reverting_old.to_f64().unwrap()))
-            .await
-        {
-            log::error!("Failed to update balance for old asset: {}", err);
+    };
+    for journal_entry in [old_journal_entry.reversal(), new_journal_entry] {
+        if let Err(err) = ledger_repo.post_entry(&txn, user_id, journal_entry).await {
+            log::error!("Failed to post payment journal entry: {}", err);
             txn.rollback().await.ok(); // Rollback on error
             return Err(err);
         }
-        log::debug!("Balance updated successfully for old asset.");
-
-        // Apply new amount to new asset's balance
-        let new_asset_sheet = match balance_repo
-            .get_current_sheet_by_asset_id(user_id, new_asset_id_uuid)
-            .await
-        {
-            Ok(Some(sheet)) => sheet,
-            Ok(None) => {
-                log::error!("Current sheet not found for new asset ID: {}", new_asset_id_uuid);
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::NotFound(format!(
-                    "Current sheet for new asset {} not found",
-                    new_asset_id_uuid
-                )));
-            }
-            Err(err) => {
-                log::error!("Error fetching current sheet for new asset: {}", err);
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(err);
-            }
-        };
-
-        let final_new_asset_balance = match Decimal::from_f64(new_amount) {
-            Some(amount) => new_asset_sheet.balance - amount,
-            None => {
-                log::error!("Failed to convert new amount to Decimal.");
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::OperationFailed(
-                    "Failed to convert new amount to Decimal".to_string(),
-                ));
-            }
-        };
-        log::debug!("Final balance for new asset: {}", final_new_asset_balance);
-
-        if let Err(err) = balance_repo
-            .update_current_sheet(user_id, new_asset_id_uuid, Some(final_new_asset_balance.to_f64().unwrap()))
-            .await
-        {
-            log::error!("Failed to update balance for new asset: {}", err);
+    }
+    log::debug!("Journal entries posted successfully.");
+
+    // Replace the tags when they are given
+    if let Some(tag_ids) = payment_record_dto.tag_ids {
+        log::debug!("Replacing tags with {} tags...", tag_ids.len());
+        let tag_repo = TagRepositoryImpl::new(Arc::clone(&self.db_pool));
+        if let Err(err) = tag_repo.set_transaction_tags(&txn, user_id, transaction_id, tag_ids).await {
+            log::error!("Failed to replace payment tags: {}", err);
             txn.rollback().await.ok(); // Rollback on error
             return Err(err);
         }
-        log::debug!("Balance updated successfully for new asset.");
     }
 
     // Commit the transaction
@@ -559,23 +475,12 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
     })?;
 
-    let balance_repo = BalanceRepositoryImpl {
-        db_pool: Arc::clone(&self.db_pool),
-    };
+    let ledger_repo = LedgerRepositoryImpl::new(Arc::clone(&self.db_pool));
 
-    // Validate transaction_id
-    let transaction_id_binary = match Uuid::parse_str(&transaction_id.to_string()) {
-        Ok(uuid) => uuid.as_bytes().to_vec(),
-        Err(err) => {
-            log::error!("Invalid transaction_id: {}", err);
-            return Err(RepositoryError::InvalidInput("Invalid transaction_id".to_string()));
-        }
-    };
-
-    // Fetch the transaction to be deleted
+    // Fetch and lock the transaction to be deleted, so a concurrent update cannot reverse it as well
     log::debug!("Fetching transaction to delete for transaction_id: {}", transaction_id);
-    let transaction_to_delete = match transaction::Entity::find_by_id(transaction_id_binary)
-        .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
+    let transaction_to_delete = match find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Payment)
+        .lock_exclusive()
         .one(&txn)
         .await
     {
@@ -597,23 +502,29 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
 
     log::debug!("Transaction to delete found: {:?}", transaction_to_delete);
 
-    let amount_to_add_back = transaction_to_delete.amount;
-    log::debug!("Amount to add back to balance: {}", amount_to_add_back);
+    // Detach the tags, the links would block the delete
+    let tag_repo = TagRepositoryImpl::new(Arc::clone(&self.db_pool));
+    if let Err(err) = tag_repo.delete_transaction_tags(&txn, transaction_id).await {
+        log::error!("Failed to detach payment tags: {}", err);
+        txn.rollback().await.ok(); // Rollback on error
+        return Err(err);
+    }
 
-    let asset_id_uuid = match Uuid::from_slice(&transaction_to_delete.asset_id) {
-        Ok(uuid) => {
-            log::debug!("Parsed asset_id to UUID: {}", uuid);
-            uuid
-        }
-        Err(e) => {
-            log::error!("Invalid asset UUID: {}", e);
+    // Remove the split lines, the reversal still needs them
+    let payment_split_repo = PaymentSplitRepositoryImpl::new(Arc::clone(&self.db_pool));
+    let splits = match payment_split_repo.find_payment_splits(&txn, transaction_id).await {
+        Ok(splits) => splits,
+        Err(err) => {
+            log::error!("Error fetching payment splits: {}", err);
             txn.rollback().await.ok(); // Rollback on error
-            return Err(RepositoryError::OperationFailed(format!(
-                "Invalid asset UUID: {}",
-                e
-            )));
+            return Err(err);
         }
     };
+    if let Err(err) = payment_split_repo.delete_payment_splits(&txn, transaction_id).await {
+        log::error!("Failed to delete payment splits: {}", err);
+        txn.rollback().await.ok(); // Rollback on error
+        return Err(err);
+    }
 
     // Delete the payment record
     log::debug!("Deleting transaction with transaction_id: {}", transaction_id);
@@ -640,57 +551,23 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         )));
     }
 
-    // Update the balance in the CurrentSheet table
-    log::debug!("Fetching current sheet for asset_id: {}", asset_id_uuid);
-    let current_sheet = match balance_repo
-        .get_current_sheet_by_asset_id(user_id, asset_id_uuid)
-        .await
-    {
-        Ok(Some(sheet)) => sheet,
-        Ok(None) => {
-            log::error!("Current sheet not found for asset_id: {}", asset_id_uuid);
-            txn.rollback().await.ok(); // Rollback on error
-            return Err(RepositoryError::NotFound(format!(
-                "Current sheet not found for asset {}",
-                asset_id_uuid
-            )));
-        }
+    // Reverse the posting, the row is gone so the entry only keeps the id in its memo
+    log::debug!("Posting reversal entry for transaction_id: {}", transaction_id);
+    let mut journal_entry = match ReqPostJournalEntryDto::for_payment(&transaction_to_delete, &splits) {
+        Ok(entry) => entry.reversal(),
         Err(err) => {
-            log::error!("Error fetching current sheet: {}", err);
+            log::error!("Failed to build reversal entry: {}", err);
             txn.rollback().await.ok(); // Rollback on error
             return Err(err);
         }
     };
-
-    log::debug!("Current sheet found: {:?}", current_sheet);
-
-    let new_balance = match Decimal::from_f64(amount_to_add_back) {
-        Some(amount) => {
-            log::debug!("Calculating new balance: current_balance = {}, amount_to_add_back = {}", current_sheet.balance, amount);
-            current_sheet.balance + amount
-        }
-        None => {
-            log::error!("Failed to convert amount_to_add_back to Decimal: {}", amount_to_add_back);
-            txn.rollback().await.ok(); // Rollback on error
-            return Err(RepositoryError::OperationFailed(
-                "Failed to convert amount_to_add_back to Decimal".to_string(),
-            ));
-        }
-    };
-
-    log::debug!("New balance calculated: {}", new_balance);
-
-    if let Err(err) = balance_repo
-        .update_current_sheet(user_id, asset_id_uuid, Some(new_balance.to_f64().unwrap()))
-        .await
-    {
-        log::error!("Failed to update balance in CurrentSheet table: {}", err);
+    journal_entry.transaction_id = None;
+    if let Err(err) = ledger_repo.post_entry(&txn, user_id, journal_entry).await {
+        log::error!("Failed to post payment reversal entry: {}", err);
         txn.rollback().await.ok(); // Rollback on error
         return Err(err);
     }
 
-    log::debug!("Balance updated successfully in CurrentSheet table.");
-
     // Commit the transaction
     log::debug!("Committing transaction...");
     txn.commit().await.map_err(|err| {
@@ -712,9 +589,7 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         -> Result<Option<transaction::Model>, RepositoryError>
     {
         // Query the database to find the payment record by ID and ensure it belongs to the user
-        let payment_record = transaction::Entity::find()
-            .filter(transaction::Column::Id.eq(transaction_id.as_bytes().to_vec())) // Filter by transaction ID
-            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
+        let payment_record = find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Payment)
             .one(self.db_pool.as_ref())
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
@@ -755,6 +630,8 @@ impl RecordPaymentRepositoryUtility for PaymentRepositoryImpl {
         let payment_records = transaction::Entity::find()
             .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Filter by user ID
             .filter(transaction::Column::TransactionTypeId.eq(payment_uuid.as_bytes().to_vec())) // Filter by transaction type
+            .order_by_desc(transaction::Column::OccurredAt) // Newest business date first
+            .order_by_desc(transaction::Column::Id)
             .all(self.db_pool.as_ref())
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
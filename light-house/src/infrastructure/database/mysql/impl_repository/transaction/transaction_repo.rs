@@ -1,14 +1,46 @@
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use sea_orm::{sea_query::{LikeExpr, Query}, ColumnTrait, Condition, DatabaseConnection, EntityTrait, Order, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select};
 use uuid::Uuid;
 
-use crate::{domain::{entities::{transaction, transaction_type}, req_repository::transaction_repository::TransactionTypeRepositoryUtility}, soc::soc_repository::RepositoryError};
+use crate::{domain::{dto::{dto_enum::{transaction_sort::{SortOrderVariant, TransactionSortVariant}, transaction_type::TransactionTypeVariant}, transaction_dto::{TransactionCursorKeyDto, TransactionPageDto, TransactionReferenceNamesDto, TransactionSearchDto}}, entities::{asset, contact, expense, payment_split, transaction, transaction_tag, transaction_type}, req_repository::{tag_repository::TransactionTagRepositoryUtility, transaction_repository::{TransactionSearchRepositoryUtility, TransactionTypeRepositoryUtility}}}, infrastructure::database::mysql::impl_repository::tag_repo::TagRepositoryImpl, soc::soc_repository::RepositoryError};
 
 
 
 
 
+// "%note%" with the note's own %, _ and \ matched literally
+fn contains_pattern(text: &str) -> LikeExpr {
+    let mut pattern = String::with_capacity(text.len() + 2);
+    pattern.push('%');
+    for ch in text.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+    pattern.push('%');
+    LikeExpr::new(pattern).escape('\\')
+}
+
+// A user's transaction of one type. The income, payment and transfer routes rebuild the journal entry
+// of their own type only, so an id of another type must not be found. The type is matched through a
+// subquery, a locking read on this select then leaves the shared transaction_type row alone
+pub fn find_owned_of_type(user_id: Uuid, transaction_id: Uuid, transaction_type: TransactionTypeVariant) -> Select<transaction::Entity> {
+    transaction::Entity::find_by_id(transaction_id.as_bytes().to_vec())
+        .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec()))
+        .filter(
+            transaction::Column::TransactionTypeId.in_subquery(
+                Query::select()
+                    .column(transaction_type::Column::Id)
+                    .from(transaction_type::Entity)
+                    .and_where(transaction_type::Column::Name.eq(transaction_type.as_str()))
+                    .to_owned(),
+            ),
+        )
+}
+
+
 pub struct TransactionRepoImpl {
     db_pool: Arc<DatabaseConnection>,
 }
@@ -17,6 +49,111 @@ impl TransactionRepoImpl {
     pub fn new(db_pool: Arc<DatabaseConnection>) -> Self {
         Self { db_pool }
     }
+
+    // Every filter except the cursor, shared by the count and the page query
+    async fn build_search_query(
+        &self,
+        user_id: Uuid,
+        search: &TransactionSearchDto
+    )
+        -> Result<Option<Select<transaction::Entity>>, RepositoryError>
+    {
+        let mut query = transaction::Entity::find()
+            .filter(transaction::Column::UserId.eq(user_id));
+
+        if !search.transaction_types.is_empty() {
+            let type_ids: Vec<Vec<u8>> = transaction_type::Entity::find()
+                .filter(transaction_type::Column::Name.is_in(search.transaction_types.clone()))
+                .all(self.db_pool.as_ref())
+                .await
+                .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+                .into_iter()
+                .map(|model| model.id)
+                .collect();
+            if type_ids.is_empty() {
+                return Ok(None);
+            }
+            query = query.filter(transaction::Column::TransactionTypeId.is_in(type_ids));
+        }
+
+        if let Some(asset_id) = search.asset_id {
+            query = query.filter(
+                Condition::any()
+                    .add(transaction::Column::AssetId.eq(asset_id))
+                    .add(transaction::Column::DestinationAssetId.eq(asset_id))
+            );
+        }
+        if let Some(contact_id) = search.contact_id {
+            query = query.filter(transaction::Column::ContactId.eq(contact_id));
+        }
+        // a split payment also matches on the expense of any of its lines
+        if let Some(expense_id) = search.expense_id {
+            query = query.filter(
+                Condition::any()
+                    .add(transaction::Column::ExpenseId.eq(expense_id))
+                    .add(transaction::Column::Id.in_subquery(
+                        Query::select()
+                            .column(payment_split::Column::TransactionId)
+                            .from(payment_split::Entity)
+                            .and_where(payment_split::Column::ExpenseId.eq(expense_id))
+                            .to_owned()
+                    ))
+            );
+        }
+        if let Some(expense_type_id) = search.expense_type_id {
+            let expense_ids: Vec<Vec<u8>> = expense::Entity::find()
+                .filter(expense::Column::UserId.eq(user_id))
+                .filter(expense::Column::ExpenseTypeId.eq(expense_type_id))
+                .all(self.db_pool.as_ref())
+                .await
+                .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+                .into_iter()
+                .map(|model| model.id)
+                .collect();
+            if expense_ids.is_empty() {
+                return Ok(None);
+            }
+            query = query.filter(
+                Condition::any()
+                    .add(transaction::Column::ExpenseId.is_in(expense_ids.clone()))
+                    .add(transaction::Column::Id.in_subquery(
+                        Query::select()
+                            .column(payment_split::Column::TransactionId)
+                            .from(payment_split::Entity)
+                            .and_where(payment_split::Column::ExpenseId.is_in(expense_ids))
+                            .to_owned()
+                    ))
+            );
+        }
+        if let Some(tag_id) = search.tag_id {
+            query = query.filter(
+                transaction::Column::Id.in_subquery(
+                    Query::select()
+                        .column(transaction_tag::Column::TransactionId)
+                        .from(transaction_tag::Entity)
+                        .and_where(transaction_tag::Column::TagId.eq(tag_id))
+                        .to_owned()
+                )
+            );
+        }
+        if let Some(min_amount) = search.min_amount {
+            query = query.filter(transaction::Column::Amount.gte(min_amount));
+        }
+        if let Some(max_amount) = search.max_amount {
+            query = query.filter(transaction::Column::Amount.lte(max_amount));
+        }
+        if let Some(from) = search.from {
+            query = query.filter(transaction::Column::OccurredAt.gte(from));
+        }
+        if let Some(to) = search.to {
+            query = query.filter(transaction::Column::OccurredAt.lte(to));
+        }
+        if let Some(note) = &search.note {
+            query = query.filter(transaction::Column::Note.like(contains_pattern(note.trim())));
+        }
+
+        Ok(Some(query))
+    }
 }
 
 #[async_trait::async_trait]
@@ -54,4 +191,184 @@ impl TransactionTypeRepositoryUtility for TransactionRepoImpl
             Err(err) => Err(RepositoryError::DatabaseError(err.to_string())),
         }
     }
-}
\ No newline at end of file
+}
+
+
+
+#[async_trait::async_trait]
+impl TransactionSearchRepositoryUtility for TransactionRepoImpl
+{
+    async fn search_transactions(
+        &self,
+        user_id: Uuid,
+        search: TransactionSearchDto
+    )
+        -> Result<TransactionPageDto, RepositoryError>
+    {
+        // Step 1: apply the filters, a filter that can not match anything ends here
+        let query = match self.build_search_query(user_id, &search).await? {
+            Some(query) => query,
+            None => return Ok(TransactionPageDto { records: Vec::new(), total: 0, has_more: false }),
+        };
+
+        // Step 2: total of every matching row, regardless of the page
+        let total = query
+            .clone()
+            .count(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        // Step 3: order by the sort key with id as tie breaker so pages never overlap
+        let order = match search.order {
+            SortOrderVariant::Asc => Order::Asc,
+            SortOrderVariant::Desc => Order::Desc,
+        };
+        let sort_column = match search.sort_by {
+            TransactionSortVariant::OccurredAt => transaction::Column::OccurredAt,
+            TransactionSortVariant::Amount => transaction::Column::Amount,
+        };
+        let mut query = query
+            .order_by(sort_column, order.clone())
+            .order_by(transaction::Column::Id, order);
+
+        // Step 4: keyset on the cursor, otherwise plain offset on the page number
+        match &search.cursor {
+            Some(cursor) => {
+                let id = cursor.id.as_bytes().to_vec();
+                let (after, same) = match (&cursor.key, search.order) {
+                    (TransactionCursorKeyDto::OccurredAt(occurred_at), SortOrderVariant::Asc) => (
+                        transaction::Column::OccurredAt.gt(*occurred_at),
+                        Condition::all().add(transaction::Column::OccurredAt.eq(*occurred_at)).add(transaction::Column::Id.gt(id)),
+                    ),
+                    (TransactionCursorKeyDto::OccurredAt(occurred_at), SortOrderVariant::Desc) => (
+                        transaction::Column::OccurredAt.lt(*occurred_at),
+                        Condition::all().add(transaction::Column::OccurredAt.eq(*occurred_at)).add(transaction::Column::Id.lt(id)),
+                    ),
+                    (TransactionCursorKeyDto::Amount(amount), SortOrderVariant::Asc) => (
+                        transaction::Column::Amount.gt(*amount),
+                        Condition::all().add(transaction::Column::Amount.eq(*amount)).add(transaction::Column::Id.gt(id)),
+                    ),
+                    (TransactionCursorKeyDto::Amount(amount), SortOrderVariant::Desc) => (
+                        transaction::Column::Amount.lt(*amount),
+                        Condition::all().add(transaction::Column::Amount.eq(*amount)).add(transaction::Column::Id.lt(id)),
+                    ),
+                };
+                query = query.filter(Condition::any().add(after).add(same));
+            }
+            None => {
+                query = query.offset((search.page - 1) * search.per_page);
+            }
+        }
+
+        // Step 5: fetch one extra row to know whether another page follows
+        let mut records = query
+            .limit(search.per_page + 1)
+            .all(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        let has_more = records.len() as u64 > search.per_page;
+        records.truncate(search.per_page as usize);
+
+        Ok(TransactionPageDto { records, total, has_more })
+    }
+
+    async fn find_reference_names(
+        &self,
+        user_id: Uuid,
+        records: Vec<transaction::Model>
+    )
+        -> Result<TransactionReferenceNamesDto, RepositoryError>
+    {
+        let mut asset_ids: HashSet<Vec<u8>> = HashSet::new();
+        let mut contact_ids: HashSet<Vec<u8>> = HashSet::new();
+        let mut expense_ids: HashSet<Vec<u8>> = HashSet::new();
+        let transaction_ids: Vec<Vec<u8>> = records.iter().map(|record| record.id.clone()).collect();
+        for record in records {
+            asset_ids.insert(record.asset_id);
+            if let Some(destination_asset_id) = record.destination_asset_id {
+                asset_ids.insert(destination_asset_id);
+            }
+            if let Some(contact_id) = record.contact_id {
+                contact_ids.insert(contact_id);
+            }
+            if let Some(expense_id) = record.expense_id {
+                expense_ids.insert(expense_id);
+            }
+        }
+
+        let mut names = TransactionReferenceNamesDto {
+            transaction_types: transaction_type::Entity::find()
+                .all(self.db_pool.as_ref())
+                .await
+                .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+                .into_iter()
+                .map(|model| (model.id, model.name))
+                .collect(),
+            ..Default::default()
+        };
+
+        if !asset_ids.is_empty() {
+            names.assets = asset::Entity::find()
+                .filter(asset::Column::UserId.eq(user_id))
+                .filter(asset::Column::Id.is_in(asset_ids))
+                .all(self.db_pool.as_ref())
+                .await
+                .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+                .into_iter()
+                .map(|model| (model.id, model.name))
+                .collect();
+        }
+
+        if !contact_ids.is_empty() {
+            names.contacts = contact::Entity::find()
+                .filter(contact::Column::Id.is_in(contact_ids))
+                .all(self.db_pool.as_ref())
+                .await
+                .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+                .into_iter()
+                .map(|model| (model.id, model.name))
+                .collect();
+        }
+
+        if !expense_ids.is_empty() {
+            names.expenses = expense::Entity::find()
+                .filter(expense::Column::UserId.eq(user_id))
+                .filter(expense::Column::Id.is_in(expense_ids))
+                .all(self.db_pool.as_ref())
+                .await
+                .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+                .into_iter()
+                .map(|model| (model.id, model.description))
+                .collect();
+        }
+
+        names.tags = TagRepositoryImpl::new(Arc::clone(&self.db_pool))
+            .find_tags_by_transaction_ids(user_id, transaction_ids)
+            .await?;
+
+        Ok(names)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use sea_orm::{DbBackend, QueryTrait};
+
+    use super::*;
+
+    #[test]
+    fn typed_lookup_does_not_find_an_id_of_another_type() {
+        let user_id = Uuid::now_v7();
+        let transaction_id = Uuid::now_v7();
+        let sql = find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Income)
+            .lock_exclusive()
+            .build(DbBackend::MySql)
+            .to_string();
+
+        // deleting a payment through the income route finds nothing and is refused as not found
+        assert!(sql.contains("`transaction`.`transaction_type_id` IN (SELECT `id` FROM `transaction_type` WHERE `transaction_type`.`name` = 'income')"), "{}", sql);
+        assert!(sql.contains("`transaction`.`user_id` = "), "{}", sql);
+        assert!(sql.ends_with("FOR UPDATE"), "{}", sql);
+    }
+}
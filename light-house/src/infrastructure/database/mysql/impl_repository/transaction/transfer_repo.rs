@@ -1,15 +1,14 @@
 use std::sync::Arc;
 
 use sea_orm::TransactionTrait;
-use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
-use rust_decimal::Decimal;
-use rust_decimal::prelude::*;
+use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect, SqlErr};
 use uuid::Uuid;
 
 use crate::domain::entities::{asset, contact, transaction_type};
+use crate::implentation::date_time_utill::parse_occurred_at;
 use crate::{
-    domain::{dto::transaction_dto::{ReqCreateTransferDto, ReqUpdateTransferDto}, entities::transaction, req_repository::{balance_repository::BalanceRepositoryBase, transaction_repository::TransferRepositoryUtility}},
-    infrastructure::database::mysql::impl_repository::balance_repo::BalanceRepositoryImpl, soc::soc_repository::RepositoryError
+    domain::{dto::{dto_enum::transaction_type::TransactionTypeVariant, ledger_dto::ReqPostJournalEntryDto, transaction_dto::{ReqCreateTransferDto, ReqUpdateTransferDto}}, entities::transaction, req_repository::{ledger_repository::LedgerRepositoryBase, tag_repository::TransactionTagRepositoryUtility, transaction_repository::TransferRepositoryUtility}},
+    infrastructure::database::mysql::impl_repository::{ledger_repo::LedgerRepositoryImpl, tag_repo::TagRepositoryImpl, transaction::transaction_repo::find_owned_of_type}, soc::soc_repository::RepositoryError
 };
 
 
@@ -53,7 +52,7 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
         };
         
         let is_transaction_type_valid = transaction_type::Entity::find_by_id(transaction_type_id_binary.clone())
-            .one(self.db_pool.as_ref())
+            .one(&txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
         if is_transaction_type_valid.is_none() {
@@ -119,20 +118,31 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
             return Err(RepositoryError::OperationFailed("Invalid contact ID".to_string()));
         }
         // >>>>> Validate the transfer_dto <<<<<
-        let balance_repo = BalanceRepositoryImpl {
-            db_pool: Arc::clone(&self.db_pool),
+        let ledger_repo = LedgerRepositoryImpl::new(Arc::clone(&self.db_pool));
+
+        // Parse the business date
+        let occurred_at_utc = match parse_occurred_at(&transfer_dto.occurred_at, transfer_dto.timezone.as_deref()) {
+            Ok(date) => date,
+            Err(err) => {
+                log::error!("Failed to parse occurred_at date: {}", err);
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::InvalidInput(format!("Invalid occurred_at date: {}", err)));
+            }
         };
 
         // Create the ActiveModel for the transfer transaction
+        let transaction_id = Uuid::new_v4();
         let new_transfer = transaction::ActiveModel {
-            id: Set(Uuid::new_v4().as_bytes().to_vec()), // Generate a new UUID for the transaction
+            id: Set(transaction_id.as_bytes().to_vec()), // Generate a new UUID for the transaction
             transaction_type_id: Set(transaction_type_id_binary.clone()),
-            amount: Set(transfer_dto.amount),
+            amount: Set(transfer_dto.amount.0),
             asset_id: Set(asset_id_binary.clone()),
             destination_asset_id: Set(Some(destination_asset_id_binary.clone())),
             contact_id: Set(Some(contact_id_binary.clone())),
             note: Set(transfer_dto.note),
             user_id: Set(user_id.as_bytes().to_vec()),
+            occurred_at: Set(occurred_at_utc),
+            external_reference: Set(transfer_dto.external_reference.filter(|reference| !reference.trim().is_empty())),
             // created_at and updated_at will be set by default
             ..Default::default()
         };
@@ -142,76 +152,37 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
             Ok(record) => record,
             Err(err) => {
                 txn.rollback().await.ok(); // Rollback on error
+                // the only unique key of a transaction is its external reference on the asset
+                if let Some(SqlErr::UniqueConstraintViolation(_)) = err.sql_err() {
+                    return Err(RepositoryError::UniqueConstraintViolation(
+                        "A transaction with this external_reference already exists on the asset".to_string(),
+                    ));
+                }
                 return Err(RepositoryError::DatabaseError(err.to_string()));
             }
         };
 
-        // Update balances
-        let amount = inserted_transfer.amount;
-        let source_asset_id_uuid = Uuid::from_slice(&inserted_transfer.asset_id)
-            .map_err(|e| RepositoryError::OperationFailed(format!("Invalid source asset UUID: {}", e)))?;
-        let dest_asset_id_uuid = Uuid::from_slice(inserted_transfer.destination_asset_id.as_ref()
-            .ok_or_else(|| RepositoryError::OperationFailed("Destination asset ID is missing".to_string()))?)
-            .map_err(|e| RepositoryError::OperationFailed(format!("Invalid destination asset UUID: {}", e)))?;
-
-        // Decrease balance of source asset
-        let source_sheet = match balance_repo
-            .get_current_sheet_by_asset_id(user_id, source_asset_id_uuid)
-            .await
-        {
-            Ok(Some(sheet)) => sheet,
-            Ok(None) => {
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::NotFound(format!(
-                    "Current sheet for source asset {} not found",
-                    source_asset_id_uuid
-                )));
-            }
+        // Post the journal entry: debit the destination asset, credit the source asset
+        let journal_entry = match ReqPostJournalEntryDto::for_transfer(&inserted_transfer) {
+            Ok(entry) => entry,
             Err(err) => {
                 txn.rollback().await.ok(); // Rollback on error
                 return Err(err);
             }
         };
-
-        let new_source_balance = source_sheet.balance - Decimal::from_f64(amount)
-            .ok_or_else(|| RepositoryError::OperationFailed("Failed to convert amount to Decimal".to_string()))?;
-
-        if let Err(err) = balance_repo
-            .update_current_sheet(user_id, source_asset_id_uuid, Some(new_source_balance.to_f64().unwrap()))
-            .await
-        {
+        if let Err(err) = ledger_repo.post_entry(&txn, user_id, journal_entry).await {
+            log::error!("Failed to post transfer journal entry: {}", err);
             txn.rollback().await.ok(); // Rollback on error
             return Err(err);
         }
 
-        // Increase balance of destination asset
-        let dest_sheet = match balance_repo
-            .get_current_sheet_by_asset_id(user_id, dest_asset_id_uuid)
-            .await
-        {
-            Ok(Some(sheet)) => sheet,
-            Ok(None) => {
-                txn.rollback().await.ok(); // Rollback on error
-                return Err(RepositoryError::NotFound(format!(
-                    "Current sheet for destination asset {} not found",
-                    dest_asset_id_uuid
-                )));
-            }
-            Err(err) => {
+        // Attach the tags
+        if let Some(tag_ids) = transfer_dto.tag_ids {
+            let tag_repo = TagRepositoryImpl::new(Arc::clone(&self.db_pool));
+            if let Err(err) = tag_repo.set_transaction_tags(&txn, user_id, transaction_id, tag_ids).await {
                 txn.rollback().await.ok(); // Rollback on error
                 return Err(err);
             }
-        };
-
-        let new_dest_balance = dest_sheet.balance + Decimal::from_f64(amount)
-            .ok_or_else(|| RepositoryError::OperationFailed("Failed to convert amount to Decimal".to_string()))?;
-
-        if let Err(err) = balance_repo
-            .update_current_sheet(user_id, dest_asset_id_uuid, Some(new_dest_balance.to_f64().unwrap()))
-            .await
-        {
-            txn.rollback().await.ok(); // Rollback on error
-            return Err(err);
         }
 
         // Commit the transaction
@@ -317,13 +288,12 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
 
 
 
-        let balance_repo = BalanceRepositoryImpl { db_pool: Arc::clone(&self.db_pool) };
+        let ledger_repo = LedgerRepositoryImpl::new(Arc::clone(&self.db_pool));
 
-        // 1. Fetch the original transaction
+        // 1. Fetch and lock the original transaction, a concurrent update or delete waits for this one
         log::debug!("Fetching original transaction for transaction_id: {}", transaction_id);
-        let original_transaction = transaction::Entity::find_by_id(transaction_id.as_bytes().to_vec())
-            .filter(transaction::Column::Id.eq(transaction_id.as_bytes().to_vec())) // Filter by transaction ID
-            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
+        let original_transaction = find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Transfer)
+            .lock_exclusive()
             .one(&txn)
             .await
             .map_err(|err| {
@@ -335,19 +305,15 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
                 RepositoryError::NotFound(format!("Transaction {} not found for user {}", transaction_id, user_id))
             })?;
 
-    let old_amount = original_transaction.amount;
-    let old_source_asset_id_uuid = Uuid::from_slice(&original_transaction.asset_id)
-        .map_err(|e| RepositoryError::OperationFailed(format!("Invalid old source asset UUID: {}", e)))?;
-    let old_dest_asset_id_uuid = Uuid::from_slice(original_transaction.destination_asset_id.as_ref()
-        .ok_or_else(|| RepositoryError::OperationFailed("Old destination asset ID is missing".to_string()))?)
-        .map_err(|e| RepositoryError::OperationFailed(format!("Invalid old destination asset UUID: {}", e)))?;
+    // Journal entry of the transfer as it is now, reversed once the update is saved
+    let old_journal_entry = ReqPostJournalEntryDto::for_transfer(&original_transaction)?;
 
     // 2. Prepare and execute the update for the transaction record
     log::debug!("Preparing to update transaction record...");
     let mut active_model: transaction::ActiveModel = original_transaction.into();
     
     if let Some(amount) = transfer_dto.amount {
-        active_model.amount = Set(amount);
+        active_model.amount = Set(amount.0);
     }
     if let Some(_asset_id) = &transfer_dto.asset_id {
         active_model.asset_id = Set(asset_id_binary);
@@ -361,6 +327,19 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
     if let Some(note) = transfer_dto.note {
         active_model.note = Set(note);
     }
+    if let Some(occurred_at) = &transfer_dto.occurred_at {
+        match parse_occurred_at(occurred_at, transfer_dto.timezone.as_deref()) {
+            Ok(date) => active_model.occurred_at = Set(date),
+            Err(err) => {
+                log::error!("Invalid occurred_at: {}", err);
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::InvalidInput(format!(
+                    "Invalid occurred_at date: {}",
+                    err
+                )));
+            }
+        }
+    }
     
 
     let updated_transaction = active_model
@@ -373,44 +352,33 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
     log::debug!("Transaction record updated successfully: {:?}", updated_transaction);
 
     // 3. Get new values
-    let new_amount = updated_transaction.amount;
-    let new_source_asset_id_uuid = Uuid::from_slice(&updated_transaction.asset_id)
-        .map_err(|e| RepositoryError::OperationFailed(format!("Invalid new source asset UUID: {}", e)))?;
-    let new_dest_asset_id_uuid = Uuid::from_slice(updated_transaction.destination_asset_id.as_ref()
-        .ok_or_else(|| RepositoryError::OperationFailed("New destination asset ID is missing".to_string()))?)
-        .map_err(|e| RepositoryError::OperationFailed(format!("Invalid new destination asset UUID: {}", e)))?;
+    let new_journal_entry = ReqPostJournalEntryDto::for_transfer(&updated_transaction)?;
 
     // Ensure destination_asset_id is not the same as asset_id for the new state
-    if new_source_asset_id_uuid == new_dest_asset_id_uuid {
+    if updated_transaction.destination_asset_id.as_ref() == Some(&updated_transaction.asset_id) {
         txn.rollback().await.ok(); // Rollback on error
         return Err(RepositoryError::OperationFailed("Source and destination asset cannot be the same after update.".to_string()));
     }
 
-    // 4. Update balances
-    log::debug!("Updating balances...");
-    // 4a. Revert old amount from old source asset
-    let old_source_sheet = balance_repo.get_current_sheet_by_asset_id(user_id, old_source_asset_id_uuid).await?
-        .ok_or_else(|| RepositoryError::NotFound(format!("Current sheet for old source asset {} not found", old_source_asset_id_uuid)))?;
-    let bal_after_reverting_old_source = old_source_sheet.balance + Decimal::from_f64(old_amount).ok_or_else(|| RepositoryError::OperationFailed("Failed to convert old_amount to Decimal".to_string()))?;
-    balance_repo.update_current_sheet(user_id, old_source_asset_id_uuid, Some(bal_after_reverting_old_source.to_f64().unwrap())).await?;
-
-    // 4b. Revert old amount from old destination asset
-    let old_dest_sheet = balance_repo.get_current_sheet_by_asset_id(user_id, old_dest_asset_id_uuid).await?
-        .ok_or_else(|| RepositoryError::NotFound(format!("Current sheet for old destination asset {} not found", old_dest_asset_id_uuid)))?;
-    let bal_after_reverting_old_dest = old_dest_sheet.balance - Decimal::from_f64(old_amount).ok_or_else(|| RepositoryError::OperationFailed("Failed to convert old_amount to Decimal".to_string()))?;
-    balance_repo.update_current_sheet(user_id, old_dest_asset_id_uuid, Some(bal_after_reverting_old_dest.to_f64().unwrap())).await?;
-
-    // 4c. Apply new amount to new source asset
-    let new_source_sheet = balance_repo.get_current_sheet_by_asset_id(user_id, new_source_asset_id_uuid).await?
-        .ok_or_else(|| RepositoryError::NotFound(format!("Current sheet for new source asset {} not found", new_source_asset_id_uuid)))?;
-    let final_new_source_balance = new_source_sheet.balance - Decimal::from_f64(new_amount).ok_or_else(|| RepositoryError::OperationFailed("Failed to convert new_amount to Decimal".to_string()))?;
-    balance_repo.update_current_sheet(user_id, new_source_asset_id_uuid, Some(final_new_source_balance.to_f64().unwrap())).await?;
-
-    // 4d. Apply new amount to new destination asset
-    let new_dest_sheet = balance_repo.get_current_sheet_by_asset_id(user_id, new_dest_asset_id_uuid).await?
-        .ok_or_else(|| RepositoryError::NotFound(format!("Current sheet for new destination asset {} not found", new_dest_asset_id_uuid)))?;
-    let final_new_dest_balance = new_dest_sheet.balance + Decimal::from_f64(new_amount).ok_or_else(|| RepositoryError::OperationFailed("Failed to convert new_amount to Decimal".to_string()))?;
-    balance_repo.update_current_sheet(user_id, new_dest_asset_id_uuid, Some(final_new_dest_balance.to_f64().unwrap())).await?;
+    // 4. Back out the old posting, then post the updated one
+    log::debug!("Reposting journal entry for updated transfer...");
+    for journal_entry in [old_journal_entry.reversal(), new_journal_entry] {
+        if let Err(err) = ledger_repo.post_entry(&txn, user_id, journal_entry).await {
+            log::error!("Failed to post transfer journal entry: {}", err);
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
+    }
+
+    // 5. Replace the tags when they are given
+    if let Some(tag_ids) = transfer_dto.tag_ids {
+        let tag_repo = TagRepositoryImpl::new(Arc::clone(&self.db_pool));
+        if let Err(err) = tag_repo.set_transaction_tags(&txn, user_id, transaction_id, tag_ids).await {
+            log::error!("Failed to replace transfer tags: {}", err);
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
+    }
 
     // Commit the transaction
     log::debug!("Committing transaction...");
@@ -430,49 +398,55 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
     ) 
         -> Result<(), RepositoryError> 
     {
-        let balance_repo = BalanceRepositoryImpl { db_pool: Arc::clone(&self.db_pool) };
+        // Start a transaction
+        let txn = self.db_pool.begin().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to start transaction: {}", err))
+        })?;
 
+        let ledger_repo = LedgerRepositoryImpl::new(Arc::clone(&self.db_pool));
 
-        // 1. Fetch the transaction to be deleted
-        let transaction_to_delete = transaction::Entity::find_by_id(transaction_id.as_bytes().to_vec())
-            .filter(transaction::Column::Id.eq(transaction_id.as_bytes().to_vec())) // Filter by transaction ID
-            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
-            .one(self.db_pool.as_ref())
+
+        // 1. Fetch and lock the transaction to be deleted, so a concurrent update cannot reverse it as well
+        let transaction_to_delete = find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Transfer)
+            .lock_exclusive()
+            .one(&txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
             .ok_or_else(|| RepositoryError::NotFound(format!("Transaction {} not found for user {}", transaction_id, user_id)))?;
 
-        let amount = transaction_to_delete.amount;
-        let source_asset_id_uuid = Uuid::from_slice(&transaction_to_delete.asset_id)
-            .map_err(|e| RepositoryError::OperationFailed(format!("Invalid source asset UUID in transaction to delete: {}", e)))?;
-        let dest_asset_id_uuid = Uuid::from_slice(transaction_to_delete.destination_asset_id.as_ref()
-            .ok_or_else(|| RepositoryError::OperationFailed("Destination asset ID is missing in transaction to delete".to_string()))?)
-            .map_err(|e| RepositoryError::OperationFailed(format!("Invalid destination asset UUID in transaction to delete: {}", e)))?;
+        // 2. Detach the tags, the links would block the delete
+        let tag_repo = TagRepositoryImpl::new(Arc::clone(&self.db_pool));
+        if let Err(err) = tag_repo.delete_transaction_tags(&txn, transaction_id).await {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
 
-        // 2. Delete the transaction record
+        // 3. Delete the transaction record
         let delete_result = transaction::Entity::delete_by_id(transaction_id.as_bytes().to_vec())
             .filter(transaction::Column::Id.eq(transaction_id.as_bytes().to_vec())) // Filter by transaction ID
             .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
-            .exec(self.db_pool.as_ref())
+            .exec(&txn)
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
 
         if delete_result.rows_affected == 0 {
+            txn.rollback().await.ok(); // Rollback on error
             return Err(RepositoryError::NotFound(format!("Transaction {} not found for user {} during delete operation", transaction_id, user_id)));
         }
 
-        // 3. Update balances
-        // Add amount back to source asset
-        let source_sheet = balance_repo.get_current_sheet_by_asset_id(user_id, source_asset_id_uuid).await?
-            .ok_or_else(|| RepositoryError::NotFound(format!("Current sheet for source asset {} not found", source_asset_id_uuid)))?;
-        let new_source_balance = source_sheet.balance + Decimal::from_f64(amount).ok_or_else(|| RepositoryError::OperationFailed("Failed to convert amount to Decimal".to_string()))?;
-        balance_repo.update_current_sheet(user_id, source_asset_id_uuid, new_source_balance.to_f64()).await?;
+        // 4. Reverse the posting, the row is gone so the entry only keeps the id in its memo
+        let mut journal_entry = ReqPostJournalEntryDto::for_transfer(&transaction_to_delete)?.reversal();
+        journal_entry.transaction_id = None;
+        if let Err(err) = ledger_repo.post_entry(&txn, user_id, journal_entry).await {
+            log::error!("Failed to post transfer reversal entry: {}", err);
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(err);
+        }
 
-        // Subtract amount from destination asset
-        let dest_sheet = balance_repo.get_current_sheet_by_asset_id(user_id, dest_asset_id_uuid).await?
-            .ok_or_else(|| RepositoryError::NotFound(format!("Current sheet for destination asset {} not found", dest_asset_id_uuid)))?;
-        let new_dest_balance = dest_sheet.balance - Decimal::from_f64(amount).ok_or_else(|| RepositoryError::OperationFailed("Failed to convert amount to Decimal".to_string()))?;
-        balance_repo.update_current_sheet(user_id, dest_asset_id_uuid, new_dest_balance.to_f64()).await?;
+        // Commit the transaction
+        txn.commit().await.map_err(|err| {
+            RepositoryError::DatabaseError(format!("Failed to commit transaction: {}", err))
+        })?;
 
         Ok(())
     }
@@ -485,14 +459,8 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
     ) 
         -> Result<Option<transaction::Model>, RepositoryError> 
     {
-        let transaction_id = Uuid::from_str(&transaction_id.to_string())
-            .map_err(|_| RepositoryError::OperationFailed("Invalid transaction ID".to_string()))?;
-        let transction_id_binary = transaction_id.as_bytes().to_vec();
-        // Check if the transaction ID is valid
         // Query the database to find the transaction by ID and ensure it belongs to the user
-        let transaction = transaction::Entity::find()
-            .filter(transaction::Column::Id.eq(transction_id_binary)) // Filter by transaction ID
-            .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Ensure it belongs to the user
+        let transaction = find_owned_of_type(user_id, transaction_id, TransactionTypeVariant::Transfer)
             .one(self.db_pool.as_ref())
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
@@ -532,6 +500,8 @@ impl TransferRepositoryUtility for TransferRepositoryImpl {
         let transactions = transaction::Entity::find()
             .filter(transaction::Column::UserId.eq(user_id.as_bytes().to_vec())) // Filter by user ID
             .filter(transaction::Column::TransactionTypeId.eq(transfer_uuid.as_bytes().to_vec())) // Filter by transaction type
+            .order_by_desc(transaction::Column::OccurredAt) // Newest business date first
+            .order_by_desc(transaction::Column::Id)
             .all(self.db_pool.as_ref())
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
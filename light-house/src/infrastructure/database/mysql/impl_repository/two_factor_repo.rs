@@ -0,0 +1,196 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ActiveValue::Set, ColumnTrait, Condition, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{domain::{entities::{two_factor_recovery_code, user_two_factor}, req_repository::two_factor_repository::TwoFactorRepository}, soc::soc_repository::RepositoryError};
+
+
+
+
+pub struct TwoFactorRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+#[async_trait::async_trait]
+impl TwoFactorRepository for TwoFactorRepositoryImpl {
+    async fn find_two_factor_by_user_id(&self, user_id: Uuid)
+        -> Result<Option<user_two_factor::Model>, RepositoryError>
+    {
+        let two_factor = user_two_factor::Entity::find()
+            .filter(user_two_factor::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(two_factor)
+    }
+
+
+    async fn save_pending_two_factor(&self, user_id: Uuid, secret: &str, now: DateTime<Utc>)
+        -> Result<user_two_factor::Model, RepositoryError>
+    {
+        // Step 1: A confirmed secret is only removed by turning two factor off
+        let existing = self.find_two_factor_by_user_id(user_id).await?;
+        if existing.as_ref().is_some_and(|two_factor| two_factor.confirmed_at.is_some()) {
+            return Err(RepositoryError::InvalidInput("Two factor authentication is already enabled".to_string()));
+        }
+
+        // Step 2: Replace the pending secret or start a new enrolment
+        let saved = match existing {
+            Some(pending) => {
+                let mut active_model: user_two_factor::ActiveModel = pending.into();
+                active_model.secret = Set(secret.to_string());
+                active_model.last_used_step = Set(None);
+                active_model.updated_at = Set(Some(now));
+                active_model.update(self.db_pool.as_ref()).await
+            }
+            None => {
+                user_two_factor::ActiveModel {
+                    id: Set(Uuid::new_v4().as_bytes().to_vec()),
+                    secret: Set(secret.to_string()),
+                    confirmed_at: Set(None),
+                    last_used_step: Set(None),
+                    user_id: Set(user_id.as_bytes().to_vec()),
+                    ..Default::default()
+                }
+                .insert(self.db_pool.as_ref())
+                .await
+            }
+        }
+        .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(saved)
+    }
+
+
+    async fn confirm_two_factor(&self, user_id: Uuid, step: i64, now: DateTime<Utc>)
+        -> Result<bool, RepositoryError>
+    {
+        let update_result = user_two_factor::Entity::update_many()
+            .col_expr(user_two_factor::Column::ConfirmedAt, Expr::value(now))
+            .col_expr(user_two_factor::Column::LastUsedStep, Expr::value(step))
+            .col_expr(user_two_factor::Column::UpdatedAt, Expr::value(now))
+            .filter(user_two_factor::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(user_two_factor::Column::ConfirmedAt.is_null())
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected > 0)
+    }
+
+
+    async fn use_two_factor_step(&self, user_id: Uuid, step: i64, now: DateTime<Utc>)
+        -> Result<bool, RepositoryError>
+    {
+        // one conditional update, the same code presented twice at once is only accepted once
+        let update_result = user_two_factor::Entity::update_many()
+            .col_expr(user_two_factor::Column::LastUsedStep, Expr::value(step))
+            .col_expr(user_two_factor::Column::UpdatedAt, Expr::value(now))
+            .filter(user_two_factor::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(user_two_factor::Column::ConfirmedAt.is_not_null())
+            .filter(
+                Condition::any()
+                    .add(user_two_factor::Column::LastUsedStep.is_null())
+                    .add(user_two_factor::Column::LastUsedStep.lt(step)),
+            )
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected > 0)
+    }
+
+
+    async fn replace_recovery_codes(&self, user_id: Uuid, code_hashes: Vec<String>)
+        -> Result<(), RepositoryError>
+    {
+        let txn = self.db_pool.begin().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        // Step 1: The previous codes stop working
+        if let Err(err) = two_factor_recovery_code::Entity::delete_many()
+            .filter(two_factor_recovery_code::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .exec(&txn)
+            .await
+        {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(RepositoryError::DatabaseError(err.to_string()));
+        }
+
+        // Step 2: Store the new ones
+        if !code_hashes.is_empty() {
+            let new_codes = code_hashes.into_iter().map(|code_hash| two_factor_recovery_code::ActiveModel {
+                id: Set(Uuid::new_v4().as_bytes().to_vec()),
+                code_hash: Set(code_hash),
+                used_at: Set(None),
+                user_id: Set(user_id.as_bytes().to_vec()),
+                ..Default::default()
+            });
+            if let Err(err) = two_factor_recovery_code::Entity::insert_many(new_codes).exec(&txn).await {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        }
+
+        txn.commit().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        Ok(())
+    }
+
+
+    async fn use_recovery_code(&self, user_id: Uuid, code_hash: &str, now: DateTime<Utc>)
+        -> Result<bool, RepositoryError>
+    {
+        let update_result = two_factor_recovery_code::Entity::update_many()
+            .col_expr(two_factor_recovery_code::Column::UsedAt, Expr::value(now))
+            .filter(two_factor_recovery_code::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(two_factor_recovery_code::Column::CodeHash.eq(code_hash))
+            .filter(two_factor_recovery_code::Column::UsedAt.is_null())
+            .exec(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok(update_result.rows_affected > 0)
+    }
+
+
+    async fn count_unused_recovery_codes(&self, user_id: Uuid)
+        -> Result<u64, RepositoryError>
+    {
+        two_factor_recovery_code::Entity::find()
+            .filter(two_factor_recovery_code::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .filter(two_factor_recovery_code::Column::UsedAt.is_null())
+            .count(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))
+    }
+
+
+    async fn delete_two_factor(&self, user_id: Uuid)
+        -> Result<(), RepositoryError>
+    {
+        let txn = self.db_pool.begin().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        if let Err(err) = two_factor_recovery_code::Entity::delete_many()
+            .filter(two_factor_recovery_code::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .exec(&txn)
+            .await
+        {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(RepositoryError::DatabaseError(err.to_string()));
+        }
+        if let Err(err) = user_two_factor::Entity::delete_many()
+            .filter(user_two_factor::Column::UserId.eq(user_id.as_bytes().to_vec()))
+            .exec(&txn)
+            .await
+        {
+            txn.rollback().await.ok(); // Rollback on error
+            return Err(RepositoryError::DatabaseError(err.to_string()));
+        }
+
+        txn.commit().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,357 @@
+use std::sync::Arc;
+
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, PaginatorTrait, QueryFilter, QuerySelect, TransactionTrait};
+use uuid::Uuid;
+
+use crate::{domain::{dto::user_data_dto::UserDataDto, entities::{api_token, asset, asset_type, attachment, budget, budget_period, contact, contact_type, current_sheet, expense, expense_type, gender, import_profile, journal_entry, journal_line, mcp_pending_operation, payment_split, recurring_occurrence, recurring_transaction, sign_in_attempt, tag, transaction, transaction_tag, transaction_type, two_factor_recovery_code, user, user_action_token, user_contact, user_role, user_session, user_two_factor}, req_repository::user_data_repository::UserDataRepository}, soc::soc_repository::RepositoryError};
+
+
+
+
+pub struct UserDataRepositoryImpl {
+    pub db_pool: Arc<DatabaseConnection>,
+}
+
+
+// every foreign key to user is Restrict, so the rows go children first.
+// Returns the storage keys of the removed attachments, their content is not in the database
+async fn delete_owned_rows(txn: &DatabaseTransaction, user: &user::Model) -> Result<Vec<String>, DbErr> {
+    let user_id = user.id.clone();
+
+    // Step 0: Attachment rows, nothing points at them
+    let storage_keys: Vec<String> = attachment::Entity::find()
+        .select_only()
+        .column(attachment::Column::StorageKey)
+        .filter(attachment::Column::UserId.eq(user_id.clone()))
+        .into_tuple()
+        .all(txn)
+        .await?;
+    attachment::Entity::delete_many()
+        .filter(attachment::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+
+    // Step 1: Recurring transactions with their occurrences, these point at transactions
+    let recurring_ids: Vec<Vec<u8>> = recurring_transaction::Entity::find()
+        .select_only()
+        .column(recurring_transaction::Column::Id)
+        .filter(recurring_transaction::Column::UserId.eq(user_id.clone()))
+        .into_tuple()
+        .all(txn)
+        .await?;
+    recurring_occurrence::Entity::delete_many()
+        .filter(recurring_occurrence::Column::RecurringTransactionId.is_in(recurring_ids))
+        .exec(txn)
+        .await?;
+    recurring_transaction::Entity::delete_many()
+        .filter(recurring_transaction::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+
+    // Step 2: The ledger, the balances, the tag links and the split lines, then the transactions they point at
+    let journal_entry_ids: Vec<Vec<u8>> = journal_entry::Entity::find()
+        .select_only()
+        .column(journal_entry::Column::Id)
+        .filter(journal_entry::Column::UserId.eq(user_id.clone()))
+        .into_tuple()
+        .all(txn)
+        .await?;
+    journal_line::Entity::delete_many()
+        .filter(journal_line::Column::JournalEntryId.is_in(journal_entry_ids))
+        .exec(txn)
+        .await?;
+    journal_entry::Entity::delete_many()
+        .filter(journal_entry::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    current_sheet::Entity::delete_many()
+        .filter(current_sheet::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    let tag_ids: Vec<Vec<u8>> = tag::Entity::find()
+        .select_only()
+        .column(tag::Column::Id)
+        .filter(tag::Column::UserId.eq(user_id.clone()))
+        .into_tuple()
+        .all(txn)
+        .await?;
+    transaction_tag::Entity::delete_many()
+        .filter(transaction_tag::Column::TagId.is_in(tag_ids))
+        .exec(txn)
+        .await?;
+    let transaction_ids: Vec<Vec<u8>> = transaction::Entity::find()
+        .select_only()
+        .column(transaction::Column::Id)
+        .filter(transaction::Column::UserId.eq(user_id.clone()))
+        .into_tuple()
+        .all(txn)
+        .await?;
+    payment_split::Entity::delete_many()
+        .filter(payment_split::Column::TransactionId.is_in(transaction_ids))
+        .exec(txn)
+        .await?;
+    transaction::Entity::delete_many()
+        .filter(transaction::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+
+    // Step 3: Budgets with their periods
+    let budget_ids: Vec<Vec<u8>> = budget::Entity::find()
+        .select_only()
+        .column(budget::Column::Id)
+        .filter(budget::Column::UserId.eq(user_id.clone()))
+        .into_tuple()
+        .all(txn)
+        .await?;
+    budget_period::Entity::delete_many()
+        .filter(budget_period::Column::BudgetId.is_in(budget_ids))
+        .exec(txn)
+        .await?;
+    budget::Entity::delete_many()
+        .filter(budget::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+
+    // Step 4: Sign in and access records
+    mcp_pending_operation::Entity::delete_many()
+        .filter(mcp_pending_operation::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    api_token::Entity::delete_many()
+        .filter(api_token::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    user_session::Entity::delete_many()
+        .filter(user_session::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    user_action_token::Entity::delete_many()
+        .filter(user_action_token::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    two_factor_recovery_code::Entity::delete_many()
+        .filter(two_factor_recovery_code::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    user_two_factor::Entity::delete_many()
+        .filter(user_two_factor::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    // attempts made with the email before it had an account go as well
+    sign_in_attempt::Entity::delete_many()
+        .filter(
+            Condition::any()
+                .add(sign_in_attempt::Column::UserId.eq(user_id.clone()))
+                .add(sign_in_attempt::Column::Email.eq(user.email.clone())),
+        )
+        .exec(txn)
+        .await?;
+
+    // Step 5: Contacts are owned through user_contact, the link goes before the contact
+    let contact_ids: Vec<Vec<u8>> = user_contact::Entity::find()
+        .select_only()
+        .column(user_contact::Column::ContactId)
+        .filter(user_contact::Column::UserId.eq(user_id.clone()))
+        .into_tuple()
+        .all(txn)
+        .await?;
+    user_contact::Entity::delete_many()
+        .filter(user_contact::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    contact::Entity::delete_many()
+        .filter(contact::Column::Id.is_in(contact_ids))
+        .exec(txn)
+        .await?;
+
+    // Step 6: The rest, each before the type it points at
+    asset::Entity::delete_many()
+        .filter(asset::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    expense::Entity::delete_many()
+        .filter(expense::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    contact_type::Entity::delete_many()
+        .filter(contact_type::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    expense_type::Entity::delete_many()
+        .filter(expense_type::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    asset_type::Entity::delete_many()
+        .filter(asset_type::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    tag::Entity::delete_many()
+        .filter(tag::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+    import_profile::Entity::delete_many()
+        .filter(import_profile::Column::UserId.eq(user_id.clone()))
+        .exec(txn)
+        .await?;
+
+    // Step 7: Finally the user
+    user::Entity::delete_by_id(user_id).exec(txn).await?;
+    Ok(storage_keys)
+}
+
+
+#[async_trait::async_trait]
+impl UserDataRepository for UserDataRepositoryImpl {
+    async fn export_user_data(&self, user_id: Uuid)
+        -> Result<UserDataDto, RepositoryError>
+    {
+        let db = self.db_pool.as_ref();
+        let owner = user_id.as_bytes().to_vec();
+        let to_repo_error = |err: DbErr| RepositoryError::DatabaseError(err.to_string());
+
+        // Step 1: The user and the names of their gender and role
+        let user = user::Entity::find_by_id(owner.clone())
+            .one(db)
+            .await
+            .map_err(to_repo_error)?
+            .ok_or_else(|| RepositoryError::NotFound(format!("User with ID {} not found", user_id)))?;
+        let gender = gender::Entity::find_by_id(user.gender_id.clone()).one(db).await.map_err(to_repo_error)?;
+        let user_role = user_role::Entity::find_by_id(user.user_role_id.clone()).one(db).await.map_err(to_repo_error)?;
+
+        // Step 2: The rows owned through user_id
+        let asset_types = asset_type::Entity::find().filter(asset_type::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let assets = asset::Entity::find().filter(asset::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let contact_types = contact_type::Entity::find().filter(contact_type::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let expense_types = expense_type::Entity::find().filter(expense_type::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let expenses = expense::Entity::find().filter(expense::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let transactions = transaction::Entity::find()
+            .filter(transaction::Column::UserId.eq(owner.clone()))
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+        let tags = tag::Entity::find().filter(tag::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let current_sheets = current_sheet::Entity::find().filter(current_sheet::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let journal_entries = journal_entry::Entity::find().filter(journal_entry::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let budgets = budget::Entity::find().filter(budget::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let recurring_transactions = recurring_transaction::Entity::find()
+            .filter(recurring_transaction::Column::UserId.eq(owner.clone()))
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+        let mcp_pending_operations = mcp_pending_operation::Entity::find()
+            .filter(mcp_pending_operation::Column::UserId.eq(owner.clone()))
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+        let api_tokens = api_token::Entity::find().filter(api_token::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let sessions = user_session::Entity::find().filter(user_session::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let sign_in_attempts = sign_in_attempt::Entity::find()
+            .filter(sign_in_attempt::Column::UserId.eq(owner.clone()))
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+        let attachments = attachment::Entity::find().filter(attachment::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let import_profiles = import_profile::Entity::find().filter(import_profile::Column::UserId.eq(owner.clone())).all(db).await.map_err(to_repo_error)?;
+        let two_factor = user_two_factor::Entity::find().filter(user_two_factor::Column::UserId.eq(owner.clone())).one(db).await.map_err(to_repo_error)?;
+        let recovery_codes_left = two_factor_recovery_code::Entity::find()
+            .filter(two_factor_recovery_code::Column::UserId.eq(owner.clone()))
+            .filter(two_factor_recovery_code::Column::UsedAt.is_null())
+            .count(db)
+            .await
+            .map_err(to_repo_error)?;
+
+        // Step 3: The rows owned through a parent row
+        let contact_ids: Vec<Vec<u8>> = user_contact::Entity::find()
+            .select_only()
+            .column(user_contact::Column::ContactId)
+            .filter(user_contact::Column::UserId.eq(owner.clone()))
+            .into_tuple()
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+        let contacts = contact::Entity::find().filter(contact::Column::Id.is_in(contact_ids)).all(db).await.map_err(to_repo_error)?;
+        let journal_lines = journal_line::Entity::find()
+            .filter(journal_line::Column::JournalEntryId.is_in(journal_entries.iter().map(|entry| entry.id.clone())))
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+        let budget_periods = budget_period::Entity::find()
+            .filter(budget_period::Column::BudgetId.is_in(budgets.iter().map(|budget| budget.id.clone())))
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+        let transaction_tags = transaction_tag::Entity::find()
+            .filter(transaction_tag::Column::TagId.is_in(tags.iter().map(|tag| tag.id.clone())))
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+        let payment_splits = payment_split::Entity::find()
+            .filter(payment_split::Column::TransactionId.is_in(transactions.iter().map(|transaction| transaction.id.clone())))
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+        let recurring_occurrences = recurring_occurrence::Entity::find()
+            .filter(recurring_occurrence::Column::RecurringTransactionId.is_in(recurring_transactions.iter().map(|recurring| recurring.id.clone())))
+            .all(db)
+            .await
+            .map_err(to_repo_error)?;
+
+        // Step 4: Shared lookup rows, so every transaction_type_id can be read
+        let transaction_types = transaction_type::Entity::find().all(db).await.map_err(to_repo_error)?;
+
+        Ok(UserDataDto {
+            user,
+            gender,
+            user_role,
+            transaction_types,
+            asset_types,
+            assets,
+            contact_types,
+            contacts,
+            expense_types,
+            expenses,
+            transactions,
+            tags,
+            transaction_tags,
+            payment_splits,
+            current_sheets,
+            journal_entries,
+            journal_lines,
+            budgets,
+            budget_periods,
+            recurring_transactions,
+            recurring_occurrences,
+            mcp_pending_operations,
+            api_tokens,
+            sessions,
+            sign_in_attempts,
+            attachments,
+            import_profiles,
+            two_factor,
+            recovery_codes_left,
+        })
+    }
+
+
+    async fn delete_user_data(&self, user_id: Uuid)
+        -> Result<(user::Model, Vec<String>), RepositoryError>
+    {
+        let user = user::Entity::find_by_id(user_id.as_bytes().to_vec())
+            .one(self.db_pool.as_ref())
+            .await
+            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?
+            .ok_or_else(|| RepositoryError::NotFound(format!("User with ID {} not found", user_id)))?;
+
+        let txn = self.db_pool.begin().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+        let storage_keys = match delete_owned_rows(&txn, &user).await {
+            Ok(storage_keys) => storage_keys,
+            Err(err) => {
+                txn.rollback().await.ok(); // Rollback on error
+                return Err(RepositoryError::DatabaseError(err.to_string()));
+            }
+        };
+        txn.commit().await.map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
+
+        Ok((user, storage_keys))
+    }
+}
@@ -1,11 +1,10 @@
 use std::sync::Arc;
 
 use bcrypt::{hash, DEFAULT_COST};
-use rand::{distr::Alphanumeric, Rng};
 use sea_orm::{ActiveModelTrait, ActiveValue::Set, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QuerySelect};
 use uuid::Uuid;
 
-use crate::{ domain::{dto::auth_dto::{ReqSignUpDto, ReqUpdateUserDto}, entities::{gender, user, user_role}, req_repository::user_repository::{McpRepositoryBase, UserRepositoryBase, UserRepositoryUtility}}, soc::soc_repository::RepositoryError};
+use crate::{ domain::{dto::auth_dto::{ReqSignUpDto, ReqUpdateUserDto}, entities::{gender, user, user_role}, req_repository::user_repository::{UserRepositoryBase, UserRepositoryUtility}}, soc::soc_repository::RepositoryError};
 
 
 
@@ -74,10 +73,6 @@ impl UserRepositoryBase for UserRepositoryImpl {
             }
         };
 
-        let mcp_token = Uuid::now_v7().to_string();
-        log::debug!("Generated UUID v7 mcp_token: {}", mcp_token);
-
-
         // Create the ActiveModel for the user
         let new_user = user::ActiveModel {
             id: Set(Uuid::new_v4().into()),
@@ -88,7 +83,6 @@ impl UserRepositoryBase for UserRepositoryImpl {
             last_name: Set(dto.last_name),
             gender_id: Set(gender_id.into()),
             user_role_id: Set(role_id.into()),
-            mcp_token: Set(mcp_token),
             ..Default::default()
         };
         log::debug!("New user ActiveModel created");
@@ -167,6 +161,10 @@ impl UserRepositoryBase for UserRepositoryImpl {
             active_model.username = Set(username);
         }
         if let Some(email) = should_update(&dto.email) {
+            // a new address has to be verified again
+            if *active_model.email.as_ref() != email {
+                active_model.email_verified_at = Set(None);
+            }
             active_model.email = Set(email);
         }
         if let Some(first_name) = should_update(&dto.first_name) {
@@ -238,9 +236,9 @@ impl UserRepositoryUtility for UserRepositoryImpl{
     }
     async fn find_by_email(&self, email: &str) -> Result<Option<user::Model>, RepositoryError>
     {
-        // Query the database to find the user by username
+        // Query the database to find the user by email
         let user = user::Entity::find()
-            .filter(user::Column::Username.eq(email)) // Filter by the `username` column
+            .filter(user::Column::Email.eq(email)) // Filter by the `email` column
             .one(self.db_pool.as_ref())
             .await
             .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
@@ -249,70 +247,3 @@ impl UserRepositoryUtility for UserRepositoryImpl{
         Ok(user)
     }
 }
-
-
-#[async_trait::async_trait]
-impl McpRepositoryBase for UserRepositoryImpl{
-    
-    async fn get_user_id_from_mcp_token(&self, mcp_token: &str) -> Result<user::Model, RepositoryError>
-    {   
-        // Query the database to find the user by MCP token
-        let user = user::Entity::find()
-            .filter(user::Column::McpToken.eq(mcp_token)) // Filter by the `mcp_token` column
-            .one(self.db_pool.as_ref())
-            .await
-            .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
-
-        // Return the user if found, or an error if not found
-        match user {
-            Some(user) => Ok(user),
-            None => Err(RepositoryError::NotFound(format!("User with MCP token '{}' not found", mcp_token))),
-        }
-    }
-
-
-    async fn regenerate_mcp_token(&self, user_id: Uuid) -> Result<(), RepositoryError>
-    {
-         // Generate a secure random token
-         let raw_token: String = rand::rng()
-         .sample_iter(&Alphanumeric)
-         .take(32) // Generate a 32-character token
-         .map(char::from)
-         .collect();
-
-     // Hash the token using bcrypt
-     let hashed_token = hash(&raw_token, DEFAULT_COST)
-         .map_err(|_| RepositoryError::InvalidInput("Failed to hash MCP token".to_string()))?;
-
-     // Find the user by ID
-     let user = user::Entity::find()
-         .filter(user::Column::Id.eq(user_id))
-         .one(self.db_pool.as_ref())
-         .await
-         .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
-
-     // Return an error if the user is not found
-     let user = match user {
-         Some(user) => user,
-         None => return Err(RepositoryError::NotFound(format!("User with ID {} not found", user_id))),
-     };
-
-     // Convert the found user into an ActiveModel for updating
-     let mut active_model: user::ActiveModel = user.into();
-
-     // Update the mcp_token field with the hashed token
-     active_model.mcp_token = Set(hashed_token);
-
-     // Save the updated user to the database
-     active_model
-         .update(self.db_pool.as_ref())
-         .await
-         .map_err(|err| RepositoryError::DatabaseError(err.to_string()))?;
-
-     // Optionally, return the raw token to the caller (if needed)
-     // For security reasons, you might want to avoid returning the raw token
-     Ok(())
-    }
-}
-
-
@@ -1,11 +1,17 @@
+use std::sync::Arc;
+
 use jsonwebtoken::{decode, DecodingKey, Validation};
 use rocket::{http::Status, request::{self, FromRequest, Outcome}, Request};
 use uuid::Uuid;
 
-use crate::{configuration::jwt_config::JwtSecret, domain::dto::auth_dto::Claims};
+use crate::{application::{usecase::{user_usecase::UserUseCase, wrapper::user_wrapper::UserRepositoryComposite}, usecase_req_impl::{admin_usecase::AdminUsecase, user_usecase::UserSessionUsecase}}, configuration::jwt_config::JwtSecret, domain::dto::auth_dto::Claims};
 
 pub struct AuthenticatedUser {
     pub id: Uuid,
+    // the session of the access token, signing out revokes it
+    pub session_id: Uuid,
+    // role at the time the token was issued
+    pub role: String,
     
 }
 
@@ -34,9 +40,48 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
                     }
                 };            
 
-                return Outcome::Success(AuthenticatedUser { id: claims.sub });
+                // a signed token is only good while its session is live
+                let user_usecase = match req.rocket().state::<Arc<UserUseCase<UserRepositoryComposite>>>() {
+                    Some(user_usecase) => user_usecase,
+                    None => return Outcome::Error((Status::InternalServerError, "User usecase not available".to_string())),
+                };
+                return match user_usecase.is_session_active(claims.sub, claims.sid).await {
+                    Ok(true) => Outcome::Success(AuthenticatedUser { id: claims.sub, session_id: claims.sid, role: claims.role }),
+                    Ok(false) => Outcome::Error((Status::Unauthorized, "Session revoked or expired".to_string())),
+                    Err(err) => Outcome::Error((Status::InternalServerError, err.to_string())),
+                };
             }
         }
         Outcome::Error((Status::Unauthorized, "Authorization header missing or malformed".to_string()))
     }
+}
+
+
+// an AuthenticatedUser holding the admin role right now, not only when the token was issued
+pub struct AuthenticatedAdmin {
+    pub id: Uuid,
+    pub session_id: Uuid,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthenticatedAdmin {
+    type Error = String;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let user = match req.guard::<AuthenticatedUser>().await {
+            Outcome::Success(user) => user,
+            Outcome::Error(err) => return Outcome::Error(err),
+            Outcome::Forward(status) => return Outcome::Forward(status),
+        };
+
+        let user_usecase = match req.rocket().state::<Arc<UserUseCase<UserRepositoryComposite>>>() {
+            Some(user_usecase) => user_usecase,
+            None => return Outcome::Error((Status::InternalServerError, "User usecase not available".to_string())),
+        };
+        match user_usecase.is_admin(user.id).await {
+            Ok(true) => Outcome::Success(AuthenticatedAdmin { id: user.id, session_id: user.session_id }),
+            Ok(false) => Outcome::Error((Status::Forbidden, "Admin role required".to_string())),
+            Err(err) => Outcome::Error((Status::InternalServerError, err.to_string())),
+        }
+    }
 }
\ No newline at end of file
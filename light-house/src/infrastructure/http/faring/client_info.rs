@@ -0,0 +1,32 @@
+use std::convert::Infallible;
+
+use rocket::{request::{self, FromRequest, Outcome}, Request};
+
+use crate::domain::dto::session_dto::SessionClientDto;
+
+// where the request came from, never fails so routes can record it when it is known
+pub struct ClientInfo {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for ClientInfo {
+    type Error = Infallible;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        Outcome::Success(ClientInfo {
+            ip_address: req.client_ip().map(|ip| ip.to_string()),
+            user_agent: req.headers().get_one("User-Agent").map(|user_agent| user_agent.to_string()),
+        })
+    }
+}
+
+impl From<ClientInfo> for SessionClientDto {
+    fn from(client: ClientInfo) -> Self {
+        SessionClientDto {
+            ip_address: client.ip_address,
+            user_agent: client.user_agent,
+        }
+    }
+}
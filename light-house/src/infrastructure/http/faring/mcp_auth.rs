@@ -1,10 +1,37 @@
-use rocket::{http::Status, outcome::Outcome, request::{self, FromRequest}, Request, State};
-use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter, ColumnTrait};
+use std::sync::Arc;
+
+use rocket::{http::Status, outcome::Outcome, request::{self, FromRequest}, Request};
 use uuid::Uuid;
-use crate::domain::entities::user;
+use crate::{application::usecase_req_impl::api_token_usecase::ApiTokenUsecase, domain::dto::{api_token_dto::ApiTokenIdentityDto, dto_enum::api_token_scope::ApiTokenScopeVariant}, infrastructure::http::response::otter_response::ErrorResponse, initiation::init_usecase_setup::ApiTokenUseCaseImpl, soc::soc_usecase::UsecaseError};
 
 pub struct McpAuthenticateUser {
     pub user_id: Uuid,
+    pub token_id: Uuid,
+    pub scopes: Vec<ApiTokenScopeVariant>,
+}
+
+impl McpAuthenticateUser {
+    // each MCP route names the scope it needs, the guard only proves who is calling
+    pub fn require_scope(&self, scope: ApiTokenScopeVariant) -> Result<(), ErrorResponse> {
+        if self.scopes.contains(&scope) {
+            Ok(())
+        } else {
+            Err(ErrorResponse(
+                Status::Forbidden,
+                format!("The MCP token is missing the '{}' scope", scope.as_str()),
+            ))
+        }
+    }
+}
+
+impl From<ApiTokenIdentityDto> for McpAuthenticateUser {
+    fn from(identity: ApiTokenIdentityDto) -> Self {
+        McpAuthenticateUser {
+            user_id: identity.user_id,
+            token_id: identity.token_id,
+            scopes: identity.scopes,
+        }
+    }
 }
 
 #[rocket::async_trait]
@@ -12,13 +39,13 @@ impl<'r> FromRequest<'r> for McpAuthenticateUser {
     type Error = String;
 
     async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
-        // Step 1: Extract the database connection from Rocket's State
-        let db_pool = match req.rocket().state::<DatabaseConnection>() {
-            Some(db) => db,
+        // Step 1: Extract the api token usecase from Rocket's State
+        let api_token_usecase = match req.rocket().state::<Arc<ApiTokenUseCaseImpl>>() {
+            Some(usecase) => usecase,
             None => {
                 return Outcome::Error((
                     Status::InternalServerError,
-                    "Database connection not available".to_string(),
+                    "API token usecase not available".to_string(),
                 ));
             }
         };
@@ -26,31 +53,19 @@ impl<'r> FromRequest<'r> for McpAuthenticateUser {
         // Step 2: Extract the MCP token from the header
         if let Some(auth_header) = req.headers().get_one("Mcp-Authorization") {
             if let Some(token) = auth_header.strip_prefix("MCP ") {
-                // Step 3: Query the user table to find the user with the given MCP token
-                match user::Entity::find()
-                    .filter(user::Column::McpToken.eq(token.to_string()))
-                    .one(db_pool)
-                    .await
-                {
-                    Ok(Some(user)) => {
-                        // Step 4: Return the user ID as McpAuthenticateUser
-                        match Uuid::from_slice(&user.id) {
-                            Ok(user_id) => Outcome::Success(McpAuthenticateUser { user_id }),
-                            Err(_) => Outcome::Error((
-                                Status::InternalServerError,
-                                "Failed to parse user ID".to_string(),
-                            )),
-                        }
-                    }
-                    Ok(None) => Outcome::Error((
+                // Step 3: Resolve the token, unknown, expired and revoked tokens get the same answer
+                let ip = req.client_ip().map(|ip| ip.to_string());
+                match api_token_usecase.authenticate_api_token(token, ip).await {
+                    Ok(identity) => Outcome::Success(McpAuthenticateUser::from(identity)),
+                    Err(UsecaseError::ResourceNotFound(_)) => Outcome::Error((
                         Status::Unauthorized,
                         "Invalid MCP token".to_string(),
                     )),
                     Err(err) => {
-                        eprintln!("Database error: {}", err);
+                        eprintln!("MCP authentication error: {}", err);
                         Outcome::Error((
                             Status::InternalServerError,
-                            "Database query failed".to_string(),
+                            "MCP authentication failed".to_string(),
                         ))
                     }
                 }
@@ -67,4 +82,4 @@ impl<'r> FromRequest<'r> for McpAuthenticateUser {
             ))
         }
     }
-}
\ No newline at end of file
+}
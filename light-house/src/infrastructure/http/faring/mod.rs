@@ -1,3 +1,4 @@
 pub mod cors;
 pub mod authentication;
-pub mod mcp_auth;
\ No newline at end of file
+pub mod mcp_auth;
+pub mod client_info;
\ No newline at end of file
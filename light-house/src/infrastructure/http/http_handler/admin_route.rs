@@ -0,0 +1,285 @@
+use std::sync::Arc;
+
+use rocket::{delete, get, http::Status, post, put, routes, serde::json::Json, Route, State};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{application::{usecase::{user_usecase::UserUseCase, wrapper::user_wrapper::UserRepositoryComposite}, usecase_req_impl::admin_usecase::AdminUsecase}, domain::dto::admin_dto::{ReqAdminUserQueryDto, ReqAssignRoleDto, ResAdminUserDto, ResListAdminUserDto, ResListRoleDto, ResSystemStatsDto}, infrastructure::http::{faring::authentication::AuthenticatedAdmin, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+pub fn admin_routes() -> Vec<Route> {
+    routes![
+        view_all_users,
+        view_all_roles,
+        assign_role,
+        revoke_role,
+        disable_user,
+        enable_user,
+        view_system_stats
+    ]
+}
+
+
+fn admin_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        UsecaseError::PermissionDenied(msg) => ErrorResponse(Status::Forbidden, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/admin/user",
+    summary = "Get all users",
+    description = "List every account with its role, oldest first. Filters are combined with AND",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ReqAdminUserQueryDto
+    ),
+    responses(
+        (status = 200, description = "Users found", body = ResListAdminUserDto),
+        (status = 400, description = "Invalid filter or pagination parameter", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Admin"]
+)]
+#[get("/user?<query..>")]
+pub async fn view_all_users(
+    _admin: AuthenticatedAdmin,
+    query: ReqAdminUserQueryDto,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResListAdminUserDto> {
+    let search = match query.into_search() {
+        Ok(search) => search,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+
+    match user_usecase.get_all_user(search).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(admin_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/admin/role",
+    summary = "Get all roles",
+    description = "The roles that can be assigned to a user",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Roles found", body = ResListRoleDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Admin"]
+)]
+#[get("/role")]
+pub async fn view_all_roles(
+    _admin: AuthenticatedAdmin,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResListRoleDto> {
+    match user_usecase.get_all_role().await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(admin_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    put,
+    path = "/admin/user/{user_id}/role",
+    summary = "Assign a role",
+    description = "Give the user a role. It is checked on every admin request, the role inside the user's token follows at their next token refresh",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("user_id" = String, description = "The ID of the user"),
+    ),
+    request_body = ReqAssignRoleDto,
+    responses(
+        (status = 200, description = "Role assigned", body = ResAdminUserDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User or role not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Admin"]
+)]
+#[put("/user/<user_id>/role", data = "<dto>")]
+pub async fn assign_role(
+    admin: AuthenticatedAdmin,
+    user_id: Uuid,
+    dto: Json<ReqAssignRoleDto>,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResAdminUserDto> {
+    // field empty Bad request
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+    match user_usecase.assign_role(admin.id, user_id, dto.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(admin_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    delete,
+    path = "/admin/user/{user_id}/role/{role}",
+    summary = "Revoke a role",
+    description = "Take the role away, the user falls back to the default 'user' role",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("user_id" = String, description = "The ID of the user"),
+        ("role" = String, description = "The role to revoke"),
+    ),
+    responses(
+        (status = 200, description = "Role revoked", body = ResAdminUserDto),
+        (status = 400, description = "The role can not be revoked", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found or does not hold the role", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Admin"]
+)]
+#[delete("/user/<user_id>/role/<role>")]
+pub async fn revoke_role(
+    admin: AuthenticatedAdmin,
+    user_id: Uuid,
+    role: String,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResAdminUserDto> {
+    match user_usecase.revoke_role(admin.id, user_id, role).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(admin_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/admin/user/{user_id}/disable",
+    summary = "Disable an account",
+    description = "The user is signed out everywhere and can not sign in or use their API tokens until the account is enabled again. Their data is kept",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("user_id" = String, description = "The ID of the user"),
+    ),
+    responses(
+        (status = 200, description = "Account disabled", body = ResAdminUserDto),
+        (status = 400, description = "An admin can not disable their own account", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Admin"]
+)]
+#[post("/user/<user_id>/disable")]
+pub async fn disable_user(
+    admin: AuthenticatedAdmin,
+    user_id: Uuid,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResAdminUserDto> {
+    match user_usecase.disable_user(admin.id, user_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(admin_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/admin/user/{user_id}/enable",
+    summary = "Enable an account",
+    description = "Let a disabled user sign in again",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("user_id" = String, description = "The ID of the user"),
+    ),
+    responses(
+        (status = 200, description = "Account enabled", body = ResAdminUserDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 404, description = "User not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Admin"]
+)]
+#[post("/user/<user_id>/enable")]
+pub async fn enable_user(
+    admin: AuthenticatedAdmin,
+    user_id: Uuid,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResAdminUserDto> {
+    match user_usecase.enable_user(admin.id, user_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(admin_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/admin/stats",
+    summary = "Get system statistics",
+    description = "Counts across every user: accounts, live sessions and API tokens, records and pending MCP operations",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Statistics found", body = ResSystemStatsDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Admin role required", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Admin"]
+)]
+#[get("/stats")]
+pub async fn view_system_stats(
+    _admin: AuthenticatedAdmin,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResSystemStatsDto> {
+    match user_usecase.get_system_stats().await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(admin_error_response(err)),
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use rocket::{delete, get, http::Status, post, routes, serde::json::Json, Route, State};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{application::usecase_req_impl::api_token_usecase::ApiTokenUsecase, domain::dto::api_token_dto::{ReqCreateApiTokenDto, ResApiTokenDto, ResCreatedApiTokenDto, ResListApiTokenDto}, infrastructure::http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}, initiation::init_usecase_setup::ApiTokenUseCaseImpl, soc::soc_usecase::UsecaseError};
+
+
+
+
+pub fn api_token_routes() -> Vec<Route> {
+    routes![
+        create_api_token,
+        view_all_api_tokens,
+        revoke_api_token
+    ]
+}
+
+
+fn api_token_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/api-token",
+    summary = "Create an API token",
+    description = "Create a named token for an MCP client, limited to the given scopes. The token is only returned by this call, only its hash is stored",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqCreateApiTokenDto,
+    responses(
+        (status = 201, description = "API token created successfully", body = ResCreatedApiTokenDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["API Token"]
+)]
+#[post("/", data = "<dto>")]
+pub async fn create_api_token(
+    user: AuthenticatedUser,
+    dto: Json<ReqCreateApiTokenDto>,
+    api_token_usecase: &State<Arc<ApiTokenUseCaseImpl>>,
+) -> OtterResponse<ResCreatedApiTokenDto> {
+    // field empty Bad request
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+    match api_token_usecase.create_api_token(user.id, dto.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Created, res)),
+        Err(err) => Err(api_token_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/api-token",
+    summary = "Get all API tokens",
+    description = "Get the user's API tokens, newest first, with their scopes and when and from where they were last used. Revoked tokens are kept in the list",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "API tokens found", body = ResListApiTokenDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["API Token"]
+)]
+#[get("/")]
+pub async fn view_all_api_tokens(
+    user: AuthenticatedUser,
+    api_token_usecase: &State<Arc<ApiTokenUseCaseImpl>>,
+) -> OtterResponse<ResListApiTokenDto> {
+    match api_token_usecase.get_all_api_token(user.id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(api_token_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    delete,
+    path = "/api-token/{token_id}",
+    summary = "Revoke an API token",
+    description = "Revoke the token, MCP requests made with it are refused from now on",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("token_id" = String, description = "The ID of the API token"),
+    ),
+    responses(
+        (status = 200, description = "API token revoked", body = ResApiTokenDto),
+        (status = 404, description = "API token not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["API Token"]
+)]
+#[delete("/<token_id>")]
+pub async fn revoke_api_token(
+    user: AuthenticatedUser,
+    token_id: Uuid,
+    api_token_usecase: &State<Arc<ApiTokenUseCaseImpl>>,
+) -> OtterResponse<ResApiTokenDto> {
+    match api_token_usecase.revoke_api_token(user.id, token_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(api_token_error_response(err)),
+    }
+}
@@ -0,0 +1,349 @@
+use std::{io::Cursor, sync::Arc};
+
+use rocket::{delete, form::Form, fs::TempFile, get, http::{ContentType, Header, Status}, post, response::{self, Responder}, routes, tokio::io::AsyncReadExt, FromForm, Request, Response, Route, State};
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::attachment_usecase::AttachmentUsecase, domain::dto::{attachment_dto::{AttachmentContentDto, AttachmentUploadDto, ReqUploadAttachmentDto, ResAttachmentUsageDto, ResEntryAttachmentDto, ResListAttachmentDto}, dto_enum::attachment_owner::AttachmentOwnerVariant}, infrastructure::http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}, initiation::init_usecase_setup::AttachmentUseCaseImpl, soc::soc_usecase::UsecaseError};
+
+
+
+
+
+
+pub fn attachment_routes() -> Vec<Route> {
+    routes![
+        upload_attachment,
+        view_attachments_by_owner,
+        view_attachment_usage,
+        view_attachment_by_id,
+        download_attachment,
+        delete_attachment_by_id
+    ]
+}
+
+
+fn attachment_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::ValidationFailed(details) => ErrorResponse(
+            Status::BadRequest,
+            details.into_iter().map(|detail| detail.message).collect::<Vec<_>>().join(", "),
+        ),
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        UsecaseError::Conflict(msg) => ErrorResponse(Status::Conflict, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+fn parse_owner_type(owner_type: &str) -> Result<AttachmentOwnerVariant, ErrorResponse> {
+    owner_type
+        .parse::<AttachmentOwnerVariant>()
+        .map_err(|msg| ErrorResponse(Status::BadRequest, msg))
+}
+
+
+// the client's file name without any directory part or control characters
+fn sanitize_file_name(raw_name: &str) -> String {
+    let name: String = raw_name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(255)
+        .collect();
+
+    match name.trim() {
+        "" | "." | ".." => "attachment".to_string(),
+        name => name.to_string(),
+    }
+}
+
+
+#[derive(FromForm)]
+pub struct AttachmentUploadForm<'r> {
+    file: TempFile<'r>,
+}
+
+
+// the stored content as a download, never rendered by the browser
+pub struct AttachmentDownload(AttachmentContentDto);
+
+impl<'r> Responder<'r, 'static> for AttachmentDownload {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let AttachmentDownload(file) = self;
+        let content_type = ContentType::parse_flexible(&file.content_type).unwrap_or(ContentType::Binary);
+        let ascii_name: String = file.file_name
+            .chars()
+            .map(|c| if c.is_ascii_graphic() || c == ' ' { c } else { '_' })
+            .collect();
+        let encoded_name: String = file.file_name
+            .bytes()
+            .map(|byte| match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (byte as char).to_string(),
+                byte => format!("%{:02X}", byte),
+            })
+            .collect();
+
+        Response::build()
+            .status(Status::Ok)
+            .header(content_type)
+            .header(Header::new(
+                "Content-Disposition",
+                format!("attachment; filename=\"{}\"; filename*=UTF-8''{}", ascii_name, encoded_name),
+            ))
+            .header(Header::new("X-Content-Type-Options", "nosniff"))
+            .sized_body(file.content.len(), Cursor::new(file.content))
+            .ok()
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/attachment/{owner_type}/{owner_id}",
+    summary = "Upload an attachment",
+    description = "Attach a receipt or document to a transaction, contact or asset as multipart/form-data with a `file` field. The size and SHA-256 of the content are recorded and the file counts against the user's storage quota",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("owner_type" = AttachmentOwnerVariant, description = "transaction, contact or asset"),
+        ("owner_id" = String, description = "The ID of the transaction, contact or asset"),
+    ),
+    request_body(content = ReqUploadAttachmentDto, content_type = "multipart/form-data"),
+    responses(
+        (status = 201, description = "Attachment uploaded successfully", body = ResEntryAttachmentDto),
+        (status = 400, description = "Empty file, file too large or invalid owner type", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Owner not found", body = ErrorResponse),
+        (status = 409, description = "Storage quota exceeded", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Attachment"]
+)]
+#[post("/<owner_type>/<owner_id>", data = "<form>")]
+pub async fn upload_attachment(
+    user: AuthenticatedUser,
+    owner_type: &str,
+    owner_id: Uuid,
+    form: Form<AttachmentUploadForm<'_>>,
+    attachment_usecase: &State<Arc<AttachmentUseCaseImpl>>,
+) -> OtterResponse<ResEntryAttachmentDto> {
+    let owner_type = parse_owner_type(owner_type)?;
+
+    let file_name = sanitize_file_name(
+        form.file
+            .raw_name()
+            .map(|name| name.dangerous_unsafe_unsanitized_raw().as_str())
+            .unwrap_or_default(),
+    );
+    let content_type = form.file
+        .content_type()
+        .map(|content_type| content_type.to_string())
+        .unwrap_or_else(|| ContentType::Binary.to_string());
+
+    let mut content = Vec::with_capacity(form.file.len() as usize);
+    let read = match form.file.open().await {
+        Ok(mut reader) => reader.read_to_end(&mut content).await,
+        Err(err) => Err(err),
+    };
+    if let Err(err) = read {
+        return Err(ErrorResponse(Status::InternalServerError, format!("Failed to read the uploaded file: {}", err)));
+    }
+
+    let upload_dto = AttachmentUploadDto {
+        file_name,
+        content_type,
+        content,
+    };
+    match attachment_usecase.upload_attachment(user.id, owner_type, owner_id, upload_dto).await {
+        Ok(res) => Ok(SuccessResponse(Status::Created, res)),
+        Err(err) => Err(attachment_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/attachment/{owner_type}/{owner_id}",
+    summary = "Get the attachments of a record",
+    description = "Get the attachments of a transaction, contact or asset, newest first",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("owner_type" = AttachmentOwnerVariant, description = "transaction, contact or asset"),
+        ("owner_id" = String, description = "The ID of the transaction, contact or asset"),
+    ),
+    responses(
+        (status = 200, description = "Attachments found", body = ResListAttachmentDto),
+        (status = 400, description = "Invalid owner type", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Attachment"]
+)]
+#[get("/<owner_type>/<owner_id>")]
+pub async fn view_attachments_by_owner(
+    user: AuthenticatedUser,
+    owner_type: &str,
+    owner_id: Uuid,
+    attachment_usecase: &State<Arc<AttachmentUseCaseImpl>>,
+) -> OtterResponse<ResListAttachmentDto> {
+    let owner_type = parse_owner_type(owner_type)?;
+
+    match attachment_usecase.get_all_attachment_by_owner(user.id, owner_type, owner_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(attachment_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/attachment/usage",
+    summary = "Get the attachment storage usage",
+    description = "Number and total size of the user's attachments against the storage quota and the size limit of one file",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Usage found", body = ResAttachmentUsageDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Attachment"]
+)]
+#[get("/usage")]
+pub async fn view_attachment_usage(
+    user: AuthenticatedUser,
+    attachment_usecase: &State<Arc<AttachmentUseCaseImpl>>,
+) -> OtterResponse<ResAttachmentUsageDto> {
+    match attachment_usecase.get_usage(user.id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(attachment_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/attachment/{attachment_id}",
+    summary = "Get an attachment by ID",
+    description = "Get the metadata of an attachment, the content is served by the download route",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("attachment_id" = String, description = "The ID of the attachment"),
+    ),
+    responses(
+        (status = 200, description = "Attachment found", body = ResEntryAttachmentDto),
+        (status = 404, description = "Attachment not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Attachment"]
+)]
+#[get("/<attachment_id>")]
+pub async fn view_attachment_by_id(
+    user: AuthenticatedUser,
+    attachment_id: Uuid,
+    attachment_usecase: &State<Arc<AttachmentUseCaseImpl>>,
+) -> OtterResponse<ResEntryAttachmentDto> {
+    if attachment_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid attachment ID".to_string()));
+    }
+
+    match attachment_usecase.get_attachment(user.id, attachment_id).await {
+        Ok(res) => {
+            match res {
+                Some(attachment) => Ok(SuccessResponse(Status::Ok, attachment)),
+                None => Err(ErrorResponse(Status::NotFound, "Attachment not found".to_string())),
+            }
+        },
+        Err(err) => Err(attachment_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/attachment/{attachment_id}/download",
+    summary = "Download an attachment",
+    description = "Download the content of an attachment with its original file name and content type",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("attachment_id" = String, description = "The ID of the attachment"),
+    ),
+    responses(
+        (status = 200, description = "The attachment content", body = Vec<u8>, content_type = "application/octet-stream"),
+        (status = 404, description = "Attachment not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Attachment"]
+)]
+#[get("/<attachment_id>/download")]
+pub async fn download_attachment(
+    user: AuthenticatedUser,
+    attachment_id: Uuid,
+    attachment_usecase: &State<Arc<AttachmentUseCaseImpl>>,
+) -> Result<AttachmentDownload, ErrorResponse> {
+    match attachment_usecase.download_attachment(user.id, attachment_id).await {
+        Ok(file) => Ok(AttachmentDownload(file)),
+        Err(err) => Err(attachment_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    delete,
+    path = "/attachment/{attachment_id}",
+    summary = "Delete an attachment by ID",
+    description = "Delete an attachment and its stored content, the space is given back to the quota",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("attachment_id" = String, description = "The ID of the attachment"),
+    ),
+    responses(
+        (status = 200, description = "Attachment deleted successfully", body = String),
+        (status = 404, description = "Attachment not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Attachment"]
+)]
+#[delete("/<attachment_id>")]
+pub async fn delete_attachment_by_id(
+    user: AuthenticatedUser,
+    attachment_id: Uuid,
+    attachment_usecase: &State<Arc<AttachmentUseCaseImpl>>,
+) -> OtterResponse<String> {
+    if attachment_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid attachment ID".to_string()));
+    }
+
+    match attachment_usecase.delete_attachment(user.id, attachment_id).await {
+        Ok(_) => Ok(SuccessResponse(Status::Ok, format!("Attachment with ID {} deleted successfully", attachment_id))),
+        Err(err) => Err(attachment_error_response(err)),
+    }
+}
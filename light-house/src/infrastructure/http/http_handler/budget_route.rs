@@ -0,0 +1,324 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use rocket::{delete, get, http::Status, post, put, routes, serde::json::Json, Route, State};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{application::{usecase::budget_usecase::BudgetUseCase, usecase_req_impl::budget_usecase::BudgetUsecase}, domain::dto::budget_dto::{ReqCreateBudgetDto, ReqUpdateBudgetDto, ResEntryBudgetDto, ResListBudgetAlertDto, ResListBudgetDto, ResListBudgetStatusDto}, implentation::date_time_utill::{parse_to_datetime_utc_end_of_day, parse_to_datetime_utc_start_of_day}, infrastructure::{database::mysql::impl_repository::{budget_repo::BudgetRepositoryImpl, expense_type_repos::ExpenseTypeRepositoryImpl}, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+
+
+pub fn budget_routes() -> Vec<Route> {
+    routes![
+        create_budget,
+        fetch_budget_status,
+        fetch_budget_alerts,
+        view_budget_by_id,
+        view_all_budgets,
+        delete_budget_by_id,
+        update_budget
+    ]
+}
+
+
+fn budget_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/budget",
+    summary = "Create a new budget",
+    description = "Create a monthly or custom period budget for an expense type. Monthly budgets can roll what was left over into the next month",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqCreateBudgetDto,
+    responses(
+        (status = 201, description = "Budget created successfully", body = ResEntryBudgetDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Expense type not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Budget"]
+)]
+#[post("/", data = "<dto>")]
+pub async fn create_budget(
+    user: AuthenticatedUser,
+    dto: Json<ReqCreateBudgetDto>,
+    budget_usecase: &State<Arc<BudgetUseCase<BudgetRepositoryImpl, ExpenseTypeRepositoryImpl>>>,
+) -> OtterResponse<ResEntryBudgetDto> {
+    // field empty Bad request
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+    let budget_dto = match dto.into_inner().into_create() {
+        Ok(budget_dto) => budget_dto,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    match budget_usecase.create_budget(user.id, budget_dto).await {
+        Ok(res) => Ok(SuccessResponse(Status::Created, res)),
+        Err(err) => Err(budget_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/budget/status",
+    summary = "Fetch budget status",
+    description = "Budgeted, spent and remaining amount of every budget running on the date, counted from the payments whose expense belongs to the budget's expense type",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("date" = Option<String>, Query, description = "YYYY-MM-DD or RFC 3339 timestamp, defaults to now"),
+    ),
+    responses(
+        (status = 200, description = "Budget status fetched successfully", body = ResListBudgetStatusDto),
+        (status = 400, description = "Invalid date", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Budget"]
+)]
+#[get("/status?<date>")]
+pub async fn fetch_budget_status(
+    user: AuthenticatedUser,
+    date: Option<&str>,
+    budget_usecase: &State<Arc<BudgetUseCase<BudgetRepositoryImpl, ExpenseTypeRepositoryImpl>>>,
+) -> OtterResponse<ResListBudgetStatusDto> {
+
+    let date = match date.map(parse_to_datetime_utc_start_of_day).transpose() {
+        Ok(date) => date.unwrap_or_else(Utc::now),
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+
+    match budget_usecase.get_budget_status(user.id, date).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(budget_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/budget/alerts",
+    summary = "Fetch budget alerts",
+    description = "Every budget period that went over budget, with the payment that pushed it over. Only payments made between from and to are listed",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("from" = Option<String>, Query, description = "YYYY-MM-DD or RFC 3339 timestamp, defaults to 30 days ago"),
+        ("to" = Option<String>, Query, description = "YYYY-MM-DD or RFC 3339 timestamp, defaults to now"),
+    ),
+    responses(
+        (status = 200, description = "Budget alerts fetched successfully", body = ResListBudgetAlertDto),
+        (status = 400, description = "Invalid range", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Budget"]
+)]
+#[get("/alerts?<from>&<to>")]
+pub async fn fetch_budget_alerts(
+    user: AuthenticatedUser,
+    from: Option<&str>,
+    to: Option<&str>,
+    budget_usecase: &State<Arc<BudgetUseCase<BudgetRepositoryImpl, ExpenseTypeRepositoryImpl>>>,
+) -> OtterResponse<ResListBudgetAlertDto> {
+
+    let to = match to.map(parse_to_datetime_utc_end_of_day).transpose() {
+        Ok(to) => to.unwrap_or_else(Utc::now),
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    let from = match from.map(parse_to_datetime_utc_start_of_day).transpose() {
+        Ok(from) => from.unwrap_or(to - Duration::days(30)),
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    if from > to {
+        return Err(ErrorResponse(Status::BadRequest, "from must not be after to".to_string()));
+    }
+
+    match budget_usecase.get_budget_alerts(user.id, from, to).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(budget_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/budget/{budget_id}",
+    summary = "Get a budget by ID",
+    description = "Get a budget by ID",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("budget_id" = String, description = "The ID of the budget"),
+    ),
+    responses(
+        (status = 200, description = "Budget found", body = ResEntryBudgetDto),
+        (status = 404, description = "Budget not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Budget"]
+)]
+#[get("/<budget_id>")]
+pub async fn view_budget_by_id(
+    user: AuthenticatedUser,
+    budget_id: Uuid,
+    budget_usecase: &State<Arc<BudgetUseCase<BudgetRepositoryImpl, ExpenseTypeRepositoryImpl>>>,
+) -> OtterResponse<ResEntryBudgetDto> {
+
+    if budget_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid budget ID".to_string()));
+    }
+
+    match budget_usecase.get_budget(user.id, budget_id).await {
+        Ok(res) => {
+            match res {
+                Some(budget) => Ok(SuccessResponse(Status::Ok, budget)),
+                None => Err(ErrorResponse(Status::NotFound, "Budget not found".to_string())),
+            }
+        },
+        Err(err) => Err(budget_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/budget",
+    summary = "Get all budgets",
+    description = "Get all budgets",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Budgets found", body = ResListBudgetDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Budget"]
+)]
+#[get("/")]
+pub async fn view_all_budgets(
+    user: AuthenticatedUser,
+    budget_usecase: &State<Arc<BudgetUseCase<BudgetRepositoryImpl, ExpenseTypeRepositoryImpl>>>,
+) -> OtterResponse<ResListBudgetDto> {
+    match budget_usecase.get_all_budget(user.id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(budget_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    delete,
+    path = "/budget/{budget_id}",
+    summary = "Delete a budget by ID",
+    description = "Delete a budget and its periods",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("budget_id" = String, description = "The ID of the budget"),
+    ),
+    responses(
+        (status = 200, description = "Budget deleted successfully", body = String),
+        (status = 404, description = "Budget not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Budget"]
+)]
+#[delete("/<budget_id>")]
+pub async fn delete_budget_by_id(
+    user: AuthenticatedUser,
+    budget_id: Uuid,
+    budget_usecase: &State<Arc<BudgetUseCase<BudgetRepositoryImpl, ExpenseTypeRepositoryImpl>>>,
+) -> OtterResponse<String> {
+    if budget_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid budget ID".to_string()));
+    }
+
+    match budget_usecase.delete_budget(user.id, budget_id).await {
+        Ok(_) => Ok(SuccessResponse(Status::Ok, format!("Budget with ID {} deleted successfully", budget_id))),
+        Err(err) => Err(budget_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    put,
+    path = "/budget/{budget_id}",
+    summary = "Update a budget by ID",
+    description = "Update a budget. A new amount applies to the current and future periods, past periods keep theirs",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqUpdateBudgetDto,
+    params(
+        ("budget_id" = String, description = "The ID of the budget"),
+    ),
+    responses(
+        (status = 200, description = "Budget updated successfully", body = ResEntryBudgetDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 404, description = "Budget not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Budget"]
+)]
+#[put("/<budget_id>", data = "<dto>")]
+pub async fn update_budget(
+    user: AuthenticatedUser,
+    budget_id: Uuid,
+    dto: Json<ReqUpdateBudgetDto>,
+    budget_usecase: &State<Arc<BudgetUseCase<BudgetRepositoryImpl, ExpenseTypeRepositoryImpl>>>,
+) -> OtterResponse<ResEntryBudgetDto> {
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+    let budget_dto = match dto.into_inner().into_update() {
+        Ok(budget_dto) => budget_dto,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+
+    match budget_usecase.update_budget(user.id, budget_id, budget_dto).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(budget_error_response(err)),
+    }
+}
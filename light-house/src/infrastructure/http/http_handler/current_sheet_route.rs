@@ -1,9 +1,10 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
-use rocket::{get, http::Status, routes, Route, State};
+use rocket::{get, http::Status, post, routes, serde::json::Json, Route, State};
 use uuid::Uuid;
+use validator::Validate;
 
-use crate::{application::{usecase::current_sheet_usecase::CurrentUseCase, usecase_req_impl::current_sheet_usecase::CurrentSheetUsecase}, domain::dto::current_sheet_dto::{ResCurrentSheetDto, ResListCurrentSheetDto}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, balance_repo::BalanceRepositoryImpl}, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}};
+use crate::{application::{usecase::current_sheet_usecase::CurrentUseCase, usecase_req_impl::current_sheet_usecase::CurrentSheetUsecase}, domain::dto::{current_sheet_dto::{ReqRebuildCurrentSheetDto, ResBalanceAsOfDto, ResBalanceHistoryDto, ResCurrentSheetDto, ResListCurrentSheetDto, ResRebuildCurrentSheetDto}, dto_enum::balance_interval::BalanceIntervalVariant}, implentation::date_time_utill::{parse_to_datetime_utc_end_of_day, parse_to_datetime_utc_start_of_day}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, balance_repo::BalanceRepositoryImpl}, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}, soc::soc_usecase::UsecaseError};
 
 
 
@@ -14,11 +15,27 @@ pub fn current_sheet_routes() -> Vec<Route> {
     routes![
         fetch_current_sheet_by_current_sheet_by_id,
         fetch_all_current_sheets_by_user_id,
-        fetch_all_current_sheets_by_asset_id
+        fetch_all_current_sheets_by_asset_id,
+        rebuild_current_sheets,
+        fetch_balance_as_of,
+        fetch_balance_history
     ]
 }
 
 
+fn current_sheet_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::ValidationFailed(details) => ErrorResponse(
+            Status::BadRequest,
+            details.into_iter().map(|detail| detail.message).collect::<Vec<_>>().join(", "),
+        ),
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
 
 
 
@@ -60,7 +77,7 @@ pub async fn fetch_current_sheet_by_current_sheet_by_id(
     {
         Ok(Some(res)) => Ok(SuccessResponse(Status::Ok, res)),
         Ok(None) => Err(ErrorResponse(Status::NotFound, "Current sheet not found".to_string())),
-        Err(err) => Err(ErrorResponse(Status::InternalServerError, err.to_string())),
+        Err(err) => Err(current_sheet_error_response(err)),
     }
 }
 
@@ -97,7 +114,7 @@ pub async fn fetch_all_current_sheets_by_user_id(
         .await
     {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
-        Err(err) => Err(ErrorResponse(Status::InternalServerError, err.to_string())),
+        Err(err) => Err(current_sheet_error_response(err)),
     }
 }
 
@@ -140,6 +157,166 @@ pub async fn fetch_all_current_sheets_by_asset_id(
         .await
     {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
-        Err(err) => Err(ErrorResponse(Status::InternalServerError, err.to_string())),
+        Err(err) => Err(current_sheet_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/current-sheet/rebuild",
+    summary = "Rebuild current sheets from the transaction history",
+    description = "Replay every transaction of the user, or of a single asset, and compare the result with the stored current sheets. Runs as a dry run unless dry_run is false, in which case every discrepancy is fixed inside one database transaction",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqRebuildCurrentSheetDto,
+    responses(
+        (status = 200, description = "Current sheets reconciled successfully", body = ResRebuildCurrentSheetDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Current Sheet"]
+)]
+#[post("/rebuild", data = "<dto>")]
+pub async fn rebuild_current_sheets(
+    user: AuthenticatedUser,
+    dto: Json<ReqRebuildCurrentSheetDto>,
+    current_sheet_usecase: &State<Arc<CurrentUseCase<BalanceRepositoryImpl, AssetRepositoryImpl>>>,
+) -> OtterResponse<ResRebuildCurrentSheetDto> {
+
+    if let Err(errors) = dto.validate() {
+        return Err(ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors)));
+    }
+    if let Some(asset_id) = &dto.asset_id
+        && Uuid::parse_str(asset_id).is_err()
+    {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid asset ID".to_string()));
+    }
+
+    match current_sheet_usecase
+        .rebuild_current_sheets(user.id, dto.into_inner())
+        .await
+    {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(current_sheet_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/current-sheet/{asset_id}/as-of",
+    summary = "Fetch the balance of an asset at a point in time",
+    description = "Replay the transaction history of the asset up to the given date. A plain YYYY-MM-DD date includes the whole day in UTC, an RFC 3339 timestamp is used as is",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("asset_id" = String, description = "The ID of the asset"),
+        ("date" = String, Query, description = "YYYY-MM-DD or RFC 3339 timestamp"),
+    ),
+    responses(
+        (status = 200, description = "Balance fetched successfully", body = ResBalanceAsOfDto),
+        (status = 400, description = "Invalid asset ID or date", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Asset not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Current Sheet"]
+)]
+#[get("/<asset_id>/as-of?<date>")]
+pub async fn fetch_balance_as_of(
+    user: AuthenticatedUser,
+    asset_id: Uuid,
+    date: &str,
+    current_sheet_usecase: &State<Arc<CurrentUseCase<BalanceRepositoryImpl, AssetRepositoryImpl>>>,
+) -> OtterResponse<ResBalanceAsOfDto> {
+
+    if asset_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid asset ID".to_string()));
+    }
+    let as_of = match parse_to_datetime_utc_end_of_day(date) {
+        Ok(as_of) => as_of,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+
+    match current_sheet_usecase
+        .get_balance_as_of(user.id, asset_id, as_of)
+        .await
+    {
+        Ok(Some(res)) => Ok(SuccessResponse(Status::Ok, res)),
+        Ok(None) => Err(ErrorResponse(Status::NotFound, "Asset not found".to_string())),
+        Err(err) => Err(current_sheet_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/current-sheet/{asset_id}/history",
+    summary = "Fetch the balance history of an asset",
+    description = "Closing balance of the asset for every day, week or month between from and to, computed from the transaction history",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("asset_id" = String, description = "The ID of the asset"),
+        ("from" = String, Query, description = "YYYY-MM-DD or RFC 3339 timestamp"),
+        ("to" = String, Query, description = "YYYY-MM-DD or RFC 3339 timestamp"),
+        ("interval" = Option<String>, Query, description = "day, week or month, defaults to day"),
+    ),
+    responses(
+        (status = 200, description = "Balance history fetched successfully", body = ResBalanceHistoryDto),
+        (status = 400, description = "Invalid asset ID, range or interval", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Asset not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Current Sheet"]
+)]
+#[get("/<asset_id>/history?<from>&<to>&<interval>")]
+pub async fn fetch_balance_history(
+    user: AuthenticatedUser,
+    asset_id: Uuid,
+    from: &str,
+    to: &str,
+    interval: Option<&str>,
+    current_sheet_usecase: &State<Arc<CurrentUseCase<BalanceRepositoryImpl, AssetRepositoryImpl>>>,
+) -> OtterResponse<ResBalanceHistoryDto> {
+
+    if asset_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid asset ID".to_string()));
+    }
+    let from = match parse_to_datetime_utc_start_of_day(from) {
+        Ok(from) => from,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    let to = match parse_to_datetime_utc_end_of_day(to) {
+        Ok(to) => to,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    if from > to {
+        return Err(ErrorResponse(Status::BadRequest, "from must not be after to".to_string()));
+    }
+    let interval = match interval.map(BalanceIntervalVariant::from_str).transpose() {
+        Ok(interval) => interval.unwrap_or(BalanceIntervalVariant::Day),
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+
+    match current_sheet_usecase
+        .get_balance_history(user.id, asset_id, from, to, interval)
+        .await
+    {
+        Ok(Some(res)) => Ok(SuccessResponse(Status::Ok, res)),
+        Ok(None) => Err(ErrorResponse(Status::NotFound, "Asset not found".to_string())),
+        Err(err) => Err(current_sheet_error_response(err)),
     }
 }
\ No newline at end of file
@@ -0,0 +1,458 @@
+use std::sync::Arc;
+
+use rocket::{delete, form::Form, fs::TempFile, get, http::Status, post, put, routes, serde::json::Json, tokio::io::AsyncReadExt, FromForm, Route, State};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{application::usecase_req_impl::import_usecase::{ImportProfileUsecase, ImportUsecase}, domain::dto::{dto_enum::date_order::DateOrderVariant, import_dto::{ReqCommitImportDto, ReqCreateImportProfileDto, ReqImportCsvDto, ReqImportOfxDto, ReqImportQifDto, ReqImportStatementDto, ReqUpdateImportProfileDto, ResEntryImportProfileDto, ResImportCommitDto, ResImportPreviewDto, ResListImportProfileDto}}, infrastructure::http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}, initiation::init_usecase_setup::ImportUseCaseImpl, soc::soc_usecase::UsecaseError};
+
+
+
+
+
+
+pub fn import_routes() -> Vec<Route> {
+    routes![
+        preview_csv_import,
+        preview_ofx_import,
+        preview_qif_import,
+        commit_import,
+        create_import_profile,
+        view_all_import_profiles,
+        view_import_profile_by_id,
+        update_import_profile,
+        delete_import_profile_by_id
+    ]
+}
+
+
+fn import_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::ValidationFailed(details) => ErrorResponse(
+            Status::BadRequest,
+            details.into_iter().map(|detail| detail.message).collect::<Vec<_>>().join(", "),
+        ),
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        UsecaseError::Conflict(msg) => ErrorResponse(Status::Conflict, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+#[derive(FromForm)]
+pub struct ImportCsvForm<'r> {
+    file: TempFile<'r>,
+    profile_id: String,
+    asset_id: String,
+    contact_id: Option<String>,
+    expense_id: Option<String>,
+    timezone: Option<String>,
+}
+
+#[derive(FromForm)]
+pub struct ImportOfxForm<'r> {
+    file: TempFile<'r>,
+    asset_id: String,
+    account_id: Option<String>,
+    contact_id: Option<String>,
+    expense_id: Option<String>,
+    timezone: Option<String>,
+}
+
+#[derive(FromForm)]
+pub struct ImportQifForm<'r> {
+    file: TempFile<'r>,
+    asset_id: String,
+    account_id: Option<String>,
+    contact_id: Option<String>,
+    expense_id: Option<String>,
+    timezone: Option<String>,
+    date_order: Option<String>,
+}
+
+
+async fn read_statement(file: &TempFile<'_>) -> Result<Vec<u8>, ErrorResponse> {
+    let mut content = Vec::with_capacity(file.len() as usize);
+    let read = match file.open().await {
+        Ok(mut reader) => reader.read_to_end(&mut content).await,
+        Err(err) => Err(err),
+    };
+    match read {
+        Ok(_) => Ok(content),
+        Err(err) => Err(ErrorResponse(Status::InternalServerError, format!("Failed to read the uploaded file: {}", err))),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/import/csv",
+    summary = "Preview a csv bank statement",
+    description = "Read a bank statement as multipart/form-data with a `file` field, using the column mapping of an import profile. Each line becomes a proposed income (money in) or payment (money out) on the asset. Proposals matching an income or payment already on the asset by type, amount and date carry `duplicate_of`. Nothing is written, send the records to /import/commit once reviewed",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(content = ReqImportCsvDto, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Statement read", body = ResImportPreviewDto),
+        (status = 400, description = "Empty or unreadable file, unknown column or invalid timezone", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Import profile or asset not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Import"]
+)]
+#[post("/csv", data = "<form>")]
+pub async fn preview_csv_import(
+    user: AuthenticatedUser,
+    form: Form<ImportCsvForm<'_>>,
+    import_usecase: &State<Arc<ImportUseCaseImpl>>,
+) -> OtterResponse<ResImportPreviewDto> {
+    let profile_id = Uuid::parse_str(form.profile_id.trim())
+        .map_err(|_| ErrorResponse(Status::BadRequest, "Invalid import profile ID".to_string()))?;
+    let statement_dto = ReqImportStatementDto {
+        asset_id: form.asset_id.trim().to_string(),
+        contact_id: form.contact_id.clone(),
+        account_id: None,
+        expense_id: form.expense_id.clone(),
+        timezone: form.timezone.clone().filter(|timezone| !timezone.trim().is_empty()),
+    };
+    if let Err(errors) = statement_dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    let content = read_statement(&form.file).await?;
+    match import_usecase.preview_csv(user.id, profile_id, statement_dto, content).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(import_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/import/ofx",
+    summary = "Preview an OFX bank statement",
+    description = "Read an OFX 1.x (SGML) or 2.x (XML) statement as multipart/form-data with a `file` field. Each STMTTRN becomes a proposed income (money in) or payment (money out) on the asset and keeps its FITID as `external_reference`. A proposal carrying the FITID of a transaction already on the asset, or otherwise matching one by type, amount and date, carries `duplicate_of`. When the file holds several accounts, `account_id` (the ACCTID) picks the one to import. Nothing is written, send the records to /import/commit once reviewed",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(content = ReqImportOfxDto, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Statement read", body = ResImportPreviewDto),
+        (status = 400, description = "Empty or unreadable file, unknown or unchosen account or invalid timezone", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Asset not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Import"]
+)]
+#[post("/ofx", data = "<form>")]
+pub async fn preview_ofx_import(
+    user: AuthenticatedUser,
+    form: Form<ImportOfxForm<'_>>,
+    import_usecase: &State<Arc<ImportUseCaseImpl>>,
+) -> OtterResponse<ResImportPreviewDto> {
+    let statement_dto = ReqImportStatementDto {
+        asset_id: form.asset_id.trim().to_string(),
+        account_id: form.account_id.clone(),
+        contact_id: form.contact_id.clone(),
+        expense_id: form.expense_id.clone(),
+        timezone: form.timezone.clone().filter(|timezone| !timezone.trim().is_empty()),
+    };
+    if let Err(errors) = statement_dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    let content = read_statement(&form.file).await?;
+    match import_usecase.preview_ofx(user.id, statement_dto, content).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(import_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/import/qif",
+    summary = "Preview a QIF bank statement",
+    description = "Read a QIF statement as multipart/form-data with a `file` field. Each Bank, Cash or CCard record becomes a proposed income (money in) or payment (money out) on the asset, a numeric check number (N) is kept as `external_reference`. QIF dates have no fixed order, `date_order` is mdy (default) or dmy. When the file holds several !Account blocks, `account_id` (the account name) picks the one to import. Nothing is written, send the records to /import/commit once reviewed",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(content = ReqImportQifDto, content_type = "multipart/form-data"),
+    responses(
+        (status = 200, description = "Statement read", body = ResImportPreviewDto),
+        (status = 400, description = "Empty or unreadable file, unknown or unchosen account, invalid date_order or timezone", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Asset not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Import"]
+)]
+#[post("/qif", data = "<form>")]
+pub async fn preview_qif_import(
+    user: AuthenticatedUser,
+    form: Form<ImportQifForm<'_>>,
+    import_usecase: &State<Arc<ImportUseCaseImpl>>,
+) -> OtterResponse<ResImportPreviewDto> {
+    let date_order = match form.date_order.as_deref().map(str::trim).filter(|date_order| !date_order.is_empty()) {
+        Some(date_order) => date_order
+            .parse::<DateOrderVariant>()
+            .map_err(|err| ErrorResponse(Status::BadRequest, err))?,
+        None => DateOrderVariant::Mdy,
+    };
+    let statement_dto = ReqImportStatementDto {
+        asset_id: form.asset_id.trim().to_string(),
+        account_id: form.account_id.clone(),
+        contact_id: form.contact_id.clone(),
+        expense_id: form.expense_id.clone(),
+        timezone: form.timezone.clone().filter(|timezone| !timezone.trim().is_empty()),
+    };
+    if let Err(errors) = statement_dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    let content = read_statement(&form.file).await?;
+    match import_usecase.preview_qif(user.id, statement_dto, date_order, content).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(import_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/import/commit",
+    summary = "Commit previewed statement records",
+    description = "Post reviewed records as incomes and payments on the asset, through the same checks as /income and /payment. Each record is posted on its own: a failed record is reported and does not undo the others. Records matching a transaction already on the asset are reported as duplicates and skipped unless skip_duplicates is false. At most 1000 records per request",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqCommitImportDto,
+    responses(
+        (status = 200, description = "Records processed, see the status of each", body = ResImportCommitDto),
+        (status = 400, description = "Validation error", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Asset not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Import"]
+)]
+#[post("/commit", data = "<dto>")]
+pub async fn commit_import(
+    user: AuthenticatedUser,
+    dto: Json<ReqCommitImportDto>,
+    import_usecase: &State<Arc<ImportUseCaseImpl>>,
+) -> OtterResponse<ResImportCommitDto> {
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match import_usecase.commit_import(user.id, dto.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(import_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/import/profile",
+    summary = "Create an import profile",
+    description = "Save how a bank lays out its csv export: delimiter, header, date column and format, a signed amount column or debit and credit columns, description column, decimal separator and encoding. Names are unique per user",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqCreateImportProfileDto,
+    responses(
+        (status = 201, description = "Import profile created successfully", body = ResEntryImportProfileDto),
+        (status = 400, description = "Validation error or no amount column", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "Import profile with the same name already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Import"]
+)]
+#[post("/profile", data = "<dto>")]
+pub async fn create_import_profile(
+    user: AuthenticatedUser,
+    dto: Json<ReqCreateImportProfileDto>,
+    import_usecase: &State<Arc<ImportUseCaseImpl>>,
+) -> OtterResponse<ResEntryImportProfileDto> {
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match import_usecase.create_import_profile(user.id, dto.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Created, res)),
+        Err(err) => Err(import_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/import/profile",
+    summary = "Get all import profiles",
+    description = "Get all import profiles of the user sorted by name",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Import profiles found", body = ResListImportProfileDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Import"]
+)]
+#[get("/profile")]
+pub async fn view_all_import_profiles(
+    user: AuthenticatedUser,
+    import_usecase: &State<Arc<ImportUseCaseImpl>>,
+) -> OtterResponse<ResListImportProfileDto> {
+    match import_usecase.get_all_import_profile(user.id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(import_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/import/profile/{profile_id}",
+    summary = "Get an import profile by ID",
+    description = "Get an import profile by ID",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("profile_id" = String, description = "The ID of the import profile"),
+    ),
+    responses(
+        (status = 200, description = "Import profile found", body = ResEntryImportProfileDto),
+        (status = 404, description = "Import profile not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Import"]
+)]
+#[get("/profile/<profile_id>")]
+pub async fn view_import_profile_by_id(
+    user: AuthenticatedUser,
+    profile_id: Uuid,
+    import_usecase: &State<Arc<ImportUseCaseImpl>>,
+) -> OtterResponse<ResEntryImportProfileDto> {
+    match import_usecase.get_import_profile(user.id, profile_id).await {
+        Ok(res) => {
+            match res {
+                Some(profile) => Ok(SuccessResponse(Status::Ok, profile)),
+                None => Err(ErrorResponse(Status::NotFound, "Import profile not found".to_string())),
+            }
+        },
+        Err(err) => Err(import_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    put,
+    path = "/import/profile/{profile_id}",
+    summary = "Update an import profile by ID",
+    description = "Change the provided fields of an import profile, an empty amount_column, debit_column or credit_column removes it. The profile must keep an amount column or a debit or credit column",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqUpdateImportProfileDto,
+    params(
+        ("profile_id" = String, description = "The ID of the import profile"),
+    ),
+    responses(
+        (status = 200, description = "Import profile updated successfully", body = ResEntryImportProfileDto),
+        (status = 400, description = "Validation error or no amount column left", body = ErrorResponse),
+        (status = 404, description = "Import profile not found", body = ErrorResponse),
+        (status = 409, description = "Import profile with the same name already exists", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Import"]
+)]
+#[put("/profile/<profile_id>", data = "<dto>")]
+pub async fn update_import_profile(
+    user: AuthenticatedUser,
+    profile_id: Uuid,
+    dto: Json<ReqUpdateImportProfileDto>,
+    import_usecase: &State<Arc<ImportUseCaseImpl>>,
+) -> OtterResponse<ResEntryImportProfileDto> {
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match import_usecase.update_import_profile(user.id, profile_id, dto.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(import_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    delete,
+    path = "/import/profile/{profile_id}",
+    summary = "Delete an import profile by ID",
+    description = "Delete an import profile, transactions imported with it are kept",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("profile_id" = String, description = "The ID of the import profile"),
+    ),
+    responses(
+        (status = 200, description = "Import profile deleted successfully", body = String),
+        (status = 404, description = "Import profile not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Import"]
+)]
+#[delete("/profile/<profile_id>")]
+pub async fn delete_import_profile_by_id(
+    user: AuthenticatedUser,
+    profile_id: Uuid,
+    import_usecase: &State<Arc<ImportUseCaseImpl>>,
+) -> OtterResponse<String> {
+    match import_usecase.delete_import_profile(user.id, profile_id).await {
+        Ok(_) => Ok(SuccessResponse(Status::Ok, format!("Import profile with ID {} deleted successfully", profile_id))),
+        Err(err) => Err(import_error_response(err)),
+    }
+}
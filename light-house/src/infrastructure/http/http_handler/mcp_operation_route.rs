@@ -0,0 +1,180 @@
+use std::{str::FromStr, sync::Arc};
+
+use rocket::{get, http::Status, post, routes, Route, State};
+use uuid::Uuid;
+
+use crate::{application::usecase_req_impl::mcp_operation_usecase::McpOperationUsecase, domain::dto::{dto_enum::mcp_operation::McpOperationStatusVariant, mcp_operation_dto::{ResListMcpPendingOperationDto, ResMcpPendingOperationDto}}, infrastructure::http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}, initiation::init_usecase_setup::McpOperationUseCaseImpl, soc::soc_usecase::UsecaseError};
+
+
+
+
+pub fn mcp_operation_routes() -> Vec<Route> {
+    routes![
+        view_all_mcp_operations,
+        view_mcp_operation_by_id,
+        confirm_mcp_operation,
+        reject_mcp_operation
+    ]
+}
+
+
+pub fn mcp_operation_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ValidationFailed(details) => ErrorResponse(
+            Status::BadRequest,
+            details
+                .into_iter()
+                .map(|detail| format!("{}: {}", detail.field, detail.message))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        UsecaseError::Conflict(msg) => ErrorResponse(Status::Conflict, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/mcp-operation",
+    summary = "Get the operations proposed over MCP",
+    description = "Get the writes an MCP client proposed, newest first. Pending operations past their expiry are shown as expired",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("status" = Option<String>, Query, description = "pending, confirmed, rejected, expired or failed"),
+    ),
+    responses(
+        (status = 200, description = "Operations found", body = ResListMcpPendingOperationDto),
+        (status = 400, description = "Invalid status", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["MCP Operation"]
+)]
+#[get("/?<status>")]
+pub async fn view_all_mcp_operations(
+    user: AuthenticatedUser,
+    status: Option<String>,
+    mcp_operation_usecase: &State<Arc<McpOperationUseCaseImpl>>,
+) -> OtterResponse<ResListMcpPendingOperationDto> {
+    let status = match status.as_deref().map(McpOperationStatusVariant::from_str).transpose() {
+        Ok(status) => status,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    match mcp_operation_usecase.get_all_operation(user.id, status).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(mcp_operation_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/mcp-operation/{operation_id}",
+    summary = "Get an operation proposed over MCP by ID",
+    description = "Get the operation with its payload and the balance change of every asset it touches",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("operation_id" = String, description = "The ID of the operation"),
+    ),
+    responses(
+        (status = 200, description = "Operation found", body = ResMcpPendingOperationDto),
+        (status = 404, description = "Operation not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["MCP Operation"]
+)]
+#[get("/<operation_id>")]
+pub async fn view_mcp_operation_by_id(
+    user: AuthenticatedUser,
+    operation_id: Uuid,
+    mcp_operation_usecase: &State<Arc<McpOperationUseCaseImpl>>,
+) -> OtterResponse<ResMcpPendingOperationDto> {
+    match mcp_operation_usecase.get_operation(user.id, operation_id).await {
+        Ok(Some(res)) => Ok(SuccessResponse(Status::Ok, res)),
+        Ok(None) => Err(ErrorResponse(Status::NotFound, "Operation not found".to_string())),
+        Err(err) => Err(mcp_operation_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/mcp-operation/{operation_id}/confirm",
+    summary = "Confirm an operation proposed over MCP",
+    description = "Run the operation. It fails with 409 when it is no longer pending, has expired, or its target changed after it was proposed",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("operation_id" = String, description = "The ID of the operation"),
+    ),
+    responses(
+        (status = 200, description = "Operation confirmed, result holds the created or updated record", body = ResMcpPendingOperationDto),
+        (status = 400, description = "The operation was rejected by validation", body = ErrorResponse),
+        (status = 404, description = "Operation not found", body = ErrorResponse),
+        (status = 409, description = "Operation already decided, expired or out of date", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["MCP Operation"]
+)]
+#[post("/<operation_id>/confirm")]
+pub async fn confirm_mcp_operation(
+    user: AuthenticatedUser,
+    operation_id: Uuid,
+    mcp_operation_usecase: &State<Arc<McpOperationUseCaseImpl>>,
+) -> OtterResponse<ResMcpPendingOperationDto> {
+    match mcp_operation_usecase.confirm_operation(user.id, operation_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(mcp_operation_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/mcp-operation/{operation_id}/reject",
+    summary = "Reject an operation proposed over MCP",
+    description = "Discard the operation without running it",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("operation_id" = String, description = "The ID of the operation"),
+    ),
+    responses(
+        (status = 200, description = "Operation rejected", body = ResMcpPendingOperationDto),
+        (status = 404, description = "Operation not found", body = ErrorResponse),
+        (status = 409, description = "Operation already decided or expired", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["MCP Operation"]
+)]
+#[post("/<operation_id>/reject")]
+pub async fn reject_mcp_operation(
+    user: AuthenticatedUser,
+    operation_id: Uuid,
+    mcp_operation_usecase: &State<Arc<McpOperationUseCaseImpl>>,
+) -> OtterResponse<ResMcpPendingOperationDto> {
+    match mcp_operation_usecase.reject_operation(user.id, operation_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(mcp_operation_error_response(err)),
+    }
+}
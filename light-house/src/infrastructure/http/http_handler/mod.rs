@@ -6,4 +6,15 @@ pub mod contact_route;
 pub mod expense_type_route;
 pub mod expense_route;
 pub mod transaction;
-pub mod current_sheet_route;
\ No newline at end of file
+pub mod current_sheet_route;
+pub mod report_route;
+pub mod budget_route;
+pub mod recurring_transaction_route;
+pub mod mcp_operation_route;
+pub mod api_token_route;
+pub mod session_route;
+pub mod admin_route;
+pub mod two_factor_route;
+pub mod tag_route;
+pub mod attachment_route;
+pub mod import_route;
\ No newline at end of file
@@ -0,0 +1,427 @@
+use std::sync::Arc;
+
+use rocket::{delete, get, http::Status, post, put, routes, serde::json::Json, Route, State};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{application::usecase_req_impl::recurring_transaction_usecase::RecurringTransactionUsecase, domain::dto::recurring_transaction_dto::{ReqCreateRecurringTransactionDto, ReqSkipRecurringOccurrenceDto, ReqUpdateRecurringTransactionDto, ResEntryRecurringTransactionDto, ResListRecurringOccurrenceDto, ResListRecurringTransactionDto, ResRecurringOccurrenceDto, ResRecurringPreviewDto}, infrastructure::http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}, initiation::init_usecase_setup::RecurringTransactionUseCaseImpl, soc::soc_usecase::UsecaseError};
+
+
+
+
+const DEFAULT_PREVIEW_COUNT: usize = 10;
+const MAX_PREVIEW_COUNT: usize = 100;
+
+
+pub fn recurring_transaction_routes() -> Vec<Route> {
+    routes![
+        create_recurring_transaction,
+        view_recurring_transaction_by_id,
+        view_all_recurring_transactions,
+        update_recurring_transaction,
+        delete_recurring_transaction_by_id,
+        pause_recurring_transaction,
+        resume_recurring_transaction,
+        skip_recurring_occurrence,
+        preview_recurring_occurrences,
+        view_recurring_occurrences
+    ]
+}
+
+
+fn recurring_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        UsecaseError::Conflict(msg) => ErrorResponse(Status::Conflict, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/recurring-transaction",
+    summary = "Create a recurring transaction",
+    description = "Create a payment, income or transfer template that is posted on a schedule: daily, weekly, monthly on a day, on the last business day of the month, or an RRULE (FREQ, INTERVAL, BYDAY, BYMONTHDAY, BYSETPOS, COUNT, UNTIL). Occurrences between start_at and now are posted on the next run",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqCreateRecurringTransactionDto,
+    responses(
+        (status = 201, description = "Recurring transaction created successfully", body = ResEntryRecurringTransactionDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Asset, contact or expense not found", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[post("/", data = "<dto>")]
+pub async fn create_recurring_transaction(
+    user: AuthenticatedUser,
+    dto: Json<ReqCreateRecurringTransactionDto>,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<ResEntryRecurringTransactionDto> {
+    // field empty Bad request
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+    let recurring_dto = match dto.into_inner().into_create() {
+        Ok(recurring_dto) => recurring_dto,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    match recurring_usecase.create_recurring_transaction(user.id, recurring_dto).await {
+        Ok(res) => Ok(SuccessResponse(Status::Created, res)),
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/recurring-transaction/{recurring_transaction_id}",
+    summary = "Get a recurring transaction by ID",
+    description = "Get a recurring transaction by ID",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("recurring_transaction_id" = String, description = "The ID of the recurring transaction"),
+    ),
+    responses(
+        (status = 200, description = "Recurring transaction found", body = ResEntryRecurringTransactionDto),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[get("/<recurring_transaction_id>")]
+pub async fn view_recurring_transaction_by_id(
+    user: AuthenticatedUser,
+    recurring_transaction_id: Uuid,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<ResEntryRecurringTransactionDto> {
+
+    if recurring_transaction_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid recurring transaction ID".to_string()));
+    }
+
+    match recurring_usecase.get_recurring_transaction(user.id, recurring_transaction_id).await {
+        Ok(res) => {
+            match res {
+                Some(recurring) => Ok(SuccessResponse(Status::Ok, recurring)),
+                None => Err(ErrorResponse(Status::NotFound, "Recurring transaction not found".to_string())),
+            }
+        },
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/recurring-transaction",
+    summary = "Get all recurring transactions",
+    description = "Get all recurring transactions",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Recurring transactions found", body = ResListRecurringTransactionDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[get("/")]
+pub async fn view_all_recurring_transactions(
+    user: AuthenticatedUser,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<ResListRecurringTransactionDto> {
+    match recurring_usecase.get_all_recurring_transaction(user.id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    put,
+    path = "/recurring-transaction/{recurring_transaction_id}",
+    summary = "Update a recurring transaction by ID",
+    description = "Update the template of a recurring transaction. Changes apply to occurrences that have not been posted yet, the schedule itself cannot be changed",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqUpdateRecurringTransactionDto,
+    params(
+        ("recurring_transaction_id" = String, description = "The ID of the recurring transaction"),
+    ),
+    responses(
+        (status = 200, description = "Recurring transaction updated successfully", body = ResEntryRecurringTransactionDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[put("/<recurring_transaction_id>", data = "<dto>")]
+pub async fn update_recurring_transaction(
+    user: AuthenticatedUser,
+    recurring_transaction_id: Uuid,
+    dto: Json<ReqUpdateRecurringTransactionDto>,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<ResEntryRecurringTransactionDto> {
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match recurring_usecase.update_recurring_transaction(user.id, recurring_transaction_id, dto.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    delete,
+    path = "/recurring-transaction/{recurring_transaction_id}",
+    summary = "Delete a recurring transaction by ID",
+    description = "Delete a recurring transaction and its occurrence history. Transactions already posted are kept",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("recurring_transaction_id" = String, description = "The ID of the recurring transaction"),
+    ),
+    responses(
+        (status = 200, description = "Recurring transaction deleted successfully", body = String),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[delete("/<recurring_transaction_id>")]
+pub async fn delete_recurring_transaction_by_id(
+    user: AuthenticatedUser,
+    recurring_transaction_id: Uuid,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<String> {
+    if recurring_transaction_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid recurring transaction ID".to_string()));
+    }
+
+    match recurring_usecase.delete_recurring_transaction(user.id, recurring_transaction_id).await {
+        Ok(_) => Ok(SuccessResponse(Status::Ok, format!("Recurring transaction with ID {} deleted successfully", recurring_transaction_id))),
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/recurring-transaction/{recurring_transaction_id}/pause",
+    summary = "Pause a recurring transaction",
+    description = "Stop posting occurrences until the recurring transaction is resumed",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("recurring_transaction_id" = String, description = "The ID of the recurring transaction"),
+    ),
+    responses(
+        (status = 200, description = "Recurring transaction paused", body = ResEntryRecurringTransactionDto),
+        (status = 400, description = "Recurring transaction has finished", body = ErrorResponse),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[post("/<recurring_transaction_id>/pause")]
+pub async fn pause_recurring_transaction(
+    user: AuthenticatedUser,
+    recurring_transaction_id: Uuid,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<ResEntryRecurringTransactionDto> {
+    match recurring_usecase.pause_recurring_transaction(user.id, recurring_transaction_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/recurring-transaction/{recurring_transaction_id}/resume",
+    summary = "Resume a recurring transaction",
+    description = "Resume a paused recurring transaction from the next occurrence after now. Occurrences missed while paused are not posted",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("recurring_transaction_id" = String, description = "The ID of the recurring transaction"),
+    ),
+    responses(
+        (status = 200, description = "Recurring transaction resumed", body = ResEntryRecurringTransactionDto),
+        (status = 400, description = "Recurring transaction has finished", body = ErrorResponse),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[post("/<recurring_transaction_id>/resume")]
+pub async fn resume_recurring_transaction(
+    user: AuthenticatedUser,
+    recurring_transaction_id: Uuid,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<ResEntryRecurringTransactionDto> {
+    match recurring_usecase.resume_recurring_transaction(user.id, recurring_transaction_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/recurring-transaction/{recurring_transaction_id}/skip",
+    summary = "Skip an occurrence",
+    description = "Skip one upcoming occurrence. Without a body the next occurrence is skipped, otherwise the one on the given local date (YYYY-MM-DD) or at the given RFC 3339 time",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = Option<ReqSkipRecurringOccurrenceDto>,
+    params(
+        ("recurring_transaction_id" = String, description = "The ID of the recurring transaction"),
+    ),
+    responses(
+        (status = 200, description = "Occurrence skipped", body = ResRecurringOccurrenceDto),
+        (status = 400, description = "No upcoming occurrence matches", body = ErrorResponse),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 409, description = "Occurrence already posted or skipped", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[post("/<recurring_transaction_id>/skip", data = "<dto>")]
+pub async fn skip_recurring_occurrence(
+    user: AuthenticatedUser,
+    recurring_transaction_id: Uuid,
+    dto: Option<Json<ReqSkipRecurringOccurrenceDto>>,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<ResRecurringOccurrenceDto> {
+    let dto = dto.map(Json::into_inner).unwrap_or_default();
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match recurring_usecase.skip_occurrence(user.id, recurring_transaction_id, dto.occurrence).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/recurring-transaction/{recurring_transaction_id}/preview",
+    summary = "Preview upcoming occurrences",
+    description = "The next occurrences of the schedule, skipped ones are included and marked",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("recurring_transaction_id" = String, description = "The ID of the recurring transaction"),
+        ("count" = Option<usize>, Query, description = "How many occurrences, 1 to 100, defaults to 10"),
+    ),
+    responses(
+        (status = 200, description = "Upcoming occurrences", body = ResRecurringPreviewDto),
+        (status = 400, description = "Invalid count", body = ErrorResponse),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[get("/<recurring_transaction_id>/preview?<count>")]
+pub async fn preview_recurring_occurrences(
+    user: AuthenticatedUser,
+    recurring_transaction_id: Uuid,
+    count: Option<usize>,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<ResRecurringPreviewDto> {
+    let count = count.unwrap_or(DEFAULT_PREVIEW_COUNT);
+    if !(1..=MAX_PREVIEW_COUNT).contains(&count) {
+        return Err(ErrorResponse(Status::BadRequest, format!("count must be between 1 and {}", MAX_PREVIEW_COUNT)));
+    }
+
+    match recurring_usecase.preview_occurrences(user.id, recurring_transaction_id, count).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/recurring-transaction/{recurring_transaction_id}/occurrences",
+    summary = "Get occurrence history",
+    description = "Posted, skipped and failed occurrences of a recurring transaction, newest first",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("recurring_transaction_id" = String, description = "The ID of the recurring transaction"),
+    ),
+    responses(
+        (status = 200, description = "Occurrence history", body = ResListRecurringOccurrenceDto),
+        (status = 404, description = "Recurring transaction not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Recurring Transaction"]
+)]
+#[get("/<recurring_transaction_id>/occurrences")]
+pub async fn view_recurring_occurrences(
+    user: AuthenticatedUser,
+    recurring_transaction_id: Uuid,
+    recurring_usecase: &State<Arc<RecurringTransactionUseCaseImpl>>,
+) -> OtterResponse<ResListRecurringOccurrenceDto> {
+    match recurring_usecase.get_occurrences(user.id, recurring_transaction_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(recurring_error_response(err)),
+    }
+}
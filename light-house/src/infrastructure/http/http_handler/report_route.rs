@@ -0,0 +1,133 @@
+use std::{str::FromStr, sync::Arc};
+
+use rocket::{get, http::Status, routes, Route, State};
+use uuid::Uuid;
+
+use crate::{application::{usecase::report_usecase::ReportUseCase, usecase_req_impl::report_usecase::ReportUsecase}, domain::dto::{dto_enum::{expense_report_group::ExpenseReportGroupVariant, report_period::ReportPeriodVariant}, report_dto::{ResExpenseReportDto, ResReportSummaryDto}}, implentation::date_time_utill::{parse_to_datetime_utc_end_of_day, parse_to_datetime_utc_start_of_day}, infrastructure::{database::mysql::impl_repository::report_repo::ReportRepositoryImpl, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}};
+
+
+
+
+
+
+pub fn report_routes() -> Vec<Route> {
+    routes![
+        fetch_summary,
+        fetch_expense_report
+    ]
+}
+
+
+
+#[utoipa::path(
+    get,
+    path = "/report/summary",
+    summary = "Fetch an income and payment summary",
+    description = "Total income, total payments, net cash flow and transfer volume for every day, week, month or year between from and to, with payments broken down by expense type and income by contact. Periods are cut in UTC",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("period" = Option<String>, Query, description = "day, week, month or year, defaults to month"),
+        ("from" = String, Query, description = "YYYY-MM-DD or RFC 3339 timestamp"),
+        ("to" = String, Query, description = "YYYY-MM-DD or RFC 3339 timestamp"),
+    ),
+    responses(
+        (status = 200, description = "Summary fetched successfully", body = ResReportSummaryDto),
+        (status = 400, description = "Invalid range or period", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Report"]
+)]
+#[get("/summary?<period>&<from>&<to>")]
+pub async fn fetch_summary(
+    user: AuthenticatedUser,
+    period: Option<&str>,
+    from: &str,
+    to: &str,
+    report_usecase: &State<Arc<ReportUseCase<ReportRepositoryImpl>>>,
+) -> OtterResponse<ResReportSummaryDto> {
+
+    let from = match parse_to_datetime_utc_start_of_day(from) {
+        Ok(from) => from,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    let to = match parse_to_datetime_utc_end_of_day(to) {
+        Ok(to) => to,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    if from > to {
+        return Err(ErrorResponse(Status::BadRequest, "from must not be after to".to_string()));
+    }
+    let period = match period.map(ReportPeriodVariant::from_str).transpose() {
+        Ok(period) => period.unwrap_or(ReportPeriodVariant::Month),
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+
+    match report_usecase.get_summary(user.id, from, to, period).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(ErrorResponse(Status::InternalServerError, err.to_string())),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/report/expenses",
+    summary = "Fetch where the money went",
+    description = "Payments between from and to grouped by expense type, expense, contact, asset or tag, with each group's share of the total and its change against the range of the same length right before from. A payment with several tags counts in each of its tags, so tag shares can add up to more than 100 percent",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("from" = String, Query, description = "YYYY-MM-DD or RFC 3339 timestamp"),
+        ("to" = String, Query, description = "YYYY-MM-DD or RFC 3339 timestamp"),
+        ("group_by" = Option<String>, Query, description = "expense_type, expense, contact, asset or tag, defaults to expense_type"),
+        ("tag_id" = Option<String>, Query, description = "Only payments carrying this tag"),
+    ),
+    responses(
+        (status = 200, description = "Expense report fetched successfully", body = ResExpenseReportDto),
+        (status = 400, description = "Invalid range, group_by or tag_id", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Report"]
+)]
+#[get("/expenses?<from>&<to>&<group_by>&<tag_id>")]
+pub async fn fetch_expense_report(
+    user: AuthenticatedUser,
+    from: &str,
+    to: &str,
+    group_by: Option<&str>,
+    tag_id: Option<&str>,
+    report_usecase: &State<Arc<ReportUseCase<ReportRepositoryImpl>>>,
+) -> OtterResponse<ResExpenseReportDto> {
+
+    let from = match parse_to_datetime_utc_start_of_day(from) {
+        Ok(from) => from,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    let to = match parse_to_datetime_utc_end_of_day(to) {
+        Ok(to) => to,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    if from > to {
+        return Err(ErrorResponse(Status::BadRequest, "from must not be after to".to_string()));
+    }
+    let group_by = match group_by.map(ExpenseReportGroupVariant::from_str).transpose() {
+        Ok(group_by) => group_by.unwrap_or(ExpenseReportGroupVariant::ExpenseType),
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    let tag_id = match tag_id.map(Uuid::parse_str).transpose() {
+        Ok(tag_id) => tag_id,
+        Err(_) => return Err(ErrorResponse(Status::BadRequest, "Invalid tag_id".to_string())),
+    };
+
+    match report_usecase.get_expense_report(user.id, from, to, group_by, tag_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(ErrorResponse(Status::InternalServerError, err.to_string())),
+    }
+}
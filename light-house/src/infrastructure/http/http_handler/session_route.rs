@@ -0,0 +1,117 @@
+use std::sync::Arc;
+
+use rocket::{delete, get, http::Status, routes, Route, State};
+use uuid::Uuid;
+
+use crate::{application::{usecase::{user_usecase::UserUseCase, wrapper::user_wrapper::UserRepositoryComposite}, usecase_req_impl::user_usecase::UserSessionUsecase}, domain::dto::session_dto::{ResListSessionDto, ResRevokeSessionDto}, infrastructure::http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+pub fn session_routes() -> Vec<Route> {
+    routes![
+        view_all_sessions,
+        revoke_other_sessions,
+        revoke_session
+    ]
+}
+
+
+fn session_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/session",
+    summary = "Get all active sessions",
+    description = "Get the signed in devices of the user, most recently used first. The session of the current access token is flagged with is_current",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Sessions found", body = ResListSessionDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Session"]
+)]
+#[get("/")]
+pub async fn view_all_sessions(
+    user: AuthenticatedUser,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResListSessionDto> {
+    match user_usecase.get_all_session(user.id, user.session_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(session_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    delete,
+    path = "/session",
+    summary = "Sign out every other session",
+    description = "Revoke every session of the user except the current one",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Other sessions revoked", body = ResRevokeSessionDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Session"]
+)]
+#[delete("/")]
+pub async fn revoke_other_sessions(
+    user: AuthenticatedUser,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResRevokeSessionDto> {
+    match user_usecase.revoke_other_session(user.id, user.session_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(session_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    delete,
+    path = "/session/{session_id}",
+    summary = "Revoke a session",
+    description = "Sign a device out, its access and refresh token stop working at once",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("session_id" = String, description = "The ID of the session"),
+    ),
+    responses(
+        (status = 204, description = "Session revoked"),
+        (status = 404, description = "Session not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Session"]
+)]
+#[delete("/<session_id>")]
+pub async fn revoke_session(
+    user: AuthenticatedUser,
+    session_id: Uuid,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<()> {
+    match user_usecase.revoke_session(user.id, session_id).await {
+        Ok(()) => Ok(SuccessResponse(Status::NoContent, ())),
+        Err(err) => Err(session_error_response(err)),
+    }
+}
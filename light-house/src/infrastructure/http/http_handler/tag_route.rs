@@ -0,0 +1,230 @@
+use std::sync::Arc;
+
+use rocket::{delete, get, http::Status, post, put, routes, serde::json::Json, Route, State};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{application::{usecase::tag_usecase::TagUseCase, usecase_req_impl::tag_usecase::TagUsecase}, domain::dto::tag_dto::{ReqCreateTagDto, ReqUpdateTagDto, ResEntryTagDto, ResListTagDto}, infrastructure::{database::mysql::impl_repository::tag_repo::TagRepositoryImpl, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+
+
+pub fn tag_routes() -> Vec<Route> {
+    routes![
+        create_tag,
+        view_tag_by_id,
+        view_all_tags,
+        delete_tag_by_id,
+        update_tag
+    ]
+}
+
+
+fn tag_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::ValidationFailed(details) => ErrorResponse(
+            Status::BadRequest,
+            details.into_iter().map(|detail| detail.message).collect::<Vec<_>>().join(", "),
+        ),
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        UsecaseError::Conflict(msg) => ErrorResponse(Status::Conflict, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/tag",
+    summary = "Create a new tag",
+    description = "Create a tag to label income, payment and transfer records. Names are unique per user",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqCreateTagDto,
+    responses(
+        (status = 201, description = "Tag created successfully", body = ResEntryTagDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "A tag with this name already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Tag"]
+)]
+#[post("/", data = "<dto>")]
+pub async fn create_tag(
+    user: AuthenticatedUser,
+    dto: Json<ReqCreateTagDto>,
+    tag_usecase: &State<Arc<TagUseCase<TagRepositoryImpl>>>,
+) -> OtterResponse<ResEntryTagDto> {
+    // field empty Bad request
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+    match tag_usecase.create_tag(user.id, dto.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Created, res)),
+        Err(err) => Err(tag_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/tag/{tag_id}",
+    summary = "Get a tag by ID",
+    description = "Get a tag by ID with the number of transactions carrying it",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("tag_id" = String, description = "The ID of the tag"),
+    ),
+    responses(
+        (status = 200, description = "Tag found", body = ResEntryTagDto),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Tag"]
+)]
+#[get("/<tag_id>")]
+pub async fn view_tag_by_id(
+    user: AuthenticatedUser,
+    tag_id: Uuid,
+    tag_usecase: &State<Arc<TagUseCase<TagRepositoryImpl>>>,
+) -> OtterResponse<ResEntryTagDto> {
+
+    if tag_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid tag ID".to_string()));
+    }
+
+    match tag_usecase.get_tag(user.id, tag_id).await {
+        Ok(res) => {
+            match res {
+                Some(tag) => Ok(SuccessResponse(Status::Ok, tag)),
+                None => Err(ErrorResponse(Status::NotFound, "Tag not found".to_string())),
+            }
+        },
+        Err(err) => Err(tag_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/tag",
+    summary = "Get all tags",
+    description = "Get all tags sorted by name, each with the number of transactions carrying it",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Tags found", body = ResListTagDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Tag"]
+)]
+#[get("/")]
+pub async fn view_all_tags(
+    user: AuthenticatedUser,
+    tag_usecase: &State<Arc<TagUseCase<TagRepositoryImpl>>>,
+) -> OtterResponse<ResListTagDto> {
+    match tag_usecase.get_all_tag(user.id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(tag_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    delete,
+    path = "/tag/{tag_id}",
+    summary = "Delete a tag by ID",
+    description = "Delete a tag. It is taken off every transaction carrying it, the transactions themselves are kept",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("tag_id" = String, description = "The ID of the tag"),
+    ),
+    responses(
+        (status = 200, description = "Tag deleted successfully", body = String),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Tag"]
+)]
+#[delete("/<tag_id>")]
+pub async fn delete_tag_by_id(
+    user: AuthenticatedUser,
+    tag_id: Uuid,
+    tag_usecase: &State<Arc<TagUseCase<TagRepositoryImpl>>>,
+) -> OtterResponse<String> {
+    if tag_id.is_nil() {
+        return Err(ErrorResponse(Status::BadRequest, "Invalid tag ID".to_string()));
+    }
+
+    match tag_usecase.delete_tag(user.id, tag_id).await {
+        Ok(_) => Ok(SuccessResponse(Status::Ok, format!("Tag with ID {} deleted successfully", tag_id))),
+        Err(err) => Err(tag_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    put,
+    path = "/tag/{tag_id}",
+    summary = "Update a tag by ID",
+    description = "Rename or recolor a tag, an empty color removes it",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqUpdateTagDto,
+    params(
+        ("tag_id" = String, description = "The ID of the tag"),
+    ),
+    responses(
+        (status = 200, description = "Tag updated successfully", body = ResEntryTagDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 404, description = "Tag not found", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "A tag with this name already exists", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Tag"]
+)]
+#[put("/<tag_id>", data = "<dto>")]
+pub async fn update_tag(
+    user: AuthenticatedUser,
+    tag_id: Uuid,
+    dto: Json<ReqUpdateTagDto>,
+    tag_usecase: &State<Arc<TagUseCase<TagRepositoryImpl>>>,
+) -> OtterResponse<ResEntryTagDto> {
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match tag_usecase.update_tag(user.id, tag_id, dto.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(tag_error_response(err)),
+    }
+}
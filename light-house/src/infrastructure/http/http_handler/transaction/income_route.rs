@@ -4,7 +4,7 @@ use rocket::{delete, get, http::Status, post, put, routes, serde::json::Json, Ro
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::{application::{usecase::{transaction::income_usecase::IncomeUseCase, wrapper::income_wrapper::IncomeRepositoryComposite}, usecase_req_impl::transaction_usecase::RecordIncomeUsecase}, domain::dto::transaction_dto::{ReqCreateIncomeDto, ReqUpdateIncomeDto, ResEntryIncomeDto, ResListIncomeDto}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, transaction_type_repo::TransactionTypeRepositoryImpl}, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}};
+use crate::{application::{usecase::{transaction::income_usecase::IncomeUseCase, wrapper::income_wrapper::IncomeRepositoryComposite}, usecase_req_impl::transaction_usecase::RecordIncomeUsecase}, domain::dto::transaction_dto::{ReqCreateIncomeDto, ReqUpdateIncomeDto, ResEntryIncomeDto, ResListIncomeDto}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, transaction_type_repo::TransactionTypeRepositoryImpl}, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}, soc::soc_usecase::UsecaseError};
 
 
 
@@ -38,6 +38,7 @@ pub fn income_routes() -> Vec<Route> {
         (status = 201, description = "Income record created successfully", body = ResEntryIncomeDto),
         (status = 400, description = "Validation errors", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "The external_reference is already used on the asset", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tags = ["Income"]
@@ -58,6 +59,7 @@ async fn create_income(
     log::info!("Creating income with amount: {}", dto.amount);
     match income_usecase.create_income(user.id, dto.into_inner()).await {
         Ok(res) => Ok(SuccessResponse(Status::Created, res)),
+        Err(UsecaseError::Conflict(msg)) => Err(ErrorResponse(Status::Conflict, msg)),
         Err(err) => {
             let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
             Err(error_response)
@@ -163,6 +165,11 @@ pub async fn update_income(
             ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", "transaction id is empty"))
         );
     }
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
 
     match income_usecase.update_income(user.id, income_id, dto.into_inner()).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
@@ -1,4 +1,5 @@
 pub mod income_route;
 pub mod transaction_type;
 pub mod payment_route;
-pub mod transfer_route;
\ No newline at end of file
+pub mod transfer_route;
+pub mod transaction_route;
\ No newline at end of file
@@ -4,7 +4,7 @@ use rocket::{delete, get, http::Status, post, put, routes, serde::json::Json, Ro
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::{application::{usecase::{transaction::payment_usecase::PaymentUseCase, wrapper::payment_wrapper::PaymentRepositoryComposite}, usecase_req_impl::transaction_usecase::RecordPaymentUsecase}, domain::dto::transaction_dto::{ReqCreatePaymentDto, ReqUpdatePaymentDto, ResEntryPaymentDto, ResListPaymentDto}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, expense_repo::ExpenseRepositoryImpl, transaction_type_repo::TransactionTypeRepositoryImpl}, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}};
+use crate::{application::{usecase::{transaction::payment_usecase::PaymentUseCase, wrapper::payment_wrapper::PaymentRepositoryComposite}, usecase_req_impl::transaction_usecase::RecordPaymentUsecase}, domain::dto::transaction_dto::{ReqCreatePaymentDto, ReqUpdatePaymentDto, ResEntryPaymentDto, ResListPaymentDto}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, expense_repo::ExpenseRepositoryImpl, transaction_type_repo::TransactionTypeRepositoryImpl}, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}, soc::soc_usecase::UsecaseError};
 
 
 
@@ -22,12 +22,27 @@ pub fn payment_routes() -> Vec<Route> {
 }
 
 
+// split lines that do not add up or point at an unknown expense are the caller's mistake
+fn payment_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::ValidationFailed(details) => ErrorResponse(
+            Status::BadRequest,
+            details.into_iter().map(|detail| detail.message).collect::<Vec<_>>().join(", "),
+        ),
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        UsecaseError::Conflict(msg) => ErrorResponse(Status::Conflict, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
 
 #[utoipa::path(
     post,
     path = "/payment",
     summary = "Create a new payment record",
-    description = "Create a new payment",
+    description = "Create a new payment. Give splits instead of expense_id to spread the amount over several expenses, the lines must add up to the amount",
     security(
         ("bearer_auth" = [])
     ),
@@ -36,6 +51,8 @@ pub fn payment_routes() -> Vec<Route> {
         (status = 201, description = "Payment record created successfully", body = ResEntryPaymentDto),
         (status = 400, description = "Validation errors", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 404, description = "Split expense not found", body = ErrorResponse),
+        (status = 409, description = "The external_reference is already used on the asset", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tags = ["Payment"]
@@ -55,10 +72,7 @@ async fn create_payment(
     log::info!("Creating payment with amount: {}", dto.amount);
     match payment_usecase.create_payment(user.id, dto.into_inner()).await {
         Ok(res) => Ok(SuccessResponse(Status::Created, res)),
-        Err(err) => {
-            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
-            Err(error_response)
-        }
+        Err(err) => Err(payment_error_response(err)),
     }
 }
 
@@ -128,7 +142,7 @@ async fn get_payment(
     put,
     path = "/payment/{payment_id}",
     summary = "Update a payment record by ID",
-    description = "Update a payment record by ID",
+    description = "Update a payment record by ID. Splits replace the current lines, an empty list turns it back into a single expense payment",
     security(
         ("bearer_auth" = [])
     ),
@@ -158,14 +172,16 @@ async fn update_payment(
             ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", "payment id is empty"))
         );
     }
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
 
     log::info!("Updating payment with ID: {}", payment_id);
     match payment_usecase.update_payment(user.id, payment_id, dto.into_inner()).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
-        Err(err) => {
-            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
-            Err(error_response)
-        }
+        Err(err) => Err(payment_error_response(err)),
     }
 }
 
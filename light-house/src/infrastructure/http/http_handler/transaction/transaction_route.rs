@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use rocket::{get, http::Status, routes, Route, State};
+
+use crate::{application::{usecase::transaction::transaction_usecase::TransactionUseCase, usecase_req_impl::transaction_usecase::TransactionSearchUsecase}, domain::dto::transaction_dto::{ReqTransactionQueryDto, ResListTransactionDto}, infrastructure::{database::mysql::impl_repository::transaction::transaction_repo::TransactionRepoImpl, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}};
+
+
+
+
+
+
+pub fn transaction_routes() -> Vec<Route> {
+    routes![
+        search_transactions
+    ]
+}
+
+
+
+#[utoipa::path(
+    get,
+    path = "/transaction",
+    summary = "List transactions of every type",
+    description = "List income, payment and transfer records together. Filters are combined with AND, results are sorted by occurred_at or amount and paginated either by page number or by the next_cursor of the previous page",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ReqTransactionQueryDto
+    ),
+    responses(
+        (status = 200, description = "Transactions retrieved successfully", body = ResListTransactionDto),
+        (status = 400, description = "Invalid filter, sort or pagination parameter", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Transaction"]
+)]
+#[get("/?<query..>")]
+pub async fn search_transactions(
+    user: AuthenticatedUser,
+    query: ReqTransactionQueryDto,
+    transaction_usecase: &State<Arc<TransactionUseCase<TransactionRepoImpl>>>,
+) -> OtterResponse<ResListTransactionDto> {
+
+    let search = match query.into_search() {
+        Ok(search) => search,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+
+    match transaction_usecase.search_transactions(user.id, search).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(ErrorResponse(Status::InternalServerError, err.to_string())),
+    }
+}
@@ -4,7 +4,7 @@ use rocket::{delete, get, http::Status, post, put, routes, serde::json::Json, Ro
 use uuid::Uuid;
 use validator::Validate;
 
-use crate::{application::{usecase::{transaction::transfer_usecase::TransferUseCase, wrapper::transfer_wrapper::TransferRepositoryComposite}, usecase_req_impl::transaction_usecase::TransferUsecase}, domain::dto::transaction_dto::{ReqCreateTransferDto, ReqUpdateTransferDto, ResEntryTransferDto, ResListTransferDto}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, transaction_type_repo::TransactionTypeRepositoryImpl}, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}};
+use crate::{application::{usecase::{transaction::transfer_usecase::TransferUseCase, wrapper::transfer_wrapper::TransferRepositoryComposite}, usecase_req_impl::transaction_usecase::TransferUsecase}, domain::dto::transaction_dto::{ReqCreateTransferDto, ReqUpdateTransferDto, ResEntryTransferDto, ResListTransferDto}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, contact_repo::ContactRepositoryImpl, transaction_type_repo::TransactionTypeRepositoryImpl}, http::{faring::authentication::AuthenticatedUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}, soc::soc_usecase::UsecaseError};
 
 
 
@@ -38,6 +38,7 @@ pub fn transfer_routes() -> Vec<Route> {
         (status = 201, description = "Transfer record created successfully", body = ResEntryTransferDto),
         (status = 400, description = "Validation errors", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 409, description = "The external_reference is already used on the asset", body = ErrorResponse),
         (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tags = ["Transfer"]
@@ -57,6 +58,7 @@ async fn create_tranfer(
     log::info!("Creating transfer with amount: {}", dto.amount);
     match transfer_usecase.create_transfer(user.id, dto.into_inner()).await {
         Ok(res) => Ok(SuccessResponse(Status::Created, res)),
+        Err(UsecaseError::Conflict(msg)) => Err(ErrorResponse(Status::Conflict, msg)),
         Err(err) => {
             let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
             Err(error_response)
@@ -157,6 +159,11 @@ async fn update_transfer(
             ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", "Id is empty"))
         );
     }
+    if let Err(errors) = dto.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
 
     match transfer_usecase.update_transfer(user.id, id, dto.into_inner()).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
@@ -0,0 +1,203 @@
+use std::sync::Arc;
+
+use rocket::{get, http::Status, post, routes, serde::json::Json, Route, State};
+use validator::Validate;
+
+use crate::{application::{usecase::{user_usecase::UserUseCase, wrapper::user_wrapper::UserRepositoryComposite}, usecase_req_impl::user_usecase::UserTwoFactorUsecase}, domain::dto::two_factor_dto::{ReqTwoFactorCodeDto, ReqTwoFactorPasswordDto, ResTwoFactorEnrolmentDto, ResTwoFactorRecoveryCodesDto, ResTwoFactorStatusDto}, infrastructure::http::{faring::{authentication::AuthenticatedUser, client_info::ClientInfo}, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}, soc::soc_usecase::UsecaseError};
+
+
+
+
+pub fn two_factor_routes() -> Vec<Route> {
+    routes![
+        view_two_factor,
+        begin_enrolment,
+        confirm_enrolment,
+        regenerate_recovery_codes,
+        disable_two_factor
+    ]
+}
+
+
+fn two_factor_error_response(err: UsecaseError) -> ErrorResponse {
+    match err {
+        UsecaseError::InvalidData(msg) => ErrorResponse(Status::BadRequest, msg),
+        UsecaseError::PermissionDenied(msg) => ErrorResponse(Status::Forbidden, msg),
+        UsecaseError::ResourceNotFound(msg) => ErrorResponse(Status::NotFound, msg),
+        UsecaseError::TooManyRequests(msg) => ErrorResponse(Status::TooManyRequests, msg),
+        err => ErrorResponse(Status::InternalServerError, err.to_string()),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    get,
+    path = "/two-factor",
+    summary = "Get the two factor status",
+    description = "Whether two factor sign in is on, an enrolment is waiting for its first code, and how many recovery codes are left",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Two factor status", body = ResTwoFactorStatusDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Two factor"]
+)]
+#[get("/")]
+pub async fn view_two_factor(
+    user: AuthenticatedUser,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResTwoFactorStatusDto> {
+    match user_usecase.get_two_factor_status(user.id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(two_factor_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/two-factor/enrol",
+    summary = "Start a two factor enrolment",
+    description = "Generate a new TOTP secret for an authenticator app. Two factor stays off until a code from the app is posted to /two-factor/confirm, starting again replaces a pending secret",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Secret generated", body = ResTwoFactorEnrolmentDto),
+        (status = 400, description = "Two factor is already on", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Two factor"]
+)]
+#[post("/enrol")]
+pub async fn begin_enrolment(
+    user: AuthenticatedUser,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResTwoFactorEnrolmentDto> {
+    match user_usecase.begin_two_factor_enrolment(user.id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(two_factor_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/two-factor/confirm",
+    summary = "Turn two factor on",
+    description = "Confirm the enrolment with a code from the authenticator app. The answer holds the recovery codes, they are not shown again",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqTwoFactorCodeDto,
+    responses(
+        (status = 200, description = "Two factor is on", body = ResTwoFactorRecoveryCodesDto),
+        (status = 400, description = "Wrong code or no pending enrolment", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Two factor"]
+)]
+#[post("/confirm", data = "<req_code>")]
+pub async fn confirm_enrolment(
+    user: AuthenticatedUser,
+    req_code: Json<ReqTwoFactorCodeDto>,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResTwoFactorRecoveryCodesDto> {
+    if let Err(errors) = req_code.validate() {
+        return Err(ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors)));
+    }
+
+    match user_usecase.confirm_two_factor_enrolment(user.id, req_code.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(two_factor_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/two-factor/recovery-codes",
+    summary = "Replace the recovery codes",
+    description = "Generate a new set of recovery codes, the previous ones stop working. Asks for the password again, held to the sign in attempt limits",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqTwoFactorPasswordDto,
+    responses(
+        (status = 200, description = "New recovery codes", body = ResTwoFactorRecoveryCodesDto),
+        (status = 400, description = "Two factor is off", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Wrong password", body = ErrorResponse),
+        (status = 429, description = "Too many wrong passwords, try again later", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Two factor"]
+)]
+#[post("/recovery-codes", data = "<req_password>")]
+pub async fn regenerate_recovery_codes(
+    user: AuthenticatedUser,
+    req_password: Json<ReqTwoFactorPasswordDto>,
+    client: ClientInfo,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResTwoFactorRecoveryCodesDto> {
+    if let Err(errors) = req_password.validate() {
+        return Err(ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors)));
+    }
+
+    match user_usecase.regenerate_recovery_codes(user.id, req_password.into_inner(), client.into()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(two_factor_error_response(err)),
+    }
+}
+
+
+
+
+#[utoipa::path(
+    post,
+    path = "/two-factor/disable",
+    summary = "Turn two factor off",
+    description = "Remove the secret and the recovery codes, signing in needs only the password again. Asks for the password again, held to the sign in attempt limits",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReqTwoFactorPasswordDto,
+    responses(
+        (status = 200, description = "Two factor is off", body = ResTwoFactorStatusDto),
+        (status = 400, description = "Two factor is off already", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Wrong password", body = ErrorResponse),
+        (status = 429, description = "Too many wrong passwords, try again later", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Two factor"]
+)]
+#[post("/disable", data = "<req_password>")]
+pub async fn disable_two_factor(
+    user: AuthenticatedUser,
+    req_password: Json<ReqTwoFactorPasswordDto>,
+    client: ClientInfo,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>,
+) -> OtterResponse<ResTwoFactorStatusDto> {
+    if let Err(errors) = req_password.validate() {
+        return Err(ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors)));
+    }
+
+    match user_usecase.disable_two_factor(user.id, req_password.into_inner(), client.into()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => Err(two_factor_error_response(err)),
+    }
+}
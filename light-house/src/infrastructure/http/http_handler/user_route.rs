@@ -1,9 +1,9 @@
 use std::sync::Arc;
 
-use rocket::{get, http::Status, post, put, routes, serde::json::Json, Route, State};
+use rocket::{delete, get, http::Status, post, put, routes, serde::json::Json, Route, State};
 use validator::Validate;
 
-use crate::{application::{usecase::{user_usecase::UserUseCase, wrapper::user_wrapper::UserRepositoryComposite}, usecase_req_impl::user_usecase::UserUsecase}, domain::dto::auth_dto::{ReqSignInDto, ReqSignUpDto, ReqUpdateUserDto, ResMcpDto, ResMeDto, ResSignInDto}, infrastructure::http::{faring::{authentication::AuthenticatedUser, cors::options}, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}};
+use crate::{application::{usecase::{user_usecase::UserUseCase, wrapper::user_wrapper::UserRepositoryComposite}, usecase_req_impl::{user_data_usecase::UserDataUsecase, user_usecase::{UserAccountUsecase, UserSessionUsecase, UserTwoFactorUsecase, UserUsecase}}}, domain::dto::{account_dto::{ReqForgotPasswordDto, ReqResetPasswordDto, ReqVerifyEmailDto, ResAccountMessageDto}, auth_dto::{ReqSignInDto, ReqSignUpDto, ReqUpdateUserDto, ResMeDto, ResSignInDto, ResSignInStepDto}, session_dto::ReqRefreshTokenDto, two_factor_dto::ReqTwoFactorSignInDto, user_data_dto::{ReqDeleteAccountDto, ResUserExportDto}}, infrastructure::http::{faring::{authentication::AuthenticatedUser, client_info::ClientInfo, cors::options}, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}, initiation::init_usecase_setup::UserDataUseCaseImpl, soc::soc_usecase::UsecaseError};
 
 
 
@@ -11,10 +11,18 @@ use crate::{application::{usecase::{user_usecase::UserUseCase, wrapper::user_wra
 pub fn user_routes() -> Vec<Route> {
     routes![
         sign_in,
+        sign_in_two_factor,
         sign_up,
+        sign_out,
+        refresh_token,
+        verify_email,
+        resend_email_verification,
+        forgot_password,
+        reset_password,
         update_user,
+        export_user_data,
+        delete_account,
         me,
-        get_mcp,
         options
         // get_users,
         // create_user,
@@ -27,19 +35,24 @@ pub fn user_routes() -> Vec<Route> {
 #[utoipa::path(
     post,
     path = "/sign-in",
+    description = "Failed attempts are counted per email and per ip, past a few failures each attempt has to wait longer and too many lock the account out for a while. When two factor is on the answer is a challenge to post to /sign-in/two-factor with a code instead of the token pair",
     request_body = ReqSignInDto,
     responses(
-        (status = 200, description = "User signed in successfully", body = ResSignInDto),
+        (status = 200, description = "User signed in successfully, or two factor required", body = ResSignInStepDto),
         (status = 400, description = "Validation errors", body = ErrorResponse),
-        (status = 500, description = "Invalid email or password", body = ErrorResponse)
+        (status = 401, description = "Invalid email or password", body = ErrorResponse),
+        (status = 403, description = "Account is disabled", body = ErrorResponse),
+        (status = 429, description = "Too many failed attempts, try again later", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
     tags = ["Auth"]
 )]
 #[post("/sign-in", data = "<req_sign_in>")]
 pub async fn sign_in(
     req_sign_in: Json<ReqSignInDto>,
+    client: ClientInfo,
     user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
-) -> OtterResponse<ResSignInDto>
+) -> OtterResponse<ResSignInStepDto>
 {
      // field empty Bad request
     if let Err(errors) = req_sign_in.validate() {
@@ -47,8 +60,11 @@ pub async fn sign_in(
             ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
         );
     }
-    match user_usecase.login(req_sign_in.into_inner()).await {
+    match user_usecase.login(req_sign_in.into_inner(), client.into()).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(UsecaseError::ResourceNotFound(msg)) => Err(ErrorResponse(Status::Unauthorized, msg)),
+        Err(UsecaseError::PermissionDenied(msg)) => Err(ErrorResponse(Status::Forbidden, msg)),
+        Err(UsecaseError::TooManyRequests(msg)) => Err(ErrorResponse(Status::TooManyRequests, msg)),
         Err(err) => {
             let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
             Err(error_response)
@@ -59,6 +75,117 @@ pub async fn sign_in(
 
 
 
+#[utoipa::path(
+    post,
+    path = "/sign-in/two-factor",
+    summary = "Finish a two factor sign in",
+    description = "Trade the challenge of /sign-in and a code from the authenticator app, or an unused recovery code, for the token pair. Wrong codes count against the same limits as wrong passwords",
+    request_body = ReqTwoFactorSignInDto,
+    responses(
+        (status = 200, description = "User signed in successfully", body = ResSignInDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 401, description = "Invalid or expired challenge, or a wrong code", body = ErrorResponse),
+        (status = 403, description = "Account is disabled", body = ErrorResponse),
+        (status = 429, description = "Too many failed attempts, try again later", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Auth"]
+)]
+#[post("/sign-in/two-factor", data = "<req_two_factor>")]
+pub async fn sign_in_two_factor(
+    req_two_factor: Json<ReqTwoFactorSignInDto>,
+    client: ClientInfo,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
+) -> OtterResponse<ResSignInDto>
+{
+    if let Err(errors) = req_two_factor.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+    match user_usecase.verify_two_factor_sign_in(req_two_factor.into_inner(), client.into()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(UsecaseError::ResourceNotFound(msg)) => Err(ErrorResponse(Status::Unauthorized, msg)),
+        Err(UsecaseError::PermissionDenied(msg)) => Err(ErrorResponse(Status::Forbidden, msg)),
+        Err(UsecaseError::TooManyRequests(msg)) => Err(ErrorResponse(Status::TooManyRequests, msg)),
+        Err(err) => {
+            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
+            Err(error_response)
+        }
+    }
+}
+
+
+
+#[utoipa::path(
+    post,
+    path = "/sign-out",
+    summary = "Sign out",
+    description = "Revoke the session of the access token, its access and refresh token stop working at once",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 204, description = "Signed out"),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Auth"]
+)]
+#[post("/sign-out")]
+pub async fn sign_out(
+    user: AuthenticatedUser,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
+) -> OtterResponse<()> {
+    match user_usecase.sign_out(user.id, user.session_id).await {
+        Ok(()) => Ok(SuccessResponse(Status::NoContent, ())),
+        Err(err) => {
+            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
+            Err(error_response)
+        }
+    }
+}
+
+
+
+#[utoipa::path(
+    post,
+    path = "/token/refresh",
+    summary = "Refresh the access token",
+    description = "Exchange a refresh token for a new access and refresh token. Each refresh token works once, presenting one that was already exchanged revokes its session",
+    request_body = ReqRefreshTokenDto,
+    responses(
+        (status = 200, description = "Tokens refreshed", body = ResSignInDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 401, description = "Invalid, expired or revoked refresh token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Auth"]
+)]
+#[post("/token/refresh", data = "<req_refresh>")]
+pub async fn refresh_token(
+    req_refresh: Json<ReqRefreshTokenDto>,
+    client: ClientInfo,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
+) -> OtterResponse<ResSignInDto> {
+    if let Err(errors) = req_refresh.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match user_usecase.refresh_token(req_refresh.into_inner(), client.into()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(UsecaseError::ResourceNotFound(msg)) => Err(ErrorResponse(Status::Unauthorized, msg)),
+        Err(err) => {
+            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
+            Err(error_response)
+        }
+    }
+}
+
+
+
 #[utoipa::path(
     post,
     path = "/sign-up",
@@ -93,19 +220,166 @@ pub async fn sign_up(
 
 
 
+#[utoipa::path(
+    post,
+    path = "/email/verify",
+    summary = "Verify the email address",
+    description = "Confirm the address with the token from the verification mail. A token works once and only for the address it was sent to",
+    request_body = ReqVerifyEmailDto,
+    responses(
+        (status = 200, description = "Email verified", body = ResAccountMessageDto),
+        (status = 400, description = "Invalid, used or expired token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Auth"]
+)]
+#[post("/email/verify", data = "<req_verify>")]
+pub async fn verify_email(
+    req_verify: Json<ReqVerifyEmailDto>,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
+) -> OtterResponse<ResAccountMessageDto> {
+    if let Err(errors) = req_verify.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match user_usecase.verify_email(req_verify.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(UsecaseError::InvalidData(msg)) => Err(ErrorResponse(Status::BadRequest, msg)),
+        Err(err) => {
+            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
+            Err(error_response)
+        }
+    }
+}
+
+
+
+#[utoipa::path(
+    post,
+    path = "/email/verify/resend",
+    summary = "Resend the verification mail",
+    description = "Mail a new verification link to the current address of the user, the previous link stops working",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Verification mail sent", body = ResAccountMessageDto),
+        (status = 400, description = "Email is already verified", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 429, description = "A mail was sent recently, try again later", body = ErrorResponse),
+        (status = 502, description = "The mail could not be sent", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Auth"]
+)]
+#[post("/email/verify/resend")]
+pub async fn resend_email_verification(
+    user: AuthenticatedUser,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
+) -> OtterResponse<ResAccountMessageDto> {
+    match user_usecase.send_email_verification(user.id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(UsecaseError::InvalidData(msg)) => Err(ErrorResponse(Status::BadRequest, msg)),
+        Err(UsecaseError::TooManyRequests(msg)) => Err(ErrorResponse(Status::TooManyRequests, msg)),
+        Err(UsecaseError::ExternalServiceError(msg)) => Err(ErrorResponse(Status::BadGateway, msg)),
+        Err(err) => {
+            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
+            Err(error_response)
+        }
+    }
+}
+
+
+
+#[utoipa::path(
+    post,
+    path = "/password/forgot",
+    summary = "Request a password reset",
+    description = "Mail a single use link to reset the password. The answer is the same whether or not the email belongs to an account",
+    request_body = ReqForgotPasswordDto,
+    responses(
+        (status = 202, description = "Request accepted", body = ResAccountMessageDto),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Auth"]
+)]
+#[post("/password/forgot", data = "<req_forgot>")]
+pub async fn forgot_password(
+    req_forgot: Json<ReqForgotPasswordDto>,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
+) -> OtterResponse<ResAccountMessageDto> {
+    if let Err(errors) = req_forgot.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match user_usecase.forgot_password(req_forgot.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Accepted, res)),
+        Err(err) => {
+            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
+            Err(error_response)
+        }
+    }
+}
+
+
+
+#[utoipa::path(
+    post,
+    path = "/password/reset",
+    summary = "Reset the password",
+    description = "Set a new password with the token from the reset mail. The token works once, every session of the user is revoked",
+    request_body = ReqResetPasswordDto,
+    responses(
+        (status = 200, description = "Password reset", body = ResAccountMessageDto),
+        (status = 400, description = "Validation errors or an invalid, used or expired token", body = ErrorResponse),
+        (status = 500, description = "Internal server error", body = ErrorResponse)
+    ),
+    tags = ["Auth"]
+)]
+#[post("/password/reset", data = "<req_reset>")]
+pub async fn reset_password(
+    req_reset: Json<ReqResetPasswordDto>,
+    user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
+) -> OtterResponse<ResAccountMessageDto> {
+    if let Err(errors) = req_reset.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match user_usecase.reset_password(req_reset.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(UsecaseError::InvalidData(msg)) => Err(ErrorResponse(Status::BadRequest, msg)),
+        Err(err) => {
+            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
+            Err(error_response)
+        }
+    }
+}
+
+
+
 #[utoipa::path(
     put,
     path = "/user",
     summary = "Update user",
-    description = "Update user information",
+    description = "Update user information. Changing the password or the email requires current_password, which is held to the sign in attempt limits. A new email has to be verified again, a new password signs out every other session",
     security(
         ("bearer_auth" = [])
     ),
     request_body = ReqUpdateUserDto,
     responses(
         (status = 200, description = "User updated successfully", body = ResMeDto),
-        (status = 400, description = "Validation errors", body = ErrorResponse),
+        (status = 400, description = "Validation errors or current_password missing", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Current password is incorrect", body = ErrorResponse),
+        (status = 409, description = "Username or email already exists", body = ErrorResponse),
+        (status = 429, description = "Too many wrong passwords, try again later", body = ErrorResponse),
         (status = 500, description = "User might not be exist", body = ErrorResponse)
     ),
     tags = ["User"]
@@ -114,6 +388,7 @@ pub async fn sign_up(
 pub async fn update_user(
     user: AuthenticatedUser,
     req_update_user: Json<ReqUpdateUserDto>,
+    client: ClientInfo,
     user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
 ) -> OtterResponse<ResMeDto> {
     if let Err(errors) = req_update_user.validate() {
@@ -122,8 +397,12 @@ pub async fn update_user(
         );
     }
 
-    match user_usecase.update_user(user.id, req_update_user.into_inner()).await {
+    match user_usecase.update_user(user.id, user.session_id, req_update_user.into_inner(), client.into()).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(UsecaseError::InvalidData(msg)) => Err(ErrorResponse(Status::BadRequest, msg)),
+        Err(UsecaseError::PermissionDenied(msg)) => Err(ErrorResponse(Status::Forbidden, msg)),
+        Err(UsecaseError::Conflict(msg)) => Err(ErrorResponse(Status::Conflict, msg)),
+        Err(UsecaseError::TooManyRequests(msg)) => Err(ErrorResponse(Status::TooManyRequests, msg)),
         Err(err) => {
             let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
             Err(error_response)
@@ -134,24 +413,26 @@ pub async fn update_user(
 
 
 #[utoipa::path(
-    post,
-    path = "/me",
-    summary = "Get user information",
-    description = "Get user information",
+    get,
+    path = "/user/export",
+    summary = "Export all personal data",
+    description = "Everything stored about the user in one document, one list per table with ids as uuid strings. Password and token hashes and the two factor secret are left out",
     security(
         ("bearer_auth" = [])
     ),
     responses(
-        (status = 200, description = "User information retrieved successfully", body = ResMeDto),
+        (status = 200, description = "Export of the user's data", body = ResUserExportDto),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 500, description = "Invalid token can't perform", body = ErrorResponse)
+        (status = 500, description = "Internal server error", body = ErrorResponse)
     ),
-    tags = ["Auth"]
+    tags = ["User"]
 )]
-#[post("/me")]
-pub async fn me(user: AuthenticatedUser, user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>) -> OtterResponse<ResMeDto> {
-    // This function is not implemented yet
-    match user_usecase.me(user.id).await {
+#[get("/user/export")]
+pub async fn export_user_data(
+    user: AuthenticatedUser,
+    user_data_usecase: &State<Arc<UserDataUseCaseImpl>>
+) -> OtterResponse<ResUserExportDto> {
+    match user_data_usecase.export_user_data(user.id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
             let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
@@ -162,29 +443,71 @@ pub async fn me(user: AuthenticatedUser, user_usecase: &State<Arc<UserUseCase<Us
 
 
 
-#[get("/mcp")]
 #[utoipa::path(
-    get,
-    path = "/mcp",
-    summary = "Get MCP token",
-    description = "Get MCP token for the authenticated user",
+    delete,
+    path = "/user",
+    summary = "Delete the account",
+    description = "Remove the user and everything they own: transactions, balances, assets, contacts, expenses, budgets, recurring transactions, tokens and sessions, all in one database transaction. Asks for the password again and can not be undone",
     security(
         ("bearer_auth" = [])
     ),
+    request_body = ReqDeleteAccountDto,
     responses(
-        (status = 200, description = "MCP token retrieved successfully", body = ResMcpDto),
+        (status = 204, description = "Account deleted"),
+        (status = 400, description = "Validation errors", body = ErrorResponse),
         (status = 401, description = "Unauthorized", body = ErrorResponse),
-        (status = 500, description = "Error retrieving MCP token", body = ErrorResponse)
+        (status = 403, description = "Wrong password", body = ErrorResponse),
+        (status = 500, description = "Internal server error, nothing was deleted", body = ErrorResponse)
     ),
-    tags = ["MCP"]
+    tags = ["User"]
 )]
-pub async fn get_mcp(user: AuthenticatedUser, user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>) -> OtterResponse<ResMcpDto> {
+#[delete("/user", data = "<req_delete>")]
+pub async fn delete_account(
+    user: AuthenticatedUser,
+    req_delete: Json<ReqDeleteAccountDto>,
+    user_data_usecase: &State<Arc<UserDataUseCaseImpl>>
+) -> OtterResponse<()> {
+    if let Err(errors) = req_delete.validate() {
+        return Err(
+            ErrorResponse(Status::BadRequest, format!("Validation errors: {:?}", errors))
+        );
+    }
+
+    match user_data_usecase.delete_account(user.id, req_delete.into_inner()).await {
+        Ok(()) => Ok(SuccessResponse(Status::NoContent, ())),
+        Err(UsecaseError::PermissionDenied(msg)) => Err(ErrorResponse(Status::Forbidden, msg)),
+        Err(err) => {
+            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
+            Err(error_response)
+        }
+    }
+}
+
+
+
+#[utoipa::path(
+    post,
+    path = "/me",
+    summary = "Get user information",
+    description = "Get user information",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "User information retrieved successfully", body = ResMeDto),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 500, description = "Invalid token can't perform", body = ErrorResponse)
+    ),
+    tags = ["Auth"]
+)]
+#[post("/me")]
+pub async fn me(user: AuthenticatedUser, user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>) -> OtterResponse<ResMeDto> {
     // This function is not implemented yet
-    match user_usecase.get_mcp_token(user.id).await {
+    match user_usecase.me(user.id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
             let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
             Err(error_response)
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,53 @@
+use std::path::PathBuf;
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{domain::{dto::account_dto::MailMessageDto, req_repository::mailer_repository::Mailer}, soc::soc_repository::RepositoryError};
+
+
+
+
+// MAILER=file (default), every mail becomes a text file in the outbox and a log line,
+// so the links can be followed without a mail server
+pub struct FileMailer {
+    outbox_dir: PathBuf,
+    from: String,
+}
+
+impl FileMailer {
+    pub fn new(outbox_dir: impl Into<PathBuf>, from: impl Into<String>) -> Self {
+        Self {
+            outbox_dir: outbox_dir.into(),
+            from: from.into(),
+        }
+    }
+}
+
+
+#[async_trait::async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, message: MailMessageDto) -> Result<(), RepositoryError>
+    {
+        let now = Utc::now();
+        let path = self.outbox_dir.join(format!("{}-{}.eml", now.format("%Y%m%dT%H%M%S"), Uuid::new_v4()));
+        let content = format!(
+            "From: {}\r\nTo: {}\r\nDate: {}\r\nSubject: {}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{}\r\n",
+            self.from,
+            message.to,
+            now.to_rfc2822(),
+            message.subject,
+            message.body,
+        );
+
+        tokio::fs::create_dir_all(&self.outbox_dir)
+            .await
+            .map_err(|err| RepositoryError::OperationFailed(format!("Failed to create the mail outbox: {}", err)))?;
+        tokio::fs::write(&path, content)
+            .await
+            .map_err(|err| RepositoryError::OperationFailed(format!("Failed to write mail: {}", err)))?;
+
+        log::info!("Mail '{}' to {} written to {}", message.subject, message.to, path.display());
+        Ok(())
+    }
+}
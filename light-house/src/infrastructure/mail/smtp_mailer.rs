@@ -0,0 +1,64 @@
+use lettre::{message::{header::ContentType, Mailbox}, transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::{configuration::mail_config::{MailConfig, SmtpSecurity}, domain::{dto::account_dto::MailMessageDto, req_repository::mailer_repository::Mailer}, soc::soc_repository::RepositoryError};
+
+
+
+
+// MAILER=smtp, connections are pooled by the transport
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(config: &MailConfig) -> Result<Self, RepositoryError> {
+        let from = config.from
+            .parse::<Mailbox>()
+            .map_err(|err| RepositoryError::InvalidInput(format!("Invalid MAIL_FROM '{}': {}", config.from, err)))?;
+
+        let mut builder = match config.smtp_security {
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host),
+            SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host),
+            SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.smtp_host)),
+        }
+        .map_err(|err| RepositoryError::InvalidInput(format!("Invalid SMTP_HOST '{}': {}", config.smtp_host, err)))?;
+
+        if let Some(port) = config.smtp_port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from,
+        })
+    }
+}
+
+
+#[async_trait::async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, message: MailMessageDto) -> Result<(), RepositoryError>
+    {
+        let to = message.to
+            .parse::<Mailbox>()
+            .map_err(|err| RepositoryError::InvalidInput(format!("Invalid recipient '{}': {}", message.to, err)))?;
+        let email = Message::builder()
+            .from(self.from.clone())
+            .to(to)
+            .subject(message.subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(message.body)
+            .map_err(|err| RepositoryError::InvalidInput(format!("Failed to build mail: {}", err)))?;
+
+        self.transport
+            .send(email)
+            .await
+            .map_err(|err| RepositoryError::OperationFailed(format!("Failed to send mail: {}", err)))?;
+
+        Ok(())
+    }
+}
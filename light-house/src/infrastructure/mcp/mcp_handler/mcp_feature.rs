@@ -1,8 +1,9 @@
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc};
 
-use rocket::{get, http::Status, routes, Route, State};
+use rocket::{get, http::Status, post, routes, serde::json::Json, Route, State};
+use uuid::Uuid;
 
-use crate::{application::{usecase::{asset_type_usecase::AssetTypeUseCase, asset_usecase::AssetUseCase, contact_type_usecase::ContactTypeUseCase, contact_usecase::ContactUseCase, expense_type_usecase::ExpenseTypeUseCase, expense_usecase::ExpenseUseCase, transaction::{income_usecase::IncomeUseCase, payment_usecase::PaymentUseCase, transfer_usecase::TransferUseCase}, user_usecase::UserUseCase, wrapper::{income_wrapper::IncomeRepositoryComposite, payment_wrapper::PaymentRepositoryComposite, transfer_wrapper::TransferRepositoryComposite, user_wrapper::UserRepositoryComposite}}, usecase_req_impl::{asset_type_usecase::AssetTypeUsecase, asset_usecase::AssetUsecase, contact_type_usecase::ContactTypeUsecase, contact_usecase::ContactUsecase, expense_type_usecase::ExpenseTypeUsecase, expense_usecase::ExpenseUsecase, transaction_usecase::{RecordIncomeUsecase, RecordPaymentUsecase, TransferUsecase}, user_usecase::UserUsecase}}, domain::dto::{assest_type_dto::ResListAssestTypeDto, asset_dto::ResListAssetDto, auth_dto::ResMeDto, contact_dto::ResListContactDto, contact_type_dto::ResListContactTypeDto, expense_dto::ResListExpenseDto, expense_type_dto::ResListExpenseTypeDto, transaction_dto::{ResListIncomeDto, ResListPaymentDto, ResListTransferDto}}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, asset_type_repo::AssetTypeRepositoryImpl, contact_repo::ContactRepositoryImpl, contact_type_repo::ContactTypeRepositoryImpl, expense_repo::ExpenseRepositoryImpl, expense_type_repos::ExpenseTypeRepositoryImpl, transaction_type_repo::TransactionTypeRepositoryImpl}, http::{faring::mcp_auth::McpAuthenticateUser, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}};
+use crate::{application::{usecase::{asset_type_usecase::AssetTypeUseCase, asset_usecase::AssetUseCase, report_usecase::ReportUseCase, contact_type_usecase::ContactTypeUseCase, contact_usecase::ContactUseCase, expense_type_usecase::ExpenseTypeUseCase, expense_usecase::ExpenseUseCase, transaction::{income_usecase::IncomeUseCase, payment_usecase::PaymentUseCase, transfer_usecase::TransferUseCase}, user_usecase::UserUseCase, wrapper::{income_wrapper::IncomeRepositoryComposite, payment_wrapper::PaymentRepositoryComposite, transfer_wrapper::TransferRepositoryComposite, user_wrapper::UserRepositoryComposite}}, usecase_req_impl::{asset_type_usecase::AssetTypeUsecase, asset_usecase::AssetUsecase, report_usecase::ReportUsecase, contact_type_usecase::ContactTypeUsecase, contact_usecase::ContactUsecase, expense_type_usecase::ExpenseTypeUsecase, expense_usecase::ExpenseUsecase, mcp_operation_usecase::McpOperationUsecase, transaction_usecase::{RecordIncomeUsecase, RecordPaymentUsecase, TransferUsecase}, user_usecase::UserUsecase}}, domain::dto::{assest_type_dto::ResListAssestTypeDto, dto_enum::{api_token_scope::ApiTokenScopeVariant, expense_report_group::ExpenseReportGroupVariant}, report_dto::ResExpenseReportDto, asset_dto::ResListAssetDto, auth_dto::ResMeDto, contact_dto::ResListContactDto, contact_type_dto::ResListContactTypeDto, expense_dto::ResListExpenseDto, expense_type_dto::ResListExpenseTypeDto, mcp_operation_dto::{ReqMcpProposeOperationDto, ResMcpPendingOperationDto}, transaction_dto::{ResListIncomeDto, ResListPaymentDto, ResListTransferDto}}, implentation::date_time_utill::{parse_to_datetime_utc_end_of_day, parse_to_datetime_utc_start_of_day}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, report_repo::ReportRepositoryImpl, asset_type_repo::AssetTypeRepositoryImpl, contact_repo::ContactRepositoryImpl, contact_type_repo::ContactTypeRepositoryImpl, expense_repo::ExpenseRepositoryImpl, expense_type_repos::ExpenseTypeRepositoryImpl, transaction_type_repo::TransactionTypeRepositoryImpl}, http::{faring::mcp_auth::McpAuthenticateUser, http_handler::mcp_operation_route::mcp_operation_error_response, response::otter_response::{ErrorResponse, OtterResponse, SuccessResponse}}}, initiation::init_usecase_setup::McpOperationUseCaseImpl};
 
 
 // init_route
@@ -25,6 +26,13 @@ pub fn mcp_routes() -> Vec<Route>{
         mcp_get_all_contact,
         mcp_get_all_expense,
         mcp_get_all_asset,
+
+        // >>> report
+        mcp_get_expense_report,
+
+        // >>> operation
+        mcp_propose_operation,
+        mcp_get_operation,
     ]
 }
 
@@ -36,6 +44,7 @@ pub async fn mcp_resme(
     user: McpAuthenticateUser,
     user_usecase: &State<Arc<UserUseCase<UserRepositoryComposite>>>
 ) -> OtterResponse<ResMeDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match user_usecase.me(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -53,6 +62,7 @@ pub async fn mcp_get_all_income(
     user: McpAuthenticateUser,
     income_usecase: &State<Arc<IncomeUseCase<IncomeRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl>>>
 ) -> OtterResponse<ResListIncomeDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match income_usecase.get_all_income(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -68,6 +78,7 @@ pub async fn mcp_get_all_payment(
     user: McpAuthenticateUser,
     payment_usecase: &State<Arc<PaymentUseCase<PaymentRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl, ExpenseRepositoryImpl>>>
 ) -> OtterResponse<ResListPaymentDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match payment_usecase.get_all_payment(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -83,6 +94,7 @@ pub async fn mcp_get_all_transfer(
     user: McpAuthenticateUser,
     transfer_usecase: &State<Arc<TransferUseCase<TransferRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl>>>
 ) -> OtterResponse<ResListTransferDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match transfer_usecase.get_all_transfer(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -100,6 +112,7 @@ pub async fn mcp_get_all_contact_type(
     user: McpAuthenticateUser,
     contact_type_usecase: &State<Arc<ContactTypeUseCase<ContactTypeRepositoryImpl>>>,
 ) -> OtterResponse<ResListContactTypeDto>{
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match contact_type_usecase.get_all_contact_type(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -114,6 +127,7 @@ pub async fn mcp_get_all_expense_type(
     user: McpAuthenticateUser,
     expense_type_usecase: &State<Arc<ExpenseTypeUseCase<ExpenseTypeRepositoryImpl>>>,
 ) -> OtterResponse<ResListExpenseTypeDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match expense_type_usecase.get_all_expense_type(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -128,6 +142,7 @@ pub async fn mcp_get_all_asset_type(
     user: McpAuthenticateUser,
     asset_type_usecase: &State<Arc<AssetTypeUseCase<AssetTypeRepositoryImpl>>>,
 ) -> OtterResponse<ResListAssestTypeDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match asset_type_usecase.get_all_asset_types(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -146,6 +161,7 @@ pub async fn mcp_get_all_contact(
     user: McpAuthenticateUser,
     contact_usecase: &State<Arc<ContactUseCase<ContactRepositoryImpl>>>,
 ) -> OtterResponse<ResListContactDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match contact_usecase.get_all_contact(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -160,6 +176,7 @@ pub async fn mcp_get_all_expense(
     user: McpAuthenticateUser,
     expense_usecase: &State<Arc<ExpenseUseCase<ExpenseRepositoryImpl>>>,
 ) -> OtterResponse<ResListExpenseDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match expense_usecase.get_all_expense(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -174,6 +191,7 @@ pub async fn mcp_get_all_asset(
     user: McpAuthenticateUser,
     asset_usecase: &State<Arc<AssetUseCase<AssetRepositoryImpl>>>,
 ) -> OtterResponse<ResListAssetDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
     match asset_usecase.get_all_asset(user.user_id).await {
         Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
         Err(err) => {
@@ -181,4 +199,79 @@ pub async fn mcp_get_all_asset(
             Err(error_response)
         }
     }
-}
\ No newline at end of file
+}
+
+
+
+// >>> report
+// read only, answers "where did my money go" for a date range
+#[get("/report/expenses?<from>&<to>&<group_by>&<tag_id>")]
+pub async fn mcp_get_expense_report(
+    user: McpAuthenticateUser,
+    from: &str,
+    to: &str,
+    group_by: Option<&str>,
+    tag_id: Option<&str>,
+    report_usecase: &State<Arc<ReportUseCase<ReportRepositoryImpl>>>,
+) -> OtterResponse<ResExpenseReportDto> {
+    user.require_scope(ApiTokenScopeVariant::Reports)?;
+    let from = match parse_to_datetime_utc_start_of_day(from) {
+        Ok(from) => from,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    let to = match parse_to_datetime_utc_end_of_day(to) {
+        Ok(to) => to,
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    if from > to {
+        return Err(ErrorResponse(Status::BadRequest, "from must not be after to".to_string()));
+    }
+    let group_by = match group_by.map(ExpenseReportGroupVariant::from_str).transpose() {
+        Ok(group_by) => group_by.unwrap_or(ExpenseReportGroupVariant::ExpenseType),
+        Err(err) => return Err(ErrorResponse(Status::BadRequest, err)),
+    };
+    let tag_id = match tag_id.map(Uuid::parse_str).transpose() {
+        Ok(tag_id) => tag_id,
+        Err(_) => return Err(ErrorResponse(Status::BadRequest, "Invalid tag_id".to_string())),
+    };
+
+    match report_usecase.get_expense_report(user.user_id, from, to, group_by, tag_id).await {
+        Ok(res) => Ok(SuccessResponse(Status::Ok, res)),
+        Err(err) => {
+            let error_response = ErrorResponse(Status::InternalServerError, err.to_string());
+            Err(error_response)
+        }
+    }
+}
+
+
+
+
+// >>> operation, writes wait for the user to confirm them over the JWT API
+#[post("/operation", data = "<dto>")]
+pub async fn mcp_propose_operation(
+    user: McpAuthenticateUser,
+    dto: Json<ReqMcpProposeOperationDto>,
+    mcp_operation_usecase: &State<Arc<McpOperationUseCaseImpl>>,
+) -> OtterResponse<ResMcpPendingOperationDto> {
+    user.require_scope(dto.operation.required_scope())?;
+    match mcp_operation_usecase.propose_operation(user.user_id, dto.into_inner()).await {
+        Ok(res) => Ok(SuccessResponse(Status::Created, res)),
+        Err(err) => Err(mcp_operation_error_response(err)),
+    }
+}
+
+
+#[get("/operation/<operation_id>")]
+pub async fn mcp_get_operation(
+    user: McpAuthenticateUser,
+    operation_id: Uuid,
+    mcp_operation_usecase: &State<Arc<McpOperationUseCaseImpl>>,
+) -> OtterResponse<ResMcpPendingOperationDto> {
+    user.require_scope(ApiTokenScopeVariant::Read)?;
+    match mcp_operation_usecase.get_operation(user.user_id, operation_id).await {
+        Ok(Some(res)) => Ok(SuccessResponse(Status::Ok, res)),
+        Ok(None) => Err(ErrorResponse(Status::NotFound, "Operation not found".to_string())),
+        Err(err) => Err(mcp_operation_error_response(err)),
+    }
+}
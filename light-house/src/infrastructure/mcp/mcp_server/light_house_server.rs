@@ -0,0 +1,431 @@
+use std::{str::FromStr, sync::Arc};
+
+use rmcp::{handler::server::{router::tool::ToolRouter, wrapper::Parameters}, model::{AnnotateAble, CallToolResult, Content, Implementation, ListResourcesResult, PaginatedRequestParam, ProtocolVersion, RawResource, ReadResourceRequestParam, ReadResourceResult, ResourceContents, ServerCapabilities, ServerInfo}, service::RequestContext, tool, tool_handler, tool_router, ErrorData as McpError, RoleServer, ServerHandler};
+use rocket::{Phase, Rocket};
+use serde::Serialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+use crate::{application::{usecase::{asset_usecase::AssetUseCase, contact_usecase::ContactUseCase, current_sheet_usecase::CurrentUseCase, expense_usecase::ExpenseUseCase, report_usecase::ReportUseCase, transaction::transaction_usecase::TransactionUseCase}, usecase_req_impl::{api_token_usecase::ApiTokenUsecase, asset_usecase::AssetUsecase, contact_usecase::ContactUsecase, current_sheet_usecase::CurrentSheetUsecase, expense_usecase::ExpenseUsecase, mcp_operation_usecase::McpOperationUsecase, report_usecase::ReportUsecase, transaction_usecase::TransactionSearchUsecase}}, domain::dto::{api_token_dto::ApiTokenIdentityDto, dto_enum::{api_token_scope::ApiTokenScopeVariant, expense_report_group::ExpenseReportGroupVariant, mcp_operation::McpOperationVariant}, mcp_operation_dto::ReqMcpProposeOperationDto, transaction_dto::ReqTransactionQueryDto}, implentation::date_time_utill::{parse_to_datetime_utc_end_of_day, parse_to_datetime_utc_start_of_day}, infrastructure::{database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, balance_repo::BalanceRepositoryImpl, contact_repo::ContactRepositoryImpl, expense_repo::ExpenseRepositoryImpl, report_repo::ReportRepositoryImpl, transaction::transaction_repo::TransactionRepoImpl}, mcp::mcp_server::mcp_tool_args::{GetBalancesArgs, GetExpenseReportArgs, GetOperationArgs, ProposeOperationArgs, RecordIncomeArgs, RecordPaymentArgs, SearchTransactionsArgs, TransferArgs}}, initiation::init_usecase_setup::{ApiTokenUseCaseImpl, McpOperationUseCaseImpl}, soc::soc_usecase::UsecaseError};
+
+
+
+
+// same header the REST guard reads, `Mcp-Authorization: MCP <token>`
+const MCP_AUTH_HEADER: &str = "Mcp-Authorization";
+const MCP_AUTH_PREFIX: &str = "MCP ";
+// set by the reverse proxy in front of the SSE server, recorded as the token's last used IP
+const FORWARDED_FOR_HEADER: &str = "X-Forwarded-For";
+
+const RESOURCE_ASSETS: &str = "lighthouse://assets";
+const RESOURCE_BALANCES: &str = "lighthouse://balances";
+const RESOURCE_CONTACTS: &str = "lighthouse://contacts";
+const RESOURCE_EXPENSES: &str = "lighthouse://expenses";
+
+
+// The use cases behind the MCP server, taken from Rocket's managed state
+pub struct McpUseCases {
+    pub api_token_usecase: Arc<ApiTokenUseCaseImpl>,
+    pub asset_usecase: Arc<AssetUseCase<AssetRepositoryImpl>>,
+    pub contact_usecase: Arc<ContactUseCase<ContactRepositoryImpl>>,
+    pub expense_usecase: Arc<ExpenseUseCase<ExpenseRepositoryImpl>>,
+    pub current_sheet_usecase: Arc<CurrentUseCase<BalanceRepositoryImpl, AssetRepositoryImpl>>,
+    pub transaction_usecase: Arc<TransactionUseCase<TransactionRepoImpl>>,
+    pub report_usecase: Arc<ReportUseCase<ReportRepositoryImpl>>,
+    pub mcp_operation_usecase: Arc<McpOperationUseCaseImpl>,
+}
+
+impl McpUseCases {
+    // None when init_usecase_setup has not run on this instance
+    pub fn from_rocket<P: Phase>(rocket: &Rocket<P>) -> Option<Self> {
+        Some(Self {
+            api_token_usecase: Arc::clone(rocket.state()?),
+            asset_usecase: Arc::clone(rocket.state()?),
+            contact_usecase: Arc::clone(rocket.state()?),
+            expense_usecase: Arc::clone(rocket.state()?),
+            current_sheet_usecase: Arc::clone(rocket.state()?),
+            transaction_usecase: Arc::clone(rocket.state()?),
+            report_usecase: Arc::clone(rocket.state()?),
+            mcp_operation_usecase: Arc::clone(rocket.state()?),
+        })
+    }
+}
+
+
+fn json_result<T: Serialize>(value: T) -> Result<CallToolResult, McpError> {
+    Ok(CallToolResult::success(vec![Content::json(value)?]))
+}
+
+// business errors go back to the assistant as a failed tool call it can read and correct
+fn error_result(message: impl Into<String>) -> Result<CallToolResult, McpError> {
+    Ok(CallToolResult::error(vec![Content::text(message.into())]))
+}
+
+fn usecase_error_result(err: UsecaseError) -> Result<CallToolResult, McpError> {
+    match err {
+        UsecaseError::ValidationFailed(details) => error_result(
+            details
+                .into_iter()
+                .map(|detail| format!("{}: {}", detail.field, detail.message))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        err => error_result(err.to_string()),
+    }
+}
+
+fn require_scope(identity: &ApiTokenIdentityDto, scope: ApiTokenScopeVariant) -> Result<Uuid, McpError> {
+    if identity.allows(scope) {
+        Ok(identity.user_id)
+    } else {
+        Err(McpError::invalid_request(format!("The MCP token is missing the '{}' scope", scope.as_str()), None))
+    }
+}
+
+// optional arguments the assistant left out are dropped so the request defaults apply
+fn without_nulls(payload: Value) -> Value {
+    match payload {
+        Value::Object(fields) => Value::Object(fields.into_iter().filter(|(_, value)| !value.is_null()).collect()),
+        payload => payload,
+    }
+}
+
+
+#[derive(Clone)]
+pub struct LightHouseMcpServer {
+    usecases: Arc<McpUseCases>,
+    // stdio serves the single token that started the process, SSE reads the token of every request,
+    // either way it is checked on every call so revoking it takes effect at once
+    bound_token: Option<String>,
+    tool_router: ToolRouter<Self>,
+}
+
+
+#[tool_router]
+impl LightHouseMcpServer {
+    pub fn new(usecases: Arc<McpUseCases>, bound_token: Option<String>) -> Self {
+        Self {
+            usecases,
+            bound_token,
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    async fn authenticate(&self, context: &RequestContext<RoleServer>) -> Result<ApiTokenIdentityDto, McpError> {
+        // Step 1: The HTTP transport hands the request head over in the extensions
+        let parts = context.extensions.get::<http::request::Parts>();
+        let token = self.bound_token.as_deref().or_else(|| {
+            parts
+                .and_then(|parts| parts.headers.get(MCP_AUTH_HEADER))
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.strip_prefix(MCP_AUTH_PREFIX))
+        });
+        let token = match token {
+            Some(token) => token,
+            None => return Err(McpError::invalid_request("Authorization header missing", None)),
+        };
+        let ip = parts
+            .and_then(|parts| parts.headers.get(FORWARDED_FOR_HEADER))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(',').next())
+            .map(|ip| ip.trim().to_string());
+
+        // Step 2: Resolve the token to its user and scopes
+        match self.usecases.api_token_usecase.authenticate_api_token(token, ip).await {
+            Ok(identity) => Ok(identity),
+            Err(UsecaseError::ResourceNotFound(_)) => Err(McpError::invalid_request("Invalid MCP token", None)),
+            Err(err) => Err(McpError::internal_error(err.to_string(), None)),
+        }
+    }
+
+    async fn authorize(&self, context: &RequestContext<RoleServer>, scope: ApiTokenScopeVariant) -> Result<Uuid, McpError> {
+        let identity = self.authenticate(context).await?;
+        require_scope(&identity, scope)
+    }
+
+    // writes only become pending operations, the user confirms them over the JWT API
+    async fn propose(&self, user_id: Uuid, operation: McpOperationVariant, target_id: Option<String>, payload: Option<Value>) -> Result<CallToolResult, McpError> {
+        let operation_dto = ReqMcpProposeOperationDto {
+            operation,
+            target_id,
+            payload,
+        };
+        match self.usecases.mcp_operation_usecase.propose_operation(user_id, operation_dto).await {
+            Ok(res) => json_result(res),
+            Err(err) => usecase_error_result(err),
+        }
+    }
+
+    async fn read_resource_json(&self, user_id: Uuid, uri: &str) -> Result<Option<String>, UsecaseError> {
+        let json = match uri {
+            RESOURCE_ASSETS => serde_json::to_string(&self.usecases.asset_usecase.get_all_asset(user_id).await?),
+            RESOURCE_BALANCES => serde_json::to_string(&self.usecases.current_sheet_usecase.get_all_current_sheets_by_user(user_id).await?),
+            RESOURCE_CONTACTS => serde_json::to_string(&self.usecases.contact_usecase.get_all_contact(user_id).await?),
+            RESOURCE_EXPENSES => serde_json::to_string(&self.usecases.expense_usecase.get_all_expense(user_id).await?),
+            _ => return Ok(None),
+        };
+        json.map(Some).map_err(|err| UsecaseError::Unexpected(err.to_string()))
+    }
+
+
+    #[tool(description = "List the assets (bank accounts, wallets, cards) of the user with their IDs and asset types")]
+    async fn list_assets(&self, context: RequestContext<RoleServer>) -> Result<CallToolResult, McpError> {
+        let user_id = self.authorize(&context, ApiTokenScopeVariant::Read).await?;
+        match self.usecases.asset_usecase.get_all_asset(user_id).await {
+            Ok(res) => json_result(res),
+            Err(err) => usecase_error_result(err),
+        }
+    }
+
+    #[tool(description = "Current balance of every asset, or of one asset when asset_id is given")]
+    async fn get_balances(
+        &self,
+        Parameters(args): Parameters<GetBalancesArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let user_id = self.authorize(&context, ApiTokenScopeVariant::Read).await?;
+        let result = match args.asset_id {
+            Some(asset_id) => {
+                let asset_id = match Uuid::parse_str(&asset_id) {
+                    Ok(asset_id) => asset_id,
+                    Err(_) => return error_result("Invalid asset_id"),
+                };
+                self.usecases.current_sheet_usecase.get_all_current_sheets_by_asset_id(user_id, asset_id).await
+            }
+            None => self.usecases.current_sheet_usecase.get_all_current_sheets_by_user(user_id).await,
+        };
+        match result {
+            Ok(res) => json_result(res),
+            Err(err) => usecase_error_result(err),
+        }
+    }
+
+    #[tool(description = "Propose a payment out of an asset for an expense. Nothing is recorded until the user confirms the returned operation, which shows the balance change")]
+    async fn record_payment(
+        &self,
+        Parameters(args): Parameters<RecordPaymentArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let user_id = self.authorize(&context, ApiTokenScopeVariant::WriteTransactions).await?;
+        let payload = json!({
+            "amount": args.amount,
+            "expense_id": args.expense_id,
+            "asset_id": args.asset_id,
+            "contact_id": args.contact_id,
+            "note": args.note,
+            "occurred_at": args.occurred_at,
+            "timezone": args.timezone,
+        });
+        self.propose(user_id, McpOperationVariant::CreatePayment, None, Some(without_nulls(payload))).await
+    }
+
+    #[tool(description = "Propose money received into an asset. Nothing is recorded until the user confirms the returned operation, which shows the balance change")]
+    async fn record_income(
+        &self,
+        Parameters(args): Parameters<RecordIncomeArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let user_id = self.authorize(&context, ApiTokenScopeVariant::WriteTransactions).await?;
+        let payload = json!({
+            "amount": args.amount,
+            "asset_id": args.asset_id,
+            "contact_id": args.contact_id,
+            "note": args.note,
+            "occurred_at": args.occurred_at,
+            "timezone": args.timezone,
+        });
+        self.propose(user_id, McpOperationVariant::CreateIncome, None, Some(without_nulls(payload))).await
+    }
+
+    #[tool(description = "Propose moving money from one asset of the user to another. Nothing is recorded until the user confirms the returned operation, which shows the balance change")]
+    async fn transfer(
+        &self,
+        Parameters(args): Parameters<TransferArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let user_id = self.authorize(&context, ApiTokenScopeVariant::WriteTransactions).await?;
+        let payload = json!({
+            "amount": args.amount,
+            "asset_id": args.asset_id,
+            "destination_asset_id": args.destination_asset_id,
+            "contact_id": args.contact_id,
+            "note": args.note,
+            "occurred_at": args.occurred_at,
+            "timezone": args.timezone,
+        });
+        self.propose(user_id, McpOperationVariant::CreateTransfer, None, Some(without_nulls(payload))).await
+    }
+
+    #[tool(description = "Propose creating, updating or deleting a payment, income, transfer, contact or expense. The payload is the body of the matching REST request. Nothing changes until the user confirms the returned operation, which shows the balance change of every asset it touches")]
+    async fn propose_operation(
+        &self,
+        Parameters(args): Parameters<ProposeOperationArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let identity = self.authenticate(&context).await?;
+        let operation = match McpOperationVariant::from_str(&args.operation) {
+            Ok(operation) => operation,
+            Err(err) => return error_result(err),
+        };
+        let user_id = require_scope(&identity, operation.required_scope())?;
+        self.propose(user_id, operation, args.target_id, args.payload).await
+    }
+
+    #[tool(description = "Status of a proposed operation: pending, confirmed, rejected, expired or failed, with the resulting record once confirmed")]
+    async fn get_operation(
+        &self,
+        Parameters(args): Parameters<GetOperationArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let user_id = self.authorize(&context, ApiTokenScopeVariant::Read).await?;
+        let operation_id = match Uuid::parse_str(&args.operation_id) {
+            Ok(operation_id) => operation_id,
+            Err(_) => return error_result("Invalid operation_id"),
+        };
+        match self.usecases.mcp_operation_usecase.get_operation(user_id, operation_id).await {
+            Ok(Some(res)) => json_result(res),
+            Ok(None) => error_result("Operation not found"),
+            Err(err) => usecase_error_result(err),
+        }
+    }
+
+    #[tool(description = "Search income, payment and transfer records with filters, sorting and pagination, the same as GET /v1/transaction")]
+    async fn search_transactions(
+        &self,
+        Parameters(args): Parameters<SearchTransactionsArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        let user_id = self.authorize(&context, ApiTokenScopeVariant::Read).await?;
+        let query = ReqTransactionQueryDto {
+            transaction_type: args.transaction_type,
+            asset_id: args.asset_id,
+            contact_id: args.contact_id,
+            expense_id: args.expense_id,
+            expense_type_id: args.expense_type_id,
+            tag_id: args.tag_id,
+            min_amount: args.min_amount,
+            max_amount: args.max_amount,
+            from: args.from,
+            to: args.to,
+            note: args.note,
+            sort_by: args.sort_by,
+            order: args.order,
+            page: args.page.map(|page| page.to_string()),
+            per_page: args.per_page.map(|per_page| per_page.to_string()),
+            cursor: args.cursor,
+        };
+        let search = match query.into_search() {
+            Ok(search) => search,
+            Err(err) => return error_result(err),
+        };
+
+        match self.usecases.transaction_usecase.search_transactions(user_id, search).await {
+            Ok(res) => json_result(res),
+            Err(err) => usecase_error_result(err),
+        }
+    }
+
+    #[tool(description = "Where the money went: payment totals, counts and share per expense type, expense, contact, asset or tag between from and to, compared with the range of the same length just before")]
+    async fn get_expense_report(
+        &self,
+        Parameters(args): Parameters<GetExpenseReportArgs>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, McpError> {
+        // read only, so a read token is enough, a token holding only `reports` works as well
+        let identity = self.authenticate(&context).await?;
+        let user_id = match require_scope(&identity, ApiTokenScopeVariant::Read) {
+            Ok(user_id) => user_id,
+            Err(_) => require_scope(&identity, ApiTokenScopeVariant::Reports)?,
+        };
+
+        let from = match parse_to_datetime_utc_start_of_day(&args.from) {
+            Ok(from) => from,
+            Err(err) => return error_result(err),
+        };
+        let to = match parse_to_datetime_utc_end_of_day(&args.to) {
+            Ok(to) => to,
+            Err(err) => return error_result(err),
+        };
+        if from > to {
+            return error_result("from must not be after to");
+        }
+        let group_by = match args.group_by.as_deref().map(ExpenseReportGroupVariant::from_str).transpose() {
+            Ok(group_by) => group_by.unwrap_or(ExpenseReportGroupVariant::ExpenseType),
+            Err(err) => return error_result(err),
+        };
+        let tag_id = match args.tag_id.as_deref().map(Uuid::parse_str).transpose() {
+            Ok(tag_id) => tag_id,
+            Err(_) => return error_result("Invalid tag_id"),
+        };
+
+        match self.usecases.report_usecase.get_expense_report(user_id, from, to, group_by, tag_id).await {
+            Ok(res) => json_result(res),
+            Err(err) => usecase_error_result(err),
+        }
+    }
+}
+
+
+#[tool_handler]
+impl ServerHandler for LightHouseMcpServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            protocol_version: ProtocolVersion::V_2024_11_05,
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
+            server_info: Implementation::from_build_env(),
+            instructions: Some(
+                "Light House keeps the user's assets, contacts, expenses and their income, payment and transfer records. \
+                Read the assets, contacts and expenses resources to find the IDs the recording tools need, \
+                get_expense_report tells where the money went over a date range. \
+                Every write is only proposed, the user confirms or rejects it in Light House, check it with get_operation."
+                    .to_string(),
+            ),
+        }
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        self.authorize(&context, ApiTokenScopeVariant::Read).await?;
+
+        let resources = [
+            (RESOURCE_ASSETS, "assets", "Assets of the user with their asset type"),
+            (RESOURCE_BALANCES, "balances", "Current balance of every asset"),
+            (RESOURCE_CONTACTS, "contacts", "Contacts money is paid to or received from"),
+            (RESOURCE_EXPENSES, "expenses", "Expenses a payment can be recorded against"),
+        ]
+        .into_iter()
+        .map(|(uri, name, description)| {
+            let mut resource = RawResource::new(uri, name);
+            resource.description = Some(description.to_string());
+            resource.mime_type = Some("application/json".to_string());
+            resource.no_annotation()
+        })
+        .collect();
+
+        Ok(ListResourcesResult {
+            resources,
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        ReadResourceRequestParam { uri }: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        let user_id = self.authorize(&context, ApiTokenScopeVariant::Read).await?;
+        match self.read_resource_json(user_id, &uri).await {
+            Ok(Some(json)) => Ok(ReadResourceResult {
+                contents: vec![ResourceContents::text(json, uri)],
+            }),
+            Ok(None) => Err(McpError::resource_not_found(format!("Unknown resource '{}'", uri), None)),
+            Err(err) => Err(McpError::internal_error(err.to_string(), None)),
+        }
+    }
+}
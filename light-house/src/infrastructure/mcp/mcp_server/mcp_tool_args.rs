@@ -0,0 +1,128 @@
+use rmcp::schemars;
+use serde::Deserialize;
+
+
+
+
+// Arguments of the MCP tools, the doc comments end up in the JSON schema shown to the assistant
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetBalancesArgs {
+    /// Only this asset, every asset of the user when omitted
+    pub asset_id: Option<String>,
+}
+
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RecordPaymentArgs {
+    /// Decimal amount greater than zero, for example "120.50"
+    pub amount: String,
+    /// The expense the money was spent on, see the expenses resource
+    pub expense_id: String,
+    /// The asset the money is paid from
+    pub asset_id: String,
+    /// Who was paid, see the contacts resource
+    pub contact_id: String,
+    pub note: String,
+    /// RFC 3339 timestamp or YYYY-MM-DD date, defaults to now
+    pub occurred_at: Option<String>,
+    /// IANA name such as Asia/Bangkok, only used when occurred_at carries no offset
+    pub timezone: Option<String>,
+}
+
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct RecordIncomeArgs {
+    /// Decimal amount greater than zero, for example "1500"
+    pub amount: String,
+    /// The asset the money is received into
+    pub asset_id: String,
+    /// Who paid, see the contacts resource
+    pub contact_id: String,
+    pub note: String,
+    /// RFC 3339 timestamp or YYYY-MM-DD date, defaults to now
+    pub occurred_at: Option<String>,
+    /// IANA name such as Asia/Bangkok, only used when occurred_at carries no offset
+    pub timezone: Option<String>,
+}
+
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct TransferArgs {
+    /// Decimal amount greater than zero
+    pub amount: String,
+    /// The asset the money leaves
+    pub asset_id: String,
+    /// The asset the money goes into
+    pub destination_asset_id: String,
+    pub contact_id: String,
+    pub note: String,
+    /// RFC 3339 timestamp or YYYY-MM-DD date, defaults to now
+    pub occurred_at: Option<String>,
+    /// IANA name such as Asia/Bangkok, only used when occurred_at carries no offset
+    pub timezone: Option<String>,
+}
+
+
+#[derive(Debug, Default, Deserialize, schemars::JsonSchema)]
+pub struct SearchTransactionsArgs {
+    /// income, payment or transfer, several can be comma separated
+    #[serde(rename = "type")]
+    pub transaction_type: Option<String>,
+    /// Matches the source or the destination asset
+    pub asset_id: Option<String>,
+    pub contact_id: Option<String>,
+    pub expense_id: Option<String>,
+    pub expense_type_id: Option<String>,
+    /// Only transactions carrying this tag
+    pub tag_id: Option<String>,
+    pub min_amount: Option<String>,
+    pub max_amount: Option<String>,
+    /// YYYY-MM-DD or RFC 3339 timestamp, compared with occurred_at
+    pub from: Option<String>,
+    /// YYYY-MM-DD or RFC 3339 timestamp, compared with occurred_at
+    pub to: Option<String>,
+    /// Case insensitive text inside the note
+    pub note: Option<String>,
+    /// occurred_at (default) or amount
+    pub sort_by: Option<String>,
+    /// asc or desc (default)
+    pub order: Option<String>,
+    /// 1 based page number, ignored when cursor is given
+    pub page: Option<u64>,
+    /// Defaults to 20, at most 100
+    pub per_page: Option<u64>,
+    /// next_cursor of the previous page
+    pub cursor: Option<String>,
+}
+
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct ProposeOperationArgs {
+    /// create_, update_ or delete_ followed by payment, income, transfer, contact or expense
+    pub operation: String,
+    /// The record an update or delete changes, omitted for creates
+    pub target_id: Option<String>,
+    /// Body of the matching create or update request, omitted for deletes
+    pub payload: Option<serde_json::Value>,
+}
+
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetOperationArgs {
+    /// ID returned when the operation was proposed
+    pub operation_id: String,
+}
+
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct GetExpenseReportArgs {
+    /// YYYY-MM-DD or RFC 3339 timestamp, first day of the range
+    pub from: String,
+    /// YYYY-MM-DD or RFC 3339 timestamp, last day of the range
+    pub to: String,
+    /// expense_type (default), expense, contact, asset or tag
+    pub group_by: Option<String>,
+    /// Only payments carrying this tag
+    pub tag_id: Option<String>,
+}
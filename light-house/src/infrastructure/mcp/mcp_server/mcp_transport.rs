@@ -0,0 +1,33 @@
+use std::{net::SocketAddr, sync::Arc};
+
+use rmcp::{transport::{sse_server::SseServer, stdio}, ServiceExt};
+use crate::infrastructure::mcp::mcp_server::light_house_server::{LightHouseMcpServer, McpUseCases};
+
+
+
+
+// Serves one token over stdin/stdout until the client closes the stream, stdout carries protocol messages only
+pub async fn serve_stdio(usecases: Arc<McpUseCases>, token: String) -> Result<(), String> {
+    let service = LightHouseMcpServer::new(usecases, Some(token))
+        .serve(stdio())
+        .await
+        .map_err(|err| format!("Failed to start MCP stdio server: {}", err))?;
+
+    service
+        .waiting()
+        .await
+        .map_err(|err| format!("MCP stdio server stopped: {}", err))?;
+    Ok(())
+}
+
+
+// Serves remote assistants over SSE, GET /sse opens the stream and POST /message carries the requests.
+// Every request is authenticated with its own Mcp-Authorization header
+pub async fn serve_sse(usecases: Arc<McpUseCases>, bind: SocketAddr) -> Result<(), String> {
+    let sse_server = SseServer::serve(bind)
+        .await
+        .map_err(|err| format!("Failed to bind MCP SSE server on {}: {}", bind, err))?;
+
+    sse_server.with_service(move || LightHouseMcpServer::new(Arc::clone(&usecases), None));
+    Ok(())
+}
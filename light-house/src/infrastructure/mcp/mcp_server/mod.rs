@@ -0,0 +1,3 @@
+pub mod light_house_server;
+pub mod mcp_tool_args;
+pub mod mcp_transport;
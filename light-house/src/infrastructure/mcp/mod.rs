@@ -1 +1,2 @@
-pub mod mcp_handler;
\ No newline at end of file
+pub mod mcp_handler;
+pub mod mcp_server;
\ No newline at end of file
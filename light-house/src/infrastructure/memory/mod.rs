@@ -0,0 +1 @@
+pub mod sign_in_limiter;
\ No newline at end of file
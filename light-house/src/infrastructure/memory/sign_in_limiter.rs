@@ -0,0 +1,55 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+
+use crate::{domain::{dto::sign_in_attempt_dto::SignInThrottleDto, req_repository::sign_in_limiter_repository::SignInLimiterRepository}, soc::soc_repository::RepositoryError};
+
+
+
+
+// counters of this process only, lost on restart, SIGN_IN_LIMITER=memory (default)
+#[derive(Default)]
+pub struct InMemorySignInLimiter {
+    throttles: Mutex<HashMap<String, SignInThrottleDto>>,
+}
+
+impl InMemorySignInLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, SignInThrottleDto>>, RepositoryError> {
+        self.throttles
+            .lock()
+            .map_err(|_| RepositoryError::OperationFailed("Sign in limiter lock poisoned".to_string()))
+    }
+}
+
+
+#[async_trait::async_trait]
+impl SignInLimiterRepository for InMemorySignInLimiter {
+    async fn get_throttle(&self, key: &str) -> Result<Option<SignInThrottleDto>, RepositoryError>
+    {
+        Ok(self.lock()?.get(key).cloned())
+    }
+
+    async fn save_throttle(&self, key: &str, throttle: SignInThrottleDto) -> Result<(), RepositoryError>
+    {
+        self.lock()?.insert(key.to_string(), throttle);
+        Ok(())
+    }
+
+    async fn clear_throttle(&self, key: &str) -> Result<(), RepositoryError>
+    {
+        self.lock()?.remove(key);
+        Ok(())
+    }
+
+    async fn purge_throttle_before(&self, before: DateTime<Utc>) -> Result<u64, RepositoryError>
+    {
+        let mut throttles = self.lock()?;
+        let count = throttles.len();
+        throttles.retain(|_, throttle| throttle.last_failure_at >= before);
+        Ok((count - throttles.len()) as u64)
+    }
+}
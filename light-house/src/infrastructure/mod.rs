@@ -1,3 +1,7 @@
 pub mod http;
 pub mod mcp;
-pub mod database;
\ No newline at end of file
+pub mod database;
+pub mod scheduler;
+pub mod memory;
+pub mod mail;
+pub mod storage;
\ No newline at end of file
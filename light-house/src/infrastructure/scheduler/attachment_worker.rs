@@ -0,0 +1,29 @@
+use std::{sync::Arc, time::Duration};
+
+use rocket::tokio::{self, task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{application::usecase_req_impl::attachment_usecase::AttachmentCleanupUsecase, configuration::attachment_config::AttachmentConfig};
+
+
+
+
+// Removes attachments left behind by deleted transactions, contacts and assets on every tick.
+// The row goes before the content, so a second instance at worst retries a file that is already gone
+pub fn spawn_attachment_worker<U>(cleaner: Arc<U>, config: AttachmentConfig) -> JoinHandle<()>
+where
+    U: AttachmentCleanupUsecase + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.cleanup_interval_secs));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match cleaner.purge_orphaned_attachments().await {
+                Ok(0) => {}
+                Ok(removed) => tracing::info!("Attachment worker removed {} orphaned attachment(s)", removed),
+                Err(err) => tracing::error!("Attachment worker failed: {}", err),
+            }
+        }
+    })
+}
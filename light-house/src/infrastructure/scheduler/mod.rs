@@ -0,0 +1,3 @@
+pub mod recurring_worker;
+
+pub mod attachment_worker;
\ No newline at end of file
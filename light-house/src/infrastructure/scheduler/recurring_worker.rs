@@ -0,0 +1,30 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{SubsecRound, Utc};
+use rocket::tokio::{self, task::JoinHandle, time::MissedTickBehavior};
+
+use crate::{application::usecase_req_impl::recurring_transaction_usecase::RecurringTransactionRunnerUsecase, configuration::recurring_config::RecurringWorkerConfig};
+
+
+
+
+// Posts due recurring transactions on every tick. Occurrences are claimed in the database,
+// so a restart or a second instance never posts the same occurrence twice
+pub fn spawn_recurring_worker<U>(runner: Arc<U>, config: RecurringWorkerConfig) -> JoinHandle<()>
+where
+    U: RecurringTransactionRunnerUsecase + Send + Sync + 'static,
+{
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(config.interval_secs));
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+            match runner.run_due_occurrences(Utc::now().trunc_subsecs(0)).await {
+                Ok(0) => {}
+                Ok(posted) => tracing::info!("Recurring worker posted {} transaction(s)", posted),
+                Err(err) => tracing::error!("Recurring worker failed: {}", err),
+            }
+        }
+    })
+}
@@ -0,0 +1,62 @@
+use std::{io::ErrorKind, path::PathBuf};
+
+use crate::{domain::req_repository::attachment_storage_repository::AttachmentStorage, soc::soc_repository::RepositoryError};
+
+
+
+
+// ATTACHMENT_STORAGE=local (default), every attachment is one file under the base directory,
+// written next to its final name first so a failed upload never leaves half a file behind
+pub struct LocalAttachmentStorage {
+    base_dir: PathBuf,
+}
+
+impl LocalAttachmentStorage {
+    pub fn new(base_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            base_dir: base_dir.into(),
+        }
+    }
+}
+
+
+#[async_trait::async_trait]
+impl AttachmentStorage for LocalAttachmentStorage {
+    async fn put(&self, key: String, content: Vec<u8>, _content_type: String) -> Result<(), RepositoryError>
+    {
+        let path = self.base_dir.join(&key);
+        let partial_path = self.base_dir.join(format!("{}.part", key));
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|err| RepositoryError::OperationFailed(format!("Failed to create the attachment directory: {}", err)))?;
+        }
+        tokio::fs::write(&partial_path, content)
+            .await
+            .map_err(|err| RepositoryError::OperationFailed(format!("Failed to write attachment: {}", err)))?;
+        tokio::fs::rename(&partial_path, &path)
+            .await
+            .map_err(|err| RepositoryError::OperationFailed(format!("Failed to write attachment: {}", err)))
+    }
+
+
+    async fn get(&self, key: String) -> Result<Vec<u8>, RepositoryError>
+    {
+        match tokio::fs::read(self.base_dir.join(&key)).await {
+            Ok(content) => Ok(content),
+            Err(err) if err.kind() == ErrorKind::NotFound => Err(RepositoryError::NotFound(format!("Attachment content '{}' not found", key))),
+            Err(err) => Err(RepositoryError::OperationFailed(format!("Failed to read attachment: {}", err))),
+        }
+    }
+
+
+    async fn delete(&self, key: String) -> Result<(), RepositoryError>
+    {
+        match tokio::fs::remove_file(self.base_dir.join(&key)).await {
+            Ok(()) => Ok(()),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+            Err(err) => Err(RepositoryError::OperationFailed(format!("Failed to delete attachment: {}", err))),
+        }
+    }
+}
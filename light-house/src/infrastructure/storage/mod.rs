@@ -0,0 +1,3 @@
+pub mod local_storage;
+#[cfg(feature = "s3")]
+pub mod s3_storage;
\ No newline at end of file
@@ -0,0 +1,100 @@
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_s3::{primitives::ByteStream, Client};
+
+use crate::{configuration::attachment_config::AttachmentConfig, domain::req_repository::attachment_storage_repository::AttachmentStorage, soc::soc_repository::RepositoryError};
+
+
+
+
+// ATTACHMENT_STORAGE=s3, built with the `s3` feature. Works with AWS and with S3 compatible services
+// such as MinIO when ATTACHMENT_S3_ENDPOINT is set
+pub struct S3AttachmentStorage {
+    client: Client,
+    bucket: String,
+    prefix: String,
+}
+
+impl S3AttachmentStorage {
+    pub async fn new(config: &AttachmentConfig) -> Self {
+        let shared_config = aws_config::defaults(BehaviorVersion::latest())
+            .region(Region::new(config.s3_region.clone()))
+            .load()
+            .await;
+
+        let mut builder = aws_sdk_s3::config::Builder::from(&shared_config);
+        if let Some(endpoint) = &config.s3_endpoint {
+            builder = builder.endpoint_url(endpoint).force_path_style(true);
+        }
+
+        Self {
+            client: Client::from_conf(builder.build()),
+            bucket: config.s3_bucket.clone(),
+            prefix: config.s3_prefix.clone(),
+        }
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", self.prefix, key)
+        }
+    }
+}
+
+
+#[async_trait::async_trait]
+impl AttachmentStorage for S3AttachmentStorage {
+    async fn put(&self, key: String, content: Vec<u8>, content_type: String) -> Result<(), RepositoryError>
+    {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(&key))
+            .content_type(content_type)
+            .body(ByteStream::from(content))
+            .send()
+            .await
+            .map_err(|err| RepositoryError::OperationFailed(format!("Failed to upload attachment: {}", err)))?;
+        Ok(())
+    }
+
+
+    async fn get(&self, key: String) -> Result<Vec<u8>, RepositoryError>
+    {
+        let object = self.client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(&key))
+            .send()
+            .await
+            .map_err(|err| {
+                let err = err.into_service_error();
+                if err.is_no_such_key() {
+                    RepositoryError::NotFound(format!("Attachment content '{}' not found", key))
+                } else {
+                    RepositoryError::OperationFailed(format!("Failed to download attachment: {}", err))
+                }
+            })?;
+
+        let content = object.body
+            .collect()
+            .await
+            .map_err(|err| RepositoryError::OperationFailed(format!("Failed to download attachment: {}", err)))?;
+        Ok(content.into_bytes().to_vec())
+    }
+
+
+    async fn delete(&self, key: String) -> Result<(), RepositoryError>
+    {
+        // deleting a missing object succeeds on S3
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(self.object_key(&key))
+            .send()
+            .await
+            .map_err(|err| RepositoryError::OperationFailed(format!("Failed to delete attachment: {}", err)))?;
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use rocket::fairing::AdHoc;
 
-use crate::infrastructure::{http::http_handler::{asset_route::asset_routes, asset_type_route::asset_type_routes, contact_route::contact_routes, contact_type_route::contact_type_routes, current_sheet_route::current_sheet_routes, expense_route::expense_routes, expense_type_route::expense_type_routes, transaction::{income_route::income_routes, payment_route::payment_routes, transaction_type::transaction_type_routes, transfer_route::transfer_routes}, user_route::user_routes}, mcp::mcp_handler::mcp_feature::mcp_routes};
+use crate::infrastructure::{http::http_handler::{admin_route::admin_routes, api_token_route::api_token_routes, asset_route::asset_routes, asset_type_route::asset_type_routes, attachment_route::attachment_routes, budget_route::budget_routes, contact_route::contact_routes, contact_type_route::contact_type_routes, current_sheet_route::current_sheet_routes, expense_route::expense_routes, expense_type_route::expense_type_routes, import_route::import_routes, mcp_operation_route::mcp_operation_routes, recurring_transaction_route::recurring_transaction_routes, report_route::report_routes, session_route::session_routes, tag_route::tag_routes, transaction::{income_route::income_routes, payment_route::payment_routes, transaction_route::transaction_routes, transaction_type::transaction_type_routes, transfer_route::transfer_routes}, two_factor_route::two_factor_routes, user_route::user_routes}, mcp::mcp_handler::mcp_feature::mcp_routes};
 
 
 
@@ -20,6 +20,18 @@ pub fn init_handler_setup() -> AdHoc {
             .mount("/v1/current-sheet", current_sheet_routes())
             .mount("/v1/payment", payment_routes())
             .mount("/v1/transfer",transfer_routes())
+            .mount("/v1/transaction", transaction_routes())
+            .mount("/v1/report", report_routes())
+            .mount("/v1/budget", budget_routes())
+            .mount("/v1/tag", tag_routes())
+            .mount("/v1/attachment", attachment_routes())
+            .mount("/v1/import", import_routes())
+            .mount("/v1/recurring-transaction", recurring_transaction_routes())
+            .mount("/v1/mcp-operation", mcp_operation_routes())
+            .mount("/v1/api-token", api_token_routes())
+            .mount("/v1/session", session_routes())
+            .mount("/v1/two-factor", two_factor_routes())
+            .mount("/v1/admin", admin_routes())
             .mount("/v1/mcp", mcp_routes())
     })
 }
\ No newline at end of file
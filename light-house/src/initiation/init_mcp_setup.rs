@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use rocket::fairing::AdHoc;
+
+use crate::{configuration::mcp_config::McpConfig, infrastructure::mcp::mcp_server::{light_house_server::McpUseCases, mcp_transport::serve_sse}};
+
+
+
+
+pub fn init_mcp_setup() -> AdHoc {
+    AdHoc::on_liftoff("Initialize MCP server", |rocket| Box::pin(async move {
+        let config = McpConfig::default();
+        if !config.sse_enabled {
+            tracing::info!("MCP SSE server is disabled");
+            return;
+        }
+
+        let usecases = match McpUseCases::from_rocket(rocket) {
+            Some(usecases) => Arc::new(usecases),
+            None => {
+                tracing::error!("MCP use cases are not managed, MCP SSE server not started");
+                return;
+            }
+        };
+        match serve_sse(usecases, config.sse_bind).await {
+            Ok(()) => tracing::info!("MCP SSE server listening on {}", config.sse_bind),
+            Err(err) => tracing::error!("{}", err),
+        }
+    }))
+}
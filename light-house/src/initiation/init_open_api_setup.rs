@@ -1,6 +1,6 @@
 use utoipa::OpenApi;
 
-use crate::{configuration::api_doc_config::ApiConfig, swagger_ui::{asset_api::AssetApi, asset_type_api::AssetTypeApi, auth_api::AuthApi, contact_api::ContactApi, contact_type_api::ContactTypeApi, current_sheet_api::CurrentSheetApi, expense_api::ExpenseApi, expense_type::ExpenseTypeApi, income_api::IncomeApi, payment_api::PaymentApi, transaction_type_api::TransactionTypeApi, transfer_api::TransferApi, user_api::UserApi}};
+use crate::{configuration::api_doc_config::ApiConfig, swagger_ui::{admin_api::AdminApi, api_token_api::ApiTokenApi, asset_api::AssetApi, asset_type_api::AssetTypeApi, attachment_api::AttachmentApi, auth_api::AuthApi, budget_api::BudgetApi, contact_api::ContactApi, contact_type_api::ContactTypeApi, current_sheet_api::CurrentSheetApi, expense_api::ExpenseApi, expense_type::ExpenseTypeApi, import_api::ImportApi, income_api::IncomeApi, mcp_operation_api::McpOperationApi, payment_api::PaymentApi, recurring_transaction_api::RecurringTransactionApi, report_api::ReportApi, session_api::SessionApi, tag_api::TagApi, transaction_type_api::TransactionTypeApi, transaction_api::TransactionApi, transfer_api::TransferApi, two_factor_api::TwoFactorApi, user_api::UserApi}};
 
 
 
@@ -20,7 +20,19 @@ pub fn init_open_api_setup() -> utoipa::openapi::OpenApi {
         TransactionTypeApi::openapi(),
         CurrentSheetApi::openapi(),
         PaymentApi::openapi(),
-        TransferApi::openapi()
+        TransferApi::openapi(),
+        TransactionApi::openapi(),
+        ReportApi::openapi(),
+        BudgetApi::openapi(),
+        TagApi::openapi(),
+        AttachmentApi::openapi(),
+        ImportApi::openapi(),
+        RecurringTransactionApi::openapi(),
+        McpOperationApi::openapi(),
+        ApiTokenApi::openapi(),
+        SessionApi::openapi(),
+        TwoFactorApi::openapi(),
+        AdminApi::openapi()
     
     ];
 
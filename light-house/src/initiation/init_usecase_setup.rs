@@ -3,10 +3,43 @@ use std::sync::Arc;
 use rocket::fairing::AdHoc;
 use sea_orm::DatabaseConnection;
 
-use crate::{application::{usecase::{asset_type_usecase::AssetTypeUseCase, asset_usecase::AssetUseCase, contact_type_usecase::ContactTypeUseCase, contact_usecase::ContactUseCase, current_sheet_usecase::CurrentUseCase, expense_type_usecase::ExpenseTypeUseCase, expense_usecase::ExpenseUseCase, transaction::{income_usecase::{self}, payment_usecase::PaymentUseCase, transaction_type_usecase::TransactionTypeUseCase, transfer_usecase::TransferUseCase}, user_usecase::UserUseCase, wrapper::{income_wrapper::IncomeRepositoryComposite, payment_wrapper::PaymentRepositoryComposite, transfer_wrapper::TransferRepositoryComposite, user_wrapper::UserRepositoryComposite}}}, infrastructure::database::mysql::impl_repository::{asset_repo::AssetRepositoryImpl, asset_type_repo::AssetTypeRepositoryImpl, auth_repo::AuthRepositoryImpl, balance_repo::BalanceRepositoryImpl, contact_repo::ContactRepositoryImpl, contact_type_repo::ContactTypeRepositoryImpl, expense_repo::ExpenseRepositoryImpl, expense_type_repos::ExpenseTypeRepositoryImpl, gender_repo::GenderRepositoryImpl, role_repo::RoleManagementRepositoryImpl, transaction::{income_repo::IncomeRepositoryImpl, payment_repo::PaymentRepositoryImpl, transfer_repo::TransferRepositoryImpl}, transaction_type_repo::TransactionTypeRepositoryImpl, user_repo::UserRepositoryImpl}};
+use crate::{application::{usecase::{api_token_usecase::ApiTokenUseCase, asset_type_usecase::AssetTypeUseCase, attachment_usecase::AttachmentUseCase, asset_usecase::AssetUseCase, budget_usecase::BudgetUseCase, contact_type_usecase::ContactTypeUseCase, contact_usecase::ContactUseCase, current_sheet_usecase::CurrentUseCase, import_usecase::ImportUseCase, recurring_transaction_usecase::RecurringTransactionUseCase, tag_usecase::TagUseCase, expense_type_usecase::ExpenseTypeUseCase, expense_usecase::ExpenseUseCase, mcp_operation_usecase::McpOperationUseCase, report_usecase::ReportUseCase, user_data_usecase::UserDataUseCase, transaction::{income_usecase::{self, IncomeUseCase}, payment_usecase::PaymentUseCase, transaction_type_usecase::TransactionTypeUseCase, transaction_usecase::TransactionUseCase, transfer_usecase::TransferUseCase}, user_usecase::UserUseCase, wrapper::{income_wrapper::IncomeRepositoryComposite, payment_wrapper::PaymentRepositoryComposite, transfer_wrapper::TransferRepositoryComposite, user_wrapper::UserRepositoryComposite}}}, infrastructure::database::mysql::impl_repository::{action_token_repo::ActionTokenRepositoryImpl, api_token_repo::ApiTokenRepositoryImpl, asset_repo::AssetRepositoryImpl, asset_type_repo::AssetTypeRepositoryImpl, attachment_repo::AttachmentRepositoryImpl, auth_repo::AuthRepositoryImpl, balance_repo::BalanceRepositoryImpl, budget_repo::BudgetRepositoryImpl, contact_repo::ContactRepositoryImpl, contact_type_repo::ContactTypeRepositoryImpl, expense_repo::ExpenseRepositoryImpl, expense_type_repos::ExpenseTypeRepositoryImpl, gender_repo::GenderRepositoryImpl, import_repo::ImportRepositoryImpl, mcp_operation_repo::McpOperationRepositoryImpl, payment_split_repo::PaymentSplitRepositoryImpl, recurring_transaction_repo::RecurringTransactionRepositoryImpl, report_repo::ReportRepositoryImpl, role_repo::RoleManagementRepositoryImpl, session_repo::SessionRepositoryImpl, sign_in_attempt_repo::SignInAttemptRepositoryImpl, sign_in_limiter_repo::SignInLimiterRepositoryImpl, tag_repo::TagRepositoryImpl, transaction::{income_repo::IncomeRepositoryImpl, payment_repo::PaymentRepositoryImpl, transaction_repo::TransactionRepoImpl, transfer_repo::TransferRepositoryImpl}, transaction_type_repo::TransactionTypeRepositoryImpl, two_factor_repo::TwoFactorRepositoryImpl, user_data_repo::UserDataRepositoryImpl, user_repo::UserRepositoryImpl}, infrastructure::{mail::{file_mailer::FileMailer, smtp_mailer::SmtpMailer}, memory::sign_in_limiter::InMemorySignInLimiter, storage::local_storage::LocalAttachmentStorage}, configuration::{attachment_config::{AttachmentConfig, AttachmentStorageBackend}, mail_config::{MailConfig, MailerBackend}, sign_in_limit_config::{SignInLimitConfig, SignInLimiterBackend}}, domain::req_repository::{attachment_storage_repository::AttachmentStorage, mailer_repository::Mailer, sign_in_limiter_repository::SignInLimiterRepository}};
 
 
 
+// recurring transactions post through the same use cases as the routes
+pub type RecurringTransactionUseCaseImpl = RecurringTransactionUseCase<
+    RecurringTransactionRepositoryImpl,
+    PaymentUseCase<PaymentRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl, ExpenseRepositoryImpl>,
+    IncomeUseCase<IncomeRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl>,
+    TransferUseCase<TransferRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl>,
+>;
+
+// confirmed mcp operations run through the same use cases as the routes
+pub type McpOperationUseCaseImpl = McpOperationUseCase<
+    McpOperationRepositoryImpl,
+    PaymentUseCase<PaymentRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl, ExpenseRepositoryImpl>,
+    IncomeUseCase<IncomeRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl>,
+    TransferUseCase<TransferRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl>,
+    ContactUseCase<ContactRepositoryImpl>,
+    ExpenseUseCase<ExpenseRepositoryImpl>,
+>;
+
+// the user repository tells whether the owner of a token is disabled
+pub type ApiTokenUseCaseImpl = ApiTokenUseCase<ApiTokenRepositoryImpl, UserRepositoryImpl>;
+
+// export and deletion of an account, the auth repository checks the password
+pub type UserDataUseCaseImpl = UserDataUseCase<UserDataRepositoryImpl, AuthRepositoryImpl>;
+
+// the cleanup worker runs through the same use case as the routes
+pub type AttachmentUseCaseImpl = AttachmentUseCase<AttachmentRepositoryImpl>;
+
+// imported statement lines post through the same use cases as the routes
+pub type ImportUseCaseImpl = ImportUseCase<
+    ImportRepositoryImpl,
+    PaymentUseCase<PaymentRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl, ExpenseRepositoryImpl>,
+    IncomeUseCase<IncomeRepositoryComposite, AssetRepositoryImpl, ContactRepositoryImpl, TransactionTypeRepositoryImpl>,
+>;
 
 
 pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
@@ -31,10 +64,42 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
             auth_repository,
             role_repository,
             gender_repository,
+            session_repository: Arc::new(SessionRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
+            sign_in_attempt_repository: Arc::new(SignInAttemptRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
+            action_token_repository: Arc::new(ActionTokenRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
+            two_factor_repository: Arc::new(TwoFactorRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
         });
 
+        // failed sign in counters, per process or shared through the database
+        let sign_in_limiter: Arc<dyn SignInLimiterRepository> = match SignInLimitConfig::default().backend {
+            SignInLimiterBackend::Memory => Arc::new(InMemorySignInLimiter::new()),
+            SignInLimiterBackend::Database => Arc::new(SignInLimiterRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
+        };
         
-        let user_usecase = Arc::new(UserUseCase::new(repository_composite));
+        // verification and password reset mail, over smtp or into the outbox directory
+        let mail_config = MailConfig::default();
+        let mailer: Arc<dyn Mailer> = match mail_config.backend {
+            MailerBackend::Smtp => match SmtpMailer::new(&mail_config) {
+                Ok(mailer) => Arc::new(mailer),
+                Err(err) => {
+                    log::error!("SMTP mailer not available, mail goes to {} instead: {}", mail_config.outbox_dir, err);
+                    Arc::new(FileMailer::new(&mail_config.outbox_dir, &mail_config.from))
+                }
+            },
+            MailerBackend::File => Arc::new(FileMailer::new(&mail_config.outbox_dir, &mail_config.from)),
+        };
+        
+        let user_usecase = Arc::new(UserUseCase::new(repository_composite, Arc::clone(&sign_in_limiter), mailer));
 
         // asset type repositories && asset type usecase
         let asset_type_repository = AssetTypeRepositoryImpl {
@@ -43,12 +108,35 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
       
         let asset_type_usecase: Arc<AssetTypeUseCase<AssetTypeRepositoryImpl>> = Arc::new(AssetTypeUseCase::new(Arc::new(asset_type_repository)));
         
+        // attachment storage && attachment usecase, files on the local disk or in an S3 bucket,
+        // ahead of the use cases whose deletes remove attachments
+        let attachment_config = AttachmentConfig::default();
+        let attachment_storage: Arc<dyn AttachmentStorage> = match attachment_config.backend {
+            #[cfg(feature = "s3")]
+            AttachmentStorageBackend::S3 => Arc::new(
+                crate::infrastructure::storage::s3_storage::S3AttachmentStorage::new(&attachment_config).await,
+            ),
+            #[cfg(not(feature = "s3"))]
+            AttachmentStorageBackend::S3 => {
+                log::error!("S3 attachment storage needs the `s3` feature, attachments go to {} instead", attachment_config.local_dir);
+                Arc::new(LocalAttachmentStorage::new(&attachment_config.local_dir))
+            }
+            AttachmentStorageBackend::Local => Arc::new(LocalAttachmentStorage::new(&attachment_config.local_dir)),
+        };
+        let attachment_usecase: Arc<AttachmentUseCaseImpl> = Arc::new(AttachmentUseCase::new(
+            Arc::new(AttachmentRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
+            Arc::clone(&attachment_storage),
+            attachment_config,
+        ));
+
         // asset repository && asset usecase
         let asset_repository = AssetRepositoryImpl{
             db_pool: Arc::clone(&db_connection),
         };
 
-        let asset_repository = Arc::new(AssetUseCase::new(Arc::new(asset_repository)));
+        let asset_repository = Arc::new(AssetUseCase::new(Arc::new(asset_repository), attachment_usecase.clone()));
 
         // expense type repositories && expense type usecase
         let expense_type_repository = ExpenseTypeRepositoryImpl{
@@ -72,7 +160,7 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
         let contact_repository = ContactRepositoryImpl{
             db_pool: Arc::clone(&db_connection),
         };
-        let contact_usecase = Arc::new(ContactUseCase::new(Arc::new(contact_repository)));
+        let contact_usecase = Arc::new(ContactUseCase::new(Arc::new(contact_repository), attachment_usecase.clone()));
         // transaction type repository && transaction type usecase
         let transaction_type_repository = TransactionTypeRepositoryImpl {
             db_pool: Arc::clone(&db_connection),
@@ -100,6 +188,12 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
             db_pool: Arc::clone(&db_connection),
         });
 
+        // tag repository && tag usecase, the composites below attach tags to transactions
+        let the_tag_repository = Arc::new(TagRepositoryImpl{
+            db_pool: Arc::clone(&db_connection),
+        });
+        let tag_usecase = Arc::new(TagUseCase::new(the_tag_repository.clone()));
+
         // income repository && income usecase
         let the_income_repository = Arc::new(IncomeRepositoryImpl{
             db_pool: Arc::clone(&db_connection),
@@ -109,6 +203,7 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
             income_repository: the_income_repository,
             asset_repository: the_asset_repository.clone(),
             contact_repository: the_contact_repository.clone(),
+            tag_repository: the_tag_repository.clone(),
         });
         let transaction_type_repository = Arc::new(TransactionTypeRepositoryImpl {
             db_pool: Arc::clone(&db_connection),
@@ -119,6 +214,7 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
             the_asset_repository.clone(),
             the_contact_repository.clone(),
             transaction_type_repository.clone(),
+            attachment_usecase.clone(),
         ));
 
         // balance repository && balance usecase
@@ -135,10 +231,15 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
         let the_payment_repository = Arc::new(PaymentRepositoryImpl{
             db_pool: Arc::clone(&db_connection),
         });
+        let the_payment_split_repository = Arc::new(PaymentSplitRepositoryImpl{
+            db_pool: Arc::clone(&db_connection),
+        });
         let payment_composit = Arc::new(PaymentRepositoryComposite{
             payment_repository: the_payment_repository,
             asset_repository: the_asset_repository.clone(),
             contact_repository: the_contact_repository.clone(),
+            tag_repository: the_tag_repository.clone(),
+            payment_split_repository: the_payment_split_repository,
         });
         let payment_usecase = Arc::new(PaymentUseCase::new(
             payment_composit.clone(),
@@ -146,7 +247,7 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
             the_contact_repository.clone(),
             transaction_type_repository.clone(),
             the_expnese_repository.clone(),
-            
+            attachment_usecase.clone(),
         ));
 
         // tranfer usecase
@@ -160,20 +261,97 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
             expense_repository: the_expense_repository,
             expense_type_repository: the_expense_type_repository,
             contact_repository: the_contact_repository.clone(),
+            tag_repository: the_tag_repository,
         });
         let transfer_usecase = Arc::new(TransferUseCase::new(
             tranfer_composite.clone(),
             the_asset_repository.clone(),
             the_contact_repository.clone(),
             transaction_type_repository.clone(),
+            attachment_usecase.clone(),
+        ));
+
+        // transaction search usecase
+        let transaction_usecase = Arc::new(TransactionUseCase::new(
+            Arc::new(TransactionRepoImpl::new(Arc::clone(&db_connection)))
+        ));
+
+        // report repository && report usecase
+        let report_repository = ReportRepositoryImpl {
+            db_pool: Arc::clone(&db_connection),
+        };
+        let report_usecase = Arc::new(ReportUseCase::new(Arc::new(report_repository)));
+
+        // budget repository && budget usecase
+        let budget_repository = BudgetRepositoryImpl {
+            db_pool: Arc::clone(&db_connection),
+        };
+        let budget_expense_type_repository = ExpenseTypeRepositoryImpl {
+            db_pool: Arc::clone(&db_connection),
+        };
+        let budget_usecase = Arc::new(BudgetUseCase::new(
+            Arc::new(budget_repository),
+            Arc::new(budget_expense_type_repository),
+        ));
+
+        // recurring transaction repository && recurring transaction usecase
+        let recurring_transaction_repository = RecurringTransactionRepositoryImpl {
+            db_pool: Arc::clone(&db_connection),
+        };
+        let recurring_transaction_usecase: Arc<RecurringTransactionUseCaseImpl> = Arc::new(RecurringTransactionUseCase::new(
+            Arc::new(recurring_transaction_repository),
+            payment_usecase.clone(),
+            income_usecase.clone(),
+            transfer_usecase.clone(),
+        ));
+
+        // api token repository && api token usecase
+        let api_token_repository = ApiTokenRepositoryImpl {
+            db_pool: Arc::clone(&db_connection),
+        };
+        let api_token_usecase: Arc<ApiTokenUseCaseImpl> = Arc::new(ApiTokenUseCase::new(
+            Arc::new(api_token_repository),
+            Arc::new(UserRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
+        ));
+
+        // mcp operation repository && mcp operation usecase
+        let mcp_operation_repository = McpOperationRepositoryImpl {
+            db_pool: Arc::clone(&db_connection),
+        };
+        let mcp_operation_usecase: Arc<McpOperationUseCaseImpl> = Arc::new(McpOperationUseCase::new(
+            Arc::new(mcp_operation_repository),
+            payment_usecase.clone(),
+            income_usecase.clone(),
+            transfer_usecase.clone(),
+            contact_usecase.clone(),
+            expense_usecase.clone(),
+        ));
+
+
+        // import usecase, bank statements previewed then committed as incomes and payments
+        let import_usecase: Arc<ImportUseCaseImpl> = Arc::new(ImportUseCase::new(
+            Arc::new(ImportRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
+            payment_usecase.clone(),
+            income_usecase.clone(),
+        ));
+
+
+        // user data usecase, export and deletion of the whole account
+        let user_data_usecase: Arc<UserDataUseCaseImpl> = Arc::new(UserDataUseCase::new(
+            Arc::new(UserDataRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
+            Arc::new(AuthRepositoryImpl {
+                db_pool: Arc::clone(&db_connection),
+            }),
+            sign_in_limiter,
+            attachment_storage,
         ));
 
-        // mcp usecase
-        // let contact_type_repository = ContactTypeRepositoryImpl{
-        //     db_pool: Arc::clone(&db_connection),
-        // };
-        // let contact_type_usecase = Arc::new(ContactTypeUseCase::new(Arc::new(contact_type_repository)));
-        
 
         // >>>>>  Manage the usecase and database connection in Rocket's state <<<<<
         rocket
@@ -191,5 +369,15 @@ pub fn init_usecase_setup(db_connection: Arc<DatabaseConnection>) -> AdHoc {
             .manage(payment_usecase)
             .manage(current_usecase)
             .manage(transfer_usecase)
+            .manage(transaction_usecase)
+            .manage(report_usecase)
+            .manage(budget_usecase)
+            .manage(tag_usecase)
+            .manage(recurring_transaction_usecase)
+            .manage(api_token_usecase)
+            .manage(mcp_operation_usecase)
+            .manage(user_data_usecase)
+            .manage(attachment_usecase)
+            .manage(import_usecase)
     })      
 }
\ No newline at end of file
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use rocket::fairing::AdHoc;
+
+use crate::{configuration::{attachment_config::AttachmentConfig, recurring_config::RecurringWorkerConfig}, infrastructure::scheduler::{attachment_worker::spawn_attachment_worker, recurring_worker::spawn_recurring_worker}, initiation::init_usecase_setup::{AttachmentUseCaseImpl, RecurringTransactionUseCaseImpl}};
+
+
+
+
+pub fn init_worker_setup() -> AdHoc {
+    AdHoc::on_liftoff("Initialize workers", |rocket| Box::pin(async move {
+        let config = RecurringWorkerConfig::default();
+        if !config.enabled {
+            tracing::info!("Recurring transaction worker is disabled");
+        } else {
+            match rocket.state::<Arc<RecurringTransactionUseCaseImpl>>() {
+                Some(recurring_usecase) => {
+                    tracing::info!("Recurring transaction worker started, every {}s", config.interval_secs);
+                    spawn_recurring_worker(Arc::clone(recurring_usecase), config);
+                }
+                None => tracing::error!("Recurring transaction usecase is not managed, worker not started"),
+            }
+        }
+
+        let attachment_config = AttachmentConfig::default();
+        if !attachment_config.cleanup_enabled {
+            tracing::info!("Attachment cleanup worker is disabled");
+            return;
+        }
+
+        match rocket.state::<Arc<AttachmentUseCaseImpl>>() {
+            Some(attachment_usecase) => {
+                tracing::info!("Attachment cleanup worker started, every {}s", attachment_config.cleanup_interval_secs);
+                spawn_attachment_worker(Arc::clone(attachment_usecase), attachment_config);
+            }
+            None => tracing::error!("Attachment usecase is not managed, worker not started"),
+        }
+    }))
+}
@@ -1,3 +1,5 @@
 pub mod init_handler_setup;
 pub mod init_usecase_setup;
-pub mod init_open_api_setup;
\ No newline at end of file
+pub mod init_open_api_setup;
+pub mod init_worker_setup;
+pub mod init_mcp_setup;
\ No newline at end of file
@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
-use light_house::{configuration::{jwt_config, mysql_config::DatabaseConfig}, domain::migration::Migrator, infrastructure::{database::mysql::mysql_connection, http::faring::cors::CORS}, initiation::{init_open_api_setup::init_open_api_setup, init_usecase_setup::init_usecase_setup}};
+use light_house::{application::usecase_req_impl::api_token_usecase::ApiTokenUsecase, configuration::{attachment_config::AttachmentConfig, jwt_config, mcp_config::McpConfig, mysql_config::DatabaseConfig}, domain::migration::Migrator, infrastructure::{database::mysql::mysql_connection, http::faring::cors::CORS, mcp::mcp_server::{light_house_server::McpUseCases, mcp_transport::serve_stdio}}, initiation::{init_mcp_setup::init_mcp_setup, init_open_api_setup::init_open_api_setup, init_usecase_setup::init_usecase_setup, init_worker_setup::init_worker_setup}};
 use rocket::{get, routes};
+use sea_orm::DatabaseConnection;
 use sea_orm_migration::MigratorTrait;
 use light_house::initiation::init_handler_setup::init_handler_setup;
 use utoipa_swagger_ui::SwaggerUi;
@@ -12,17 +13,61 @@ fn index() -> &'static str {
     "Hello, world!"
 }
 
+// `light-house --mcp-stdio` serves the MCP_TOKEN user over stdin/stdout instead of starting the HTTP server
+async fn run_mcp_stdio(db_arc: Arc<DatabaseConnection>) -> Result<(), rocket::Error> {
+    // Rocket only builds the use cases here, its own logging would write to stdout
+    let rocket = rocket::custom(rocket::Config::figment().merge(("log_level", "off")))
+        .attach(init_usecase_setup(Arc::clone(&db_arc)))
+        .ignite()
+        .await?;
+
+    let usecases = match McpUseCases::from_rocket(&rocket) {
+        Some(usecases) => Arc::new(usecases),
+        None => {
+            tracing::error!("MCP use cases are not managed");
+            return Ok(());
+        }
+    };
+    let token = match McpConfig::default().stdio_token {
+        Some(token) => token,
+        None => {
+            tracing::error!("MCP_TOKEN environment variable is not set");
+            return Ok(());
+        }
+    };
+    // checked once up front so a bad token fails at start, the server checks it again on every call
+    if let Err(err) = usecases.api_token_usecase.authenticate_api_token(&token, None).await {
+        tracing::error!("MCP_TOKEN rejected: {}", err);
+        return Ok(());
+    }
+
+    tracing::info!("Serving MCP over stdio");
+    if let Err(err) = serve_stdio(usecases, token).await {
+        tracing::error!("{}", err);
+    }
+    Ok(())
+}
+
 #[rocket::main]
 async fn main() -> Result<(), rocket::Error>  {
     
     // Load environment variables from .env file
     dotenvy::dotenv().ok();
+    let mcp_stdio = std::env::args().any(|arg| arg == "--mcp-stdio");
     
-    // Initialize the logger
-    tracing_subscriber::fmt()
-        .with_max_level(tracing::Level::DEBUG)
-        .with_target(false)
-        .init();
+    // Initialize the logger, stdout belongs to the protocol in MCP stdio mode
+    if mcp_stdio {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_target(false)
+            .with_writer(std::io::stderr)
+            .init();
+    } else {
+        tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_target(false)
+            .init();
+    }
     // Initialize the database connection pool
     let config = DatabaseConfig::default();
     let db = mysql_connection::connect(&config).await.unwrap();
@@ -34,13 +79,25 @@ async fn main() -> Result<(), rocket::Error>  {
     tracing::info!("Database migrations completed");
     let db_arc = Arc::new(db);
 
-    match rocket::build()
+    if mcp_stdio {
+        return run_mcp_stdio(db_arc).await;
+    }
+
+    // attachment uploads are larger than Rocket's default form limits
+    let attachment_config = AttachmentConfig::default();
+    let figment = rocket::Config::figment()
+        .merge(("limits.file", attachment_config.max_file_bytes))
+        .merge(("limits.data-form", attachment_config.max_file_bytes + 64 * 1024));
+
+    match rocket::custom(figment)
         .attach(CORS)
         .attach(init_usecase_setup(Arc::clone(&db_arc)))
         .manage(db_arc.as_ref().clone())
         .manage(jwt_config::JwtSecret::default())
         .mount("/", routes![index])
         .attach(init_handler_setup())
+        .attach(init_worker_setup())
+        .attach(init_mcp_setup())
         .mount("/",
             SwaggerUi::new("/swagger-ui/<_..>")
                 .url("/api-doc/openapi.json", init_open_api_setup())
@@ -25,6 +25,9 @@ pub enum UsecaseError {
 
     #[error("Invalid data: {0}")]
     InvalidData(String),
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
 }
 
 // Validation error detail structure
@@ -0,0 +1,33 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::admin_dto::{ReqAssignRoleDto, ResAdminUserDto, ResListAdminUserDto, ResListRoleDto, ResRoleDto, ResSystemStatsDto}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::admin_route::view_all_users,
+        crate::infrastructure::http::http_handler::admin_route::view_all_roles,
+        crate::infrastructure::http::http_handler::admin_route::assign_role,
+        crate::infrastructure::http::http_handler::admin_route::revoke_role,
+        crate::infrastructure::http::http_handler::admin_route::disable_user,
+        crate::infrastructure::http::http_handler::admin_route::enable_user,
+        crate::infrastructure::http::http_handler::admin_route::view_system_stats
+    ),
+    components(
+        schemas(
+            ReqAssignRoleDto,
+            ResAdminUserDto,
+            ResListAdminUserDto,
+            ResRoleDto,
+            ResListRoleDto,
+            ResSystemStatsDto
+        )
+    )
+)]
+pub struct AdminApi;
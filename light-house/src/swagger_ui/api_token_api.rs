@@ -0,0 +1,28 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{api_token_dto::{ReqCreateApiTokenDto, ResApiTokenDto, ResCreatedApiTokenDto, ResListApiTokenDto}, dto_enum::api_token_scope::ApiTokenScopeVariant}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::api_token_route::create_api_token,
+        crate::infrastructure::http::http_handler::api_token_route::view_all_api_tokens,
+        crate::infrastructure::http::http_handler::api_token_route::revoke_api_token
+    ),
+    components(
+        schemas(
+            ApiTokenScopeVariant,
+            ReqCreateApiTokenDto,
+            ResApiTokenDto,
+            ResCreatedApiTokenDto,
+            ResListApiTokenDto
+        )
+    )
+)]
+pub struct ApiTokenApi;
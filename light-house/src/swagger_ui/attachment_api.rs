@@ -0,0 +1,31 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{attachment_dto::{ReqUploadAttachmentDto, ResAttachmentUsageDto, ResEntryAttachmentDto, ResListAttachmentDto}, dto_enum::attachment_owner::AttachmentOwnerVariant}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::attachment_route::upload_attachment,
+        crate::infrastructure::http::http_handler::attachment_route::view_attachments_by_owner,
+        crate::infrastructure::http::http_handler::attachment_route::view_attachment_usage,
+        crate::infrastructure::http::http_handler::attachment_route::view_attachment_by_id,
+        crate::infrastructure::http::http_handler::attachment_route::download_attachment,
+        crate::infrastructure::http::http_handler::attachment_route::delete_attachment_by_id
+    ),
+    components(
+        schemas(
+            ReqUploadAttachmentDto,
+            ResEntryAttachmentDto,
+            ResListAttachmentDto,
+            ResAttachmentUsageDto,
+            AttachmentOwnerVariant
+        )
+    )
+)]
+pub struct AttachmentApi;
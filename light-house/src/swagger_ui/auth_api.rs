@@ -1,6 +1,6 @@
 use utoipa::OpenApi;
 
-use crate::domain::dto::auth_dto::{ReqSignInDto, ReqSignUpDto, ResMeDto};
+use crate::domain::dto::{account_dto::{ReqForgotPasswordDto, ReqResetPasswordDto, ReqVerifyEmailDto, ResAccountMessageDto}, auth_dto::{ReqSignInDto, ReqSignUpDto, ResMeDto, ResSignInDto, ResSignInStepDto}, session_dto::ReqRefreshTokenDto, two_factor_dto::{ReqTwoFactorSignInDto, ResTwoFactorChallengeDto}};
 
 
 
@@ -11,7 +11,14 @@ use crate::domain::dto::auth_dto::{ReqSignInDto, ReqSignUpDto, ResMeDto};
     modifiers(),
     paths(
         crate::infrastructure::http::http_handler::user_route::sign_in,
+        crate::infrastructure::http::http_handler::user_route::sign_in_two_factor,
         crate::infrastructure::http::http_handler::user_route::sign_up,
+        crate::infrastructure::http::http_handler::user_route::sign_out,
+        crate::infrastructure::http::http_handler::user_route::refresh_token,
+        crate::infrastructure::http::http_handler::user_route::verify_email,
+        crate::infrastructure::http::http_handler::user_route::resend_email_verification,
+        crate::infrastructure::http::http_handler::user_route::forgot_password,
+        crate::infrastructure::http::http_handler::user_route::reset_password,
         
         
     ),
@@ -19,7 +26,16 @@ use crate::domain::dto::auth_dto::{ReqSignInDto, ReqSignUpDto, ResMeDto};
         schemas(
             ReqSignUpDto,
             ResMeDto,
-            ReqSignInDto
+            ReqSignInDto,
+            ResSignInDto,
+            ResSignInStepDto,
+            ResTwoFactorChallengeDto,
+            ReqTwoFactorSignInDto,
+            ReqRefreshTokenDto,
+            ReqVerifyEmailDto,
+            ReqForgotPasswordDto,
+            ReqResetPasswordDto,
+            ResAccountMessageDto
         )
     )
 )]
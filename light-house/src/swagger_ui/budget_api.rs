@@ -0,0 +1,36 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{budget_dto::{ReqCreateBudgetDto, ReqUpdateBudgetDto, ResBudgetAlertDto, ResBudgetStatusDto, ResEntryBudgetDto, ResListBudgetAlertDto, ResListBudgetDto, ResListBudgetStatusDto}, dto_enum::budget_period_type::BudgetPeriodVariant}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::budget_route::create_budget,
+        crate::infrastructure::http::http_handler::budget_route::fetch_budget_status,
+        crate::infrastructure::http::http_handler::budget_route::fetch_budget_alerts,
+        crate::infrastructure::http::http_handler::budget_route::view_budget_by_id,
+        crate::infrastructure::http::http_handler::budget_route::view_all_budgets,
+        crate::infrastructure::http::http_handler::budget_route::delete_budget_by_id,
+        crate::infrastructure::http::http_handler::budget_route::update_budget
+    ),
+    components(
+        schemas(
+            BudgetPeriodVariant,
+            ReqCreateBudgetDto,
+            ReqUpdateBudgetDto,
+            ResEntryBudgetDto,
+            ResListBudgetDto,
+            ResBudgetStatusDto,
+            ResListBudgetStatusDto,
+            ResBudgetAlertDto,
+            ResListBudgetAlertDto
+        )
+    )
+)]
+pub struct BudgetApi;
@@ -1,6 +1,6 @@
 use utoipa::OpenApi;
 
-use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::current_sheet_dto::{ResCurrentSheetDto, ResListCurrentSheetDto}};
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{dto_enum::balance_interval::BalanceIntervalVariant, current_sheet_dto::{ReqRebuildCurrentSheetDto, ResBalanceAsOfDto, ResBalanceHistoryDto, ResBalancePointDto, ResCurrentSheetDiscrepancyDto, ResCurrentSheetDto, ResListCurrentSheetDto, ResRebuildCurrentSheetDto}}};
 
 
 
@@ -13,12 +13,22 @@ use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::curre
     paths(
         crate::infrastructure::http::http_handler::current_sheet_route::fetch_current_sheet_by_current_sheet_by_id,
         crate::infrastructure::http::http_handler::current_sheet_route::fetch_all_current_sheets_by_user_id,
-        crate::infrastructure::http::http_handler::current_sheet_route::fetch_all_current_sheets_by_asset_id
+        crate::infrastructure::http::http_handler::current_sheet_route::fetch_all_current_sheets_by_asset_id,
+        crate::infrastructure::http::http_handler::current_sheet_route::rebuild_current_sheets,
+        crate::infrastructure::http::http_handler::current_sheet_route::fetch_balance_as_of,
+        crate::infrastructure::http::http_handler::current_sheet_route::fetch_balance_history
     ),
     components(
         schemas(
             ResCurrentSheetDto,
-            ResListCurrentSheetDto
+            ResListCurrentSheetDto,
+            ReqRebuildCurrentSheetDto,
+            ResCurrentSheetDiscrepancyDto,
+            ResRebuildCurrentSheetDto,
+            ResBalanceAsOfDto,
+            ResBalancePointDto,
+            ResBalanceHistoryDto,
+            BalanceIntervalVariant
         )
     )
 )]
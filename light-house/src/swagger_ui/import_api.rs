@@ -0,0 +1,45 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{dto_enum::{date_order::DateOrderVariant, import_record_status::ImportRecordStatusVariant}, import_dto::{ReqCommitImportDto, ReqCreateImportProfileDto, ReqImportCsvDto, ReqImportOfxDto, ReqImportQifDto, ReqImportRecordDto, ReqUpdateImportProfileDto, ResEntryImportProfileDto, ResImportCommitDto, ResImportCommitResultDto, ResImportLineErrorDto, ResImportPreviewDto, ResImportRecordDto, ResListImportProfileDto}}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::import_route::preview_csv_import,
+        crate::infrastructure::http::http_handler::import_route::preview_ofx_import,
+        crate::infrastructure::http::http_handler::import_route::preview_qif_import,
+        crate::infrastructure::http::http_handler::import_route::commit_import,
+        crate::infrastructure::http::http_handler::import_route::create_import_profile,
+        crate::infrastructure::http::http_handler::import_route::view_all_import_profiles,
+        crate::infrastructure::http::http_handler::import_route::view_import_profile_by_id,
+        crate::infrastructure::http::http_handler::import_route::update_import_profile,
+        crate::infrastructure::http::http_handler::import_route::delete_import_profile_by_id
+    ),
+    components(
+        schemas(
+            ReqImportCsvDto,
+            ReqImportOfxDto,
+            ReqImportQifDto,
+            ReqCommitImportDto,
+            ReqImportRecordDto,
+            ReqCreateImportProfileDto,
+            ReqUpdateImportProfileDto,
+            ResEntryImportProfileDto,
+            ResListImportProfileDto,
+            ResImportPreviewDto,
+            ResImportRecordDto,
+            ResImportLineErrorDto,
+            ResImportCommitDto,
+            ResImportCommitResultDto,
+            ImportRecordStatusVariant,
+            DateOrderVariant
+        )
+    )
+)]
+pub struct ImportApi;
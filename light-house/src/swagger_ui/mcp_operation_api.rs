@@ -0,0 +1,30 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{dto_enum::mcp_operation::{McpOperationStatusVariant, McpOperationVariant}, mcp_operation_dto::{ReqMcpProposeOperationDto, ResListMcpPendingOperationDto, ResMcpBalanceImpactDto, ResMcpPendingOperationDto}}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::mcp_operation_route::view_all_mcp_operations,
+        crate::infrastructure::http::http_handler::mcp_operation_route::view_mcp_operation_by_id,
+        crate::infrastructure::http::http_handler::mcp_operation_route::confirm_mcp_operation,
+        crate::infrastructure::http::http_handler::mcp_operation_route::reject_mcp_operation
+    ),
+    components(
+        schemas(
+            McpOperationVariant,
+            McpOperationStatusVariant,
+            ReqMcpProposeOperationDto,
+            ResMcpBalanceImpactDto,
+            ResMcpPendingOperationDto,
+            ResListMcpPendingOperationDto
+        )
+    )
+)]
+pub struct McpOperationApi;
@@ -10,4 +10,16 @@ pub mod income_api;
 pub mod transaction_type_api;
 pub mod current_sheet_api;
 pub mod payment_api;
-pub mod transfer_api;
\ No newline at end of file
+pub mod transfer_api;
+pub mod transaction_api;
+pub mod report_api;
+pub mod budget_api;
+pub mod recurring_transaction_api;
+pub mod mcp_operation_api;
+pub mod api_token_api;
+pub mod session_api;
+pub mod admin_api;
+pub mod two_factor_api;
+pub mod tag_api;
+pub mod attachment_api;
+pub mod import_api;
\ No newline at end of file
@@ -1,6 +1,6 @@
 use utoipa::OpenApi;
 
-use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::transaction_dto::{ReqCreatePaymentDto, ReqUpdatePaymentDto, ResEntryPaymentDto}};
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::transaction_dto::{ReqCreatePaymentDto, ReqPaymentSplitDto, ReqUpdatePaymentDto, ResEntryPaymentDto, ResPaymentSplitDto}};
 
 
 
@@ -20,8 +20,10 @@ use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::trans
     components(
         schemas(
             ReqCreatePaymentDto,
+            ReqPaymentSplitDto,
             ReqUpdatePaymentDto,
-            ResEntryPaymentDto
+            ResEntryPaymentDto,
+            ResPaymentSplitDto
         )
     )
 )]
@@ -0,0 +1,43 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{dto_enum::{recurring_schedule_type::RecurringScheduleVariant, recurring_status::{RecurringOccurrenceStatusVariant, RecurringStatusVariant}, transaction_type::TransactionTypeVariant}, recurring_transaction_dto::{ReqCreateRecurringTransactionDto, ReqSkipRecurringOccurrenceDto, ReqUpdateRecurringTransactionDto, ResEntryRecurringTransactionDto, ResListRecurringOccurrenceDto, ResListRecurringTransactionDto, ResRecurringOccurrenceDto, ResRecurringPreviewDto, ResRecurringPreviewEntryDto}}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::recurring_transaction_route::create_recurring_transaction,
+        crate::infrastructure::http::http_handler::recurring_transaction_route::view_recurring_transaction_by_id,
+        crate::infrastructure::http::http_handler::recurring_transaction_route::view_all_recurring_transactions,
+        crate::infrastructure::http::http_handler::recurring_transaction_route::update_recurring_transaction,
+        crate::infrastructure::http::http_handler::recurring_transaction_route::delete_recurring_transaction_by_id,
+        crate::infrastructure::http::http_handler::recurring_transaction_route::pause_recurring_transaction,
+        crate::infrastructure::http::http_handler::recurring_transaction_route::resume_recurring_transaction,
+        crate::infrastructure::http::http_handler::recurring_transaction_route::skip_recurring_occurrence,
+        crate::infrastructure::http::http_handler::recurring_transaction_route::preview_recurring_occurrences,
+        crate::infrastructure::http::http_handler::recurring_transaction_route::view_recurring_occurrences
+    ),
+    components(
+        schemas(
+            TransactionTypeVariant,
+            RecurringScheduleVariant,
+            RecurringStatusVariant,
+            RecurringOccurrenceStatusVariant,
+            ReqCreateRecurringTransactionDto,
+            ReqUpdateRecurringTransactionDto,
+            ReqSkipRecurringOccurrenceDto,
+            ResEntryRecurringTransactionDto,
+            ResListRecurringTransactionDto,
+            ResRecurringOccurrenceDto,
+            ResListRecurringOccurrenceDto,
+            ResRecurringPreviewEntryDto,
+            ResRecurringPreviewDto
+        )
+    )
+)]
+pub struct RecurringTransactionApi;
@@ -0,0 +1,29 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{dto_enum::{expense_report_group::ExpenseReportGroupVariant, report_period::ReportPeriodVariant}, report_dto::{ResExpenseReportDto, ResExpenseReportGroupDto, ResReportBreakdownDto, ResReportPeriodDto, ResReportSummaryDto}}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::report_route::fetch_summary,
+        crate::infrastructure::http::http_handler::report_route::fetch_expense_report
+    ),
+    components(
+        schemas(
+            ReportPeriodVariant,
+            ResReportBreakdownDto,
+            ResReportPeriodDto,
+            ResReportSummaryDto,
+            ExpenseReportGroupVariant,
+            ResExpenseReportGroupDto,
+            ResExpenseReportDto
+        )
+    )
+)]
+pub struct ReportApi;
@@ -0,0 +1,26 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::session_dto::{ResListSessionDto, ResRevokeSessionDto, ResSessionDto}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::session_route::view_all_sessions,
+        crate::infrastructure::http::http_handler::session_route::revoke_other_sessions,
+        crate::infrastructure::http::http_handler::session_route::revoke_session
+    ),
+    components(
+        schemas(
+            ResSessionDto,
+            ResListSessionDto,
+            ResRevokeSessionDto
+        )
+    )
+)]
+pub struct SessionApi;
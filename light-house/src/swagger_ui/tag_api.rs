@@ -0,0 +1,30 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::tag_dto::{ReqCreateTagDto, ReqUpdateTagDto, ResEntryTagDto, ResListTagDto, ResTransactionTagDto}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::tag_route::create_tag,
+        crate::infrastructure::http::http_handler::tag_route::view_tag_by_id,
+        crate::infrastructure::http::http_handler::tag_route::view_all_tags,
+        crate::infrastructure::http::http_handler::tag_route::delete_tag_by_id,
+        crate::infrastructure::http::http_handler::tag_route::update_tag
+    ),
+    components(
+        schemas(
+            ReqCreateTagDto,
+            ReqUpdateTagDto,
+            ResEntryTagDto,
+            ResListTagDto,
+            ResTransactionTagDto
+        )
+    )
+)]
+pub struct TagApi;
@@ -0,0 +1,24 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{pagination_dto::ResPageMetaDto, transaction_dto::{ResEntryTransactionDto, ResListTransactionDto}}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::transaction::transaction_route::search_transactions
+    ),
+    components(
+        schemas(
+            ResPageMetaDto,
+            ResEntryTransactionDto,
+            ResListTransactionDto
+        )
+    )
+)]
+pub struct TransactionApi;
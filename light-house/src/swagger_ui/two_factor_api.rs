@@ -0,0 +1,30 @@
+use utoipa::OpenApi;
+
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::two_factor_dto::{ReqTwoFactorCodeDto, ReqTwoFactorPasswordDto, ResTwoFactorEnrolmentDto, ResTwoFactorRecoveryCodesDto, ResTwoFactorStatusDto}};
+
+
+
+
+
+#[derive(OpenApi)]
+#[openapi(
+    security(),
+    modifiers(&SecurityAddon),
+    paths(
+        crate::infrastructure::http::http_handler::two_factor_route::view_two_factor,
+        crate::infrastructure::http::http_handler::two_factor_route::begin_enrolment,
+        crate::infrastructure::http::http_handler::two_factor_route::confirm_enrolment,
+        crate::infrastructure::http::http_handler::two_factor_route::regenerate_recovery_codes,
+        crate::infrastructure::http::http_handler::two_factor_route::disable_two_factor
+    ),
+    components(
+        schemas(
+            ResTwoFactorStatusDto,
+            ResTwoFactorEnrolmentDto,
+            ReqTwoFactorCodeDto,
+            ReqTwoFactorPasswordDto,
+            ResTwoFactorRecoveryCodesDto
+        )
+    )
+)]
+pub struct TwoFactorApi;
@@ -1,5 +1,5 @@
 use utoipa::OpenApi;
-use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::auth_dto::{ReqUpdateUserDto, ResMcpDto, ResMeDto}};
+use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::{auth_dto::{ReqUpdateUserDto, ResMeDto}, user_data_dto::{ReqDeleteAccountDto, ResUserExportDto}}};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -7,14 +7,16 @@ use crate::{configuration::api_security_addon::SecurityAddon, domain::dto::auth_
     modifiers(&SecurityAddon),
     paths(
         crate::infrastructure::http::http_handler::user_route::update_user,
+        crate::infrastructure::http::http_handler::user_route::export_user_data,
+        crate::infrastructure::http::http_handler::user_route::delete_account,
         crate::infrastructure::http::http_handler::user_route::me,
-        crate::infrastructure::http::http_handler::user_route::get_mcp,
     ),
     components(
         schemas(
             ReqUpdateUserDto,
             ResMeDto,
-            ResMcpDto
+            ResUserExportDto,
+            ReqDeleteAccountDto
             
         )
     )